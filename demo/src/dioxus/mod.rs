@@ -0,0 +1,115 @@
+use dioxus::prelude::*;
+use seigi::{
+    dioxus::{FormStage, MultiStageForm, use_focus_trap, use_form, use_toaster},
+    focus::FocusTrapOptions,
+    toast::Toast,
+};
+
+pub fn initialize() {
+    dioxus::web::launch::launch_cfg(App, dioxus::web::Config::new().rootname("dioxus.root"));
+}
+
+#[component]
+fn App() -> Element {
+    rsx! {
+        h1 { class: "text-3xl font-semibold", "Dioxus" }
+        h2 { class: "text-2xl font-semibold", "Focus Trap" }
+        Trap {}
+        div { class: "divider" }
+        h2 { class: "text-2xl font-semibold", "Toasts" }
+        Toasts {}
+        div { class: "divider" }
+        h2 { class: "text-2xl font-semibold", "Multi Staged Form" }
+        MultiStageForm {
+            class: "card bg-base-100 shadow-sm w-64",
+            onchange: move |stage| {
+                seigi::toast::create_toast_unchecked(Toast::builder().title(format!("Stage {}", stage + 1)));
+            },
+            FormStage { class: "card-body", label: "Account",
+                div { class: "card-title justify-center", "Stage 1" }
+                StageActions {}
+            }
+            FormStage { class: "card-body", label: "Profile",
+                div { class: "card-title justify-center", "Stage 2" }
+                StageActions {}
+            }
+            FormStage { class: "card-body", label: "Done",
+                div { class: "card-title justify-center", "Stage 3" }
+                StageActions {}
+            }
+        }
+    }
+}
+
+#[component]
+fn Trap() -> Element {
+    let trap = use_focus_trap(|target| {
+        FocusTrapOptions::builder()
+            .target(target)
+            .deactivate_on_escape(true)
+            .build()
+    });
+
+    rsx! {
+        button { class: "btn btn-wide", onclick: move |_| trap.activate(), "Activate Trap" }
+        div {
+            class: "card bg-base-100 shadow-sm w-48",
+            class: if trap.is_activated() { "card-border" },
+            onmounted: move |event| trap.mount(event),
+            div { class: "card-body",
+                div { class: "card-title",
+                    "Trap"
+                    if trap.is_activated() {
+                        span { class: "status status-success" }
+                    }
+                }
+                a { href: "#", "Anchor" }
+                button { class: "btn", onclick: move |_| trap.deactivate(), "Deactivate" }
+            }
+        }
+    }
+}
+
+#[component]
+fn Toasts() -> Element {
+    let toaster = use_toaster();
+
+    rsx! {
+        div { class: "flex gap-2",
+            button {
+                class: "btn btn-primary",
+                onclick: move |_| {
+                    toaster.create(Toast::builder().title("Created from Dioxus"));
+                },
+                "Create a toast"
+            }
+            button {
+                class: "btn btn-warning",
+                onclick: move |_| {
+                    if let Some(handle) = toaster.toasts().first() {
+                        toaster.dismiss(*handle);
+                    }
+                },
+                "Dismiss the oldest toast"
+            }
+        }
+        p { "{toaster.toasts().len()} toasts shown" }
+    }
+}
+
+#[component]
+fn StageActions() -> Element {
+    let form = use_form();
+    let current = form.current();
+
+    rsx! {
+        div { class: "card-actions",
+            if current > 0 {
+                button { class: "btn btn-primary flex-1", onclick: move |_| form.previous(), "Previous" }
+            }
+            if current + 1 < form.len() {
+                button { class: "btn btn-primary flex-1", onclick: move |_| form.next(), "Next" }
+            }
+        }
+    }
+}
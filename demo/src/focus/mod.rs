@@ -1,6 +1,8 @@
+use std::time::Duration;
+
 use gloo::{events::EventListener, utils::document};
 use seigi::{
-    focus::{FocusTrapHooks, FocusTrapOptions, InitialFocus},
+    focus::{FocusTrapHooks, FocusTrapOptions, InitialFocus, NavigationPolicy},
     toast::Toast,
 };
 use wasm_bindgen::JsCast;
@@ -46,20 +48,25 @@ fn initialize_trap(
             activate: Some(Box::new({
                 let target = target.clone();
                 move || {
-                    seigi::toast::create_toast(Toast::builder().title("Activated").build());
+                    seigi::toast::create_toast_unchecked(Toast::builder().title("Activated").build());
                     target.set_attribute("data-seigi-trap-active", "");
                 }
             })),
             deactivate: Some(Box::new({
                 let target = target.clone();
                 move || {
-                    seigi::toast::create_toast(Toast::builder().title("Deactivated").build());
+                    seigi::toast::create_toast_unchecked(Toast::builder().title("Deactivated").build());
                     target.remove_attribute("data-seigi-trap-active");
                 }
             })),
         },
+        navigation: NavigationPolicy::default(),
+        on_event: None,
+        rejected_feedback: Some(Duration::from_millis(400)),
+        hide_others: true,
         scope: document().unchecked_into(),
         target: target.clone().unchecked_into(),
+        document: document(),
     });
 
     EventListener::new(activate.clone().unchecked_ref(), "click", {
@@ -19,7 +19,7 @@ pub fn initialize() {
         .add_stage(Stage::from_container(
             query_selector("#forms.multi_stage.animated.3").unwrap(),
         ))
-        .build();
+        .build_unchecked();
 
     for node in document()
         .query_selector_all("[data-seigi-form-next]")
@@ -3,6 +3,7 @@ use seigi::toast::ToasterOptions;
 use wasm_bindgen::{JsCast, JsValue};
 use web_sys::{HtmlElement, NodeList};
 
+mod dioxus;
 mod focus;
 mod form;
 mod router;
@@ -11,11 +12,12 @@ mod toast;
 fn main() {
     console_error_panic_hook::set_once();
     router::initialize();
-    seigi::toast::initialize(ToasterOptions::default());
+    seigi::toast::initialize_unchecked(ToasterOptions::default());
 
     toast::initialize();
     focus::initialize();
     form::initialize();
+    dioxus::initialize();
 }
 
 fn escape_selector(selector: &str) -> String {
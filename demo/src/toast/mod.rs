@@ -1,8 +1,4 @@
-use std::{
-    cell::RefCell,
-    collections::VecDeque,
-    sync::{Arc, Mutex},
-};
+use std::cell::RefCell;
 
 use gloo::{console::console_dbg, events::EventListener, utils::document};
 use seigi::toast::Toast;
@@ -20,35 +16,30 @@ pub fn initialize() {
         .unwrap();
 
     let sequence = RefCell::new(0usize);
-    let toasts = Arc::new(Mutex::new(VecDeque::new()));
 
-    EventListener::new(create_button.unchecked_ref(), "click", {
-        let toasts = toasts.clone();
-        move |_| {
-            let current = sequence.replace_with(|v| *v + 1);
-            let mut toasts = toasts.lock().unwrap();
-            toasts.push_front(seigi::toast::create_toast(
-                Toast::builder()
-                    .title(format!("Toast {current}"))
-                    .description("Description")
-                    .build(),
-            ));
-        }
+    EventListener::new(create_button.unchecked_ref(), "click", move |_| {
+        let current = sequence.replace_with(|v| *v + 1);
+        seigi::toast::create_toast_unchecked(
+            Toast::builder()
+                .title(format!("Toast {current}"))
+                .description("Description")
+                .build(),
+        );
     })
     .forget();
 
     EventListener::new(dismiss_button.unchecked_ref(), "click", move |_| {
-        let mut toasts = toasts.lock().unwrap();
-        let Some(last) = toasts.pop_back() else {
-            toasts.push_front(seigi::toast::create_toast(
+        let oldest = seigi::toast::toaster().and_then(|v| v.iter_handles().next());
+        let Some(oldest) = oldest else {
+            seigi::toast::create_toast_unchecked(
                 Toast::builder()
                     .title(format!("No toast to dismiss"))
                     .build(),
-            ));
+            );
             return;
         };
 
-        seigi::toast::dismiss_toast(last);
+        seigi::toast::dismiss_toast(oldest);
     })
     .forget();
 }
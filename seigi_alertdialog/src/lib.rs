@@ -0,0 +1,552 @@
+//! An alert dialog asking for an explicit choice, awaited as a future
+
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    rc::{Rc, Weak},
+    sync::Mutex,
+    task::{Context, Poll, Waker},
+};
+
+use gloo::{
+    events::{EventListener, EventListenerOptions},
+    utils::{body, document},
+};
+use seigi_focus::{FocusTrap, FocusTrapOptions, InitialFocus};
+use seigi_utils::{
+    audit,
+    id::{self, Relation},
+    refs::Refs,
+    top_layer, view_transitions,
+};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlDialogElement, HtmlElement};
+
+/// The choice made in an alert dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Choice {
+    Confirm,
+    Cancel,
+}
+
+/// Contents of a confirmation
+pub struct ConfirmOptions {
+    pub title: String,
+    pub description: Option<String>,
+    pub confirm_label: String,
+    pub cancel_label: String,
+    /// Whether confirming is destructive, which is exposed for styling
+    pub destructive: bool,
+    /// Whether escape cancels. Clicks outside never do
+    pub cancel_on_escape: bool,
+}
+
+impl ConfirmOptions {
+    pub fn builder() -> ConfirmOptionsBuilder {
+        ConfirmOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [ConfirmOptions]
+pub struct ConfirmOptionsBuilder {
+    title: Option<String>,
+    description: Option<String>,
+    confirm_label: String,
+    cancel_label: String,
+    destructive: bool,
+    cancel_on_escape: bool,
+}
+
+impl Default for ConfirmOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            title: None,
+            description: None,
+            confirm_label: "Confirm".to_string(),
+            cancel_label: "Cancel".to_string(),
+            destructive: false,
+            cancel_on_escape: true,
+        }
+    }
+}
+
+impl ConfirmOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn confirm_label(mut self, confirm_label: impl Into<String>) -> Self {
+        self.confirm_label = confirm_label.into();
+        self
+    }
+
+    pub fn cancel_label(mut self, cancel_label: impl Into<String>) -> Self {
+        self.cancel_label = cancel_label.into();
+        self
+    }
+
+    pub fn destructive(mut self, destructive: bool) -> Self {
+        self.destructive = destructive;
+        self
+    }
+
+    pub fn cancel_on_escape(mut self, cancel_on_escape: bool) -> Self {
+        self.cancel_on_escape = cancel_on_escape;
+        self
+    }
+
+    /// Builds into [ConfirmOptions]
+    ///
+    /// # Panics
+    /// This method panics if title field is not set
+    pub fn build(self) -> ConfirmOptions {
+        ConfirmOptions {
+            title: self
+                .title
+                .expect("title must be set to build ConfirmOptions"),
+            description: self.description,
+            confirm_label: self.confirm_label,
+            cancel_label: self.cancel_label,
+            destructive: self.destructive,
+            cancel_on_escape: self.cancel_on_escape,
+        }
+    }
+}
+
+/// The pending choice shared between the dialog and its future
+#[derive(Default)]
+struct Slot {
+    choice: Option<Choice>,
+    waker: Option<Waker>,
+}
+
+struct Inner {
+    dialog: HtmlDialogElement,
+    trap: Option<FocusTrap>,
+    pending: Option<Rc<RefCell<Slot>>>,
+    cancel_on_escape: bool,
+    view_transition: bool,
+    scroll_lock: bool,
+    /// Referenced elements of the dialog, made when asked first
+    refs: Option<Refs>,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn part(&self, attribute: &str) -> Option<HtmlElement> {
+        self.dialog
+            .query_selector(&format!("[{attribute}]"))
+            .ok()
+            .flatten()
+            .and_then(|v| v.dyn_into::<HtmlElement>().ok())
+    }
+}
+
+/// An instance of alert dialog
+///
+/// The dialog contains buttons with `data-seigi-alert-confirm` and `data-seigi-alert-cancel`, and
+/// optionally elements with `data-seigi-alert-title` and `data-seigi-alert-description`, which are
+/// filled with texts of each confirmation. It is opened as a modal, where focus starts at the
+/// cancel button so the least destructive action is taken by an accidental Enter. Clicks outside
+/// don't close it.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **role** is set to `alertdialog` in the dialog, with **aria-labelledby** and
+/// **aria-describedby** pointing to the title and the description
+///
+/// **data-destructive** is set in the dialog and the confirm button while a destructive
+/// confirmation is shown
+#[derive(Clone)]
+pub struct AlertDialog(Rc<Mutex<Inner>>);
+
+impl AlertDialog {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    pub fn dialog(&self) -> HtmlDialogElement {
+        self.0.lock().unwrap().dialog.clone()
+    }
+
+    /// Returns elements of the dialog referenced by `data-seigi-ref`, which are kept across
+    /// calls until the dialog changes
+    pub fn refs(&self) -> Refs {
+        let mut inner = self.0.lock().unwrap();
+        let dialog = inner.dialog.clone();
+        inner
+            .refs
+            .get_or_insert_with(|| Refs::new(dialog.into()))
+            .clone()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.0.lock().unwrap().pending.is_some()
+    }
+
+    /// Shows the confirmation and returns a future resolving to the choice
+    ///
+    /// A confirmation still shown is cancelled. Dropping the future before it resolves also
+    /// cancels it.
+    pub fn confirm(&self, options: ConfirmOptions) -> ConfirmFuture {
+        self.resolve(Choice::Cancel);
+
+        let slot = Rc::new(RefCell::new(Slot::default()));
+        let (trap, dialog, view_transition) = {
+            let mut inner = self.0.lock().unwrap();
+            let dialog = inner.dialog.clone();
+            if let Some(title) = inner.part("data-seigi-alert-title") {
+                title.set_text_content(Some(&options.title));
+            } else {
+                let _ = dialog.set_attribute("aria-label", &options.title);
+            }
+            if let Some(description) = inner.part("data-seigi-alert-description") {
+                description.set_text_content(options.description.as_deref());
+                description.set_hidden(options.description.is_none());
+            }
+            let confirm = inner.part("data-seigi-alert-confirm");
+            let cancel = inner.part("data-seigi-alert-cancel");
+            if let Some(confirm) = &confirm {
+                confirm.set_text_content(Some(&options.confirm_label));
+            }
+            if let Some(cancel) = &cancel {
+                cancel.set_text_content(Some(&options.cancel_label));
+            }
+            for element in [Some(dialog.clone().into()), confirm].iter().flatten() {
+                let element: &HtmlElement = element;
+                if options.destructive {
+                    let _ = element.set_attribute("data-destructive", "");
+                } else {
+                    let _ = element.remove_attribute("data-destructive");
+                }
+            }
+
+            let trap = seigi_focus::create(
+                FocusTrapOptions::builder()
+                    .target(dialog.clone().into())
+                    .initial_focus(match cancel {
+                        Some(cancel) => InitialFocus::Element(cancel),
+                        None => InitialFocus::Auto,
+                    })
+                    .scroll_lock(inner.scroll_lock)
+                    .build(),
+            );
+            inner.trap = Some(trap.clone());
+            inner.pending = Some(slot.clone());
+            inner.cancel_on_escape = options.cancel_on_escape;
+            (trap, dialog, inner.view_transition)
+        };
+
+        let show = move || {
+            top_layer::show_modal(&dialog);
+            audit::dialog("seigi_alertdialog", &dialog);
+            trap.activate();
+        };
+        if view_transition {
+            view_transitions::start(show);
+        } else {
+            show();
+        }
+        ConfirmFuture {
+            dialog: self.clone(),
+            slot,
+            remove: false,
+        }
+    }
+
+    /// Resolves the shown confirmation with the choice and closes the dialog
+    pub fn resolve(&self, choice: Choice) {
+        let (slot, trap, dialog, view_transition) = {
+            let mut inner = self.0.lock().unwrap();
+            let Some(slot) = inner.pending.take() else {
+                return;
+            };
+            (
+                slot,
+                inner.trap.take(),
+                inner.dialog.clone(),
+                inner.view_transition,
+            )
+        };
+
+        let hide = move || {
+            top_layer::close_modal(&dialog);
+            if let Some(trap) = trap {
+                trap.deactivate();
+            }
+        };
+        if view_transition {
+            view_transitions::start(hide);
+        } else {
+            hide();
+        }
+        let waker = {
+            let mut slot = slot.borrow_mut();
+            slot.choice = Some(choice);
+            slot.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// A future resolving to the choice made in an [AlertDialog]
+pub struct ConfirmFuture {
+    dialog: AlertDialog,
+    slot: Rc<RefCell<Slot>>,
+    /// Whether the dialog was created for this confirmation and is removed after
+    remove: bool,
+}
+
+impl Future for ConfirmFuture {
+    type Output = Choice;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.slot.borrow_mut();
+        match slot.choice {
+            Some(choice) => Poll::Ready(choice),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for ConfirmFuture {
+    fn drop(&mut self) {
+        let is_pending = self.slot.borrow().choice.is_none();
+        let is_current = self
+            .dialog
+            .0
+            .lock()
+            .unwrap()
+            .pending
+            .as_ref()
+            .is_some_and(|v| Rc::ptr_eq(v, &self.slot));
+        if is_pending && is_current {
+            self.dialog.resolve(Choice::Cancel);
+        }
+        if self.remove {
+            self.dialog.dialog().remove();
+        }
+    }
+}
+
+/// Options of [AlertDialog]
+pub struct AlertDialogOptions {
+    pub dialog: HtmlDialogElement,
+    /// Whether opening and closing animate in view transitions, where supported and the user
+    /// doesn't prefer reduced motion
+    pub view_transition: bool,
+    /// Whether the document can't be scrolled while a confirmation is shown
+    pub scroll_lock: bool,
+}
+
+impl AlertDialogOptions {
+    pub fn builder() -> AlertDialogOptionsBuilder {
+        AlertDialogOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [AlertDialogOptions]
+pub struct AlertDialogOptionsBuilder {
+    dialog: Option<HtmlDialogElement>,
+    view_transition: bool,
+    scroll_lock: bool,
+}
+
+impl Default for AlertDialogOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            dialog: None,
+            view_transition: false,
+            scroll_lock: true,
+        }
+    }
+}
+
+impl AlertDialogOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn dialog(mut self, dialog: HtmlDialogElement) -> Self {
+        self.dialog = Some(dialog);
+        self
+    }
+
+    pub fn view_transition(mut self, view_transition: bool) -> Self {
+        self.view_transition = view_transition;
+        self
+    }
+
+    pub fn scroll_lock(mut self, scroll_lock: bool) -> Self {
+        self.scroll_lock = scroll_lock;
+        self
+    }
+
+    /// Builds into [AlertDialogOptions]
+    ///
+    /// # Panics
+    /// This method panics if dialog field is not set
+    pub fn build(self) -> AlertDialogOptions {
+        AlertDialogOptions {
+            dialog: self
+                .dialog
+                .expect("dialog must be set to build AlertDialogOptions"),
+            view_transition: self.view_transition,
+            scroll_lock: self.scroll_lock,
+        }
+    }
+}
+
+pub fn create_alert_dialog(options: AlertDialogOptions) -> AlertDialog {
+    let dialog = options.dialog;
+    let _ = dialog.set_attribute("role", "alertdialog");
+    let _ = dialog.set_attribute("aria-modal", "true");
+
+    let alert = AlertDialog(Rc::new(Mutex::new(Inner {
+        dialog: dialog.clone(),
+        trap: None,
+        pending: None,
+        cancel_on_escape: true,
+        view_transition: options.view_transition,
+        scroll_lock: options.scroll_lock,
+        refs: None,
+        _listeners: vec![],
+    })));
+
+    {
+        let inner = alert.0.lock().unwrap();
+        for (attribute, relation) in [
+            ("data-seigi-alert-title", Relation::LabelledBy),
+            ("data-seigi-alert-description", Relation::DescribedBy),
+        ] {
+            let Some(part) = inner.part(attribute) else {
+                continue;
+            };
+            id::ensure(&part, "alert");
+            id::relate(&dialog, relation, &part);
+        }
+    }
+
+    let weak = Rc::downgrade(&alert.0);
+    let listeners = vec![
+        EventListener::new(&dialog, "click", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(alert), Some(target)) = (
+                    AlertDialog::from_weak(&weak),
+                    event.target().and_then(|v| v.dyn_into::<Element>().ok()),
+                ) else {
+                    return;
+                };
+                if target
+                    .closest("[data-seigi-alert-confirm]")
+                    .ok()
+                    .flatten()
+                    .is_some()
+                {
+                    alert.resolve(Choice::Confirm);
+                } else if target
+                    .closest("[data-seigi-alert-cancel]")
+                    .ok()
+                    .flatten()
+                    .is_some()
+                {
+                    alert.resolve(Choice::Cancel);
+                }
+            }
+        }),
+        EventListener::new_with_options(
+            &dialog,
+            "cancel",
+            EventListenerOptions::enable_prevent_default(),
+            {
+                let weak = weak.clone();
+                move |event| {
+                    let Some(alert) = AlertDialog::from_weak(&weak) else {
+                        return;
+                    };
+                    event.prevent_default();
+                    if alert.0.lock().unwrap().cancel_on_escape {
+                        alert.resolve(Choice::Cancel);
+                    }
+                }
+            },
+        ),
+        EventListener::new(&dialog, "close", {
+            let weak = weak.clone();
+            let dialog = dialog.clone();
+            move |_| {
+                let Some(alert) = AlertDialog::from_weak(&weak) else {
+                    return;
+                };
+                // The event is queued, so the next confirmation might be shown by the time it
+                // fires
+                if dialog.open() {
+                    return;
+                }
+                // Closed by a form with method="dialog" or by calling close on the dialog
+                alert.resolve(Choice::Cancel);
+            }
+        }),
+    ];
+
+    alert.0.lock().unwrap()._listeners = listeners;
+    alert
+}
+
+/// Shows a confirmation in a dialog created for it, and returns a future resolving to the
+/// choice
+///
+/// The dialog is appended to the body and removed once the future resolves or is dropped. Its
+/// title, description and buttons have the same attributes as the markup of [AlertDialog], and
+/// the actions are wrapped in an element with `data-seigi-alert-actions`.
+pub fn confirm(options: ConfirmOptions) -> ConfirmFuture {
+    let document = document();
+    let create = |tag: &str, attribute: &str| {
+        let element = document
+            .create_element(tag)
+            .expect("failed to create element");
+        let _ = element.set_attribute(attribute, "");
+        element
+    };
+
+    let dialog = create("dialog", "data-seigi-alert-dialog");
+    let actions = create("div", "data-seigi-alert-actions");
+    let cancel = create("button", "data-seigi-alert-cancel");
+    let confirm = create("button", "data-seigi-alert-confirm");
+    for button in [&cancel, &confirm] {
+        let _ = button.set_attribute("type", "button");
+        let _ = actions.append_child(button);
+    }
+    let _ = dialog.append_child(&create("h2", "data-seigi-alert-title"));
+    let _ = dialog.append_child(&create("p", "data-seigi-alert-description"));
+    let _ = dialog.append_child(&actions);
+    let _ = body().append_child(&dialog);
+
+    let alert = create_alert_dialog(AlertDialogOptions {
+        dialog: dialog.unchecked_into(),
+        view_transition: false,
+        scroll_lock: true,
+    });
+    let mut future = alert.confirm(options);
+    future.remove = true;
+    future
+}
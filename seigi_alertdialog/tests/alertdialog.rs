@@ -0,0 +1,194 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_alertdialog::{AlertDialog, AlertDialogOptions, Choice, ConfirmOptions};
+use seigi_testkit::{Fixture, assert_focused, events, wait};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::HtmlDialogElement;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<button id="trigger">Delete</button>
+<dialog id="dialog">
+    <h2 id="title" data-seigi-alert-title></h2>
+    <p id="description" data-seigi-alert-description></p>
+    <button id="cancel" data-seigi-alert-cancel></button>
+    <button id="confirm" data-seigi-alert-confirm></button>
+</dialog>
+"#;
+
+fn alert(fixture: &Fixture) -> AlertDialog {
+    seigi_alertdialog::create_alert_dialog(
+        AlertDialogOptions::builder()
+            .dialog(fixture.get("#dialog").unchecked_into())
+            .build(),
+    )
+}
+
+fn delete() -> ConfirmOptions {
+    ConfirmOptions::builder()
+        .title("Delete file?")
+        .description("This can't be undone")
+        .confirm_label("Delete")
+        .destructive(true)
+        .build()
+}
+
+fn dialog(fixture: &Fixture) -> HtmlDialogElement {
+    fixture.get("#dialog").unchecked_into()
+}
+
+#[wasm_bindgen_test]
+fn dialog_is_related_to_its_texts() {
+    let fixture = Fixture::new(MARKUP);
+    let _alert = alert(&fixture);
+    let dialog = dialog(&fixture);
+
+    assert_eq!(dialog.get_attribute("role").as_deref(), Some("alertdialog"));
+    assert_eq!(dialog.get_attribute("aria-modal").as_deref(), Some("true"));
+    assert_eq!(
+        dialog.get_attribute("aria-labelledby").as_deref(),
+        Some("title")
+    );
+    assert_eq!(
+        dialog.get_attribute("aria-describedby").as_deref(),
+        Some("description")
+    );
+}
+
+#[wasm_bindgen_test]
+async fn confirm_button_resolves_confirm() {
+    let fixture = Fixture::new(MARKUP);
+    let alert = alert(&fixture);
+    fixture.get("#trigger").focus().unwrap();
+
+    let future = alert.confirm(delete());
+    wait::tick().await;
+    assert!(alert.is_open());
+    assert!(dialog(&fixture).open());
+    assert_eq!(
+        fixture.get("#title").text_content().as_deref(),
+        Some("Delete file?")
+    );
+    assert_eq!(
+        fixture.get("#description").text_content().as_deref(),
+        Some("This can't be undone")
+    );
+    assert_eq!(
+        fixture.get("#confirm").text_content().as_deref(),
+        Some("Delete")
+    );
+    assert_eq!(
+        fixture.get("#cancel").text_content().as_deref(),
+        Some("Cancel")
+    );
+    assert!(dialog(&fixture).has_attribute("data-destructive"));
+    assert!(fixture.get("#confirm").has_attribute("data-destructive"));
+    // An accidental Enter takes the least destructive action
+    assert_focused(&fixture.get("#cancel"));
+
+    events::click(&fixture.get("#confirm"));
+    assert_eq!(future.await, Choice::Confirm);
+    assert!(!alert.is_open());
+    assert!(!dialog(&fixture).open());
+    wait::tick().await;
+    assert_focused(&fixture.get("#trigger"));
+}
+
+#[wasm_bindgen_test]
+async fn cancel_button_and_escape_resolve_cancel() {
+    let fixture = Fixture::new(MARKUP);
+    let alert = alert(&fixture);
+
+    let future = alert.confirm(ConfirmOptions::builder().title("Leave?").build());
+    // Confirmations without a description hide it
+    assert!(fixture.get("#description").hidden());
+    assert!(!dialog(&fixture).has_attribute("data-destructive"));
+    events::click(&fixture.get("#cancel"));
+    assert_eq!(future.await, Choice::Cancel);
+
+    let future = alert.confirm(delete());
+    assert!(!fixture.get("#description").hidden());
+    assert!(!events::fire(&dialog(&fixture), "cancel"));
+    assert_eq!(future.await, Choice::Cancel);
+}
+
+#[wasm_bindgen_test]
+async fn escape_can_be_disabled() {
+    let fixture = Fixture::new(MARKUP);
+    let alert = alert(&fixture);
+
+    let future = alert.confirm(
+        ConfirmOptions::builder()
+            .title("Leave?")
+            .cancel_on_escape(false)
+            .build(),
+    );
+    assert!(!events::fire(&dialog(&fixture), "cancel"));
+    assert!(alert.is_open());
+    assert!(dialog(&fixture).open());
+
+    alert.resolve(Choice::Confirm);
+    assert_eq!(future.await, Choice::Confirm);
+}
+
+#[wasm_bindgen_test]
+async fn next_confirmation_cancels_the_shown_one() {
+    let fixture = Fixture::new(MARKUP);
+    let alert = alert(&fixture);
+
+    let first = alert.confirm(delete());
+    let second = alert.confirm(ConfirmOptions::builder().title("Leave?").build());
+    assert_eq!(first.await, Choice::Cancel);
+    assert!(alert.is_open());
+    assert_eq!(
+        fixture.get("#title").text_content().as_deref(),
+        Some("Leave?")
+    );
+
+    // The close event queued by the first confirmation leaves the second one shown
+    wait::tick().await;
+    assert!(alert.is_open());
+    assert!(dialog(&fixture).open());
+
+    events::click(&fixture.get("#confirm"));
+    assert_eq!(second.await, Choice::Confirm);
+}
+
+#[wasm_bindgen_test]
+async fn dropping_the_future_cancels() {
+    let fixture = Fixture::new(MARKUP);
+    let alert = alert(&fixture);
+
+    drop(alert.confirm(delete()));
+    assert!(!alert.is_open());
+    assert!(!dialog(&fixture).open());
+
+    // Closing the dialog directly cancels as well
+    let future = alert.confirm(delete());
+    dialog(&fixture).close();
+    assert_eq!(future.await, Choice::Cancel);
+}
+
+#[wasm_bindgen_test]
+async fn standalone_confirm_removes_its_dialog() {
+    let future = seigi_alertdialog::confirm(delete());
+    let dialog = gloo::utils::document()
+        .query_selector("[data-seigi-alert-dialog]")
+        .unwrap()
+        .expect("a dialog must be created");
+    let title = dialog
+        .query_selector("[data-seigi-alert-title]")
+        .unwrap()
+        .unwrap();
+    assert_eq!(title.text_content().as_deref(), Some("Delete file?"));
+
+    let confirm = dialog
+        .query_selector("[data-seigi-alert-actions] [data-seigi-alert-confirm]")
+        .unwrap()
+        .unwrap();
+    events::click(&confirm);
+    assert_eq!(future.await, Choice::Confirm);
+    assert!(!dialog.is_connected());
+}
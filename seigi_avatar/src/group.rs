@@ -0,0 +1,162 @@
+//! Groups of avatars showing a count of those over the maximum
+
+use std::{rc::Rc, sync::Mutex};
+
+use seigi_utils::i18n;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+/// Formats the count of hidden avatars for the counter
+pub type FormatHook = Box<dyn Fn(usize) -> String>;
+
+/// Options of [AvatarGroup]
+pub struct AvatarGroupOptions {
+    /// The element containing avatars with `data-seigi-avatar`
+    pub container: HtmlElement,
+    /// The element showing the count of hidden avatars
+    pub counter: HtmlElement,
+    /// The maximum count of avatars shown
+    pub max: usize,
+    pub format: FormatHook,
+}
+
+impl AvatarGroupOptions {
+    pub fn builder() -> AvatarGroupOptionsBuilder {
+        AvatarGroupOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [AvatarGroupOptions]
+pub struct AvatarGroupOptionsBuilder {
+    container: Option<HtmlElement>,
+    counter: Option<HtmlElement>,
+    max: usize,
+    format: FormatHook,
+}
+
+impl Default for AvatarGroupOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            container: None,
+            counter: None,
+            max: 3,
+            format: Box::new(|v| format!("+{v}")),
+        }
+    }
+}
+
+impl AvatarGroupOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn container(mut self, container: HtmlElement) -> Self {
+        self.container = Some(container);
+        self
+    }
+
+    pub fn counter(mut self, counter: HtmlElement) -> Self {
+        self.counter = Some(counter);
+        self
+    }
+
+    pub fn max(mut self, max: usize) -> Self {
+        self.max = max;
+        self
+    }
+
+    pub fn format(mut self, format: FormatHook) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Builds into [AvatarGroupOptions]
+    ///
+    /// # Panics
+    /// This method panics if container or counter field is not set
+    pub fn build(self) -> AvatarGroupOptions {
+        AvatarGroupOptions {
+            container: self
+                .container
+                .expect("container must be set to build AvatarGroupOptions"),
+            counter: self
+                .counter
+                .expect("counter must be set to build AvatarGroupOptions"),
+            max: self.max,
+            format: self.format,
+        }
+    }
+}
+
+/// An instance of avatar group
+///
+/// Avatars past the maximum are hidden, and the counter shows how many are. Call
+/// [AvatarGroup::update] after avatars are added or removed.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-overflow** is set in the container to the count of hidden avatars, and removed with
+/// the counter hidden while there are none
+///
+/// Hidden avatars and the counter are hidden with `hidden` attribute
+#[derive(Clone)]
+pub struct AvatarGroup(Rc<Mutex<AvatarGroupOptions>>);
+
+impl AvatarGroup {
+    pub fn max(&self) -> usize {
+        self.0.lock().unwrap().max
+    }
+
+    pub fn set_max(&self, max: usize) {
+        self.0.lock().unwrap().max = max;
+        self.update();
+    }
+
+    /// Returns the count of hidden avatars
+    pub fn overflow(&self) -> usize {
+        let options = self.0.lock().unwrap();
+        options
+            .container
+            .query_selector_all("[data-seigi-avatar]")
+            .map_or(0, |v| v.length() as usize)
+            .saturating_sub(options.max)
+    }
+
+    pub fn update(&self) {
+        let options = self.0.lock().unwrap();
+        let Ok(nodes) = options.container.query_selector_all("[data-seigi-avatar]") else {
+            return;
+        };
+        let avatars = (0..nodes.length())
+            .filter_map(|v| nodes.get(v))
+            .filter_map(|v| v.dyn_into::<HtmlElement>().ok())
+            .collect::<Vec<_>>();
+        for (index, avatar) in avatars.iter().enumerate() {
+            avatar.set_hidden(index >= options.max);
+        }
+
+        let overflow = avatars.len().saturating_sub(options.max);
+        let counter = &options.counter;
+        counter.set_hidden(overflow == 0);
+        if overflow == 0 {
+            let _ = options.container.remove_attribute("data-overflow");
+            return;
+        }
+        let _ = options
+            .container
+            .set_attribute("data-overflow", &overflow.to_string());
+        counter.set_text_content(Some(&(options.format)(overflow)));
+        let _ = counter.set_attribute(
+            "aria-label",
+            &i18n::translate("avatar.overflow", &[("count", &overflow)]),
+        );
+    }
+}
+
+pub fn create_avatar_group(options: AvatarGroupOptions) -> AvatarGroup {
+    let group = AvatarGroup(Rc::new(Mutex::new(options)));
+    group.update();
+    group
+}
@@ -0,0 +1,315 @@
+//! Avatars falling back to initials while images load or after they fail, and groups of them
+
+pub mod group;
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::{events::EventListener, timers::callback::Timeout};
+use web_sys::{HtmlElement, HtmlImageElement};
+
+pub use group::{AvatarGroup, AvatarGroupOptions, AvatarGroupOptionsBuilder, create_avatar_group};
+
+/// Loading status of the image of an avatar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Loading,
+    Loaded,
+    /// The image failed to load or has no source
+    Error,
+}
+
+impl Status {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Loading => "loading",
+            Self::Loaded => "loaded",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// Returns initials of the name, from its first and last words
+///
+/// # Examples
+/// `"Ada Lovelace"` is `"AL"`, and `"Plato"` is `"P"`
+pub fn initials(name: &str) -> String {
+    let mut words = name.split_whitespace();
+    let first = words.next().and_then(|v| v.chars().next());
+    let last = words.next_back().and_then(|v| v.chars().next());
+    first
+        .into_iter()
+        .chain(last)
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+pub type StatusHook = Box<dyn Fn(Status)>;
+
+/// Hooks to [Avatar]
+#[derive(Default)]
+pub struct AvatarHooks {
+    /// Called with the status when it changes
+    pub status: Option<StatusHook>,
+}
+
+/// Options of [Avatar]
+pub struct AvatarOptions {
+    pub root: HtmlElement,
+    pub image: HtmlImageElement,
+    /// The element shown instead of the image
+    pub fallback: HtmlElement,
+    /// The name initials are filled into the fallback from, if it is empty
+    pub name: Option<String>,
+    /// Milliseconds the fallback waits for the image before it is shown, so fast loads don't
+    /// flash it
+    pub fallback_delay: u32,
+    pub hooks: AvatarHooks,
+}
+
+impl AvatarOptions {
+    pub fn builder() -> AvatarOptionsBuilder {
+        AvatarOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [AvatarOptions]
+pub struct AvatarOptionsBuilder {
+    root: Option<HtmlElement>,
+    image: Option<HtmlImageElement>,
+    fallback: Option<HtmlElement>,
+    name: Option<String>,
+    fallback_delay: u32,
+    hooks: AvatarHooks,
+}
+
+impl Default for AvatarOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            root: None,
+            image: None,
+            fallback: None,
+            name: None,
+            fallback_delay: 600,
+            hooks: AvatarHooks::default(),
+        }
+    }
+}
+
+impl AvatarOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn root(mut self, root: HtmlElement) -> Self {
+        self.root = Some(root);
+        self
+    }
+
+    pub fn image(mut self, image: HtmlImageElement) -> Self {
+        self.image = Some(image);
+        self
+    }
+
+    pub fn fallback(mut self, fallback: HtmlElement) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn fallback_delay(mut self, fallback_delay: u32) -> Self {
+        self.fallback_delay = fallback_delay;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: AvatarHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [AvatarOptions]
+    ///
+    /// # Panics
+    /// This method panics if root, image or fallback field is not set
+    pub fn build(self) -> AvatarOptions {
+        AvatarOptions {
+            root: self.root.expect("root must be set to build AvatarOptions"),
+            image: self
+                .image
+                .expect("image must be set to build AvatarOptions"),
+            fallback: self
+                .fallback
+                .expect("fallback must be set to build AvatarOptions"),
+            name: self.name,
+            fallback_delay: self.fallback_delay,
+            hooks: self.hooks,
+        }
+    }
+}
+
+struct Inner {
+    options: Rc<AvatarOptions>,
+    status: Status,
+    /// Whether the delay of the fallback passed while loading
+    is_delayed: bool,
+    timer: Option<Timeout>,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn render(&self) {
+        let options = &self.options;
+        let _ = options
+            .root
+            .set_attribute("data-status", self.status.as_str());
+        options.image.set_hidden(self.status != Status::Loaded);
+        options.fallback.set_hidden(match self.status {
+            Status::Loading => !self.is_delayed,
+            Status::Loaded => true,
+            Status::Error => false,
+        });
+    }
+}
+
+/// An instance of avatar
+///
+/// The image is shown once it loaded, and the fallback after it failed or, while it loads,
+/// after the delay. An image without a source fails at once. The fallback is filled with
+/// initials of the name if it has no content of its own.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-status** is set in the root to `loading`, `loaded` or `error`
+///
+/// The image and the fallback are hidden with `hidden` attribute while they aren't shown
+#[derive(Clone)]
+pub struct Avatar(Rc<Mutex<Inner>>);
+
+impl Avatar {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    pub fn status(&self) -> Status {
+        self.0.lock().unwrap().status
+    }
+
+    /// Sets the source of the image, loading it again
+    pub fn set_src(&self, src: &str) {
+        let image = self.0.lock().unwrap().options.image.clone();
+        image.set_src(src);
+        self.load();
+    }
+
+    /// Starts tracking the image from its current state
+    fn load(&self) {
+        let status = {
+            let mut inner = self.0.lock().unwrap();
+            let image = &inner.options.image;
+            let status = if image.src().is_empty() {
+                Status::Error
+            } else if image.complete() {
+                if image.natural_width() > 0 {
+                    Status::Loaded
+                } else {
+                    Status::Error
+                }
+            } else {
+                Status::Loading
+            };
+
+            inner.is_delayed = false;
+            inner.timer = None;
+            if status == Status::Loading {
+                let weak = Rc::downgrade(&self.0);
+                inner.timer = Some(Timeout::new(inner.options.fallback_delay, move || {
+                    if let Some(avatar) = Avatar::from_weak(&weak) {
+                        let mut inner = avatar.0.lock().unwrap();
+                        inner.is_delayed = true;
+                        inner.timer = None;
+                        inner.render();
+                    }
+                }));
+            }
+            status
+        };
+        self.set_status(status);
+    }
+
+    fn set_status(&self, status: Status) {
+        let options = {
+            let mut inner = self.0.lock().unwrap();
+            let is_changed = inner.status != status;
+            inner.status = status;
+            if status != Status::Loading {
+                inner.timer = None;
+            }
+            inner.render();
+            if !is_changed {
+                return;
+            }
+            inner.options.clone()
+        };
+
+        if let Some(hook) = &options.hooks.status {
+            hook(status);
+        }
+    }
+}
+
+pub fn create_avatar(options: AvatarOptions) -> Avatar {
+    let image = options.image.clone();
+    if let Some(name) = &options.name {
+        if options.fallback.child_element_count() == 0
+            && options
+                .fallback
+                .text_content()
+                .is_none_or(|v| v.trim().is_empty())
+        {
+            options.fallback.set_text_content(Some(&initials(name)));
+        }
+        if !image.has_attribute("alt") {
+            image.set_alt(name);
+        }
+    }
+
+    let avatar = Avatar(Rc::new(Mutex::new(Inner {
+        options: Rc::new(options),
+        status: Status::Loading,
+        is_delayed: false,
+        timer: None,
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&avatar.0);
+    let listeners = vec![
+        EventListener::new(&image, "load", {
+            let weak = weak.clone();
+            move |_| {
+                if let Some(avatar) = Avatar::from_weak(&weak) {
+                    avatar.set_status(Status::Loaded);
+                }
+            }
+        }),
+        EventListener::new(&image, "error", {
+            let weak = weak.clone();
+            move |_| {
+                if let Some(avatar) = Avatar::from_weak(&weak) {
+                    avatar.set_status(Status::Error);
+                }
+            }
+        }),
+    ];
+
+    avatar.0.lock().unwrap()._listeners = listeners;
+    avatar.load();
+    avatar
+}
@@ -0,0 +1,143 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use seigi_avatar::{Avatar, AvatarHooks, AvatarOptions, Status};
+use seigi_testkit::{Fixture, wait};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::HtmlImageElement;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const DELAY: u32 = 20;
+const WAIT: u32 = 60;
+
+/// A transparent image of a pixel
+const PIXEL: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAQAAAC1HAwCAAAAC0lEQVR42mNkYAAAAAYAAjCB0C8AAAAASUVORK5CYII=";
+
+fn markup(fallback: &str) -> String {
+    format!(
+        r#"
+<span id="root">
+    <img id="image">
+    <span id="fallback">{fallback}</span>
+</span>
+"#
+    )
+}
+
+fn image(fixture: &Fixture) -> HtmlImageElement {
+    fixture.get("#image").unchecked_into()
+}
+
+fn avatar(fixture: &Fixture) -> (Avatar, Rc<RefCell<Vec<Status>>>) {
+    let statuses = Rc::new(RefCell::new(vec![]));
+    let avatar = seigi_avatar::create_avatar(
+        AvatarOptions::builder()
+            .root(fixture.get("#root"))
+            .image(image(fixture))
+            .fallback(fixture.get("#fallback"))
+            .name("Ada Lovelace")
+            .fallback_delay(DELAY)
+            .hooks(AvatarHooks {
+                status: Some(Box::new({
+                    let statuses = statuses.clone();
+                    move |status| statuses.borrow_mut().push(status)
+                })),
+            })
+            .build(),
+    );
+    (avatar, statuses)
+}
+
+/// Waits until the image loads or fails
+async fn settle(avatar: &Avatar) {
+    for _ in 0..100 {
+        if avatar.status() != Status::Loading {
+            return;
+        }
+        wait::frame().await;
+    }
+}
+
+#[wasm_bindgen_test]
+fn image_without_source_falls_back() {
+    let fixture = Fixture::new(&markup(""));
+    let (avatar, statuses) = avatar(&fixture);
+
+    assert_eq!(avatar.status(), Status::Error);
+    assert_eq!(
+        fixture.get("#root").get_attribute("data-status").as_deref(),
+        Some("error")
+    );
+    assert!(image(&fixture).hidden());
+    let fallback = fixture.get("#fallback");
+    assert!(!fallback.hidden());
+    assert_eq!(fallback.text_content().as_deref(), Some("AL"));
+    assert_eq!(image(&fixture).alt(), "Ada Lovelace");
+    assert_eq!(*statuses.borrow(), [Status::Error]);
+}
+
+#[wasm_bindgen_test]
+fn fallback_content_is_kept() {
+    let fixture = Fixture::new(&markup("<svg></svg>"));
+    fixture.get("#image").set_attribute("alt", "").unwrap();
+    avatar(&fixture);
+
+    assert_eq!(fixture.get("#fallback").child_element_count(), 1);
+    // Decorative images keep their empty alternative text
+    assert_eq!(image(&fixture).alt(), "");
+}
+
+#[wasm_bindgen_test]
+async fn loaded_image_is_shown() {
+    let fixture = Fixture::new(&markup(""));
+    let (avatar, statuses) = avatar(&fixture);
+
+    avatar.set_src(PIXEL);
+    assert_eq!(avatar.status(), Status::Loading);
+    // The fallback waits for the delay, so fast loads don't flash it
+    assert!(fixture.get("#fallback").hidden());
+    assert!(image(&fixture).hidden());
+
+    settle(&avatar).await;
+    assert_eq!(avatar.status(), Status::Loaded);
+    assert!(!image(&fixture).hidden());
+    assert!(fixture.get("#fallback").hidden());
+
+    // An image already loaded is shown at once
+    avatar.set_src(PIXEL);
+    assert_eq!(avatar.status(), Status::Loaded);
+    assert_eq!(
+        *statuses.borrow(),
+        [Status::Error, Status::Loading, Status::Loaded]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn broken_image_falls_back() {
+    let fixture = Fixture::new(&markup(""));
+    let (avatar, _) = avatar(&fixture);
+
+    avatar.set_src("data:image/png;base64,AAAA");
+    settle(&avatar).await;
+    assert_eq!(avatar.status(), Status::Error);
+    assert!(image(&fixture).hidden());
+    assert!(!fixture.get("#fallback").hidden());
+}
+
+#[wasm_bindgen_test]
+async fn fallback_is_shown_after_delay() {
+    let fixture = Fixture::new(&markup(""));
+    // Lazy images aren't loaded while hidden, so this one stays loading
+    image(&fixture).set_attribute("loading", "lazy").unwrap();
+    let (avatar, _) = avatar(&fixture);
+
+    avatar.set_src("/seigi-avatar-lazy.png");
+    assert_eq!(avatar.status(), Status::Loading);
+    assert!(fixture.get("#fallback").hidden());
+    wait::sleep(WAIT).await;
+    assert_eq!(avatar.status(), Status::Loading);
+    assert!(!fixture.get("#fallback").hidden());
+}
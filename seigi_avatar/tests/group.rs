@@ -0,0 +1,103 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_avatar::{AvatarGroup, AvatarGroupOptions};
+use seigi_testkit::Fixture;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<div id="group">
+    <span data-seigi-avatar>A</span>
+    <span data-seigi-avatar>B</span>
+    <span data-seigi-avatar>C</span>
+    <span data-seigi-avatar>D</span>
+    <span data-seigi-avatar>E</span>
+    <span id="counter"></span>
+</div>
+"#;
+
+fn group(fixture: &Fixture, max: usize) -> AvatarGroup {
+    seigi_avatar::create_avatar_group(
+        AvatarGroupOptions::builder()
+            .container(fixture.get("#group"))
+            .counter(fixture.get("#counter"))
+            .max(max)
+            .build(),
+    )
+}
+
+fn hidden(fixture: &Fixture) -> Vec<bool> {
+    fixture
+        .get_all("[data-seigi-avatar]")
+        .iter()
+        .map(|v| v.hidden())
+        .collect()
+}
+
+#[wasm_bindgen_test]
+fn avatars_past_max_are_counted() {
+    let fixture = Fixture::new(MARKUP);
+    let group = group(&fixture, 3);
+    let counter = fixture.get("#counter");
+
+    assert_eq!(group.overflow(), 2);
+    assert_eq!(hidden(&fixture), [false, false, false, true, true]);
+    assert!(!counter.hidden());
+    assert_eq!(counter.text_content().as_deref(), Some("+2"));
+    assert_eq!(
+        counter.get_attribute("aria-label").as_deref(),
+        Some("2 more")
+    );
+    assert_eq!(
+        fixture
+            .get("#group")
+            .get_attribute("data-overflow")
+            .as_deref(),
+        Some("2")
+    );
+}
+
+#[wasm_bindgen_test]
+fn counter_is_hidden_without_overflow() {
+    let fixture = Fixture::new(MARKUP);
+    let group = group(&fixture, 3);
+
+    group.set_max(5);
+    assert_eq!(group.max(), 5);
+    assert_eq!(group.overflow(), 0);
+    assert_eq!(hidden(&fixture), [false; 5]);
+    assert!(fixture.get("#counter").hidden());
+    assert!(!fixture.get("#group").has_attribute("data-overflow"));
+}
+
+#[wasm_bindgen_test]
+fn update_follows_removed_avatars() {
+    let fixture = Fixture::new(MARKUP);
+    let group = group(&fixture, 3);
+
+    fixture.get_all("[data-seigi-avatar]")[0].remove();
+    group.update();
+    assert_eq!(hidden(&fixture), [false, false, false, true]);
+    assert_eq!(
+        fixture.get("#counter").text_content().as_deref(),
+        Some("+1")
+    );
+}
+
+#[wasm_bindgen_test]
+fn counter_is_formatted_by_hook() {
+    let fixture = Fixture::new(MARKUP);
+    seigi_avatar::create_avatar_group(
+        AvatarGroupOptions::builder()
+            .container(fixture.get("#group"))
+            .counter(fixture.get("#counter"))
+            .max(1)
+            .format(Box::new(|v| format!("{v} others")))
+            .build(),
+    );
+    assert_eq!(
+        fixture.get("#counter").text_content().as_deref(),
+        Some("4 others")
+    );
+}
@@ -0,0 +1,24 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use seigi_avatar::initials;
+
+#[test]
+fn first_and_last_words() {
+    assert_eq!(initials("Ada Lovelace"), "AL");
+    assert_eq!(initials("Ada King Lovelace"), "AL");
+    assert_eq!(initials("Plato"), "P");
+}
+
+#[test]
+fn whitespace_is_ignored() {
+    assert_eq!(initials("  grace \t hopper \n"), "GH");
+    assert_eq!(initials(""), "");
+    assert_eq!(initials("   "), "");
+}
+
+#[test]
+fn letters_are_uppercased() {
+    assert_eq!(initials("émile zola"), "ÉZ");
+    // Some letters uppercase into more than one
+    assert_eq!(initials("ßaß"), "SS");
+}
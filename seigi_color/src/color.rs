@@ -0,0 +1,344 @@
+use std::{fmt::Display, str::FromStr};
+
+/// A color in sRGB with red, green and blue in 0-255 and alpha in 0-1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: f64,
+}
+
+/// A color with hue in degrees, saturation and lightness in 0-1 and alpha in 0-1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    pub h: f64,
+    pub s: f64,
+    pub l: f64,
+    pub a: f64,
+}
+
+/// A color in OKLCH with lightness in 0-1, chroma in 0-0.4 and hue in degrees
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklch {
+    pub l: f64,
+    pub c: f64,
+    pub h: f64,
+    pub a: f64,
+}
+
+/// A format a [Color] is serialized to
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ColorFormat {
+    /// `#rrggbb`, or `#rrggbbaa` if the color is translucent
+    #[default]
+    Hex,
+    /// `rgb(r g b / a)`
+    Rgb,
+    /// `hsl(h s% l% / a)`
+    Hsl,
+    /// `oklch(l% c h / a)`
+    Oklch,
+}
+
+/// A color used by picker primitives
+///
+/// Colors are stored as hue, saturation, value and alpha, so the hue is kept while the color is
+/// gray, which is what users expect from a saturation/value area and a hue slider.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    /// Hue in degrees, 0-360
+    pub hue: f64,
+    /// Saturation in 0-1
+    pub saturation: f64,
+    /// Value(brightness) in 0-1
+    pub value: f64,
+    /// Alpha in 0-1
+    pub alpha: f64,
+}
+
+impl Default for Color {
+    fn default() -> Self {
+        Self::from_hsva(0.0, 0.0, 0.0, 1.0)
+    }
+}
+
+impl Color {
+    /// Creates a color from hue in degrees and saturation, value and alpha in 0-1, clamping
+    /// out-of-range values
+    pub fn from_hsva(hue: f64, saturation: f64, value: f64, alpha: f64) -> Self {
+        Self {
+            hue: hue.rem_euclid(360.0),
+            saturation: saturation.clamp(0.0, 1.0),
+            value: value.clamp(0.0, 1.0),
+            alpha: alpha.clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn from_rgb(rgb: Rgb) -> Self {
+        let [r, g, b] = [rgb.r, rgb.g, rgb.b].map(|v| v as f64 / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+
+        let hue = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        let saturation = if max == 0.0 { 0.0 } else { delta / max };
+
+        Self::from_hsva(hue, saturation, max, rgb.a)
+    }
+
+    pub fn from_hsl(hsl: Hsl) -> Self {
+        let value = hsl.l + hsl.s * hsl.l.min(1.0 - hsl.l);
+        let saturation = if value == 0.0 {
+            0.0
+        } else {
+            2.0 * (1.0 - hsl.l / value)
+        };
+
+        Self::from_hsva(hsl.h, saturation, value, hsl.a)
+    }
+
+    /// Creates a color from OKLCH, clipping to the sRGB gamut
+    pub fn from_oklch(oklch: Oklch) -> Self {
+        let hue = oklch.h.to_radians();
+        let (l, a, b) = (oklch.l, oklch.c * hue.cos(), oklch.c * hue.sin());
+
+        // https://bottosson.github.io/posts/oklab/
+        let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+        let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+        let s_ = (l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+
+        let linear = [
+            4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_,
+            -1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_,
+            -0.0041960863 * l_ - 0.7034186147 * m_ + 1.7076147010 * s_,
+        ];
+        let [r, g, b] = linear.map(|v| (gamma(v.clamp(0.0, 1.0)) * 255.0).round() as u8);
+
+        let mut color = Self::from_rgb(Rgb {
+            r,
+            g,
+            b,
+            a: oklch.a,
+        });
+        if color.saturation == 0.0 {
+            color.hue = oklch.h.rem_euclid(360.0);
+        }
+        color
+    }
+
+    /// Parses `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`, with or without the leading `#`
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.trim().trim_start_matches('#');
+        if !hex.is_ascii() {
+            return None;
+        }
+        let digits = match hex.len() {
+            3 | 4 => hex.chars().flat_map(|v| [v, v]).collect::<String>(),
+            6 | 8 => hex.to_string(),
+            _ => return None,
+        };
+
+        let channel = |index: usize| u8::from_str_radix(&digits[index * 2..index * 2 + 2], 16).ok();
+        let alpha = if digits.len() == 8 {
+            channel(3)? as f64 / 255.0
+        } else {
+            1.0
+        };
+
+        Some(Self::from_rgb(Rgb {
+            r: channel(0)?,
+            g: channel(1)?,
+            b: channel(2)?,
+            a: alpha,
+        }))
+    }
+
+    pub fn to_rgb(&self) -> Rgb {
+        let c = self.value * self.saturation;
+        let x = c * (1.0 - ((self.hue / 60.0).rem_euclid(2.0) - 1.0).abs());
+        let m = self.value - c;
+        let (r, g, b) = match self.hue {
+            h if h < 60.0 => (c, x, 0.0),
+            h if h < 120.0 => (x, c, 0.0),
+            h if h < 180.0 => (0.0, c, x),
+            h if h < 240.0 => (0.0, x, c),
+            h if h < 300.0 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let [r, g, b] = [r, g, b].map(|v| ((v + m) * 255.0).round() as u8);
+
+        Rgb {
+            r,
+            g,
+            b,
+            a: self.alpha,
+        }
+    }
+
+    pub fn to_hsl(&self) -> Hsl {
+        let l = self.value * (1.0 - self.saturation / 2.0);
+        let s = if l == 0.0 || l == 1.0 {
+            0.0
+        } else {
+            (self.value - l) / l.min(1.0 - l)
+        };
+
+        Hsl {
+            h: self.hue,
+            s,
+            l,
+            a: self.alpha,
+        }
+    }
+
+    pub fn to_oklch(&self) -> Oklch {
+        let rgb = self.to_rgb();
+        let [r, g, b] = [rgb.r, rgb.g, rgb.b].map(|v| linear(v as f64 / 255.0));
+
+        let l_ = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m_ = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s_ = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+        let l = 0.2104542553 * l_ + 0.7936177850 * m_ - 0.0040720468 * s_;
+        let a = 1.9779984951 * l_ - 2.4285922050 * m_ + 0.4505937099 * s_;
+        let b = 0.0259040371 * l_ + 0.7827717662 * m_ - 0.8086757660 * s_;
+
+        let c = (a * a + b * b).sqrt();
+        // Achromatic colors keep the picker hue instead of a meaningless one
+        let h = if c < 1e-4 {
+            self.hue
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        };
+
+        Oklch {
+            l,
+            c,
+            h,
+            a: self.alpha,
+        }
+    }
+
+    /// Returns `#rrggbb`, or `#rrggbbaa` if the color is translucent
+    pub fn to_hex(&self) -> String {
+        let rgb = self.to_rgb();
+        if self.alpha < 1.0 {
+            format!(
+                "#{:02x}{:02x}{:02x}{:02x}",
+                rgb.r,
+                rgb.g,
+                rgb.b,
+                (self.alpha * 255.0).round() as u8
+            )
+        } else {
+            format!("#{:02x}{:02x}{:02x}", rgb.r, rgb.g, rgb.b)
+        }
+    }
+
+    /// Serializes the color as a CSS color value of the format
+    pub fn to_css(&self, format: ColorFormat) -> String {
+        let alpha = if self.alpha < 1.0 {
+            format!(" / {}", round(self.alpha, 3))
+        } else {
+            String::new()
+        };
+
+        match format {
+            ColorFormat::Hex => self.to_hex(),
+            ColorFormat::Rgb => {
+                let rgb = self.to_rgb();
+                format!("rgb({} {} {}{alpha})", rgb.r, rgb.g, rgb.b)
+            }
+            ColorFormat::Hsl => {
+                let hsl = self.to_hsl();
+                format!(
+                    "hsl({} {}% {}%{alpha})",
+                    round(hsl.h, 1),
+                    round(hsl.s * 100.0, 1),
+                    round(hsl.l * 100.0, 1)
+                )
+            }
+            ColorFormat::Oklch => {
+                let oklch = self.to_oklch();
+                format!(
+                    "oklch({}% {} {}{alpha})",
+                    round(oklch.l * 100.0, 2),
+                    round(oklch.c, 4),
+                    round(oklch.h, 2)
+                )
+            }
+        }
+    }
+
+    pub fn with_hue(self, hue: f64) -> Self {
+        Self::from_hsva(hue, self.saturation, self.value, self.alpha)
+    }
+
+    pub fn with_saturation_value(self, saturation: f64, value: f64) -> Self {
+        Self::from_hsva(self.hue, saturation, value, self.alpha)
+    }
+
+    pub fn with_alpha(self, alpha: f64) -> Self {
+        Self::from_hsva(self.hue, self.saturation, self.value, alpha)
+    }
+}
+
+fn round(value: f64, digits: i32) -> f64 {
+    let factor = 10f64.powi(digits);
+    (value * factor).round() / factor
+}
+
+/// Converts a gamma-encoded sRGB channel to linear
+fn linear(v: f64) -> f64 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a linear sRGB channel to gamma-encoded
+fn gamma(v: f64) -> f64 {
+    if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Formats as `#rrggbb` or `#rrggbbaa`
+impl Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+/// The error returned when parsing a [Color] fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseColorError;
+
+impl Display for ParseColorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid color, expected a hex color")
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+/// Parses a hex color. See [Color::from_hex]
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Color::from_hex(s).ok_or(ParseColorError)
+    }
+}
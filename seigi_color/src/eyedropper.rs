@@ -0,0 +1,47 @@
+//! Screen color sampling with the EyeDropper API
+//!
+//! The API is only available in Chromium-based browsers, so check [is_supported] before showing
+//! an eyedropper button.
+
+use js_sys::{Function, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+use crate::Color;
+
+fn constructor() -> Option<Function> {
+    Reflect::get(&gloo::utils::window(), &JsValue::from_str("EyeDropper"))
+        .ok()?
+        .dyn_into::<Function>()
+        .ok()
+}
+
+/// Returns whether the browser supports the EyeDropper API
+pub fn is_supported() -> bool {
+    constructor().is_some()
+}
+
+/// Opens the eyedropper and returns the picked color
+///
+/// Must be called from a user gesture such as a click.
+///
+/// # Returns
+/// None if the API is not supported or the user cancelled picking
+pub async fn pick() -> Option<Color> {
+    let eyedropper = Reflect::construct(&constructor()?, &js_sys::Array::new()).ok()?;
+    let open = Reflect::get(&eyedropper, &JsValue::from_str("open"))
+        .ok()?
+        .dyn_into::<Function>()
+        .ok()?;
+    let promise = open
+        .call0(&eyedropper)
+        .ok()?
+        .dyn_into::<js_sys::Promise>()
+        .ok()?;
+    let result = JsFuture::from(promise).await.ok()?;
+
+    Reflect::get(&result, &JsValue::from_str("sRGBHex"))
+        .ok()?
+        .as_string()
+        .and_then(|v| Color::from_hex(&v))
+}
@@ -0,0 +1,102 @@
+//! Headless color area, channel sliders and swatches sharing a typed color state
+
+mod color;
+pub mod eyedropper;
+mod primitives;
+
+use std::{
+    rc::Rc,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+pub use color::*;
+pub use primitives::*;
+
+struct Subscriber {
+    callback: Box<dyn Fn(&Color)>,
+    handle: u64,
+}
+
+struct Inner {
+    color: Color,
+    subscribers: Vec<Rc<Subscriber>>,
+}
+
+/// A color shared between picker primitives
+///
+/// Every primitive bound to the state reflects its color, and subscribers are notified of every
+/// change with the typed [Color].
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+#[derive(Clone)]
+pub struct ColorState(Rc<Mutex<Inner>>);
+
+impl ColorState {
+    pub fn new(color: Color) -> Self {
+        Self(Rc::new(Mutex::new(Inner {
+            color,
+            subscribers: vec![],
+        })))
+    }
+
+    pub fn color(&self) -> Color {
+        self.0.lock().unwrap().color
+    }
+
+    /// Replaces the color and notifies subscribers if it has changed
+    pub fn set_color(&self, color: Color) {
+        let subscribers = {
+            let mut inner = self.0.lock().unwrap();
+            if inner.color == color {
+                return;
+            }
+            inner.color = color;
+            inner.subscribers.clone()
+        };
+
+        for subscriber in subscribers {
+            (subscriber.callback)(&color);
+        }
+    }
+
+    /// Updates the color with the function
+    pub fn update(&self, f: impl FnOnce(Color) -> Color) {
+        let color = f(self.color());
+        self.set_color(color);
+    }
+
+    /// Add subscriber to state and return handle to it
+    ///
+    /// # Returns
+    /// Handle of added subscriber
+    pub fn subscribe(&self, callback: Box<dyn Fn(&Color)>) -> u64 {
+        static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+        let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .push(Rc::new(Subscriber { callback, handle }));
+
+        handle
+    }
+
+    /// Remove subscriber from state
+    pub fn unsubscribe(&self, handle: u64) {
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .retain(|v| v.handle != handle);
+    }
+}
+
+impl Default for ColorState {
+    fn default() -> Self {
+        Self::new(Color::default())
+    }
+}
@@ -0,0 +1,463 @@
+use std::{cell::Cell, rc::Rc};
+
+use gloo::events::EventListener;
+use seigi_utils::i18n;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, KeyboardEvent, PointerEvent};
+
+use crate::{Color, ColorFormat, ColorState};
+
+/// Direction a slider lays out its track
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+
+/// A channel a [ColorSlider] edits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    /// Hue in degrees, 0-360
+    Hue,
+    /// Alpha in percent, 0-100
+    Alpha,
+}
+
+impl Channel {
+    fn max(&self) -> f64 {
+        match self {
+            Channel::Hue => 360.0,
+            Channel::Alpha => 100.0,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Channel::Hue => "Hue",
+            Channel::Alpha => "Alpha",
+        }
+    }
+
+    fn get(&self, color: &Color) -> f64 {
+        match self {
+            Channel::Hue => color.hue,
+            Channel::Alpha => color.alpha * 100.0,
+        }
+    }
+
+    fn set(&self, color: Color, value: f64) -> Color {
+        let value = value.clamp(0.0, self.max());
+        match self {
+            // 360 wraps to 0 in Color, so the end of the track is kept just below it
+            Channel::Hue => color.with_hue(value.min(359.999)),
+            Channel::Alpha => color.with_alpha(value / 100.0),
+        }
+    }
+}
+
+/// Returns the step of a key press, 10 times larger with shift
+fn step(event: &KeyboardEvent, base: f64) -> f64 {
+    if event.shift_key() { base * 10.0 } else { base }
+}
+
+/// Attaches pointer listeners that report the pointer position relative to the element as
+/// ratios in 0-1, capturing the pointer while dragging
+fn drag_listeners(
+    element: &HtmlElement,
+    on_position: impl Fn(f64, f64) + 'static,
+) -> Vec<EventListener> {
+    let dragging = Rc::new(Cell::new(false));
+    let on_position = Rc::new(on_position);
+
+    let report = {
+        let element = element.clone();
+        move |event: &PointerEvent| {
+            let rect = element.get_bounding_client_rect();
+            let x = if rect.width() > 0.0 {
+                (event.client_x() as f64 - rect.left()) / rect.width()
+            } else {
+                0.0
+            };
+            let y = if rect.height() > 0.0 {
+                (event.client_y() as f64 - rect.top()) / rect.height()
+            } else {
+                0.0
+            };
+            on_position(x.clamp(0.0, 1.0), y.clamp(0.0, 1.0));
+        }
+    };
+    let report = Rc::new(report);
+
+    let down = EventListener::new(element, "pointerdown", {
+        let element = element.clone();
+        let dragging = dragging.clone();
+        let report = report.clone();
+        move |event| {
+            let Some(event) = event.dyn_ref::<PointerEvent>() else {
+                return;
+            };
+            if event.button() != 0 {
+                return;
+            }
+            event.prevent_default();
+            let _ = element.set_pointer_capture(event.pointer_id());
+            let _ = element.focus();
+            let _ = element.set_attribute("data-dragging", "");
+            dragging.set(true);
+            report(event);
+        }
+    });
+    let moved = EventListener::new(element, "pointermove", {
+        let dragging = dragging.clone();
+        move |event| {
+            if !dragging.get() {
+                return;
+            }
+            if let Some(event) = event.dyn_ref::<PointerEvent>() {
+                report(event);
+            }
+        }
+    });
+    let up = |event_type: &'static str| {
+        EventListener::new(element, event_type, {
+            let element = element.clone();
+            let dragging = dragging.clone();
+            move |_| {
+                dragging.set(false);
+                let _ = element.remove_attribute("data-dragging");
+            }
+        })
+    };
+
+    vec![down, moved, up("pointerup"), up("pointercancel")]
+}
+
+fn set_property(element: &HtmlElement, name: &str, value: &str) {
+    let _ = element.style().set_property(name, value);
+}
+
+/// A two-dimensional area editing saturation on the x axis and value(brightness) on the y axis
+///
+/// The area is focusable and moves by 1% with arrow keys, or 10% with shift.
+///
+/// Dropping the area removes its listeners and subscription.
+///
+/// # Attributes
+/// **--seigi-color-area-x** and **--seigi-color-area-y** CSS variables are set in the area to
+/// the thumb position in percent
+///
+/// **--seigi-color-hue** CSS variable is set in the area to the hue in degrees, for painting the
+/// background gradient
+///
+/// **--seigi-color** CSS variable is set in the area to the current color
+///
+/// **data-dragging** is set in the area while the pointer drags the thumb
+pub struct ColorArea {
+    state: ColorState,
+    subscription: u64,
+    _listeners: Vec<EventListener>,
+}
+
+impl ColorArea {
+    fn reflect(element: &HtmlElement, color: &Color) {
+        set_property(
+            element,
+            "--seigi-color-area-x",
+            format!("{}%", color.saturation * 100.0).as_str(),
+        );
+        set_property(
+            element,
+            "--seigi-color-area-y",
+            format!("{}%", (1.0 - color.value) * 100.0).as_str(),
+        );
+        set_property(element, "--seigi-color-hue", color.hue.to_string().as_str());
+        set_property(
+            element,
+            "--seigi-color",
+            color.to_css(ColorFormat::Rgb).as_str(),
+        );
+        let _ = element.set_attribute(
+            "aria-valuetext",
+            format!(
+                "Saturation {}%, Brightness {}%",
+                (color.saturation * 100.0).round(),
+                (color.value * 100.0).round()
+            )
+            .as_str(),
+        );
+    }
+
+    pub fn state(&self) -> &ColorState {
+        &self.state
+    }
+}
+
+impl Drop for ColorArea {
+    fn drop(&mut self) {
+        self.state.unsubscribe(self.subscription);
+    }
+}
+
+pub fn create_area(state: ColorState, element: HtmlElement) -> ColorArea {
+    let _ = element.set_attribute("role", "slider");
+    let _ = element.set_attribute(
+        "aria-roledescription",
+        &i18n::translate("color.slider", &[]),
+    );
+    let _ = element.set_attribute("aria-label", &i18n::translate("color.area", &[]));
+    if !element.has_attribute("tabindex") {
+        element.set_tab_index(0);
+    }
+    ColorArea::reflect(&element, &state.color());
+
+    let mut listeners = drag_listeners(&element, {
+        let state = state.clone();
+        move |x, y| {
+            state.update(|color| color.with_saturation_value(x, 1.0 - y));
+        }
+    });
+    listeners.push(EventListener::new(&element, "keydown", {
+        let state = state.clone();
+        move |event| {
+            let Some(event) = event.dyn_ref::<KeyboardEvent>() else {
+                return;
+            };
+            let delta = step(event, 0.01);
+            let (dx, dy) = match event.key().as_str() {
+                "ArrowLeft" => (-delta, 0.0),
+                "ArrowRight" => (delta, 0.0),
+                "ArrowUp" => (0.0, delta),
+                "ArrowDown" => (0.0, -delta),
+                _ => return,
+            };
+            event.prevent_default();
+            state.update(|color| {
+                color.with_saturation_value(color.saturation + dx, color.value + dy)
+            });
+        }
+    }));
+
+    let subscription = state.subscribe(Box::new({
+        let element = element.clone();
+        move |color| ColorArea::reflect(&element, color)
+    }));
+
+    ColorArea {
+        state,
+        subscription,
+        _listeners: listeners,
+    }
+}
+
+/// A slider editing a single [Channel] of the color
+///
+/// Arrow keys move by 1 unit, or 10 with shift. PageUp/PageDown move by 10 and Home/End to the
+/// ends of the track.
+///
+/// Dropping the slider removes its listeners and subscription.
+///
+/// # Attributes
+/// **--seigi-color-slider-value** CSS variable is set in the slider to the thumb position in
+/// percent
+///
+/// **--seigi-color-hue** and **--seigi-color** CSS variables are set in the slider to the hue in
+/// degrees and the opaque color, for painting track gradients
+///
+/// **data-orientation** is set in the slider to `horizontal` or `vertical`
+///
+/// **data-dragging** is set in the slider while the pointer drags the thumb
+pub struct ColorSlider {
+    state: ColorState,
+    channel: Channel,
+    subscription: u64,
+    _listeners: Vec<EventListener>,
+}
+
+impl ColorSlider {
+    fn reflect(element: &HtmlElement, channel: Channel, color: &Color) {
+        let value = channel.get(color);
+        set_property(
+            element,
+            "--seigi-color-slider-value",
+            format!("{}%", value / channel.max() * 100.0).as_str(),
+        );
+        set_property(element, "--seigi-color-hue", color.hue.to_string().as_str());
+        set_property(
+            element,
+            "--seigi-color",
+            color.with_alpha(1.0).to_css(ColorFormat::Rgb).as_str(),
+        );
+        let _ = element.set_attribute("aria-valuenow", value.round().to_string().as_str());
+        let valuetext = match channel {
+            Channel::Hue => format!("{}°", value.round()),
+            Channel::Alpha => format!("{}%", value.round()),
+        };
+        let _ = element.set_attribute("aria-valuetext", valuetext.as_str());
+    }
+
+    pub fn channel(&self) -> Channel {
+        self.channel
+    }
+
+    pub fn state(&self) -> &ColorState {
+        &self.state
+    }
+}
+
+impl Drop for ColorSlider {
+    fn drop(&mut self) {
+        self.state.unsubscribe(self.subscription);
+    }
+}
+
+pub fn create_slider(
+    state: ColorState,
+    element: HtmlElement,
+    channel: Channel,
+    orientation: Orientation,
+) -> ColorSlider {
+    let _ = element.set_attribute("role", "slider");
+    let _ = element.set_attribute("aria-label", channel.label());
+    let _ = element.set_attribute("aria-valuemin", "0");
+    let _ = element.set_attribute("aria-valuemax", channel.max().to_string().as_str());
+    let orientation_name = match orientation {
+        Orientation::Horizontal => "horizontal",
+        Orientation::Vertical => "vertical",
+    };
+    let _ = element.set_attribute("aria-orientation", orientation_name);
+    let _ = element.set_attribute("data-orientation", orientation_name);
+    if !element.has_attribute("tabindex") {
+        element.set_tab_index(0);
+    }
+    ColorSlider::reflect(&element, channel, &state.color());
+
+    let mut listeners = drag_listeners(&element, {
+        let state = state.clone();
+        move |x, y| {
+            // Vertical sliders have their minimum at the bottom
+            let ratio = match orientation {
+                Orientation::Horizontal => x,
+                Orientation::Vertical => 1.0 - y,
+            };
+            state.update(|color| channel.set(color, ratio * channel.max()));
+        }
+    });
+    listeners.push(EventListener::new(&element, "keydown", {
+        let state = state.clone();
+        move |event| {
+            let Some(event) = event.dyn_ref::<KeyboardEvent>() else {
+                return;
+            };
+            let current = channel.get(&state.color());
+            let value = match event.key().as_str() {
+                "ArrowLeft" | "ArrowDown" => current - step(event, 1.0),
+                "ArrowRight" | "ArrowUp" => current + step(event, 1.0),
+                "PageDown" => current - 10.0,
+                "PageUp" => current + 10.0,
+                "Home" => 0.0,
+                "End" => channel.max(),
+                _ => return,
+            };
+            event.prevent_default();
+            state.update(|color| channel.set(color, value));
+        }
+    }));
+
+    let subscription = state.subscribe(Box::new({
+        let element = element.clone();
+        move |color| ColorSlider::reflect(&element, channel, color)
+    }));
+
+    ColorSlider {
+        state,
+        channel,
+        subscription,
+        _listeners: listeners,
+    }
+}
+
+/// A preset color that replaces the state color when clicked or activated with Enter/Space
+///
+/// Dropping the swatch removes its listeners and subscription.
+///
+/// # Attributes
+/// **--seigi-color** CSS variable is set in the swatch to its color
+///
+/// **data-selected** and **aria-pressed** are set in the swatch while the state holds its color
+pub struct ColorSwatch {
+    state: ColorState,
+    color: Color,
+    subscription: u64,
+    _listeners: Vec<EventListener>,
+}
+
+impl ColorSwatch {
+    fn reflect(element: &HtmlElement, swatch: &Color, color: &Color) {
+        let is_selected = swatch.to_hex() == color.to_hex();
+        let _ = element.set_attribute("aria-pressed", if is_selected { "true" } else { "false" });
+        if is_selected {
+            let _ = element.set_attribute("data-selected", "");
+        } else {
+            let _ = element.remove_attribute("data-selected");
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    pub fn state(&self) -> &ColorState {
+        &self.state
+    }
+}
+
+impl Drop for ColorSwatch {
+    fn drop(&mut self) {
+        self.state.unsubscribe(self.subscription);
+    }
+}
+
+pub fn create_swatch(state: ColorState, element: HtmlElement, color: Color) -> ColorSwatch {
+    set_property(
+        &element,
+        "--seigi-color",
+        color.to_css(ColorFormat::Rgb).as_str(),
+    );
+    if !element.has_attribute("aria-label") {
+        let _ = element.set_attribute("aria-label", color.to_hex().as_str());
+    }
+    if !element.has_attribute("tabindex") {
+        element.set_tab_index(0);
+    }
+    ColorSwatch::reflect(&element, &color, &state.color());
+
+    let click = EventListener::new(&element, "click", {
+        let state = state.clone();
+        move |_| state.set_color(color)
+    });
+    let key_down = EventListener::new(&element, "keydown", {
+        let state = state.clone();
+        move |event| {
+            let Some(event) = event.dyn_ref::<KeyboardEvent>() else {
+                return;
+            };
+            if matches!(event.key().as_str(), "Enter" | " ") {
+                event.prevent_default();
+                state.set_color(color);
+            }
+        }
+    });
+
+    let subscription = state.subscribe(Box::new({
+        let element = element.clone();
+        move |current| ColorSwatch::reflect(&element, &color, current)
+    }));
+
+    ColorSwatch {
+        state,
+        color,
+        subscription,
+        _listeners: vec![click, key_down],
+    }
+}
@@ -0,0 +1,153 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use seigi_color::{Color, ColorFormat, Hsl, Oklch, ParseColorError, Rgb};
+
+const fn rgb(r: u8, g: u8, b: u8) -> Rgb {
+    Rgb { r, g, b, a: 1.0 }
+}
+
+const SAMPLES: [Rgb; 8] = [
+    rgb(0, 0, 0),
+    rgb(255, 255, 255),
+    rgb(128, 128, 128),
+    rgb(255, 0, 0),
+    rgb(0, 255, 0),
+    rgb(0, 0, 255),
+    rgb(51, 102, 153),
+    rgb(250, 128, 114),
+];
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-3,
+        "{actual} isn't close to {expected}"
+    );
+}
+
+#[test]
+fn hex_parses_every_length() {
+    let cases = [
+        ("#f00", rgb(255, 0, 0), 1.0),
+        ("#f008", rgb(255, 0, 0), 136.0 / 255.0),
+        ("#336699", rgb(51, 102, 153), 1.0),
+        ("#33669980", rgb(51, 102, 153), 128.0 / 255.0),
+        (" 336699 ", rgb(51, 102, 153), 1.0),
+    ];
+    for (hex, expected, alpha) in cases {
+        let color = Color::from_hex(hex).unwrap_or_else(|| panic!("{hex} isn't parsed"));
+        let actual = color.to_rgb();
+        assert_eq!(
+            (actual.r, actual.g, actual.b),
+            (expected.r, expected.g, expected.b)
+        );
+        assert_close(actual.a, alpha);
+    }
+    assert_eq!(
+        "#336699".parse::<Color>().map(|v| v.to_hex()),
+        Ok("#336699".into())
+    );
+    assert_eq!(Color::from_hex("#33669980").unwrap().to_hex(), "#33669980");
+}
+
+#[test]
+fn malformed_hex_is_rejected() {
+    for hex in [
+        "",
+        "#",
+        "#12",
+        "#12345",
+        "#1234567",
+        "#123456789",
+        "#ggg",
+        "#12345z",
+        "#ééé",
+        "rgb(0 0 0)",
+    ] {
+        assert_eq!(Color::from_hex(hex), None, "{hex:?} is parsed");
+        assert_eq!(hex.parse::<Color>(), Err(ParseColorError));
+    }
+}
+
+#[test]
+fn rgb_round_trips_through_hsv() {
+    for sample in SAMPLES {
+        assert_eq!(Color::from_rgb(sample).to_rgb(), sample);
+    }
+}
+
+#[test]
+fn rgb_round_trips_through_hsl() {
+    for sample in SAMPLES {
+        let hsl = Color::from_rgb(sample).to_hsl();
+        assert_eq!(Color::from_hsl(hsl).to_rgb(), sample);
+    }
+
+    let hsl = Color::from_rgb(rgb(51, 102, 153)).to_hsl();
+    assert_close(hsl.h, 210.0);
+    assert_close(hsl.s, 0.5);
+    assert_close(hsl.l, 0.4);
+    let color = Color::from_hsl(Hsl {
+        h: 0.0,
+        s: 1.0,
+        l: 0.5,
+        a: 0.5,
+    });
+    assert_eq!(
+        color.to_rgb(),
+        Rgb {
+            a: 0.5,
+            ..rgb(255, 0, 0)
+        }
+    );
+}
+
+#[test]
+fn rgb_round_trips_through_oklch() {
+    for sample in SAMPLES {
+        let oklch = Color::from_rgb(sample).to_oklch();
+        assert_eq!(Color::from_oklch(oklch).to_rgb(), sample);
+    }
+
+    let red = Color::from_rgb(rgb(255, 0, 0)).to_oklch();
+    assert_close(red.l, 0.628);
+    assert!((red.c - 0.2577).abs() < 1e-3);
+    assert!((red.h - 29.23).abs() < 0.1);
+    assert_eq!(
+        Color::from_rgb(rgb(255, 0, 0)).to_css(ColorFormat::Oklch),
+        "oklch(62.8% 0.2577 29.23)"
+    );
+}
+
+#[test]
+fn gray_keeps_hue_through_oklch() {
+    let gray = Color::from_hsva(120.0, 0.0, 0.5, 1.0);
+    assert_close(gray.to_oklch().h, 120.0);
+
+    let white = Color::from_oklch(Oklch {
+        l: 1.0,
+        c: 0.0,
+        h: 200.0,
+        a: 1.0,
+    });
+    assert_eq!(white.to_rgb(), rgb(255, 255, 255));
+    assert_close(white.hue, 200.0);
+}
+
+#[test]
+fn out_of_gamut_oklch_is_clipped() {
+    let oklch = |l, c, h| Oklch { l, c, h, a: 1.0 };
+    // Channels below 0 are clipped, leaving the green one
+    assert_eq!(
+        Color::from_oklch(oklch(0.7, 0.4, 150.0)).to_rgb(),
+        rgb(0, 214, 0)
+    );
+    // Channels above 1 are clipped
+    assert_eq!(
+        Color::from_oklch(oklch(0.9, 0.3, 264.0)).to_rgb(),
+        rgb(115, 205, 255)
+    );
+    assert_eq!(
+        Color::from_oklch(oklch(1.2, 0.0, 0.0)).to_rgb(),
+        rgb(255, 255, 255)
+    );
+}
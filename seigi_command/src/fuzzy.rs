@@ -0,0 +1,97 @@
+//! Fuzzy matching of queries against command labels
+
+/// A successful match of a query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    /// Higher is a better match
+    pub score: i32,
+    /// Indices of matched characters in the text, in chars
+    pub positions: Vec<usize>,
+}
+
+fn is_word_start(chars: &[char], index: usize) -> bool {
+    let Some(previous) = index.checked_sub(1).map(|v| chars[v]) else {
+        return true;
+    };
+    !previous.is_alphanumeric() || (previous.is_lowercase() && chars[index].is_uppercase())
+}
+
+fn is_subsequence(query: &[char], text: &[char]) -> bool {
+    let mut text = text.iter();
+    query.iter().all(|c| text.any(|v| v == c))
+}
+
+/// Matches the query against the text as a case-insensitive subsequence
+///
+/// Matches at the start of the text or words and consecutive matches score higher, and gaps
+/// between matched characters score lower. Whitespace in the query is ignored.
+///
+/// # Returns
+/// None if some character of the query is not in the text in order
+pub fn score(query: &str, text: &str) -> Option<Match> {
+    let query = query
+        .chars()
+        .filter(|v| !v.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect::<Vec<_>>();
+    let chars = text.chars().collect::<Vec<_>>();
+    if query.is_empty() {
+        return Some(Match {
+            score: 0,
+            positions: vec![],
+        });
+    }
+
+    // Lowercasing may expand a character, like `İ` into `i` and a combining dot, so each
+    // lowercase character keeps the index of the character it came from
+    let (lower, source): (Vec<_>, Vec<_>) = chars
+        .iter()
+        .enumerate()
+        .flat_map(|(i, c)| c.to_lowercase().map(move |v| (v, i)))
+        .unzip();
+    let is_start = |index: usize| {
+        (index == 0 || source[index - 1] != source[index]) && is_word_start(&chars, source[index])
+    };
+
+    // Prefers a consecutive match, then a word start, then the earliest match for each character,
+    // as long as the rest of the query still matches after it
+    let mut matched = Vec::<usize>::with_capacity(query.len());
+    let mut from = 0;
+    for (query_index, c) in query.iter().enumerate() {
+        let rest = &query[query_index + 1..];
+        let candidates = (from..lower.len())
+            .filter(|v| lower[*v] == *c && is_subsequence(rest, &lower[v + 1..]))
+            .collect::<Vec<_>>();
+        let index = candidates
+            .iter()
+            .find(|v| matched.last().is_some_and(|last| last + 1 == **v))
+            .or_else(|| candidates.iter().find(|v| is_start(**v)))
+            .or(candidates.first())?;
+        matched.push(*index);
+        from = index + 1;
+    }
+
+    let mut score = 0;
+    for (i, index) in matched.iter().enumerate() {
+        score += 1;
+        if *index == 0 {
+            score += 8;
+        } else if is_start(*index) {
+            score += 6;
+        }
+        if i > 0 {
+            let gap = source[*index].saturating_sub(source[matched[i - 1]] + 1);
+            if gap == 0 {
+                score += 4;
+            } else {
+                score -= gap.min(5) as i32;
+            }
+        }
+    }
+    // Shorter texts are closer to what was typed
+    score -= (lower.len().saturating_sub(query.len()) / 8) as i32;
+
+    let mut positions = matched.into_iter().map(|v| source[v]).collect::<Vec<_>>();
+    positions.dedup();
+    Some(Match { score, positions })
+}
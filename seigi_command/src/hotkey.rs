@@ -0,0 +1,67 @@
+//! Keyboard shortcuts opening the palette
+
+use web_sys::KeyboardEvent;
+
+/// A key combination
+///
+/// `primary` is Cmd on Apple platforms and Ctrl elsewhere, which is what most palettes bind to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hotkey {
+    /// The key, compared case-insensitively against [KeyboardEvent::key]
+    pub key: String,
+    pub primary: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl Hotkey {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            primary: false,
+            shift: false,
+            alt: false,
+        }
+    }
+
+    /// Parses a combination like `Mod+K` or `Shift+Alt+P`
+    ///
+    /// `Mod` is the primary modifier, and `Ctrl`, `Control`, `Cmd` and `Meta` are accepted as its
+    /// aliases.
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.split('+').map(str::trim).collect::<Vec<_>>();
+        let key = parts.pop().filter(|v| !v.is_empty())?;
+        let mut hotkey = Self::new(key);
+        for part in parts {
+            match part.to_lowercase().as_str() {
+                "mod" | "ctrl" | "control" | "cmd" | "meta" => hotkey.primary = true,
+                "shift" => hotkey.shift = true,
+                "alt" | "option" => hotkey.alt = true,
+                _ => return None,
+            }
+        }
+        Some(hotkey)
+    }
+
+    pub fn primary(mut self) -> Self {
+        self.primary = true;
+        self
+    }
+
+    pub fn shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    pub fn alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    pub fn matches(&self, event: &KeyboardEvent) -> bool {
+        event.key().eq_ignore_ascii_case(&self.key)
+            && (event.ctrl_key() || event.meta_key()) == self.primary
+            && event.shift_key() == self.shift
+            && event.alt_key() == self.alt
+    }
+}
@@ -0,0 +1,821 @@
+//! A command palette with fuzzy filtering, groups and recent commands
+
+pub mod fuzzy;
+mod hotkey;
+
+use std::{
+    rc::{Rc, Weak},
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use gloo::{events::EventListener, utils::document};
+use seigi_focus::{FocusTrap, FocusTrapOptions, InitialFocus};
+use seigi_utils::{
+    audit,
+    id::{self, Relation},
+    ime, top_layer,
+};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlDialogElement, HtmlElement, HtmlInputElement, KeyboardEvent};
+
+pub use hotkey::Hotkey;
+
+pub type ActionHook = Rc<dyn Fn()>;
+
+/// A command shown in the palette
+#[derive(Clone)]
+pub struct Command {
+    /// The unique identifier of the command
+    pub id: String,
+    pub label: String,
+    /// The group the command is listed under
+    pub group: Option<String>,
+    /// Additional words the command matches, which are not displayed
+    pub keywords: Vec<String>,
+    /// The shortcut displayed next to the label. It is only a hint, and not bound by the palette
+    pub shortcut: Option<String>,
+    pub disabled: bool,
+    /// Called when the command is executed
+    pub action: Option<ActionHook>,
+}
+
+impl Command {
+    pub fn new(id: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            group: None,
+            keywords: vec![],
+            shortcut: None,
+            disabled: false,
+            action: None,
+        }
+    }
+
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    pub fn keywords(mut self, keywords: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.keywords = keywords.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn shortcut(mut self, shortcut: impl Into<String>) -> Self {
+        self.shortcut = Some(shortcut.into());
+        self
+    }
+
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    pub fn action(mut self, action: impl Fn() + 'static) -> Self {
+        self.action = Some(Rc::new(action));
+        self
+    }
+
+    /// Matches the query against the label, falling back to keywords with a lower score
+    fn matches(&self, query: &str) -> Option<fuzzy::Match> {
+        if let Some(found) = fuzzy::score(query, &self.label) {
+            return Some(found);
+        }
+        self.keywords
+            .iter()
+            .filter_map(|v| fuzzy::score(query, v))
+            .map(|v| fuzzy::Match {
+                score: v.score - 4,
+                positions: vec![],
+            })
+            .max_by_key(|v| v.score)
+    }
+}
+
+/// An event of [CommandPalette]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandEvent {
+    Open,
+    Close,
+    /// A command was executed, with its id
+    Execute(String),
+}
+
+struct Subscriber {
+    callback: Box<dyn Fn(&CommandEvent)>,
+    handle: u64,
+}
+
+/// Options of [CommandPalette]
+pub struct CommandPaletteOptions {
+    /// The dialog the palette is shown in, opened as a modal
+    pub dialog: HtmlDialogElement,
+    /// The input the query is typed in
+    pub input: HtmlInputElement,
+    /// The element commands are rendered into
+    pub list: HtmlElement,
+    /// The shortcut toggling the palette anywhere in the document
+    pub hotkey: Option<Hotkey>,
+    /// The number of recently executed commands shown while the query is empty
+    pub recent_limit: usize,
+    /// The heading of the group of recent commands
+    pub recent_heading: String,
+    /// Whether executing a command closes the palette
+    pub close_on_execute: bool,
+    /// Whether the document can't be scrolled while the palette is open
+    pub scroll_lock: bool,
+}
+
+impl CommandPaletteOptions {
+    pub fn builder() -> CommandPaletteOptionsBuilder {
+        CommandPaletteOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [CommandPaletteOptions]
+pub struct CommandPaletteOptionsBuilder {
+    dialog: Option<HtmlDialogElement>,
+    input: Option<HtmlInputElement>,
+    list: Option<HtmlElement>,
+    hotkey: Option<Hotkey>,
+    recent_limit: usize,
+    recent_heading: String,
+    close_on_execute: bool,
+    scroll_lock: bool,
+}
+
+impl Default for CommandPaletteOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            dialog: None,
+            input: None,
+            list: None,
+            hotkey: Some(Hotkey::new("k").primary()),
+            recent_limit: 5,
+            recent_heading: "Recent".to_string(),
+            close_on_execute: true,
+            scroll_lock: true,
+        }
+    }
+}
+
+impl CommandPaletteOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn dialog(mut self, dialog: HtmlDialogElement) -> Self {
+        self.dialog = Some(dialog);
+        self
+    }
+
+    pub fn input(mut self, input: HtmlInputElement) -> Self {
+        self.input = Some(input);
+        self
+    }
+
+    pub fn list(mut self, list: HtmlElement) -> Self {
+        self.list = Some(list);
+        self
+    }
+
+    /// Sets the shortcut toggling the palette. Defaults to Ctrl/Cmd+K, and None disables it
+    pub fn hotkey(mut self, hotkey: Option<Hotkey>) -> Self {
+        self.hotkey = hotkey;
+        self
+    }
+
+    pub fn recent_limit(mut self, recent_limit: usize) -> Self {
+        self.recent_limit = recent_limit;
+        self
+    }
+
+    pub fn recent_heading(mut self, recent_heading: impl Into<String>) -> Self {
+        self.recent_heading = recent_heading.into();
+        self
+    }
+
+    pub fn close_on_execute(mut self, close_on_execute: bool) -> Self {
+        self.close_on_execute = close_on_execute;
+        self
+    }
+
+    pub fn scroll_lock(mut self, scroll_lock: bool) -> Self {
+        self.scroll_lock = scroll_lock;
+        self
+    }
+
+    /// Builds into [CommandPaletteOptions]
+    ///
+    /// # Panics
+    /// This method panics if dialog, input or list field is not set
+    pub fn build(self) -> CommandPaletteOptions {
+        CommandPaletteOptions {
+            dialog: self
+                .dialog
+                .expect("dialog must be set to build CommandPaletteOptions"),
+            input: self
+                .input
+                .expect("input must be set to build CommandPaletteOptions"),
+            list: self
+                .list
+                .expect("list must be set to build CommandPaletteOptions"),
+            hotkey: self.hotkey,
+            recent_limit: self.recent_limit,
+            recent_heading: self.recent_heading,
+            close_on_execute: self.close_on_execute,
+            scroll_lock: self.scroll_lock,
+        }
+    }
+}
+
+/// Commands rendered under a heading, with matched positions of their labels
+struct Section {
+    heading: Option<String>,
+    commands: Vec<(usize, Vec<usize>)>,
+}
+
+struct Inner {
+    options: Rc<CommandPaletteOptions>,
+    commands: Vec<Command>,
+    /// Ids of executed commands, the latest first
+    recent: Vec<String>,
+    /// Indices of rendered commands in their order
+    visible: Vec<usize>,
+    /// Index into visible of the highlighted command
+    active: Option<usize>,
+    trap: FocusTrap,
+    subscribers: Vec<Rc<Subscriber>>,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn sections(&self, query: &str) -> Vec<Section> {
+        let mut sections = Vec::<Section>::new();
+        let mut push =
+            |heading: Option<&String>, index: usize, positions: Vec<usize>| match sections
+                .iter_mut()
+                .find(|v| v.heading.as_ref() == heading)
+            {
+                Some(section) => section.commands.push((index, positions)),
+                None => sections.push(Section {
+                    heading: heading.cloned(),
+                    commands: vec![(index, positions)],
+                }),
+            };
+
+        if query.trim().is_empty() {
+            let recent = self
+                .recent
+                .iter()
+                .filter_map(|id| self.commands.iter().position(|v| v.id == *id))
+                .take(self.options.recent_limit)
+                .collect::<Vec<_>>();
+            for index in &recent {
+                push(Some(&self.options.recent_heading), *index, vec![]);
+            }
+            for (index, command) in self.commands.iter().enumerate() {
+                if !recent.contains(&index) {
+                    push(command.group.as_ref(), index, vec![]);
+                }
+            }
+        } else {
+            let mut matches = self
+                .commands
+                .iter()
+                .enumerate()
+                .filter_map(|(index, command)| command.matches(query).map(|v| (index, v)))
+                .collect::<Vec<_>>();
+            matches.sort_by_key(|(_, v)| -v.score);
+            for (index, found) in matches {
+                push(self.commands[index].group.as_ref(), index, found.positions);
+            }
+        }
+
+        // Ungrouped commands are listed before groups
+        sections.sort_by_key(|v| v.heading.is_some());
+        sections
+    }
+
+    fn list_id(&self) -> String {
+        id::ensure(&self.options.list, "command")
+    }
+
+    /// Renders commands matching the current query
+    fn render(&mut self) {
+        let document = document();
+        let create = |tag: &str| {
+            document
+                .create_element(tag)
+                .expect("failed to create element")
+        };
+        let list_id = self.list_id();
+        let list = &self.options.list;
+        list.set_text_content(None);
+        self.visible.clear();
+
+        let sections = self.sections(&self.options.input.value());
+        for (section_index, section) in sections.iter().enumerate() {
+            let parent: Element = match &section.heading {
+                Some(heading) => {
+                    let group = create("div");
+                    let label = create("div");
+                    let label_id = format!("{list_id}-group-{section_index}");
+                    label.set_id(&label_id);
+                    let _ = label.set_attribute("data-seigi-command-group-heading", "");
+                    label.set_text_content(Some(heading));
+                    let _ = group.set_attribute("role", "group");
+                    let _ = group.set_attribute("data-seigi-command-group", heading);
+                    id::relate(&group, Relation::LabelledBy, &label);
+                    let _ = group.append_child(&label);
+                    let _ = list.append_child(&group);
+                    group
+                }
+                None => list.clone().into(),
+            };
+
+            for (index, positions) in &section.commands {
+                let command = &self.commands[*index];
+                let option = create("div");
+                option.set_id(&format!("{list_id}-option-{}", self.visible.len()));
+                let _ = option.set_attribute("role", "option");
+                let _ = option.set_attribute("data-seigi-command", &command.id);
+                let _ = option.set_attribute("aria-selected", "false");
+                if command.disabled {
+                    let _ = option.set_attribute("aria-disabled", "true");
+                    let _ = option.set_attribute("data-disabled", "");
+                }
+
+                let label = create("span");
+                let _ = label.set_attribute("data-seigi-command-label", "");
+                let mut text = String::new();
+                for (char_index, c) in command.label.chars().enumerate() {
+                    if positions.contains(&char_index) {
+                        if !text.is_empty() {
+                            let _ = label.append_with_str_1(&text);
+                            text.clear();
+                        }
+                        let mark = create("mark");
+                        mark.set_text_content(Some(&c.to_string()));
+                        let _ = label.append_child(&mark);
+                    } else {
+                        text.push(c);
+                    }
+                }
+                if !text.is_empty() {
+                    let _ = label.append_with_str_1(&text);
+                }
+                let _ = option.append_child(&label);
+
+                if let Some(shortcut) = &command.shortcut {
+                    let kbd = create("kbd");
+                    let _ = kbd.set_attribute("data-seigi-command-shortcut", "");
+                    kbd.set_text_content(Some(shortcut));
+                    let _ = option.append_child(&kbd);
+                }
+
+                let _ = parent.append_child(&option);
+                self.visible.push(*index);
+            }
+        }
+
+        if let Ok(Some(empty)) = self
+            .options
+            .dialog
+            .query_selector("[data-seigi-command-empty]")
+            && let Ok(empty) = empty.dyn_into::<HtmlElement>()
+        {
+            empty.set_hidden(!self.visible.is_empty());
+        }
+
+        let first = self.step(None, 1);
+        self.set_active(first);
+    }
+
+    fn options(&self) -> Vec<HtmlElement> {
+        let Ok(nodes) = self.options.list.query_selector_all("[role=option]") else {
+            return vec![];
+        };
+        (0..nodes.length())
+            .filter_map(|v| nodes.get(v))
+            .filter_map(|v| v.dyn_into::<HtmlElement>().ok())
+            .collect()
+    }
+
+    /// Returns the next enabled command from the position in the direction, wrapping around
+    fn step(&self, from: Option<usize>, direction: isize) -> Option<usize> {
+        let len = self.visible.len() as isize;
+        let start = match from {
+            Some(from) => from as isize,
+            None if direction > 0 => -1,
+            None => len,
+        };
+        (1..=len)
+            .map(|v| (start + v * direction).rem_euclid(len) as usize)
+            .find(|v| !self.commands[self.visible[*v]].disabled)
+    }
+
+    fn set_active(&mut self, active: Option<usize>) {
+        self.active = active;
+        let input = &self.options.input;
+        for (index, option) in self.options().iter().enumerate() {
+            if Some(index) == active {
+                let _ = option.set_attribute("aria-selected", "true");
+                let _ = option.set_attribute("data-active", "");
+                let _ = input.set_attribute("aria-activedescendant", &option.id());
+
+                // Scrolls the list just enough to show the option
+                let list = &self.options.list;
+                let (option_rect, list_rect) = (
+                    option.get_bounding_client_rect(),
+                    list.get_bounding_client_rect(),
+                );
+                if option_rect.top() < list_rect.top() {
+                    list.set_scroll_top(
+                        list.scroll_top() - (list_rect.top() - option_rect.top()).ceil() as i32,
+                    );
+                } else if option_rect.bottom() > list_rect.bottom() {
+                    list.set_scroll_top(
+                        list.scroll_top()
+                            + (option_rect.bottom() - list_rect.bottom()).ceil() as i32,
+                    );
+                }
+            } else {
+                let _ = option.set_attribute("aria-selected", "false");
+                let _ = option.remove_attribute("data-active");
+            }
+        }
+        if active.is_none() {
+            let _ = input.remove_attribute("aria-activedescendant");
+        }
+    }
+}
+
+/// An instance of command palette
+///
+/// The palette is shown in a modal dialog, with focus trapped inside and returned when it closes.
+/// Typing in the input filters commands with [fuzzy::score], arrow keys highlight commands and
+/// Enter executes the highlighted one. While the query is empty, recently executed commands are
+/// listed first.
+///
+/// An element with `data-seigi-command-empty` inside the dialog is shown only when no command
+/// matches.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// Commands are rendered as `role="option"` elements with **data-seigi-command** of their id,
+/// containing a **data-seigi-command-label** element where matched characters are wrapped in
+/// `<mark>`, and a **data-seigi-command-shortcut** `<kbd>` if the command has a shortcut
+///
+/// Groups are rendered as `role="group"` elements with **data-seigi-command-group** of their
+/// name, containing a **data-seigi-command-group-heading** element
+///
+/// **data-active** and **aria-selected** are set in the highlighted command
+///
+/// **data-disabled** and **aria-disabled** are set in disabled commands
+///
+/// **data-open** is set in the dialog while the palette is open
+#[derive(Clone)]
+pub struct CommandPalette(Rc<Mutex<Inner>>);
+
+impl CommandPalette {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    fn options(&self) -> Rc<CommandPaletteOptions> {
+        self.0.lock().unwrap().options.clone()
+    }
+
+    /// Adds the command, replacing a command with the same id
+    pub fn register_command(&self, command: Command) {
+        let mut inner = self.0.lock().unwrap();
+        match inner.commands.iter_mut().find(|v| v.id == command.id) {
+            Some(current) => *current = command,
+            None => inner.commands.push(command),
+        }
+        inner.render();
+    }
+
+    pub fn unregister_command(&self, id: &str) {
+        let mut inner = self.0.lock().unwrap();
+        inner.commands.retain(|v| v.id != id);
+        inner.render();
+    }
+
+    pub fn commands(&self) -> Vec<Command> {
+        self.0.lock().unwrap().commands.clone()
+    }
+
+    /// Returns ids of recently executed commands, the latest first
+    pub fn recent(&self) -> Vec<String> {
+        self.0.lock().unwrap().recent.clone()
+    }
+
+    /// Replaces recently executed commands, to restore them from a storage for example
+    pub fn set_recent(&self, recent: Vec<String>) {
+        let mut inner = self.0.lock().unwrap();
+        inner.recent = recent;
+        inner.render();
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.options().dialog.open()
+    }
+
+    /// Opens the palette with an empty query
+    pub fn open(&self) {
+        let (options, trap) = {
+            let mut inner = self.0.lock().unwrap();
+            if inner.options.dialog.open() {
+                return;
+            }
+            inner.options.input.set_value("");
+            inner.render();
+            (inner.options.clone(), inner.trap.clone())
+        };
+
+        top_layer::show_modal(&options.dialog);
+        let _ = options.dialog.set_attribute("data-open", "");
+        audit::dialog("seigi_command", &options.dialog);
+        let _ = options.input.set_attribute("aria-expanded", "true");
+        trap.activate();
+        self.emit(CommandEvent::Open);
+    }
+
+    pub fn close(&self) {
+        let options = self.options();
+        if options.dialog.open() {
+            // The close event listener finishes closing
+            top_layer::close_modal(&options.dialog);
+        }
+    }
+
+    pub fn toggle(&self) {
+        if self.is_open() {
+            self.close();
+        } else {
+            self.open();
+        }
+    }
+
+    /// Executes the command, calling its action and emitting [CommandEvent::Execute]
+    ///
+    /// Disabled commands are not executed
+    pub fn execute(&self, id: &str) {
+        let (action, close) = {
+            let mut inner = self.0.lock().unwrap();
+            let Some(command) = inner.commands.iter().find(|v| v.id == id && !v.disabled) else {
+                return;
+            };
+            let action = command.action.clone();
+            inner.recent.retain(|v| v != id);
+            inner.recent.insert(0, id.to_string());
+            let limit = inner.options.recent_limit;
+            inner.recent.truncate(limit);
+            (action, inner.options.close_on_execute)
+        };
+
+        // Closes first, so focus is returned before the action may move it
+        if close {
+            self.close();
+        }
+        if let Some(action) = action {
+            action();
+        }
+        self.emit(CommandEvent::Execute(id.to_string()));
+    }
+
+    /// Add subscriber to the palette and return handle to it
+    ///
+    /// # Returns
+    /// Handle of added subscriber
+    pub fn subscribe(&self, callback: Box<dyn Fn(&CommandEvent)>) -> u64 {
+        static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+        let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .push(Rc::new(Subscriber { callback, handle }));
+
+        handle
+    }
+
+    /// Remove subscriber from the palette
+    pub fn unsubscribe(&self, handle: u64) {
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .retain(|v| v.handle != handle);
+    }
+
+    fn emit(&self, event: CommandEvent) {
+        let subscribers = self.0.lock().unwrap().subscribers.clone();
+        for subscriber in subscribers {
+            (subscriber.callback)(&event);
+        }
+    }
+
+    fn handle_close(&self) {
+        let (options, trap) = {
+            let mut inner = self.0.lock().unwrap();
+            inner.set_active(None);
+            (inner.options.clone(), inner.trap.clone())
+        };
+        let _ = options.dialog.remove_attribute("data-open");
+        let _ = options.input.set_attribute("aria-expanded", "false");
+        trap.deactivate();
+        self.emit(CommandEvent::Close);
+    }
+
+    fn handle_key_down(&self, event: &KeyboardEvent) {
+        // Enter and arrow keys confirm and pick candidates while an IME composes the query
+        if ime::is_composing(event) {
+            return;
+        }
+        let mut inner = self.0.lock().unwrap();
+        let active = inner.active;
+        let next = match event.key().as_str() {
+            "ArrowDown" => inner.step(active, 1),
+            "ArrowUp" => inner.step(active, -1),
+            "Enter" => {
+                let Some(id) = active.map(|v| inner.commands[inner.visible[v]].id.clone()) else {
+                    return;
+                };
+                event.prevent_default();
+                drop(inner);
+                self.execute(&id);
+                return;
+            }
+            _ => return,
+        };
+        event.prevent_default();
+        inner.set_active(next);
+    }
+
+    /// Returns the index into visible commands of the option containing the target
+    fn option_at(&self, target: &Element) -> Option<usize> {
+        let option = target.closest("[role=option]").ok().flatten()?;
+        self.0
+            .lock()
+            .unwrap()
+            .options()
+            .iter()
+            .position(|v| **v == option)
+    }
+}
+
+pub fn create_command_palette(options: CommandPaletteOptions) -> CommandPalette {
+    let dialog = options.dialog.clone();
+    let input = options.input.clone();
+    let list = options.list.clone();
+
+    let trap = seigi_focus::create(
+        FocusTrapOptions::builder()
+            .target(dialog.clone().into())
+            .initial_focus(InitialFocus::Element(input.clone().into()))
+            .scroll_lock(options.scroll_lock)
+            .build(),
+    );
+
+    let palette = CommandPalette(Rc::new(Mutex::new(Inner {
+        options: Rc::new(options),
+        commands: vec![],
+        recent: vec![],
+        visible: vec![],
+        active: None,
+        trap,
+        subscribers: vec![],
+        _listeners: vec![],
+    })));
+
+    // The list is given its own id before it is referred to
+    palette.0.lock().unwrap().list_id();
+    let _ = list.set_attribute("role", "listbox");
+    let _ = input.set_attribute("role", "combobox");
+    let _ = input.set_attribute("aria-autocomplete", "list");
+    id::relate(&input, Relation::Controls, &list);
+    let _ = input.set_attribute("aria-expanded", "false");
+    let _ = input.set_attribute("autocomplete", "off");
+
+    let weak = Rc::downgrade(&palette.0);
+    let mut listeners = vec![
+        EventListener::new(&input, "input", {
+            let weak = weak.clone();
+            move |_| {
+                let Some(palette) = CommandPalette::from_weak(&weak) else {
+                    return;
+                };
+                palette.0.lock().unwrap().render();
+            }
+        }),
+        EventListener::new(&input, "keydown", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(palette), Some(event)) = (
+                    CommandPalette::from_weak(&weak),
+                    event.dyn_ref::<KeyboardEvent>(),
+                ) else {
+                    return;
+                };
+                palette.handle_key_down(event);
+            }
+        }),
+        EventListener::new(&list, "pointermove", {
+            let weak = weak.clone();
+            move |event| {
+                let Some(palette) = CommandPalette::from_weak(&weak) else {
+                    return;
+                };
+                let Some(index) = event
+                    .target()
+                    .and_then(|v| v.dyn_into::<Element>().ok())
+                    .and_then(|v| palette.option_at(&v))
+                else {
+                    return;
+                };
+                let mut inner = palette.0.lock().unwrap();
+                if inner.active != Some(index) && !inner.commands[inner.visible[index]].disabled {
+                    inner.set_active(Some(index));
+                }
+            }
+        }),
+        EventListener::new(&list, "click", {
+            let weak = weak.clone();
+            move |event| {
+                let Some(palette) = CommandPalette::from_weak(&weak) else {
+                    return;
+                };
+                let Some(index) = event
+                    .target()
+                    .and_then(|v| v.dyn_into::<Element>().ok())
+                    .and_then(|v| palette.option_at(&v))
+                else {
+                    return;
+                };
+                let id = {
+                    let inner = palette.0.lock().unwrap();
+                    inner.commands[inner.visible[index]].id.clone()
+                };
+                palette.execute(&id);
+            }
+        }),
+        EventListener::new(&dialog, "click", {
+            let weak = weak.clone();
+            let dialog = dialog.clone();
+            move |event| {
+                let Some(palette) = CommandPalette::from_weak(&weak) else {
+                    return;
+                };
+                // Clicks on the backdrop target the dialog itself
+                if event.target().as_ref() == Some(dialog.as_ref()) {
+                    palette.close();
+                }
+            }
+        }),
+        EventListener::new(&dialog, "close", {
+            let weak = weak.clone();
+            move |_| {
+                let Some(palette) = CommandPalette::from_weak(&weak) else {
+                    return;
+                };
+                palette.handle_close();
+            }
+        }),
+    ];
+
+    let hotkey = palette.options().hotkey.clone();
+    if let Some(hotkey) = hotkey {
+        listeners.push(EventListener::new(&document(), "keydown", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(palette), Some(event)) = (
+                    CommandPalette::from_weak(&weak),
+                    event.dyn_ref::<KeyboardEvent>(),
+                ) else {
+                    return;
+                };
+                if hotkey.matches(event) {
+                    event.prevent_default();
+                    palette.toggle();
+                }
+            }
+        }));
+    }
+
+    {
+        let mut inner = palette.0.lock().unwrap();
+        inner._listeners = listeners;
+        inner.render();
+    }
+
+    palette
+}
@@ -0,0 +1,74 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use seigi_command::fuzzy::{Match, score};
+
+fn rank(query: &str, text: &str) -> i32 {
+    score(query, text).unwrap().score
+}
+
+fn positions(query: &str, text: &str) -> Vec<usize> {
+    score(query, text).unwrap().positions
+}
+
+#[test]
+fn prefix_beats_word_start_beats_gaps() {
+    let prefix = rank("term", "Terminal");
+    let word_start = rank("term", "Open Terminal");
+    let gaps = rank("term", "Setter form");
+    assert!(prefix > word_start, "{prefix} > {word_start}");
+    assert!(word_start > gaps, "{word_start} > {gaps}");
+}
+
+#[test]
+fn consecutive_matches_score_higher() {
+    assert!(rank("set", "Settings") > rank("set", "Select text"));
+    // Longer texts with the same match score lower
+    assert!(rank("open", "Open") > rank("open", "Open recent files and folders"));
+}
+
+#[test]
+fn word_starts_are_preferred() {
+    assert_eq!(positions("gc", "Git Commit"), [0, 4]);
+    assert_eq!(positions("fb", "toggleFooBar"), [6, 9]);
+    assert_eq!(positions("rf", "reformat-file"), [0, 9]);
+    // A consecutive match is preferred over a later word start
+    assert_eq!(positions("ab", "ab Bc"), [0, 1]);
+}
+
+#[test]
+fn unmatched_queries_are_none() {
+    assert_eq!(score("xyz", "Open file"), None);
+    // Characters have to be in order
+    assert_eq!(score("fo", "Open file"), None);
+    assert_eq!(
+        score("", "Open file"),
+        Some(Match {
+            score: 0,
+            positions: vec![],
+        })
+    );
+}
+
+#[test]
+fn matching_is_case_insensitive() {
+    assert_eq!(score("OPEN", "open file"), score("open", "Open file"));
+    assert_eq!(positions("OF", "open file"), [0, 5]);
+}
+
+#[test]
+fn whitespace_in_query_is_ignored() {
+    assert_eq!(
+        score("op term", "Open Terminal"),
+        score("opterm", "Open Terminal")
+    );
+    assert!(score("  ", "Open Terminal").unwrap().positions.is_empty());
+}
+
+#[test]
+fn characters_lowercased_into_several_match() {
+    // `İ` lowercases into `i` and a combining dot, on both sides
+    assert_eq!(positions("İst", "İstanbul"), [0, 1, 2]);
+    assert_eq!(positions("ist", "İSTANBUL"), [0, 1, 2]);
+    assert_eq!(positions("İs", "İSTANBUL"), [0, 1]);
+    assert_eq!(rank("is", "İstanbul"), rank("is", "Istanbul"));
+}
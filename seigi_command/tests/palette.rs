@@ -0,0 +1,44 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_command::{Command, CommandPaletteOptions};
+use seigi_testkit::{Fixture, events, wait};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<p id="outside">Outside</p>
+<dialog id="palette">
+    <input id="query">
+    <div id="list"></div>
+</dialog>
+"#;
+
+#[wasm_bindgen_test]
+async fn touch_scrolls_inside_open_palette() {
+    let fixture = Fixture::new(MARKUP);
+    let palette = seigi_command::create_command_palette(
+        CommandPaletteOptions::builder()
+            .dialog(fixture.get("#palette").unchecked_into())
+            .input(fixture.get("#query").unchecked_into())
+            .list(fixture.get("#list"))
+            .hotkey(None)
+            .build(),
+    );
+    for index in 0..50 {
+        palette.register_command(Command::new(format!("{index}"), format!("Command {index}")));
+    }
+
+    palette.open();
+    assert!(seigi_utils::scroll_lock::is_locked());
+    let option = fixture.get("#list [role=option]");
+    assert!(events::fire(&option, "touchmove"));
+    assert!(!events::fire(&fixture.get("#outside"), "touchmove"));
+
+    palette.close();
+    // The close event of the dialog finishes closing
+    wait::frame().await;
+    assert!(!seigi_utils::scroll_lock::is_locked());
+    assert!(events::fire(&fixture.get("#outside"), "touchmove"));
+}
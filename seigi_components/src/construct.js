@@ -24,11 +24,16 @@ export function construct(
       }
 
       connectedCallback() {
-        const shadowMode = this._attachShadow(this);
-        if (shadowMode) {
-          this.attachShadow({ mode: shadowMode });
+        // Elements moved to another parent or document are connected again, keeping their
+        // shadow roots and templates
+        if (!this._seigiInitialized) {
+          this._seigiInitialized = true;
+          const shadowMode = this._attachShadow(this);
+          if (shadowMode) {
+            this.attachShadow({ mode: shadowMode });
+          }
+          this.insertAdjacentHTML("afterbegin", template);
         }
-        this.insertAdjacentHTML("afterbegin", template);
         this._connectedCallback(this);
       }
 
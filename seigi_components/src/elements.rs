@@ -1,6 +1,7 @@
 use wasm_bindgen::{JsValue, convert::FromWasmAbi};
 use web_sys::*;
 
+#[allow(non_upper_case_globals, dead_code)]
 pub mod constructors {
     use wasm_bindgen::prelude::*;
 
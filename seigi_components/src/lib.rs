@@ -2,7 +2,12 @@
 
 mod elements;
 
-use std::sync::Arc;
+use std::{
+    cell::RefCell,
+    fmt::{self, Display},
+    rc::Rc,
+    sync::Arc,
+};
 
 use wasm_bindgen::{
     JsCast, JsValue, UnwrapThrowExt,
@@ -12,14 +17,32 @@ use web_sys::{HtmlElement, ShadowRootMode};
 
 use crate::elements::InheritableElement;
 
+/// An error defining a component
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The browser rejected the definition, like when the tag isn't a valid custom element name
+    /// or is already defined, with the message described by [seigi_utils::error::describe]
+    Define { tag: String, message: String },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Define { tag, message } => write!(f, "failed to define <{tag}>: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 pub trait Component: 'static {
-    ///
+    /// The built-in element class this component extends
     type Super: InheritableElement = HtmlElement;
 
     /// Constructs a new instance of component
     fn construct() -> Self;
 
-    /// An html template that is inserted under the element when connected
+    /// An html template that is inserted under the element when first connected
     ///
     fn template() -> &'static str {
         ""
@@ -48,6 +71,25 @@ pub trait Component: 'static {
     #[allow(unused_variables)]
     fn adopted(self: &Arc<Self>, element: &Self::Super) {}
 
+    /// Serializes the state of the component when the element is disconnected, before
+    /// [Component::disconnected], to be given to [Component::restore_state] if the element is
+    /// connected again
+    ///
+    /// This keeps state which is torn down on disconnection across moves of the element, like
+    /// reparenting by drag and drop or adoption into another document.
+    ///
+    /// # Returns
+    /// None if there is nothing to restore
+    #[allow(unused_variables)]
+    fn save_state(self: &Arc<Self>, element: &Self::Super) -> Option<String> {
+        None
+    }
+
+    /// Restores the state saved by [Component::save_state] when the element is connected again,
+    /// after [Component::connected]
+    #[allow(unused_variables)]
+    fn restore_state(self: &Arc<Self>, element: &Self::Super, state: String) {}
+
     #[allow(unused_variables)]
     fn attribute_changed(
         self: &Arc<Self>,
@@ -59,6 +101,8 @@ pub trait Component: 'static {
     }
 }
 
+type AttributeChangedCallback<E> = Closure<dyn FnMut(E, String, Option<String>, Option<String>)>;
+
 fn reflect_set<T: AsRef<JsValue>, V: AsRef<JsValue>>(
     target: &T,
     field: &str,
@@ -67,7 +111,12 @@ fn reflect_set<T: AsRef<JsValue>, V: AsRef<JsValue>>(
     js_sys::Reflect::set(target.as_ref(), &JsValue::from_str(field), value.as_ref())
 }
 
-pub fn define<T>(tag: &str)
+/// Defines the component as a custom element of the tag
+///
+/// # Errors
+/// Returns an error if the browser rejects the definition, like when the tag isn't a valid custom
+/// element name or is already defined
+pub fn define<T>(tag: &str) -> Result<(), Error>
 where
     T: Component,
 {
@@ -75,6 +124,8 @@ where
 
     let constructor: Closure<dyn Fn(T::Super)> = Closure::new(move |this: T::Super| {
         let instance = Arc::new(T::construct());
+        // Saved on disconnection and taken on the next connection, while the element moves
+        let state: Rc<RefCell<Option<String>>> = Rc::default();
 
         let attach_shadow: Closure<dyn FnMut(T::Super) -> Option<ShadowRootMode>> = Closure::new({
             let instance = instance.clone();
@@ -82,13 +133,21 @@ where
         });
         let connected_callback: Closure<dyn FnMut(T::Super)> = Closure::new({
             let instance = instance.clone();
+            let state = state.clone();
             move |element| {
+                seigi_utils::debug!("component {} connected", std::any::type_name::<T>());
                 instance.connected(&element);
+                let saved = state.borrow_mut().take();
+                if let Some(saved) = saved {
+                    instance.restore_state(&element, saved);
+                }
             }
         });
         let disconnected_callback: Closure<dyn FnMut(T::Super)> = Closure::new({
             let instance = instance.clone();
             move |element| {
+                seigi_utils::debug!("component {} disconnected", std::any::type_name::<T>());
+                *state.borrow_mut() = instance.save_state(&element);
                 instance.disconnected(&element);
             }
         });
@@ -98,9 +157,7 @@ where
                 instance.adopted(&element);
             }
         });
-        let attribute_changed_callback: Closure<
-            dyn FnMut(T::Super, String, Option<String>, Option<String>),
-        > = Closure::new({
+        let attribute_changed_callback: AttributeChangedCallback<T::Super> = Closure::new({
             let instance = instance.clone();
             move |element, name, old, new| {
                 instance.attribute_changed(&element, name, old, new);
@@ -144,13 +201,34 @@ where
         constructor.as_ref().unchecked_ref(),
         template,
         observed_attributes,
+    )
+    .map_err(|v| Error::Define {
+        tag: tag.to_string(),
+        message: seigi_utils::error::describe(&v),
+    })?;
+
+    seigi_utils::debug!(
+        "component {} defined as <{tag}>",
+        std::any::type_name::<T>()
     );
-
     constructor.forget();
+    Ok(())
+}
+
+/// Defines the component as a custom element of the tag, like [define]
+///
+/// # Panics
+/// This function panics if [define] returns an error
+pub fn define_unchecked<T>(tag: &str)
+where
+    T: Component,
+{
+    define::<T>(tag).unwrap_or_else(|error| panic!("{error}"))
 }
 
 #[wasm_bindgen(module = "/src/construct.js")]
 extern "C" {
+    #[wasm_bindgen(catch)]
     fn construct(
         tag: &str,
         superclass: &js_sys::Function,
@@ -158,5 +236,5 @@ extern "C" {
         constructor: &js_sys::Function,
         template: String,
         observed_attributes: Vec<String>,
-    );
+    ) -> Result<(), JsValue>;
 }
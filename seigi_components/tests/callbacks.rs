@@ -0,0 +1,194 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{
+    cell::{Cell, RefCell},
+    sync::Arc,
+};
+
+use gloo::utils::document;
+use seigi_components::Component;
+use seigi_testkit::Fixture;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::{HtmlElement, HtmlIFrameElement};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+thread_local! {
+    static CALLS: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+}
+
+fn record(call: impl Into<String>) {
+    CALLS.with(|calls| calls.borrow_mut().push(call.into()));
+}
+
+/// Takes calls recorded until now
+fn calls() -> Vec<String> {
+    CALLS.with(|calls| calls.take())
+}
+
+struct Recorded {
+    /// Times the element was connected, which is restored across moves
+    connections: Cell<u32>,
+}
+
+impl Component for Recorded {
+    fn construct() -> Self {
+        record("construct");
+        Recorded {
+            connections: Cell::new(0),
+        }
+    }
+
+    fn template() -> &'static str {
+        "<span part=\"label\">Recorded</span>"
+    }
+
+    fn observed_attributes() -> Vec<&'static str> {
+        vec!["data-state"]
+    }
+
+    fn connected(self: &Arc<Self>, _element: &HtmlElement) {
+        record("connected");
+        self.connections.set(self.connections.get() + 1);
+    }
+
+    fn disconnected(self: &Arc<Self>, _element: &HtmlElement) {
+        record("disconnected");
+        self.connections.set(0);
+    }
+
+    fn adopted(self: &Arc<Self>, _element: &HtmlElement) {
+        record("adopted");
+    }
+
+    fn save_state(self: &Arc<Self>, _element: &HtmlElement) -> Option<String> {
+        Some(self.connections.get().to_string())
+    }
+
+    fn restore_state(self: &Arc<Self>, _element: &HtmlElement, state: String) {
+        record(format!("restore: {state}"));
+        let saved = state.parse::<u32>().unwrap();
+        self.connections.set(saved + self.connections.get());
+    }
+
+    fn attribute_changed(
+        self: &Arc<Self>,
+        _element: &HtmlElement,
+        name: String,
+        old: Option<String>,
+        new: Option<String>,
+    ) {
+        record(format!("{name}: {old:?} -> {new:?}"));
+    }
+}
+
+/// Defines the component once, as tests share the registry of the page
+fn define() {
+    if document()
+        .create_element("seigi-recorded")
+        .is_ok_and(|v| v.matches(":defined").unwrap_or(false))
+    {
+        return;
+    }
+    seigi_components::define::<Recorded>("seigi-recorded").unwrap();
+}
+
+#[wasm_bindgen_test]
+fn callbacks_follow_lifecycle() {
+    define();
+    calls();
+
+    let fixture = Fixture::new("");
+    let element = document().create_element("seigi-recorded").unwrap();
+    assert_eq!(calls(), ["construct"]);
+
+    fixture.root().append_child(&element).unwrap();
+    assert_eq!(calls(), ["connected"]);
+    assert_eq!(element.text_content().as_deref(), Some("Recorded"));
+
+    element.set_attribute("data-state", "open").unwrap();
+    element.set_attribute("data-ignored", "").unwrap();
+    element.set_attribute("data-state", "closed").unwrap();
+    assert_eq!(
+        calls(),
+        [
+            "data-state: None -> Some(\"open\")",
+            "data-state: Some(\"open\") -> Some(\"closed\")",
+        ]
+    );
+
+    element.remove();
+    assert_eq!(calls(), ["disconnected"]);
+}
+
+#[wasm_bindgen_test]
+fn parsed_elements_are_upgraded() {
+    define();
+    calls();
+
+    let fixture = Fixture::new(r#"<seigi-recorded data-state="open"></seigi-recorded>"#);
+    assert_eq!(
+        calls(),
+        [
+            "construct",
+            "data-state: None -> Some(\"open\")",
+            "connected"
+        ]
+    );
+
+    drop(fixture);
+    assert_eq!(calls(), ["disconnected"]);
+}
+
+#[wasm_bindgen_test]
+fn define_rejects_invalid_and_defined_tags() {
+    define();
+    assert!(matches!(
+        seigi_components::define::<Recorded>("seigi-recorded"),
+        Err(seigi_components::Error::Define { .. })
+    ));
+    assert!(seigi_components::define::<Recorded>("recorded").is_err());
+}
+
+#[wasm_bindgen_test]
+fn state_is_restored_after_moves() {
+    define();
+    calls();
+
+    let fixture = Fixture::new(r#"<div id="from"></div><div id="to"></div>"#);
+    let element = document().create_element("seigi-recorded").unwrap();
+    fixture.get("#from").append_child(&element).unwrap();
+    assert_eq!(calls(), ["construct", "connected"]);
+
+    fixture.get("#to").append_child(&element).unwrap();
+    assert_eq!(calls(), ["disconnected", "connected", "restore: 1"]);
+    // The template is inserted only once
+    assert_eq!(element.text_content().as_deref(), Some("Recorded"));
+}
+
+#[wasm_bindgen_test]
+fn state_is_restored_after_adoption() {
+    define();
+    calls();
+
+    let fixture = Fixture::new(r#"<iframe id="frame"></iframe>"#);
+    let element = document().create_element("seigi-recorded").unwrap();
+    fixture.root().append_child(&element).unwrap();
+    calls();
+
+    let frame = fixture.get("#frame").unchecked_into::<HtmlIFrameElement>();
+    let body = frame.content_document().unwrap().body().unwrap();
+    body.append_child(&element).unwrap();
+    assert_eq!(
+        calls(),
+        ["disconnected", "adopted", "connected", "restore: 1"]
+    );
+
+    fixture.root().append_child(&element).unwrap();
+    assert_eq!(
+        calls(),
+        ["disconnected", "adopted", "connected", "restore: 2"]
+    );
+    assert_eq!(element.text_content().as_deref(), Some("Recorded"));
+}
@@ -0,0 +1,679 @@
+//! Headless calendar grid following the ARIA grid pattern
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::{events::EventListener, utils::document};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, KeyboardEvent};
+
+use crate::{Date, Weekday};
+
+/// How a calendar selects dates
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionMode {
+    /// A single date is selected
+    #[default]
+    Single,
+    /// A range of dates is selected with two selections
+    Range,
+}
+
+/// Dates selected in a calendar
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    #[default]
+    None,
+    Single(Date),
+    /// A range of dates, both inclusive
+    ///
+    /// End is None while the user has only picked the start of the range
+    Range {
+        start: Date,
+        end: Option<Date>,
+    },
+}
+
+impl Selection {
+    /// Returns true if the selection is finished, i.e. a single date or a range with both ends
+    pub fn is_complete(&self) -> bool {
+        matches!(
+            self,
+            Selection::Single(_) | Selection::Range { end: Some(_), .. }
+        )
+    }
+
+    pub fn contains(&self, date: &Date) -> bool {
+        match self {
+            Selection::None => false,
+            Selection::Single(selected) => selected == date,
+            Selection::Range { start, end: None } => start == date,
+            Selection::Range {
+                start,
+                end: Some(end),
+            } => start <= date && date <= end,
+        }
+    }
+}
+
+/// A cell of the calendar grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Day {
+    pub date: Date,
+    /// The date belongs to the previous or next month of the visible month
+    pub is_outside: bool,
+    pub is_disabled: bool,
+    pub is_selected: bool,
+    pub is_focused: bool,
+    pub is_today: bool,
+    pub is_range_start: bool,
+    pub is_range_end: bool,
+}
+
+/// A keyboard movement of the focused date
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Navigation {
+    PreviousDay,
+    NextDay,
+    PreviousWeek,
+    NextWeek,
+    StartOfWeek,
+    EndOfWeek,
+    PreviousMonth,
+    NextMonth,
+    PreviousYear,
+    NextYear,
+}
+
+impl Navigation {
+    /// Maps a key of the ARIA grid pattern to a navigation
+    pub fn from_key(key: &str, shift: bool) -> Option<Self> {
+        Some(match key {
+            "ArrowLeft" => Navigation::PreviousDay,
+            "ArrowRight" => Navigation::NextDay,
+            "ArrowUp" => Navigation::PreviousWeek,
+            "ArrowDown" => Navigation::NextWeek,
+            "Home" => Navigation::StartOfWeek,
+            "End" => Navigation::EndOfWeek,
+            "PageUp" if shift => Navigation::PreviousYear,
+            "PageDown" if shift => Navigation::NextYear,
+            "PageUp" => Navigation::PreviousMonth,
+            "PageDown" => Navigation::NextMonth,
+            _ => return None,
+        })
+    }
+}
+
+/// A predicate deciding whether a date can be selected
+pub type DisabledPredicate = Box<dyn Fn(&Date) -> bool>;
+
+/// A hook called with the selection of a calendar
+pub type SelectionHook = Box<dyn Fn(&Selection)>;
+
+/// Hooks to [Calendar]
+#[derive(Default)]
+pub struct CalendarHooks {
+    /// Called when the selection changes
+    pub select: Option<SelectionHook>,
+    /// Called with year and month when the visible month changes
+    pub visible_change: Option<Box<dyn Fn(i32, u8)>>,
+}
+
+/// Options of [Calendar]
+pub struct CalendarOptions {
+    pub mode: SelectionMode,
+    /// The earliest selectable date
+    pub min: Option<Date>,
+    /// The latest selectable date
+    pub max: Option<Date>,
+    /// Dates the predicate returns true for can't be selected
+    pub disabled: Option<DisabledPredicate>,
+    /// The first day of rendered weeks
+    pub week_start: Weekday,
+    /// Labels of weekdays starting from sunday, rendered in the header row
+    pub weekday_labels: [String; 7],
+    /// The selection the calendar starts with
+    pub initial_selection: Selection,
+    /// The date focused first. Defaults to the selected date or today
+    pub initial_focus: Option<Date>,
+    pub hooks: CalendarHooks,
+}
+
+impl CalendarOptions {
+    pub fn builder() -> CalendarOptionsBuilder {
+        CalendarOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [CalendarOptions]
+pub struct CalendarOptionsBuilder {
+    options: CalendarOptions,
+}
+
+impl Default for CalendarOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            options: CalendarOptions {
+                mode: SelectionMode::default(),
+                min: None,
+                max: None,
+                disabled: None,
+                week_start: Weekday::Sunday,
+                weekday_labels: ["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"].map(String::from),
+                initial_selection: Selection::None,
+                initial_focus: None,
+                hooks: CalendarHooks::default(),
+            },
+        }
+    }
+}
+
+impl CalendarOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn mode(mut self, mode: SelectionMode) -> Self {
+        self.options.mode = mode;
+        self
+    }
+
+    pub fn min(mut self, min: Date) -> Self {
+        self.options.min = Some(min);
+        self
+    }
+
+    pub fn max(mut self, max: Date) -> Self {
+        self.options.max = Some(max);
+        self
+    }
+
+    pub fn disabled(mut self, disabled: impl Fn(&Date) -> bool + 'static) -> Self {
+        self.options.disabled = Some(Box::new(disabled));
+        self
+    }
+
+    pub fn week_start(mut self, week_start: Weekday) -> Self {
+        self.options.week_start = week_start;
+        self
+    }
+
+    pub fn weekday_labels(mut self, labels: [String; 7]) -> Self {
+        self.options.weekday_labels = labels;
+        self
+    }
+
+    pub fn initial_selection(mut self, selection: Selection) -> Self {
+        self.options.initial_selection = selection;
+        self
+    }
+
+    pub fn initial_focus(mut self, date: Date) -> Self {
+        self.options.initial_focus = Some(date);
+        self
+    }
+
+    pub fn hooks(mut self, hooks: CalendarHooks) -> Self {
+        self.options.hooks = hooks;
+        self
+    }
+
+    pub fn build(self) -> CalendarOptions {
+        self.options
+    }
+}
+
+/// The state machine of a calendar, independent from DOM
+pub struct CalendarState {
+    options: Rc<CalendarOptions>,
+    focused: Date,
+    selection: Selection,
+    today: Date,
+}
+
+impl CalendarState {
+    pub fn new(options: Rc<CalendarOptions>) -> Self {
+        let today = Date::today();
+        let selection = options.initial_selection;
+        let focused = options.initial_focus.unwrap_or(match selection {
+            Selection::None => today,
+            Selection::Single(date) => date,
+            Selection::Range { start, .. } => start,
+        });
+
+        let mut state = Self {
+            options,
+            focused,
+            selection,
+            today,
+        };
+        state.focused = state.clamp(focused);
+        state
+    }
+
+    pub fn focused(&self) -> Date {
+        self.focused
+    }
+
+    pub fn selection(&self) -> Selection {
+        self.selection
+    }
+
+    /// Returns year and month of the visible month, which always contains the focused date
+    pub fn visible_month(&self) -> (i32, u8) {
+        (self.focused.year(), self.focused.month())
+    }
+
+    fn clamp(&self, date: Date) -> Date {
+        let date = match self.options.min {
+            Some(min) if date < min => min,
+            _ => date,
+        };
+        match self.options.max {
+            Some(max) if date > max => max,
+            _ => date,
+        }
+    }
+
+    /// Returns true if the date is out of min/max bounds or rejected by the predicate
+    pub fn is_disabled(&self, date: &Date) -> bool {
+        if self.options.min.is_some_and(|min| *date < min)
+            || self.options.max.is_some_and(|max| *date > max)
+        {
+            return true;
+        }
+
+        self.options.disabled.as_ref().is_some_and(|f| f(date))
+    }
+
+    /// Generates 6 weeks of days covering the visible month
+    pub fn grid(&self) -> Vec<[Day; 7]> {
+        let first = self
+            .focused
+            .first_of_month()
+            .start_of_week(self.options.week_start);
+
+        (0..6)
+            .map(|week| {
+                std::array::from_fn(|weekday| {
+                    let date = first.add_days(week * 7 + weekday as i64);
+                    let (is_range_start, is_range_end) = match self.selection {
+                        Selection::Range { start, end } => {
+                            (start == date, end.is_some_and(|end| end == date))
+                        }
+                        _ => (false, false),
+                    };
+
+                    Day {
+                        date,
+                        is_outside: !date.is_same_month(&self.focused),
+                        is_disabled: self.is_disabled(&date),
+                        is_selected: self.selection.contains(&date),
+                        is_focused: date == self.focused,
+                        is_today: date == self.today,
+                        is_range_start,
+                        is_range_end,
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Moves the focused date, keeping it in min/max bounds
+    ///
+    /// # Returns
+    /// True if the focused date has changed
+    pub fn navigate(&mut self, navigation: Navigation) -> bool {
+        let focused = self.focused;
+        let week_start = self.options.week_start;
+        let target = match navigation {
+            Navigation::PreviousDay => focused.add_days(-1),
+            Navigation::NextDay => focused.add_days(1),
+            Navigation::PreviousWeek => focused.add_days(-7),
+            Navigation::NextWeek => focused.add_days(7),
+            Navigation::StartOfWeek => focused.start_of_week(week_start),
+            Navigation::EndOfWeek => focused.start_of_week(week_start).add_days(6),
+            Navigation::PreviousMonth => focused.add_months(-1),
+            Navigation::NextMonth => focused.add_months(1),
+            Navigation::PreviousYear => focused.add_years(-1),
+            Navigation::NextYear => focused.add_years(1),
+        };
+
+        self.focus(target)
+    }
+
+    /// Focuses the date, keeping it in min/max bounds
+    ///
+    /// # Returns
+    /// True if the focused date has changed
+    pub fn focus(&mut self, date: Date) -> bool {
+        let date = self.clamp(date);
+        let changed = self.focused != date;
+        self.focused = date;
+        changed
+    }
+
+    /// Selects the date according to the selection mode
+    ///
+    /// In range mode, the first selection starts a range and the second one completes it. A date
+    /// earlier than the start restarts the range.
+    ///
+    /// # Returns
+    /// False if the date is disabled
+    pub fn select(&mut self, date: Date) -> bool {
+        if self.is_disabled(&date) {
+            return false;
+        }
+
+        self.focused = date;
+        self.selection = match (self.options.mode, self.selection) {
+            (SelectionMode::Single, _) => Selection::Single(date),
+            (SelectionMode::Range, Selection::Range { start, end: None }) if start <= date => {
+                Selection::Range {
+                    start,
+                    end: Some(date),
+                }
+            }
+            (SelectionMode::Range, _) => Selection::Range {
+                start: date,
+                end: None,
+            },
+        };
+
+        true
+    }
+
+    /// Replaces the selection without validating it
+    pub fn set_selection(&mut self, selection: Selection) {
+        self.selection = selection;
+    }
+}
+
+fn set_flag(element: &Element, name: &str, value: bool) {
+    if value {
+        let _ = element.set_attribute(name, "");
+    } else {
+        let _ = element.remove_attribute(name);
+    }
+}
+
+struct Inner {
+    container: HtmlElement,
+    body: Element,
+    state: CalendarState,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn render_header(&self) {
+        let Ok(head) = document().create_element("thead") else {
+            return;
+        };
+        let Ok(row) = document().create_element("tr") else {
+            return;
+        };
+        let week_start = self.state.options.week_start.index();
+        for offset in 0..7 {
+            let weekday = Weekday::from_index(week_start + offset);
+            let Ok(cell) = document().create_element("th") else {
+                continue;
+            };
+            let _ = cell.set_attribute("scope", "col");
+            let _ = cell.set_attribute("data-seigi-weekday", weekday.index().to_string().as_str());
+            cell.set_text_content(Some(
+                self.state.options.weekday_labels[weekday.index() as usize].as_str(),
+            ));
+            let _ = row.append_child(&cell);
+        }
+        let _ = head.append_child(&row);
+        let _ = self.container.insert_before(&head, Some(&self.body));
+    }
+
+    fn render(&self) {
+        let has_focus = self.container.contains(
+            document()
+                .active_element()
+                .as_ref()
+                .map(|v| v.unchecked_ref()),
+        );
+
+        self.body.set_inner_html("");
+        let (year, month) = self.state.visible_month();
+        let _ = self.container.set_attribute(
+            "data-seigi-calendar-month",
+            format!("{year:04}-{month:02}").as_str(),
+        );
+
+        let mut focused = None;
+        for week in self.state.grid() {
+            let Ok(row) = document().create_element("tr") else {
+                continue;
+            };
+            for day in week {
+                let Ok(cell) = document().create_element("td") else {
+                    continue;
+                };
+                let _ = cell.set_attribute("role", "gridcell");
+                let _ = cell.set_attribute("data-seigi-date", day.date.to_string().as_str());
+                let _ = cell.set_attribute("tabindex", if day.is_focused { "0" } else { "-1" });
+                let _ = cell.set_attribute(
+                    "aria-selected",
+                    if day.is_selected { "true" } else { "false" },
+                );
+                if day.is_disabled {
+                    let _ = cell.set_attribute("aria-disabled", "true");
+                }
+                if day.is_today {
+                    let _ = cell.set_attribute("aria-current", "date");
+                }
+                set_flag(&cell, "data-selected", day.is_selected);
+                set_flag(&cell, "data-disabled", day.is_disabled);
+                set_flag(&cell, "data-today", day.is_today);
+                set_flag(&cell, "data-outside", day.is_outside);
+                set_flag(&cell, "data-range-start", day.is_range_start);
+                set_flag(&cell, "data-range-end", day.is_range_end);
+                cell.set_text_content(Some(day.date.day().to_string().as_str()));
+
+                if day.is_focused {
+                    focused = Some(cell.clone());
+                }
+                let _ = row.append_child(&cell);
+            }
+            let _ = self.body.append_child(&row);
+        }
+
+        if has_focus && let Some(focused) = focused {
+            let _ = focused.unchecked_into::<HtmlElement>().focus();
+        }
+    }
+
+    fn focused_element(&self) -> Option<HtmlElement> {
+        self.body
+            .query_selector("[tabindex=\"0\"]")
+            .ok()
+            .flatten()
+            .and_then(|v| v.dyn_into().ok())
+    }
+}
+
+/// An instance of calendar grid rendered into a `<table>`
+///
+/// The calendar renders a header row of weekdays and 6 weeks of `role="gridcell"` cells with a
+/// roving tabindex. Arrow keys move by a day or week, Home/End to the start/end of the week,
+/// PageUp/PageDown by a month and Shift+PageUp/PageDown by a year. Enter, Space or a click
+/// selects the focused date.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-seigi-calendar-month** is set in the table to the visible month formatted as `YYYY-MM`
+///
+/// **data-seigi-date** is set in each cell to the date formatted as `YYYY-MM-DD`
+///
+/// **data-selected**, **data-disabled**, **data-today**, **data-outside**, **data-range-start**
+/// and **data-range-end** are set in each cell if the state applies to the date. Cells outside
+/// are in the previous or next month of the visible month
+#[derive(Clone)]
+pub struct Calendar(Rc<Mutex<Inner>>);
+
+impl Calendar {
+    fn update(&self, f: impl FnOnce(&mut CalendarState) -> bool) {
+        let (options, selection, month, selection_changed, month_changed) = {
+            let mut inner = self.0.lock().unwrap();
+            let selection = inner.state.selection();
+            let month = inner.state.visible_month();
+            if !f(&mut inner.state) {
+                return;
+            }
+            inner.render();
+
+            let new_selection = inner.state.selection();
+            let new_month = inner.state.visible_month();
+            (
+                inner.state.options.clone(),
+                new_selection,
+                new_month,
+                new_selection != selection,
+                new_month != month,
+            )
+        };
+
+        if month_changed && let Some(hook) = &options.hooks.visible_change {
+            hook(month.0, month.1);
+        }
+        if selection_changed && let Some(hook) = &options.hooks.select {
+            hook(&selection);
+        }
+    }
+
+    pub fn selection(&self) -> Selection {
+        self.0.lock().unwrap().state.selection()
+    }
+
+    pub fn focused(&self) -> Date {
+        self.0.lock().unwrap().state.focused()
+    }
+
+    /// Returns year and month of the visible month
+    pub fn visible_month(&self) -> (i32, u8) {
+        self.0.lock().unwrap().state.visible_month()
+    }
+
+    /// Returns the cell of the focused date
+    pub fn focused_element(&self) -> Option<HtmlElement> {
+        self.0.lock().unwrap().focused_element()
+    }
+
+    /// Selects the date according to the selection mode
+    ///
+    /// Does nothing if the date is disabled
+    pub fn select(&self, date: Date) {
+        self.update(|state| state.select(date));
+    }
+
+    /// Replaces the selection without validation and focuses its first date
+    pub fn set_selection(&self, selection: Selection) {
+        self.update(|state| {
+            state.set_selection(selection);
+            match selection {
+                Selection::None => {}
+                Selection::Single(date) | Selection::Range { start: date, .. } => {
+                    state.focus(date);
+                }
+            }
+            true
+        });
+    }
+
+    /// Moves the focused date
+    pub fn navigate(&self, navigation: Navigation) {
+        self.update(|state| state.navigate(navigation));
+    }
+
+    /// Moves the focused date, showing its month
+    pub fn focus_date(&self, date: Date) {
+        self.update(|state| state.focus(date));
+    }
+
+    /// Focuses the cell of the focused date
+    pub fn focus(&self) {
+        if let Some(element) = self.focused_element() {
+            let _ = element.focus();
+        }
+    }
+}
+
+/// Creates a calendar in the table element
+///
+/// Existing content of the table is replaced
+pub fn create_calendar(options: CalendarOptions, table: HtmlElement) -> Calendar {
+    let options = Rc::new(options);
+    table.set_inner_html("");
+    let _ = table.set_attribute("role", "grid");
+    let body = document().create_element("tbody").unwrap();
+    table.append_child(&body).unwrap();
+
+    let calendar = Calendar(Rc::new_cyclic(|weak: &Weak<Mutex<Inner>>| {
+        let key_down = EventListener::new(&table, "keydown", {
+            let weak = weak.clone();
+            move |event| {
+                let Some(rc) = weak.upgrade() else {
+                    return;
+                };
+                let Some(event) = event.dyn_ref::<KeyboardEvent>() else {
+                    return;
+                };
+                let calendar = Calendar(rc);
+                match event.key().as_str() {
+                    "Enter" | " " => {
+                        event.prevent_default();
+                        let focused = calendar.focused();
+                        calendar.select(focused);
+                    }
+                    key => {
+                        if let Some(navigation) = Navigation::from_key(key, event.shift_key()) {
+                            event.prevent_default();
+                            calendar.navigate(navigation);
+                        }
+                    }
+                }
+            }
+        });
+        let click = EventListener::new(&table, "click", {
+            let weak = weak.clone();
+            move |event| {
+                let Some(rc) = weak.upgrade() else {
+                    return;
+                };
+                let Some(date) = event
+                    .target()
+                    .and_then(|v| v.dyn_into::<Element>().ok())
+                    .and_then(|v| v.closest("[data-seigi-date]").ok().flatten())
+                    .and_then(|v| v.get_attribute("data-seigi-date"))
+                    .and_then(|v| v.parse::<Date>().ok())
+                else {
+                    return;
+                };
+                Calendar(rc).select(date);
+            }
+        });
+
+        Mutex::new(Inner {
+            container: table.clone(),
+            body,
+            state: CalendarState::new(options),
+            _listeners: vec![key_down, click],
+        })
+    }));
+
+    {
+        let inner = calendar.0.lock().unwrap();
+        inner.render_header();
+        inner.render();
+    }
+
+    calendar
+}
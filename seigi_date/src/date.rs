@@ -0,0 +1,233 @@
+use std::{fmt::Display, str::FromStr};
+
+/// A day of the week
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Weekday {
+    #[default]
+    Sunday,
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+}
+
+impl Weekday {
+    /// Returns the weekday from days since sunday, wrapping around
+    pub fn from_index(index: u8) -> Self {
+        match index % 7 {
+            0 => Weekday::Sunday,
+            1 => Weekday::Monday,
+            2 => Weekday::Tuesday,
+            3 => Weekday::Wednesday,
+            4 => Weekday::Thursday,
+            5 => Weekday::Friday,
+            _ => Weekday::Saturday,
+        }
+    }
+
+    /// Returns the days since sunday
+    pub fn index(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// Returns true if the year is a leap year in the proleptic gregorian calendar
+pub fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+/// Returns the number of days in the month of the year
+///
+/// Month is 1-based, so january is 1
+pub fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+/// A calendar date without time zone in the proleptic gregorian calendar
+///
+/// Dates are ordered chronologically
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    /// Creates a date, returning None if the month or day is out of range
+    ///
+    /// Month and day are 1-based
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+
+        Some(Self { year, month, day })
+    }
+
+    /// Returns the date of today in the local time zone of the browser
+    pub fn today() -> Self {
+        let now = js_sys::Date::new_0();
+        Self {
+            year: now.get_full_year() as i32,
+            month: now.get_month() as u8 + 1,
+            day: now.get_date() as u8,
+        }
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// Returns the number of days since 1970-01-01
+    pub fn to_days(&self) -> i64 {
+        // https://howardhinnant.github.io/date_algorithms.html#days_from_civil
+        let year = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = self.month as i64;
+        let day_of_year =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+        era * 146097 + day_of_era - 719468
+    }
+
+    /// Creates a date from the number of days since 1970-01-01
+    pub fn from_days(days: i64) -> Self {
+        // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let days = days + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days - era * 146097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        Self {
+            year: year as i32,
+            month: month as u8,
+            day: day as u8,
+        }
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        // 1970-01-01 is a thursday
+        Weekday::from_index((self.to_days() + 4).rem_euclid(7) as u8)
+    }
+
+    /// Returns the date moved by given days
+    pub fn add_days(&self, days: i64) -> Self {
+        Self::from_days(self.to_days() + days)
+    }
+
+    /// Returns the date moved by given months, clamping the day to the end of the target month
+    pub fn add_months(&self, months: i32) -> Self {
+        let index = self.year * 12 + self.month as i32 - 1 + months;
+        let year = index.div_euclid(12);
+        let month = index.rem_euclid(12) as u8 + 1;
+
+        Self {
+            year,
+            month,
+            day: self.day.min(days_in_month(year, month)),
+        }
+    }
+
+    /// Returns the date moved by given years, clamping february 29th on non-leap years
+    pub fn add_years(&self, years: i32) -> Self {
+        self.add_months(years * 12)
+    }
+
+    pub fn first_of_month(&self) -> Self {
+        Self { day: 1, ..*self }
+    }
+
+    pub fn last_of_month(&self) -> Self {
+        Self {
+            day: days_in_month(self.year, self.month),
+            ..*self
+        }
+    }
+
+    /// Returns the first date of the week containing this date
+    pub fn start_of_week(&self, week_start: Weekday) -> Self {
+        let offset = (self.weekday().index() + 7 - week_start.index()) % 7;
+        self.add_days(-(offset as i64))
+    }
+
+    /// Returns true if both dates are in the same month of the same year
+    pub fn is_same_month(&self, other: &Date) -> bool {
+        self.year == other.year && self.month == other.month
+    }
+}
+
+/// Formats as ISO 8601 calendar date (`YYYY-MM-DD`), the format used by `<input type="date">`
+///
+/// Years before 1 are written with a minus sign before four digits, like `-0001-01-01`
+impl Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.year < 0 { "-" } else { "" };
+        write!(
+            f,
+            "{sign}{:04}-{:02}-{:02}",
+            self.year.unsigned_abs(),
+            self.month,
+            self.day
+        )
+    }
+}
+
+/// The error returned when parsing a [Date] fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDateError;
+
+impl Display for ParseDateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid date, expected YYYY-MM-DD")
+    }
+}
+
+impl std::error::Error for ParseDateError {}
+
+/// Parses ISO 8601 calendar date (`YYYY-MM-DD`), where years before 1 start with a minus sign
+impl FromStr for Date {
+    type Err = ParseDateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (sign, s) = match s.strip_prefix('-') {
+            Some(s) => (-1, s),
+            None => (1, s),
+        };
+        let mut parts = s.splitn(3, '-');
+        let (Some(year), Some(month), Some(day)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(ParseDateError);
+        };
+
+        let year = sign * year.parse::<i32>().map_err(|_| ParseDateError)?;
+        let month = month.parse().map_err(|_| ParseDateError)?;
+        let day = day.parse().map_err(|_| ParseDateError)?;
+
+        Date::new(year, month, day).ok_or(ParseDateError)
+    }
+}
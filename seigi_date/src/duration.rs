@@ -0,0 +1,236 @@
+//! Duration field edited by hours, minutes and seconds segments
+
+use std::{rc::Rc, time::Duration};
+
+use web_sys::{HtmlElement, HtmlInputElement};
+
+use crate::segment::{
+    SegmentField, SegmentFieldOptions, SegmentSpec, Validator, create_segment_field,
+    reflect_validity,
+};
+
+/// Formats a duration as ISO 8601 duration such as `PT1H30M5S`, dropping fractions of seconds
+pub fn to_iso_string(duration: &Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    let mut string = String::from("PT");
+    if hours > 0 {
+        string.push_str(format!("{hours}H").as_str());
+    }
+    if minutes > 0 {
+        string.push_str(format!("{minutes}M").as_str());
+    }
+    if seconds > 0 || (hours == 0 && minutes == 0) {
+        string.push_str(format!("{seconds}S").as_str());
+    }
+    string
+}
+
+/// Hooks to [DurationField]
+#[derive(Default)]
+pub struct DurationFieldHooks {
+    /// Called when the value changes. None if every segment is empty
+    pub change: Option<Box<dyn Fn(Option<Duration>)>>,
+}
+
+/// Options of [DurationField]
+pub struct DurationFieldOptions {
+    /// The element containing elements with `data-seigi-segment` of `hours`, `minutes` and
+    /// `seconds`. Missing segments are treated as 0
+    pub container: HtmlElement,
+    /// An input the value is mirrored to as ISO 8601 duration
+    pub input: Option<HtmlInputElement>,
+    /// The largest value of the hours segment
+    pub max_hours: u32,
+    /// Called with a value; an error marks the field invalid
+    pub validate: Option<Validator<Duration>>,
+    pub hooks: DurationFieldHooks,
+}
+
+impl DurationFieldOptions {
+    pub fn builder() -> DurationFieldOptionsBuilder {
+        DurationFieldOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [DurationFieldOptions]
+pub struct DurationFieldOptionsBuilder {
+    container: Option<HtmlElement>,
+    input: Option<HtmlInputElement>,
+    max_hours: u32,
+    validate: Option<Validator<Duration>>,
+    hooks: DurationFieldHooks,
+}
+
+impl Default for DurationFieldOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            container: None,
+            input: None,
+            max_hours: 99,
+            validate: None,
+            hooks: DurationFieldHooks::default(),
+        }
+    }
+}
+
+impl DurationFieldOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn container(mut self, container: HtmlElement) -> Self {
+        self.container = Some(container);
+        self
+    }
+
+    pub fn input(mut self, input: HtmlInputElement) -> Self {
+        self.input = Some(input);
+        self
+    }
+
+    pub fn max_hours(mut self, max_hours: u32) -> Self {
+        self.max_hours = max_hours;
+        self
+    }
+
+    pub fn validate(
+        mut self,
+        validate: impl Fn(&Duration) -> Result<(), String> + 'static,
+    ) -> Self {
+        self.validate = Some(Box::new(validate));
+        self
+    }
+
+    pub fn hooks(mut self, hooks: DurationFieldHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [DurationFieldOptions]
+    ///
+    /// # Panics
+    /// This method panics if container field is not set
+    pub fn build(self) -> DurationFieldOptions {
+        DurationFieldOptions {
+            container: self
+                .container
+                .expect("container must be set to build DurationFieldOptions"),
+            input: self.input,
+            max_hours: self.max_hours,
+            validate: self.validate,
+            hooks: self.hooks,
+        }
+    }
+}
+
+fn to_duration(values: &[Option<u32>]) -> Option<Duration> {
+    if values.iter().all(Option::is_none) {
+        return None;
+    }
+
+    let [hours, minutes, seconds] = [0, 1, 2].map(|v| values[v].unwrap_or(0) as u64);
+    Some(Duration::from_secs(hours * 3600 + minutes * 60 + seconds))
+}
+
+fn reflect(options: &DurationFieldOptions, duration: Option<Duration>) {
+    let validity = match (duration, &options.validate) {
+        (Some(duration), Some(validate)) => validate(&duration),
+        _ => Ok(()),
+    };
+    reflect_validity(&options.container, options.input.as_ref(), &validity);
+
+    if let Some(input) = &options.input {
+        input.set_value(
+            duration
+                .as_ref()
+                .map(to_iso_string)
+                .unwrap_or_default()
+                .as_str(),
+        );
+    }
+}
+
+/// An instance of duration field
+///
+/// The field binds segments named `hours`, `minutes` and `seconds` inside the container. Unlike
+/// [TimeField](crate::time::TimeField), a partially filled duration is complete because empty
+/// segments count as 0.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-invalid** and **aria-invalid** are set in the container while the validator rejects
+/// the value
+#[derive(Clone)]
+pub struct DurationField {
+    options: Rc<DurationFieldOptions>,
+    segments: SegmentField,
+}
+
+impl DurationField {
+    /// Returns the duration, or None if every segment is empty
+    pub fn value(&self) -> Option<Duration> {
+        to_duration(&self.segments.values())
+    }
+
+    /// Sets the value, clamping hours to max_hours
+    pub fn set_value(&self, duration: Option<Duration>) {
+        let Some(duration) = duration else {
+            self.segments.clear();
+            return;
+        };
+
+        let seconds = duration.as_secs();
+        self.segments.set_values(&[
+            Some((seconds / 3600).min(self.options.max_hours as u64) as u32),
+            Some((seconds / 60 % 60) as u32),
+            Some((seconds % 60) as u32),
+        ]);
+    }
+
+    pub fn set_disabled(&self, disabled: bool) {
+        self.segments.set_disabled(disabled);
+    }
+
+    /// Focuses the first segment
+    pub fn focus(&self) {
+        self.segments.focus();
+    }
+
+    /// Returns the underlying segments
+    pub fn segments(&self) -> &SegmentField {
+        &self.segments
+    }
+}
+
+pub fn create_duration_field(options: DurationFieldOptions) -> DurationField {
+    let options = Rc::new(options);
+    let width = options.max_hours.to_string().len().max(2);
+
+    let segments = create_segment_field(SegmentFieldOptions {
+        container: options.container.clone(),
+        specs: vec![
+            SegmentSpec::new("hours", 0, options.max_hours, width).label("Hours"),
+            SegmentSpec::new("minutes", 0, 59, 2).label("Minutes"),
+            SegmentSpec::new("seconds", 0, 59, 2).label("Seconds"),
+        ],
+        normalizer: None,
+        change: Some(Box::new({
+            let options = options.clone();
+            move |values| {
+                let duration = to_duration(values);
+                reflect(&options, duration);
+                if let Some(hook) = &options.hooks.change {
+                    hook(duration);
+                }
+            }
+        })),
+    });
+    let _ = options.container.set_attribute("role", "group");
+    reflect(&options, to_duration(&segments.values()));
+
+    DurationField { options, segments }
+}
@@ -0,0 +1,229 @@
+//! Date field edited by year, month and day segments
+
+use std::rc::Rc;
+
+use web_sys::{HtmlElement, HtmlInputElement};
+
+use crate::{
+    Date, days_in_month,
+    segment::{
+        SegmentField, SegmentFieldOptions, SegmentSpec, Validator, create_segment_field,
+        reflect_validity,
+    },
+};
+
+const YEAR: usize = 0;
+const MONTH: usize = 1;
+const DAY: usize = 2;
+
+/// Hooks to [DateField]
+#[derive(Default)]
+pub struct DateFieldHooks {
+    /// Called when the value changes. None if any of the segments is empty or the date is invalid
+    pub change: Option<Box<dyn Fn(Option<Date>)>>,
+}
+
+/// Options of [DateField]
+pub struct DateFieldOptions {
+    /// The element containing elements with `data-seigi-segment` of `year`, `month` and `day`
+    pub container: HtmlElement,
+    /// An input the value is mirrored to as `YYYY-MM-DD`, so the field takes part in form
+    /// submission
+    pub input: Option<HtmlInputElement>,
+    /// The earliest valid date
+    pub min: Option<Date>,
+    /// The latest valid date
+    pub max: Option<Date>,
+    /// Called with a date in bounds; an error marks the field invalid
+    pub validate: Option<Validator<Date>>,
+    pub hooks: DateFieldHooks,
+}
+
+impl DateFieldOptions {
+    pub fn builder() -> DateFieldOptionsBuilder {
+        DateFieldOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [DateFieldOptions]
+#[derive(Default)]
+pub struct DateFieldOptionsBuilder {
+    container: Option<HtmlElement>,
+    input: Option<HtmlInputElement>,
+    min: Option<Date>,
+    max: Option<Date>,
+    validate: Option<Validator<Date>>,
+    hooks: DateFieldHooks,
+}
+
+impl DateFieldOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn container(mut self, container: HtmlElement) -> Self {
+        self.container = Some(container);
+        self
+    }
+
+    pub fn input(mut self, input: HtmlInputElement) -> Self {
+        self.input = Some(input);
+        self
+    }
+
+    pub fn min(mut self, min: Date) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    pub fn max(mut self, max: Date) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    pub fn validate(mut self, validate: impl Fn(&Date) -> Result<(), String> + 'static) -> Self {
+        self.validate = Some(Box::new(validate));
+        self
+    }
+
+    pub fn hooks(mut self, hooks: DateFieldHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [DateFieldOptions]
+    ///
+    /// # Panics
+    /// This method panics if container field is not set
+    pub fn build(self) -> DateFieldOptions {
+        DateFieldOptions {
+            container: self
+                .container
+                .expect("container must be set to build DateFieldOptions"),
+            input: self.input,
+            min: self.min,
+            max: self.max,
+            validate: self.validate,
+            hooks: self.hooks,
+        }
+    }
+}
+
+fn to_date(values: &[Option<u32>]) -> Option<Date> {
+    let (Some(year), Some(month), Some(day)) = (values[YEAR], values[MONTH], values[DAY]) else {
+        return None;
+    };
+
+    Date::new(year as i32, month as u8, day as u8)
+}
+
+fn is_in_bounds(options: &DateFieldOptions, date: &Date) -> bool {
+    options.min.is_none_or(|min| *date >= min) && options.max.is_none_or(|max| *date <= max)
+}
+
+/// Updates validity attributes and the mirrored input
+fn reflect(options: &DateFieldOptions, values: &[Option<u32>]) -> Option<Date> {
+    let date = to_date(values);
+    let is_filled = values.iter().all(Option::is_some);
+    let validity = match date {
+        Some(date) if !is_in_bounds(options, &date) => Err("The date is out of range".to_string()),
+        Some(date) => options.validate.as_ref().map_or(Ok(()), |v| v(&date)),
+        None if is_filled => Err("The date is invalid".to_string()),
+        None => Ok(()),
+    };
+    reflect_validity(&options.container, options.input.as_ref(), &validity);
+
+    if let Some(input) = &options.input {
+        input.set_value(date.map(|v| v.to_string()).unwrap_or_default().as_str());
+    }
+
+    date.filter(|v| is_in_bounds(options, v))
+}
+
+/// An instance of date field
+///
+/// The field binds segments named `year`, `month` and `day` inside the container. Their order
+/// follows the markup, so locale-specific order is decided by the user. The day is clamped to
+/// the length of the month while editing.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-invalid** and **aria-invalid** are set in the container when every segment is filled
+/// but the date is out of min/max bounds or rejected by the validator
+#[derive(Clone)]
+pub struct DateField {
+    options: Rc<DateFieldOptions>,
+    segments: SegmentField,
+}
+
+impl DateField {
+    /// Returns the date if every segment is filled and the date is in bounds
+    pub fn value(&self) -> Option<Date> {
+        to_date(&self.segments.values()).filter(|v| is_in_bounds(&self.options, v))
+    }
+
+    pub fn set_value(&self, date: Option<Date>) {
+        match date {
+            Some(date) => self.segments.set_values(&[
+                Some(date.year() as u32),
+                Some(date.month() as u32),
+                Some(date.day() as u32),
+            ]),
+            None => self.segments.clear(),
+        }
+    }
+
+    pub fn set_disabled(&self, disabled: bool) {
+        self.segments.set_disabled(disabled);
+    }
+
+    /// Focuses the first segment
+    pub fn focus(&self) {
+        self.segments.focus();
+    }
+
+    /// Returns the underlying segments
+    pub fn segments(&self) -> &SegmentField {
+        &self.segments
+    }
+}
+
+pub fn create_date_field(options: DateFieldOptions) -> DateField {
+    let options = Rc::new(options);
+    let segments = create_segment_field(SegmentFieldOptions {
+        container: options.container.clone(),
+        specs: vec![
+            SegmentSpec::new("year", 1, 9999, 4)
+                .label("Year")
+                .placeholder("yyyy"),
+            SegmentSpec::new("month", 1, 12, 2)
+                .label("Month")
+                .placeholder("mm"),
+            SegmentSpec::new("day", 1, 31, 2)
+                .label("Day")
+                .placeholder("dd"),
+        ],
+        normalizer: Some(Box::new(|values| {
+            if let (Some(month), Some(day)) = (values[MONTH], values[DAY]) {
+                // Without a year, february allows the 29th of leap years
+                let year = values[YEAR].unwrap_or(2000) as i32;
+                values[DAY] = Some(day.min(days_in_month(year, month as u8) as u32));
+            }
+        })),
+        change: Some(Box::new({
+            let options = options.clone();
+            move |values| {
+                let date = reflect(&options, values);
+                if let Some(hook) = &options.hooks.change {
+                    hook(date);
+                }
+            }
+        })),
+    });
+    let _ = options.container.set_attribute("role", "group");
+    reflect(&options, &segments.values());
+
+    DateField { options, segments }
+}
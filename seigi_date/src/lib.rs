@@ -0,0 +1,11 @@
+//! Headless calendar, date picker and segmented date, time and duration fields
+
+pub mod calendar;
+mod date;
+pub mod duration;
+pub mod field;
+pub mod picker;
+pub mod segment;
+pub mod time;
+
+pub use date::*;
@@ -0,0 +1,344 @@
+//! Date picker composing a calendar, a date field and a focus trapped popup
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::{events::EventListener, utils::body};
+use seigi_focus::{FocusTrap, FocusTrapHooks, FocusTrapOptions, InitialFocus};
+use web_sys::HtmlElement;
+
+use crate::{
+    calendar::{Calendar, CalendarOptions, Selection, SelectionHook, create_calendar},
+    field::{DateField, DateFieldHooks, DateFieldOptions, create_date_field},
+};
+
+/// Hooks to [DatePicker]
+#[derive(Default)]
+pub struct DatePickerHooks {
+    /// Called when the selection changes, either from the calendar or the field
+    pub change: Option<SelectionHook>,
+    /// Called with the open state when the popup opens or closes
+    pub open_change: Option<Box<dyn Fn(bool)>>,
+}
+
+/// Options of [DatePicker]
+pub struct DatePickerOptions {
+    /// The button that toggles the popup
+    pub trigger: HtmlElement,
+    /// The popup containing the calendar
+    pub popup: HtmlElement,
+    /// The `<table>` the calendar is rendered in
+    pub calendar: HtmlElement,
+    /// Options of the calendar
+    pub calendar_options: CalendarOptions,
+    /// The container of date field segments, if the picker has a field
+    ///
+    /// The field reflects single selections only
+    pub field: Option<HtmlElement>,
+    /// Whether the popup closes when the selection is complete
+    pub close_on_select: bool,
+    pub hooks: DatePickerHooks,
+}
+
+impl DatePickerOptions {
+    pub fn builder() -> DatePickerOptionsBuilder {
+        DatePickerOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [DatePickerOptions]
+pub struct DatePickerOptionsBuilder {
+    trigger: Option<HtmlElement>,
+    popup: Option<HtmlElement>,
+    calendar: Option<HtmlElement>,
+    calendar_options: CalendarOptions,
+    field: Option<HtmlElement>,
+    close_on_select: bool,
+    hooks: DatePickerHooks,
+}
+
+impl Default for DatePickerOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            trigger: None,
+            popup: None,
+            calendar: None,
+            calendar_options: CalendarOptions::builder().build(),
+            field: None,
+            close_on_select: true,
+            hooks: DatePickerHooks::default(),
+        }
+    }
+}
+
+impl DatePickerOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn trigger(mut self, trigger: HtmlElement) -> Self {
+        self.trigger = Some(trigger);
+        self
+    }
+
+    pub fn popup(mut self, popup: HtmlElement) -> Self {
+        self.popup = Some(popup);
+        self
+    }
+
+    pub fn calendar(mut self, calendar: HtmlElement) -> Self {
+        self.calendar = Some(calendar);
+        self
+    }
+
+    pub fn calendar_options(mut self, calendar_options: CalendarOptions) -> Self {
+        self.calendar_options = calendar_options;
+        self
+    }
+
+    pub fn field(mut self, field: HtmlElement) -> Self {
+        self.field = Some(field);
+        self
+    }
+
+    pub fn close_on_select(mut self, close_on_select: bool) -> Self {
+        self.close_on_select = close_on_select;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: DatePickerHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [DatePickerOptions]
+    ///
+    /// # Panics
+    /// This method panics if trigger, popup or calendar field is not set
+    pub fn build(self) -> DatePickerOptions {
+        DatePickerOptions {
+            trigger: self
+                .trigger
+                .expect("trigger must be set to build DatePickerOptions"),
+            popup: self
+                .popup
+                .expect("popup must be set to build DatePickerOptions"),
+            calendar: self
+                .calendar
+                .expect("calendar must be set to build DatePickerOptions"),
+            calendar_options: self.calendar_options,
+            field: self.field,
+            close_on_select: self.close_on_select,
+            hooks: self.hooks,
+        }
+    }
+}
+
+struct Shared {
+    trigger: HtmlElement,
+    popup: HtmlElement,
+    close_on_select: bool,
+    hooks: DatePickerHooks,
+    is_open: Mutex<bool>,
+    calendar: Calendar,
+    field: Option<DateField>,
+    trap: FocusTrap,
+    _listener: EventListener,
+}
+
+impl Shared {
+    /// Updates the open state and attributes without touching the trap
+    ///
+    /// # Returns
+    /// True if the state has changed
+    fn set_open(&self, open: bool) -> bool {
+        {
+            let mut is_open = self.is_open.lock().unwrap();
+            if *is_open == open {
+                return false;
+            }
+            *is_open = open;
+        }
+
+        self.popup.set_hidden(!open);
+        let _ = self
+            .trigger
+            .set_attribute("aria-expanded", if open { "true" } else { "false" });
+        if open {
+            let _ = self.popup.set_attribute("data-seigi-picker-open", "");
+        } else {
+            let _ = self.popup.remove_attribute("data-seigi-picker-open");
+        }
+
+        if let Some(hook) = &self.hooks.open_change {
+            hook(open);
+        }
+
+        true
+    }
+
+    fn handle_select(&self, selection: &Selection) {
+        if let (Some(field), Selection::Single(date)) = (&self.field, selection) {
+            field.set_value(Some(*date));
+        }
+
+        if let Some(hook) = &self.hooks.change {
+            hook(selection);
+        }
+
+        if self.close_on_select && selection.is_complete() && self.set_open(false) {
+            self.trap.deactivate();
+        }
+    }
+}
+
+/// An instance of date picker
+///
+/// The trigger toggles the popup, which traps focus while open and closes on escape or when the
+/// selection is complete. Positioning of the popup is left to user CSS.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-seigi-picker-open** is set in the popup while it's open. The popup is also hidden with
+/// `hidden` attribute while closed
+#[derive(Clone)]
+pub struct DatePicker(Rc<Shared>);
+
+impl DatePicker {
+    pub fn is_open(&self) -> bool {
+        *self.0.is_open.lock().unwrap()
+    }
+
+    /// Opens the popup and focuses the calendar
+    pub fn open(&self) {
+        if self.0.set_open(true) {
+            self.0.trap.activate();
+        }
+    }
+
+    pub fn close(&self) {
+        if self.0.set_open(false) {
+            self.0.trap.deactivate();
+        }
+    }
+
+    pub fn toggle(&self) {
+        if self.is_open() {
+            self.close();
+        } else {
+            self.open();
+        }
+    }
+
+    pub fn selection(&self) -> Selection {
+        self.0.calendar.selection()
+    }
+
+    pub fn calendar(&self) -> &Calendar {
+        &self.0.calendar
+    }
+
+    pub fn field(&self) -> Option<&DateField> {
+        self.0.field.as_ref()
+    }
+}
+
+pub fn create_date_picker(options: DatePickerOptions) -> DatePicker {
+    let DatePickerOptions {
+        trigger,
+        popup,
+        calendar,
+        mut calendar_options,
+        field,
+        close_on_select,
+        hooks,
+    } = options;
+
+    let _ = trigger.set_attribute("aria-haspopup", "dialog");
+    let _ = trigger.set_attribute("aria-expanded", "false");
+    popup.set_hidden(true);
+
+    DatePicker(Rc::new_cyclic(|weak: &Weak<Shared>| {
+        let select = calendar_options.hooks.select.take();
+        calendar_options.hooks.select = Some(Box::new({
+            let weak = weak.clone();
+            move |selection| {
+                if let Some(select) = &select {
+                    select(selection);
+                }
+                if let Some(shared) = weak.upgrade() {
+                    shared.handle_select(selection);
+                }
+            }
+        }));
+        let calendar = create_calendar(calendar_options, calendar);
+
+        let field = field.map(|container| {
+            create_date_field(
+                DateFieldOptions::builder()
+                    .container(container)
+                    .hooks(DateFieldHooks {
+                        change: Some(Box::new({
+                            let calendar = calendar.clone();
+                            move |date| {
+                                if let Some(date) = date {
+                                    calendar.set_selection(Selection::Single(date));
+                                }
+                            }
+                        })),
+                    })
+                    .build(),
+            )
+        });
+
+        let trap = seigi_focus::create(
+            FocusTrapOptions::builder()
+                .scope(body())
+                .target(popup.clone())
+                .deactivate_on_escape(true)
+                .initial_focus(InitialFocus::Function(Box::new({
+                    let calendar = calendar.clone();
+                    let popup = popup.clone();
+                    move || calendar.focused_element().unwrap_or_else(|| popup.clone())
+                })))
+                .hooks(FocusTrapHooks {
+                    activate: None,
+                    deactivate: Some(Box::new({
+                        let weak = weak.clone();
+                        move || {
+                            // Escape deactivates the trap by itself, so only the state follows
+                            if let Some(shared) = weak.upgrade() {
+                                shared.set_open(false);
+                            }
+                        }
+                    })),
+                })
+                .build(),
+        );
+
+        let listener = EventListener::new(&trigger, "click", {
+            let weak = weak.clone();
+            move |_| {
+                if let Some(shared) = weak.upgrade() {
+                    DatePicker(shared).toggle();
+                }
+            }
+        });
+
+        Shared {
+            trigger,
+            popup,
+            close_on_select,
+            hooks,
+            is_open: Mutex::new(false),
+            calendar,
+            field,
+            trap,
+            _listener: listener,
+        }
+    }))
+}
@@ -0,0 +1,496 @@
+//! Segmented editing shared by date, time and duration fields
+//!
+//! A segment is an element holding a single numeric part of a value such as a year or a minute.
+//! Segments behave like spinbuttons: digits are typed in place, arrow keys increment or decrement
+//! the value and focus moves between segments automatically.
+
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, KeyboardEvent};
+
+/// Specification of a segment
+#[derive(Debug, Clone)]
+pub struct SegmentSpec {
+    /// The name of the segment, matching `data-seigi-segment` attribute of its element
+    pub name: &'static str,
+    /// The accessible label of the segment
+    pub label: String,
+    pub min: u32,
+    pub max: u32,
+    /// The number of digits the value is zero-padded to
+    pub width: usize,
+    /// The text shown while the segment is empty
+    pub placeholder: String,
+    /// Labels of an enumerated segment such as a day period, shown instead of the value
+    ///
+    /// The label of value `v` is at index `v - min`, and typing the first letter of a label
+    /// selects it
+    pub labels: Option<Vec<String>>,
+}
+
+impl SegmentSpec {
+    pub fn new(name: &'static str, min: u32, max: u32, width: usize) -> Self {
+        Self {
+            name,
+            label: name.to_string(),
+            min,
+            max,
+            width,
+            placeholder: "-".repeat(width),
+            labels: None,
+        }
+    }
+
+    /// Creates an enumerated segment with values starting from 0
+    pub fn enumerated(name: &'static str, labels: Vec<String>) -> Self {
+        Self {
+            name,
+            label: name.to_string(),
+            min: 0,
+            max: labels.len().saturating_sub(1) as u32,
+            width: 1,
+            placeholder: "--".to_string(),
+            labels: Some(labels),
+        }
+    }
+
+    pub fn label(mut self, label: impl ToString) -> Self {
+        self.label = label.to_string();
+        self
+    }
+
+    pub fn placeholder(mut self, placeholder: impl ToString) -> Self {
+        self.placeholder = placeholder.to_string();
+        self
+    }
+
+    fn format(&self, value: u32) -> String {
+        match &self.labels {
+            Some(labels) => labels
+                .get(value.saturating_sub(self.min) as usize)
+                .cloned()
+                .unwrap_or_default(),
+            None => format!("{value:0width$}", width = self.width),
+        }
+    }
+
+    /// Finds the value of the label starting with the character
+    fn value_of_initial(&self, c: char) -> Option<u32> {
+        let c = c.to_lowercase().next()?;
+        let labels = self.labels.as_ref()?;
+        labels
+            .iter()
+            .position(|v| v.to_lowercase().starts_with(c))
+            .map(|v| v as u32 + self.min)
+    }
+}
+
+/// A function called after every edit that can rewrite values, e.g. to clamp a day to the
+/// length of a month
+pub type Normalizer = Box<dyn Fn(&mut [Option<u32>])>;
+
+/// A hook called with values of every segment
+pub type ValuesHook = Box<dyn Fn(&[Option<u32>])>;
+
+/// Options of [SegmentField]
+pub struct SegmentFieldOptions {
+    /// The element that contains the segment elements
+    pub container: HtmlElement,
+    pub specs: Vec<SegmentSpec>,
+    pub normalizer: Option<Normalizer>,
+    /// Called with values of every segment when any of them changes
+    pub change: Option<ValuesHook>,
+}
+
+struct Inner {
+    options: Rc<SegmentFieldOptions>,
+    /// Elements of segments, None if the container does not have the segment
+    elements: Vec<Option<HtmlElement>>,
+    values: Vec<Option<u32>>,
+    /// Digits typed into the focused segment, shared with the `focusout` listener which clears
+    /// it without locking the state
+    buffer: Rc<RefCell<String>>,
+    /// The segment to focus once the state is unlocked, as focusing fires `focusout` at once
+    focus: Option<HtmlElement>,
+    is_disabled: bool,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn index_of(&self, target: &Element) -> Option<usize> {
+        let segment = target.closest("[data-seigi-segment]").ok().flatten()?;
+        self.elements
+            .iter()
+            .position(|v| v.as_ref().is_some_and(|v| **v == segment))
+    }
+
+    fn render(&self) {
+        for ((spec, element), value) in self
+            .options
+            .specs
+            .iter()
+            .zip(self.elements.iter())
+            .zip(self.values.iter())
+        {
+            let Some(element) = element else {
+                continue;
+            };
+
+            let _ = element.set_attribute("role", "spinbutton");
+            if spec.labels.is_none() {
+                let _ = element.set_attribute("inputmode", "numeric");
+            }
+            let _ = element.set_attribute("aria-label", spec.label.as_str());
+            let _ = element.set_attribute("aria-valuemin", spec.min.to_string().as_str());
+            let _ = element.set_attribute("aria-valuemax", spec.max.to_string().as_str());
+
+            if self.is_disabled {
+                let _ = element.remove_attribute("tabindex");
+                let _ = element.set_attribute("aria-disabled", "true");
+            } else {
+                let _ = element.set_attribute("tabindex", "0");
+                let _ = element.remove_attribute("aria-disabled");
+            }
+
+            match value {
+                Some(value) => {
+                    let text = spec.format(*value);
+                    let _ = element.set_attribute("aria-valuenow", value.to_string().as_str());
+                    let _ = element.set_attribute("aria-valuetext", text.as_str());
+                    let _ = element.remove_attribute("data-placeholder");
+                    element.set_text_content(Some(text.as_str()));
+                }
+                None => {
+                    let _ = element.remove_attribute("aria-valuenow");
+                    let _ = element.set_attribute("aria-valuetext", "Empty");
+                    let _ = element.set_attribute("data-placeholder", "");
+                    element.set_text_content(Some(spec.placeholder.as_str()));
+                }
+            }
+        }
+    }
+
+    fn normalize(&mut self) {
+        if let Some(normalizer) = &self.options.normalizer {
+            normalizer(&mut self.values);
+        }
+    }
+
+    /// Focuses the segment after the state is unlocked by [SegmentField::update]
+    fn focus(&mut self, index: usize) {
+        if let Some(Some(element)) = self.elements.get(index) {
+            self.focus = Some(element.clone());
+        }
+    }
+
+    /// Focuses the next existing segment after index
+    fn focus_next(&mut self, index: usize) {
+        if let Some(next) = (index + 1..self.elements.len()).find(|v| self.elements[*v].is_some()) {
+            self.focus(next);
+        }
+    }
+
+    /// Focuses the previous existing segment before index
+    fn focus_previous(&mut self, index: usize) {
+        if let Some(previous) = (0..index).rev().find(|v| self.elements[*v].is_some()) {
+            self.focus(previous);
+        }
+    }
+
+    fn step(&mut self, index: usize, delta: i64) {
+        let spec = &self.options.specs[index];
+        let (min, max) = (spec.min as i64, spec.max as i64);
+        let value = match self.values[index] {
+            Some(value) => (value as i64 - min + delta).rem_euclid(max - min + 1) + min,
+            None if delta > 0 => min,
+            None => max,
+        };
+        self.values[index] = Some(value as u32);
+    }
+
+    fn type_digit(&mut self, index: usize, digit: u32) {
+        let spec = &self.options.specs[index];
+        let mut buffer = self.buffer.borrow_mut();
+        buffer.push(char::from_digit(digit, 10).unwrap_or('0'));
+
+        let mut value = buffer.parse::<u32>().unwrap_or(digit);
+        if value > spec.max {
+            *buffer = digit.to_string();
+            value = digit;
+        }
+        self.values[index] = Some(value.max(spec.min).min(spec.max));
+
+        // Advance when no more digits could produce a valid value
+        if buffer.len() >= spec.width || value * 10 > spec.max {
+            buffer.clear();
+            drop(buffer);
+            self.focus_next(index);
+        }
+    }
+
+    /// Handles keydown of segment at index
+    ///
+    /// # Returns
+    /// True if a value may have changed
+    fn handle_key_down(&mut self, index: usize, event: &KeyboardEvent) -> bool {
+        if self.is_disabled || event.is_composing() {
+            return false;
+        }
+
+        let key = event.key();
+        match key.as_str() {
+            "ArrowUp" => {
+                self.buffer.borrow_mut().clear();
+                self.step(index, 1);
+            }
+            "ArrowDown" => {
+                self.buffer.borrow_mut().clear();
+                self.step(index, -1);
+            }
+            "PageUp" => {
+                self.buffer.borrow_mut().clear();
+                self.step(index, 10);
+            }
+            "PageDown" => {
+                self.buffer.borrow_mut().clear();
+                self.step(index, -10);
+            }
+            "Home" => {
+                self.buffer.borrow_mut().clear();
+                self.values[index] = Some(self.options.specs[index].min);
+            }
+            "End" => {
+                self.buffer.borrow_mut().clear();
+                self.values[index] = Some(self.options.specs[index].max);
+            }
+            "Backspace" | "Delete" => {
+                let mut buffer = self.buffer.borrow_mut();
+                if buffer.pop().is_some() && !buffer.is_empty() {
+                    self.values[index] = buffer.parse().ok();
+                } else {
+                    buffer.clear();
+                    self.values[index] = None;
+                }
+            }
+            "ArrowLeft" => {
+                event.prevent_default();
+                self.buffer.borrow_mut().clear();
+                self.focus_previous(index);
+                return false;
+            }
+            "ArrowRight" => {
+                event.prevent_default();
+                self.buffer.borrow_mut().clear();
+                self.focus_next(index);
+                return false;
+            }
+            key => {
+                let mut chars = key.chars();
+                let (Some(c), None) = (chars.next(), chars.next()) else {
+                    return false;
+                };
+                let spec = &self.options.specs[index];
+                if spec.labels.is_some() {
+                    let Some(value) = spec.value_of_initial(c) else {
+                        return false;
+                    };
+                    self.values[index] = Some(value);
+                    self.focus_next(index);
+                } else {
+                    let Some(digit) = c.to_digit(10) else {
+                        return false;
+                    };
+                    self.type_digit(index, digit);
+                }
+            }
+        }
+
+        event.prevent_default();
+        true
+    }
+}
+
+/// An instance of segmented field
+///
+/// Each [SegmentSpec] is bound to the element with matching `data-seigi-segment` attribute in the
+/// container, so the order of segments follows the markup. Missing segments are skipped.
+///
+/// # Attributes
+/// **data-placeholder** is set in each segment element while it's empty
+#[derive(Clone)]
+pub struct SegmentField(Rc<Mutex<Inner>>);
+
+impl SegmentField {
+    fn update(&self, f: impl FnOnce(&mut Inner) -> bool) {
+        let (options, values, focus) = {
+            let mut inner = self.0.lock().unwrap();
+            let previous = inner.values.clone();
+            let is_changed = f(&mut inner);
+            if is_changed {
+                inner.normalize();
+                inner.render();
+            }
+            let values = (is_changed && inner.values != previous).then(|| inner.values.clone());
+            (inner.options.clone(), values, inner.focus.take())
+        };
+
+        if let Some(element) = focus {
+            let _ = element.focus();
+        }
+        if let (Some(hook), Some(values)) = (&options.change, values) {
+            hook(&values);
+        }
+    }
+
+    /// Returns values of every segment in the order of specs
+    pub fn values(&self) -> Vec<Option<u32>> {
+        self.0.lock().unwrap().values.clone()
+    }
+
+    /// Returns the value of the segment of name
+    pub fn value(&self, name: &str) -> Option<u32> {
+        let inner = self.0.lock().unwrap();
+        let index = inner.options.specs.iter().position(|v| v.name == name)?;
+        inner.values[index]
+    }
+
+    /// Replaces values of every segment in the order of specs
+    pub fn set_values(&self, values: &[Option<u32>]) {
+        self.update(|inner| {
+            for (index, value) in values.iter().enumerate().take(inner.values.len()) {
+                let spec = &inner.options.specs[index];
+                inner.values[index] = value.map(|v| v.clamp(spec.min, spec.max));
+            }
+            true
+        });
+    }
+
+    /// Sets the value of the segment of name
+    pub fn set_value(&self, name: &str, value: Option<u32>) {
+        self.update(|inner| {
+            let Some(index) = inner.options.specs.iter().position(|v| v.name == name) else {
+                return false;
+            };
+            let spec = &inner.options.specs[index];
+            inner.values[index] = value.map(|v| v.clamp(spec.min, spec.max));
+            true
+        });
+    }
+
+    /// Clears every segment
+    pub fn clear(&self) {
+        self.update(|inner| {
+            inner.values.fill(None);
+            true
+        });
+    }
+
+    /// Disables or enables editing of every segment
+    pub fn set_disabled(&self, disabled: bool) {
+        let mut inner = self.0.lock().unwrap();
+        inner.is_disabled = disabled;
+        inner.render();
+    }
+
+    /// Focuses the first segment
+    pub fn focus(&self) {
+        self.update(|inner| {
+            if let Some(index) = inner.elements.iter().position(Option::is_some) {
+                inner.focus(index);
+            }
+            false
+        });
+    }
+}
+
+pub fn create_segment_field(options: SegmentFieldOptions) -> SegmentField {
+    let options = Rc::new(options);
+    let elements = options
+        .specs
+        .iter()
+        .map(|spec| {
+            options
+                .container
+                .query_selector(format!("[data-seigi-segment=\"{}\"]", spec.name).as_str())
+                .ok()
+                .flatten()
+                .and_then(|v| v.dyn_into::<HtmlElement>().ok())
+        })
+        .collect();
+
+    let field = SegmentField(Rc::new_cyclic(|weak: &Weak<Mutex<Inner>>| {
+        let key_down = EventListener::new(&options.container, "keydown", {
+            let weak = weak.clone();
+            move |event| {
+                let Some(rc) = weak.upgrade() else {
+                    return;
+                };
+                let (Some(event), Some(target)) = (
+                    event.dyn_ref::<KeyboardEvent>(),
+                    event.target().and_then(|v| v.dyn_into::<Element>().ok()),
+                ) else {
+                    return;
+                };
+                SegmentField(rc).update(|inner| {
+                    let Some(index) = inner.index_of(&target) else {
+                        return false;
+                    };
+                    inner.handle_key_down(index, event)
+                });
+            }
+        });
+        let buffer = Rc::new(RefCell::new(String::new()));
+        let focus_out = EventListener::new(&options.container, "focusout", {
+            let buffer = buffer.clone();
+            move |_| buffer.borrow_mut().clear()
+        });
+
+        Mutex::new(Inner {
+            values: vec![None; options.specs.len()],
+            options: options.clone(),
+            elements,
+            buffer,
+            focus: None,
+            is_disabled: false,
+            _listeners: vec![key_down, focus_out],
+        })
+    }));
+
+    field.0.lock().unwrap().render();
+
+    field
+}
+
+/// A validation hook returning an error message for invalid values
+pub type Validator<T> = Box<dyn Fn(&T) -> Result<(), String>>;
+
+/// Reflects the validity of a field to the container and the mirrored input
+///
+/// Sets **aria-invalid** and **data-invalid** in the container while the field is invalid, and
+/// the custom validity message of the input so it takes part in constraint validation
+pub(crate) fn reflect_validity(
+    container: &HtmlElement,
+    input: Option<&web_sys::HtmlInputElement>,
+    validity: &Result<(), String>,
+) {
+    match validity {
+        Ok(()) => {
+            let _ = container.remove_attribute("aria-invalid");
+            let _ = container.remove_attribute("data-invalid");
+        }
+        Err(_) => {
+            let _ = container.set_attribute("aria-invalid", "true");
+            let _ = container.set_attribute("data-invalid", "");
+        }
+    }
+
+    if let Some(input) = input {
+        input.set_custom_validity(validity.as_ref().err().map_or("", String::as_str));
+    }
+}
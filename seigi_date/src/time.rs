@@ -0,0 +1,393 @@
+//! Time of day and its segmented field
+
+use std::{fmt::Display, rc::Rc, str::FromStr};
+
+use gloo::utils::window;
+use js_sys::{Array, Intl, Object, Reflect};
+use wasm_bindgen::JsValue;
+use web_sys::{HtmlElement, HtmlInputElement};
+
+use crate::segment::{
+    SegmentField, SegmentFieldOptions, SegmentSpec, Validator, create_segment_field,
+    reflect_validity,
+};
+
+/// A time of day with second precision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time {
+    hour: u8,
+    minute: u8,
+    second: u8,
+}
+
+impl Time {
+    /// Creates a time, returning None if any of the parts is out of range
+    pub fn new(hour: u8, minute: u8, second: u8) -> Option<Self> {
+        if hour > 23 || minute > 59 || second > 59 {
+            return None;
+        }
+
+        Some(Self {
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    /// Returns the seconds since midnight
+    pub fn to_seconds(&self) -> u32 {
+        self.hour as u32 * 3600 + self.minute as u32 * 60 + self.second as u32
+    }
+}
+
+/// Formats as `HH:MM:SS`, the format used by `<input type="time" step="1">`
+impl Display for Time {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)
+    }
+}
+
+/// The error returned when parsing a [Time] fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTimeError;
+
+impl Display for ParseTimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid time, expected HH:MM or HH:MM:SS")
+    }
+}
+
+impl std::error::Error for ParseTimeError {}
+
+/// Parses `HH:MM` or `HH:MM:SS`
+impl FromStr for Time {
+    type Err = ParseTimeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, ':');
+        let (Some(hour), Some(minute)) = (parts.next(), parts.next()) else {
+            return Err(ParseTimeError);
+        };
+
+        let hour = hour.parse().map_err(|_| ParseTimeError)?;
+        let minute = minute.parse().map_err(|_| ParseTimeError)?;
+        let second = match parts.next() {
+            // Fractions of seconds are ignored
+            Some(second) => second
+                .split('.')
+                .next()
+                .unwrap_or_default()
+                .parse()
+                .map_err(|_| ParseTimeError)?,
+            None => 0,
+        };
+
+        Time::new(hour, minute, second).ok_or(ParseTimeError)
+    }
+}
+
+/// How hours of a day are presented
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum HourCycle {
+    /// Hours 1 to 12 with a day period segment
+    H12,
+    /// Hours 0 to 23
+    #[default]
+    H24,
+}
+
+impl HourCycle {
+    /// Resolves the hour cycle the locale prefers with `Intl.DateTimeFormat`
+    ///
+    /// Falls back to [HourCycle::H24] if the locale can't be resolved
+    pub fn from_locale(locale: &str) -> Self {
+        let locales = Array::of1(&JsValue::from_str(locale));
+        let options = Object::new();
+        let _ = Reflect::set(&options, &"hour".into(), &"numeric".into());
+        let format = Intl::DateTimeFormat::new(&locales, &options);
+        let resolved = format.resolved_options();
+
+        match Reflect::get(&resolved, &"hour12".into()).map(|v| v.as_bool()) {
+            Ok(Some(true)) => HourCycle::H12,
+            _ => HourCycle::H24,
+        }
+    }
+
+    /// Resolves the hour cycle of the browser's preferred locale
+    pub fn from_navigator() -> Self {
+        let locale = window().navigator().language().unwrap_or_default();
+        Self::from_locale(locale.as_str())
+    }
+}
+
+/// Hooks to [TimeField]
+#[derive(Default)]
+pub struct TimeFieldHooks {
+    /// Called when the value changes. None if any of the segments is empty
+    pub change: Option<Box<dyn Fn(Option<Time>)>>,
+}
+
+/// Options of [TimeField]
+pub struct TimeFieldOptions {
+    /// The element containing elements with `data-seigi-segment` of `hour`, `minute`, `second`
+    /// and `period`
+    ///
+    /// `second` is optional and treated as 0 when missing. `period` is only used with
+    /// [HourCycle::H12]
+    pub container: HtmlElement,
+    /// An input the value is mirrored to as `HH:MM:SS`
+    pub input: Option<HtmlInputElement>,
+    pub hour_cycle: HourCycle,
+    /// Labels of the day period segment for [HourCycle::H12]
+    pub period_labels: [String; 2],
+    /// Called with a complete value; an error marks the field invalid
+    pub validate: Option<Validator<Time>>,
+    pub hooks: TimeFieldHooks,
+}
+
+impl TimeFieldOptions {
+    pub fn builder() -> TimeFieldOptionsBuilder {
+        TimeFieldOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [TimeFieldOptions]
+pub struct TimeFieldOptionsBuilder {
+    container: Option<HtmlElement>,
+    input: Option<HtmlInputElement>,
+    hour_cycle: HourCycle,
+    period_labels: [String; 2],
+    validate: Option<Validator<Time>>,
+    hooks: TimeFieldHooks,
+}
+
+impl Default for TimeFieldOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            container: None,
+            input: None,
+            hour_cycle: HourCycle::default(),
+            period_labels: ["AM".to_string(), "PM".to_string()],
+            validate: None,
+            hooks: TimeFieldHooks::default(),
+        }
+    }
+}
+
+impl TimeFieldOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn container(mut self, container: HtmlElement) -> Self {
+        self.container = Some(container);
+        self
+    }
+
+    pub fn input(mut self, input: HtmlInputElement) -> Self {
+        self.input = Some(input);
+        self
+    }
+
+    pub fn hour_cycle(mut self, hour_cycle: HourCycle) -> Self {
+        self.hour_cycle = hour_cycle;
+        self
+    }
+
+    pub fn period_labels(mut self, am: impl ToString, pm: impl ToString) -> Self {
+        self.period_labels = [am.to_string(), pm.to_string()];
+        self
+    }
+
+    pub fn validate(mut self, validate: impl Fn(&Time) -> Result<(), String> + 'static) -> Self {
+        self.validate = Some(Box::new(validate));
+        self
+    }
+
+    pub fn hooks(mut self, hooks: TimeFieldHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [TimeFieldOptions]
+    ///
+    /// # Panics
+    /// This method panics if container field is not set
+    pub fn build(self) -> TimeFieldOptions {
+        TimeFieldOptions {
+            container: self
+                .container
+                .expect("container must be set to build TimeFieldOptions"),
+            input: self.input,
+            hour_cycle: self.hour_cycle,
+            period_labels: self.period_labels,
+            validate: self.validate,
+            hooks: self.hooks,
+        }
+    }
+}
+
+const HOUR: usize = 0;
+const MINUTE: usize = 1;
+const SECOND: usize = 2;
+const PERIOD: usize = 3;
+
+/// Composes a time from segment values
+///
+/// The second is treated as 0 if the container has no second segment
+fn to_time(options: &TimeFieldOptions, has_second: bool, values: &[Option<u32>]) -> Option<Time> {
+    let hour = values[HOUR]?;
+    let minute = values[MINUTE]?;
+    let second = if has_second { values[SECOND]? } else { 0 };
+
+    let hour = match options.hour_cycle {
+        HourCycle::H24 => hour,
+        HourCycle::H12 => hour % 12 + values[PERIOD]? * 12,
+    };
+
+    Time::new(hour as u8, minute as u8, second as u8)
+}
+
+fn reflect(options: &TimeFieldOptions, time: Option<Time>) {
+    let validity = match (time, &options.validate) {
+        (Some(time), Some(validate)) => validate(&time),
+        _ => Ok(()),
+    };
+    reflect_validity(&options.container, options.input.as_ref(), &validity);
+
+    if let Some(input) = &options.input {
+        input.set_value(time.map(|v| v.to_string()).unwrap_or_default().as_str());
+    }
+}
+
+/// An instance of time field
+///
+/// The field binds segments named `hour`, `minute`, `second` and `period` inside the container.
+/// Arrow keys increment or decrement the focused segment and digits are typed in place. With
+/// [HourCycle::H12], the period segment is toggled by arrow keys or the initial of its label.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-invalid** and **aria-invalid** are set in the container while the validator rejects
+/// the value
+#[derive(Clone)]
+pub struct TimeField {
+    options: Rc<TimeFieldOptions>,
+    has_second: bool,
+    segments: SegmentField,
+}
+
+impl TimeField {
+    /// Returns the time if every segment is filled
+    pub fn value(&self) -> Option<Time> {
+        to_time(&self.options, self.has_second, &self.segments.values())
+    }
+
+    /// Returns true if the value is complete and accepted by the validator
+    pub fn is_valid(&self) -> bool {
+        match (self.value(), &self.options.validate) {
+            (Some(time), Some(validate)) => validate(&time).is_ok(),
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+
+    pub fn set_value(&self, time: Option<Time>) {
+        let Some(time) = time else {
+            self.segments.clear();
+            return;
+        };
+
+        let (hour, period) = match self.options.hour_cycle {
+            HourCycle::H24 => (time.hour as u32, None),
+            HourCycle::H12 => match time.hour {
+                0 => (12, Some(0)),
+                hour @ 1..=11 => (hour as u32, Some(0)),
+                12 => (12, Some(1)),
+                hour => (hour as u32 - 12, Some(1)),
+            },
+        };
+        self.segments.set_values(&[
+            Some(hour),
+            Some(time.minute as u32),
+            Some(time.second as u32),
+            period,
+        ]);
+    }
+
+    pub fn set_disabled(&self, disabled: bool) {
+        self.segments.set_disabled(disabled);
+    }
+
+    /// Focuses the first segment
+    pub fn focus(&self) {
+        self.segments.focus();
+    }
+
+    /// Returns the underlying segments
+    pub fn segments(&self) -> &SegmentField {
+        &self.segments
+    }
+}
+
+pub fn create_time_field(options: TimeFieldOptions) -> TimeField {
+    let options = Rc::new(options);
+    let hour = match options.hour_cycle {
+        HourCycle::H12 => SegmentSpec::new("hour", 1, 12, 2),
+        HourCycle::H24 => SegmentSpec::new("hour", 0, 23, 2),
+    };
+
+    let has_second = options
+        .container
+        .query_selector("[data-seigi-segment=\"second\"]")
+        .ok()
+        .flatten()
+        .is_some();
+
+    let segments = create_segment_field(SegmentFieldOptions {
+        container: options.container.clone(),
+        specs: vec![
+            hour.label("Hours").placeholder("--"),
+            SegmentSpec::new("minute", 0, 59, 2)
+                .label("Minutes")
+                .placeholder("--"),
+            SegmentSpec::new("second", 0, 59, 2)
+                .label("Seconds")
+                .placeholder("--"),
+            SegmentSpec::enumerated("period", options.period_labels.to_vec()).label("AM/PM"),
+        ],
+        normalizer: None,
+        change: Some(Box::new({
+            let options = options.clone();
+            move |values| {
+                let time = to_time(&options, has_second, values);
+                reflect(&options, time);
+                if let Some(hook) = &options.hooks.change {
+                    hook(time);
+                }
+            }
+        })),
+    });
+    let _ = options.container.set_attribute("role", "group");
+    reflect(&options, to_time(&options, has_second, &segments.values()));
+
+    TimeField {
+        options,
+        has_second,
+        segments,
+    }
+}
@@ -0,0 +1,128 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use seigi_date::{Date, ParseDateError, Weekday, days_in_month, is_leap_year};
+
+fn date(year: i32, month: u8, day: u8) -> Date {
+    Date::new(year, month, day).unwrap()
+}
+
+#[test]
+fn days_count_from_epoch() {
+    assert_eq!(date(1970, 1, 1).to_days(), 0);
+    assert_eq!(date(1970, 1, 2).to_days(), 1);
+    assert_eq!(date(1969, 12, 31).to_days(), -1);
+    assert_eq!(date(2000, 3, 1).to_days(), 11017);
+    assert_eq!(date(1900, 1, 1).to_days(), -25567);
+
+    assert_eq!(Date::from_days(0), date(1970, 1, 1));
+    assert_eq!(Date::from_days(-1), date(1969, 12, 31));
+    assert_eq!(Date::from_days(-719528), date(0, 1, 1));
+}
+
+#[test]
+fn days_round_trip_across_leap_years() {
+    // Leap days of a common, a century and a 400th year, and days around them
+    for (year, month, day) in [
+        (2024, 2, 29),
+        (2023, 2, 28),
+        (2023, 3, 1),
+        (2000, 2, 29),
+        (1900, 2, 28),
+        (1900, 3, 1),
+        (0, 2, 29),
+        (-1, 12, 31),
+        (-400, 2, 29),
+    ] {
+        let value = date(year, month, day);
+        assert_eq!(Date::from_days(value.to_days()), value, "{value}");
+    }
+
+    // Every day of four centuries, across a year skipping a leap day
+    let start = date(1800, 1, 1).to_days();
+    let mut previous = Date::from_days(start - 1);
+    for days in start..start + 146097 {
+        let value = Date::from_days(days);
+        assert_eq!(value.to_days(), days);
+        assert!(value > previous);
+        assert_eq!(previous.add_days(1), value);
+        previous = value;
+    }
+    assert_eq!(previous, date(2199, 12, 31));
+}
+
+#[test]
+fn leap_years_follow_gregorian_rules() {
+    assert!(is_leap_year(2024));
+    assert!(!is_leap_year(1900));
+    assert!(is_leap_year(2000));
+    assert!(is_leap_year(0));
+    assert!(is_leap_year(-4));
+    assert!(!is_leap_year(-1));
+    assert_eq!(days_in_month(2023, 2), 28);
+    assert_eq!(days_in_month(2024, 2), 29);
+    assert_eq!(days_in_month(2024, 13), 0);
+    assert_eq!(Date::new(2023, 2, 29), None);
+    assert_eq!(Date::new(2024, 0, 1), None);
+}
+
+#[test]
+fn weekdays_are_computed() {
+    assert_eq!(date(1970, 1, 1).weekday(), Weekday::Thursday);
+    assert_eq!(date(1969, 12, 28).weekday(), Weekday::Sunday);
+    assert_eq!(date(2024, 2, 29).weekday(), Weekday::Thursday);
+    assert_eq!(date(2000, 1, 1).weekday(), Weekday::Saturday);
+    assert_eq!(date(1, 1, 1).weekday(), Weekday::Monday);
+    assert_eq!(
+        date(2024, 2, 29).start_of_week(Weekday::Monday),
+        date(2024, 2, 26)
+    );
+}
+
+#[test]
+fn months_are_added_clamping_day() {
+    assert_eq!(date(2023, 1, 31).add_months(1), date(2023, 2, 28));
+    assert_eq!(date(2024, 1, 31).add_months(1), date(2024, 2, 29));
+    assert_eq!(date(2024, 3, 31).add_months(-1), date(2024, 2, 29));
+    assert_eq!(date(2024, 1, 31).add_months(3), date(2024, 4, 30));
+    assert_eq!(date(2024, 11, 15).add_months(2), date(2025, 1, 15));
+    assert_eq!(date(2024, 1, 15).add_months(-13), date(2022, 12, 15));
+    assert_eq!(date(0, 1, 1).add_months(-1), date(-1, 12, 1));
+    assert_eq!(date(2024, 2, 29).add_years(1), date(2025, 2, 28));
+    assert_eq!(date(2024, 2, 29).add_years(4), date(2028, 2, 29));
+}
+
+#[test]
+fn dates_are_parsed() {
+    assert_eq!("2024-02-29".parse(), Ok(date(2024, 2, 29)));
+    assert_eq!("0001-01-01".parse(), Ok(date(1, 1, 1)));
+    assert_eq!("-0001-12-31".parse(), Ok(date(-1, 12, 31)));
+    assert_eq!("12024-01-01".parse(), Ok(date(12024, 1, 1)));
+
+    for value in [
+        "",
+        "2024",
+        "2024-02",
+        "2023-02-29",
+        "2024-13-01",
+        "2024-01-01-01",
+        "--2024-01-01",
+        "2024/01/01",
+    ] {
+        assert_eq!(value.parse::<Date>(), Err(ParseDateError), "{value}");
+    }
+}
+
+#[test]
+fn dates_round_trip_through_display() {
+    for (value, text) in [
+        (date(2024, 2, 29), "2024-02-29"),
+        (date(33, 7, 4), "0033-07-04"),
+        (date(0, 1, 1), "0000-01-01"),
+        (date(-1, 12, 31), "-0001-12-31"),
+        (date(-2024, 2, 29), "-2024-02-29"),
+        (date(12024, 1, 1), "12024-01-01"),
+    ] {
+        assert_eq!(value.to_string(), text);
+        assert_eq!(text.parse(), Ok(value));
+    }
+}
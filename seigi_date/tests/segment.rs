@@ -0,0 +1,64 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_date::segment::{SegmentFieldOptions, SegmentSpec, create_segment_field};
+use seigi_testkit::{
+    Fixture, assert_focused,
+    events::{self, Modifiers},
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<div id="field">
+    <span data-seigi-segment="month"></span>
+    <span data-seigi-segment="day"></span>
+    <span data-seigi-segment="year"></span>
+</div>
+"#;
+
+#[wasm_bindgen_test]
+fn keyboard_moves_between_segments() {
+    let fixture = Fixture::new(MARKUP);
+    let field = create_segment_field(SegmentFieldOptions {
+        container: fixture.get("#field"),
+        specs: vec![
+            SegmentSpec::new("month", 1, 12, 2),
+            SegmentSpec::new("day", 1, 31, 2),
+            SegmentSpec::new("year", 1, 9999, 4),
+        ],
+        normalizer: None,
+        change: None,
+    });
+    let month = fixture.get("[data-seigi-segment=\"month\"]");
+    let day = fixture.get("[data-seigi-segment=\"day\"]");
+    let year = fixture.get("[data-seigi-segment=\"year\"]");
+    let press = |segment, key| events::key_down(segment, key, Modifiers::default());
+
+    field.focus();
+    assert_focused(&month);
+    press(&month, "ArrowRight");
+    assert_focused(&day);
+    press(&day, "ArrowRight");
+    assert_focused(&year);
+    press(&year, "ArrowRight");
+    assert_focused(&year);
+    press(&year, "ArrowLeft");
+    press(&day, "ArrowLeft");
+    assert_focused(&month);
+
+    // Typing advances once no more digits could fit
+    press(&month, "1");
+    assert_focused(&month);
+    press(&month, "2");
+    assert_eq!(field.value("month"), Some(12));
+    assert_focused(&day);
+
+    // Leaving a segment drops its typed digits
+    press(&day, "1");
+    let _ = month.focus();
+    let _ = day.focus();
+    press(&day, "5");
+    assert_eq!(field.value("day"), Some(5));
+    assert_focused(&year);
+}
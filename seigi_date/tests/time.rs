@@ -0,0 +1,73 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_date::{
+    duration::{DurationFieldOptions, create_duration_field},
+    time::{HourCycle, Time, TimeFieldOptions, create_time_field},
+};
+use seigi_testkit::{
+    Fixture, assert_focused,
+    events::{self, Modifiers},
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const TIME_MARKUP: &str = r#"
+<div id="field">
+    <span data-seigi-segment="hour"></span>
+    <span data-seigi-segment="minute"></span>
+</div>
+"#;
+
+const DURATION_MARKUP: &str = r#"
+<div id="field">
+    <span data-seigi-segment="hours"></span>
+    <span data-seigi-segment="minutes"></span>
+    <span data-seigi-segment="seconds"></span>
+</div>
+"#;
+
+#[wasm_bindgen_test]
+fn time_segments_increment_and_advance() {
+    let fixture = Fixture::new(TIME_MARKUP);
+    let field = create_time_field(
+        TimeFieldOptions::builder()
+            .container(fixture.get("#field"))
+            .hour_cycle(HourCycle::H24)
+            .build(),
+    );
+    let hour = fixture.get("[data-seigi-segment=\"hour\"]");
+    let minute = fixture.get("[data-seigi-segment=\"minute\"]");
+
+    field.focus();
+    assert_focused(&hour);
+    events::key_down(&hour, "ArrowUp", Modifiers::default());
+    events::key_down(&hour, "ArrowUp", Modifiers::default());
+    assert_focused(&hour);
+    events::key_down(&hour, "9", Modifiers::default());
+    assert_focused(&minute);
+    events::key_down(&minute, "ArrowDown", Modifiers::default());
+    assert_eq!(field.value(), Time::new(9, 59, 0));
+}
+
+#[wasm_bindgen_test]
+fn duration_segments_increment_and_advance() {
+    let fixture = Fixture::new(DURATION_MARKUP);
+    let field = create_duration_field(
+        DurationFieldOptions::builder()
+            .container(fixture.get("#field"))
+            .build(),
+    );
+    let hours = fixture.get("[data-seigi-segment=\"hours\"]");
+    let minutes = fixture.get("[data-seigi-segment=\"minutes\"]");
+    let seconds = fixture.get("[data-seigi-segment=\"seconds\"]");
+
+    field.focus();
+    events::key_down(&hours, "ArrowUp", Modifiers::default());
+    events::key_down(&hours, "ArrowRight", Modifiers::default());
+    assert_focused(&minutes);
+    events::key_down(&minutes, "3", Modifiers::default());
+    events::key_down(&minutes, "0", Modifiers::default());
+    assert_focused(&seconds);
+    assert_eq!(field.value().map(|v| v.as_secs()), Some(30 * 60));
+}
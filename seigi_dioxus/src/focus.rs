@@ -0,0 +1,119 @@
+//! Focus traps attached to mounted elements
+
+use std::rc::Rc;
+
+use dioxus::{prelude::*, web::WebEventExt};
+use seigi_focus::{FocusTrap, FocusTrapOptions};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+type BuildOptions = Rc<dyn Fn(HtmlElement) -> FocusTrapOptions>;
+
+/// A focus trap of a component, created by [use_focus_trap]
+///
+/// This struct is a handle to signals owned by the component, so copying it is a lightweight
+/// operation
+#[derive(Clone, Copy)]
+pub struct UseFocusTrap {
+    trap: Signal<Option<FocusTrap>>,
+    is_activated: Signal<bool>,
+    options: CopyValue<BuildOptions>,
+}
+
+impl UseFocusTrap {
+    /// Attaches the trap to the mounted element, which is given to `onmounted` of the target
+    ///
+    /// A trap attached to an element mounted before is deactivated and replaced.
+    pub fn mount(&self, event: MountedEvent) {
+        let Some(target) = event
+            .data()
+            .try_as_web_event()
+            .and_then(|v| v.dyn_into::<HtmlElement>().ok())
+        else {
+            return;
+        };
+
+        let mut options = (self.options.read())(target);
+        let is_activated = self.is_activated;
+        let activate = options.hooks.activate.take();
+        options.hooks.activate = Some(Box::new(move || {
+            let mut is_activated = is_activated;
+            if let Ok(mut value) = is_activated.try_write() {
+                *value = true;
+            }
+            if let Some(activate) = &activate {
+                activate();
+            }
+        }));
+        let deactivate = options.hooks.deactivate.take();
+        options.hooks.deactivate = Some(Box::new(move || {
+            let mut is_activated = is_activated;
+            if let Ok(mut value) = is_activated.try_write() {
+                *value = false;
+            }
+            if let Some(deactivate) = &deactivate {
+                deactivate();
+            }
+        }));
+
+        let mut trap = self.trap;
+        if let Some(previous) = trap.replace(Some(seigi_focus::create(options))) {
+            previous.deactivate();
+        }
+    }
+
+    /// Returns the trap, or None until the target is mounted
+    pub fn trap(&self) -> Option<FocusTrap> {
+        self.trap.read().clone()
+    }
+
+    /// Returns whether the trap is activated, rerendering the component reading it as it changes
+    pub fn is_activated(&self) -> bool {
+        *self.is_activated.read()
+    }
+
+    pub fn activate(&self) {
+        if let Some(trap) = self.trap.peek().as_ref() {
+            trap.activate();
+        }
+    }
+
+    pub fn deactivate(&self) {
+        if let Some(trap) = self.trap.peek().as_ref() {
+            trap.deactivate();
+        }
+    }
+
+    pub fn toggle(&self) {
+        if self.is_activated() {
+            self.deactivate();
+        } else {
+            self.activate();
+        }
+    }
+}
+
+/// Creates a focus trap of the component, attached to an element by [UseFocusTrap::mount]
+///
+/// The options are built with the mounted target, such as
+/// `|target| FocusTrapOptions::builder().target(target).build()`. The trap is deactivated when
+/// the component is dropped, returning focus.
+pub fn use_focus_trap(options: impl Fn(HtmlElement) -> FocusTrapOptions + 'static) -> UseFocusTrap {
+    let trap = use_signal(|| None::<FocusTrap>);
+    let is_activated = use_signal(|| false);
+    let options = use_hook(|| CopyValue::new(Rc::new(options) as BuildOptions));
+
+    use_drop(move || {
+        if let Ok(trap) = trap.try_peek()
+            && let Some(trap) = trap.as_ref()
+        {
+            trap.deactivate();
+        }
+    });
+
+    UseFocusTrap {
+        trap,
+        is_activated,
+        options,
+    }
+}
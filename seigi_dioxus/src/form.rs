@@ -0,0 +1,196 @@
+//! Multi staged forms built from stage children
+
+use dioxus::{prelude::*, web::WebEventExt};
+use seigi_form::multi_stage::{Form, Orientation, Stage};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+/// The form of the nearest [MultiStageForm], obtained by [use_form]
+///
+/// This struct is a handle to signals owned by the form component, so copying it is a
+/// lightweight operation
+#[derive(Clone, Copy)]
+pub struct FormContext {
+    form: Signal<Option<Form>>,
+    current: Signal<usize>,
+}
+
+impl FormContext {
+    /// Returns the form, or None until the form is mounted
+    pub fn form(&self) -> Option<Form> {
+        self.form.read().clone()
+    }
+
+    /// Returns the index of the current stage, rerendering the component reading it as it
+    /// changes
+    pub fn current(&self) -> usize {
+        *self.current.read()
+    }
+
+    /// Returns the number of stages, which is 0 until the form is mounted
+    pub fn len(&self) -> usize {
+        self.form.read().as_ref().map_or(0, Form::len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Moves to the next stage, if the validator of the current stage passes
+    pub fn next(&self) {
+        if let Some(form) = self.form.peek().as_ref() {
+            form.next();
+        }
+    }
+
+    /// Moves to the previous stage
+    pub fn previous(&self) {
+        if let Some(form) = self.form.peek().as_ref() {
+            form.previous();
+        }
+    }
+
+    /// Moves to the stage if the user is allowed to
+    ///
+    /// # Returns
+    /// Whether the stage is allowed
+    pub fn navigate(&self, stage: usize) -> bool {
+        self.form
+            .peek()
+            .as_ref()
+            .is_some_and(|form| form.navigate(stage))
+    }
+}
+
+/// Returns the form of the nearest [MultiStageForm]
+///
+/// # Panics
+/// This function panics if the component isn't a descendant of a [MultiStageForm]
+pub fn use_form() -> FormContext {
+    use_context()
+}
+
+/// A multi staged form, whose stages are its [FormStage] children
+///
+/// The form is built once mounted, from its stages in document order, and activated. Stages
+/// may be wrapped in other elements, such as to clip them, but not in another form.
+/// Descendants control it with [use_form].
+#[component]
+pub fn MultiStageForm(
+    /// The index of the stage shown first
+    #[props(default)]
+    initial_stage: usize,
+    /// Whether stage changes animate in view transitions
+    #[props(default)]
+    view_transition: bool,
+    /// The axis stages are laid out along
+    #[props(default)]
+    orientation: Orientation,
+    /// Called with the index of the current stage as it changes
+    onchange: Option<EventHandler<usize>>,
+    #[props(extends = GlobalAttributes)] attributes: Vec<Attribute>,
+    children: Element,
+) -> Element {
+    let mut form = use_signal(|| None::<Form>);
+    let current = use_signal(|| initial_stage);
+    use_context_provider(|| FormContext { form, current });
+
+    let mut subscription = use_signal(|| None::<u64>);
+    use_drop(move || {
+        if let (Ok(form), Ok(subscription)) = (form.try_peek(), subscription.try_peek())
+            && let (Some(form), Some(subscription)) = (form.as_ref(), *subscription)
+        {
+            form.unsubscribe(subscription);
+            form.deactivate();
+        }
+    });
+
+    let mount = move |event: MountedEvent| {
+        if form.peek().is_some() {
+            return;
+        }
+        let Some(container) = event
+            .data()
+            .try_as_web_event()
+            .and_then(|v| v.dyn_into::<HtmlElement>().ok())
+        else {
+            return;
+        };
+        let Ok(elements) = container.query_selector_all("[data-seigi-stage]") else {
+            return;
+        };
+        let is_own = |element: &HtmlElement| {
+            element
+                .parent_element()
+                .and_then(|v| v.closest("[data-seigi-form]").ok().flatten())
+                .is_some_and(|v| &v == container.unchecked_ref::<web_sys::Element>())
+        };
+        let stages = (0..elements.length())
+            .filter_map(|index| elements.get(index)?.dyn_into::<HtmlElement>().ok())
+            .filter(is_own)
+            .map(|element| {
+                let label = element.get_attribute("data-seigi-stage-label");
+                let description = element.get_attribute("data-seigi-stage-description");
+                let mut stage = Stage::from_container(element);
+                if let Some(label) = label {
+                    stage = stage.label(label);
+                }
+                if let Some(description) = description {
+                    stage = stage.description(description);
+                }
+                stage
+            })
+            .collect::<Vec<_>>();
+        if stages.is_empty() {
+            return;
+        }
+
+        let Ok(built) = Form::builder()
+            .container(container)
+            .add_stages(stages.into_iter())
+            .initial_stage(initial_stage)
+            .view_transition(view_transition)
+            .orientation(orientation)
+            .build()
+        else {
+            return;
+        };
+        subscription.set(Some(built.subscribe(Box::new(move |stage| {
+            let mut current = current;
+            if let Ok(mut current) = current.try_write() {
+                *current = stage;
+            }
+            if let Some(onchange) = &onchange {
+                onchange.call(stage);
+            }
+        }))));
+        built.initialize();
+        built.activate();
+        form.set(Some(built));
+    };
+
+    rsx! {
+        div { "data-seigi-form": "", onmounted: mount, ..attributes, {children} }
+    }
+}
+
+/// A stage of a [MultiStageForm]
+///
+/// The label and the description are read by step indicators through [Form::meta].
+#[component]
+pub fn FormStage(
+    label: Option<String>,
+    description: Option<String>,
+    #[props(extends = GlobalAttributes)] attributes: Vec<Attribute>,
+    children: Element,
+) -> Element {
+    rsx! {
+        div {
+            "data-seigi-stage": "",
+            "data-seigi-stage-label": label,
+            "data-seigi-stage-description": description,
+            ..attributes,
+            {children}
+        }
+    }
+}
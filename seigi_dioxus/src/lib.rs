@@ -0,0 +1,15 @@
+//! Dioxus hooks and components of seigi
+//!
+//! Widgets are attached to elements once Dioxus mounts them, and their states are exposed as
+//! signals so components rerender as they change. Widgets are torn down with the components
+//! owning them.
+
+mod focus;
+mod form;
+mod toast;
+
+pub use focus::{UseFocusTrap, use_focus_trap};
+pub use form::{
+    FormContext, FormStage, FormStageProps, MultiStageForm, MultiStageFormProps, use_form,
+};
+pub use toast::{UseToaster, use_toaster};
@@ -0,0 +1,60 @@
+//! Global toasts observed by components
+
+use dioxus::prelude::*;
+use seigi_toast::{Toast, ToastEvent, ToastHandle, ToasterOptions};
+
+/// The global toaster seen by a component, created by [use_toaster]
+///
+/// This struct is a handle to a signal owned by the component, so copying it is a lightweight
+/// operation
+#[derive(Clone, Copy)]
+pub struct UseToaster {
+    toasts: Signal<Vec<ToastHandle>>,
+}
+
+impl UseToaster {
+    /// Adds the toast to the global toaster
+    pub fn create(&self, toast: impl Into<Toast>) -> ToastHandle {
+        // The toaster is initialized by use_toaster
+        seigi_toast::create_toast_unchecked(toast)
+    }
+
+    /// Dismisses the toast by the user
+    ///
+    /// # Returns
+    /// Whether a toast of the handle was found
+    pub fn dismiss(&self, handle: ToastHandle) -> bool {
+        seigi_toast::dismiss_toast(handle)
+    }
+
+    /// Returns handles of toasts created since the component was created and not dismissed yet,
+    /// newest last, rerendering the component reading them as they change
+    pub fn toasts(&self) -> Vec<ToastHandle> {
+        self.toasts.read().clone()
+    }
+}
+
+/// Observes the global toaster, initializing it with default options if it isn't yet
+pub fn use_toaster() -> UseToaster {
+    let toasts = use_signal(Vec::new);
+    let (toaster, subscription) = use_hook(move || {
+        let toaster = seigi_toast::toaster()
+            .unwrap_or_else(|| seigi_toast::initialize_unchecked(ToasterOptions::default()));
+        let subscription = toaster.subscribe(Box::new(move |event: &ToastEvent| {
+            let mut toasts = toasts;
+            let Ok(mut toasts) = toasts.try_write() else {
+                return;
+            };
+            match event {
+                ToastEvent::Create { handle } => toasts.push(*handle),
+                ToastEvent::Dismiss { handle, .. } => toasts.retain(|v| v != handle),
+                ToastEvent::Update { .. } => {}
+            }
+        }));
+        (toaster, subscription)
+    });
+
+    use_drop(move || toaster.unsubscribe(subscription));
+
+    UseToaster { toasts }
+}
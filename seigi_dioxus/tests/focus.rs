@@ -0,0 +1,82 @@
+#![cfg(target_arch = "wasm32")]
+
+use dioxus::{
+    prelude::*,
+    web::{Config, launch::launch_virtual_dom},
+};
+use seigi_dioxus::use_focus_trap;
+use seigi_focus::FocusTrapOptions;
+use seigi_testkit::{
+    Fixture, assert_focused,
+    events::{self, Modifiers},
+    wait,
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Renders the component into the fixture, where it lives until the tests end
+fn launch(fixture: &Fixture, root: fn() -> Element) {
+    launch_virtual_dom(
+        VirtualDom::new(root),
+        Config::new().rootelement(fixture.root().into()),
+    );
+}
+
+/// Waits for the condition, as Dioxus renders asynchronously
+async fn until(condition: impl Fn() -> bool) -> bool {
+    for _ in 0..100 {
+        if condition() {
+            return true;
+        }
+        wait::frame().await;
+    }
+    false
+}
+
+fn text(fixture: &Fixture, selector: &str) -> String {
+    fixture
+        .find(selector)
+        .and_then(|v| v.text_content())
+        .unwrap_or_default()
+}
+
+#[component]
+fn Trap() -> Element {
+    let trap = use_focus_trap(|target| {
+        FocusTrapOptions::builder()
+            .target(target)
+            .deactivate_on_escape(true)
+            .build()
+    });
+    let state = if trap.is_activated() {
+        "activated"
+    } else {
+        "deactivated"
+    };
+
+    rsx! {
+        button { id: "toggle", onclick: move |_| trap.toggle(), "Toggle" }
+        span { id: "state", "{state}" }
+        div { id: "trap", onmounted: move |event| trap.mount(event),
+            button { id: "first", "First" }
+            button { id: "last", "Last" }
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+async fn trap_follows_activation() {
+    let fixture = Fixture::new("");
+    launch(&fixture, Trap);
+    assert!(until(|| text(&fixture, "#state") == "deactivated").await);
+
+    events::click(&fixture.get("#toggle"));
+    assert!(until(|| text(&fixture, "#state") == "activated").await);
+    wait::tick().await;
+    assert_focused(&fixture.get("#first"));
+
+    // Deactivated by the trap itself, which the signal follows
+    events::key_down(&fixture.get("#first"), "Escape", Modifiers::default());
+    assert!(until(|| text(&fixture, "#state") == "deactivated").await);
+}
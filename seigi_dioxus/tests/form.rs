@@ -0,0 +1,113 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::cell::RefCell;
+
+use dioxus::{
+    prelude::*,
+    web::{Config, launch::launch_virtual_dom},
+};
+use seigi_dioxus::{FormStage, MultiStageForm, use_form};
+use seigi_testkit::{Fixture, events, wait};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+thread_local! {
+    /// Stages passed to `onchange` of the form
+    static CHANGES: RefCell<Vec<usize>> = const { RefCell::new(vec![]) };
+}
+
+/// Renders the component into the fixture, where it lives until the tests end
+fn launch(fixture: &Fixture, root: fn() -> Element) {
+    launch_virtual_dom(
+        VirtualDom::new(root),
+        Config::new().rootelement(fixture.root().into()),
+    );
+}
+
+/// Waits for the condition, as Dioxus renders asynchronously
+async fn until(condition: impl Fn() -> bool) -> bool {
+    for _ in 0..100 {
+        if condition() {
+            return true;
+        }
+        wait::frame().await;
+    }
+    false
+}
+
+fn text(fixture: &Fixture, selector: &str) -> String {
+    fixture
+        .find(selector)
+        .and_then(|v| v.text_content())
+        .unwrap_or_default()
+}
+
+fn relative(fixture: &Fixture, selector: &str) -> Option<String> {
+    fixture
+        .get(selector)
+        .get_attribute("data-seigi-stage-relative")
+}
+
+#[component]
+fn Controls() -> Element {
+    let form = use_form();
+    rsx! {
+        span { id: "current", "{form.current()}" }
+        span { id: "len", "{form.len()}" }
+        button { id: "previous", r#type: "button", onclick: move |_| form.previous(), "Previous" }
+        button { id: "next", r#type: "button", onclick: move |_| form.next(), "Next" }
+    }
+}
+
+#[component]
+fn Wizard() -> Element {
+    rsx! {
+        MultiStageForm {
+            id: "form",
+            onchange: move |stage| CHANGES.with_borrow_mut(|v| v.push(stage)),
+            FormStage { id: "account", label: "Account", description: "Sign in details",
+                input { name: "email" }
+            }
+            div {
+                FormStage { id: "profile", label: "Profile",
+                    input { name: "name" }
+                }
+            }
+            Controls {}
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+async fn stages_are_built_from_children() {
+    let fixture = Fixture::new("");
+    launch(&fixture, Wizard);
+    // The form is built once mounted, which rerenders its descendants
+    assert!(until(|| text(&fixture, "#len") == "2").await);
+    assert_eq!(text(&fixture, "#current"), "0");
+
+    let account = fixture.get("#account");
+    assert_eq!(
+        account.get_attribute("data-seigi-stage-label").as_deref(),
+        Some("Account")
+    );
+    assert_eq!(
+        account
+            .get_attribute("data-seigi-stage-description")
+            .as_deref(),
+        Some("Sign in details")
+    );
+    assert!(fixture.get("#form").has_attribute("data-seigi-form-active"));
+    assert_eq!(relative(&fixture, "#account").as_deref(), Some("0"));
+    assert_eq!(relative(&fixture, "#profile").as_deref(), Some("1"));
+
+    events::click(&fixture.get("#next"));
+    assert!(until(|| text(&fixture, "#current") == "1").await);
+    assert_eq!(relative(&fixture, "#account").as_deref(), Some("-1"));
+    assert_eq!(relative(&fixture, "#profile").as_deref(), Some("0"));
+
+    events::click(&fixture.get("#previous"));
+    assert!(until(|| text(&fixture, "#current") == "0").await);
+    assert_eq!(CHANGES.with_borrow(Clone::clone), [1, 0]);
+}
@@ -0,0 +1,83 @@
+#![cfg(target_arch = "wasm32")]
+
+use dioxus::{
+    prelude::*,
+    web::{Config, launch::launch_virtual_dom},
+};
+use seigi_dioxus::use_toaster;
+use seigi_testkit::{Fixture, events, wait};
+use seigi_toast::Toast;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Renders the component into the fixture, where it lives until the tests end
+fn launch(fixture: &Fixture, root: fn() -> Element) {
+    launch_virtual_dom(
+        VirtualDom::new(root),
+        Config::new().rootelement(fixture.root().into()),
+    );
+}
+
+/// Waits for the condition, as Dioxus renders asynchronously
+async fn until(condition: impl Fn() -> bool) -> bool {
+    for _ in 0..100 {
+        if condition() {
+            return true;
+        }
+        wait::frame().await;
+    }
+    false
+}
+
+fn text(fixture: &Fixture, selector: &str) -> String {
+    fixture
+        .find(selector)
+        .and_then(|v| v.text_content())
+        .unwrap_or_default()
+}
+
+#[component]
+fn Toasts() -> Element {
+    let toaster = use_toaster();
+    let count = toaster.toasts().len();
+    rsx! {
+        button {
+            id: "create",
+            onclick: move |_| {
+                toaster.create(Toast::builder().title("Saved"));
+            },
+            "Create"
+        }
+        button {
+            id: "dismiss",
+            onclick: move |_| {
+                if let Some(handle) = toaster.toasts().first() {
+                    toaster.dismiss(*handle);
+                }
+            },
+            "Dismiss"
+        }
+        span { id: "count", "{count}" }
+    }
+}
+
+#[wasm_bindgen_test]
+async fn toasts_are_observed() {
+    let fixture = Fixture::new("");
+    launch(&fixture, Toasts);
+    assert!(until(|| text(&fixture, "#count") == "0").await);
+    // The global toaster is initialized by the hook
+    assert!(seigi_toast::toaster().is_some());
+
+    events::click(&fixture.get("#create"));
+    events::click(&fixture.get("#create"));
+    assert!(until(|| text(&fixture, "#count") == "2").await);
+
+    events::click(&fixture.get("#dismiss"));
+    assert!(until(|| text(&fixture, "#count") == "1").await);
+
+    // Toasts created elsewhere are seen too
+    seigi_toast::create_toast_unchecked(Toast::builder().title("Elsewhere"));
+    assert!(until(|| text(&fixture, "#count") == "2").await);
+}
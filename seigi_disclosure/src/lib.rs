@@ -0,0 +1,247 @@
+//! A trigger showing and hiding a content, with the measured size of the content exposed for
+//! animations
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::events::EventListener;
+use seigi_utils::{
+    id::{self, Relation},
+    presence::{Presence, PresenceOptions, Unmount, create_presence},
+};
+use web_sys::HtmlElement;
+
+pub type OpenChangeHook = Box<dyn Fn(bool)>;
+
+/// Hooks to [Disclosure]
+#[derive(Default)]
+pub struct DisclosureHooks {
+    /// Called with whether the content is open when it changes
+    pub open_change: Option<OpenChangeHook>,
+}
+
+/// Options of [Disclosure]
+pub struct DisclosureOptions {
+    /// The button toggling the content
+    pub trigger: HtmlElement,
+    pub content: HtmlElement,
+    /// Whether the content is initially open
+    pub open: bool,
+    pub hooks: DisclosureHooks,
+}
+
+impl DisclosureOptions {
+    pub fn builder() -> DisclosureOptionsBuilder {
+        DisclosureOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [DisclosureOptions]
+#[derive(Default)]
+pub struct DisclosureOptionsBuilder {
+    trigger: Option<HtmlElement>,
+    content: Option<HtmlElement>,
+    open: bool,
+    hooks: DisclosureHooks,
+}
+
+impl DisclosureOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn trigger(mut self, trigger: HtmlElement) -> Self {
+        self.trigger = Some(trigger);
+        self
+    }
+
+    pub fn content(mut self, content: HtmlElement) -> Self {
+        self.content = Some(content);
+        self
+    }
+
+    pub fn open(mut self, open: bool) -> Self {
+        self.open = open;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: DisclosureHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [DisclosureOptions]
+    ///
+    /// # Panics
+    /// This method panics if trigger or content field is not set
+    pub fn build(self) -> DisclosureOptions {
+        DisclosureOptions {
+            trigger: self
+                .trigger
+                .expect("trigger must be set to build DisclosureOptions"),
+            content: self
+                .content
+                .expect("content must be set to build DisclosureOptions"),
+            open: self.open,
+            hooks: self.hooks,
+        }
+    }
+}
+
+struct Inner {
+    trigger: HtmlElement,
+    content: HtmlElement,
+    is_open: bool,
+    hooks: Rc<DisclosureHooks>,
+    /// Hides the content once its closing animation ends
+    presence: Presence,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    /// Exposes the size of the content, which is measured while it is shown
+    fn measure(&self) {
+        let style = self.content.style();
+        // Animations driven by the variables would otherwise be measured midway
+        let animation = style
+            .get_property_value("animation-name")
+            .unwrap_or_default();
+        let transition = style
+            .get_property_value("transition-duration")
+            .unwrap_or_default();
+        let _ = style.set_property("animation-name", "none");
+        let _ = style.set_property("transition-duration", "0s");
+
+        let rect = self.content.get_bounding_client_rect();
+        let height = rect.height().max(self.content.scroll_height() as f64);
+        let width = rect.width().max(self.content.scroll_width() as f64);
+        let _ = style.set_property("--seigi-collapsible-height", &format!("{height}px"));
+        let _ = style.set_property("--seigi-collapsible-width", &format!("{width}px"));
+
+        let _ = style.set_property("animation-name", &animation);
+        let _ = style.set_property("transition-duration", &transition);
+    }
+
+    fn reflect(&self) {
+        let state = if self.is_open { "open" } else { "closed" };
+        let _ = self
+            .trigger
+            .set_attribute("aria-expanded", if self.is_open { "true" } else { "false" });
+        let _ = self.trigger.set_attribute("data-state", state);
+        let _ = self.content.set_attribute("data-state", state);
+    }
+}
+
+/// An instance of disclosure
+///
+/// Clicking the trigger toggles the content, which is `hidden` while closed. When the content has
+/// a closing animation or transition, it is hidden after it ends.
+///
+/// This is a single collapsible section; an accordion coordinates several of them.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **aria-expanded** is set in the trigger to `true` or `false`, and **aria-controls** to the id
+/// of the content
+///
+/// **data-state** is set in the trigger and the content to `open` or `closed`
+///
+/// # CSS Variables
+/// **--seigi-collapsible-height** and **--seigi-collapsible-width** are set in the content to its
+/// size when it opens or closes, to animate between it and zero
+#[derive(Clone)]
+pub struct Disclosure(Rc<Mutex<Inner>>);
+
+impl Disclosure {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.0.lock().unwrap().is_open
+    }
+
+    pub fn open(&self) {
+        self.set_open(true);
+    }
+
+    pub fn close(&self) {
+        self.set_open(false);
+    }
+
+    pub fn toggle(&self) {
+        let is_open = self.is_open();
+        self.set_open(!is_open);
+    }
+
+    pub fn set_open(&self, is_open: bool) {
+        let hooks = {
+            let mut inner = self.0.lock().unwrap();
+            if inner.is_open == is_open {
+                return;
+            }
+            inner.is_open = is_open;
+
+            if is_open {
+                inner.presence.show();
+                inner.measure();
+                inner.reflect();
+            } else {
+                inner.measure();
+                inner.reflect();
+                inner.presence.hide();
+            }
+            inner.hooks.clone()
+        };
+
+        if let Some(hook) = &hooks.open_change {
+            hook(is_open);
+        }
+    }
+}
+
+pub fn create_disclosure(options: DisclosureOptions) -> Disclosure {
+    let trigger = options.trigger.clone();
+    let content = options.content.clone();
+    id::ensure(&content, "disclosure");
+    id::relate(&trigger, Relation::Controls, &content);
+    let presence = create_presence(
+        PresenceOptions::builder()
+            .element(content.clone())
+            .unmount(Unmount::Hide)
+            .open(options.open)
+            .build(),
+    );
+
+    let disclosure = Disclosure(Rc::new(Mutex::new(Inner {
+        trigger: options.trigger,
+        content: options.content,
+        is_open: options.open,
+        hooks: Rc::new(options.hooks),
+        presence,
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&disclosure.0);
+    let listeners = vec![EventListener::new(&trigger, "click", move |_| {
+        let Some(disclosure) = Disclosure::from_weak(&weak) else {
+            return;
+        };
+        disclosure.toggle();
+    })];
+
+    {
+        let mut inner = disclosure.0.lock().unwrap();
+        inner._listeners = listeners;
+        if inner.is_open {
+            inner.measure();
+        }
+        inner.reflect();
+    }
+
+    disclosure
+}
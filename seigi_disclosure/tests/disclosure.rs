@@ -0,0 +1,165 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use seigi_disclosure::{Disclosure, DisclosureHooks, DisclosureOptions};
+use seigi_testkit::{Fixture, events, wait};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<button id="trigger">Details</button>
+<div id="content" style="width: 120px; height: 40px">Content</div>
+"#;
+
+fn disclosure(fixture: &Fixture, open: bool) -> (Disclosure, Rc<RefCell<Vec<bool>>>) {
+    let changes = Rc::new(RefCell::new(vec![]));
+    let disclosure = seigi_disclosure::create_disclosure(
+        DisclosureOptions::builder()
+            .trigger(fixture.get("#trigger"))
+            .content(fixture.get("#content"))
+            .open(open)
+            .hooks(DisclosureHooks {
+                open_change: Some(Box::new({
+                    let changes = changes.clone();
+                    move |is_open| changes.borrow_mut().push(is_open)
+                })),
+            })
+            .build(),
+    );
+    (disclosure, changes)
+}
+
+fn state(fixture: &Fixture, selector: &str) -> Option<String> {
+    fixture.get(selector).get_attribute("data-state")
+}
+
+fn size(fixture: &Fixture, name: &str) -> String {
+    fixture
+        .get("#content")
+        .style()
+        .get_property_value(&format!("--seigi-collapsible-{name}"))
+        .unwrap()
+}
+
+#[wasm_bindgen_test]
+fn closed_content_is_hidden() {
+    let fixture = Fixture::new(MARKUP);
+    let (disclosure, _) = disclosure(&fixture, false);
+    let trigger = fixture.get("#trigger");
+
+    assert!(!disclosure.is_open());
+    assert!(fixture.get("#content").hidden());
+    assert_eq!(
+        trigger.get_attribute("aria-expanded").as_deref(),
+        Some("false")
+    );
+    assert_eq!(
+        trigger.get_attribute("aria-controls").as_deref(),
+        Some("content")
+    );
+    assert_eq!(state(&fixture, "#trigger").as_deref(), Some("closed"));
+    assert_eq!(state(&fixture, "#content").as_deref(), Some("closed"));
+}
+
+#[wasm_bindgen_test]
+fn content_without_id_is_given_one() {
+    let fixture = Fixture::new(r#"<button id="trigger"></button><div class="content"></div>"#);
+    let _disclosure = seigi_disclosure::create_disclosure(
+        DisclosureOptions::builder()
+            .trigger(fixture.get("#trigger"))
+            .content(fixture.get(".content"))
+            .build(),
+    );
+
+    let id = fixture.get(".content").id();
+    assert!(!id.is_empty());
+    assert_eq!(
+        fixture.get("#trigger").get_attribute("aria-controls"),
+        Some(id)
+    );
+}
+
+#[wasm_bindgen_test]
+fn trigger_toggles_content() {
+    let fixture = Fixture::new(MARKUP);
+    let (disclosure, changes) = disclosure(&fixture, false);
+    let trigger = fixture.get("#trigger");
+
+    events::click(&trigger);
+    assert!(disclosure.is_open());
+    assert!(!fixture.get("#content").hidden());
+    assert_eq!(
+        trigger.get_attribute("aria-expanded").as_deref(),
+        Some("true")
+    );
+    assert_eq!(state(&fixture, "#content").as_deref(), Some("open"));
+    assert_eq!(size(&fixture, "height"), "40px");
+    assert_eq!(size(&fixture, "width"), "120px");
+
+    events::click(&trigger);
+    assert!(!disclosure.is_open());
+    assert!(fixture.get("#content").hidden());
+    assert_eq!(*changes.borrow(), [true, false]);
+}
+
+#[wasm_bindgen_test]
+fn initially_open_content_is_measured() {
+    let fixture = Fixture::new(MARKUP);
+    let (disclosure, changes) = disclosure(&fixture, true);
+
+    assert!(disclosure.is_open());
+    assert!(!fixture.get("#content").hidden());
+    assert_eq!(state(&fixture, "#trigger").as_deref(), Some("open"));
+    assert_eq!(size(&fixture, "height"), "40px");
+
+    // Setting the same state again does nothing
+    disclosure.open();
+    disclosure.toggle();
+    disclosure.close();
+    assert_eq!(*changes.borrow(), [false]);
+}
+
+#[wasm_bindgen_test]
+async fn content_is_hidden_after_closing_animation() {
+    let fixture = Fixture::new(&format!(
+        r#"
+<style>
+    @keyframes seigi-disclosure-collapse {{
+        to {{ opacity: 0; }}
+    }}
+    #content[data-state="closed"] {{
+        animation: seigi-disclosure-collapse 30ms;
+    }}
+</style>
+{MARKUP}
+"#
+    ));
+    let (disclosure, _) = disclosure(&fixture, true);
+
+    disclosure.close();
+    assert_eq!(state(&fixture, "#content").as_deref(), Some("closed"));
+    // Animations paused while measuring are restored
+    assert_eq!(
+        fixture
+            .get("#content")
+            .style()
+            .get_property_value("animation-name")
+            .unwrap(),
+        ""
+    );
+    assert!(!fixture.get("#content").hidden());
+
+    wait::sleep(150).await;
+    assert!(fixture.get("#content").hidden());
+}
+
+#[wasm_bindgen_test]
+fn dropped_disclosure_ignores_trigger() {
+    let fixture = Fixture::new(MARKUP);
+    drop(disclosure(&fixture, false));
+
+    events::click(&fixture.get("#trigger"));
+    assert!(fixture.get("#content").hidden());
+}
@@ -0,0 +1,122 @@
+//! Collision detection between the dragged item and drop targets
+
+use web_sys::DomRect;
+
+/// A rectangle in viewport coordinates
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn center(&self) -> (f64, f64) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    pub fn contains(&self, (x, y): (f64, f64)) -> bool {
+        x >= self.x && x <= self.x + self.width && y >= self.y && y <= self.y + self.height
+    }
+
+    /// Returns the area shared with the other rect
+    pub fn intersection(&self, other: &Rect) -> f64 {
+        let width = (self.x + self.width).min(other.x + other.width) - self.x.max(other.x);
+        let height = (self.y + self.height).min(other.y + other.height) - self.y.max(other.y);
+        width.max(0.0) * height.max(0.0)
+    }
+}
+
+impl From<DomRect> for Rect {
+    fn from(value: DomRect) -> Self {
+        Self::new(value.x(), value.y(), value.width(), value.height())
+    }
+}
+
+/// A strategy deciding which drop target the dragged item is over
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Collision {
+    /// The target under the pointer
+    PointerWithin,
+    /// The target whose center is closest to the center of the dragged item
+    #[default]
+    ClosestCenter,
+    /// The target sharing the largest area with the dragged item
+    RectIntersection,
+}
+
+fn distance((ax, ay): (f64, f64), (bx, by): (f64, f64)) -> f64 {
+    ((ax - bx).powi(2) + (ay - by).powi(2)).sqrt()
+}
+
+/// Returns the index of the target the dragged item is over
+///
+/// # Arguments
+/// * `pointer` - The pointer position in viewport coordinates
+/// * `active` - The rect of the dragged item
+/// * `targets` - The rects of drop targets
+pub fn detect(
+    collision: Collision,
+    pointer: (f64, f64),
+    active: &Rect,
+    targets: &[Rect],
+) -> Option<usize> {
+    match collision {
+        Collision::PointerWithin => targets
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| v.contains(pointer))
+            // Nested targets are smaller, so the innermost one wins
+            .min_by(|(_, a), (_, b)| (a.width * a.height).total_cmp(&(b.width * b.height)))
+            .map(|(index, _)| index),
+        Collision::ClosestCenter => targets
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                distance(a.center(), active.center())
+                    .total_cmp(&distance(b.center(), active.center()))
+            })
+            .map(|(index, _)| index),
+        Collision::RectIntersection => targets
+            .iter()
+            .enumerate()
+            .map(|(index, v)| (index, v.intersection(active)))
+            .filter(|(_, area)| *area > 0.0)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index),
+    }
+}
+
+/// Returns the index of the closest target in the direction of the arrow key from the rect, for
+/// moving between targets with a keyboard
+pub fn closest_in_direction(key: &str, from: &Rect, targets: &[Rect]) -> Option<usize> {
+    let (fx, fy) = from.center();
+    targets
+        .iter()
+        .enumerate()
+        .filter_map(|(index, v)| {
+            let (x, y) = v.center();
+            let (dx, dy) = (x - fx, y - fy);
+            // Distance along the direction, and the offset across it
+            let (along, across) = match key {
+                "ArrowUp" => (-dy, dx),
+                "ArrowDown" => (dy, dx),
+                "ArrowLeft" => (-dx, dy),
+                "ArrowRight" => (dx, dy),
+                _ => return None,
+            };
+            (along > 0.5).then_some((index, along + across.abs() * 2.0))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(index, _)| index)
+}
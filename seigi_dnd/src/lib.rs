@@ -0,0 +1,877 @@
+//! Headless pointer and keyboard drag-and-drop with sortable lists
+
+pub mod collision;
+pub mod sortable;
+
+use std::{
+    rc::{Rc, Weak},
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use gloo::{events::EventListener, timers::callback::Interval, utils::document};
+use seigi_utils::{
+    announcer::{self, VISUALLY_HIDDEN},
+    i18n,
+    id::{self, Relation},
+};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, KeyboardEvent, Node, PointerEvent};
+
+use crate::collision::{Collision, Rect, closest_in_direction, detect};
+
+/// An event emitted by [DragDrop] with ids of the dragged item and the drop target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DndEvent {
+    Start {
+        active: String,
+    },
+    /// The drop target under the dragged item changed
+    Over {
+        active: String,
+        over: Option<String>,
+    },
+    /// The item was dropped, over the target if any
+    End {
+        active: String,
+        over: Option<String>,
+    },
+    Cancel {
+        active: String,
+    },
+}
+
+impl DndEvent {
+    /// Returns the id of the dragged item
+    pub fn active(&self) -> &str {
+        match self {
+            DndEvent::Start { active }
+            | DndEvent::Over { active, .. }
+            | DndEvent::End { active, .. }
+            | DndEvent::Cancel { active } => active,
+        }
+    }
+}
+
+pub type Announce = Box<dyn Fn(&DndEvent) -> Option<String>>;
+
+/// Returns the default screen reader announcement of the event, from messages `dnd.start`,
+/// `dnd.over`, `dnd.leave`, `dnd.drop`, `dnd.end` and `dnd.cancel` of [i18n]
+pub fn default_announcement(event: &DndEvent) -> Option<String> {
+    Some(match event {
+        DndEvent::Start { active } => i18n::translate("dnd.start", &[("active", active)]),
+        DndEvent::Over {
+            active,
+            over: Some(over),
+        } => i18n::translate("dnd.over", &[("active", active), ("over", over)]),
+        DndEvent::Over { active, over: None } => {
+            i18n::translate("dnd.leave", &[("active", active)])
+        }
+        DndEvent::End {
+            active,
+            over: Some(over),
+        } => i18n::translate("dnd.drop", &[("active", active), ("over", over)]),
+        DndEvent::End { active, over: None } => i18n::translate("dnd.end", &[("active", active)]),
+        DndEvent::Cancel { active } => i18n::translate("dnd.cancel", &[("active", active)]),
+    })
+}
+
+/// Options of [DragDrop]
+pub struct DragDropOptions {
+    pub collision: Collision,
+    /// The distance in pixels the pointer moves before a drag starts, so clicks on draggable
+    /// items still work
+    pub activation_distance: f64,
+    /// Whether the scroll container under the pointer scrolls while the pointer is near its
+    /// edges
+    pub auto_scroll: bool,
+    /// Instructions read by screen readers when a draggable item is focused
+    pub instructions: String,
+    /// Returns the announcement of an event, or None to stay silent. Defaults to
+    /// [default_announcement]
+    pub announce: Announce,
+}
+
+impl DragDropOptions {
+    pub fn builder() -> DragDropOptionsBuilder {
+        DragDropOptionsBuilder::new()
+    }
+}
+
+impl Default for DragDropOptions {
+    fn default() -> Self {
+        DragDropOptionsBuilder::new().build()
+    }
+}
+
+/// A builder struct of [DragDropOptions]
+pub struct DragDropOptionsBuilder {
+    collision: Collision,
+    activation_distance: f64,
+    auto_scroll: bool,
+    instructions: String,
+    announce: Announce,
+}
+
+impl Default for DragDropOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            collision: Collision::default(),
+            activation_distance: 4.0,
+            auto_scroll: true,
+            instructions: i18n::translate("dnd.instructions", &[]),
+            announce: Box::new(default_announcement),
+        }
+    }
+}
+
+impl DragDropOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn collision(mut self, collision: Collision) -> Self {
+        self.collision = collision;
+        self
+    }
+
+    pub fn activation_distance(mut self, activation_distance: f64) -> Self {
+        self.activation_distance = activation_distance;
+        self
+    }
+
+    pub fn auto_scroll(mut self, auto_scroll: bool) -> Self {
+        self.auto_scroll = auto_scroll;
+        self
+    }
+
+    pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = instructions.into();
+        self
+    }
+
+    pub fn announce(mut self, announce: impl Fn(&DndEvent) -> Option<String> + 'static) -> Self {
+        self.announce = Box::new(announce);
+        self
+    }
+
+    /// Builds into [DragDropOptions]
+    pub fn build(self) -> DragDropOptions {
+        DragDropOptions {
+            collision: self.collision,
+            activation_distance: self.activation_distance,
+            auto_scroll: self.auto_scroll,
+            instructions: self.instructions,
+            announce: self.announce,
+        }
+    }
+}
+
+/// The distance from the edge of a scroll container that starts auto scroll
+const SCROLL_EDGE: f64 = 40.0;
+/// The largest distance auto scroll moves per frame
+const SCROLL_SPEED: f64 = 16.0;
+
+struct Subscriber {
+    callback: Box<dyn Fn(&DndEvent)>,
+    handle: u64,
+}
+
+struct Draggable {
+    id: String,
+    element: HtmlElement,
+    _listeners: Vec<EventListener>,
+}
+
+struct Droppable {
+    id: String,
+    element: HtmlElement,
+}
+
+enum Mode {
+    Pointer {
+        pointer_id: i32,
+        origin: (f64, f64),
+        started: bool,
+    },
+    Keyboard,
+}
+
+struct Active {
+    id: String,
+    element: HtmlElement,
+    mode: Mode,
+    over: Option<String>,
+    pointer: (f64, f64),
+    _listeners: Vec<EventListener>,
+    _scroll: Option<Interval>,
+}
+
+struct Inner {
+    options: DragDropOptions,
+    draggables: Vec<Draggable>,
+    droppables: Vec<Droppable>,
+    active: Option<Active>,
+    subscribers: Vec<Rc<Subscriber>>,
+    instructions: HtmlElement,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.instructions.remove();
+    }
+}
+
+/// A drag-and-drop context of draggable items and drop targets
+///
+/// Items are dragged with a pointer, or picked up with space/enter and moved between drop targets
+/// with arrow keys. Progress is announced to screen readers through a live region. While a
+/// pointer drags an item, the item is not moved; its offset is exposed as CSS variables, so it
+/// can be translated with CSS.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-seigi-draggable** and **data-seigi-droppable** are set in registered elements
+///
+/// **data-dragging** is set in the dragged item
+///
+/// **--seigi-drag-x** and **--seigi-drag-y** CSS variables are set in the dragged item to the
+/// pointer offset from where the drag started
+///
+/// **data-over** is set in the drop target under the dragged item
+#[derive(Clone)]
+pub struct DragDrop(Rc<Mutex<Inner>>);
+
+impl DragDrop {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    /// Add subscriber to context and return handle to it
+    ///
+    /// # Returns
+    /// Handle of added subscriber
+    pub fn subscribe(&self, callback: Box<dyn Fn(&DndEvent)>) -> u64 {
+        static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+        let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .push(Rc::new(Subscriber { callback, handle }));
+
+        handle
+    }
+
+    /// Remove subscriber from context
+    pub fn unsubscribe(&self, handle: u64) {
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .retain(|v| v.handle != handle);
+    }
+
+    /// Returns the id of the dragged item, if any
+    pub fn active(&self) -> Option<String> {
+        self.0
+            .lock()
+            .unwrap()
+            .active
+            .as_ref()
+            .filter(|v| !matches!(v.mode, Mode::Pointer { started: false, .. }))
+            .map(|v| v.id.clone())
+    }
+
+    /// Registers the element as a draggable item with the id
+    ///
+    /// If the element contains an element with `data-seigi-drag-handle`, only the handle starts
+    /// pointer drags.
+    pub fn add_draggable(&self, id: impl Into<String>, element: HtmlElement) {
+        let id = id.into();
+        self.remove_draggable(&id);
+
+        let _ = element.set_attribute("data-seigi-draggable", "");
+        let _ = element.set_attribute("aria-roledescription", "draggable");
+        id::relate(
+            &element,
+            Relation::DescribedBy,
+            &self.0.lock().unwrap().instructions,
+        );
+        if !element.has_attribute("role") {
+            let _ = element.set_attribute("role", "button");
+        }
+        if !element.has_attribute("tabindex") {
+            element.set_tab_index(0);
+        }
+
+        let weak = Rc::downgrade(&self.0);
+        let listeners = vec![
+            EventListener::new(&element, "pointerdown", {
+                let weak = weak.clone();
+                let id = id.clone();
+                let element = element.clone();
+                move |event| {
+                    let (Some(dnd), Some(event)) =
+                        (DragDrop::from_weak(&weak), event.dyn_ref::<PointerEvent>())
+                    else {
+                        return;
+                    };
+                    if event.button() != 0 || !is_from_handle(&element, event) {
+                        return;
+                    }
+                    dnd.handle_pointer_down(&id, &element, event);
+                }
+            }),
+            EventListener::new(&element, "keydown", {
+                let weak = weak.clone();
+                let id = id.clone();
+                let element = element.clone();
+                move |event| {
+                    let (Some(dnd), Some(event)) =
+                        (DragDrop::from_weak(&weak), event.dyn_ref::<KeyboardEvent>())
+                    else {
+                        return;
+                    };
+                    if !matches!(event.key().as_str(), "Enter" | " ")
+                        || dnd.0.lock().unwrap().active.is_some()
+                    {
+                        return;
+                    }
+                    event.prevent_default();
+                    // The same key press would otherwise drop the item right away
+                    event.stop_propagation();
+                    dnd.start_keyboard(&id, &element);
+                }
+            }),
+        ];
+
+        self.0.lock().unwrap().draggables.push(Draggable {
+            id,
+            element,
+            _listeners: listeners,
+        });
+    }
+
+    /// Unregisters the draggable item, cancelling its drag if active
+    pub fn remove_draggable(&self, id: &str) {
+        let is_active = self
+            .0
+            .lock()
+            .unwrap()
+            .active
+            .as_ref()
+            .is_some_and(|v| v.id == id);
+        if is_active {
+            self.cancel();
+        }
+
+        let removed = {
+            let mut inner = self.0.lock().unwrap();
+            let index = inner.draggables.iter().position(|v| v.id == id);
+            index.map(|v| inner.draggables.remove(v))
+        };
+        if let Some(draggable) = removed {
+            let _ = draggable.element.remove_attribute("data-seigi-draggable");
+            let _ = draggable.element.remove_attribute("aria-roledescription");
+            let instructions = self.0.lock().unwrap().instructions.clone();
+            id::unrelate(&draggable.element, Relation::DescribedBy, &instructions);
+        }
+    }
+
+    /// Registers the element as a drop target with the id
+    pub fn add_droppable(&self, id: impl Into<String>, element: HtmlElement) {
+        let id = id.into();
+        self.remove_droppable(&id);
+
+        let _ = element.set_attribute("data-seigi-droppable", "");
+        self.0
+            .lock()
+            .unwrap()
+            .droppables
+            .push(Droppable { id, element });
+    }
+
+    /// Unregisters the drop target
+    pub fn remove_droppable(&self, id: &str) {
+        let removed = {
+            let mut inner = self.0.lock().unwrap();
+            let index = inner.droppables.iter().position(|v| v.id == id);
+            index.map(|v| inner.droppables.remove(v))
+        };
+        if let Some(droppable) = removed {
+            let _ = droppable.element.remove_attribute("data-seigi-droppable");
+            let _ = droppable.element.remove_attribute("data-over");
+        }
+    }
+
+    /// Cancels the active drag, if any
+    pub fn cancel(&self) {
+        self.finish(false);
+    }
+
+    fn emit(&self, event: DndEvent) {
+        let (subscribers, announcement) = {
+            let inner = self.0.lock().unwrap();
+            (inner.subscribers.clone(), (inner.options.announce)(&event))
+        };
+
+        if let Some(announcement) = announcement {
+            announcer::assertive(announcement);
+        }
+        for subscriber in subscribers {
+            (subscriber.callback)(&event);
+        }
+    }
+
+    fn window_listeners(&self) -> Vec<EventListener> {
+        let weak = Rc::downgrade(&self.0);
+        let window = gloo::utils::window();
+        vec![
+            EventListener::new(&window, "pointermove", {
+                let weak = weak.clone();
+                move |event| {
+                    let (Some(dnd), Some(event)) =
+                        (DragDrop::from_weak(&weak), event.dyn_ref::<PointerEvent>())
+                    else {
+                        return;
+                    };
+                    dnd.handle_pointer_move(event);
+                }
+            }),
+            EventListener::new(&window, "pointerup", {
+                let weak = weak.clone();
+                move |_| {
+                    if let Some(dnd) = DragDrop::from_weak(&weak) {
+                        dnd.finish(true);
+                    }
+                }
+            }),
+            EventListener::new(&window, "pointercancel", {
+                let weak = weak.clone();
+                move |_| {
+                    if let Some(dnd) = DragDrop::from_weak(&weak) {
+                        dnd.finish(false);
+                    }
+                }
+            }),
+            EventListener::new(&window, "keydown", {
+                let weak = weak.clone();
+                move |event| {
+                    let (Some(dnd), Some(event)) =
+                        (DragDrop::from_weak(&weak), event.dyn_ref::<KeyboardEvent>())
+                    else {
+                        return;
+                    };
+                    dnd.handle_key_down(event);
+                }
+            }),
+        ]
+    }
+
+    fn handle_pointer_down(&self, id: &str, element: &HtmlElement, event: &PointerEvent) {
+        if self.0.lock().unwrap().active.is_some() {
+            return;
+        }
+
+        let listeners = self.window_listeners();
+        let pointer = (event.client_x() as f64, event.client_y() as f64);
+        self.0.lock().unwrap().active = Some(Active {
+            id: id.to_string(),
+            element: element.clone(),
+            mode: Mode::Pointer {
+                pointer_id: event.pointer_id(),
+                origin: pointer,
+                started: false,
+            },
+            over: None,
+            pointer,
+            _listeners: listeners,
+            _scroll: None,
+        });
+    }
+
+    fn handle_pointer_move(&self, event: &PointerEvent) {
+        let pointer = (event.client_x() as f64, event.client_y() as f64);
+        let (start, origin) = {
+            let mut inner = self.0.lock().unwrap();
+            let activation_distance = inner.options.activation_distance;
+            let Some(active) = &mut inner.active else {
+                return;
+            };
+            let Mode::Pointer {
+                pointer_id,
+                origin,
+                started,
+            } = &mut active.mode
+            else {
+                return;
+            };
+            if *pointer_id != event.pointer_id() {
+                return;
+            }
+
+            active.pointer = pointer;
+            let distance = ((pointer.0 - origin.0).powi(2) + (pointer.1 - origin.1).powi(2)).sqrt();
+            let start = !*started && distance >= activation_distance;
+            if !*started && !start {
+                return;
+            }
+            *started = true;
+            (start.then(|| active.id.clone()), *origin)
+        };
+
+        // Text selection would follow the pointer while dragging
+        event.prevent_default();
+
+        if let Some(id) = start {
+            self.begin();
+            self.emit(DndEvent::Start { active: id });
+        }
+
+        let element = self
+            .0
+            .lock()
+            .unwrap()
+            .active
+            .as_ref()
+            .map(|v| v.element.clone());
+        if let Some(element) = element {
+            let style = element.style();
+            let _ = style.set_property("--seigi-drag-x", &format!("{}px", pointer.0 - origin.0));
+            let _ = style.set_property("--seigi-drag-y", &format!("{}px", pointer.1 - origin.1));
+        }
+
+        self.update_over();
+    }
+
+    fn handle_key_down(&self, event: &KeyboardEvent) {
+        let key = event.key();
+        let mode = {
+            let inner = self.0.lock().unwrap();
+            let Some(active) = &inner.active else {
+                return;
+            };
+            match active.mode {
+                Mode::Keyboard => Some(true),
+                Mode::Pointer { started: true, .. } => Some(false),
+                Mode::Pointer { started: false, .. } => None,
+            }
+        };
+        let Some(is_keyboard) = mode else {
+            return;
+        };
+
+        match key.as_str() {
+            "Escape" => {
+                event.prevent_default();
+                self.finish(false);
+            }
+            "Enter" | " " if is_keyboard => {
+                event.prevent_default();
+                self.finish(true);
+            }
+            "ArrowUp" | "ArrowDown" | "ArrowLeft" | "ArrowRight" if is_keyboard => {
+                event.prevent_default();
+                self.move_keyboard(&key);
+            }
+            // Focus must stay on the dragged item while dragging with a keyboard
+            "Tab" if is_keyboard => event.prevent_default(),
+            _ => {}
+        }
+    }
+
+    fn start_keyboard(&self, id: &str, element: &HtmlElement) {
+        let listeners = self.window_listeners();
+        // The drop target containing the item is where the item starts
+        let over = {
+            let inner = self.0.lock().unwrap();
+            inner
+                .droppables
+                .iter()
+                .filter(|v| v.element.contains(Some(element)))
+                .min_by_key(|v| depth_from(&v.element, element))
+                .map(|v| v.id.clone())
+        };
+
+        self.0.lock().unwrap().active = Some(Active {
+            id: id.to_string(),
+            element: element.clone(),
+            mode: Mode::Keyboard,
+            over: None,
+            pointer: (0.0, 0.0),
+            _listeners: listeners,
+            _scroll: None,
+        });
+
+        self.begin();
+        self.emit(DndEvent::Start {
+            active: id.to_string(),
+        });
+        if over.is_some() {
+            self.set_over(over);
+        }
+    }
+
+    fn move_keyboard(&self, key: &str) {
+        let next = {
+            let inner = self.0.lock().unwrap();
+            let Some(active) = &inner.active else {
+                return;
+            };
+            let from = inner
+                .droppables
+                .iter()
+                .find(|v| Some(&v.id) == active.over.as_ref())
+                .map(|v| &v.element)
+                .unwrap_or(&active.element);
+            let from = Rect::from(from.get_bounding_client_rect());
+            let rects = inner
+                .droppables
+                .iter()
+                .map(|v| Rect::from(v.element.get_bounding_client_rect()))
+                .collect::<Vec<_>>();
+
+            closest_in_direction(key, &from, &rects).map(|v| inner.droppables[v].id.clone())
+        };
+
+        if next.is_some() {
+            self.set_over(next);
+        }
+    }
+
+    /// Marks the item as dragged once the drag has started
+    fn begin(&self) {
+        let mut inner = self.0.lock().unwrap();
+        let auto_scroll = inner.options.auto_scroll;
+        let weak = Rc::downgrade(&self.0);
+        let Some(active) = &mut inner.active else {
+            return;
+        };
+
+        let _ = active.element.set_attribute("data-dragging", "");
+        if auto_scroll && matches!(active.mode, Mode::Pointer { .. }) {
+            active._scroll = Some(Interval::new(16, move || {
+                if let Some(dnd) = DragDrop::from_weak(&weak) {
+                    dnd.auto_scroll();
+                }
+            }));
+        }
+    }
+
+    fn update_over(&self) {
+        let over = {
+            let inner = self.0.lock().unwrap();
+            let Some(active) = &inner.active else {
+                return;
+            };
+            let rects = inner
+                .droppables
+                .iter()
+                .map(|v| Rect::from(v.element.get_bounding_client_rect()))
+                .collect::<Vec<_>>();
+            let active_rect = Rect::from(active.element.get_bounding_client_rect());
+
+            detect(
+                inner.options.collision,
+                active.pointer,
+                &active_rect,
+                &rects,
+            )
+            .map(|v| inner.droppables[v].id.clone())
+        };
+
+        self.set_over(over);
+    }
+
+    fn set_over(&self, over: Option<String>) {
+        let active = {
+            let mut inner = self.0.lock().unwrap();
+            let Inner {
+                active, droppables, ..
+            } = &mut *inner;
+            let Some(active) = active else {
+                return;
+            };
+            if active.over == over {
+                return;
+            }
+
+            for droppable in droppables.iter() {
+                if Some(&droppable.id) == over.as_ref() {
+                    let _ = droppable.element.set_attribute("data-over", "");
+                } else {
+                    let _ = droppable.element.remove_attribute("data-over");
+                }
+            }
+            active.over = over.clone();
+            active.id.clone()
+        };
+
+        self.emit(DndEvent::Over { active, over });
+    }
+
+    fn finish(&self, drop: bool) {
+        let (active, droppables) = {
+            let mut inner = self.0.lock().unwrap();
+            let Some(active) = inner.active.take() else {
+                return;
+            };
+            let droppables = inner
+                .droppables
+                .iter()
+                .map(|v| v.element.clone())
+                .collect::<Vec<_>>();
+            (active, droppables)
+        };
+
+        let _ = active.element.remove_attribute("data-dragging");
+        let style = active.element.style();
+        let _ = style.remove_property("--seigi-drag-x");
+        let _ = style.remove_property("--seigi-drag-y");
+        for element in droppables {
+            let _ = element.remove_attribute("data-over");
+        }
+
+        if matches!(active.mode, Mode::Pointer { started: false, .. }) {
+            return;
+        }
+        if matches!(active.mode, Mode::Keyboard) {
+            let _ = active.element.focus();
+        }
+
+        let Active { id, over, .. } = active;
+        if drop {
+            self.emit(DndEvent::End { active: id, over });
+        } else {
+            self.emit(DndEvent::Cancel { active: id });
+        }
+    }
+
+    fn auto_scroll(&self) {
+        let Some((pointer, element)) = self
+            .0
+            .lock()
+            .unwrap()
+            .active
+            .as_ref()
+            .map(|v| (v.pointer, v.element.clone()))
+        else {
+            return;
+        };
+
+        let target = document()
+            .element_from_point(pointer.0 as f32, pointer.1 as f32)
+            .unwrap_or_else(|| element.into());
+        let Some(container) = scroll_container(&target) else {
+            return;
+        };
+
+        let rect = if container == document().scrolling_element().unwrap_or(container.clone()) {
+            let window = gloo::utils::window();
+            Rect::new(
+                0.0,
+                0.0,
+                window
+                    .inner_width()
+                    .ok()
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(0.0),
+                window
+                    .inner_height()
+                    .ok()
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(0.0),
+            )
+        } else {
+            Rect::from(container.get_bounding_client_rect())
+        };
+
+        let speed = |distance: f64| {
+            if distance < SCROLL_EDGE {
+                (1.0 - distance.max(0.0) / SCROLL_EDGE) * SCROLL_SPEED
+            } else {
+                0.0
+            }
+        };
+        let dx = speed(rect.x + rect.width - pointer.0) - speed(pointer.0 - rect.x);
+        let dy = speed(rect.y + rect.height - pointer.1) - speed(pointer.1 - rect.y);
+        if dx != 0.0 || dy != 0.0 {
+            container.scroll_by_with_x_and_y(dx, dy);
+            self.update_over();
+        }
+    }
+}
+
+/// Returns whether the pointer event may start a drag of the element
+fn is_from_handle(element: &HtmlElement, event: &PointerEvent) -> bool {
+    let Ok(Some(handle)) = element.query_selector("[data-seigi-drag-handle]") else {
+        return true;
+    };
+    let target = event.target().and_then(|v| v.dyn_into::<Node>().ok());
+    handle.contains(target.as_ref())
+}
+
+fn depth_from(ancestor: &Element, element: &Element) -> usize {
+    let mut depth = 0;
+    let mut current = Some(element.clone());
+    while let Some(node) = current {
+        if &node == ancestor {
+            break;
+        }
+        depth += 1;
+        current = node.parent_element();
+    }
+    depth
+}
+
+/// Returns the closest scrollable ancestor of the element, or the document scroller
+fn scroll_container(element: &Element) -> Option<Element> {
+    let window = gloo::utils::window();
+    let mut current = Some(element.clone());
+    while let Some(node) = current {
+        let is_overflowing = node.scroll_height() > node.client_height()
+            || node.scroll_width() > node.client_width();
+        if is_overflowing && let Ok(Some(style)) = window.get_computed_style(&node) {
+            let overflow = format!(
+                "{} {}",
+                style.get_property_value("overflow-x").unwrap_or_default(),
+                style.get_property_value("overflow-y").unwrap_or_default()
+            );
+            if overflow.contains("auto") || overflow.contains("scroll") {
+                return Some(node);
+            }
+        }
+        current = node.parent_element();
+    }
+
+    document().scrolling_element()
+}
+
+pub fn create_drag_drop(options: DragDropOptions) -> DragDrop {
+    let document = document();
+
+    let instructions = document
+        .create_element("div")
+        .expect("failed to create element")
+        .unchecked_into::<HtmlElement>();
+    let _ = instructions.set_attribute("style", VISUALLY_HIDDEN);
+    instructions.set_id(&id::generate("dnd-instructions"));
+    instructions.set_text_content(Some(&options.instructions));
+    if let Some(body) = document.body() {
+        let _ = body.append_child(&instructions);
+    }
+
+    DragDrop(Rc::new(Mutex::new(Inner {
+        options,
+        draggables: vec![],
+        droppables: vec![],
+        active: None,
+        subscribers: vec![],
+        instructions,
+    })))
+}
@@ -0,0 +1,296 @@
+//! Sortable list built on [DragDrop]
+
+use std::{rc::Rc, sync::Mutex};
+
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+use crate::{DndEvent, DragDrop, DragDropOptions, create_drag_drop};
+
+/// A reorder of a [Sortable]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reorder {
+    /// Id of the moved item
+    pub id: String,
+    /// The index the item was at
+    pub from: usize,
+    /// The index the item is moved to
+    pub to: usize,
+}
+
+impl Reorder {
+    /// Applies the reorder to a slice of items in the same order as the list
+    pub fn apply<T>(&self, items: &mut [T]) {
+        if self.from < self.to {
+            items[self.from..=self.to].rotate_left(1);
+        } else {
+            items[self.to..=self.from].rotate_right(1);
+        }
+    }
+}
+
+pub type ReorderHook = Box<dyn Fn(&Reorder)>;
+
+/// Hooks to [Sortable]
+#[derive(Default)]
+pub struct SortableHooks {
+    /// Called when an item is dropped at a different index
+    pub reorder: Option<ReorderHook>,
+}
+
+/// Options of [Sortable]
+pub struct SortableOptions {
+    /// The element containing items with `data-seigi-sortable-item` of their id
+    pub container: HtmlElement,
+    /// Whether item elements are moved in the DOM on reorder. Disable this if a renderer owns
+    /// the list and reorders it from the reorder hook
+    pub move_elements: bool,
+    pub drag_drop: DragDropOptions,
+    pub hooks: SortableHooks,
+}
+
+impl SortableOptions {
+    pub fn builder() -> SortableOptionsBuilder {
+        SortableOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [SortableOptions]
+pub struct SortableOptionsBuilder {
+    container: Option<HtmlElement>,
+    move_elements: bool,
+    drag_drop: Option<DragDropOptions>,
+    hooks: SortableHooks,
+}
+
+impl Default for SortableOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            container: None,
+            move_elements: true,
+            drag_drop: None,
+            hooks: SortableHooks::default(),
+        }
+    }
+}
+
+impl SortableOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn container(mut self, container: HtmlElement) -> Self {
+        self.container = Some(container);
+        self
+    }
+
+    pub fn move_elements(mut self, move_elements: bool) -> Self {
+        self.move_elements = move_elements;
+        self
+    }
+
+    pub fn drag_drop(mut self, drag_drop: DragDropOptions) -> Self {
+        self.drag_drop = Some(drag_drop);
+        self
+    }
+
+    pub fn hooks(mut self, hooks: SortableHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [SortableOptions]
+    ///
+    /// # Panics
+    /// This method panics if container field is not set
+    pub fn build(self) -> SortableOptions {
+        SortableOptions {
+            container: self
+                .container
+                .expect("container must be set to build SortableOptions"),
+            move_elements: self.move_elements,
+            drag_drop: self.drag_drop.unwrap_or_default(),
+            hooks: self.hooks,
+        }
+    }
+}
+
+struct Inner {
+    container: HtmlElement,
+    move_elements: bool,
+    hooks: SortableHooks,
+}
+
+impl Inner {
+    /// Returns ids and elements of items in DOM order
+    fn items(&self) -> Vec<(String, HtmlElement)> {
+        let Ok(nodes) = self
+            .container
+            .query_selector_all("[data-seigi-sortable-item]")
+        else {
+            return vec![];
+        };
+
+        (0..nodes.length())
+            .filter_map(|v| nodes.get(v))
+            .filter_map(|v| v.dyn_into::<HtmlElement>().ok())
+            .filter_map(|v| Some((v.get_attribute("data-seigi-sortable-item")?, v)))
+            .collect()
+    }
+
+    fn clear_indicators(&self) {
+        for (_, element) in self.items() {
+            let _ = element.remove_attribute("data-sortable-before");
+            let _ = element.remove_attribute("data-sortable-after");
+        }
+    }
+
+    fn handle_event(&self, event: &DndEvent) {
+        match event {
+            DndEvent::Over { active, over } => {
+                self.clear_indicators();
+                let items = self.items();
+                let (Some(from), Some(over)) = (
+                    items.iter().position(|(id, _)| id == active),
+                    over.as_ref()
+                        .and_then(|over| items.iter().position(|(id, _)| id == over)),
+                ) else {
+                    return;
+                };
+
+                let attribute = match over.cmp(&from) {
+                    std::cmp::Ordering::Less => "data-sortable-before",
+                    std::cmp::Ordering::Greater => "data-sortable-after",
+                    std::cmp::Ordering::Equal => return,
+                };
+                let _ = items[over].1.set_attribute(attribute, "");
+            }
+            DndEvent::End { active, over } => {
+                self.clear_indicators();
+                let items = self.items();
+                let (Some(from), Some(to)) = (
+                    items.iter().position(|(id, _)| id == active),
+                    over.as_ref()
+                        .and_then(|over| items.iter().position(|(id, _)| id == over)),
+                ) else {
+                    return;
+                };
+                if from == to {
+                    return;
+                }
+
+                if self.move_elements {
+                    let element = &items[from].1;
+                    let target = &items[to].1;
+                    let reference = if to > from {
+                        target.next_sibling()
+                    } else {
+                        Some(target.clone().into())
+                    };
+                    if let Some(parent) = target.parent_node() {
+                        let _ = parent.insert_before(element, reference.as_ref());
+                    }
+                    // Moving the element in the DOM blurs it
+                    let _ = element.focus();
+                }
+
+                if let Some(hook) = &self.hooks.reorder {
+                    hook(&Reorder {
+                        id: active.clone(),
+                        from,
+                        to,
+                    });
+                }
+            }
+            DndEvent::Cancel { .. } => self.clear_indicators(),
+            DndEvent::Start { .. } => {}
+        }
+    }
+}
+
+/// An instance of sortable list
+///
+/// Every item is both a draggable item and a drop target, and dropping an item over another moves
+/// it to the index of that item. Items are reordered with a pointer, or with arrow keys after
+/// picking an item up with space/enter.
+///
+/// Call [Sortable::refresh] after items are added or removed.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-seigi-sortable** is set in the container
+///
+/// **data-sortable-before** and **data-sortable-after** are set in the item the dragged item is
+/// over, depending on which side of it the dragged item will be placed
+#[derive(Clone)]
+pub struct Sortable {
+    inner: Rc<Inner>,
+    drag_drop: DragDrop,
+    ids: Rc<Mutex<Vec<String>>>,
+}
+
+impl Sortable {
+    /// Registers items added to and unregisters items removed from the container
+    pub fn refresh(&self) {
+        let items = self.inner.items();
+        let mut ids = self.ids.lock().unwrap();
+        for id in ids.iter() {
+            if !items.iter().any(|(v, _)| v == id) {
+                self.drag_drop.remove_draggable(id);
+                self.drag_drop.remove_droppable(id);
+            }
+        }
+        for (id, element) in &items {
+            if !ids.contains(id) {
+                self.drag_drop.add_draggable(id.clone(), element.clone());
+                self.drag_drop.add_droppable(id.clone(), element.clone());
+            }
+        }
+        *ids = items.into_iter().map(|(id, _)| id).collect();
+    }
+
+    /// Returns ids of items in DOM order
+    pub fn ids(&self) -> Vec<String> {
+        self.inner.items().into_iter().map(|(id, _)| id).collect()
+    }
+
+    /// Returns the underlying drag-and-drop context
+    pub fn drag_drop(&self) -> &DragDrop {
+        &self.drag_drop
+    }
+}
+
+pub fn create_sortable(options: SortableOptions) -> Sortable {
+    let SortableOptions {
+        container,
+        move_elements,
+        drag_drop,
+        hooks,
+    } = options;
+    let _ = container.set_attribute("data-seigi-sortable", "");
+
+    let inner = Rc::new(Inner {
+        container,
+        move_elements,
+        hooks,
+    });
+    let drag_drop = create_drag_drop(drag_drop);
+    drag_drop.subscribe(Box::new({
+        let inner = Rc::downgrade(&inner);
+        move |event| {
+            if let Some(inner) = inner.upgrade() {
+                inner.handle_event(event);
+            }
+        }
+    }));
+
+    let sortable = Sortable {
+        inner,
+        drag_drop,
+        ids: Rc::default(),
+    };
+    sortable.refresh();
+    sortable
+}
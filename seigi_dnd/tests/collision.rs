@@ -0,0 +1,169 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use seigi_dnd::{
+    DndEvent,
+    collision::{Collision, Rect, closest_in_direction, detect},
+    default_announcement,
+    sortable::Reorder,
+};
+
+/// Rects of 10x10 in a 3x3 grid with gaps of 10, in reading order
+fn grid() -> Vec<Rect> {
+    (0..9)
+        .map(|v| Rect::new((v % 3) as f64 * 20.0, (v / 3) as f64 * 20.0, 10.0, 10.0))
+        .collect()
+}
+
+fn detect_without_targets(collision: Collision) -> Option<usize> {
+    detect(collision, (0.0, 0.0), &Rect::default(), &[])
+}
+
+#[test]
+fn rect_geometry() {
+    let rect = Rect::new(10.0, 20.0, 30.0, 40.0);
+    assert_eq!(rect.center(), (25.0, 40.0));
+    assert!(rect.contains((10.0, 20.0)));
+    assert!(rect.contains((40.0, 60.0)));
+    assert!(!rect.contains((9.9, 30.0)));
+    assert!(!rect.contains((20.0, 60.1)));
+
+    assert_eq!(rect.intersection(&Rect::new(30.0, 50.0, 20.0, 20.0)), 100.0);
+    assert_eq!(rect.intersection(&rect), 1200.0);
+    // Touching and separate rects share nothing
+    assert_eq!(rect.intersection(&Rect::new(40.0, 20.0, 10.0, 10.0)), 0.0);
+    assert_eq!(rect.intersection(&Rect::new(100.0, 100.0, 10.0, 10.0)), 0.0);
+}
+
+#[test]
+fn pointer_within_picks_innermost_target() {
+    let targets = [
+        Rect::new(0.0, 0.0, 100.0, 100.0),
+        Rect::new(10.0, 10.0, 30.0, 30.0),
+        Rect::new(200.0, 0.0, 50.0, 50.0),
+    ];
+    let active = Rect::new(200.0, 0.0, 10.0, 10.0);
+    let detect = |pointer| detect(Collision::PointerWithin, pointer, &active, &targets);
+    assert_eq!(detect((20.0, 20.0)), Some(1));
+    assert_eq!(detect((80.0, 80.0)), Some(0));
+    assert_eq!(detect((220.0, 20.0)), Some(2));
+    assert_eq!(detect((150.0, 20.0)), None);
+}
+
+#[test]
+fn closest_center_ignores_pointer() {
+    let targets = grid();
+    let detect = |active: Rect| detect(Collision::ClosestCenter, (0.0, 0.0), &active, &targets);
+    assert_eq!(detect(Rect::new(18.0, 22.0, 10.0, 10.0)), Some(4));
+    assert_eq!(detect(Rect::new(100.0, 100.0, 10.0, 10.0)), Some(8));
+    assert_eq!(detect_without_targets(Collision::ClosestCenter), None);
+}
+
+#[test]
+fn rect_intersection_picks_largest_overlap() {
+    let targets = grid();
+    let detect = |active: Rect| detect(Collision::RectIntersection, (0.0, 0.0), &active, &targets);
+    // Overlaps 0 by 4x4 and 1 by 6x4
+    assert_eq!(detect(Rect::new(6.0, 6.0, 20.0, 4.0)), Some(1));
+    assert_eq!(detect(Rect::new(11.0, 11.0, 8.0, 8.0)), None);
+    assert_eq!(detect_without_targets(Collision::RectIntersection), None);
+}
+
+#[test]
+fn arrow_keys_move_to_closest_target_in_direction() {
+    let targets = grid();
+    let center = targets[4];
+    assert_eq!(closest_in_direction("ArrowUp", &center, &targets), Some(1));
+    assert_eq!(
+        closest_in_direction("ArrowDown", &center, &targets),
+        Some(7)
+    );
+    assert_eq!(
+        closest_in_direction("ArrowLeft", &center, &targets),
+        Some(3)
+    );
+    assert_eq!(
+        closest_in_direction("ArrowRight", &center, &targets),
+        Some(5)
+    );
+    assert_eq!(closest_in_direction("Enter", &center, &targets), None);
+
+    // Nothing is further in the direction at the edges
+    assert_eq!(
+        closest_in_direction("ArrowRight", &targets[2], &targets),
+        None
+    );
+    assert_eq!(closest_in_direction("ArrowUp", &targets[2], &targets), None);
+
+    // Aligned targets beat closer diagonal ones
+    let targets = [
+        Rect::new(30.0, 0.0, 10.0, 10.0),
+        Rect::new(15.0, 15.0, 10.0, 10.0),
+    ];
+    let from = Rect::new(0.0, 0.0, 10.0, 10.0);
+    assert_eq!(closest_in_direction("ArrowRight", &from, &targets), Some(0));
+    assert_eq!(closest_in_direction("ArrowDown", &from, &targets), Some(1));
+}
+
+#[test]
+fn reorder_moves_item() {
+    let reorder = |from: usize, to: usize| {
+        let mut items = ['a', 'b', 'c', 'd'];
+        Reorder {
+            id: items[from].to_string(),
+            from,
+            to,
+        }
+        .apply(&mut items);
+        items.iter().collect::<String>()
+    };
+    assert_eq!(reorder(0, 2), "bcad");
+    assert_eq!(reorder(3, 1), "adbc");
+    assert_eq!(reorder(0, 3), "bcda");
+    assert_eq!(reorder(2, 2), "abcd");
+}
+
+#[test]
+fn announcements_name_items() {
+    let (active, over) = ("card".to_string(), Some("done".to_string()));
+    let announce = |event| default_announcement(&event).unwrap();
+    assert_eq!(
+        announce(DndEvent::Start {
+            active: active.clone()
+        }),
+        "Picked up draggable item card."
+    );
+    assert_eq!(
+        announce(DndEvent::Over {
+            active: active.clone(),
+            over: over.clone()
+        }),
+        "Draggable item card was moved over droppable area done."
+    );
+    assert_eq!(
+        announce(DndEvent::Over {
+            active: active.clone(),
+            over: None
+        }),
+        "Draggable item card is no longer over a droppable area."
+    );
+    assert_eq!(
+        announce(DndEvent::End {
+            active: active.clone(),
+            over
+        }),
+        "Draggable item card was dropped over droppable area done."
+    );
+    assert_eq!(
+        announce(DndEvent::End {
+            active: active.clone(),
+            over: None
+        }),
+        "Draggable item card was dropped."
+    );
+    let cancel = DndEvent::Cancel { active };
+    assert_eq!(cancel.active(), "card");
+    assert_eq!(
+        announce(cancel),
+        "Dragging was cancelled. Draggable item card was dropped."
+    );
+}
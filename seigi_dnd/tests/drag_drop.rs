@@ -0,0 +1,231 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use seigi_dnd::{DndEvent, DragDrop, DragDropOptions, collision::Collision};
+use seigi_testkit::{
+    Fixture, assert_focused,
+    events::{self, Modifiers},
+};
+use wasm_bindgen_test::*;
+use web_sys::HtmlElement;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<div id="item" style="position: fixed; left: 0; top: 0; width: 50px; height: 50px">
+    <span id="handle" data-seigi-drag-handle>Handle</span>
+</div>
+<div id="first" style="position: fixed; left: 0; top: 200px; width: 100px; height: 100px"></div>
+<div id="second" style="position: fixed; left: 200px; top: 200px; width: 100px; height: 100px"></div>
+"#;
+
+fn over(id: &str) -> Option<String> {
+    Some(id.to_string())
+}
+
+fn create(fixture: &Fixture) -> (DragDrop, Rc<RefCell<Vec<DndEvent>>>) {
+    let dnd = seigi_dnd::create_drag_drop(
+        DragDropOptions::builder()
+            .collision(Collision::PointerWithin)
+            .auto_scroll(false)
+            .build(),
+    );
+    dnd.add_draggable("item", fixture.get("#item"));
+    dnd.add_droppable("first", fixture.get("#first"));
+    dnd.add_droppable("second", fixture.get("#second"));
+
+    let events = Rc::new(RefCell::new(vec![]));
+    dnd.subscribe(Box::new({
+        let events = events.clone();
+        move |event| events.borrow_mut().push(event.clone())
+    }));
+    (dnd, events)
+}
+
+fn pointer(target: &HtmlElement, event_type: &str, x: f64, y: f64) -> bool {
+    events::dispatch(target, &events::pointer_event(event_type, x, y))
+}
+
+#[wasm_bindgen_test]
+fn draggable_is_described_by_instructions() {
+    let fixture = Fixture::new(MARKUP);
+    let (dnd, _) = create(&fixture);
+    let item = fixture.get("#item");
+
+    assert!(item.has_attribute("data-seigi-draggable"));
+    assert_eq!(
+        item.get_attribute("aria-roledescription").as_deref(),
+        Some("draggable")
+    );
+    assert_eq!(item.get_attribute("role").as_deref(), Some("button"));
+    assert_eq!(item.tab_index(), 0);
+    let instructions = item.get_attribute("aria-describedby").unwrap();
+    let instructions = gloo::utils::document()
+        .get_element_by_id(&instructions)
+        .unwrap();
+    assert!(
+        instructions
+            .text_content()
+            .unwrap()
+            .starts_with("To pick up a draggable item")
+    );
+    assert!(fixture.get("#first").has_attribute("data-seigi-droppable"));
+
+    dnd.remove_draggable("item");
+    dnd.remove_droppable("first");
+    assert!(!item.has_attribute("data-seigi-draggable"));
+    assert!(!item.has_attribute("aria-describedby"));
+    assert!(!fixture.get("#first").has_attribute("data-seigi-droppable"));
+}
+
+#[wasm_bindgen_test]
+fn pointer_drags_item_over_targets() {
+    let fixture = Fixture::new(MARKUP);
+    let (dnd, events) = create(&fixture);
+    let (handle, first) = (fixture.get("#handle"), fixture.get("#first"));
+    let document = gloo::utils::document();
+
+    pointer(&handle, "pointerdown", 10.0, 10.0);
+    // Moves shorter than the activation distance are clicks
+    pointer(&document.body().unwrap(), "pointermove", 12.0, 10.0);
+    assert_eq!(dnd.active(), None);
+    assert!(events.borrow().is_empty());
+
+    pointer(&first, "pointermove", 50.0, 250.0);
+    assert_eq!(dnd.active(), over("item"));
+    let item = fixture.get("#item");
+    assert!(item.has_attribute("data-dragging"));
+    assert_eq!(
+        item.style().get_property_value("--seigi-drag-x").unwrap(),
+        "40px"
+    );
+    assert_eq!(
+        item.style().get_property_value("--seigi-drag-y").unwrap(),
+        "240px"
+    );
+    assert!(first.has_attribute("data-over"));
+
+    pointer(&first, "pointermove", 250.0, 250.0);
+    assert!(!first.has_attribute("data-over"));
+    assert!(fixture.get("#second").has_attribute("data-over"));
+
+    pointer(&first, "pointerup", 250.0, 250.0);
+    assert_eq!(dnd.active(), None);
+    assert!(!item.has_attribute("data-dragging"));
+    assert_eq!(
+        item.style().get_property_value("--seigi-drag-x").unwrap(),
+        ""
+    );
+    assert!(!fixture.get("#second").has_attribute("data-over"));
+    assert_eq!(
+        *events.borrow(),
+        [
+            DndEvent::Start {
+                active: "item".into()
+            },
+            DndEvent::Over {
+                active: "item".into(),
+                over: over("first")
+            },
+            DndEvent::Over {
+                active: "item".into(),
+                over: over("second")
+            },
+            DndEvent::End {
+                active: "item".into(),
+                over: over("second")
+            },
+        ]
+    );
+}
+
+#[wasm_bindgen_test]
+fn press_outside_handle_and_click_dont_drag() {
+    let fixture = Fixture::new(MARKUP);
+    let (dnd, events) = create(&fixture);
+    let (item, handle) = (fixture.get("#item"), fixture.get("#handle"));
+
+    pointer(&item, "pointerdown", 40.0, 40.0);
+    pointer(&item, "pointermove", 50.0, 250.0);
+    pointer(&item, "pointerup", 50.0, 250.0);
+
+    pointer(&handle, "pointerdown", 10.0, 10.0);
+    pointer(&handle, "pointerup", 10.0, 10.0);
+    assert_eq!(dnd.active(), None);
+    assert!(events.borrow().is_empty());
+}
+
+#[wasm_bindgen_test]
+fn escape_cancels_pointer_drag() {
+    let fixture = Fixture::new(MARKUP);
+    let (dnd, events) = create(&fixture);
+    let handle = fixture.get("#handle");
+
+    pointer(&handle, "pointerdown", 10.0, 10.0);
+    pointer(&handle, "pointermove", 50.0, 250.0);
+    assert!(!events::key_down(&handle, "Escape", Modifiers::default()));
+    assert_eq!(dnd.active(), None);
+    assert!(!fixture.get("#first").has_attribute("data-over"));
+    assert_eq!(
+        events.borrow().last(),
+        Some(&DndEvent::Cancel {
+            active: "item".into()
+        })
+    );
+
+    // The pointer released after cancelling drops nothing
+    pointer(&handle, "pointerup", 50.0, 250.0);
+    assert_eq!(events.borrow().len(), 3);
+}
+
+#[wasm_bindgen_test]
+fn keyboard_moves_item_between_targets() {
+    let fixture = Fixture::new(MARKUP);
+    let (dnd, events) = create(&fixture);
+    let item = fixture.get("#item");
+    let _ = item.focus();
+
+    assert!(!events::key_down(&item, " ", Modifiers::default()));
+    assert_eq!(dnd.active(), over("item"));
+    assert!(item.has_attribute("data-dragging"));
+    // Not over a target, as none contains the item
+    assert_eq!(events.borrow().len(), 1);
+
+    assert!(!events::key_down(&item, "ArrowDown", Modifiers::default()));
+    assert!(fixture.get("#first").has_attribute("data-over"));
+    assert!(!events::key_down(&item, "ArrowRight", Modifiers::default()));
+    assert!(fixture.get("#second").has_attribute("data-over"));
+    // Focus stays on the item
+    assert!(!events::key_down(&item, "Tab", Modifiers::default()));
+
+    assert!(!events::key_down(&item, "Enter", Modifiers::default()));
+    assert_eq!(dnd.active(), None);
+    assert_focused(&item);
+    assert_eq!(
+        events.borrow().last(),
+        Some(&DndEvent::End {
+            active: "item".into(),
+            over: over("second")
+        })
+    );
+}
+
+#[wasm_bindgen_test]
+fn removing_dragged_item_cancels() {
+    let fixture = Fixture::new(MARKUP);
+    let (dnd, events) = create(&fixture);
+    let item = fixture.get("#item");
+
+    events::key_down(&item, "Enter", Modifiers::default());
+    assert_eq!(dnd.active(), over("item"));
+    dnd.remove_draggable("item");
+    assert_eq!(dnd.active(), None);
+    assert!(!item.has_attribute("data-dragging"));
+    assert_eq!(
+        events.borrow().last(),
+        Some(&DndEvent::Cancel {
+            active: "item".into()
+        })
+    );
+}
@@ -0,0 +1,121 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use seigi_dnd::sortable::{self, Reorder, Sortable, SortableHooks, SortableOptions};
+use seigi_testkit::{
+    Fixture,
+    events::{self, Modifiers},
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<ul id="list">
+    <li data-seigi-sortable-item="a">A</li>
+    <li data-seigi-sortable-item="b">B</li>
+    <li data-seigi-sortable-item="c">C</li>
+</ul>
+"#;
+
+fn create(fixture: &Fixture, move_elements: bool) -> (Sortable, Rc<RefCell<Vec<Reorder>>>) {
+    let reorders = Rc::new(RefCell::new(vec![]));
+    let sortable = sortable::create_sortable(
+        SortableOptions::builder()
+            .container(fixture.get("#list"))
+            .move_elements(move_elements)
+            .hooks(SortableHooks {
+                reorder: Some(Box::new({
+                    let reorders = reorders.clone();
+                    move |reorder| reorders.borrow_mut().push(reorder.clone())
+                })),
+            })
+            .build(),
+    );
+    (sortable, reorders)
+}
+
+fn press(fixture: &Fixture, id: &str, key: &str) {
+    let item = fixture.get(&format!("[data-seigi-sortable-item={id}]"));
+    events::key_down(&item, key, Modifiers::default());
+}
+
+#[wasm_bindgen_test]
+fn keyboard_reorders_items() {
+    let fixture = Fixture::new(MARKUP);
+    let (sortable, reorders) = create(&fixture, true);
+    assert!(fixture.get("#list").has_attribute("data-seigi-sortable"));
+    let [_, b, c] = fixture.get_all("li").try_into().unwrap();
+
+    press(&fixture, "a", " ");
+    press(&fixture, "a", "ArrowDown");
+    assert!(b.has_attribute("data-sortable-after"));
+    press(&fixture, "a", "ArrowDown");
+    assert!(!b.has_attribute("data-sortable-after"));
+    assert!(c.has_attribute("data-sortable-after"));
+
+    press(&fixture, "a", "Enter");
+    assert!(!c.has_attribute("data-sortable-after"));
+    assert_eq!(sortable.ids(), ["b", "c", "a"]);
+    assert_eq!(
+        *reorders.borrow(),
+        [Reorder {
+            id: "a".into(),
+            from: 0,
+            to: 2
+        }]
+    );
+
+    press(&fixture, "a", " ");
+    press(&fixture, "a", "ArrowUp");
+    assert!(c.has_attribute("data-sortable-before"));
+    press(&fixture, "a", "Enter");
+    assert_eq!(sortable.ids(), ["b", "a", "c"]);
+}
+
+#[wasm_bindgen_test]
+fn dropping_in_place_or_cancelling_keeps_order() {
+    let fixture = Fixture::new(MARKUP);
+    let (sortable, reorders) = create(&fixture, false);
+
+    press(&fixture, "b", " ");
+    press(&fixture, "b", "Enter");
+    press(&fixture, "b", " ");
+    press(&fixture, "b", "ArrowDown");
+    press(&fixture, "b", "Escape");
+    assert!(fixture.get_all("[data-sortable-after]").is_empty());
+    assert!(reorders.borrow().is_empty());
+
+    // Without moving elements, only the hook reorders
+    press(&fixture, "b", " ");
+    press(&fixture, "b", "ArrowUp");
+    press(&fixture, "b", "Enter");
+    assert_eq!(sortable.ids(), ["a", "b", "c"]);
+    assert_eq!(
+        *reorders.borrow(),
+        [Reorder {
+            id: "b".into(),
+            from: 1,
+            to: 0
+        }]
+    );
+}
+
+#[wasm_bindgen_test]
+fn refresh_follows_items() {
+    let fixture = Fixture::new(MARKUP);
+    let (sortable, _) = create(&fixture, true);
+    let list = fixture.get("#list");
+    let [_, b, _] = fixture.get_all("li").try_into().unwrap();
+
+    b.remove();
+    list.insert_adjacent_html("beforeend", r#"<li data-seigi-sortable-item="d">D</li>"#)
+        .unwrap();
+    sortable.refresh();
+    assert_eq!(sortable.ids(), ["a", "c", "d"]);
+    assert!(!b.has_attribute("data-seigi-draggable"));
+    let d = fixture.get("[data-seigi-sortable-item=d]");
+    assert!(d.has_attribute("data-seigi-draggable"));
+    assert!(d.has_attribute("data-seigi-droppable"));
+}
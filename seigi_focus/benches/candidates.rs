@@ -0,0 +1,102 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::time::Duration;
+
+use seigi_focus::{
+    FocusTrapOptions,
+    candidates::{self, CandidateCache},
+};
+use seigi_testkit::{
+    Fixture,
+    events::{self, Modifiers},
+    perf, wait,
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Tab candidates of the large document, about what a long settings page has
+const CANDIDATES: usize = 5_000;
+
+#[wasm_bindgen_bench]
+fn tab_candidates(c: &mut Criterion) {
+    let fixture = Fixture::new(&perf::large_document(CANDIDATES));
+    let root = fixture.root();
+
+    c.bench_function("tab_candidates", |b| {
+        b.iter(|| candidates::tab_candidates(&root))
+    });
+    perf::assert_budget(
+        "tab_candidates",
+        Duration::from_millis(10),
+        perf::median(11, || drop(candidates::tab_candidates(&root))),
+    );
+}
+
+#[wasm_bindgen_bench]
+fn first_focus_candidate(c: &mut Criterion) {
+    let fixture = Fixture::new(&perf::large_document(CANDIDATES));
+    let root = fixture.root();
+
+    c.bench_function("first_focus_candidate", |b| {
+        b.iter(|| candidates::first_focus_candidate(&root))
+    });
+    perf::assert_budget(
+        "first_focus_candidate",
+        Duration::from_micros(200),
+        perf::median(11, || drop(candidates::first_focus_candidate(&root))),
+    );
+}
+
+#[wasm_bindgen_bench]
+fn cached_tab_candidates(c: &mut Criterion) {
+    let fixture = Fixture::new(&perf::large_document(CANDIDATES));
+    let cache = CandidateCache::new(fixture.root().into());
+
+    c.bench_function("cached tab_candidates", |b| {
+        b.iter(|| cache.tab_candidates())
+    });
+    perf::assert_budget(
+        "cached tab_candidates",
+        Duration::from_micros(50),
+        perf::median(11, || drop(cache.tab_candidates())),
+    );
+}
+
+#[wasm_bindgen_bench]
+async fn trap_tab(c: &mut Criterion) {
+    let fixture = Fixture::new(&perf::large_document(CANDIDATES));
+    let trap = seigi_focus::create(
+        FocusTrapOptions::builder()
+            .target(fixture.get("#row-0"))
+            .build(),
+    );
+    trap.activate();
+    wait::tick().await;
+
+    // Tab within the trap is left to the browser, while Tab from the last candidate wraps around
+    let inner = fixture.get("#row-0 button");
+    let last = fixture.get("#row-0 [tabindex='0']");
+    c.bench_function("Tab within trap", |b| {
+        b.iter(|| events::key_down(&inner, "Tab", Modifiers::default()))
+    });
+    c.bench_function("Tab wrapping around trap", |b| {
+        b.iter(|| events::key_down(&last, "Tab", Modifiers::default()))
+    });
+    perf::assert_budget(
+        "Tab within trap",
+        Duration::from_micros(200),
+        perf::median(11, || {
+            events::key_down(&inner, "Tab", Modifiers::default());
+        }),
+    );
+    perf::assert_budget(
+        "Tab wrapping around trap",
+        Duration::from_millis(16),
+        perf::median(11, || {
+            events::key_down(&last, "Tab", Modifiers::default());
+        }),
+    );
+
+    trap.deactivate();
+}
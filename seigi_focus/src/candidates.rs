@@ -1,17 +1,48 @@
-use wasm_bindgen::JsCast;
-use web_sys::{Element, HtmlElement};
-
-const CANDIDATE_SELECTOR: &str = "input:not([inert]),\
-    select:not([inert]),\
-    textarea:not([inert]),\
-    a[href]:not([inert]),\
-    button:not([inert]),\
-    [tabindex]:not(slot):not([inert]),\
-    audio[controls]:not([inert]),\
-    video[controls]:not([inert]),\
-    [contenteditable]:not([contenteditable=\"false\"]):not([inert]),\
-    details>summary:first-of-type:not([inert]),\
-    details:not([inert])";
+//! Focusable and tabbable element queries
+//!
+//! Queries leave inert subtrees and hidden inputs out in the selector, so the browser skips them
+//! natively rather than each candidate being checked. [CandidateCache] keeps the tab candidates
+//! of a container until its subtree changes, for widgets querying them on every key press.
+//!
+//! Containers with an open shadow root, like a custom element rendering its children into slots,
+//! are walked in the flattened tree instead, which is the order the browser tabs through them in.
+//! Their shadow trees are included, and light DOM children are included where they are assigned
+//! to slots, once each.
+
+use std::{cell::RefCell, rc::Rc};
+
+use js_sys::Array;
+use seigi_utils::diagnostics::{self, Resource, Tracked};
+use wasm_bindgen::{JsCast, prelude::Closure};
+use web_sys::{
+    AssignedNodesOptions, Element, HtmlCollection, HtmlElement, HtmlSlotElement, MutationObserver,
+    MutationObserverInit,
+};
+
+const CANDIDATE_SELECTOR: &str = "input:not([type=hidden]):not([inert], [inert] *),\
+    select:not([inert], [inert] *),\
+    textarea:not([inert], [inert] *),\
+    a[href]:not([inert], [inert] *),\
+    button:not([inert], [inert] *),\
+    [tabindex]:not(slot):not([inert], [inert] *),\
+    audio[controls]:not([inert], [inert] *),\
+    video[controls]:not([inert], [inert] *),\
+    [contenteditable]:not([contenteditable=\"false\"]):not([inert], [inert] *),\
+    details>summary:first-of-type:not([inert], [inert] *),\
+    details:not([inert], [inert] *)";
+
+/// Attributes deciding whether elements are candidates, which invalidate [CandidateCache]
+const CANDIDATE_ATTRIBUTES: [&str; 9] = [
+    "disabled",
+    "tabindex",
+    "inert",
+    "href",
+    "contenteditable",
+    "controls",
+    "type",
+    "open",
+    "slot",
+];
 
 fn is_disabled(element: &Element) -> bool {
     if let Some(disabled) = element.get_attribute("disabled") {
@@ -30,7 +61,7 @@ fn is_inert(element: &Element) -> bool {
 }
 
 fn is_hidden_input(element: &Element) -> bool {
-    if element.tag_name() == "input"
+    if element.tag_name().eq_ignore_ascii_case("input")
         && let Some(t) = element.get_attribute("type")
         && t == "hidden"
     {
@@ -40,6 +71,7 @@ fn is_hidden_input(element: &Element) -> bool {
     false
 }
 
+/// Returns true if the element can receive focus programmatically
 pub fn is_focusable(element: &HtmlElement) -> bool {
     if is_disabled(element) || is_inert(element) || is_hidden_input(element) {
         return false;
@@ -48,6 +80,7 @@ pub fn is_focusable(element: &HtmlElement) -> bool {
     true
 }
 
+/// Returns true if the element participates in sequential(tab) navigation
 pub fn is_tabbable(element: &HtmlElement) -> bool {
     if element.tab_index() < 0 || !is_focusable(element) {
         return false;
@@ -55,68 +88,210 @@ pub fn is_tabbable(element: &HtmlElement) -> bool {
 
     true
 }
+
+/// Returns true if the element matched by the candidate query participates in sequential(tab)
+/// navigation, without checking what the query has ruled out already
+fn is_tabbable_candidate(element: &HtmlElement) -> bool {
+    element.tab_index() >= 0 && !is_disabled(element)
+}
+
+/// Returns true if the element matched by the candidate query can receive focus
+/// programmatically, without checking what the query has ruled out already
+fn is_focusable_candidate(element: &HtmlElement) -> bool {
+    !is_disabled(element)
+}
+
+/// Collects focus candidates inside the container that pass the filter, in document order
+///
+/// Elements in inert subtrees, including the container being in one, and hidden inputs are never
+/// collected. Containers with an open shadow root are collected in the flattened tree order.
 pub fn candidates(container: &Element, filter: impl Fn(&HtmlElement) -> bool) -> Vec<HtmlElement> {
+    if container.shadow_root().is_some() {
+        if is_inert(container) {
+            return vec![];
+        }
+        let mut candidates = vec![];
+        collect_flattened(container, &filter, &mut candidates);
+        return candidates;
+    }
+
     let Ok(elements) = container.query_selector_all(CANDIDATE_SELECTOR) else {
         return vec![];
     };
 
-    let mut candidates = Vec::with_capacity(elements.length() as usize);
-    for element in elements.values() {
-        let Ok(element) = element else {
-            continue;
-        };
-
-        let Ok(element) = element.dyn_into::<HtmlElement>() else {
-            continue;
-        };
+    // Indexing the list is cheaper than its iterator, which allocates an object per element
+    (0..elements.length())
+        .filter_map(|index| crate::html_element(elements.get(index)?.into()))
+        .filter(|element| filter(element))
+        .collect()
+}
 
-        if !filter(&element) {
+/// Collects candidates among descendants of the element in the flattened tree, where shadow
+/// hosts are replaced by their shadow trees and slots by the elements assigned to them
+fn collect_flattened(
+    element: &Element,
+    filter: &impl Fn(&HtmlElement) -> bool,
+    candidates: &mut Vec<HtmlElement>,
+) {
+    for child in flattened_children(element) {
+        if child.has_attribute("inert") {
             continue;
         }
+        if child.matches(CANDIDATE_SELECTOR).unwrap_or(false)
+            && let Some(child) = crate::html_element(child.clone().into())
+            && filter(&child)
+        {
+            candidates.push(child);
+        }
+        collect_flattened(&child, filter, candidates);
+    }
+}
 
-        candidates.push(element);
+/// Element children of the element in the flattened tree
+fn flattened_children(element: &Element) -> Vec<Element> {
+    if let Some(root) = element.shadow_root() {
+        return elements(&root.children());
     }
+    if let Some(slot) = element.dyn_ref::<HtmlSlotElement>() {
+        // Elements assigned to slots of nested shadow trees, or fallback content if none is
+        let options = AssignedNodesOptions::new();
+        options.set_flatten(true);
+        let assigned = slot.assigned_nodes_with_options(&options);
+        if assigned.length() > 0 {
+            return assigned
+                .iter()
+                .filter_map(|v| v.dyn_into::<Element>().ok())
+                .collect();
+        }
+    }
+    elements(&element.children())
+}
 
-    candidates
+fn elements(collection: &HtmlCollection) -> Vec<Element> {
+    (0..collection.length())
+        .filter_map(|index| collection.item(index))
+        .collect()
 }
 
 fn first_candidate(
     container: &Element,
     filter: impl Fn(&HtmlElement) -> bool,
 ) -> Option<HtmlElement> {
-    let Ok(elements) = container.query_selector_all(CANDIDATE_SELECTOR) else {
-        return None;
-    };
-
-    for element in elements.values() {
-        let Ok(element) = element else {
-            continue;
-        };
-
-        let Ok(element) = element.dyn_into::<HtmlElement>() else {
-            continue;
-        };
-
-        if filter(&element) {
-            return Some(element);
-        }
+    if container.shadow_root().is_some() {
+        return candidates(container, filter).into_iter().next();
+    }
+    // The first match usually passes, which the browser finds without matching the rest
+    let first = container
+        .query_selector(CANDIDATE_SELECTOR)
+        .ok()
+        .flatten()
+        .and_then(|v| crate::html_element(v.into()));
+    if let Some(first) = first.filter(|v| filter(v)) {
+        return Some(first);
     }
 
-    None
+    candidates(container, filter).into_iter().next()
 }
 
+/// Collects tabbable elements inside the container
 pub fn tab_candidates(container: &Element) -> Vec<HtmlElement> {
-    candidates(container, is_tabbable)
+    candidates(container, is_tabbable_candidate)
 }
 
+/// Collects focusable elements inside the container
 pub fn focus_candidates(container: &Element) -> Vec<HtmlElement> {
-    candidates(container, is_focusable)
+    candidates(container, is_focusable_candidate)
 }
 
+/// Finds the first tabbable element inside the container
 pub fn first_tab_candidate(container: &Element) -> Option<HtmlElement> {
-    first_candidate(container, is_tabbable)
+    first_candidate(container, is_tabbable_candidate)
 }
 
+/// Finds the first focusable element inside the container
 pub fn first_focus_candidate(container: &Element) -> Option<HtmlElement> {
-    first_candidate(container, is_focusable)
+    first_candidate(container, is_focusable_candidate)
+}
+
+/// Tab candidates of a container, kept until the subtree of the container changes
+///
+/// Changes of children and of attributes deciding candidates, like `disabled` and `tabindex`,
+/// clear the candidates, which are collected again when asked next. The shadow tree of the
+/// container is observed along with it, but changes outside the container, like an ancestor
+/// becoming inert, and inside nested shadow trees aren't seen.
+pub struct CandidateCache {
+    container: Element,
+    candidates: Rc<RefCell<Option<Rc<[HtmlElement]>>>>,
+    /// Absent if the browser failed to create it, in which case candidates aren't kept
+    observer: Option<MutationObserver>,
+    _callback: Closure<dyn Fn(Array)>,
+    /// Counts the observer in diagnostics while alive
+    _tracked: Tracked,
+}
+
+impl CandidateCache {
+    /// Starts observing the container, which stops when dropped
+    pub fn new(container: Element) -> Self {
+        let candidates = Rc::new(RefCell::new(None));
+        let callback = Closure::<dyn Fn(Array)>::new({
+            let candidates = candidates.clone();
+            move |_| {
+                candidates.replace(None);
+            }
+        });
+        let observer = MutationObserver::new(callback.as_ref().unchecked_ref())
+            .ok()
+            .filter(|observer| {
+                let init = MutationObserverInit::new();
+                init.set_child_list(true);
+                init.set_subtree(true);
+                init.set_attributes(true);
+                init.set_attribute_filter(
+                    &CANDIDATE_ATTRIBUTES
+                        .iter()
+                        .map(|v| wasm_bindgen::JsValue::from_str(v))
+                        .collect::<Array>(),
+                );
+                observer.observe_with_options(&container, &init).is_ok()
+                    && container
+                        .shadow_root()
+                        .is_none_or(|root| observer.observe_with_options(&root, &init).is_ok())
+            });
+
+        Self {
+            container,
+            candidates,
+            observer,
+            _callback: callback,
+            _tracked: diagnostics::track(Resource::Observer),
+        }
+    }
+
+    /// Returns the tabbable elements inside the container, like [tab_candidates]
+    pub fn tab_candidates(&self) -> Rc<[HtmlElement]> {
+        let Some(observer) = &self.observer else {
+            return tab_candidates(&self.container).into();
+        };
+
+        // Mutations are delivered to the callback in a microtask, so ones made earlier in the
+        // same task are taken here
+        if observer.take_records().length() > 0 {
+            self.candidates.replace(None);
+        }
+        if let Some(candidates) = self.candidates.borrow().as_ref() {
+            return candidates.clone();
+        }
+
+        let candidates: Rc<[HtmlElement]> = tab_candidates(&self.container).into();
+        self.candidates.replace(Some(candidates.clone()));
+        candidates
+    }
+}
+
+impl Drop for CandidateCache {
+    fn drop(&mut self) {
+        if let Some(observer) = &self.observer {
+            observer.disconnect();
+        }
+    }
 }
@@ -1,26 +1,75 @@
 //! Focus management with accessibility
 
-mod candidates;
+pub mod candidates;
+#[cfg(feature = "overlay")]
+pub mod overlay;
+pub mod recorder;
 
 use std::{
+    cell::Cell,
     rc::{Rc, Weak},
-    sync::{Mutex, MutexGuard},
+    sync::{
+        Mutex, MutexGuard,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
 };
 
-use gloo::{
-    timers::callback::Timeout,
-    utils::{body, document},
+use gloo::{timers::callback::Timeout, utils::document};
+use js_sys::Reflect;
+use seigi_utils::{
+    config,
+    diagnostics::{self, Resource},
+    id, ime,
+    intercept::Interceptor,
+    keyboard,
+    listener::{Listener, ListenerOptions},
+    outside::{self, HiddenOthers},
+    refs::Refs,
+    schedule::{self, ScheduledFocus},
+    scroll_lock::{self, ScrollLock},
 };
-use js_sys::Function;
-use wasm_bindgen::{JsCast, prelude::Closure};
-use web_sys::{AddEventListenerOptions, Event, FocusEvent, HtmlElement, KeyboardEvent, MouseEvent};
-
-macro_rules! callback {
-    ($state: ident, $closure: expr) => {{
-        let $state = $state.clone();
-        let _closure: Closure<dyn FnMut(&Event)> = Closure::new($closure);
-        Callback(_closure)
-    }};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{
+    Document, Event, FocusEvent, HtmlElement, KeyboardEvent, MouseEvent, Node,
+    ScrollIntoViewOptions, ScrollLogicalPosition,
+};
+
+pub use seigi_utils::intercept::EventOutcome;
+
+use crate::{
+    candidates::CandidateCache,
+    recorder::{Decision, Input, Recording},
+};
+
+/// Set in the target of a trap while the trap is activated
+pub const ACTIVE_ATTRIBUTE: &str = "data-seigi-trap-active";
+
+/// Set in the target of a trap to the id of the trap, once activated
+pub const ID_ATTRIBUTE: &str = "data-seigi-trap-id";
+
+/// Set in the target of a trap for [FocusTrapOptions::rejected_feedback] after a pointer press
+/// outside is blocked
+pub const REJECTED_ATTRIBUTE: &str = "data-seigi-trap-rejected";
+
+/// Set in tab candidates of activated traps to their position in tab order, while debugging with
+/// [set_debugging]
+pub const TAB_INDEX_ATTRIBUTE: &str = "data-seigi-tab-index";
+
+thread_local! {
+    static DEBUGGING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Sets whether activated traps mark their tab candidates with [TAB_INDEX_ATTRIBUTE], so the
+/// tab order can be inspected and asserted by end-to-end tests
+///
+/// Traps follow it from their next activation or focus move.
+pub fn set_debugging(debugging: bool) {
+    DEBUGGING.set(debugging);
+}
+
+pub fn is_debugging() -> bool {
+    DEBUGGING.get()
 }
 
 /// Runs given closure with acquired guard of Weak<Mutex<T>> and return the return of closure
@@ -35,41 +84,36 @@ fn acquired<T, R>(weak: &Weak<Mutex<T>>, mut f: impl FnMut(MutexGuard<'_, T>) ->
     f(state)
 }
 
-/// Gets document.activeElement
-fn active_element() -> Option<HtmlElement> {
-    document()
-        .active_element()
-        .and_then(|v| v.dyn_into::<HtmlElement>().ok())
+/// Casts the value into HtmlElement if it is an HTML element of any document
+///
+/// `dyn_into` checks against prototypes of the current window, which elements of iframes and
+/// popups don't share.
+pub fn html_element(value: JsValue) -> Option<HtmlElement> {
+    if value.is_instance_of::<HtmlElement>() {
+        return Some(value.unchecked_into());
+    }
+    let namespace = Reflect::get(&value, &JsValue::from_str("namespaceURI"))
+        .ok()?
+        .as_string()?;
+    (namespace == "http://www.w3.org/1999/xhtml").then(|| value.unchecked_into())
 }
 
-/// Sets immediate timeout(0ms) for focusing given element
-fn schedule_focus(target: HtmlElement) {
-    Timeout::new(0, move || {
-        let _ = target.focus();
-    })
-    .forget();
+/// Gets document.activeElement
+fn active_element(document: &Document) -> Option<HtmlElement> {
+    document
+        .active_element()
+        .and_then(|v| html_element(v.into()))
 }
 
 /// Gets the target of Event as HtmlElement
 fn target(event: &Event) -> Option<HtmlElement> {
-    event
-        .target()
-        .and_then(|v| v.dyn_into::<HtmlElement>().ok())
+    event.target().and_then(|v| html_element(v.into()))
 }
 
-struct Callback(Closure<dyn FnMut(&Event)>);
-
-impl Callback {
-    fn as_function(&self) -> &Function {
-        self.0.as_ref().unchecked_ref()
-    }
-}
-
-struct Callbacks {
-    focus_in: Callback,
-    pointer_down: Callback,
-    click: Callback,
-    key_down: Callback,
+/// Gets the element Event was dispatched to, inside open shadow trees the target is retargeted
+/// out of
+fn deep_target(event: &Event) -> Option<HtmlElement> {
+    html_element(event.composed_path().get(0)).or_else(|| target(event))
 }
 
 /// The method trap will use to decide the initial focus element
@@ -80,6 +124,10 @@ pub enum InitialFocus {
     /// The trap focuses the first focusable element inside the trap
     #[default]
     Auto,
+    /// The trap focuses the element focused last in the trap while activated before, like a
+    /// field of a stage navigated back to, or the first focusable element as [InitialFocus::Auto]
+    /// if there is none or it can't be focused anymore
+    Last,
     /// The trap focuses the first element that matches given selector inside the trap
     Selector(String),
     /// The trap focuses given element
@@ -88,6 +136,35 @@ pub enum InitialFocus {
     Function(Box<dyn Fn() -> HtmlElement>),
 }
 
+/// How the user tried to interact outside an activated trap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InteractionKind {
+    /// A press of a mouse, pen or touch
+    Pointer,
+    /// A focus move
+    Focus,
+    /// A key press moving focus, like Tab in a trap without tab candidates
+    Key,
+}
+
+/// An event of [FocusTrap]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FocusTrapEvent {
+    /// The trap blocked an interaction outside its target, so apps can hint the user to finish
+    /// it first instead of the interaction silently doing nothing
+    ///
+    /// The target is the element pressed or focused, or the focused element for key presses.
+    OutsideInteraction {
+        kind: InteractionKind,
+        target: HtmlElement,
+    },
+}
+
+struct Subscriber {
+    callback: Box<dyn Fn(&FocusTrapEvent)>,
+    handle: u64,
+}
+
 /// Hooks to [FocusTrap]
 #[derive(Default)]
 pub struct FocusTrapHooks {
@@ -97,21 +174,54 @@ pub struct FocusTrapHooks {
     pub deactivate: Option<Box<dyn Fn()>>,
 }
 
+/// What an activated trap does when the page navigates back or forward through history
+#[derive(Default)]
+pub enum NavigationPolicy {
+    /// The trap stays activated, for traps living across routes
+    Ignore,
+    /// The trap deactivates, as the next route likely replaces its scope
+    #[default]
+    Deactivate,
+    /// The guard decides whether the navigation goes on, which deactivates the trap
+    ///
+    /// Blocked navigations are cancelled where the Navigation API can, and otherwise the URL
+    /// changes while the trap stays activated, leaving the route to the guard to restore.
+    Guard(Box<dyn Fn() -> bool>),
+}
+
 /// Options of [FocusTrap]
 pub struct FocusTrapOptions {
     /// Whether trap should return focus to the last focused element before trap activation
     pub return_focus: bool,
     pub initial_focus: InitialFocus,
-    /// Whether trap should deactivate when user press esc
+    /// Whether trap should deactivate when user press esc, defaulting to
+    /// [seigi_utils::config::GlobalConfig::deactivate_on_escape]
     pub deactivate_on_escape: bool,
     /// The hooks
     pub hooks: FocusTrapHooks,
+    /// What the trap does on navigations through history, like the back button
+    pub navigation: NavigationPolicy,
+    /// Called with `focusin`, `mousedown`, `click` and `keydown` events before the trap handles
+    /// them, which it leaves alone once consumed
+    pub on_event: Option<Interceptor>,
+    /// How long [REJECTED_ATTRIBUTE] stays in the target after a pointer press outside is
+    /// blocked, so styles can pulse it, or None to never set it
+    pub rejected_feedback: Option<Duration>,
+    /// Whether the rest of the page is hidden from assistive technologies while activated, as
+    /// in [seigi_utils::outside::hide_others]
+    pub hide_others: bool,
+    /// Whether the document can't be scrolled while activated, sharing the counted lock of
+    /// [seigi_utils::scroll_lock] with other modal layers. The target still scrolls by touch
+    pub scroll_lock: bool,
     /// The scope trap is affected.
     ///
     /// Elements outside the scope are not affected by the trap
     pub scope: HtmlElement,
     /// The element focus trap is attached to
     pub target: HtmlElement,
+    /// The document focus is managed in, which is the document of the target by default, like
+    /// one of a same-origin iframe or a popup opened by `window.open`
+    pub document: Document,
 }
 
 impl FocusTrapOptions {
@@ -120,14 +230,26 @@ impl FocusTrapOptions {
     }
 }
 
+impl From<FocusTrapOptionsBuilder> for FocusTrapOptions {
+    fn from(value: FocusTrapOptionsBuilder) -> Self {
+        value.build()
+    }
+}
+
 /// A builder struct of [FocusTrapOptions]
 pub struct FocusTrapOptionsBuilder {
     return_focus: bool,
     initial_focus: InitialFocus,
     deactivate_on_escape: bool,
     hooks: FocusTrapHooks,
-    scope: HtmlElement,
+    navigation: NavigationPolicy,
+    on_event: Option<Interceptor>,
+    rejected_feedback: Option<Duration>,
+    hide_others: bool,
+    scroll_lock: bool,
+    scope: Option<HtmlElement>,
     target: Option<HtmlElement>,
+    document: Option<Document>,
 }
 
 impl Default for FocusTrapOptionsBuilder {
@@ -135,10 +257,16 @@ impl Default for FocusTrapOptionsBuilder {
         Self {
             return_focus: true,
             initial_focus: InitialFocus::default(),
-            deactivate_on_escape: false,
+            deactivate_on_escape: config::config().deactivate_on_escape,
             hooks: FocusTrapHooks::default(),
-            scope: body(),
+            navigation: NavigationPolicy::default(),
+            on_event: None,
+            rejected_feedback: Some(Duration::from_millis(400)),
+            hide_others: false,
+            scroll_lock: false,
+            scope: None,
             target: None,
+            document: None,
         }
     }
 }
@@ -168,8 +296,43 @@ impl FocusTrapOptionsBuilder {
         self
     }
 
+    pub fn navigation(mut self, navigation: NavigationPolicy) -> Self {
+        self.navigation = navigation;
+        self
+    }
+
+    /// Sets the interceptor of events the trap handles, like one submitting a stage on Tab from
+    /// its last field
+    pub fn on_event(mut self, on_event: impl Fn(&Event) -> EventOutcome + 'static) -> Self {
+        self.on_event = Some(Box::new(on_event));
+        self
+    }
+
+    /// Sets how long [REJECTED_ATTRIBUTE] stays after a pointer press outside is blocked, or
+    /// None to never set it
+    ///
+    /// Defaults to 400ms.
+    pub fn rejected_feedback(mut self, rejected_feedback: Option<Duration>) -> Self {
+        self.rejected_feedback = rejected_feedback;
+        self
+    }
+
+    /// Sets whether the rest of the page is hidden from assistive technologies while activated,
+    /// leaving the toast region and live regions announcing
+    pub fn hide_others(mut self, hide_others: bool) -> Self {
+        self.hide_others = hide_others;
+        self
+    }
+
+    /// Sets whether the document can't be scrolled while activated, like under modal dialogs
+    pub fn scroll_lock(mut self, scroll_lock: bool) -> Self {
+        self.scroll_lock = scroll_lock;
+        self
+    }
+
+    /// Sets the scope, which is the body of the document by default
     pub fn scope(mut self, scope: HtmlElement) -> Self {
-        self.scope = scope;
+        self.scope = Some(scope);
         self
     }
 
@@ -178,101 +341,271 @@ impl FocusTrapOptionsBuilder {
         self
     }
 
+    /// Sets the document focus is managed in, instead of the document of the target
+    pub fn document(mut self, document: Document) -> Self {
+        self.document = Some(document);
+        self
+    }
+
     /// Builds into [FocusTrapOptions]
     ///
     /// # Panics
-    /// This method panics if target field is not set
+    /// This method panics if target field is not set, or if the scope is not set and the
+    /// document has no body
     pub fn build(self) -> FocusTrapOptions {
+        let target = self
+            .target
+            .expect("target must be set to build FocusTrapOptions");
+        let document = self
+            .document
+            .or_else(|| target.owner_document())
+            .unwrap_or_else(document);
+        let scope = self
+            .scope
+            .or_else(|| document.body())
+            .expect("document must have a body to build FocusTrapOptions");
         FocusTrapOptions {
             return_focus: self.return_focus,
             initial_focus: self.initial_focus,
             deactivate_on_escape: self.deactivate_on_escape,
             hooks: self.hooks,
-            scope: self.scope,
-            target: self
-                .target
-                .expect("target must be set to build FocusTrapOptions"),
+            navigation: self.navigation,
+            on_event: self.on_event,
+            rejected_feedback: self.rejected_feedback,
+            hide_others: self.hide_others,
+            scroll_lock: self.scroll_lock,
+            scope,
+            target,
+            document,
         }
     }
 }
 
 struct State {
     options: Rc<FocusTrapOptions>,
+    /// Unique in the page, reflected to [ID_ATTRIBUTE]
+    id: String,
     is_activated: bool,
     last_focus: Option<HtmlElement>,
     return_element: Option<HtmlElement>,
-    callbacks: Callbacks,
+    this: Weak<Mutex<State>>,
+    /// Listeners registered while activated, which are removed when the state is dropped
+    listener: Listener,
+    /// Tab candidates of the target, kept while activated
+    candidates: Option<CandidateCache>,
+    /// Candidates marked with [TAB_INDEX_ATTRIBUTE] while debugging
+    marked: Vec<HtmlElement>,
+    subscribers: Vec<Rc<Subscriber>>,
+    /// Subscription to the on-screen keyboard while activated
+    keyboard: Option<u64>,
+    /// Inputs handled since [FocusTrap::start_recording]
+    recording: Option<Recording>,
+    /// Count of recorded inputs whose follow-up focus moves may still come
+    following: Rc<Cell<u32>>,
+    /// Removes [REJECTED_ATTRIBUTE] once the feedback ends, cancelled when dropped
+    rejected: Option<Timeout>,
+    /// Referenced elements of the target, made when asked first
+    refs: Option<Refs>,
+    /// Focus moved into the target, cancelled once deactivated or dropped
+    focus: Option<ScheduledFocus>,
+    /// Focus returned by deactivation, cancelled once activated again
+    returning: Option<ScheduledFocus>,
+    /// The rest of the page hidden by [FocusTrapOptions::hide_others] while activated
+    hidden: Option<HiddenOthers>,
+    /// The lock taken by [FocusTrapOptions::scroll_lock] while activated
+    scroll_lock: Option<ScrollLock>,
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        if let Some(handle) = self.keyboard.take() {
+            keyboard::unsubscribe(handle);
+        }
+        // Traps are usually dropped right after deactivating, which still returns focus
+        if let Some(returning) = self.returning.take() {
+            returning.forget();
+        }
+    }
+}
+
+/// Calls subscribers of the trap with the event, once its state is unlocked
+fn publish(weak: &Weak<Mutex<State>>, event: Option<FocusTrapEvent>) {
+    let Some(event) = event else {
+        return;
+    };
+    let subscribers = acquired(weak, |state| state.subscribers.clone());
+    for subscriber in subscribers {
+        (subscriber.callback)(&event);
+    }
+}
+
+/// Applies the navigation policy of the trap, once its state is unlocked for the guard
+///
+/// # Returns
+/// Whether the navigation may go on
+fn navigate(weak: &Weak<Mutex<State>>) -> bool {
+    let Some(state) = weak.upgrade() else {
+        return true;
+    };
+    let options = {
+        let state = state.lock().unwrap();
+        if !state.is_activated {
+            return true;
+        }
+        state.options.clone()
+    };
+    let is_allowed = match &options.navigation {
+        NavigationPolicy::Ignore => return true,
+        NavigationPolicy::Deactivate => true,
+        NavigationPolicy::Guard(guard) => guard(),
+    };
+    if is_allowed {
+        seigi_utils::debug!("focus trap deactivated by navigation");
+        state.lock().unwrap().deactivate();
+    }
+    is_allowed
+}
+
+/// Calls the interceptor of the trap with the event, once its state is unlocked, recording the
+/// event if consumed
+///
+/// # Returns
+/// Whether the event is consumed, which the trap leaves alone
+fn intercept(weak: &Weak<Mutex<State>>, event: &Event) -> bool {
+    let options = acquired(weak, |state| state.options.clone());
+    let Some(on_event) = &options.on_event else {
+        return false;
+    };
+    if on_event(event) == EventOutcome::Continue {
+        return false;
+    }
+    let input = match event.type_().as_str() {
+        "focusin" => Some(Input::FocusIn),
+        "mousedown" => Some(Input::PointerDown),
+        "keydown" => {
+            let event = event.unchecked_ref::<KeyboardEvent>();
+            matches!(event.key().as_str(), "Tab" | "Escape").then(|| Input::KeyDown {
+                key: event.key(),
+                shift: event.shift_key(),
+            })
+        }
+        _ => None,
+    };
+    if let (Some(input), Some(target)) = (input, target(event))
+        && let Some(state) = weak.upgrade()
+    {
+        state
+            .lock()
+            .unwrap()
+            .record(input, &target, Decision::Consumed);
+    }
+    true
+}
+
+/// Returns whether the `navigate` event of the Navigation API goes back or forward in the
+/// document, which browsers without the API tell through `popstate`
+fn is_traversal(event: &Event) -> bool {
+    let property = |target: &JsValue, name: &str| Reflect::get(target, &JsValue::from_str(name));
+    let is_traverse = property(event, "navigationType")
+        .is_ok_and(|v| v.as_string().as_deref() == Some("traverse"));
+    let is_same_document = property(event, "destination")
+        .and_then(|v| property(&v, "sameDocument"))
+        .is_ok_and(|v| v.is_truthy());
+    is_traverse && is_same_document
 }
 
 impl State {
     fn add_listeners(&mut self) {
-        let option_captures = {
-            let options = AddEventListenerOptions::new();
-            options.set_capture(true);
-            options
+        let scope = self.options.scope.clone();
+        let capture = ListenerOptions::capture();
+        // Focus moves can't be cancelled, so the browser never waits for the listener
+        let passive = ListenerOptions {
+            passive: true,
+            ..ListenerOptions::capture()
         };
+        let weak = &self.this;
 
-        let scope = &self.options.scope;
-
-        let _ = scope.add_event_listener_with_callback_and_add_event_listener_options(
-            "focusin",
-            self.callbacks.focus_in.as_function(),
-            &option_captures,
-        );
-        let _ = scope.add_event_listener_with_callback_and_add_event_listener_options(
-            "mousedown",
-            self.callbacks.pointer_down.as_function(),
-            &option_captures,
-        );
-        let _ = scope.add_event_listener_with_callback_and_add_event_listener_options(
-            "touchstart",
-            self.callbacks.pointer_down.as_function(),
-            &option_captures,
-        );
-        let _ = scope.add_event_listener_with_callback_and_add_event_listener_options(
-            "click",
-            self.callbacks.click.as_function(),
-            &option_captures,
-        );
-        let _ = scope.add_event_listener_with_callback_and_add_event_listener_options(
-            "keydown",
-            self.callbacks.key_down.as_function(),
-            &option_captures,
-        );
-        let _ = scope
-            .add_event_listener_with_callback("keydown", self.callbacks.key_down.as_function());
-    }
-
-    fn remove_listeners(&mut self) {
-        let scope = &self.options.scope;
-
-        let _ = scope.remove_event_listener_with_callback_and_bool(
-            "focusin",
-            self.callbacks.focus_in.as_function(),
-            true,
-        );
-        let _ = scope.remove_event_listener_with_callback_and_bool(
-            "mousedown",
-            self.callbacks.pointer_down.as_function(),
-            true,
-        );
-        let _ = scope.remove_event_listener_with_callback_and_bool(
-            "touchstart",
-            self.callbacks.pointer_down.as_function(),
-            true,
-        );
-        let _ = scope.remove_event_listener_with_callback_and_bool(
-            "click",
-            self.callbacks.click.as_function(),
-            true,
-        );
-        let _ = scope.remove_event_listener_with_callback_and_bool(
-            "keydown",
-            self.callbacks.key_down.as_function(),
-            true,
-        );
-        let _ = scope
-            .remove_event_listener_with_callback("keydown", self.callbacks.key_down.as_function());
+        // Presses of mice, pens and touches are all handled through mousedown, which browsers
+        // dispatch for taps too. Unlike touchstart it never delays scrolling, and unlike
+        // pointerdown cancelling it keeps the browser from moving focus
+        self.listener
+            .on_with_options(&scope, "focusin", passive, {
+                let weak = weak.clone();
+                move |event| {
+                    if intercept(&weak, event) {
+                        return;
+                    }
+                    let event = acquired(&weak, |mut state| {
+                        state.handle_focus_in(event.unchecked_ref())
+                    });
+                    publish(&weak, event);
+                }
+            })
+            .on_with_options(&scope, "mousedown", capture, {
+                let weak = weak.clone();
+                move |event| {
+                    if intercept(&weak, event) {
+                        return;
+                    }
+                    let event = acquired(&weak, |mut state| {
+                        state.handle_pointer_down(event.unchecked_ref())
+                    });
+                    publish(&weak, event);
+                }
+            })
+            .on_with_options(&scope, "click", capture, {
+                let weak = weak.clone();
+                move |event| {
+                    if intercept(&weak, event) {
+                        return;
+                    }
+                    acquired(&weak, |mut state| state.handle_click(event.unchecked_ref()));
+                }
+            })
+            .on_with_options(&scope, "keydown", capture, {
+                let weak = weak.clone();
+                move |event| {
+                    if intercept(&weak, event) {
+                        return;
+                    }
+                    // Events of other documents aren't instances of KeyboardEvent of this window
+                    let event = acquired(&weak, |mut state| {
+                        state.handle_key_down(event.unchecked_ref())
+                    });
+                    publish(&weak, event);
+                }
+            });
+        self.add_navigation_listener();
+    }
+
+    /// Follows navigations through history with the Navigation API where supported, which can
+    /// cancel them, or with `popstate` otherwise
+    fn add_navigation_listener(&mut self) {
+        if matches!(self.options.navigation, NavigationPolicy::Ignore) {
+            return;
+        }
+        let Some(window) = self.options.document.default_view() else {
+            return;
+        };
+        let weak = self.this.clone();
+        let navigation = Reflect::get(&window, &JsValue::from_str("navigation"))
+            .ok()
+            .filter(|v| v.is_object());
+        match navigation {
+            Some(navigation) => {
+                self.listener
+                    .on(navigation.unchecked_ref(), "navigate", move |event| {
+                        if is_traversal(event) && !navigate(&weak) && event.cancelable() {
+                            event.prevent_default();
+                        }
+                    });
+            }
+            None => {
+                self.listener.on(&window, "popstate", move |_| {
+                    navigate(&weak);
+                });
+            }
+        }
     }
 
     fn activate(&mut self) {
@@ -280,9 +613,33 @@ impl State {
             return;
         }
         self.is_activated = true;
+        seigi_utils::debug!("focus trap activated on {:?}", self.options.target.id());
+        self.returning = None;
 
-        self.return_element = active_element();
+        self.return_element = active_element(&self.options.document);
+        self.candidates = Some(CandidateCache::new(
+            self.options.target.clone().unchecked_into(),
+        ));
         self.add_listeners();
+        self.follow_keyboard();
+        let target = &self.options.target;
+        let _ = target.set_attribute(ACTIVE_ATTRIBUTE, "");
+        let _ = target.set_attribute(ID_ATTRIBUTE, &self.id);
+        self.mark_candidates();
+        if self.options.hide_others {
+            self.hidden = Some(outside::hide_others(&self.options.target));
+        }
+        if self.options.scroll_lock {
+            self.scroll_lock = Some(scroll_lock::lock_for(&self.options.target));
+        }
+        if seigi_utils::audit::is_enabled() {
+            let target = self.options.target.unchecked_ref();
+            seigi_utils::audit::focus_target(
+                "seigi_focus",
+                target,
+                candidates::first_focus_candidate(target).is_some(),
+            );
+        }
         self.initial_focus();
 
         if let Some(hook) = &self.options.hooks.activate {
@@ -295,8 +652,20 @@ impl State {
             return;
         }
         self.is_activated = false;
+        seigi_utils::debug!("focus trap deactivated on {:?}", self.options.target.id());
 
-        self.remove_listeners();
+        self.listener.abort();
+        if let Some(handle) = self.keyboard.take() {
+            keyboard::unsubscribe(handle);
+        }
+        self.candidates = None;
+        self.rejected = None;
+        self.focus = None;
+        self.hidden = None;
+        self.scroll_lock = None;
+        let _ = self.options.target.remove_attribute(ACTIVE_ATTRIBUTE);
+        let _ = self.options.target.remove_attribute(REJECTED_ATTRIBUTE);
+        self.unmark_candidates();
         self.return_focus();
 
         if let Some(hook) = &self.options.hooks.deactivate {
@@ -304,21 +673,106 @@ impl State {
         }
     }
 
-    fn initial_focus(&self) {
+    /// Scrolls the focused element in the target into view as the on-screen keyboard appears,
+    /// which covers inputs near the bottom of the page otherwise
+    fn follow_keyboard(&mut self) {
+        let weak = self.this.clone();
+        self.keyboard = Some(keyboard::subscribe(Box::new(move |height| {
+            if height == 0.0 {
+                return;
+            }
+            let Some(state) = weak.upgrade() else {
+                return;
+            };
+            let focused = {
+                let state = state.lock().unwrap();
+                active_element(&state.options.document)
+                    .filter(|v| state.options.target.contains(Some(v)))
+            };
+            if let Some(focused) = focused {
+                let options = ScrollIntoViewOptions::new();
+                options.set_block(ScrollLogicalPosition::Nearest);
+                focused.scroll_into_view_with_scroll_into_view_options(&options);
+            }
+        })));
+    }
+
+    /// Marks tab candidates with their positions while debugging, replacing earlier marks
+    fn mark_candidates(&mut self) {
+        self.unmark_candidates();
+        if !is_debugging() {
+            return;
+        }
+        let Some(candidates) = &self.candidates else {
+            return;
+        };
+        let candidates = candidates.tab_candidates();
+        for (index, element) in candidates.iter().enumerate() {
+            let _ = element.set_attribute(TAB_INDEX_ATTRIBUTE, &index.to_string());
+        }
+        self.marked = candidates.to_vec();
+    }
+
+    fn unmark_candidates(&mut self) {
+        for element in self.marked.drain(..) {
+            let _ = element.remove_attribute(TAB_INDEX_ATTRIBUTE);
+        }
+    }
+
+    /// Logs the input into the recording, if recording
+    ///
+    /// Focus moves are marked as follow-ups until timeouts scheduled by now have run, which
+    /// covers focus moved by browsers for the input and by the trap in response.
+    fn record(&mut self, input: Input, target: &HtmlElement, decision: Decision) {
+        let Some(recording) = &mut self.recording else {
+            return;
+        };
+        let Some(path) = recorder::path(self.options.scope.unchecked_ref(), target) else {
+            return;
+        };
+        let follow_up = input == Input::FocusIn && self.following.get() > 0;
+        recording.push(input, path, decision, follow_up);
+
+        let following = self.following.clone();
+        following.set(following.get() + 1);
+        Timeout::new(0, move || following.set(following.get() - 1)).forget();
+        diagnostics::track(Resource::Timeout).forget();
+    }
+
+    /// Returns the path of the element a focus move goes to, for the recording
+    fn path(&self, element: &HtmlElement) -> Option<String> {
+        self.recording.as_ref()?;
+        recorder::path(self.options.scope.unchecked_ref(), element)
+    }
+
+    fn initial_focus(&mut self) {
+        let first = || candidates::first_focus_candidate(self.options.target.unchecked_ref());
         let element = match &self.options.initial_focus {
             InitialFocus::None => return,
-            InitialFocus::Auto => {
-                match candidates::first_focus_candidate(self.options.target.unchecked_ref()) {
+            InitialFocus::Auto => match first() {
+                Some(element) => element,
+                None => return,
+            },
+            InitialFocus::Last => {
+                let last = self.last_focus.clone().filter(|v| {
+                    v.is_connected()
+                        && self.options.target.contains(Some(v))
+                        && candidates::is_focusable(v)
+                });
+                match last.or_else(first) {
                     Some(element) => element,
                     None => return,
                 }
             }
             InitialFocus::Selector(selector) => {
-                match document().query_selector(selector).ok().flatten() {
-                    Some(element) => match element.dyn_into::<HtmlElement>() {
-                        Ok(element) => element,
-                        Err(_) => return,
-                    },
+                let element = self
+                    .options
+                    .document
+                    .query_selector(selector)
+                    .ok()
+                    .flatten();
+                match element.and_then(|v| html_element(v.into())) {
+                    Some(element) => element,
                     None => return,
                 }
             }
@@ -326,40 +780,79 @@ impl State {
             InitialFocus::Function(function) => function(),
         };
 
-        schedule_focus(element);
+        self.schedule_focus(element);
+    }
+
+    /// Focuses the element in the next task, superseding the focus move scheduled before
+    fn schedule_focus(&mut self, element: HtmlElement) {
+        self.focus = Some(schedule::focus(element));
     }
 
-    fn return_focus(&self) {
+    fn return_focus(&mut self) {
         if let Some(element) = &self.return_element {
-            schedule_focus(element.clone());
+            self.returning = Some(schedule::focus(element.clone()));
         }
     }
 
-    fn handle_focus_in(&mut self, event: &FocusEvent) {
-        let Some(target) = target(event.unchecked_ref()) else {
-            return;
-        };
+    fn handle_focus_in(&mut self, event: &FocusEvent) -> Option<FocusTrapEvent> {
+        let target = target(event.unchecked_ref())?;
 
         if self.options.target.contains(Some(&target)) {
-            self.last_focus = Some(target)
-        } else {
+            self.record(Input::FocusIn, &target, Decision::Allowed);
+            self.last_focus = deep_target(event).or(Some(target));
+            // Candidates may have changed since they were marked
+            if is_debugging() {
+                self.mark_candidates();
+            }
+        } else if !outside::is_allowed(&target) {
             // the focus has escaped out of focus trap
             event.stop_immediate_propagation();
 
-            if let Some(last_focus) = &self.last_focus {
-                schedule_focus(last_focus.clone());
+            if let Some(last_focus) = self.last_focus.clone() {
+                self.schedule_focus(last_focus);
             }
+            self.record(Input::FocusIn, &target, Decision::Blocked);
+            return Some(FocusTrapEvent::OutsideInteraction {
+                kind: InteractionKind::Focus,
+                target,
+            });
+        } else {
+            self.record(Input::FocusIn, &target, Decision::AllowedOutside);
         }
+        None
     }
 
-    fn handle_pointer_down(&mut self, event: &Event) {
-        let Some(target) = target(event) else {
-            return;
-        };
+    /// Cancels presses outside, which keeps the browser from moving focus out of the trap
+    fn handle_pointer_down(&mut self, event: &MouseEvent) -> Option<FocusTrapEvent> {
+        let target = target(event.unchecked_ref())?;
 
-        if !self.options.target.contains(Some(&target)) {
+        if self.options.target.contains(Some(&target)) {
+            self.record(Input::PointerDown, &target, Decision::Allowed);
+        } else if !outside::is_allowed(&target) {
             event.prevent_default();
+            self.record(Input::PointerDown, &target, Decision::Blocked);
+            self.reject();
+            return Some(FocusTrapEvent::OutsideInteraction {
+                kind: InteractionKind::Pointer,
+                target,
+            });
+        } else {
+            self.record(Input::PointerDown, &target, Decision::AllowedOutside);
         }
+        None
+    }
+
+    /// Sets [REJECTED_ATTRIBUTE] in the target for the duration of the feedback, extending it
+    /// if set already
+    fn reject(&mut self) {
+        let Some(duration) = self.options.rejected_feedback else {
+            return;
+        };
+        let target = self.options.target.clone();
+        let _ = target.set_attribute(REJECTED_ATTRIBUTE, "");
+        self.rejected = Some(Timeout::new(duration.as_millis() as u32, move || {
+            let _ = target.remove_attribute(REJECTED_ATTRIBUTE);
+        }));
     }
 
     fn handle_click(&mut self, event: &MouseEvent) {
@@ -367,91 +860,117 @@ impl State {
             return;
         };
 
-        if !self.options.target.contains(Some(&target)) {
+        if !self.options.target.contains(Some(&target)) && !outside::is_allowed(&target) {
             event.prevent_default();
             event.stop_immediate_propagation();
         }
     }
 
-    fn handle_key_down(&mut self, event: &KeyboardEvent) {
+    fn handle_key_down(&mut self, event: &KeyboardEvent) -> Option<FocusTrapEvent> {
+        // Tab and Escape pick and cancel candidates while an IME composes text in the trap
+        if ime::is_composing(event) {
+            return None;
+        }
         if event.key() == "Tab" {
-            let Some(target) = event.target() else {
-                return;
-            };
-            let target = target.unchecked_ref::<HtmlElement>();
+            // Compared with candidates, which include those in the shadow tree of the target
+            let target = &deep_target(event)?;
             let is_backward = event.shift_key();
+            let input = Input::KeyDown {
+                key: "Tab".to_string(),
+                shift: is_backward,
+            };
 
-            let body_tab_candidates = {
+            let container_tab_candidates = match &self.candidates {
+                Some(cache) => cache.tab_candidates(),
+                None => candidates::tab_candidates(self.options.target.unchecked_ref()).into(),
+            };
+            // Candidates of the whole document are only needed when focus wraps around
+            let body_tab_candidates = || {
                 let container = &self.options.target;
                 let scope = &self.options.scope;
-                candidates::candidates(body().unchecked_ref(), move |v| {
+                let root = self
+                    .options
+                    .document
+                    .body()
+                    .unwrap_or_else(|| scope.clone());
+                let mut candidates = candidates::candidates(root.unchecked_ref(), move |v| {
                     candidates::is_tabbable(v)
-                        && (!scope.contains(Some(v.unchecked_ref()))
-                            || container.contains(Some(v.unchecked_ref())))
-                })
+                        && !scope.contains(Some(v.unchecked_ref()))
+                        && !container.contains(Some(v.unchecked_ref()))
+                });
+                // Those of the target are placed where it is, in the order it is tabbed through
+                let position = candidates
+                    .iter()
+                    .position(|v| {
+                        v.compare_document_position(container) & Node::DOCUMENT_POSITION_PRECEDING
+                            != 0
+                    })
+                    .unwrap_or(candidates.len());
+                candidates.splice(position..position, container_tab_candidates.iter().cloned());
+                candidates
             };
-            let container_tab_candidates =
-                candidates::tab_candidates(self.options.target.unchecked_ref());
-
-            if is_backward {
-                let Some(first) = container_tab_candidates.first() else {
-                    event.prevent_default();
-                    return;
-                };
-
-                if target == first {
-                    let position = body_tab_candidates
-                        .iter()
-                        .position(|v| v == target)
-                        .unwrap();
-
-                    if position == 0 {
-                        // If there was a first element in vec, then there must be last one too
-                        let last = body_tab_candidates.last().unwrap();
-                        schedule_focus(last.clone());
-                    } else {
-                        let target = body_tab_candidates
-                            .get(position - 1)
-                            .unwrap_or_else(|| body_tab_candidates.last().unwrap());
-                        schedule_focus(target.clone());
-                    }
-                    event.prevent_default();
-                }
+
+            let edge = if is_backward {
+                container_tab_candidates.first()
             } else {
-                let Some(last) = container_tab_candidates.last() else {
-                    event.prevent_default();
-                    return;
-                };
-
-                if target == last {
-                    let position = body_tab_candidates
-                        .iter()
-                        .position(|v| v == target)
-                        .unwrap();
-
-                    if position == body_tab_candidates.len() {
-                        let first = container_tab_candidates.first().unwrap();
-                        schedule_focus(first.clone());
-                    } else {
-                        let target = body_tab_candidates
-                            .get(position + 1)
-                            .unwrap_or_else(|| body_tab_candidates.first().unwrap());
-                        schedule_focus(target.clone());
-                    }
-                    event.prevent_default();
-                }
+                container_tab_candidates.last()
+            };
+            let Some(edge) = edge else {
+                event.prevent_default();
+                self.record(input, target, Decision::Blocked);
+                return Some(FocusTrapEvent::OutsideInteraction {
+                    kind: InteractionKind::Key,
+                    target: target.clone(),
+                });
+            };
+            if target != edge {
+                self.record(input, target, Decision::Passed);
+                return None;
             }
-        } else if event.key() == "Escape" && self.options.deactivate_on_escape {
+
+            let body_tab_candidates = body_tab_candidates();
+            let position = body_tab_candidates
+                .iter()
+                .position(|v| v == target)
+                .unwrap();
+            let next = if is_backward {
+                if position == 0 {
+                    // If there was a first element in vec, then there must be last one too
+                    body_tab_candidates.last().unwrap()
+                } else {
+                    body_tab_candidates
+                        .get(position - 1)
+                        .unwrap_or_else(|| body_tab_candidates.last().unwrap())
+                }
+            } else if position == body_tab_candidates.len() {
+                container_tab_candidates.first().unwrap()
+            } else {
+                body_tab_candidates
+                    .get(position + 1)
+                    .unwrap_or_else(|| body_tab_candidates.first().unwrap())
+            };
+            self.schedule_focus(next.clone());
             event.prevent_default();
-            self.deactivate();
+            let to = self.path(next);
+            self.record(input, target, Decision::Moved { to });
+        } else if event.key() == "Escape" {
+            let input = Input::KeyDown {
+                key: "Escape".to_string(),
+                shift: event.shift_key(),
+            };
+            let target = target(event.unchecked_ref());
+            let decision = if self.options.deactivate_on_escape {
+                event.prevent_default();
+                self.deactivate();
+                Decision::Deactivated
+            } else {
+                Decision::Passed
+            };
+            if let Some(target) = target {
+                self.record(input, &target, decision);
+            }
         }
-    }
-}
-
-impl Drop for State {
-    fn drop(&mut self) {
-        // Clean up listeners so there are no dangling listeners pointing to dropped rust closures
-        self.remove_listeners();
+        None
     }
 }
 
@@ -460,6 +979,36 @@ impl Drop for State {
 /// This struct contains a handle(Rc) to actual state.
 ///
 /// Dropping this struct would also unregister all event listeners the trap has attached
+///
+/// Focus and pointer presses in zones allowed by [seigi_utils::outside::allow], such as the
+/// toast region, are let through while activated. Others are blocked, and reported to
+/// [FocusTrap::subscribe] as [FocusTrapEvent::OutsideInteraction].
+///
+/// While activated, the focused element of the target is scrolled into view as the on-screen
+/// keyboard of [seigi_utils::keyboard] appears. Tab and Escape are left to IME composition, as
+/// told by [seigi_utils::ime::is_composing].
+///
+/// Navigations back and forward through history deactivate the trap by default, as told by
+/// [FocusTrapOptions::navigation], so listeners aren't left on a scope the next route removes.
+///
+/// Events can be intercepted before the trap handles them with [FocusTrapOptions::on_event],
+/// for behaviors the trap has no option for.
+///
+/// Inputs the trap handles can be recorded with [FocusTrap::start_recording], into a log of
+/// [recorder] to replay a reported bug with.
+///
+/// # Attributes
+/// Attributes are kept stable for end-to-end tests and styles to follow traps with.
+///
+/// **data-seigi-trap-active** is set in the target while the trap is activated
+///
+/// **data-seigi-trap-id** is set in the target to [FocusTrap::id] once the trap is activated
+///
+/// **data-seigi-trap-rejected** is set in the target for [FocusTrapOptions::rejected_feedback]
+/// after a pointer press outside is blocked, so styles can tell the user the press was ignored
+///
+/// **data-seigi-tab-index** is set in each tab candidate of the target to its position in tab
+/// order, starting from 0, while the trap is activated with [set_debugging] enabled
 #[derive(Clone)]
 pub struct FocusTrap {
     state: Rc<Mutex<State>>,
@@ -473,6 +1022,31 @@ impl FocusTrap {
         self.state.lock().unwrap().options.clone()
     }
 
+    /// Returns the id of the trap, unique in the page
+    pub fn id(&self) -> String {
+        self.state.lock().unwrap().id.clone()
+    }
+
+    /// Returns elements of the target referenced by `data-seigi-ref`, which are kept across
+    /// calls until the target changes
+    pub fn refs(&self) -> Refs {
+        let mut state = self.state.lock().unwrap();
+        let target = state.options.target.clone();
+        state
+            .refs
+            .get_or_insert_with(|| Refs::new(target.into()))
+            .clone()
+    }
+
+    /// Shows the tab candidates of the target numbered in tab order, until the overlay is dropped
+    ///
+    /// This function locks the state
+    #[cfg(feature = "overlay")]
+    pub fn show_focus_order(&self) -> overlay::FocusOrderOverlay {
+        let target = self.state.lock().unwrap().options.target.clone();
+        overlay::FocusOrderOverlay::new(target.into())
+    }
+
     /// Return true if the trap is activated
     ///
     /// This function locks the state
@@ -493,39 +1067,80 @@ impl FocusTrap {
     pub fn deactivate(&self) {
         self.state.lock().unwrap().deactivate();
     }
+
+    /// Applies [FocusTrapOptions::navigation] for a navigation the app makes itself, like a
+    /// router pushing history, which the trap can't follow
+    ///
+    /// Does nothing while deactivated.
+    ///
+    /// # Returns
+    /// Whether the navigation may go on, which is false if the guard blocks it
+    pub fn before_navigation(&self) -> bool {
+        navigate(&Rc::downgrade(&self.state))
+    }
+
+    /// Starts recording inputs the trap handles into a [Recording], discarding any recording in
+    /// progress
+    ///
+    /// Recordings are kept across activations until taken with [FocusTrap::take_recording].
+    pub fn start_recording(&self) {
+        self.state.lock().unwrap().recording = Some(Recording::new());
+    }
+
+    /// Stops recording and returns the recording, or None if the trap wasn't recording
+    pub fn take_recording(&self) -> Option<Recording> {
+        self.state.lock().unwrap().recording.take()
+    }
+
+    /// Subscribes to [FocusTrapEvent]s of the trap
+    ///
+    /// # Returns
+    /// A handle to unsubscribe with [FocusTrap::unsubscribe]
+    pub fn subscribe(&self, callback: Box<dyn Fn(&FocusTrapEvent)>) -> u64 {
+        static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+        let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        self.state
+            .lock()
+            .unwrap()
+            .subscribers
+            .push(Rc::new(Subscriber { callback, handle }));
+        handle
+    }
+
+    pub fn unsubscribe(&self, handle: u64) {
+        self.state
+            .lock()
+            .unwrap()
+            .subscribers
+            .retain(|v| v.handle != handle);
+    }
 }
 
-pub fn create(options: FocusTrapOptions) -> FocusTrap {
-    let options = Rc::new(options);
+/// Creates a focus trap, from options or their builder which is built like
+/// [FocusTrapOptionsBuilder::build]
+pub fn create(options: impl Into<FocusTrapOptions>) -> FocusTrap {
+    let options = Rc::new(options.into());
     let state = Rc::new_cyclic(|weak: &Weak<Mutex<State>>| {
-        let weak = weak.clone();
-        let focus_in = callback!(weak, move |event: &Event| acquired(&weak, |mut state| {
-            state.handle_focus_in(event.unchecked_ref())
-        }));
-        let pointer_down = callback!(weak, move |event: &Event| acquired(&weak, |mut state| {
-            state.handle_pointer_down(event)
-        }));
-        let click = callback!(weak, move |event: &Event| acquired(&weak, |mut state| {
-            state.handle_click(event.unchecked_ref())
-        }));
-        let key_down = callback!(weak, move |event: &Event| acquired(&weak, |mut state| {
-            let Some(event) = event.dyn_ref() else {
-                return;
-            };
-            state.handle_key_down(event);
-        }));
-
         Mutex::new(State {
             options,
+            id: id::generate("trap"),
             is_activated: false,
             last_focus: None,
             return_element: None,
-            callbacks: Callbacks {
-                focus_in,
-                pointer_down,
-                click,
-                key_down,
-            },
+            this: weak.clone(),
+            listener: Listener::new(),
+            candidates: None,
+            marked: vec![],
+            subscribers: vec![],
+            keyboard: None,
+            recording: None,
+            following: Rc::new(Cell::new(0)),
+            rejected: None,
+            refs: None,
+            focus: None,
+            returning: None,
+            hidden: None,
+            scroll_lock: None,
         })
     });
 
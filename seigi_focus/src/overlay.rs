@@ -0,0 +1,218 @@
+//! An overlay numbering and outlining tab candidates on screen, for checking tab orders in
+//! development builds
+//!
+//! ```ignore
+//! let overlay = FocusOrderOverlay::new(dialog.into());
+//! // or, for the target of a trap
+//! let overlay = trap.show_focus_order();
+//! ```
+//!
+//! Each tab candidate of the container is outlined with its position in the order traps tab
+//! through, which the overlay draws again as the subtree changes and the page scrolls or resizes.
+//! Candidates with a positive `tabindex`, which browsers visit before the others outside traps,
+//! are marked with `data-ordered` in amber, and candidates taking no space, which are focused
+//! without being seen, with `data-hidden` in red.
+//!
+//! The overlay is a manual popover where the Popover API is supported, so it stays above modal
+//! dialogs opened before it. It ignores pointers and is hidden from assistive technologies.
+
+use std::rc::{Rc, Weak};
+
+use gloo::utils::{body, document, window};
+use js_sys::Array;
+use seigi_utils::{
+    diagnostics::{self, Resource, Tracked},
+    listener::{Listener, ListenerOptions},
+    schedule::{self, Frame},
+    top_layer::{self, Popover},
+};
+use wasm_bindgen::{JsCast, prelude::Closure};
+use web_sys::{Element, HtmlElement, MutationObserver, MutationObserverInit};
+
+use crate::candidates;
+
+/// Set in the overlay element, which holds an item of [ITEM_ATTRIBUTE] for each candidate
+pub const OVERLAY_ATTRIBUTE: &str = "data-seigi-focus-overlay";
+
+/// Set in each item of the overlay to the position of its candidate in tab order, starting from 1
+pub const ITEM_ATTRIBUTE: &str = "data-seigi-focus-overlay-item";
+
+const COLOR: &str = "#2563eb";
+const ORDERED_COLOR: &str = "#d97706";
+const HIDDEN_COLOR: &str = "#dc2626";
+
+struct Inner {
+    container: Element,
+    element: HtmlElement,
+    /// Absent if the browser failed to create it, in which case the overlay is only drawn again
+    /// on scrolls and resizes
+    observer: Option<MutationObserver>,
+    _callback: Closure<dyn Fn(Array)>,
+    /// Draws the overlay once per frame, however many changes come in it
+    frame: Frame,
+    /// Listeners of scrolls and resizes moving candidates
+    _listener: Listener,
+    /// Counts the observer in diagnostics while alive
+    _tracked: Tracked,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        if let Some(observer) = &self.observer {
+            observer.disconnect();
+        }
+        self.element.remove();
+    }
+}
+
+/// Numbered outlines of the tab candidates of a container, shown until every clone is dropped
+#[derive(Clone)]
+pub struct FocusOrderOverlay(Rc<Inner>);
+
+impl FocusOrderOverlay {
+    /// Shows the overlay of the container
+    ///
+    /// # Panics
+    /// This function panics if the overlay element can't be created
+    pub fn new(container: Element) -> Self {
+        let element = document()
+            .create_element("div")
+            .expect("failed to create the overlay")
+            .unchecked_into::<HtmlElement>();
+        let _ = element.set_attribute(OVERLAY_ATTRIBUTE, "");
+        let _ = element.set_attribute("aria-hidden", "true");
+        let _ = element.set_attribute(
+            "style",
+            "position: fixed; inset: 0; width: auto; height: auto; margin: 0; padding: 0; \
+             border: none; overflow: visible; background: transparent; pointer-events: none; \
+             z-index: 2147483647;",
+        );
+        let _ = body().append_child(&element);
+        top_layer::make_popover(&element, Popover::Manual);
+        top_layer::show(&element);
+
+        let overlay = Rc::new_cyclic(|weak: &Weak<Inner>| {
+            let frame = schedule::frame({
+                let weak = weak.clone();
+                move || {
+                    if let Some(inner) = weak.upgrade() {
+                        FocusOrderOverlay(inner).draw();
+                    }
+                }
+            });
+            let callback = Closure::<dyn Fn(Array)>::new({
+                let frame = frame.clone();
+                move |_| frame.schedule()
+            });
+            let observer = MutationObserver::new(callback.as_ref().unchecked_ref())
+                .ok()
+                .filter(|observer| {
+                    let init = MutationObserverInit::new();
+                    init.set_child_list(true);
+                    init.set_subtree(true);
+                    // Any attribute may move candidates, like classes and styles
+                    init.set_attributes(true);
+                    observer.observe_with_options(&container, &init).is_ok()
+                        && container
+                            .shadow_root()
+                            .is_none_or(|root| observer.observe_with_options(&root, &init).is_ok())
+                });
+
+            let mut listener = Listener::new();
+            let options = ListenerOptions {
+                capture: true,
+                passive: true,
+                ..ListenerOptions::default()
+            };
+            listener
+                .on_with_options(&window(), "scroll", options, {
+                    let frame = frame.clone();
+                    move |_| frame.schedule()
+                })
+                .on(&window(), "resize", {
+                    let frame = frame.clone();
+                    move |_| frame.schedule()
+                });
+
+            Inner {
+                container,
+                element,
+                observer,
+                _callback: callback,
+                frame,
+                _listener: listener,
+                _tracked: diagnostics::track(Resource::Observer),
+            }
+        });
+        let overlay = Self(overlay);
+        overlay.draw();
+        overlay
+    }
+
+    /// Returns the container whose candidates are shown
+    pub fn container(&self) -> Element {
+        self.0.container.clone()
+    }
+
+    /// Draws the overlay again in the next frame, like after moving candidates with animations
+    /// the overlay doesn't see
+    pub fn refresh(&self) {
+        self.0.frame.schedule();
+    }
+
+    /// Replaces the items of the overlay with ones at the current candidates
+    fn draw(&self) {
+        let inner = &self.0;
+        let document = document();
+        inner.element.set_text_content(None);
+        for (index, candidate) in candidates::tab_candidates(&inner.container)
+            .iter()
+            .enumerate()
+        {
+            let Ok(item) = document.create_element("div") else {
+                continue;
+            };
+            let position = (index + 1).to_string();
+            let _ = item.set_attribute(ITEM_ATTRIBUTE, &position);
+            let rect = candidate.get_bounding_client_rect();
+            let color = if rect.width() == 0.0 && rect.height() == 0.0 {
+                let _ = item.set_attribute("data-hidden", "");
+                HIDDEN_COLOR
+            } else if candidate.tab_index() > 0 {
+                let _ = item.set_attribute("data-ordered", "");
+                ORDERED_COLOR
+            } else {
+                COLOR
+            };
+            let _ = item.set_attribute(
+                "style",
+                &format!(
+                    "position: fixed; left: {}px; top: {}px; width: {}px; height: {}px; \
+                     box-sizing: border-box; outline: 2px solid {color}; outline-offset: 1px;",
+                    rect.left(),
+                    rect.top(),
+                    rect.width(),
+                    rect.height()
+                ),
+            );
+            let Ok(label) = document.create_element("span") else {
+                continue;
+            };
+            label.set_text_content(Some(&position));
+            let _ = label.set_attribute(
+                "style",
+                &format!(
+                    "position: absolute; left: -3px; top: -3px; translate: 0 -100%; \
+                     padding: 1px 4px; border-radius: 3px; font: bold 11px/1.2 monospace; \
+                     color: #fff; background: {color};"
+                ),
+            );
+            let _ = item.append_child(&label);
+            let _ = inner.element.append_child(&item);
+        }
+        // Drawing inside the container, like the body, would draw the overlay again otherwise
+        if let Some(observer) = &inner.observer {
+            observer.take_records();
+        }
+    }
+}
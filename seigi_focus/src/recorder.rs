@@ -0,0 +1,150 @@
+//! Recordings of what focus traps did, for reproducing focus bugs reported by users
+//!
+//! While [crate::FocusTrap::start_recording] is in effect, a trap logs every focus move, pointer
+//! press and key press it handled, along with what it decided to do about it. The [Recording]
+//! serializes to JSON, so it can be attached to a bug report and replayed against the same markup
+//! by `seigi_testkit::replay`.
+//!
+//! Targets are kept as paths of child positions from the scope of the trap, so a recording is
+//! replayed by mounting the markup of the scope anywhere. Only Tab and Escape are recorded among
+//! keys, which keeps what users type out of the logs.
+
+use js_sys::Date;
+use serde::{Deserialize, Serialize};
+use web_sys::{Element, HtmlElement};
+
+/// What a trap handled
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Input {
+    /// Focus moved to the target
+    FocusIn,
+    /// A mouse, pen or touch pressed the target
+    PointerDown,
+    /// A key was pressed on the target, which is Tab or Escape
+    KeyDown { key: String, shift: bool },
+}
+
+/// What a trap did about an [Input]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Decision {
+    /// The target is in the trap, so the browser went on as usual
+    Allowed,
+    /// The target is outside the trap, in a zone allowed by [seigi_utils::outside::allow]
+    AllowedOutside,
+    /// The trap blocked the input, pulling focus back in for focus moves
+    Blocked,
+    /// The trap moved focus itself, like wrapping Tab around, to the element at the path
+    ///
+    /// The path is None if the element is out of the scope.
+    Moved { to: Option<String> },
+    /// The trap was deactivated by Escape
+    Deactivated,
+    /// The trap let the key through without doing anything
+    Passed,
+    /// The interceptor of [crate::FocusTrapOptions::on_event] consumed the input
+    Consumed,
+}
+
+/// An input handled by a trap
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Milliseconds since the recording started
+    pub time: f64,
+    pub input: Input,
+    /// The path of the target from the scope of the trap, as made by [path]
+    pub target: String,
+    pub decision: Decision,
+    /// Whether the input is a focus move following an earlier input, like focus moved by the
+    /// browser for a click or by the trap wrapping Tab around, which replays cause by themselves
+    #[serde(default)]
+    pub follow_up: bool,
+}
+
+/// A log of inputs a trap handled, oldest first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    /// When the recording started, in milliseconds since the Unix epoch
+    pub started_at: f64,
+    pub entries: Vec<Entry>,
+}
+
+impl Recording {
+    pub(crate) fn new() -> Self {
+        Self {
+            started_at: Date::now(),
+            entries: vec![],
+        }
+    }
+
+    pub(crate) fn push(
+        &mut self,
+        input: Input,
+        target: String,
+        decision: Decision,
+        follow_up: bool,
+    ) {
+        self.entries.push(Entry {
+            time: Date::now() - self.started_at,
+            input,
+            target,
+            decision,
+            follow_up,
+        });
+    }
+
+    /// Returns decisions of the entries in order, to compare a replay against the original
+    pub fn decisions(&self) -> Vec<Decision> {
+        self.entries.iter().map(|v| v.decision.clone()).collect()
+    }
+
+    /// Serializes the recording into JSON
+    pub fn to_json(&self) -> String {
+        // Every field serializes into JSON without fail
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Deserializes a recording from JSON made by [Recording::to_json]
+    ///
+    /// # Errors
+    /// Returns an error if the text isn't a recording
+    pub fn from_json(text: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(text)
+    }
+}
+
+/// Returns the path of the element from the root, like `div:nth-child(2) > button:nth-child(1)`,
+/// which is empty for the root itself
+///
+/// # Returns
+/// None if the element is out of the root
+pub fn path(root: &Element, element: &Element) -> Option<String> {
+    let mut segments = vec![];
+    let mut current = element.clone();
+    while current != *root {
+        let parent = current.parent_element()?;
+        let children = parent.children();
+        let position =
+            (0..children.length()).position(|v| children.item(v).as_ref() == Some(&current))?;
+        segments.push(format!(
+            "{}:nth-child({})",
+            current.local_name(),
+            position + 1
+        ));
+        current = parent;
+    }
+    segments.reverse();
+    Some(segments.join(" > "))
+}
+
+/// Returns the element at the path from the root, as made by [path]
+pub fn resolve(root: &Element, path: &str) -> Option<HtmlElement> {
+    if path.is_empty() {
+        return crate::html_element(root.clone().into());
+    }
+    root.query_selector(&format!(":scope > {path}"))
+        .ok()
+        .flatten()
+        .and_then(|v| crate::html_element(v.into()))
+}
@@ -0,0 +1,133 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_focus::candidates::{self, CandidateCache};
+use seigi_testkit::Fixture;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<div id="root">
+    <button id="first">First</button>
+    <input type="hidden">
+    <button disabled="true">Disabled</button>
+    <div tabindex="-1" id="programmatic">Programmatic</div>
+    <div inert><button>Inert</button></div>
+    <a id="last" href="">Last</a>
+</div>
+"#;
+
+/// A dialog rendering its children into slots of its shadow tree, with one left unassigned
+const SLOTTED: &str = r#"
+<div id="host">
+    <button id="footer" slot="footer">Footer</button>
+    <button id="unassigned" slot="nowhere">Unassigned</button>
+    <p slot="body"><a id="link" href="">Link</a></p>
+</div>
+"#;
+
+const SHADOW: &str = r#"
+<button id="close">Close</button>
+<slot name="body"></slot>
+<div inert><button id="inert">Inert</button></div>
+<footer><slot name="footer"><button id="replaced">Replaced</button></slot></footer>
+<slot name="empty"><button id="fallback">Fallback</button></slot>
+"#;
+
+fn attach_shadow(host: &web_sys::HtmlElement) -> web_sys::ShadowRoot {
+    let init = web_sys::ShadowRootInit::new(web_sys::ShadowRootMode::Open);
+    let root = host.attach_shadow(&init).unwrap();
+    root.set_inner_html(SHADOW);
+    root
+}
+
+fn ids(elements: &[web_sys::HtmlElement]) -> Vec<String> {
+    elements.iter().map(|v| v.id()).collect()
+}
+
+#[wasm_bindgen_test]
+fn queries_skip_what_cannot_be_focused() {
+    let fixture = Fixture::new(MARKUP);
+    let root = fixture.get("#root");
+
+    assert_eq!(ids(&candidates::tab_candidates(&root)), ["first", "last"]);
+    assert_eq!(
+        ids(&candidates::focus_candidates(&root)),
+        ["first", "programmatic", "last"]
+    );
+    assert_eq!(
+        candidates::first_tab_candidate(&root).map(|v| v.id()),
+        Some("first".to_string())
+    );
+}
+
+#[wasm_bindgen_test]
+fn queries_skip_inert_containers() {
+    let fixture = Fixture::new(MARKUP);
+    fixture.get("#root").set_attribute("inert", "").unwrap();
+
+    assert!(candidates::tab_candidates(&fixture.get("#root")).is_empty());
+    assert!(candidates::first_focus_candidate(&fixture.get("#root")).is_none());
+}
+
+#[wasm_bindgen_test]
+fn cache_follows_changes_of_subtree() {
+    let fixture = Fixture::new(MARKUP);
+    let cache = CandidateCache::new(fixture.get("#root").into());
+    assert_eq!(ids(&cache.tab_candidates()), ["first", "last"]);
+
+    fixture
+        .get("#first")
+        .set_attribute("disabled", "true")
+        .unwrap();
+    assert_eq!(ids(&cache.tab_candidates()), ["last"]);
+
+    fixture.get("#last").remove();
+    assert!(cache.tab_candidates().is_empty());
+
+    fixture.get("#programmatic").set_tab_index(0);
+    assert_eq!(ids(&cache.tab_candidates()), ["programmatic"]);
+}
+
+#[wasm_bindgen_test]
+fn queries_follow_flattened_tree_of_shadow_hosts() {
+    let fixture = Fixture::new(SLOTTED);
+    let host = fixture.get("#host");
+    attach_shadow(&host);
+
+    assert_eq!(
+        ids(&candidates::tab_candidates(&host)),
+        ["close", "link", "footer", "fallback"]
+    );
+    assert_eq!(
+        candidates::first_tab_candidate(&host).map(|v| v.id()),
+        Some("close".to_string())
+    );
+}
+
+#[wasm_bindgen_test]
+fn cache_follows_slot_assignments() {
+    let fixture = Fixture::new(SLOTTED);
+    let host = fixture.get("#host");
+    let root = attach_shadow(&host);
+    let cache = CandidateCache::new(host.into());
+    assert_eq!(
+        ids(&cache.tab_candidates()),
+        ["close", "link", "footer", "fallback"]
+    );
+
+    fixture
+        .get("#unassigned")
+        .set_attribute("slot", "empty")
+        .unwrap();
+    assert_eq!(
+        ids(&cache.tab_candidates()),
+        ["close", "link", "footer", "unassigned"]
+    );
+
+    root.get_element_by_id("close").unwrap().remove();
+    assert_eq!(
+        ids(&cache.tab_candidates()),
+        ["link", "footer", "unassigned"]
+    );
+}
@@ -0,0 +1,64 @@
+#![cfg(all(target_arch = "wasm32", feature = "overlay"))]
+
+use gloo::utils::document;
+use seigi_focus::overlay::{FocusOrderOverlay, ITEM_ATTRIBUTE, OVERLAY_ATTRIBUTE};
+use seigi_testkit::{Fixture, wait};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::Element;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<div id="root">
+    <button id="first">First</button>
+    <button id="ordered" tabindex="2">Ordered</button>
+    <button id="collapsed" style="width: 0; height: 0; padding: 0; border: 0"></button>
+    <button disabled>Disabled</button>
+</div>
+"#;
+
+fn items() -> Vec<(String, bool, bool)> {
+    let list = document()
+        .query_selector_all(&format!("[{OVERLAY_ATTRIBUTE}] [{ITEM_ATTRIBUTE}]"))
+        .unwrap();
+    (0..list.length())
+        .filter_map(|v| list.item(v))
+        .map(|v| {
+            let element = v.unchecked_into::<Element>();
+            (
+                element.get_attribute(ITEM_ATTRIBUTE).unwrap(),
+                element.has_attribute("data-ordered"),
+                element.has_attribute("data-hidden"),
+            )
+        })
+        .collect()
+}
+
+#[wasm_bindgen_test]
+async fn overlay_numbers_tab_candidates_and_follows_changes() {
+    let fixture = Fixture::new(MARKUP);
+    let overlay = FocusOrderOverlay::new(fixture.get("#root").into());
+
+    assert_eq!(
+        items(),
+        [
+            ("1".to_string(), false, false),
+            ("2".to_string(), true, false),
+            ("3".to_string(), false, true),
+        ]
+    );
+
+    fixture.get("#first").remove();
+    wait::frame().await;
+    wait::frame().await;
+    assert_eq!(items().len(), 2);
+
+    drop(overlay);
+    assert!(
+        document()
+            .query_selector(&format!("[{OVERLAY_ATTRIBUTE}]"))
+            .unwrap()
+            .is_none()
+    );
+}
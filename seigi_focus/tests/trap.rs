@@ -0,0 +1,628 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::Duration,
+};
+
+use seigi_focus::{
+    EventOutcome, FocusTrapEvent, FocusTrapHooks, FocusTrapOptions, InitialFocus, InteractionKind,
+    NavigationPolicy, REJECTED_ATTRIBUTE,
+    recorder::{Decision, Input, Recording},
+};
+use seigi_testkit::{
+    Fixture, assert_focus_outside, assert_focus_within, assert_focused,
+    events::{self, Modifiers},
+    keyboard, pointer, replay, wait,
+};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r##"
+<button id="outside">Outside</button>
+<div id="trap">
+    <button id="first">First</button>
+    <a id="middle" href="#">Middle</a>
+    <button id="last">Last</button>
+</div>
+"##;
+
+fn options(fixture: &Fixture) -> seigi_focus::FocusTrapOptionsBuilder {
+    FocusTrapOptions::builder().target(fixture.get("#trap"))
+}
+
+#[wasm_bindgen_test]
+async fn initial_focus_moves_into_trap() {
+    let fixture = Fixture::new(MARKUP);
+    let trap = seigi_focus::create(options(&fixture).build());
+
+    trap.activate();
+    wait::tick().await;
+    assert!(trap.is_activated());
+    assert_eq!(fixture.active_element(), Some(fixture.get("#first")));
+
+    trap.deactivate();
+}
+
+#[wasm_bindgen_test]
+async fn scheduled_focus_is_cancelled_by_deactivation() {
+    let fixture = Fixture::new(MARKUP);
+    let outside = fixture.get("#outside");
+    outside.focus().unwrap();
+    let trap = seigi_focus::create(options(&fixture).build());
+
+    // Initial focus scheduled by activation never lands in a trap deactivated meanwhile
+    trap.activate();
+    trap.deactivate();
+    wait::tick().await;
+    assert_focused(&outside);
+
+    // Focus returned by deactivation never leaves a trap activated again meanwhile
+    trap.activate();
+    wait::tick().await;
+    trap.deactivate();
+    trap.activate();
+    wait::tick().await;
+    assert_focus_within(&fixture.get("#trap"));
+    trap.deactivate();
+
+    // Dropping the trap still returns focus
+    drop(trap);
+    wait::tick().await;
+    assert_focused(&outside);
+}
+
+#[wasm_bindgen_test]
+async fn initial_focus_selector() {
+    let fixture = Fixture::new(MARKUP);
+    let trap = seigi_focus::create(
+        options(&fixture)
+            .initial_focus(InitialFocus::Selector("#middle".to_string()))
+            .build(),
+    );
+
+    trap.activate();
+    wait::tick().await;
+    assert_eq!(fixture.active_element(), Some(fixture.get("#middle")));
+
+    trap.deactivate();
+}
+
+#[wasm_bindgen_test]
+async fn tab_cycles_from_last_to_first() {
+    let fixture = Fixture::new(MARKUP);
+    let trap = seigi_focus::create(options(&fixture).build());
+    trap.activate();
+    wait::tick().await;
+
+    let last = fixture.get("#last");
+    last.focus().unwrap();
+    let not_cancelled = events::key_down(&last, "Tab", Modifiers::default());
+    wait::tick().await;
+
+    assert!(!not_cancelled);
+    assert_eq!(fixture.active_element(), Some(fixture.get("#first")));
+
+    trap.deactivate();
+}
+
+#[wasm_bindgen_test]
+async fn shift_tab_cycles_from_first_to_last() {
+    let fixture = Fixture::new(MARKUP);
+    let trap = seigi_focus::create(options(&fixture).build());
+    trap.activate();
+    wait::tick().await;
+
+    let first = fixture.get("#first");
+    let not_cancelled = events::key_down(&first, "Tab", Modifiers::shift());
+    wait::tick().await;
+
+    assert!(!not_cancelled);
+    assert_eq!(fixture.active_element(), Some(fixture.get("#last")));
+
+    trap.deactivate();
+}
+
+#[wasm_bindgen_test]
+async fn tab_cycles_through_shadow_tree_and_slots() {
+    let fixture = Fixture::new(
+        r#"<button id="outside">Outside</button><div id="trap"><button id="footer" slot="footer">Footer</button></div>"#,
+    );
+    let target = fixture.get("#trap");
+    let root = target
+        .attach_shadow(&web_sys::ShadowRootInit::new(web_sys::ShadowRootMode::Open))
+        .unwrap();
+    root.set_inner_html(r#"<button id="close">Close</button><slot name="footer"></slot>"#);
+    let close: web_sys::HtmlElement = root.get_element_by_id("close").unwrap().unchecked_into();
+    let trap = seigi_focus::create(options(&fixture).build());
+    trap.activate();
+    wait::tick().await;
+    assert_eq!(root.active_element(), Some(close.clone().into()));
+
+    let footer = fixture.get("#footer");
+    footer.focus().unwrap();
+    let not_cancelled = events::key_down(&footer, "Tab", Modifiers::default());
+    wait::tick().await;
+    assert!(!not_cancelled);
+    assert_eq!(root.active_element(), Some(close.clone().into()));
+
+    let not_cancelled = events::key_down(&close, "Tab", Modifiers::shift());
+    wait::tick().await;
+    assert!(!not_cancelled);
+    assert_focused(&footer);
+
+    trap.deactivate();
+}
+
+#[wasm_bindgen_test]
+async fn tab_within_trap_is_left_to_browser() {
+    let fixture = Fixture::new(MARKUP);
+    let trap = seigi_focus::create(options(&fixture).build());
+    trap.activate();
+    wait::tick().await;
+
+    let first = fixture.get("#first");
+    assert!(events::key_down(&first, "Tab", Modifiers::default()));
+
+    trap.deactivate();
+}
+
+#[wasm_bindgen_test]
+async fn escape_deactivates_and_returns_focus() {
+    let fixture = Fixture::new(MARKUP);
+    let deactivated = Rc::new(Cell::new(false));
+    let trap = seigi_focus::create(
+        options(&fixture)
+            .deactivate_on_escape(true)
+            .hooks(FocusTrapHooks {
+                activate: None,
+                deactivate: Some(Box::new({
+                    let deactivated = deactivated.clone();
+                    move || deactivated.set(true)
+                })),
+            })
+            .build(),
+    );
+
+    let outside = fixture.get("#outside");
+    outside.focus().unwrap();
+    trap.activate();
+    wait::tick().await;
+
+    events::key_down(&fixture.get("#first"), "Escape", Modifiers::default());
+    wait::tick().await;
+
+    assert!(!trap.is_activated());
+    assert!(deactivated.get());
+    assert_eq!(fixture.active_element(), Some(outside));
+}
+
+#[wasm_bindgen_test]
+async fn escape_is_ignored_unless_enabled() {
+    let fixture = Fixture::new(MARKUP);
+    let trap = seigi_focus::create(options(&fixture).deactivate_on_escape(false).build());
+    trap.activate();
+    wait::tick().await;
+
+    events::key_down(&fixture.get("#first"), "Escape", Modifiers::default());
+    assert!(trap.is_activated());
+
+    trap.deactivate();
+}
+
+#[wasm_bindgen_test]
+async fn keys_composing_text_are_left_to_ime() {
+    let fixture = Fixture::new(MARKUP);
+    let trap = seigi_focus::create(options(&fixture).deactivate_on_escape(true).build());
+    trap.activate();
+    wait::tick().await;
+
+    let last = fixture.get("#last");
+    last.focus().unwrap();
+    assert!(events::composing_key_down(&last, "Escape"));
+    assert!(events::composing_key_down(&last, "Tab"));
+    wait::tick().await;
+    assert!(trap.is_activated());
+    assert_focused(&last);
+
+    trap.deactivate();
+}
+
+#[wasm_bindgen_test]
+async fn clicks_outside_are_blocked() {
+    let fixture = Fixture::new(MARKUP);
+    let trap = seigi_focus::create(options(&fixture).build());
+    trap.activate();
+    wait::tick().await;
+
+    assert!(!events::click(&fixture.get("#outside")));
+    assert!(events::click(&fixture.get("#first")));
+
+    trap.deactivate();
+    assert!(events::click(&fixture.get("#outside")));
+}
+
+#[wasm_bindgen_test]
+async fn keyboard_users_stay_within_trap() {
+    let fixture = Fixture::new(MARKUP);
+    let trap = seigi_focus::create(options(&fixture).deactivate_on_escape(true).build());
+    let container = fixture.get("#trap");
+    fixture.get("#outside").focus().unwrap();
+    trap.activate();
+    wait::tick().await;
+
+    for _ in 0..5 {
+        keyboard::tab().await;
+        assert_focus_within(&container);
+    }
+    for _ in 0..5 {
+        keyboard::shift_tab().await;
+        assert_focus_within(&container);
+    }
+
+    keyboard::escape().await;
+    assert_focus_outside(&container);
+    assert_focused(&fixture.get("#outside"));
+}
+
+#[wasm_bindgen_test]
+async fn pointer_outside_keeps_focus_within_trap() {
+    let fixture = Fixture::new(MARKUP);
+    let trap = seigi_focus::create(options(&fixture).build());
+    trap.activate();
+    wait::tick().await;
+
+    let outcome = pointer::click(&fixture.get("#outside")).await;
+    assert!(!outcome.pressed);
+    assert!(!outcome.clicked);
+    assert_focused(&fixture.get("#first"));
+
+    let outcome = pointer::click(&fixture.get("#last")).await;
+    assert!(outcome.pressed && outcome.clicked);
+    assert_focused(&fixture.get("#last"));
+
+    trap.deactivate();
+}
+
+#[wasm_bindgen_test]
+async fn press_outside_never_blurs_trap() {
+    let fixture = Fixture::new(MARKUP);
+    let trap = seigi_focus::create(options(&fixture).build());
+    trap.activate();
+    wait::tick().await;
+    let blurs = Rc::new(Cell::new(0));
+    let _listener = gloo::events::EventListener::new(&fixture.get("#trap"), "focusout", {
+        let blurs = blurs.clone();
+        move |_| blurs.set(blurs.get() + 1)
+    });
+
+    let outside = fixture.get("#outside");
+    assert!(!pointer::down(&outside));
+    assert_focused(&fixture.get("#first"));
+    pointer::up(&outside);
+    wait::tick().await;
+    assert_eq!(blurs.get(), 0);
+
+    // pointerdown is left alone, so presses outside still scroll, and taps are blocked through
+    // the mousedown browsers dispatch for them
+    assert!(events::dispatch(
+        &outside,
+        &events::pointer_event("pointerdown", 0.0, 0.0)
+    ));
+    trap.start_recording();
+    assert!(!events::dispatch(
+        &outside,
+        &events::mouse_event("mousedown", 0.0, 0.0)
+    ));
+    let recording = trap.take_recording().unwrap();
+    assert_eq!(recording.entries.len(), 1);
+    assert_eq!(recording.entries[0].input, Input::PointerDown);
+    assert_eq!(recording.entries[0].decision, Decision::Blocked);
+
+    trap.deactivate();
+}
+
+#[wasm_bindgen_test]
+async fn attributes_follow_trap_state() {
+    let fixture = Fixture::new(MARKUP);
+    let trap = seigi_focus::create(options(&fixture).build());
+    let target = fixture.get("#trap");
+
+    seigi_focus::set_debugging(true);
+    trap.activate();
+    assert!(target.has_attribute(seigi_focus::ACTIVE_ATTRIBUTE));
+    assert_eq!(
+        target.get_attribute(seigi_focus::ID_ATTRIBUTE),
+        Some(trap.id())
+    );
+    let tab_index = |selector: &str| {
+        fixture
+            .get(selector)
+            .get_attribute(seigi_focus::TAB_INDEX_ATTRIBUTE)
+    };
+    assert_eq!(tab_index("#first").as_deref(), Some("0"));
+    assert_eq!(tab_index("#last").as_deref(), Some("2"));
+    assert_eq!(tab_index("#outside"), None);
+
+    trap.deactivate();
+    seigi_focus::set_debugging(false);
+    assert!(!target.has_attribute(seigi_focus::ACTIVE_ATTRIBUTE));
+    assert_eq!(tab_index("#first"), None);
+}
+
+#[wasm_bindgen_test]
+async fn allowed_zones_stay_interactive() {
+    let fixture = Fixture::new(MARKUP);
+    let trap = seigi_focus::create(options(&fixture).build());
+    let zone = seigi_utils::outside::allow(&fixture.get("#outside"));
+    trap.activate();
+    wait::tick().await;
+
+    let outcome = pointer::click(&fixture.get("#outside")).await;
+    assert!(outcome.pressed && outcome.clicked);
+    fixture.get("#outside").focus().unwrap();
+    wait::tick().await;
+    assert_focused(&fixture.get("#outside"));
+
+    seigi_utils::outside::disallow(zone);
+    let outcome = pointer::click(&fixture.get("#outside")).await;
+    assert!(!outcome.pressed);
+
+    trap.deactivate();
+}
+
+#[wasm_bindgen_test]
+async fn blocked_interactions_are_reported() {
+    let fixture = Fixture::new(MARKUP);
+    let trap = seigi_focus::create(options(&fixture).build());
+    let interactions = Rc::new(RefCell::new(vec![]));
+    trap.subscribe(Box::new({
+        let interactions = interactions.clone();
+        move |event: &FocusTrapEvent| {
+            let FocusTrapEvent::OutsideInteraction { kind, target } = event;
+            interactions.borrow_mut().push((*kind, target.id()));
+        }
+    }));
+    trap.activate();
+    wait::tick().await;
+
+    pointer::click(&fixture.get("#last")).await;
+    pointer::click(&fixture.get("#outside")).await;
+    fixture.get("#outside").focus().unwrap();
+    wait::tick().await;
+    assert_eq!(
+        *interactions.borrow(),
+        [
+            (InteractionKind::Pointer, "outside".to_string()),
+            (InteractionKind::Focus, "outside".to_string()),
+        ]
+    );
+
+    trap.deactivate();
+    pointer::click(&fixture.get("#outside")).await;
+    assert_eq!(interactions.borrow().len(), 2);
+}
+
+#[wasm_bindgen_test]
+async fn blocked_presses_mark_target_rejected() {
+    let fixture = Fixture::new(MARKUP);
+    let trap = seigi_focus::create(
+        options(&fixture)
+            .rejected_feedback(Some(Duration::from_millis(50)))
+            .build(),
+    );
+    let target = fixture.get("#trap");
+    trap.activate();
+    wait::tick().await;
+
+    pointer::click(&fixture.get("#last")).await;
+    assert!(!target.has_attribute(REJECTED_ATTRIBUTE));
+    pointer::click(&fixture.get("#outside")).await;
+    assert!(target.has_attribute(REJECTED_ATTRIBUTE));
+    wait::sleep(100).await;
+    assert!(!target.has_attribute(REJECTED_ATTRIBUTE));
+
+    // Deactivating ends the feedback at once
+    pointer::click(&fixture.get("#outside")).await;
+    trap.deactivate();
+    assert!(!target.has_attribute(REJECTED_ATTRIBUTE));
+}
+
+#[wasm_bindgen_test]
+async fn recording_replays_against_fixture() {
+    let fixture = Fixture::new(MARKUP);
+    let trap = seigi_focus::create(options(&fixture).scope(fixture.root()).build());
+    trap.activate();
+    wait::tick().await;
+    trap.start_recording();
+
+    fixture.get("#last").focus().unwrap();
+    keyboard::tab().await;
+    pointer::click(&fixture.get("#outside")).await;
+    let recording = trap.take_recording().unwrap();
+    trap.deactivate();
+
+    let tab = &recording.entries[1];
+    assert_eq!(tab.target, "div:nth-child(2) > button:nth-child(3)");
+    assert_eq!(
+        tab.input,
+        Input::KeyDown {
+            key: "Tab".to_string(),
+            shift: false,
+        }
+    );
+    assert!(matches!(tab.decision, Decision::Moved { .. }));
+    // Focus wrapped around by the trap follows the Tab
+    assert!(recording.entries[2].follow_up);
+    assert!(recording.decisions().contains(&Decision::Blocked));
+
+    // Replayed against the same markup mounted again, the trap decides the same
+    let recording = Recording::from_json(&recording.to_json()).unwrap();
+    drop(fixture);
+    let fixture = Fixture::new(MARKUP);
+    let trap = seigi_focus::create(options(&fixture).scope(fixture.root()).build());
+    trap.activate();
+    wait::tick().await;
+    trap.start_recording();
+    replay::replay(&fixture.root(), &recording).await;
+    assert_eq!(
+        trap.take_recording().unwrap().decisions(),
+        recording.decisions()
+    );
+
+    trap.deactivate();
+}
+
+#[wasm_bindgen_test]
+async fn trap_manages_focus_in_iframe() {
+    let fixture = Fixture::new(r#"<iframe id="frame"></iframe>"#);
+    let frame = fixture
+        .get("#frame")
+        .unchecked_into::<web_sys::HtmlIFrameElement>();
+    let document = frame.content_document().unwrap();
+    document.body().unwrap().set_inner_html(MARKUP);
+    let get = |selector: &str| {
+        document
+            .query_selector(selector)
+            .unwrap()
+            .unwrap()
+            .unchecked_into::<web_sys::HtmlElement>()
+    };
+
+    // The document of the target is managed without being given
+    let trap = seigi_focus::create(FocusTrapOptions::builder().target(get("#trap")).build());
+    assert_eq!(trap.options().document, document);
+    assert_eq!(trap.options().scope, document.body().unwrap());
+
+    trap.activate();
+    wait::tick().await;
+    assert_eq!(document.active_element(), Some(get("#first").into()));
+
+    get("#outside").focus().unwrap();
+    wait::tick().await;
+    assert_eq!(document.active_element(), Some(get("#first").into()));
+
+    trap.deactivate();
+}
+
+#[wasm_bindgen_test]
+async fn back_navigation_deactivates_trap() {
+    let fixture = Fixture::new(MARKUP);
+    let trap = seigi_focus::create(options(&fixture).build());
+    let history = gloo::utils::window().history().unwrap();
+    history
+        .push_state_with_url(&JsValue::NULL, "", Some("#dialog"))
+        .unwrap();
+    trap.activate();
+    wait::tick().await;
+
+    history.back().unwrap();
+    // Traversals through history are asynchronous
+    wait::sleep(100).await;
+
+    assert!(!trap.is_activated());
+}
+
+#[wasm_bindgen_test]
+async fn guard_decides_whether_navigation_goes_on() {
+    let fixture = Fixture::new(MARKUP);
+    let is_allowed = Rc::new(Cell::new(false));
+    let trap = seigi_focus::create(
+        options(&fixture)
+            .navigation(NavigationPolicy::Guard(Box::new({
+                let is_allowed = is_allowed.clone();
+                move || is_allowed.get()
+            })))
+            .build(),
+    );
+    trap.activate();
+    wait::tick().await;
+
+    assert!(!trap.before_navigation());
+    assert!(trap.is_activated());
+
+    is_allowed.set(true);
+    assert!(trap.before_navigation());
+    assert!(!trap.is_activated());
+
+    let ignoring = seigi_focus::create(
+        options(&fixture)
+            .navigation(NavigationPolicy::Ignore)
+            .build(),
+    );
+    ignoring.activate();
+    assert!(ignoring.before_navigation());
+    assert!(ignoring.is_activated());
+    ignoring.deactivate();
+}
+
+#[wasm_bindgen_test]
+async fn consumed_events_are_left_to_interceptor() {
+    let fixture = Fixture::new(MARKUP);
+    let submitted = Rc::new(Cell::new(0));
+    let trap = seigi_focus::create(
+        options(&fixture)
+            .on_event({
+                let submitted = submitted.clone();
+                move |event| {
+                    let Some(event) = event.dyn_ref::<web_sys::KeyboardEvent>() else {
+                        return EventOutcome::Continue;
+                    };
+                    let target = event
+                        .target()
+                        .and_then(|v| v.dyn_into::<web_sys::HtmlElement>().ok());
+                    if event.key() == "Tab" && target.is_some_and(|v| v.id() == "last") {
+                        event.prevent_default();
+                        submitted.set(submitted.get() + 1);
+                        return EventOutcome::Consumed;
+                    }
+                    EventOutcome::Continue
+                }
+            })
+            .build(),
+    );
+    trap.activate();
+    wait::tick().await;
+    trap.start_recording();
+
+    let last = fixture.get("#last");
+    last.focus().unwrap();
+    let not_cancelled = events::key_down(&last, "Tab", Modifiers::default());
+    wait::tick().await;
+
+    // The trap doesn't wrap focus around, as the interceptor submitted instead
+    assert!(!not_cancelled);
+    assert_eq!(submitted.get(), 1);
+    assert_focused(&last);
+    let decisions = trap.take_recording().unwrap().decisions();
+    assert_eq!(decisions.last(), Some(&Decision::Consumed));
+
+    // Other keys are handled as usual
+    let first = fixture.get("#first");
+    first.focus().unwrap();
+    events::key_down(&first, "Tab", Modifiers::shift());
+    wait::tick().await;
+    assert_eq!(submitted.get(), 1);
+    assert_focused(&last);
+
+    trap.deactivate();
+}
+
+#[wasm_bindgen_test]
+fn scroll_lock_is_shared_while_activated() {
+    let fixture = Fixture::new(MARKUP);
+    let trap = seigi_focus::create(options(&fixture).scroll_lock(true).build());
+    assert!(!seigi_utils::scroll_lock::is_locked());
+
+    trap.activate();
+    assert!(seigi_utils::scroll_lock::is_locked());
+    let sheet = seigi_utils::scroll_lock::lock();
+    trap.deactivate();
+    // The lock of another layer keeps the document locked
+    assert!(seigi_utils::scroll_lock::is_locked());
+    sheet.unlock();
+    assert!(!seigi_utils::scroll_lock::is_locked());
+}
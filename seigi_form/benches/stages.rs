@@ -0,0 +1,50 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::time::Duration;
+
+use seigi_form::multi_stage::{Form, Stage};
+use seigi_testkit::{Fixture, perf};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Stages of the form, each with a large document of [CANDIDATES] tab candidates
+const STAGES: usize = 10;
+const CANDIDATES: usize = 500;
+
+#[wasm_bindgen_bench]
+fn stage_switch(c: &mut Criterion) {
+    let stages = (0..STAGES)
+        .map(|_| {
+            format!(
+                "<div data-seigi-stage>{}</div>",
+                perf::large_document(CANDIDATES)
+            )
+        })
+        .collect::<String>();
+    let fixture = Fixture::new(&format!(r#"<div id="form">{stages}</div>"#));
+    let form = Form::builder()
+        .container(fixture.get("#form"))
+        .add_stages(
+            fixture
+                .get_all("[data-seigi-stage]")
+                .into_iter()
+                .map(Stage::from_container),
+        )
+        .build()
+        .unwrap();
+    form.initialize();
+    form.activate();
+
+    let mut target = 0;
+    let mut switch = move || {
+        target = (target + 1) % STAGES;
+        form.stage(target);
+    };
+    c.bench_function("stage switch", |b| b.iter(&mut switch));
+    perf::assert_budget(
+        "stage switch",
+        Duration::from_millis(2),
+        perf::median(11, &mut switch),
+    );
+}
@@ -0,0 +1,406 @@
+//! Headless error summary listing invalid fields as links to them
+//!
+//! Following the GOV.UK error summary pattern, an [ErrorSummary] collects errors of validators,
+//! including those of stages of a [Form], into a list of links in its container. Showing it moves
+//! focus to the container and announces the problem through [announcer], and activating a link
+//! moves focus to the field, moving the form to the stage of the field first.
+//!
+//! The heading and any other content of the container are left to the markup.
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::timers::callback::Timeout;
+use seigi_focus::candidates;
+use seigi_utils::{
+    announcer,
+    diagnostics::{self, Resource},
+    i18n, id,
+    listener::Listener,
+    outside,
+};
+use seigi_validate::Validator;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, Event, HtmlAnchorElement, HtmlElement};
+
+use crate::{
+    Error,
+    multi_stage::{Form, FormEvent},
+};
+
+/// Attribute set in the container
+pub const ATTRIBUTE: &str = "data-seigi-error-summary";
+/// Attribute set in the list of links
+pub const LIST_ATTRIBUTE: &str = "data-seigi-error-summary-list";
+/// Attribute set in each link to the index of its item in [ErrorSummary::items]
+pub const LINK_ATTRIBUTE: &str = "data-seigi-error-summary-link";
+
+/// An invalid field listed in the summary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummaryItem {
+    /// The name of the field in its validator
+    pub name: String,
+    /// The first error message of the field
+    pub message: String,
+    pub element: HtmlElement,
+    /// The stage of the form the field is in, if it is validated by a stage
+    pub stage: Option<usize>,
+}
+
+/// Actual implementation of [ErrorSummary]
+struct Inner {
+    container: HtmlElement,
+    list: HtmlElement,
+    /// Whether the list was created by the summary, which removes it when dropped
+    owns_list: bool,
+    form: Option<Form>,
+    /// Validators with the stages they validate
+    sources: Vec<(Option<usize>, Validator)>,
+    subscriptions: Vec<(Validator, u64)>,
+    form_subscription: Option<u64>,
+    outside_handle: u64,
+    items: Vec<SummaryItem>,
+    is_shown: bool,
+    _listener: Listener,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        for (validator, handle) in &self.subscriptions {
+            validator.unsubscribe(*handle);
+        }
+        if let (Some(form), Some(handle)) = (&self.form, self.form_subscription) {
+            form.unsubscribe_events(handle);
+        }
+        outside::disallow(self.outside_handle);
+        if self.owns_list {
+            self.list.remove();
+        }
+    }
+}
+
+/// An instance of error summary
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// While shown, the summary follows changes of errors, and hides itself once none is left. With
+/// a form, it is shown whenever the form keeps the user in a stage by invalid fields.
+///
+/// # Attributes
+/// **data-seigi-error-summary** is set in the container, which is also made focusable by
+/// `tabindex="-1"` and hidden by `hidden` while the summary isn't shown
+///
+/// **data-seigi-error-summary-list** is set in the list of links, which is a `ul` appended to the
+/// container unless given by [ErrorSummaryBuilder::list]
+///
+/// **data-seigi-error-summary-link** is set in each link to the index of its item, and the link
+/// points to the id of the field, which is generated if missing
+#[derive(Clone)]
+pub struct ErrorSummary(Rc<Mutex<Inner>>);
+
+impl ErrorSummary {
+    pub fn builder() -> ErrorSummaryBuilder {
+        ErrorSummaryBuilder::new()
+    }
+
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    /// Returns invalid fields currently listed
+    pub fn items(&self) -> Vec<SummaryItem> {
+        self.0.lock().unwrap().items.clone()
+    }
+
+    /// Resolve the shown state of the summary
+    pub fn is_shown(&self) -> bool {
+        self.0.lock().unwrap().is_shown
+    }
+
+    /// Collects errors in the order of stages and fields
+    fn collect(&self) -> Vec<SummaryItem> {
+        let sources = self.0.lock().unwrap().sources.clone();
+        let mut items = vec![];
+        for (stage, validator) in sources {
+            for name in validator.fields() {
+                let Some(error) = validator.errors(&name).into_iter().next() else {
+                    continue;
+                };
+                let Some(element) = validator.element(&name) else {
+                    continue;
+                };
+                items.push(SummaryItem {
+                    name,
+                    message: error.message,
+                    element,
+                    stage,
+                });
+            }
+        }
+        items
+    }
+
+    /// Renders links of the items into the list
+    fn render(&self, items: Vec<SummaryItem>) {
+        let mut inner = self.0.lock().unwrap();
+        let Some(document) = inner.list.owner_document() else {
+            return;
+        };
+        inner.list.set_inner_html("");
+        for (index, item) in items.iter().enumerate() {
+            let (Ok(entry), Ok(link)) =
+                (document.create_element("li"), document.create_element("a"))
+            else {
+                continue;
+            };
+            let link = link.unchecked_into::<HtmlAnchorElement>();
+            let id = id::ensure(&item.element, "field");
+            link.set_href(&format!("#{id}"));
+            let _ = link.set_attribute(LINK_ATTRIBUTE, &index.to_string());
+            link.set_text_content(Some(&item.message));
+            let _ = entry.append_child(&link);
+            let _ = inner.list.append_child(&entry);
+        }
+        inner.items = items;
+    }
+
+    /// Lists errors of every validator, and shows the summary if there is any
+    ///
+    /// Focus moves to the container, and the count of errors is announced assertively.
+    ///
+    /// # Returns
+    /// Whether there were errors
+    pub fn show(&self) -> bool {
+        let items = self.collect();
+        if items.is_empty() {
+            self.hide();
+            return false;
+        }
+        let count = items.len();
+        self.render(items);
+        let container = {
+            let mut inner = self.0.lock().unwrap();
+            inner.is_shown = true;
+            inner.container.clone()
+        };
+        let _ = container.remove_attribute("hidden");
+        let _ = container.focus();
+        announcer::assertive(i18n::translate("form.error_summary", &[("count", &count)]));
+        true
+    }
+
+    /// Hides the summary
+    pub fn hide(&self) {
+        let mut inner = self.0.lock().unwrap();
+        inner.is_shown = false;
+        inner.items.clear();
+        inner.list.set_inner_html("");
+        let _ = inner.container.set_attribute("hidden", "");
+    }
+
+    /// Lists errors again following a change while shown
+    fn refresh(&self) {
+        if !self.is_shown() {
+            return;
+        }
+        let items = self.collect();
+        if items.is_empty() {
+            self.hide();
+        } else {
+            self.render(items);
+        }
+    }
+
+    /// Focuses the field of the item, moving the form to its stage first
+    ///
+    /// Fields which aren't focusable themselves, such as groups of radio buttons, have their
+    /// first focusable descendant focused instead.
+    ///
+    /// # Returns
+    /// Whether the item exists
+    pub fn focus_item(&self, index: usize) -> bool {
+        let (item, form) = {
+            let inner = self.0.lock().unwrap();
+            let Some(item) = inner.items.get(index).cloned() else {
+                return false;
+            };
+            (item, inner.form.clone())
+        };
+        // Focus is moved after the focus trap of the stage moves it initially
+        let focus = move || {
+            Timeout::new(0, move || {
+                let target = if candidates::is_focusable(&item.element) {
+                    Some(item.element)
+                } else {
+                    candidates::first_focus_candidate(&item.element)
+                };
+                if let Some(target) = target {
+                    let _ = target.focus();
+                }
+            })
+            .forget();
+            diagnostics::track(Resource::Timeout).forget();
+        };
+        match (form, item.stage) {
+            (Some(form), Some(stage)) if form.current() != stage => form.stage_then(stage, focus),
+            _ => focus(),
+        }
+        true
+    }
+
+    fn handle_click(&self, event: &Event) {
+        let Some(link) = event
+            .target()
+            .and_then(|v| v.dyn_into::<Element>().ok())
+            .and_then(|v| v.closest(&format!("[{LINK_ATTRIBUTE}]")).ok().flatten())
+        else {
+            return;
+        };
+        let Some(index) = link
+            .get_attribute(LINK_ATTRIBUTE)
+            .and_then(|v| v.parse().ok())
+        else {
+            return;
+        };
+        event.prevent_default();
+        self.focus_item(index);
+    }
+}
+
+/// A builder struct for [ErrorSummary]
+#[derive(Default)]
+pub struct ErrorSummaryBuilder {
+    container: Option<HtmlElement>,
+    list: Option<HtmlElement>,
+    form: Option<Form>,
+    validators: Vec<Validator>,
+}
+
+impl ErrorSummaryBuilder {
+    /// Creates a new [ErrorSummaryBuilder]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets container element for the summary
+    pub fn container(mut self, container: HtmlElement) -> Self {
+        self.container = Some(container);
+        self
+    }
+
+    /// Sets the element links are rendered into, instead of a `ul` appended to the container
+    pub fn list(mut self, list: HtmlElement) -> Self {
+        self.list = Some(list);
+        self
+    }
+
+    /// Sets the form whose stage validators are summarized, in the order of stages
+    ///
+    /// The summary is shown whenever the form reports [FormEvent::ValidationFailed].
+    pub fn form(mut self, form: Form) -> Self {
+        self.form = Some(form);
+        self
+    }
+
+    /// Adds a validator to summarize, after those of the form
+    pub fn validator(mut self, validator: Validator) -> Self {
+        self.validators.push(validator);
+        self
+    }
+
+    /// Builds into [ErrorSummary]
+    ///
+    /// # Errors
+    /// Returns an error if the container isn't set, or the list can't be created
+    pub fn build(self) -> Result<ErrorSummary, Error> {
+        let container = self.container.ok_or(Error::MissingContainer)?;
+        let (list, owns_list) = match self.list {
+            Some(list) => (list, false),
+            None => {
+                let list = container
+                    .owner_document()
+                    .ok_or_else(|| Error::Browser("container has no document".to_string()))?
+                    .create_element("ul")
+                    .map_err(|v| Error::Browser(seigi_utils::error::describe(&v)))?
+                    .unchecked_into::<HtmlElement>();
+                let _ = container.append_child(&list);
+                (list, true)
+            }
+        };
+        let _ = container.set_attribute(ATTRIBUTE, "");
+        let _ = list.set_attribute(LIST_ATTRIBUTE, "");
+        if !container.has_attribute("tabindex") {
+            container.set_tab_index(-1);
+        }
+        let _ = container.set_attribute("hidden", "");
+
+        let mut sources: Vec<(Option<usize>, Validator)> = self
+            .form
+            .iter()
+            .flat_map(|v| v.validators())
+            .map(|(stage, validator)| (Some(stage), validator))
+            .collect();
+        sources.extend(self.validators.into_iter().map(|v| (None, v)));
+
+        let outside_handle = outside::allow(&container);
+        let summary = ErrorSummary(Rc::new_cyclic(|weak: &Weak<Mutex<Inner>>| {
+            let mut listener = Listener::new();
+            let handler = weak.clone();
+            listener.on(&list, "click", move |event| {
+                if let Some(summary) = ErrorSummary::from_weak(&handler) {
+                    summary.handle_click(event);
+                }
+            });
+
+            let subscriptions = sources
+                .iter()
+                .map(|(_, validator)| {
+                    let weak = weak.clone();
+                    let handle = validator.subscribe(Box::new(move |_, _| {
+                        if let Some(summary) = ErrorSummary::from_weak(&weak) {
+                            summary.refresh();
+                        }
+                    }));
+                    (validator.clone(), handle)
+                })
+                .collect();
+
+            let form_subscription = self.form.as_ref().map(|form| {
+                let weak = weak.clone();
+                form.subscribe_events(Box::new(move |event| {
+                    if let FormEvent::ValidationFailed { .. } = event
+                        && let Some(summary) = ErrorSummary::from_weak(&weak)
+                    {
+                        summary.show();
+                    }
+                }))
+            });
+
+            Mutex::new(Inner {
+                container,
+                list,
+                owns_list,
+                form: self.form,
+                sources,
+                subscriptions,
+                form_subscription,
+                outside_handle,
+                items: vec![],
+                is_shown: false,
+                _listener: listener,
+            })
+        }));
+        Ok(summary)
+    }
+
+    /// Builds into [ErrorSummary], like [ErrorSummaryBuilder::build]
+    ///
+    /// # Panics
+    /// This method panics if [ErrorSummaryBuilder::build] returns an error
+    pub fn build_unchecked(self) -> ErrorSummary {
+        self.build().unwrap_or_else(|error| panic!("{error}"))
+    }
+}
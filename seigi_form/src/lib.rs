@@ -1,3 +1,33 @@
 //! Form validation and multi-staged forms
 
+pub mod error_summary;
 pub mod multi_stage;
+
+use std::fmt::{self, Display};
+
+/// An error building a form
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The container of the form or the error summary isn't set
+    MissingContainer,
+    /// The initial stage isn't one of the stages, including when there are no stages
+    InitialStageOutOfRange { initial_stage: usize, len: usize },
+    /// The browser failed to create an object the form needs, described by
+    /// [seigi_utils::error::describe]
+    Browser(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingContainer => write!(f, "container must be set"),
+            Self::InitialStageOutOfRange { initial_stage, len } => write!(
+                f,
+                "initial stage {initial_stage} must be less than the stage count {len}"
+            ),
+            Self::Browser(message) => write!(f, "failed to build Form: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,128 @@
+//! Dirty state of fields, which are dirty while their values differ from the initial ones
+//!
+//! Initial values are taken from every control in the stages when the form is built, and again
+//! by [Form::mark_pristine](super::Form::mark_pristine). Controls added later, like those of
+//! stages rendered by loaders, start from the defaults of their markup instead, such as the
+//! `value` attribute of inputs.
+
+use wasm_bindgen::JsCast;
+use web_sys::{
+    Element, HtmlElement, HtmlInputElement, HtmlOptionElement, HtmlSelectElement,
+    HtmlTextAreaElement,
+};
+
+/// Controls holding values the user edits, which leaves out buttons and hidden inputs
+const CONTROLS: &str = "input:not([type=hidden], [type=submit], [type=reset], [type=button], \
+                        [type=image]), select, textarea";
+
+/// Returns controls in the container in document order
+pub(super) fn controls(container: &Element) -> Vec<HtmlElement> {
+    let Ok(list) = container.query_selector_all(CONTROLS) else {
+        return vec![];
+    };
+    (0..list.length())
+        .filter_map(|v| list.item(v))
+        .filter_map(|v| v.dyn_into::<HtmlElement>().ok())
+        .collect()
+}
+
+/// Returns the name of the field of the control, which is its `name` or else its `id`
+pub(super) fn field_name(control: &HtmlElement) -> Option<String> {
+    control
+        .get_attribute("name")
+        .filter(|v| !v.is_empty())
+        .or_else(|| Some(control.id()).filter(|v| !v.is_empty()))
+}
+
+/// Returns the value of the control, which is whether it is checked for checkboxes and radios
+fn value(control: &HtmlElement) -> String {
+    if let Some(input) = control.dyn_ref::<HtmlInputElement>() {
+        if is_checkable(input) {
+            return input.checked().to_string();
+        }
+        input.value()
+    } else if let Some(select) = control.dyn_ref::<HtmlSelectElement>() {
+        selected(select, HtmlOptionElement::selected)
+    } else if let Some(textarea) = control.dyn_ref::<HtmlTextAreaElement>() {
+        textarea.value()
+    } else {
+        String::new()
+    }
+}
+
+/// Returns the value the markup of the control gives it, which resetting the form restores
+fn default_value(control: &HtmlElement) -> String {
+    if let Some(input) = control.dyn_ref::<HtmlInputElement>() {
+        if is_checkable(input) {
+            return input.default_checked().to_string();
+        }
+        input.default_value()
+    } else if let Some(select) = control.dyn_ref::<HtmlSelectElement>() {
+        let value = selected(select, HtmlOptionElement::default_selected);
+        // Single selects without a default select their first option
+        if value.is_empty() && !select.multiple() {
+            options(select)
+                .first()
+                .map(HtmlOptionElement::value)
+                .unwrap_or_default()
+        } else {
+            value
+        }
+    } else if let Some(textarea) = control.dyn_ref::<HtmlTextAreaElement>() {
+        textarea.default_value().unwrap_or_default()
+    } else {
+        String::new()
+    }
+}
+
+fn is_checkable(input: &HtmlInputElement) -> bool {
+    matches!(input.type_().as_str(), "checkbox" | "radio")
+}
+
+fn options(select: &HtmlSelectElement) -> Vec<HtmlOptionElement> {
+    let Ok(list) = select.query_selector_all("option") else {
+        return vec![];
+    };
+    (0..list.length())
+        .filter_map(|v| list.item(v))
+        .filter_map(|v| v.dyn_into::<HtmlOptionElement>().ok())
+        .collect()
+}
+
+/// Returns values of the options picked by the predicate, one per line
+fn selected(select: &HtmlSelectElement, is_selected: fn(&HtmlOptionElement) -> bool) -> String {
+    options(select)
+        .iter()
+        .filter(|v| is_selected(v))
+        .map(HtmlOptionElement::value)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Initial values of controls to tell dirty fields with
+#[derive(Default)]
+pub(super) struct Baseline(Vec<(HtmlElement, String)>);
+
+impl Baseline {
+    /// Takes the values of controls in the containers as initial ones
+    pub(super) fn snapshot<'a>(containers: impl Iterator<Item = &'a HtmlElement>) -> Self {
+        Self(
+            containers
+                .flat_map(|v| controls(v))
+                .map(|v| {
+                    let value = value(&v);
+                    (v, value)
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns whether the value of the control differs from its initial one
+    pub(super) fn is_dirty(&self, control: &HtmlElement) -> bool {
+        let current = value(control);
+        match self.0.iter().find(|(v, _)| v == control) {
+            Some((_, initial)) => current != *initial,
+            None => current != default_value(control),
+        }
+    }
+}
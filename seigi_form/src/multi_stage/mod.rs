@@ -1,35 +1,349 @@
 //! Headless multi staged form with support of user visuals
 
+mod dirty;
+mod repeat;
+
 use std::{
+    future::Future,
+    pin::Pin,
     rc::{Rc, Weak},
-    sync::Mutex,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use seigi_focus::{FocusTrap, FocusTrapOptions, InitialFocus, candidates};
+use seigi_utils::{
+    announcer,
+    diagnostics::{self, Resource, Tracked},
+    i18n, keyboard,
+    listener::Listener,
+    media,
+    refs::Refs,
+    schedule, view_transitions,
+};
+
+use crate::Error;
+use repeat::Repeat;
+use seigi_validate::Validator;
+use wasm_bindgen::{JsCast, JsValue, prelude::Closure};
+use web_sys::{
+    BeforeUnloadEvent, FocusOptions, HtmlElement, HtmlTemplateElement, ResizeObserver,
+    ScrollBehavior, ScrollIntoViewOptions, ScrollLogicalPosition, Url, UrlSearchParams,
 };
 
-use seigi_focus::{FocusTrap, FocusTrapOptions};
-use wasm_bindgen::{JsCast, prelude::Closure};
-use web_sys::{HtmlElement, ResizeObserver};
+/// Controls disabled by [Form::set_readonly], which include buttons moving between stages
+const READONLY_CONTROLS: &str = "input:not([disabled]), select:not([disabled]), \
+                                 textarea:not([disabled]), button:not([disabled])";
+
+/// Loads content of a stage, like [Stage::loader]
+pub type Loader = Rc<dyn Fn() -> Pin<Box<dyn Future<Output = ()>>>>;
 
 /// A instance of stage of a form
 pub struct Stage {
     container: HtmlElement,
+    meta: StageMeta,
+    validator: Option<Validator>,
+    loader: Option<Loader>,
+    /// Shared by the instances of a repeatable stage
+    repeat: Option<Rc<Repeat>>,
+    /// Referenced elements of the container, made when asked first
+    refs: Option<Refs>,
 }
 
 impl Stage {
     /// Creates a stage from given container element
     pub fn from_container(container: HtmlElement) -> Self {
-        Self { container }
+        Self {
+            container,
+            meta: StageMeta::default(),
+            validator: None,
+            loader: None,
+            repeat: None,
+            refs: None,
+        }
+    }
+
+    /// Makes the stage repeatable, with instances cloned from the template by
+    /// [Form::repeat_stage]
+    ///
+    /// The template holds a single stage container, with fields named as in the stage, which
+    /// are namespaced in clones by the name and the number of the instance, like
+    /// `passenger[1].name`. The stage itself is the 0th instance, so its fields are left as they
+    /// are.
+    pub fn repeatable(mut self, template: HtmlTemplateElement, name: impl Into<String>) -> Self {
+        self.repeat = Some(Rc::new(Repeat {
+            template,
+            name: name.into(),
+        }));
+        self
+    }
+
+    /// Sets the label of the stage, shown by step indicators
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.meta.label = Some(label.into());
+        self
+    }
+
+    /// Sets the id of the stage, which names it in URLs synced by [FormBuilder::url_sync]
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.meta.id = Some(id.into());
+        self
+    }
+
+    /// Sets the description of the stage, shown by step indicators
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.meta.description = Some(description.into());
+        self
+    }
+
+    /// Sets the validator of fields in the stage, which must pass before the user moves past
+    /// the stage
+    pub fn validator(mut self, validator: Validator) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+
+    /// Sets the loader of content of the stage, such as maps or rich editors, which the form
+    /// awaits before the stage is entered
+    ///
+    /// The loader runs once, ahead of time while the previous stage is current unless
+    /// [FormBuilder::prefetch] is disabled, so navigation rarely waits for it.
+    pub fn loader<F>(mut self, loader: impl Fn() -> F + 'static) -> Self
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        self.loader = Some(Rc::new(move || Box::pin(loader())));
+        self
+    }
+}
+
+/// The state of the loader of a stage
+enum Load {
+    Idle,
+    /// Loading, with callbacks to call once loaded
+    Loading(Vec<Box<dyn FnOnce()>>),
+    /// Loaded, or without a loader
+    Loaded,
+}
+
+/// Metadata of a stage describing it to step indicators
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StageMeta {
+    /// Names the stage in URLs, where stages without ids are named by their numbers from 1
+    pub id: Option<String>,
+    pub label: Option<String>,
+    pub description: Option<String>,
+}
+
+/// The axis stages are laid out along
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+
+impl Orientation {
+    /// Returns the value reflected to `data-seigi-form-orientation`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Horizontal => "horizontal",
+            Self::Vertical => "vertical",
+        }
+    }
+}
+
+/// How the form leads the user to errors once validation keeps them in a stage, set by
+/// [FormBuilder::error_focus]
+///
+/// The first invalid field is focused either way, or its first focusable descendant for fields
+/// which aren't focusable themselves, such as groups of radio buttons.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorFocus {
+    /// Whether the field is focused without scrolling it into view
+    ///
+    /// Otherwise it is scrolled into the nearest edge of the view, smoothly unless the user
+    /// prefers reduced motion.
+    pub prevent_scroll: bool,
+    /// Whether the count of invalid fields is announced assertively through [announcer]
+    ///
+    /// Leave it off with an [ErrorSummary](crate::error_summary::ErrorSummary), which announces
+    /// errors itself.
+    pub announce: bool,
+}
+
+/// Where the current stage is kept in the URL, set by [FormBuilder::url_sync]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlSync {
+    /// A query parameter of the name, like `?step=shipping`
+    Query(String),
+    /// The fragment, like `#shipping`, which plain links to `#shipping` move the form by
+    Hash,
+}
+
+impl UrlSync {
+    /// Reads the name of the stage from the URL of the page
+    fn read(&self) -> Option<String> {
+        let location = gloo::utils::window().location();
+        let value = match self {
+            Self::Query(name) => UrlSearchParams::new_with_str(&location.search().ok()?)
+                .ok()?
+                .get(name)?,
+            Self::Hash => {
+                let hash = location.hash().ok()?;
+                let hash = hash.strip_prefix('#')?;
+                js_sys::decode_uri_component(hash).ok()?.into()
+            }
+        };
+        Some(value).filter(|v| !v.is_empty())
+    }
+
+    /// Writes the name of the stage into the URL of the page, adding an entry to the session
+    /// history unless replacing the current one
+    fn write(&self, name: &str, replace: bool) {
+        let window = gloo::utils::window();
+        let (Ok(href), Ok(history)) = (window.location().href(), window.history()) else {
+            return;
+        };
+        let Ok(url) = Url::new(&href) else {
+            return;
+        };
+        match self {
+            Self::Query(key) => url.search_params().set(key, name),
+            Self::Hash => url.set_hash(name),
+        }
+        let href = url.href();
+        let _ = if replace {
+            history.replace_state_with_url(&JsValue::NULL, "", Some(&href))
+        } else {
+            history.push_state_with_url(&JsValue::NULL, "", Some(&href))
+        };
+    }
+}
+
+/// Returns the name of the stage in URLs, its id or else its number from 1
+fn url_name(stages: &[Stage], stage: usize) -> Option<String> {
+    let meta = &stages.get(stage)?.meta;
+    Some(meta.id.clone().unwrap_or_else(|| (stage + 1).to_string()))
+}
+
+/// Returns the stage named in URLs by the name
+fn url_stage(stages: &[Stage], name: &str) -> Option<usize> {
+    (0..stages.len()).find(|v| url_name(stages, *v).as_deref() == Some(name))
+}
+
+/// A predicate deciding whether the form can move from a stage to another by the user
+pub type NavigatePredicate = Box<dyn Fn(usize, usize) -> bool>;
+
+struct Subscriber {
+    callback: Box<dyn Fn(usize)>,
+    handle: u64,
+}
+
+/// An event of the form, for feeding funnel analytics
+///
+/// Times are measured only while the form is activated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormEvent {
+    /// The stage became the current stage of an activated form, at the time since the time
+    /// origin of the page
+    StageEntered { stage: usize, timestamp: Duration },
+    /// The stage stopped being the current stage, or the form was deactivated, after the user
+    /// spent the time in it
+    StageLeft { stage: usize, time_spent: Duration },
+    /// The user was kept in the stage by the fields failing validation
+    ValidationFailed { stage: usize, fields: Vec<String> },
+    /// The form was deactivated in the stage before the last stage was marked complete, after the
+    /// time it was activated for
+    Abandoned { stage: usize, time_spent: Duration },
+}
+
+struct EventSubscriber {
+    callback: Box<dyn Fn(&FormEvent)>,
+    handle: u64,
+}
+
+fn toggle_attribute(element: &HtmlElement, name: &str, is_set: bool) {
+    if is_set {
+        let _ = element.set_attribute(name, "");
+    } else {
+        let _ = element.remove_attribute(name);
     }
 }
 
+/// Creates the focus trap of the stage, which keeps focus in it while it is current
+///
+/// Stages navigated back to focus the field focused last in them, rather than the first one.
+fn create_trap(container: &HtmlElement, stage: &HtmlElement) -> FocusTrap {
+    seigi_focus::create(
+        FocusTrapOptions::builder()
+            .initial_focus(InitialFocus::Last)
+            .return_focus(false)
+            .deactivate_on_escape(false)
+            .scope(container.clone().unchecked_into())
+            .target(stage.clone())
+            .build(),
+    )
+}
+
+/// Returns the time since the time origin of the page
+fn now() -> Duration {
+    let millis = gloo::utils::window().performance().map_or(0.0, |v| v.now());
+    Duration::from_secs_f64(millis / 1000.0)
+}
+
 /// Actual implementation of [Form]
 struct Inner {
     container: HtmlElement,
     stages: Vec<Stage>,
     traps: Vec<FocusTrap>,
-    resize_observer: ResizeObserver,
+    /// Only absent in forms failing to build, which are dropped right away
+    resize_observer: Option<ResizeObserver>,
+    /// Called by the resize observer, which updates meta once per frame
+    _resize_callback: Closure<dyn Fn()>,
+    /// Counts the observer in diagnostics while alive
+    _tracked: Tracked,
     current: usize,
+    /// Whether each stage has been the current stage
+    visited: Vec<bool>,
+    /// Whether each stage is marked complete
+    complete: Vec<bool>,
+    can_navigate: Option<Rc<NavigatePredicate>>,
+    subscribers: Vec<Rc<Subscriber>>,
+    event_subscribers: Vec<Rc<EventSubscriber>>,
+    /// Events to publish once the state is unlocked
+    events: Vec<FormEvent>,
+    /// When the form was activated, if it is
+    activated_at: Option<Duration>,
+    /// When the current stage was entered while activated
+    entered_at: Duration,
+    /// Whether stage changes run in view transitions
+    view_transition: bool,
+    loads: Vec<Load>,
+    /// Whether loaders of stages run ahead of time
+    prefetch: bool,
+    orientation: Orientation,
+    error_focus: ErrorFocus,
+    /// Subscription to the on-screen keyboard, which moves and resizes stages
+    keyboard: Option<u64>,
+    /// Initial values of fields, which dirty fields differ from
+    baseline: dirty::Baseline,
+    /// Follows edits of fields to reflect their dirty state
+    listener: Listener,
+    /// Referenced elements of the container, made when asked first
+    refs: Option<Refs>,
+    /// Incremented by each stage change, so one waiting for a loader is dropped if another
+    /// follows
+    navigation: u64,
     is_activated: bool,
     is_locked: bool,
+    is_readonly: bool,
+    /// Controls disabled by readonly mode, which are enabled again once it ends
+    disabled: Vec<HtmlElement>,
+    url_sync: Option<UrlSync>,
 }
 
 impl Inner {
@@ -39,28 +353,111 @@ impl Inner {
         stages: Vec<Stage>,
         traps: Vec<FocusTrap>,
         current: usize,
-    ) -> Self {
-        Self {
+        can_navigate: Option<NavigatePredicate>,
+        view_transition: bool,
+    ) -> (Self, Result<(), Error>) {
+        let mut visited = vec![false; stages.len()];
+        visited[current] = true;
+        let complete = vec![false; stages.len()];
+        let loads = stages
+            .iter()
+            .map(|v| match v.loader {
+                Some(_) => Load::Idle,
+                None => Load::Loaded,
+            })
+            .collect();
+        let (resize_observer, resize_callback) = Self::create_resize_observer(this);
+        let (resize_observer, result) = match resize_observer {
+            Ok(v) => (Some(v), Ok(())),
+            Err(error) => (None, Err(error)),
+        };
+        let inner = Self {
             stages,
             container,
             traps,
-            resize_observer: Self::create_resize_observer(this),
+            resize_observer,
+            _resize_callback: resize_callback,
+            _tracked: diagnostics::track(Resource::Observer),
             current,
+            visited,
+            complete,
+            can_navigate: can_navigate.map(Rc::new),
+            subscribers: vec![],
+            event_subscribers: vec![],
+            events: vec![],
+            activated_at: None,
+            entered_at: Duration::ZERO,
+            view_transition,
+            loads,
+            prefetch: true,
+            orientation: Orientation::default(),
+            error_focus: ErrorFocus::default(),
+            keyboard: None,
+            baseline: dirty::Baseline::default(),
+            listener: Listener::new(),
+            refs: None,
+            navigation: 0,
             is_activated: false,
             is_locked: false,
-        }
+            is_readonly: false,
+            disabled: vec![],
+            url_sync: None,
+        };
+        (inner, result)
     }
 
-    fn create_resize_observer(this: Weak<Mutex<Self>>) -> ResizeObserver {
-        let closure: Closure<dyn Fn()> = Closure::new(move || {
-            let this = this.upgrade().unwrap();
-            this.lock().unwrap().update_meta();
+    fn create_resize_observer(
+        this: Weak<Mutex<Self>>,
+    ) -> (Result<ResizeObserver, Error>, Closure<dyn Fn()>) {
+        let frame = schedule::frame(move || {
+            if let Some(this) = this.upgrade() {
+                this.lock().unwrap().update_meta();
+            }
         });
+        let closure: Closure<dyn Fn()> = Closure::new(move || frame.schedule());
 
-        let resize_observer = ResizeObserver::new(closure.as_ref().unchecked_ref()).unwrap();
-        closure.forget();
+        let resize_observer = ResizeObserver::new(closure.as_ref().unchecked_ref())
+            .map_err(|v| Error::Browser(seigi_utils::error::describe(&v)));
 
-        resize_observer
+        (resize_observer, closure)
+    }
+
+    /// Reflects dirty state of fields, stages and the form to their attributes
+    fn update_dirty(&self) {
+        let mut is_form_dirty = false;
+        for stage in &self.stages {
+            let mut is_stage_dirty = false;
+            for control in dirty::controls(&stage.container) {
+                if self.baseline.is_dirty(&control) {
+                    is_stage_dirty = true;
+                    let _ = control.set_attribute("data-seigi-field-dirty", "");
+                } else {
+                    let _ = control.remove_attribute("data-seigi-field-dirty");
+                }
+            }
+            toggle_attribute(&stage.container, "data-seigi-stage-dirty", is_stage_dirty);
+            is_form_dirty |= is_stage_dirty;
+        }
+        toggle_attribute(&self.container, "data-seigi-form-dirty", is_form_dirty);
+    }
+
+    /// Inserts the stage at the index, shifting later stages including the current one
+    fn insert_stage(&mut self, index: usize, stage: Stage) {
+        let trap = create_trap(&self.container, &stage.container);
+        let load = match stage.loader {
+            Some(_) => Load::Idle,
+            None => Load::Loaded,
+        };
+        self.stages.insert(index, stage);
+        self.traps.insert(index, trap);
+        self.loads.insert(index, load);
+        self.visited.insert(index, false);
+        self.complete.insert(index, false);
+        if self.current >= index {
+            self.current += 1;
+        }
+        self.update_relatives();
+        self.update_dirty();
     }
 
     fn update_relatives(&mut self) {
@@ -83,30 +480,78 @@ impl Inner {
             "data-seigi-form-height",
             stage.offset_height().to_string().as_str(),
         );
-        let _ = self.container.set_attribute(
-            "data-seigi-form-offset-x",
-            (stage.offset_left()).to_string().as_str(),
-        );
-        let _ = self.container.set_attribute(
-            "data-seigi-form-offset-y",
-            (stage.offset_top()).to_string().as_str(),
-        );
+        // Only the offset along the orientation is written, so CSS can translate by it alone
+        let (attribute, offset, other) = match self.orientation {
+            Orientation::Horizontal => (
+                "data-seigi-form-offset-x",
+                stage.offset_left(),
+                "data-seigi-form-offset-y",
+            ),
+            Orientation::Vertical => (
+                "data-seigi-form-offset-y",
+                stage.offset_top(),
+                "data-seigi-form-offset-x",
+            ),
+        };
+        let _ = self
+            .container
+            .set_attribute(attribute, offset.to_string().as_str());
+        let _ = self.container.remove_attribute(other);
     }
 
-    fn update_stage(&mut self, target: usize) {
-        if self.is_locked || !self.is_activated {
+    /// Reflects the height of the on-screen keyboard, and updates meta of the stage it resized
+    fn update_keyboard(&mut self, height: f64) {
+        if !self.is_activated {
             return;
         }
+        if height > 0.0 {
+            let _ = self
+                .container
+                .set_attribute("data-seigi-form-keyboard-inset", &height.to_string());
+        } else {
+            let _ = self
+                .container
+                .remove_attribute("data-seigi-form-keyboard-inset");
+        }
+        self.update_meta();
+    }
+
+    /// # Returns
+    /// Whether the current stage changed
+    fn update_stage(&mut self, target: usize) -> bool {
+        if self.is_locked || self.is_readonly || !self.is_activated || target >= self.stages.len() {
+            return false;
+        }
 
         self.traps.get(self.current).unwrap().deactivate();
         self.traps.get(target).unwrap().activate();
-        self.resize_observer
-            .unobserve(self.stages[self.current].container.unchecked_ref());
-        self.resize_observer
-            .observe(self.stages[target].container.unchecked_ref());
+        if let Some(observer) = &self.resize_observer {
+            observer.unobserve(self.stages[self.current].container.unchecked_ref());
+            observer.observe(self.stages[target].container.unchecked_ref());
+        }
 
+        seigi_utils::debug!("form moved from stage {} to {target}", self.current);
+        self.leave_stage();
         self.current = target;
+        self.visited[target] = true;
+        self.enter_stage();
         self.update_relatives();
+        true
+    }
+
+    fn enter_stage(&mut self) {
+        self.entered_at = now();
+        self.events.push(FormEvent::StageEntered {
+            stage: self.current,
+            timestamp: self.entered_at,
+        });
+    }
+
+    fn leave_stage(&mut self) {
+        self.events.push(FormEvent::StageLeft {
+            stage: self.current,
+            time_spent: now().saturating_sub(self.entered_at),
+        });
     }
 
     fn activate(&mut self) {
@@ -114,10 +559,15 @@ impl Inner {
             return;
         }
         self.is_activated = true;
+        self.activated_at = Some(now());
+        self.enter_stage();
 
-        self.traps.get(self.current).unwrap().activate();
-        self.resize_observer
-            .observe(self.stages[self.current].container.unchecked_ref());
+        if !self.is_readonly {
+            self.traps.get(self.current).unwrap().activate();
+        }
+        if let Some(observer) = &self.resize_observer {
+            observer.observe(self.stages[self.current].container.unchecked_ref());
+        }
 
         let _ = self.container.set_attribute("data-seigi-form-active", "");
 
@@ -129,12 +579,77 @@ impl Inner {
             return;
         }
         self.is_activated = false;
+        self.leave_stage();
+        if let Some(activated_at) = self.activated_at.take()
+            && !self.complete.last().copied().unwrap_or(false)
+        {
+            self.events.push(FormEvent::Abandoned {
+                stage: self.current,
+                time_spent: now().saturating_sub(activated_at),
+            });
+        }
 
         self.traps.get(self.current).unwrap().deactivate();
-        self.resize_observer
-            .unobserve(self.stages[self.current].container.unchecked_ref());
+        if let Some(observer) = &self.resize_observer {
+            observer.unobserve(self.stages[self.current].container.unchecked_ref());
+        }
 
         let _ = self.container.remove_attribute("data-seigi-form-active");
+        let _ = self
+            .container
+            .remove_attribute("data-seigi-form-keyboard-inset");
+    }
+
+    /// # Returns
+    /// Whether readonly mode changed
+    fn set_readonly(&mut self, is_readonly: bool) -> bool {
+        if self.is_readonly == is_readonly {
+            return false;
+        }
+        self.is_readonly = is_readonly;
+        toggle_attribute(&self.container, "data-seigi-form-readonly", is_readonly);
+
+        if is_readonly {
+            for stage in &self.stages {
+                let Ok(list) = stage.container.query_selector_all(READONLY_CONTROLS) else {
+                    continue;
+                };
+                for control in (0..list.length()).filter_map(|v| list.item(v)) {
+                    let Ok(control) = control.dyn_into::<HtmlElement>() else {
+                        continue;
+                    };
+                    let _ = control.set_attribute("disabled", "");
+                    self.disabled.push(control);
+                }
+            }
+        } else {
+            for control in std::mem::take(&mut self.disabled) {
+                let _ = control.remove_attribute("disabled");
+            }
+        }
+
+        if self.is_activated {
+            let trap = self.traps.get(self.current).unwrap();
+            if is_readonly {
+                trap.deactivate();
+            } else {
+                trap.activate();
+            }
+        }
+        seigi_utils::debug!("form readonly mode set to {is_readonly}");
+        true
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        if let Some(handle) = self.keyboard.take() {
+            keyboard::unsubscribe(handle);
+        }
+        // The callback is dropped with the state, so the observer must not call it afterwards
+        if let Some(observer) = &self.resize_observer {
+            observer.disconnect();
+        }
     }
 }
 
@@ -153,15 +668,38 @@ impl Inner {
 /// **data-seigi-form-height** is set in the root container to the height of current stage container
 /// in px
 ///
+/// **data-seigi-form-orientation** is set in the root container to the orientation given by
+/// [FormBuilder::orientation], `horizontal` or `vertical`
+///
 /// **data-seigi-form-offset-x** is set in the root container to the sum of widths of previous
-/// stages
+/// stages, if the form is horizontal
 ///
 /// **data-seigi-form-offset-y** is set in the root container to the sum of heights of previous
-/// stages
+/// stages, if the form is vertical
+///
+/// **data-seigi-form-keyboard-inset** is set in the root container to the height of the
+/// on-screen keyboard in px while it is shown, so stages can be padded above it
 ///
 /// **data-seigi-stage-relative** is set in the each stage containers to the relative index from
 /// current stage. For example, a stage currently active has this value of 0, the previous one is
 /// -1, and the next one is 1
+///
+/// **data-seigi-stage-loading** and **aria-busy** are set in stage containers while their
+/// loaders run, and **data-seigi-stage-loaded** once they finish
+///
+/// **data-seigi-form-loading** is set in the root container while a stage change waits for the
+/// loader of the target
+///
+/// **data-seigi-stage-repeat** is set in stage containers cloned by [Form::repeat_stage] to the
+/// number of the instance
+///
+/// **data-seigi-field-dirty** is set in controls of stages whose values differ from the initial
+/// ones, **data-seigi-stage-dirty** in stage containers with such controls, and
+/// **data-seigi-form-dirty** in the root container if any stage has them, as told by
+/// [Form::is_dirty]
+///
+/// **data-seigi-form-readonly** is set in the root container in readonly mode of
+/// [Form::set_readonly], where CSS shows every stage at once
 #[derive(Clone)]
 pub struct Form(Rc<Mutex<Inner>>);
 
@@ -180,23 +718,638 @@ impl Form {
         self.0.lock().unwrap().is_locked
     }
 
-    /// Updates the current stage to next stage
+    /// Resolve whether the form is in readonly mode
+    pub fn is_readonly(&self) -> bool {
+        self.0.lock().unwrap().is_readonly
+    }
+
+    /// Sets readonly mode, where a completed form is reviewed or printed as a whole
+    ///
+    /// Controls of every stage, including buttons, are disabled, and the focus trap of the
+    /// current stage is deactivated, while stage changes are ignored. CSS is left to show every
+    /// stage at once by **data-seigi-form-readonly** of the root container. Ending readonly mode
+    /// enables only the controls it disabled, and returns to the current stage.
+    pub fn set_readonly(&self, is_readonly: bool) {
+        let is_changed = self.0.lock().unwrap().set_readonly(is_readonly);
+        if is_changed {
+            self.notify();
+        }
+    }
+
+    /// Updates the current stage to next stage, if the validator of the current stage passes
+    ///
+    /// Otherwise, errors are shown and the first invalid field is focused.
     pub fn next(&self) {
-        let mut inner = self.0.lock().unwrap();
-        let current = inner.current;
-        inner.update_stage(current + 1);
+        let current = self.current();
+        if !self.check(current) {
+            return;
+        }
+        self.stage(current + 1);
     }
 
     /// Updates the current stage to previous stage
     pub fn previous(&self) {
-        let mut inner = self.0.lock().unwrap();
-        let current = inner.current;
-        inner.update_stage(current - 1);
+        let current = self.current();
+        if current > 0 {
+            self.stage(current - 1);
+        }
     }
 
     /// Updates the current stage
+    ///
+    /// With [FormBuilder::view_transition], the stage changes once the browser has captured the
+    /// page, so [Form::current] may not be updated right after this call. The same goes for
+    /// stages whose [Stage::loader] hasn't finished. Subscribe with [Form::subscribe] to follow
+    /// changes instead.
     pub fn stage(&self, stage: usize) {
-        self.0.lock().unwrap().update_stage(stage);
+        self.stage_then(stage, || {});
+    }
+
+    /// Updates the current stage like [Form::stage], and calls `then` once it is updated
+    ///
+    /// The stage changes once its loader finishes, unless another change follows meanwhile.
+    pub(crate) fn stage_then(&self, stage: usize, then: impl FnOnce() + 'static) {
+        let (navigation, is_loaded) = {
+            let mut inner = self.0.lock().unwrap();
+            inner.navigation += 1;
+            let is_loaded = !matches!(inner.loads.get(stage), Some(Load::Idle | Load::Loading(_)));
+            if is_loaded {
+                let _ = inner.container.remove_attribute("data-seigi-form-loading");
+            } else {
+                let _ = inner.container.set_attribute("data-seigi-form-loading", "");
+            }
+            (inner.navigation, is_loaded)
+        };
+        if is_loaded {
+            self.enter(stage, then);
+            return;
+        }
+        let form = self.clone();
+        self.load(stage, move || {
+            {
+                let inner = form.0.lock().unwrap();
+                if inner.navigation != navigation {
+                    return;
+                }
+                let _ = inner.container.remove_attribute("data-seigi-form-loading");
+            }
+            form.enter(stage, then);
+        });
+    }
+
+    fn enter(&self, stage: usize, then: impl FnOnce() + 'static) {
+        if !self.0.lock().unwrap().view_transition {
+            self.update_stage(stage);
+            then();
+            return;
+        }
+        let form = self.clone();
+        view_transitions::start(move || {
+            form.update_stage(stage);
+            then();
+        });
+    }
+
+    fn update_stage(&self, stage: usize) {
+        let is_changed = self.0.lock().unwrap().update_stage(stage);
+        if is_changed {
+            self.write_url(false);
+            self.publish();
+            self.notify();
+            self.prefetch();
+        }
+    }
+
+    /// Runs the loader of the stage unless it ran, and calls `then` once it finishes
+    fn load(&self, stage: usize, then: impl FnOnce() + 'static) {
+        let loader = {
+            let mut inner = self.0.lock().unwrap();
+            let loader = inner.stages.get(stage).and_then(|v| v.loader.clone());
+            match inner.loads.get_mut(stage) {
+                Some(Load::Idle) => {
+                    inner.loads[stage] = Load::Loading(vec![Box::new(then)]);
+                    let container = &inner.stages[stage].container;
+                    let _ = container.set_attribute("data-seigi-stage-loading", "");
+                    let _ = container.set_attribute("aria-busy", "true");
+                    loader
+                }
+                Some(Load::Loading(callbacks)) => {
+                    callbacks.push(Box::new(then));
+                    return;
+                }
+                Some(Load::Loaded) | None => {
+                    drop(inner);
+                    then();
+                    return;
+                }
+            }
+        };
+        let Some(loader) = loader else {
+            return;
+        };
+        seigi_utils::debug!("form loading stage {stage}");
+        let form = self.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            loader().await;
+            let callbacks = {
+                let mut inner = form.0.lock().unwrap();
+                let container = &inner.stages[stage].container;
+                let _ = container.remove_attribute("data-seigi-stage-loading");
+                let _ = container.remove_attribute("aria-busy");
+                let _ = container.set_attribute("data-seigi-stage-loaded", "");
+                match std::mem::replace(&mut inner.loads[stage], Load::Loaded) {
+                    Load::Loading(callbacks) => callbacks,
+                    _ => vec![],
+                }
+            };
+            for callback in callbacks {
+                callback();
+            }
+        });
+    }
+
+    /// Loads the current stage of an activated form, and the next one ahead of time if enabled
+    fn prefetch(&self) {
+        let (current, is_activated, prefetch) = {
+            let inner = self.0.lock().unwrap();
+            (inner.current, inner.is_activated, inner.prefetch)
+        };
+        if !is_activated {
+            return;
+        }
+        self.load(current, || {});
+        if prefetch {
+            self.load(current + 1, || {});
+        }
+    }
+
+    /// Writes the current stage into the URL if it is synced and names another stage
+    fn write_url(&self, replace: bool) {
+        let (url_sync, name) = {
+            let inner = self.0.lock().unwrap();
+            let Some(url_sync) = inner.url_sync.clone() else {
+                return;
+            };
+            let Some(name) = url_name(&inner.stages, inner.current) else {
+                return;
+            };
+            (url_sync, name)
+        };
+        if url_sync.read().as_deref() != Some(name.as_str()) {
+            url_sync.write(&name, replace);
+        }
+    }
+
+    /// Moves to the stage named by the URL after it changed outside the form, like by the back
+    /// button, or writes the current stage back if the user isn't allowed to move there
+    fn follow_url(&self) {
+        let stage = {
+            let inner = self.0.lock().unwrap();
+            let Some(name) = inner.url_sync.as_ref().and_then(UrlSync::read) else {
+                return;
+            };
+            url_stage(&inner.stages, &name)
+        };
+        match stage {
+            Some(stage) if stage == self.current() => {}
+            Some(stage) if self.navigate(stage) => {}
+            _ => self.write_url(true),
+        }
+    }
+
+    /// Returns whether the loader of the stage finished, which it has without one
+    pub fn is_loaded(&self, stage: usize) -> bool {
+        matches!(self.0.lock().unwrap().loads.get(stage), Some(Load::Loaded))
+    }
+
+    /// Updates the current stage if the user is allowed to move to it by [Form::can_navigate]
+    ///
+    /// # Returns
+    /// Whether the stage is allowed
+    pub fn navigate(&self, stage: usize) -> bool {
+        if !self.can_navigate(stage) {
+            return false;
+        }
+        let current = self.current();
+        if stage > current && !(current..stage).all(|v| self.check(v)) {
+            return false;
+        }
+        self.stage(stage);
+        true
+    }
+
+    /// Returns whether the user is allowed to move from the current stage to the stage
+    ///
+    /// The predicate given to [FormBuilder::can_navigate] decides, and without one, stages
+    /// visited before and the one next to the current stage are allowed.
+    pub fn can_navigate(&self, stage: usize) -> bool {
+        let (predicate, current, is_allowed) = {
+            let inner = self.0.lock().unwrap();
+            if stage >= inner.stages.len() {
+                return false;
+            }
+            let is_allowed = inner.visited[stage] || stage == inner.current + 1;
+            (inner.can_navigate.clone(), inner.current, is_allowed)
+        };
+        match predicate {
+            Some(predicate) => predicate(current, stage),
+            None => is_allowed,
+        }
+    }
+
+    /// Returns the count of stages
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().stages.len()
+    }
+
+    /// Returns whether the form has no stages, which is never true for a built form
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the root container of the form
+    pub fn container(&self) -> HtmlElement {
+        self.0.lock().unwrap().container.clone()
+    }
+
+    /// Returns elements of the root container referenced by `data-seigi-ref`, which are kept
+    /// across calls until the container changes
+    pub fn refs(&self) -> Refs {
+        let mut inner = self.0.lock().unwrap();
+        let container = inner.container.clone();
+        inner
+            .refs
+            .get_or_insert_with(|| Refs::new(container.into()))
+            .clone()
+    }
+
+    /// Returns elements of the container of the stage referenced by `data-seigi-ref`, like
+    /// [Form::refs]
+    pub fn stage_refs(&self, stage: usize) -> Option<Refs> {
+        let mut inner = self.0.lock().unwrap();
+        let stage = inner.stages.get_mut(stage)?;
+        let container = stage.container.clone();
+        Some(
+            stage
+                .refs
+                .get_or_insert_with(|| Refs::new(container.into()))
+                .clone(),
+        )
+    }
+
+    /// Returns metadata of the stage
+    pub fn meta(&self, stage: usize) -> Option<StageMeta> {
+        self.0
+            .lock()
+            .unwrap()
+            .stages
+            .get(stage)
+            .map(|v| v.meta.clone())
+    }
+
+    /// Returns whether the stage has been the current stage
+    pub fn is_visited(&self, stage: usize) -> bool {
+        self.0
+            .lock()
+            .unwrap()
+            .visited
+            .get(stage)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Returns whether the stage is marked complete
+    pub fn is_complete(&self, stage: usize) -> bool {
+        self.0
+            .lock()
+            .unwrap()
+            .complete
+            .get(stage)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Marks the stage complete or not, for step indicators
+    pub fn set_complete(&self, stage: usize, complete: bool) {
+        {
+            let mut inner = self.0.lock().unwrap();
+            match inner.complete.get_mut(stage) {
+                Some(v) if *v != complete => *v = complete,
+                _ => return,
+            }
+        }
+        self.notify();
+    }
+
+    /// Returns validators of stages with their indices
+    pub(crate) fn validators(&self) -> Vec<(usize, Validator)> {
+        self.0
+            .lock()
+            .unwrap()
+            .stages
+            .iter()
+            .enumerate()
+            .filter_map(|(index, v)| v.validator.clone().map(|v| (index, v)))
+            .collect()
+    }
+
+    fn validator(&self, stage: usize) -> Option<Validator> {
+        self.0
+            .lock()
+            .unwrap()
+            .stages
+            .get(stage)
+            .and_then(|v| v.validator.clone())
+    }
+
+    /// Returns whether no control in the stage fails constraint validation, and its validator
+    /// passes if any
+    pub fn is_valid(&self, stage: usize) -> bool {
+        let is_valid = self
+            .0
+            .lock()
+            .unwrap()
+            .stages
+            .get(stage)
+            .is_none_or(|v| matches!(v.container.query_selector(":invalid"), Ok(None)));
+        is_valid && self.validator(stage).is_none_or(|v| v.is_valid())
+    }
+
+    /// Checks the validator of the stage showing errors, and focuses the first invalid field if
+    /// it fails
+    fn check(&self, stage: usize) -> bool {
+        let Some(validator) = self.validator(stage) else {
+            return true;
+        };
+        let is_valid = validator.check();
+        if !is_valid {
+            seigi_utils::debug!("form stage {stage} blocked by invalid fields");
+            self.fail_validation(stage, &validator);
+        }
+        is_valid
+    }
+
+    /// Validates the stage against every rule of its validator, including asynchronous ones,
+    /// showing errors
+    ///
+    /// Asynchronous results are reused by [Form::next] while values stay unchanged, so awaiting
+    /// this before moving on gates the stage by them too.
+    ///
+    /// # Returns
+    /// Whether the stage is valid, which it is without a validator
+    pub async fn validate(&self, stage: usize) -> bool {
+        let Some(validator) = self.validator(stage) else {
+            return true;
+        };
+        let is_valid = validator.validate().await;
+        if !is_valid {
+            self.fail_validation(stage, &validator);
+        }
+        is_valid
+    }
+
+    /// Leads the user to the first invalid field as set by [FormBuilder::error_focus]
+    fn focus_errors(&self, validator: &Validator, fields: &[String]) {
+        let error_focus = self.0.lock().unwrap().error_focus;
+        let target = fields
+            .first()
+            .and_then(|v| validator.element(v))
+            .and_then(|v| {
+                if candidates::is_focusable(&v) {
+                    Some(v)
+                } else {
+                    candidates::first_focus_candidate(&v)
+                }
+            });
+        if let Some(target) = target {
+            let options = FocusOptions::new();
+            options.set_prevent_scroll(true);
+            let _ = target.focus_with_options(&options);
+            if !error_focus.prevent_scroll {
+                let options = ScrollIntoViewOptions::new();
+                options.set_block(ScrollLogicalPosition::Nearest);
+                options.set_behavior(if media::prefers_reduced_motion() {
+                    ScrollBehavior::Auto
+                } else {
+                    ScrollBehavior::Smooth
+                });
+                target.scroll_into_view_with_scroll_into_view_options(&options);
+            }
+        }
+        if error_focus.announce {
+            announcer::assertive(i18n::translate("form.invalid", &[("count", &fields.len())]));
+        }
+    }
+
+    /// Publishes [FormEvent::ValidationFailed] after the first invalid field is focused, so
+    /// subscribers like [ErrorSummary](crate::error_summary::ErrorSummary) can move focus
+    fn fail_validation(&self, stage: usize, validator: &Validator) {
+        let fields = validator
+            .fields()
+            .into_iter()
+            .filter(|v| !validator.errors(v).is_empty())
+            .collect::<Vec<_>>();
+        self.focus_errors(validator, &fields);
+        self.0
+            .lock()
+            .unwrap()
+            .events
+            .push(FormEvent::ValidationFailed { stage, fields });
+        self.publish();
+    }
+
+    /// Adds an instance of the repeatable stage, cloned from the template given to
+    /// [Stage::repeatable], after its last instance
+    ///
+    /// The instance gets the label, description and loader of the stage, but no validator, as
+    /// validators are bound to fields; constraint validation still gates [Form::is_valid]. Stages
+    /// after the instance shift by one, including the current stage, and subscribers are
+    /// notified.
+    ///
+    /// # Returns
+    /// The index of the instance, or None if the stage isn't repeatable or its template has no
+    /// element
+    pub fn repeat_stage(&self, template_index: usize) -> Option<usize> {
+        let index = {
+            let mut inner = self.0.lock().unwrap();
+            let template = inner.stages.get(template_index)?;
+            let repeat = template.repeat.clone()?;
+            let instances = inner
+                .stages
+                .iter()
+                .enumerate()
+                .filter(|(_, v)| v.repeat.as_ref().is_some_and(|v| Rc::ptr_eq(v, &repeat)))
+                .map(|(index, _)| index)
+                .collect::<Vec<_>>();
+            let last = *instances.last()?;
+            let container = repeat.instantiate(instances.len())?;
+            inner.stages[last]
+                .container
+                .after_with_node_1(&container)
+                .ok()?;
+            let mut meta = template.meta.clone();
+            // Instances are named in URLs apart from the stage they repeat
+            if let Some(id) = &mut meta.id {
+                *id = format!("{id}-{}", instances.len());
+            }
+            let stage = Stage {
+                container,
+                meta,
+                validator: None,
+                loader: template.loader.clone(),
+                repeat: Some(repeat),
+                refs: None,
+            };
+            seigi_utils::debug!("form repeated stage {template_index} as stage {}", last + 1);
+            inner.insert_stage(last + 1, stage);
+            last + 1
+        };
+        self.notify();
+        Some(index)
+    }
+
+    /// Returns whether any field of the form differs from its initial value
+    ///
+    /// Initial values are taken when the form is built, and again by [Form::mark_pristine].
+    pub fn is_dirty(&self) -> bool {
+        (0..self.len()).any(|v| self.is_stage_dirty(v))
+    }
+
+    /// Returns whether any field of the stage differs from its initial value
+    pub fn is_stage_dirty(&self, stage: usize) -> bool {
+        let inner = self.0.lock().unwrap();
+        inner.stages.get(stage).is_some_and(|v| {
+            dirty::controls(&v.container)
+                .iter()
+                .any(|v| inner.baseline.is_dirty(v))
+        })
+    }
+
+    /// Returns names of fields of the stage differing from their initial values, in document
+    /// order
+    ///
+    /// Fields are named by the `name` of their controls, or else the `id`, and controls with
+    /// neither are left out while still making the stage dirty.
+    pub fn dirty_fields(&self, stage: usize) -> Vec<String> {
+        let inner = self.0.lock().unwrap();
+        let Some(stage) = inner.stages.get(stage) else {
+            return vec![];
+        };
+        let mut fields = vec![];
+        for control in dirty::controls(&stage.container) {
+            if !inner.baseline.is_dirty(&control) {
+                continue;
+            }
+            if let Some(name) = dirty::field_name(&control)
+                && !fields.contains(&name)
+            {
+                fields.push(name);
+            }
+        }
+        fields
+    }
+
+    /// Takes the current values of fields as initial ones, like once a draft is saved
+    pub fn mark_pristine(&self) {
+        let mut inner = self.0.lock().unwrap();
+        inner.baseline = dirty::Baseline::snapshot(inner.stages.iter().map(|v| &v.container));
+        inner.update_dirty();
+    }
+
+    /// Asks the user to confirm leaving the page while the form is dirty, until the returned
+    /// listener is dropped
+    ///
+    /// Browsers show their own message, and only once the user has interacted with the page.
+    pub fn warn_before_unload(&self) -> Listener {
+        let weak = Rc::downgrade(&self.0);
+        let mut listener = Listener::new();
+        listener.on(&gloo::utils::window(), "beforeunload", move |event| {
+            let Some(form) = weak.upgrade().map(Form) else {
+                return;
+            };
+            if form.is_dirty() {
+                event.prevent_default();
+                // Browsers predating preventDefault for this event only follow returnValue
+                event
+                    .unchecked_ref::<BeforeUnloadEvent>()
+                    .set_return_value("");
+            }
+        });
+        listener
+    }
+
+    /// Subscribes to changes of the current stage and completion of stages
+    ///
+    /// The callback is called with the current stage.
+    ///
+    /// # Returns
+    /// A handle to unsubscribe with [Form::unsubscribe]
+    pub fn subscribe(&self, callback: Box<dyn Fn(usize)>) -> u64 {
+        static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+        let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .push(Rc::new(Subscriber { callback, handle }));
+        handle
+    }
+
+    pub fn unsubscribe(&self, handle: u64) {
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .retain(|v| v.handle != handle);
+    }
+
+    /// Subscribes to [FormEvent]s of the form
+    ///
+    /// # Returns
+    /// A handle to unsubscribe with [Form::unsubscribe_events]
+    pub fn subscribe_events(&self, callback: Box<dyn Fn(&FormEvent)>) -> u64 {
+        static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+        let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        self.0
+            .lock()
+            .unwrap()
+            .event_subscribers
+            .push(Rc::new(EventSubscriber { callback, handle }));
+        handle
+    }
+
+    pub fn unsubscribe_events(&self, handle: u64) {
+        self.0
+            .lock()
+            .unwrap()
+            .event_subscribers
+            .retain(|v| v.handle != handle);
+    }
+
+    /// Publishes events queued while the state was locked
+    fn publish(&self) {
+        let (subscribers, events) = {
+            let mut inner = self.0.lock().unwrap();
+            (
+                inner.event_subscribers.clone(),
+                std::mem::take(&mut inner.events),
+            )
+        };
+        for event in &events {
+            for subscriber in &subscribers {
+                (subscriber.callback)(event);
+            }
+        }
+    }
+
+    fn notify(&self) {
+        let (subscribers, current) = {
+            let inner = self.0.lock().unwrap();
+            (inner.subscribers.clone(), inner.current)
+        };
+        for subscriber in subscribers {
+            (subscriber.callback)(current);
+        }
     }
 
     /// Returns the current stage
@@ -204,9 +1357,17 @@ impl Form {
         self.0.lock().unwrap().current
     }
 
+    /// Returns the axis stages are laid out along
+    pub fn orientation(&self) -> Orientation {
+        self.0.lock().unwrap().orientation
+    }
+
     /// Initialize the attributes
     pub fn initialize(&self) {
         let mut state = self.0.lock().unwrap();
+        let _ = state
+            .container
+            .set_attribute("data-seigi-form-orientation", state.orientation.as_str());
         state.update_meta();
         state.update_relatives();
     }
@@ -214,23 +1375,33 @@ impl Form {
     /// Activate the form
     pub fn activate(&self) {
         self.0.lock().unwrap().activate();
+        self.publish();
+        self.prefetch();
     }
 
     /// Deactivate the form
+    ///
+    /// Unless the last stage is marked complete with [Form::set_complete], the form is reported
+    /// abandoned by [FormEvent::Abandoned].
     pub fn deactivate(&self) {
         self.0.lock().unwrap().deactivate();
+        self.publish();
     }
 
     /// Toggle the form
     ///
     /// Activate the form if it is deactivated and deactivate the form if it is activated
     pub fn toggle(&self) {
-        let mut inner = self.0.lock().unwrap();
-        if inner.is_activated {
-            inner.deactivate();
-        } else {
-            inner.activate();
+        {
+            let mut inner = self.0.lock().unwrap();
+            if inner.is_activated {
+                inner.deactivate();
+            } else {
+                inner.activate();
+            }
         }
+        self.publish();
+        self.prefetch();
     }
 }
 
@@ -239,6 +1410,12 @@ pub struct FormBuilder {
     initial_stage: usize,
     container: Option<HtmlElement>,
     stages: Vec<Stage>,
+    can_navigate: Option<NavigatePredicate>,
+    view_transition: bool,
+    prefetch: bool,
+    orientation: Orientation,
+    error_focus: ErrorFocus,
+    url_sync: Option<UrlSync>,
 }
 
 impl FormBuilder {
@@ -248,6 +1425,12 @@ impl FormBuilder {
             initial_stage: 0,
             container: None,
             stages: vec![],
+            can_navigate: None,
+            view_transition: false,
+            prefetch: true,
+            orientation: Orientation::default(),
+            error_focus: ErrorFocus::default(),
+            url_sync: None,
         }
     }
 
@@ -275,37 +1458,145 @@ impl FormBuilder {
         self
     }
 
-    pub fn build(self) -> Form {
+    /// Sets the predicate deciding whether the user can move from a stage to another, called
+    /// with indices of the current stage and the target
+    pub fn can_navigate(mut self, can_navigate: NavigatePredicate) -> Self {
+        self.can_navigate = Some(can_navigate);
+        self
+    }
+
+    /// Sets whether stage changes animate in view transitions, where supported and the user
+    /// doesn't prefer reduced motion
+    ///
+    /// Give stage containers `view-transition-name`s in CSS to animate them individually.
+    pub fn view_transition(mut self, view_transition: bool) -> Self {
+        self.view_transition = view_transition;
+        self
+    }
+
+    /// Sets the axis stages are laid out along, which decides the offset written to the root
+    /// container and the arrow keys of steppers of the form
+    ///
+    /// Defaults to [Orientation::Horizontal].
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Sets how the form leads the user to errors once validation keeps them in a stage, by
+    /// [Form::next] and [Form::validate]
+    ///
+    /// Defaults to focusing the first invalid field and scrolling it into view, without
+    /// announcing.
+    pub fn error_focus(mut self, error_focus: ErrorFocus) -> Self {
+        self.error_focus = error_focus;
+        self
+    }
+
+    /// Sets whether the loader of the stage next to the current stage, given by [Stage::loader],
+    /// runs ahead of time, or only once the form moves to the stage
+    ///
+    /// Defaults to true.
+    pub fn prefetch(mut self, prefetch: bool) -> Self {
+        self.prefetch = prefetch;
+        self
+    }
+
+    /// Keeps the current stage in the URL, so links can point at stages, like support links to
+    /// `?step=shipping`
+    ///
+    /// Stages are named by [Stage::id], or by their numbers from 1 without ids. The stage named
+    /// by the URL when built becomes the initial stage in place of [FormBuilder::initial_stage].
+    /// Stage changes add entries to the session history, and changes of the URL from outside,
+    /// like the back button, move the form by [Form::navigate], which writes the current stage
+    /// back if the user isn't allowed to move there.
+    pub fn url_sync(mut self, url_sync: UrlSync) -> Self {
+        self.url_sync = Some(url_sync);
+        self
+    }
+
+    /// Builds into [Form]
+    ///
+    /// # Errors
+    /// Returns an error if the container isn't set, the initial stage isn't one of the stages,
+    /// or the browser doesn't support `ResizeObserver`
+    pub fn build(mut self) -> Result<Form, Error> {
+        if let Some(stage) = self
+            .url_sync
+            .as_ref()
+            .and_then(UrlSync::read)
+            .and_then(|name| url_stage(&self.stages, &name))
+        {
+            self.initial_stage = stage;
+        }
         if self.initial_stage >= self.stages.len() {
-            panic!("initial_stage must be less than stage count");
+            return Err(Error::InitialStageOutOfRange {
+                initial_stage: self.initial_stage,
+                len: self.stages.len(),
+            });
         }
 
-        let container = self.container.expect("container must be set to build Form");
+        let container = self.container.ok_or(Error::MissingContainer)?;
 
         let traps = self
             .stages
             .iter()
-            .map(|v| {
-                seigi_focus::create(
-                    FocusTrapOptions::builder()
-                        .return_focus(false)
-                        .deactivate_on_escape(false)
-                        .scope(container.clone().unchecked_into())
-                        .target(v.container.clone())
-                        .build(),
-                )
-            })
+            .map(|v| create_trap(&container, &v.container))
             .collect();
 
-        Form(Rc::new_cyclic(|weak| {
-            Mutex::new(Inner::new(
+        let mut result = Ok(());
+        let form = Form(Rc::new_cyclic(|weak| {
+            let (mut inner, resize_observer) = Inner::new(
                 weak.clone(),
                 container,
                 self.stages,
                 traps,
                 self.initial_stage,
-            ))
-        }))
+                self.can_navigate,
+                self.view_transition,
+            );
+            inner.prefetch = self.prefetch;
+            inner.orientation = self.orientation;
+            inner.error_focus = self.error_focus;
+            if let Some(url_sync) = self.url_sync {
+                for event in ["popstate", "hashchange"] {
+                    let this = weak.clone();
+                    inner.listener.on(&gloo::utils::window(), event, move |_| {
+                        if let Some(this) = this.upgrade() {
+                            Form(this).follow_url();
+                        }
+                    });
+                }
+                inner.url_sync = Some(url_sync);
+            }
+            inner.baseline = dirty::Baseline::snapshot(inner.stages.iter().map(|v| &v.container));
+            let container = inner.container.clone();
+            for event in ["input", "change"] {
+                let this = weak.clone();
+                inner.listener.on(&container, event, move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.lock().unwrap().update_dirty();
+                    }
+                });
+            }
+            let this = weak.clone();
+            inner.keyboard = Some(keyboard::subscribe(Box::new(move |height| {
+                if let Some(this) = this.upgrade() {
+                    this.lock().unwrap().update_keyboard(height);
+                }
+            })));
+            result = resize_observer;
+            Mutex::new(inner)
+        }));
+        result.map(|_| form)
+    }
+
+    /// Builds into [Form], like [FormBuilder::build]
+    ///
+    /// # Panics
+    /// This method panics if [FormBuilder::build] returns an error
+    pub fn build_unchecked(self) -> Form {
+        self.build().unwrap_or_else(|error| panic!("{error}"))
     }
 }
 
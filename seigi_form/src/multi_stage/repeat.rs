@@ -0,0 +1,91 @@
+//! Repeatable stages, whose instances are cloned from a `<template>`, like a stage per passenger
+//!
+//! Each instance is numbered, counting the stage given to the builder as the 0th. Clones have
+//! the `name` of their fields namespaced by the number, like `passenger[2].name` for a field
+//! named `name`, and their ids suffixed by it, like `name-2`, along with references to those ids.
+
+use std::collections::HashMap;
+
+use wasm_bindgen::JsCast;
+use web_sys::{DocumentFragment, Element, HtmlElement, HtmlTemplateElement};
+
+/// Attributes referencing elements by their ids, which are kept pointing inside the clone
+const REFERENCES: [&str; 6] = [
+    "for",
+    "aria-controls",
+    "aria-describedby",
+    "aria-errormessage",
+    "aria-labelledby",
+    "aria-owns",
+];
+
+/// The template instances of a repeatable stage are cloned from, shared by them
+pub(super) struct Repeat {
+    pub template: HtmlTemplateElement,
+    /// Prefix of field names of instances
+    pub name: String,
+}
+
+impl Repeat {
+    /// Clones the stage container in the template as the instance of the number
+    ///
+    /// # Returns
+    /// None if the template has no element
+    pub(super) fn instantiate(&self, index: usize) -> Option<HtmlElement> {
+        let content = self.template.content().clone_node_with_deep(true).ok()?;
+        let container = content
+            .dyn_into::<DocumentFragment>()
+            .ok()?
+            .first_element_child()?
+            .dyn_into::<HtmlElement>()
+            .ok()?;
+        let _ = container.set_attribute("data-seigi-stage-repeat", &index.to_string());
+
+        let elements = descendants(&container, "[name]");
+        for element in elements {
+            if let Some(field) = element.get_attribute("name") {
+                let name = format!("{}[{index}].{field}", self.name);
+                let _ = element.set_attribute("name", &name);
+            }
+        }
+
+        let mut ids = HashMap::new();
+        let mut elements = descendants(&container, "[id]");
+        elements.push(container.clone().into());
+        for element in elements {
+            let id = element.id();
+            if id.is_empty() {
+                continue;
+            }
+            let renamed = format!("{id}-{index}");
+            element.set_id(&renamed);
+            ids.insert(id, renamed);
+        }
+
+        let selector = REFERENCES.map(|v| format!("[{v}]")).join(", ");
+        for element in descendants(&container, &selector) {
+            for attribute in REFERENCES {
+                let Some(value) = element.get_attribute(attribute) else {
+                    continue;
+                };
+                let value = value
+                    .split_whitespace()
+                    .map(|v| ids.get(v).map_or(v, String::as_str))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let _ = element.set_attribute(attribute, &value);
+            }
+        }
+        Some(container)
+    }
+}
+
+fn descendants(container: &Element, selector: &str) -> Vec<Element> {
+    let Ok(list) = container.query_selector_all(selector) else {
+        return vec![];
+    };
+    (0..list.length())
+        .filter_map(|v| list.item(v))
+        .filter_map(|v| v.dyn_into::<Element>().ok())
+        .collect()
+}
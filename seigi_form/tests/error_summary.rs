@@ -0,0 +1,125 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_form::{
+    error_summary::{ErrorSummary, LINK_ATTRIBUTE},
+    multi_stage::{Form, Stage},
+};
+use seigi_testkit::{Fixture, assert_focused, events, wait};
+use seigi_validate::{Field, Rule, Validator, ValidatorOptions, create_validator};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::HtmlInputElement;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<div id="summary"><h2>There is a problem</h2></div>
+<div id="form">
+    <div data-seigi-stage><input id="name" name="name"></div>
+    <div data-seigi-stage><input name="email"></div>
+    <div data-seigi-stage><button>Submit</button></div>
+</div>
+"#;
+
+fn validator(fixture: &Fixture, selector: &str, name: &str) -> Validator {
+    create_validator(
+        ValidatorOptions::builder()
+            .field(Field::new(name, fixture.get(selector)).rule(Rule::required()))
+            .build(),
+    )
+}
+
+fn links(fixture: &Fixture) -> Vec<String> {
+    fixture
+        .get_all(&format!("[{LINK_ATTRIBUTE}]"))
+        .iter()
+        .map(|v| v.get_attribute("href").unwrap_or_default())
+        .collect()
+}
+
+#[wasm_bindgen_test]
+fn show_lists_errors_and_focuses_container() {
+    let fixture = Fixture::new(MARKUP);
+    let name = validator(&fixture, "#name", "name");
+    let summary = ErrorSummary::builder()
+        .container(fixture.get("#summary"))
+        .validator(name.clone())
+        .build()
+        .unwrap();
+
+    assert!(fixture.get("#summary").has_attribute("hidden"));
+    assert!(!summary.show());
+    assert!(!summary.is_shown());
+
+    name.check();
+    assert!(summary.show());
+    assert!(!fixture.get("#summary").has_attribute("hidden"));
+    assert_focused(&fixture.get("#summary"));
+    assert_eq!(links(&fixture), ["#name"]);
+    assert_eq!(summary.items()[0].message, "This field is required");
+}
+
+#[wasm_bindgen_test]
+fn summary_hides_once_errors_are_fixed() {
+    let fixture = Fixture::new(MARKUP);
+    let name = validator(&fixture, "#name", "name");
+    let summary = ErrorSummary::builder()
+        .container(fixture.get("#summary"))
+        .validator(name.clone())
+        .build()
+        .unwrap();
+    name.check();
+    summary.show();
+
+    fixture
+        .get("#name")
+        .unchecked_into::<HtmlInputElement>()
+        .set_value("Alice");
+    name.check();
+    assert!(!summary.is_shown());
+    assert!(summary.items().is_empty());
+    assert!(fixture.get("#summary").has_attribute("hidden"));
+}
+
+#[wasm_bindgen_test]
+async fn form_shows_summary_and_links_move_to_stage() {
+    let fixture = Fixture::new(MARKUP);
+    let stages = fixture.get_all("[data-seigi-stage]");
+    let form = Form::builder()
+        .container(fixture.get("#form"))
+        .add_stage(Stage::from_container(stages[0].clone()))
+        .add_stage(
+            Stage::from_container(stages[1].clone()).validator(validator(
+                &fixture,
+                "[name=email]",
+                "email",
+            )),
+        )
+        .add_stage(Stage::from_container(stages[2].clone()))
+        .initial_stage(1)
+        .build()
+        .unwrap();
+    form.initialize();
+    form.activate();
+    let summary = ErrorSummary::builder()
+        .container(fixture.get("#summary"))
+        .form(form.clone())
+        .build()
+        .unwrap();
+
+    form.next();
+    assert_eq!(form.current(), 1);
+    assert!(summary.is_shown());
+    assert_eq!(summary.items()[0].stage, Some(1));
+    wait::tick().await;
+    assert_focused(&fixture.get("#summary"));
+
+    form.stage(0);
+    wait::tick().await;
+    events::click(&fixture.get(&format!("[{LINK_ATTRIBUTE}]")));
+    assert_eq!(form.current(), 1);
+    wait::tick().await;
+    assert_focused(&fixture.get("[name=email]"));
+
+    form.deactivate();
+}
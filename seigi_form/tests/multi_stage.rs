@@ -0,0 +1,567 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use seigi_form::multi_stage::{ErrorFocus, Form, FormEvent, Orientation, Stage, UrlSync};
+use seigi_testkit::{Fixture, assert_focused, events, wait};
+use seigi_validate::{Field, Rule, ValidationError, ValidatorOptions, create_validator};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::*;
+use web_sys::HtmlInputElement;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<div id="form">
+    <div data-seigi-stage><input name="name"></div>
+    <div data-seigi-stage><input name="email"></div>
+    <div data-seigi-stage><button>Submit</button></div>
+</div>
+"#;
+
+fn form(fixture: &Fixture, initial_stage: usize) -> Form {
+    let form = Form::builder()
+        .container(fixture.get("#form"))
+        .add_stages(
+            fixture
+                .get_all("[data-seigi-stage]")
+                .into_iter()
+                .map(Stage::from_container),
+        )
+        .initial_stage(initial_stage)
+        .build()
+        .unwrap();
+    form.initialize();
+    form.activate();
+    form
+}
+
+fn relatives(fixture: &Fixture) -> Vec<String> {
+    fixture
+        .get_all("[data-seigi-stage]")
+        .iter()
+        .map(|v| {
+            v.get_attribute("data-seigi-stage-relative")
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[wasm_bindgen_test]
+fn initialize_marks_relatives() {
+    let fixture = Fixture::new(MARKUP);
+    let form = form(&fixture, 1);
+
+    assert_eq!(form.len(), 3);
+    assert_eq!(form.current(), 1);
+    assert_eq!(relatives(&fixture), ["-1", "0", "1"]);
+    assert!(fixture.get("#form").has_attribute("data-seigi-form-active"));
+
+    form.deactivate();
+}
+
+#[wasm_bindgen_test]
+fn next_and_previous_move_by_one() {
+    let fixture = Fixture::new(MARKUP);
+    let form = form(&fixture, 0);
+
+    form.next();
+    assert_eq!(form.current(), 1);
+    assert_eq!(relatives(&fixture), ["-1", "0", "1"]);
+
+    form.next();
+    form.next();
+    assert_eq!(form.current(), 2);
+
+    form.previous();
+    assert_eq!(form.current(), 1);
+    form.previous();
+    form.previous();
+    assert_eq!(form.current(), 0);
+
+    form.deactivate();
+}
+
+#[wasm_bindgen_test]
+fn navigate_allows_visited_and_next_stages() {
+    let fixture = Fixture::new(MARKUP);
+    let form = form(&fixture, 0);
+
+    assert!(!form.can_navigate(2));
+    assert!(!form.navigate(2));
+    assert_eq!(form.current(), 0);
+
+    assert!(form.navigate(1));
+    assert!(form.navigate(2));
+    assert!(form.is_visited(2));
+
+    assert!(form.navigate(0));
+    assert!(form.navigate(2));
+    assert!(!form.navigate(3));
+    assert_eq!(form.current(), 2);
+
+    form.deactivate();
+}
+
+#[wasm_bindgen_test]
+fn subscribers_are_notified_of_changes() {
+    let fixture = Fixture::new(MARKUP);
+    let form = form(&fixture, 0);
+    let changes = Rc::new(RefCell::new(vec![]));
+    let subscription = form.subscribe(Box::new({
+        let changes = changes.clone();
+        move |stage| changes.borrow_mut().push(stage)
+    }));
+
+    form.next();
+    form.next();
+    form.previous();
+    assert_eq!(*changes.borrow(), [1, 2, 1]);
+
+    form.unsubscribe(subscription);
+    form.previous();
+    assert_eq!(*changes.borrow(), [1, 2, 1]);
+
+    form.deactivate();
+}
+
+#[wasm_bindgen_test]
+fn deactivated_form_stays() {
+    let fixture = Fixture::new(MARKUP);
+    let form = form(&fixture, 0);
+
+    form.deactivate();
+    assert!(!form.is_active());
+    form.next();
+    assert_eq!(form.current(), 0);
+}
+
+#[wasm_bindgen_test]
+fn events_follow_stages_and_abandonment() {
+    let fixture = Fixture::new(MARKUP);
+    let form = form(&fixture, 0);
+    let events = Rc::new(RefCell::new(vec![]));
+    form.subscribe_events(Box::new({
+        let events = events.clone();
+        move |event: &FormEvent| events.borrow_mut().push(event.clone())
+    }));
+
+    form.next();
+    form.deactivate();
+    let kinds = events
+        .borrow()
+        .iter()
+        .map(|v| match v {
+            FormEvent::StageEntered { stage, .. } => format!("entered {stage}"),
+            FormEvent::StageLeft { stage, .. } => format!("left {stage}"),
+            FormEvent::ValidationFailed { stage, .. } => format!("failed {stage}"),
+            FormEvent::Abandoned { stage, .. } => format!("abandoned {stage}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(kinds, ["left 0", "entered 1", "left 1", "abandoned 1"]);
+
+    // Forms whose last stage is complete are finished rather than abandoned
+    events.borrow_mut().clear();
+    form.set_complete(2, true);
+    form.activate();
+    form.deactivate();
+    assert!(
+        !events
+            .borrow()
+            .iter()
+            .any(|v| matches!(v, FormEvent::Abandoned { .. }))
+    );
+}
+
+fn loading_form(fixture: &Fixture, prefetch: bool, loads: Rc<Cell<usize>>) -> Form {
+    let stages = fixture.get_all("[data-seigi-stage]");
+    let form = Form::builder()
+        .container(fixture.get("#form"))
+        .add_stage(Stage::from_container(stages[0].clone()))
+        .add_stage(Stage::from_container(stages[1].clone()).loader(move || {
+            let loads = loads.clone();
+            async move {
+                wait::sleep(20).await;
+                loads.set(loads.get() + 1);
+            }
+        }))
+        .add_stage(Stage::from_container(stages[2].clone()))
+        .prefetch(prefetch)
+        .build()
+        .unwrap();
+    form.initialize();
+    form.activate();
+    form
+}
+
+#[wasm_bindgen_test]
+async fn next_stage_is_prefetched() {
+    let fixture = Fixture::new(MARKUP);
+    let loads = Rc::new(Cell::new(0));
+    let form = loading_form(&fixture, true, loads.clone());
+    let stage = &fixture.get_all("[data-seigi-stage]")[1];
+
+    assert!(stage.has_attribute("data-seigi-stage-loading"));
+    assert_eq!(stage.get_attribute("aria-busy").as_deref(), Some("true"));
+    wait::sleep(40).await;
+    assert!(form.is_loaded(1));
+    assert!(stage.has_attribute("data-seigi-stage-loaded"));
+    assert!(!stage.has_attribute("aria-busy"));
+
+    form.next();
+    assert_eq!(form.current(), 1);
+    form.previous();
+    form.next();
+    assert_eq!(loads.get(), 1);
+
+    form.deactivate();
+}
+
+#[wasm_bindgen_test]
+async fn navigation_waits_for_loader() {
+    let fixture = Fixture::new(MARKUP);
+    let loads = Rc::new(Cell::new(0));
+    let form = loading_form(&fixture, false, loads.clone());
+    assert!(!form.is_loaded(1));
+    assert_eq!(loads.get(), 0);
+
+    form.next();
+    assert_eq!(form.current(), 0);
+    assert!(
+        fixture
+            .get("#form")
+            .has_attribute("data-seigi-form-loading")
+    );
+    wait::sleep(40).await;
+    assert_eq!(form.current(), 1);
+    assert!(
+        !fixture
+            .get("#form")
+            .has_attribute("data-seigi-form-loading")
+    );
+
+    form.deactivate();
+}
+
+#[wasm_bindgen_test]
+async fn later_navigation_supersedes_waiting_one() {
+    let fixture = Fixture::new(MARKUP);
+    let form = loading_form(&fixture, false, Rc::new(Cell::new(0)));
+
+    form.next();
+    form.stage(2);
+    assert_eq!(form.current(), 2);
+    wait::sleep(40).await;
+    assert_eq!(form.current(), 2);
+    assert!(form.is_loaded(1));
+
+    form.deactivate();
+}
+
+#[wasm_bindgen_test]
+fn orientation_decides_written_offset() {
+    let fixture = Fixture::new(MARKUP);
+    let container = fixture.get("#form");
+    let horizontal = form(&fixture, 1);
+    assert_eq!(
+        container
+            .get_attribute("data-seigi-form-orientation")
+            .as_deref(),
+        Some("horizontal")
+    );
+    assert!(container.has_attribute("data-seigi-form-offset-x"));
+    assert!(!container.has_attribute("data-seigi-form-offset-y"));
+    horizontal.deactivate();
+
+    let vertical = Form::builder()
+        .container(container.clone())
+        .add_stages(
+            fixture
+                .get_all("[data-seigi-stage]")
+                .into_iter()
+                .map(Stage::from_container),
+        )
+        .orientation(Orientation::Vertical)
+        .build()
+        .unwrap();
+    vertical.initialize();
+    assert_eq!(vertical.orientation(), Orientation::Vertical);
+    assert_eq!(
+        container
+            .get_attribute("data-seigi-form-orientation")
+            .as_deref(),
+        Some("vertical")
+    );
+    assert!(container.has_attribute("data-seigi-form-offset-y"));
+    assert!(!container.has_attribute("data-seigi-form-offset-x"));
+}
+
+#[wasm_bindgen_test]
+async fn failed_validation_focuses_first_invalid_control() {
+    let fixture = Fixture::new(
+        r#"
+<div id="form">
+    <div data-seigi-stage>
+        <input name="nickname">
+        <div id="plan"><input type="radio" name="plan" value="free"></div>
+    </div>
+    <div data-seigi-stage><button>Submit</button></div>
+</div>
+"#,
+    );
+    let validator = create_validator(
+        ValidatorOptions::builder()
+            .field(
+                Field::new("plan", fixture.get("#plan")).rule(Rule::custom(|_, _| {
+                    Some(ValidationError::new("plan", "Choose a plan"))
+                })),
+            )
+            .build(),
+    );
+    let stages = fixture.get_all("[data-seigi-stage]");
+    let form = Form::builder()
+        .container(fixture.get("#form"))
+        .add_stage(Stage::from_container(stages[0].clone()).validator(validator))
+        .add_stage(Stage::from_container(stages[1].clone()))
+        .error_focus(ErrorFocus {
+            prevent_scroll: true,
+            announce: true,
+        })
+        .build()
+        .unwrap();
+    form.initialize();
+    form.activate();
+    wait::tick().await;
+
+    assert_focused(&fixture.get("[name=nickname]"));
+    form.next();
+    assert_eq!(form.current(), 0);
+    // The group isn't focusable, so its first radio button is
+    assert_focused(&fixture.get("[name=plan]"));
+
+    form.deactivate();
+}
+
+#[wasm_bindgen_test]
+fn dirty_state_follows_edits() {
+    let fixture = Fixture::new(MARKUP);
+    let form = form(&fixture, 0);
+    let name = fixture.get("[name=name]");
+    assert!(!form.is_dirty());
+
+    name.unchecked_ref::<HtmlInputElement>().set_value("Kim");
+    events::fire(&name, "input");
+    assert!(form.is_dirty());
+    assert!(form.is_stage_dirty(0));
+    assert!(!form.is_stage_dirty(1));
+    assert_eq!(form.dirty_fields(0), ["name"]);
+    assert!(name.has_attribute("data-seigi-field-dirty"));
+    assert!(fixture.get("#form").has_attribute("data-seigi-form-dirty"));
+
+    // Restoring the initial value makes the field clean again
+    name.unchecked_ref::<HtmlInputElement>().set_value("");
+    events::fire(&name, "input");
+    assert!(!form.is_dirty());
+    assert!(!fixture.get("#form").has_attribute("data-seigi-form-dirty"));
+
+    name.unchecked_ref::<HtmlInputElement>().set_value("Lee");
+    events::fire(&name, "change");
+    form.mark_pristine();
+    assert!(!form.is_dirty());
+    assert!(!name.has_attribute("data-seigi-field-dirty"));
+    assert!(form.dirty_fields(0).is_empty());
+}
+
+#[wasm_bindgen_test]
+fn repeated_stages_are_cloned_from_templates() {
+    let fixture = Fixture::new(
+        r#"
+<div id="form">
+    <div data-seigi-stage>
+        <label for="passenger-name">Name</label>
+        <input id="passenger-name" name="passenger[0].name">
+    </div>
+    <div data-seigi-stage><button>Submit</button></div>
+</div>
+<template id="passenger">
+    <div data-seigi-stage>
+        <label for="passenger-name">Name</label>
+        <input id="passenger-name" name="name">
+    </div>
+</template>
+"#,
+    );
+    let stages = fixture.get_all("[data-seigi-stage]");
+    let form = Form::builder()
+        .container(fixture.get("#form"))
+        .add_stage(
+            Stage::from_container(stages[0].clone())
+                .label("Passenger")
+                .repeatable(fixture.get("#passenger").unchecked_into(), "passenger"),
+        )
+        .add_stage(Stage::from_container(stages[1].clone()))
+        .initial_stage(1)
+        .build()
+        .unwrap();
+    form.initialize();
+    form.activate();
+
+    let notified = Rc::new(Cell::new(None));
+    let callback = notified.clone();
+    form.subscribe(Box::new(move |stage| callback.set(Some(stage))));
+
+    assert_eq!(form.repeat_stage(0), Some(1));
+    assert_eq!(form.repeat_stage(0), Some(2));
+    assert_eq!(form.repeat_stage(1), None);
+    assert_eq!(form.len(), 4);
+    // The submit stage shifted after the instances
+    assert_eq!(form.current(), 3);
+    assert_eq!(notified.get(), Some(3));
+    assert_eq!(form.meta(2).unwrap().label.as_deref(), Some("Passenger"));
+    assert_eq!(relatives(&fixture), ["-3", "-2", "-1", "0"]);
+
+    let second = fixture.get("[data-seigi-stage-repeat='2']");
+    let input = second.query_selector("input").unwrap().unwrap();
+    assert_eq!(
+        input.get_attribute("name").as_deref(),
+        Some("passenger[2].name")
+    );
+    assert_eq!(input.id(), "passenger-name-2");
+    let label = second.query_selector("label").unwrap().unwrap();
+    assert_eq!(
+        label.get_attribute("for").as_deref(),
+        Some("passenger-name-2")
+    );
+
+    // Instances are stages like others
+    form.stage(1);
+    assert_eq!(form.current(), 1);
+    assert_eq!(relatives(&fixture), ["-1", "0", "1", "2"]);
+}
+
+#[wasm_bindgen_test]
+fn readonly_mode_disables_controls_and_stays_on_stage() {
+    let fixture = Fixture::new(
+        r#"
+<div id="form">
+    <div data-seigi-stage><input id="name" name="name"></div>
+    <div data-seigi-stage><input id="email" name="email" disabled></div>
+    <div data-seigi-stage><button id="submit">Submit</button></div>
+</div>
+"#,
+    );
+    let form = form(&fixture, 0);
+    let container = fixture.get("#form");
+    let stage = fixture.get_all("[data-seigi-stage]").remove(0);
+    assert!(stage.has_attribute("data-seigi-trap-active"));
+
+    form.set_readonly(true);
+    assert!(form.is_readonly());
+    assert!(container.has_attribute("data-seigi-form-readonly"));
+    assert!(fixture.get("#name").has_attribute("disabled"));
+    assert!(fixture.get("#submit").has_attribute("disabled"));
+    assert!(!stage.has_attribute("data-seigi-trap-active"));
+
+    form.next();
+    assert_eq!(form.current(), 0);
+
+    form.set_readonly(false);
+    assert!(!container.has_attribute("data-seigi-form-readonly"));
+    assert!(!fixture.get("#name").has_attribute("disabled"));
+    assert!(!fixture.get("#submit").has_attribute("disabled"));
+    // Disabled by the markup, so left as it was
+    assert!(fixture.get("#email").has_attribute("disabled"));
+    assert!(stage.has_attribute("data-seigi-trap-active"));
+
+    form.next();
+    assert_eq!(form.current(), 1);
+    form.deactivate();
+}
+
+#[wasm_bindgen_test]
+async fn stages_navigated_back_to_focus_last_focused_field() {
+    let fixture = Fixture::new(
+        r#"
+<div id="form">
+    <div data-seigi-stage><input id="first"><input id="second"></div>
+    <div data-seigi-stage><input id="third"></div>
+</div>
+"#,
+    );
+    let form = form(&fixture, 0);
+    wait::tick().await;
+    assert_focused(&fixture.get("#first"));
+
+    fixture.get("#second").focus().unwrap();
+    form.next();
+    wait::tick().await;
+    assert_focused(&fixture.get("#third"));
+
+    form.previous();
+    wait::tick().await;
+    assert_focused(&fixture.get("#second"));
+
+    form.deactivate();
+}
+
+#[wasm_bindgen_test]
+fn url_sync_follows_query_parameter() {
+    let fixture = Fixture::new(MARKUP);
+    let window = gloo::utils::window();
+    let history = window.history().unwrap();
+    let set_step = |step: &str| {
+        history
+            .replace_state_with_url(&JsValue::NULL, "", Some(&format!("?step={step}")))
+            .unwrap();
+    };
+    let step = || {
+        web_sys::UrlSearchParams::new_with_str(&window.location().search().unwrap())
+            .unwrap()
+            .get("step")
+    };
+    let stages = fixture
+        .get_all("[data-seigi-stage]")
+        .into_iter()
+        .map(Stage::from_container)
+        .enumerate()
+        .map(|(index, stage)| match index {
+            1 => stage.id("contact"),
+            _ => stage,
+        });
+
+    set_step("contact");
+    let form = Form::builder()
+        .container(fixture.get("#form"))
+        .add_stages(stages)
+        .url_sync(UrlSync::Query("step".into()))
+        .build()
+        .unwrap();
+    form.initialize();
+    form.activate();
+    assert_eq!(form.current(), 1);
+
+    form.next();
+    assert_eq!(step().as_deref(), Some("3"));
+
+    set_step("contact");
+    events::dispatch(&window, &web_sys::Event::new("popstate").unwrap());
+    assert_eq!(form.current(), 1);
+
+    // Names of no stage are replaced by the current stage
+    set_step("unknown");
+    events::dispatch(&window, &web_sys::Event::new("popstate").unwrap());
+    assert_eq!(form.current(), 1);
+    assert_eq!(step().as_deref(), Some("contact"));
+
+    form.deactivate();
+    history
+        .replace_state_with_url(
+            &JsValue::NULL,
+            "",
+            Some(&window.location().pathname().unwrap()),
+        )
+        .unwrap();
+}
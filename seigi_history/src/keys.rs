@@ -0,0 +1,55 @@
+//! Keyboard shortcuts of undo and redo
+
+use seigi_hotkeys::{Conflict, Scope, ShortcutOptions};
+
+use crate::History;
+
+/// Shortcuts registered by [bind], which are unregistered when dropped
+pub struct KeyBinding {
+    handles: Vec<u64>,
+}
+
+impl Drop for KeyBinding {
+    fn drop(&mut self) {
+        for handle in &self.handles {
+            seigi_hotkeys::unregister(*handle);
+        }
+    }
+}
+
+/// Binds `mod+z` to undo, and `mod+shift+z` and `mod+y` to redo in the scope
+///
+/// Shortcuts are ignored while typing in text fields, so their native undo keeps working.
+///
+/// # Errors
+/// [Conflict] if the scope already has one of the shortcuts, in which case none is registered
+pub fn bind<T: Clone + 'static>(
+    history: &History<T>,
+    scope: Scope,
+) -> Result<KeyBinding, Conflict> {
+    let mut binding = KeyBinding { handles: vec![] };
+    let shortcuts = [
+        ("mod+z", "Undo", true),
+        ("mod+shift+z", "Redo", false),
+        ("mod+y", "Redo", false),
+    ];
+    for (keys, description, is_undo) in shortcuts {
+        let history = history.clone();
+        let handle = seigi_hotkeys::register(
+            ShortcutOptions::builder()
+                .keys(keys)
+                .description(description)
+                .scope(scope.clone())
+                .handler(move |_| {
+                    if is_undo {
+                        history.undo();
+                    } else {
+                        history.redo();
+                    }
+                })
+                .build(),
+        )?;
+        binding.handles.push(handle);
+    }
+    Ok(binding)
+}
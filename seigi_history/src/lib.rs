@@ -0,0 +1,357 @@
+//! Undo and redo history of snapshots of a state
+//!
+//! A [History] keeps past and future snapshots around the present one. Changes recorded with
+//! [History::push] are undoable, and changes made in a transaction are undone at once, such as
+//! every keystroke of a field edited until it blurs. [bind] maps the usual keyboard shortcuts to
+//! undo and redo.
+
+mod keys;
+
+use std::{
+    collections::VecDeque,
+    rc::Rc,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+pub use keys::{KeyBinding, bind};
+
+/// The number of snapshots kept before the present by default
+pub const MAX_DEPTH: usize = 100;
+
+/// A snapshot of [History] passed to subscribers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryState<T> {
+    pub present: T,
+    pub can_undo: bool,
+    pub can_redo: bool,
+}
+
+pub type Callback<T> = Box<dyn Fn(&HistoryState<T>)>;
+
+/// Options of [History]
+pub struct HistoryOptions<T> {
+    pub initial: T,
+    /// The number of snapshots kept before the present, where the oldest are dropped first
+    pub max_depth: usize,
+}
+
+impl<T> HistoryOptions<T> {
+    pub fn builder() -> HistoryOptionsBuilder<T> {
+        HistoryOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [HistoryOptions]
+pub struct HistoryOptionsBuilder<T> {
+    initial: Option<T>,
+    max_depth: usize,
+}
+
+impl<T> Default for HistoryOptionsBuilder<T> {
+    fn default() -> Self {
+        Self {
+            initial: None,
+            max_depth: MAX_DEPTH,
+        }
+    }
+}
+
+impl<T> HistoryOptionsBuilder<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn initial(mut self, initial: T) -> Self {
+        self.initial = Some(initial);
+        self
+    }
+
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Builds into [HistoryOptions]
+    ///
+    /// # Panics
+    /// This method panics if initial field is not set
+    pub fn build(self) -> HistoryOptions<T> {
+        HistoryOptions {
+            initial: self
+                .initial
+                .expect("initial must be set to build HistoryOptions"),
+            max_depth: self.max_depth,
+        }
+    }
+}
+
+struct Subscriber<T> {
+    callback: Callback<T>,
+    handle: u64,
+}
+
+struct Transaction<T> {
+    /// The present when the outermost transaction began
+    before: T,
+    /// The number of transactions begun and not yet committed, as they nest
+    depth: usize,
+    /// Whether a snapshot was pushed in the transaction, as committing one without changes
+    /// records nothing
+    is_changed: bool,
+}
+
+struct Inner<T> {
+    max_depth: usize,
+    past: VecDeque<T>,
+    present: T,
+    future: Vec<T>,
+    transaction: Option<Transaction<T>>,
+    subscribers: Vec<Rc<Subscriber<T>>>,
+}
+
+impl<T: Clone> Inner<T> {
+    fn state(&self) -> HistoryState<T> {
+        HistoryState {
+            present: self.present.clone(),
+            can_undo: !self.past.is_empty(),
+            can_redo: !self.future.is_empty(),
+        }
+    }
+
+    fn record(&mut self, before: T) {
+        self.past.push_back(before);
+        while self.past.len() > self.max_depth {
+            self.past.pop_front();
+        }
+        self.future.clear();
+    }
+}
+
+/// An instance of undo and redo history
+///
+/// Snapshots are cloned when read and recorded, so states holding large data are better kept
+/// in `Rc`s.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+pub struct History<T>(Rc<Mutex<Inner<T>>>);
+
+impl<T> Clone for History<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: Clone + 'static> History<T> {
+    /// Returns the present snapshot
+    pub fn get(&self) -> T {
+        self.0.lock().unwrap().present.clone()
+    }
+
+    pub fn state(&self) -> HistoryState<T> {
+        self.0.lock().unwrap().state()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.0.lock().unwrap().past.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.0.lock().unwrap().future.is_empty()
+    }
+
+    /// Makes the snapshot present, recording the previous one to undo to
+    ///
+    /// In a transaction, the previous one isn't recorded as the transaction is recorded once
+    /// committed.
+    pub fn push(&self, present: T) {
+        {
+            let mut inner = self.0.lock().unwrap();
+            let before = std::mem::replace(&mut inner.present, present);
+            match &mut inner.transaction {
+                Some(transaction) => transaction.is_changed = true,
+                None => inner.record(before),
+            }
+        }
+        self.publish();
+    }
+
+    /// Makes the snapshot present without recording the previous one, such as to correct the
+    /// present from outside
+    pub fn replace(&self, present: T) {
+        self.0.lock().unwrap().present = present;
+        self.publish();
+    }
+
+    /// Updates the present with the function, and records the previous one
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        let mut present = self.get();
+        f(&mut present);
+        self.push(present);
+    }
+
+    /// Steps back to the previous snapshot, committing a transaction in progress first
+    ///
+    /// # Returns
+    /// The snapshot now present, or None if there was nothing to undo
+    pub fn undo(&self) -> Option<T> {
+        self.commit_all();
+        let present = {
+            let mut inner = self.0.lock().unwrap();
+            let previous = inner.past.pop_back()?;
+            let present = std::mem::replace(&mut inner.present, previous.clone());
+            inner.future.push(present);
+            previous
+        };
+        self.publish();
+        Some(present)
+    }
+
+    /// Steps forward to the snapshot undone last
+    ///
+    /// # Returns
+    /// The snapshot now present, or None if there was nothing to redo
+    pub fn redo(&self) -> Option<T> {
+        self.commit_all();
+        let present = {
+            let mut inner = self.0.lock().unwrap();
+            let next = inner.future.pop()?;
+            let present = std::mem::replace(&mut inner.present, next.clone());
+            inner.past.push_back(present);
+            next
+        };
+        self.publish();
+        Some(present)
+    }
+
+    /// Begins a transaction, where snapshots pushed until it is committed are undone at once
+    ///
+    /// Transactions nest, and only the outermost one is recorded.
+    pub fn begin(&self) {
+        let mut inner = self.0.lock().unwrap();
+        match &mut inner.transaction {
+            Some(transaction) => transaction.depth += 1,
+            None => {
+                inner.transaction = Some(Transaction {
+                    before: inner.present.clone(),
+                    depth: 1,
+                    is_changed: false,
+                })
+            }
+        }
+    }
+
+    /// Commits the innermost transaction, recording the outermost one once it is committed if
+    /// anything was pushed in it
+    pub fn commit(&self) {
+        let mut inner = self.0.lock().unwrap();
+        let Some(transaction) = &mut inner.transaction else {
+            return;
+        };
+        transaction.depth -= 1;
+        if transaction.depth > 0 {
+            return;
+        }
+        let transaction = inner.transaction.take().unwrap();
+        if !transaction.is_changed {
+            return;
+        }
+        inner.record(transaction.before);
+        drop(inner);
+        self.publish();
+    }
+
+    /// Cancels every transaction in progress, restoring the present they began from
+    pub fn rollback(&self) {
+        {
+            let mut inner = self.0.lock().unwrap();
+            let Some(transaction) = inner.transaction.take() else {
+                return;
+            };
+            inner.present = transaction.before;
+        }
+        self.publish();
+    }
+
+    /// Runs the function in a transaction
+    pub fn transaction(&self, f: impl FnOnce(&Self)) {
+        self.begin();
+        f(self);
+        self.commit();
+    }
+
+    pub fn is_in_transaction(&self) -> bool {
+        self.0.lock().unwrap().transaction.is_some()
+    }
+
+    fn commit_all(&self) {
+        let depth = self
+            .0
+            .lock()
+            .unwrap()
+            .transaction
+            .as_ref()
+            .map_or(0, |v| v.depth);
+        for _ in 0..depth {
+            self.commit();
+        }
+    }
+
+    /// Forgets past and future snapshots, keeping the present
+    pub fn clear(&self) {
+        {
+            let mut inner = self.0.lock().unwrap();
+            inner.past.clear();
+            inner.future.clear();
+            inner.transaction = None;
+        }
+        self.publish();
+    }
+
+    /// Subscribes to changes of the present and of whether undo and redo are available
+    ///
+    /// # Returns
+    /// A handle to unsubscribe with [History::unsubscribe]
+    pub fn subscribe(&self, callback: Callback<T>) -> u64 {
+        static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+        let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .push(Rc::new(Subscriber { callback, handle }));
+        handle
+    }
+
+    pub fn unsubscribe(&self, handle: u64) {
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .retain(|v| v.handle != handle);
+    }
+
+    fn publish(&self) {
+        let (subscribers, state) = {
+            let inner = self.0.lock().unwrap();
+            (inner.subscribers.clone(), inner.state())
+        };
+        for subscriber in subscribers {
+            (subscriber.callback)(&state);
+        }
+    }
+}
+
+pub fn create_history<T: Clone + 'static>(options: HistoryOptions<T>) -> History<T> {
+    History(Rc::new(Mutex::new(Inner {
+        max_depth: options.max_depth,
+        past: VecDeque::new(),
+        present: options.initial,
+        future: vec![],
+        transaction: None,
+        subscribers: vec![],
+    })))
+}
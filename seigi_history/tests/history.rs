@@ -0,0 +1,211 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::{cell::RefCell, rc::Rc};
+
+use seigi_history::{History, HistoryOptions, HistoryState};
+
+fn create(max_depth: usize) -> History<i32> {
+    seigi_history::create_history(
+        HistoryOptions::builder()
+            .initial(0)
+            .max_depth(max_depth)
+            .build(),
+    )
+}
+
+/// Undoes until nothing is left, returning each snapshot made present
+fn undo_all(history: &History<i32>) -> Vec<i32> {
+    std::iter::from_fn(|| history.undo()).collect()
+}
+
+#[test]
+fn pushed_snapshots_are_undone_and_redone() {
+    let history = create(seigi_history::MAX_DEPTH);
+    assert!(!history.can_undo());
+    assert_eq!(history.undo(), None);
+
+    history.push(1);
+    history.push(2);
+    assert_eq!(
+        history.state(),
+        HistoryState {
+            present: 2,
+            can_undo: true,
+            can_redo: false,
+        }
+    );
+
+    assert_eq!(history.undo(), Some(1));
+    assert_eq!(history.undo(), Some(0));
+    assert_eq!(history.undo(), None);
+    assert!(history.can_redo());
+    assert_eq!(history.redo(), Some(1));
+    assert_eq!(history.redo(), Some(2));
+    assert_eq!(history.redo(), None);
+}
+
+#[test]
+fn pushing_drops_future() {
+    let history = create(seigi_history::MAX_DEPTH);
+    history.push(1);
+    history.push(2);
+    history.undo();
+
+    history.update(|v| *v += 10);
+    assert_eq!(history.get(), 11);
+    assert!(!history.can_redo());
+    assert_eq!(undo_all(&history), [1, 0]);
+}
+
+#[test]
+fn replaced_snapshots_are_not_recorded() {
+    let history = create(seigi_history::MAX_DEPTH);
+    history.push(1);
+    history.replace(5);
+    assert_eq!(undo_all(&history), [0]);
+    assert_eq!(history.redo(), Some(5));
+}
+
+#[test]
+fn oldest_snapshots_are_dropped_past_max_depth() {
+    let history = create(2);
+    for value in 1..=4 {
+        history.push(value);
+    }
+    assert_eq!(undo_all(&history), [3, 2]);
+
+    // Every snapshot undone can be redone
+    assert_eq!(std::iter::from_fn(|| history.redo()).count(), 2);
+    assert_eq!(history.get(), 4);
+
+    let history = create(0);
+    history.push(1);
+    assert!(!history.can_undo());
+}
+
+#[test]
+fn transactions_are_undone_at_once() {
+    let history = create(seigi_history::MAX_DEPTH);
+    history.push(1);
+
+    history.begin();
+    assert!(history.is_in_transaction());
+    history.push(2);
+    history.push(3);
+    assert!(history.can_undo());
+    history.commit();
+    assert!(!history.is_in_transaction());
+
+    assert_eq!(history.get(), 3);
+    assert_eq!(undo_all(&history), [1, 0]);
+}
+
+#[test]
+fn nested_transactions_are_recorded_by_outermost() {
+    let history = create(seigi_history::MAX_DEPTH);
+    history.begin();
+    history.push(1);
+    history.transaction(|history| {
+        history.push(2);
+        history.transaction(|history| history.push(3));
+    });
+    // The inner transactions are committed, the outer one is still in progress
+    assert!(history.is_in_transaction());
+    assert!(!history.can_undo());
+
+    history.commit();
+    assert!(!history.is_in_transaction());
+    assert_eq!(undo_all(&history), [0]);
+
+    // Committing without a transaction does nothing
+    history.commit();
+    assert_eq!(history.redo(), Some(3));
+}
+
+#[test]
+fn empty_transactions_are_not_recorded() {
+    let history = create(seigi_history::MAX_DEPTH);
+    history.push(1);
+    history.undo();
+
+    history.transaction(|_| {});
+    assert!(!history.can_undo());
+    assert_eq!(history.redo(), Some(1));
+}
+
+#[test]
+fn undo_commits_transaction_in_progress() {
+    let history = create(seigi_history::MAX_DEPTH);
+    history.push(1);
+    history.begin();
+    history.begin();
+    history.push(2);
+    history.push(3);
+
+    assert_eq!(history.undo(), Some(1));
+    assert!(!history.is_in_transaction());
+    assert_eq!(history.redo(), Some(3));
+
+    // Redo commits too
+    history.begin();
+    history.push(4);
+    assert_eq!(history.redo(), None);
+    assert!(!history.is_in_transaction());
+    assert_eq!(undo_all(&history), [3, 1, 0]);
+}
+
+#[test]
+fn rollback_restores_present_before_transaction() {
+    let history = create(seigi_history::MAX_DEPTH);
+    history.push(1);
+    history.begin();
+    history.push(2);
+    history.begin();
+    history.push(3);
+
+    history.rollback();
+    assert!(!history.is_in_transaction());
+    assert_eq!(history.get(), 1);
+    assert_eq!(undo_all(&history), [0]);
+}
+
+#[test]
+fn clear_keeps_present() {
+    let history = create(seigi_history::MAX_DEPTH);
+    history.push(1);
+    history.push(2);
+    history.undo();
+    history.begin();
+
+    history.clear();
+    assert_eq!(
+        history.state(),
+        HistoryState {
+            present: 1,
+            can_undo: false,
+            can_redo: false,
+        }
+    );
+    assert!(!history.is_in_transaction());
+}
+
+#[test]
+fn subscribers_are_notified_of_changes() {
+    let history = create(seigi_history::MAX_DEPTH);
+    let states = Rc::new(RefCell::new(vec![]));
+    let handle = history.subscribe(Box::new({
+        let states = states.clone();
+        move |state: &HistoryState<i32>| states.borrow_mut().push((state.present, state.can_undo))
+    }));
+
+    history.push(1);
+    history.undo();
+    history.transaction(|history| history.push(2));
+    history.unsubscribe(handle);
+    history.push(3);
+    // Pushes in the transaction notify of the present, and the commit of undo being available
+    assert_eq!(
+        *states.borrow(),
+        [(1, true), (0, false), (2, false), (2, true)]
+    );
+}
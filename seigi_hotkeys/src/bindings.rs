@@ -0,0 +1,147 @@
+//! Keys handled by a single widget, like arrow keys moving through a list
+//!
+//! Unlike shortcuts of the registry, [KeyBindings] belong to a widget and handle key presses on
+//! its element, so they suit components and primitives which already own a `keydown` listener.
+//! Bindings are tried in the order they were added, and the first matching one handles the
+//! press. Key presses composing text with an IME are left alone, and held keys repeating are
+//! ignored unless the binding accepts them.
+//!
+//! # Examples
+//! ```ignore
+//! let bindings = keymap! {
+//!     "arrowdown", repeat => move |_| list.next(),
+//!     "arrowup", repeat => move |_| list.previous(),
+//!     "escape" => move |_| list.close(),
+//! };
+//! let _listener = bindings.listen(&element);
+//! ```
+
+use gloo::events::{EventListener, EventListenerOptions};
+use seigi_utils::ime;
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, KeyboardEvent};
+
+use crate::{Combo, Handler};
+
+/// A key bound to a handler
+pub struct Binding {
+    pub combo: Combo,
+    /// Whether the binding handles presses repeated while the key is held
+    pub repeat: bool,
+    /// Whether the default action of the press is prevented once handled
+    pub prevent_default: bool,
+    pub handler: Handler,
+}
+
+impl Binding {
+    /// Binds keys in the form [Combo::parse] accepts to the handler
+    ///
+    /// # Panics
+    /// This method panics if the keys can't be parsed
+    pub fn new(keys: &str, handler: impl Fn(&KeyboardEvent) + 'static) -> Self {
+        let combo = Combo::parse(keys)
+            .unwrap_or_else(|| panic!("keys `{keys}` of Binding can't be parsed"));
+        Self::from_combo(combo, handler)
+    }
+
+    pub fn from_combo(combo: Combo, handler: impl Fn(&KeyboardEvent) + 'static) -> Self {
+        Self {
+            combo,
+            repeat: false,
+            prevent_default: true,
+            handler: Box::new(handler),
+        }
+    }
+
+    pub fn repeat(mut self, repeat: bool) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    pub fn prevent_default(mut self, prevent_default: bool) -> Self {
+        self.prevent_default = prevent_default;
+        self
+    }
+
+    fn matches(&self, event: &KeyboardEvent) -> bool {
+        (self.repeat || !event.repeat()) && self.combo.matches(event)
+    }
+}
+
+/// Bindings of keys to handlers of a widget, tried in order
+#[derive(Default)]
+pub struct KeyBindings {
+    bindings: Vec<Binding>,
+}
+
+impl KeyBindings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the binding after those added before, which take precedence over it
+    pub fn bind(mut self, binding: Binding) -> Self {
+        self.bindings.push(binding);
+        self
+    }
+
+    pub fn bindings(&self) -> &[Binding] {
+        &self.bindings
+    }
+
+    /// Calls the handler of the first binding matching the key press
+    ///
+    /// Presses cancelled by another listener or composing text with an IME are ignored.
+    ///
+    /// # Returns
+    /// Whether a binding handled the press
+    pub fn handle(&self, event: &KeyboardEvent) -> bool {
+        if event.default_prevented() || ime::is_composing(event) {
+            return false;
+        }
+        let Some(binding) = self.bindings.iter().find(|v| v.matches(event)) else {
+            return false;
+        };
+        if binding.prevent_default {
+            event.prevent_default();
+        }
+        (binding.handler)(event);
+        true
+    }
+
+    /// Handles key presses on the target until the returned listener is dropped
+    pub fn listen(self, target: &EventTarget) -> EventListener {
+        EventListener::new_with_options(
+            target,
+            "keydown",
+            EventListenerOptions::enable_prevent_default(),
+            move |event| {
+                // Events of other documents aren't instances of KeyboardEvent of this window
+                self.handle(event.unchecked_ref());
+            },
+        )
+    }
+}
+
+/// Builds [KeyBindings] from keys in the form [Combo::parse] accepts and handlers, in order
+///
+/// Flags after the keys set the options of [Binding] of the same names, where `repeat` handles
+/// presses repeated while the key is held.
+///
+/// # Panics
+/// Panics if keys can't be parsed, like [Binding::new]
+///
+/// # Examples
+/// ```ignore
+/// let bindings = keymap! {
+///     "home" => move |_| list.first(),
+///     "arrowdown", repeat => move |_| list.next(),
+/// };
+/// ```
+#[macro_export]
+macro_rules! keymap {
+    ($($keys:literal $(, $flag:ident)* => $handler:expr),* $(,)?) => {
+        $crate::KeyBindings::new()
+            $(.bind($crate::Binding::new($keys, $handler)$(.$flag(true))*))*
+    };
+}
@@ -0,0 +1,238 @@
+//! Key combinations and sequences of them
+
+use std::fmt::{self, Display};
+
+use gloo::utils::window;
+use web_sys::KeyboardEvent;
+
+/// Returns whether the platform is an Apple one, where the primary modifier is Cmd
+pub fn is_apple() -> bool {
+    let navigator = window().navigator();
+    navigator
+        .platform()
+        .or_else(|_| navigator.user_agent())
+        .is_ok_and(|v| v.contains("Mac") || v.contains("iPhone") || v.contains("iPad"))
+}
+
+/// Normalizes a key name to the lowercase value of [KeyboardEvent::key]
+fn normalize(key: &str) -> String {
+    let key = key.to_lowercase();
+    match key.as_str() {
+        "esc" => "escape",
+        "space" => " ",
+        "plus" => "+",
+        "up" => "arrowup",
+        "down" => "arrowdown",
+        "left" => "arrowleft",
+        "right" => "arrowright",
+        "del" => "delete",
+        "return" => "enter",
+        _ => return key,
+    }
+    .to_string()
+}
+
+/// A key combination
+///
+/// `primary` is Cmd on Apple platforms and Ctrl elsewhere.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Combo {
+    /// The key, normalized to lowercase [KeyboardEvent::key]
+    pub key: String,
+    pub primary: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl Combo {
+    pub fn new(key: &str) -> Self {
+        Self {
+            key: normalize(key),
+            primary: false,
+            shift: false,
+            alt: false,
+        }
+    }
+
+    /// Parses a combination like `mod+k` or `shift+alt+p`
+    ///
+    /// `mod` is the primary modifier, and `ctrl`, `control`, `cmd` and `meta` are accepted as its
+    /// aliases. Keys are named as [KeyboardEvent::key], with aliases such as `esc`, `space`,
+    /// `plus` and `up`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.split('+').map(str::trim).collect::<Vec<_>>();
+        let key = parts.pop().filter(|v| !v.is_empty())?;
+        let mut combo = Self::new(key);
+        for part in parts {
+            match part.to_lowercase().as_str() {
+                "mod" | "ctrl" | "control" | "cmd" | "meta" => combo.primary = true,
+                "shift" => combo.shift = true,
+                "alt" | "option" => combo.alt = true,
+                _ => return None,
+            }
+        }
+        Some(combo)
+    }
+
+    pub fn primary(mut self) -> Self {
+        self.primary = true;
+        self
+    }
+
+    pub fn shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    pub fn alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    pub fn matches(&self, event: &KeyboardEvent) -> bool {
+        // Symbols like `?` are typed with Shift on some layouts and without on others
+        let is_symbol = self.key.chars().count() == 1
+            && self.key.chars().all(|v| !v.is_alphanumeric() && v != ' ');
+        normalize(&event.key()) == self.key
+            && (event.ctrl_key() || event.meta_key()) == self.primary
+            && (event.shift_key() == self.shift || (is_symbol && !self.shift))
+            && event.alt_key() == self.alt
+    }
+
+    /// Returns the combination as shown to users on this platform, like `⌘K` or `Ctrl+K`
+    pub fn label(&self) -> String {
+        let key = match self.key.as_str() {
+            " " => "Space".to_string(),
+            "escape" => "Esc".to_string(),
+            "arrowup" => "↑".to_string(),
+            "arrowdown" => "↓".to_string(),
+            "arrowleft" => "←".to_string(),
+            "arrowright" => "→".to_string(),
+            key => {
+                let mut chars = key.chars();
+                chars
+                    .next()
+                    .map(|v| v.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+        };
+
+        if is_apple() {
+            let mut label = String::new();
+            if self.alt {
+                label.push('⌥');
+            }
+            if self.shift {
+                label.push('⇧');
+            }
+            if self.primary {
+                label.push('⌘');
+            }
+            label + &key
+        } else {
+            let mut parts = vec![];
+            if self.primary {
+                parts.push("Ctrl");
+            }
+            if self.alt {
+                parts.push("Alt");
+            }
+            if self.shift {
+                parts.push("Shift");
+            }
+            parts.push(&key);
+            parts.join("+")
+        }
+    }
+}
+
+/// Formats into the form [Combo::parse] accepts
+impl Display for Combo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.primary {
+            f.write_str("mod+")?;
+        }
+        if self.shift {
+            f.write_str("shift+")?;
+        }
+        if self.alt {
+            f.write_str("alt+")?;
+        }
+        match self.key.as_str() {
+            " " => f.write_str("space"),
+            "+" => f.write_str("plus"),
+            key => f.write_str(key),
+        }
+    }
+}
+
+/// Combinations pressed one after another, like `g d`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Sequence(pub Vec<Combo>);
+
+impl Sequence {
+    /// Parses whitespace separated combinations, like `mod+k` or `g d`
+    pub fn parse(value: &str) -> Option<Self> {
+        let combos = value
+            .split_whitespace()
+            .map(Combo::parse)
+            .collect::<Option<Vec<_>>>()?;
+        (!combos.is_empty()).then_some(Self(combos))
+    }
+
+    pub fn combos(&self) -> &[Combo] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns whether either sequence starts with the other, so pressing the shorter one is
+    /// ambiguous
+    pub fn overlaps(&self, other: &Sequence) -> bool {
+        self.0.iter().zip(&other.0).all(|(a, b)| a == b)
+    }
+
+    /// Returns whether the events pressed so far are the sequence, or its start if `partial`
+    pub(crate) fn matches(&self, events: &[KeyboardEvent], partial: bool) -> bool {
+        let is_length = if partial {
+            events.len() < self.0.len()
+        } else {
+            events.len() == self.0.len()
+        };
+        is_length && self.0.iter().zip(events).all(|(a, b)| a.matches(b))
+    }
+
+    /// Returns the sequence as shown to users on this platform
+    pub fn label(&self) -> String {
+        self.0
+            .iter()
+            .map(Combo::label)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl From<Combo> for Sequence {
+    fn from(value: Combo) -> Self {
+        Self(vec![value])
+    }
+}
+
+/// Formats into the form [Sequence::parse] accepts
+impl Display for Sequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, combo) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            combo.fmt(f)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,397 @@
+//! A global registry of keyboard shortcuts
+//!
+//! Shortcuts are combinations like `mod+k`, or sequences of them like `g d`, registered with
+//! [register] and handled by a single listener on the document. They are scoped to the document,
+//! to an element containing focus, or to an active focus trap, and ignored while typing in text
+//! fields unless allowed. [shortcuts] lists them for help dialogs.
+//!
+//! Keys handled by a widget itself, like arrow keys moving through a list, are bound with
+//! [KeyBindings] or [keymap!] on the element of the widget instead.
+
+pub mod bindings;
+pub mod combo;
+
+use std::{
+    cell::RefCell,
+    error::Error,
+    fmt::{self, Display},
+    rc::Rc,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use gloo::{
+    events::{EventListener, EventListenerOptions},
+    timers::callback::Timeout,
+    utils::document,
+};
+use seigi_focus::FocusTrap;
+use seigi_utils::ime;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, HtmlInputElement, KeyboardEvent, Node};
+
+pub use bindings::{Binding, KeyBindings};
+pub use combo::{Combo, Sequence};
+
+/// Milliseconds a sequence waits for its next combination
+pub const SEQUENCE_TIMEOUT: u32 = 1000;
+
+/// Where a shortcut is active
+#[derive(Clone, Default)]
+pub enum Scope {
+    /// Anywhere in the document
+    #[default]
+    Global,
+    /// While focus is in the element
+    Element(Element),
+    /// While the trap is activated
+    Trap(FocusTrap),
+}
+
+impl Scope {
+    /// Returns how specific the scope is, where a more specific one takes precedence
+    fn specificity(&self) -> u8 {
+        match self {
+            Self::Global => 0,
+            Self::Element(_) => 1,
+            Self::Trap(_) => 2,
+        }
+    }
+
+    fn is_active(&self, target: Option<&Node>) -> bool {
+        match self {
+            Self::Global => true,
+            Self::Element(element) => element.contains(target),
+            Self::Trap(trap) => trap.is_activated(),
+        }
+    }
+
+    fn is_same(&self, other: &Scope) -> bool {
+        match (self, other) {
+            (Self::Global, Self::Global) => true,
+            (Self::Element(a), Self::Element(b)) => a == b,
+            (Self::Trap(a), Self::Trap(b)) => a.options().target == b.options().target,
+            _ => false,
+        }
+    }
+}
+
+pub type Handler = Box<dyn Fn(&KeyboardEvent)>;
+
+/// Options of a shortcut
+pub struct ShortcutOptions {
+    pub keys: Sequence,
+    /// What the shortcut does, listed by [shortcuts]
+    pub description: Option<String>,
+    pub scope: Scope,
+    /// Whether the shortcut works while typing in text fields
+    pub allow_in_inputs: bool,
+    /// Whether the default action of the last key press is prevented
+    pub prevent_default: bool,
+    pub handler: Handler,
+}
+
+impl ShortcutOptions {
+    pub fn builder() -> ShortcutOptionsBuilder {
+        ShortcutOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [ShortcutOptions]
+pub struct ShortcutOptionsBuilder {
+    keys: Option<String>,
+    description: Option<String>,
+    scope: Scope,
+    allow_in_inputs: bool,
+    prevent_default: bool,
+    handler: Option<Handler>,
+}
+
+impl Default for ShortcutOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            keys: None,
+            description: None,
+            scope: Scope::default(),
+            allow_in_inputs: false,
+            prevent_default: true,
+            handler: None,
+        }
+    }
+}
+
+impl ShortcutOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets keys in the form [Sequence::parse] accepts
+    pub fn keys(mut self, keys: impl Into<String>) -> Self {
+        self.keys = Some(keys.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn scope(mut self, scope: Scope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    pub fn allow_in_inputs(mut self, allow_in_inputs: bool) -> Self {
+        self.allow_in_inputs = allow_in_inputs;
+        self
+    }
+
+    pub fn prevent_default(mut self, prevent_default: bool) -> Self {
+        self.prevent_default = prevent_default;
+        self
+    }
+
+    pub fn handler(mut self, handler: impl Fn(&KeyboardEvent) + 'static) -> Self {
+        self.handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Builds into [ShortcutOptions]
+    ///
+    /// # Panics
+    /// This method panics if keys or handler field is not set, or keys can't be parsed
+    pub fn build(self) -> ShortcutOptions {
+        let keys = self
+            .keys
+            .expect("keys must be set to build ShortcutOptions");
+        ShortcutOptions {
+            keys: Sequence::parse(&keys)
+                .unwrap_or_else(|| panic!("keys `{keys}` of ShortcutOptions can't be parsed")),
+            description: self.description,
+            scope: self.scope,
+            allow_in_inputs: self.allow_in_inputs,
+            prevent_default: self.prevent_default,
+            handler: self
+                .handler
+                .expect("handler must be set to build ShortcutOptions"),
+        }
+    }
+}
+
+/// An error registering a shortcut ambiguous with a registered one in the same scope
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    /// The handle of the registered shortcut
+    pub handle: u64,
+    pub keys: Sequence,
+    pub description: Option<String>,
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "shortcut conflicts with `{}`", self.keys)?;
+        if let Some(description) = &self.description {
+            write!(f, " ({description})")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for Conflict {}
+
+/// A registered shortcut, as listed by [shortcuts]
+#[derive(Debug, Clone)]
+pub struct Shortcut {
+    pub handle: u64,
+    pub keys: Sequence,
+    pub description: Option<String>,
+    /// Whether the scope of the shortcut is active with the current focus
+    pub is_active: bool,
+}
+
+struct Entry {
+    handle: u64,
+    options: Rc<ShortcutOptions>,
+}
+
+#[derive(Default)]
+struct State {
+    entries: Vec<Entry>,
+    /// Key presses of a sequence in progress
+    pending: Vec<KeyboardEvent>,
+    timer: Option<Timeout>,
+    listener: Option<EventListener>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+/// Returns whether the target is a field text is typed into
+fn is_typing(target: Option<&Element>) -> bool {
+    let Some(target) = target else {
+        return false;
+    };
+    if let Some(input) = target.dyn_ref::<HtmlInputElement>() {
+        return !matches!(
+            input.type_().as_str(),
+            "button"
+                | "checkbox"
+                | "color"
+                | "file"
+                | "image"
+                | "radio"
+                | "range"
+                | "reset"
+                | "submit"
+        );
+    }
+    matches!(target.tag_name().as_str(), "TEXTAREA" | "SELECT")
+        || target
+            .dyn_ref::<HtmlElement>()
+            .is_some_and(|v| v.is_content_editable())
+}
+
+fn handle_key_down(event: &KeyboardEvent) {
+    // Keys composing text with an IME aren't shortcuts
+    if event.default_prevented() || ime::is_composing(event) {
+        return;
+    }
+    if matches!(
+        event.key().as_str(),
+        "Control" | "Meta" | "Shift" | "Alt" | "AltGraph" | "CapsLock"
+    ) {
+        return;
+    }
+
+    let target = event.target().and_then(|v| v.dyn_into::<Element>().ok());
+    let is_typing = is_typing(target.as_ref());
+    let node = target.as_ref().map(|v| v.unchecked_ref::<Node>());
+
+    let matched = STATE.with_borrow_mut(|state| {
+        let candidates = state
+            .entries
+            .iter()
+            .filter(|v| v.options.allow_in_inputs || !is_typing)
+            .filter(|v| v.options.scope.is_active(node))
+            .collect::<Vec<_>>();
+
+        state.pending.push(event.clone());
+        loop {
+            // The most specific scope wins, then the latest registered shortcut
+            let exact = candidates
+                .iter()
+                .filter(|v| v.options.keys.matches(&state.pending, false))
+                .max_by_key(|v| (v.options.scope.specificity(), v.handle));
+            if let Some(entry) = exact {
+                state.pending.clear();
+                state.timer = None;
+                return Some(entry.options.clone());
+            }
+
+            let is_partial = candidates
+                .iter()
+                .any(|v| v.options.keys.matches(&state.pending, true));
+            if is_partial {
+                state.timer = Some(Timeout::new(SEQUENCE_TIMEOUT, || {
+                    STATE.with_borrow_mut(|state| {
+                        state.pending.clear();
+                        state.timer = None;
+                    });
+                }));
+                return None;
+            }
+
+            // Later presses may still be another sequence or its start, like `g g d` after `g g`
+            if state.pending.len() > 1 {
+                state.pending.remove(0);
+                continue;
+            }
+            state.pending.clear();
+            state.timer = None;
+            return None;
+        }
+    });
+
+    if let Some(options) = matched {
+        if options.prevent_default {
+            event.prevent_default();
+        }
+        (options.handler)(event);
+    }
+}
+
+/// Registers the shortcut
+///
+/// # Returns
+/// A handle to unregister with [unregister]
+///
+/// # Errors
+/// [Conflict] if a shortcut registered in the same scope has the same keys, or keys one of them
+/// starts with, so pressing them would be ambiguous
+pub fn register(options: ShortcutOptions) -> Result<u64, Conflict> {
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+    STATE.with_borrow_mut(|state| {
+        if let Some(entry) = state.entries.iter().find(|v| {
+            v.options.scope.is_same(&options.scope) && v.options.keys.overlaps(&options.keys)
+        }) {
+            return Err(Conflict {
+                handle: entry.handle,
+                keys: entry.options.keys.clone(),
+                description: entry.options.description.clone(),
+            });
+        }
+
+        let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        state.entries.push(Entry {
+            handle,
+            options: Rc::new(options),
+        });
+        if state.listener.is_none() {
+            state.listener = Some(EventListener::new_with_options(
+                &document(),
+                "keydown",
+                EventListenerOptions::enable_prevent_default(),
+                |event| {
+                    if let Some(event) = event.dyn_ref::<KeyboardEvent>() {
+                        handle_key_down(event);
+                    }
+                },
+            ));
+        }
+        Ok(handle)
+    })
+}
+
+pub fn unregister(handle: u64) {
+    STATE.with_borrow_mut(|state| {
+        state.entries.retain(|v| v.handle != handle);
+        if state.entries.is_empty() {
+            state.listener = None;
+            state.pending.clear();
+            state.timer = None;
+        }
+    });
+}
+
+/// Returns registered shortcuts in the order they were registered
+pub fn shortcuts() -> Vec<Shortcut> {
+    let focused = document().active_element();
+    let node = focused.as_ref().map(|v| v.unchecked_ref::<Node>());
+    STATE.with_borrow(|state| {
+        state
+            .entries
+            .iter()
+            .map(|v| Shortcut {
+                handle: v.handle,
+                keys: v.options.keys.clone(),
+                description: v.options.description.clone(),
+                is_active: v.options.scope.is_active(node),
+            })
+            .collect()
+    })
+}
+
+/// Returns shortcuts whose scope is active with the current focus
+pub fn active_shortcuts() -> Vec<Shortcut> {
+    shortcuts().into_iter().filter(|v| v.is_active).collect()
+}
@@ -0,0 +1,88 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use seigi_hotkeys::{Combo, Sequence};
+
+#[test]
+fn combos_are_parsed() {
+    assert_eq!(Combo::parse("mod+k"), Some(Combo::new("k").primary()));
+    assert_eq!(
+        Combo::parse("shift+alt+p"),
+        Some(Combo::new("p").shift().alt())
+    );
+    assert_eq!(Combo::parse("k"), Some(Combo::new("k")));
+    // Parts are trimmed and modifiers are case insensitive
+    assert_eq!(Combo::parse(" Shift + K "), Some(Combo::new("k").shift()));
+}
+
+#[test]
+fn modifier_aliases_are_primary() {
+    for value in ["mod+k", "ctrl+k", "control+k", "cmd+k", "meta+k"] {
+        assert_eq!(
+            Combo::parse(value),
+            Some(Combo::new("k").primary()),
+            "{value}"
+        );
+    }
+    assert_eq!(Combo::parse("option+k"), Some(Combo::new("k").alt()));
+}
+
+#[test]
+fn key_aliases_are_normalized() {
+    let key = |value: &str| Combo::parse(value).map(|v| v.key);
+    assert_eq!(key("esc").as_deref(), Some("escape"));
+    assert_eq!(key("Space").as_deref(), Some(" "));
+    assert_eq!(key("mod+plus").as_deref(), Some("+"));
+    assert_eq!(key("up").as_deref(), Some("arrowup"));
+    assert_eq!(key("del").as_deref(), Some("delete"));
+    assert_eq!(key("return").as_deref(), Some("enter"));
+    assert_eq!(key("ArrowLeft").as_deref(), Some("arrowleft"));
+}
+
+#[test]
+fn malformed_combos_are_rejected() {
+    assert_eq!(Combo::parse(""), None);
+    assert_eq!(Combo::parse("mod+"), None);
+    // `+` itself is named `plus`
+    assert_eq!(Combo::parse("mod++"), None);
+    assert_eq!(Combo::parse("hyper+k"), None);
+}
+
+#[test]
+fn combos_round_trip_through_display() {
+    for value in ["mod+shift+alt+k", "space", "mod+plus", "escape", "?"] {
+        let combo = Combo::parse(value).unwrap();
+        assert_eq!(combo.to_string(), value);
+        assert_eq!(Combo::parse(&combo.to_string()), Some(combo));
+    }
+    // Modifiers are written in a fixed order
+    assert_eq!(
+        Combo::parse("alt+shift+cmd+k").unwrap().to_string(),
+        "mod+shift+alt+k"
+    );
+}
+
+#[test]
+fn sequences_are_parsed() {
+    let sequence = Sequence::parse("g  d").unwrap();
+    assert_eq!(sequence.combos(), [Combo::new("g"), Combo::new("d")]);
+    assert_eq!(sequence.len(), 2);
+    assert_eq!(sequence.to_string(), "g d");
+    assert_eq!(
+        Sequence::parse("mod+k"),
+        Some(Sequence::from(Combo::new("k").primary()))
+    );
+
+    assert_eq!(Sequence::parse(""), None);
+    assert_eq!(Sequence::parse("g hyper+d"), None);
+}
+
+#[test]
+fn sequences_overlap_with_their_starts() {
+    let parse = |value: &str| Sequence::parse(value).unwrap();
+    assert!(parse("g d").overlaps(&parse("g d")));
+    assert!(parse("g").overlaps(&parse("g d")));
+    assert!(parse("g d e").overlaps(&parse("g d")));
+    assert!(!parse("g d").overlaps(&parse("g e")));
+    assert!(!parse("d").overlaps(&parse("g d")));
+    assert!(!parse("mod+g").overlaps(&parse("g d")));
+}
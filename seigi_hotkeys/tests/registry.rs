@@ -0,0 +1,301 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use gloo::utils::body;
+use seigi_hotkeys::{Combo, Conflict, Scope, Sequence, ShortcutOptions, combo};
+use seigi_testkit::{
+    Fixture,
+    events::{self, Modifiers},
+    wait,
+};
+use wasm_bindgen_test::*;
+use web_sys::EventTarget;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+type Calls = Rc<RefCell<Vec<&'static str>>>;
+
+/// Shortcuts registered by a test, unregistered when it ends as the registry is global
+#[derive(Default)]
+struct Registered {
+    handles: Vec<u64>,
+    calls: Calls,
+}
+
+impl Registered {
+    fn register(&mut self, keys: &str, name: &'static str, scope: Scope) -> Result<u64, Conflict> {
+        let calls = self.calls.clone();
+        let handle = seigi_hotkeys::register(
+            ShortcutOptions::builder()
+                .keys(keys)
+                .description(name)
+                .scope(scope)
+                .handler(move |_| calls.borrow_mut().push(name))
+                .build(),
+        )?;
+        self.handles.push(handle);
+        Ok(handle)
+    }
+
+    fn global(&mut self, keys: &str, name: &'static str) -> u64 {
+        self.register(keys, name, Scope::Global).unwrap()
+    }
+
+    fn calls(&self) -> Vec<&'static str> {
+        self.calls.borrow().clone()
+    }
+}
+
+impl Drop for Registered {
+    fn drop(&mut self) {
+        for handle in &self.handles {
+            seigi_hotkeys::unregister(*handle);
+        }
+    }
+}
+
+fn press_on(target: &EventTarget, key: &str, modifiers: Modifiers) -> bool {
+    events::key_down(target, key, modifiers)
+}
+
+/// Presses the keys one after another on the body
+fn press(keys: &[&str]) {
+    for key in keys {
+        press_on(&body(), key, Modifiers::default());
+    }
+}
+
+#[wasm_bindgen_test]
+fn combos_call_handlers() {
+    let mut registered = Registered::default();
+    registered.global("mod+k", "palette");
+
+    // Modifiers have to match
+    assert!(press_on(&body(), "k", Modifiers::default()));
+    assert!(press_on(
+        &body(),
+        "k",
+        Modifiers {
+            ctrl: true,
+            shift: true,
+            ..Modifiers::default()
+        }
+    ));
+    assert!(registered.calls().is_empty());
+
+    assert!(!press_on(
+        &body(),
+        "k",
+        Modifiers {
+            ctrl: true,
+            ..Modifiers::default()
+        }
+    ));
+    assert!(!press_on(
+        &body(),
+        "K",
+        Modifiers {
+            meta: true,
+            ..Modifiers::default()
+        }
+    ));
+    assert_eq!(registered.calls(), ["palette", "palette"]);
+}
+
+#[wasm_bindgen_test]
+fn symbols_match_with_or_without_shift() {
+    let mut registered = Registered::default();
+    registered.global("?", "help");
+
+    press_on(&body(), "?", Modifiers::shift());
+    press_on(&body(), "?", Modifiers::default());
+    assert_eq!(registered.calls(), ["help", "help"]);
+}
+
+#[wasm_bindgen_test]
+fn shortcuts_are_ignored_while_typing() {
+    let fixture = Fixture::new(r#"<input id="text"><input id="check" type="checkbox">"#);
+    let mut registered = Registered::default();
+    registered.global("k", "plain");
+    let calls = registered.calls.clone();
+    registered.handles.push(
+        seigi_hotkeys::register(
+            ShortcutOptions::builder()
+                .keys("mod+s")
+                .allow_in_inputs(true)
+                .handler(move |_| calls.borrow_mut().push("save"))
+                .build(),
+        )
+        .unwrap(),
+    );
+
+    let text = fixture.get("#text");
+    assert!(press_on(&text, "k", Modifiers::default()));
+    press_on(
+        &text,
+        "s",
+        Modifiers {
+            ctrl: true,
+            ..Modifiers::default()
+        },
+    );
+    // Fields text isn't typed into don't count
+    press_on(&fixture.get("#check"), "k", Modifiers::default());
+    assert_eq!(registered.calls(), ["save", "plain"]);
+}
+
+#[wasm_bindgen_test]
+fn cancelled_presses_are_ignored() {
+    let fixture = Fixture::new(r#"<div id="widget"></div>"#);
+    let mut registered = Registered::default();
+    registered.global("k", "plain");
+    let widget = fixture.get("#widget");
+    let _listener = gloo::events::EventListener::new_with_options(
+        &widget,
+        "keydown",
+        gloo::events::EventListenerOptions::enable_prevent_default(),
+        |event| event.prevent_default(),
+    );
+
+    press_on(&widget, "k", Modifiers::default());
+    assert!(registered.calls().is_empty());
+}
+
+#[wasm_bindgen_test]
+fn sequences_are_pressed_in_order() {
+    let mut registered = Registered::default();
+    registered.global("g d", "dashboard");
+
+    press(&["g", "d"]);
+    assert_eq!(registered.calls(), ["dashboard"]);
+    // Other keys between reset the sequence
+    press(&["g", "x", "d"]);
+    press(&["d"]);
+    assert_eq!(registered.calls(), ["dashboard"]);
+}
+
+#[wasm_bindgen_test]
+fn presses_breaking_sequence_start_another() {
+    let mut registered = Registered::default();
+    registered.global("g d", "dashboard");
+    registered.global("x", "close");
+    registered.global("g g d", "deep");
+
+    press(&["g", "x"]);
+    assert_eq!(registered.calls(), ["close"]);
+    press(&["g", "g", "g", "d"]);
+    assert_eq!(registered.calls(), ["close", "deep"]);
+}
+
+#[wasm_bindgen_test]
+async fn sequences_time_out() {
+    let mut registered = Registered::default();
+    registered.global("g d", "dashboard");
+
+    press(&["g"]);
+    wait::sleep(seigi_hotkeys::SEQUENCE_TIMEOUT + 100).await;
+    press(&["d"]);
+    assert!(registered.calls().is_empty());
+}
+
+#[wasm_bindgen_test]
+fn ambiguous_shortcuts_conflict_in_same_scope() {
+    let fixture = Fixture::new(r#"<div id="panel"></div>"#);
+    let mut registered = Registered::default();
+    let handle = registered.global("g d", "dashboard");
+
+    let conflict = registered.register("g", "go", Scope::Global).unwrap_err();
+    assert_eq!(
+        conflict,
+        Conflict {
+            handle,
+            keys: Sequence::parse("g d").unwrap(),
+            description: Some("dashboard".into()),
+        }
+    );
+    assert_eq!(
+        conflict.to_string(),
+        "shortcut conflicts with `g d` (dashboard)"
+    );
+    assert!(
+        registered
+            .register("g d e", "deeper", Scope::Global)
+            .is_err()
+    );
+    assert!(registered.register("G D", "same", Scope::Global).is_err());
+
+    // Different keys or scopes don't conflict
+    assert!(registered.register("g e", "editor", Scope::Global).is_ok());
+    let panel = Scope::Element(fixture.get("#panel").into());
+    assert!(registered.register("g", "panel", panel).is_ok());
+
+    seigi_hotkeys::unregister(handle);
+    assert!(
+        registered
+            .register("g d e", "deeper", Scope::Global)
+            .is_ok()
+    );
+}
+
+#[wasm_bindgen_test]
+fn specific_scopes_take_precedence() {
+    let fixture = Fixture::new(r#"<div id="panel"><button id="inner"></button></div>"#);
+    let mut registered = Registered::default();
+    registered.global("escape", "global");
+    registered
+        .register(
+            "escape",
+            "panel",
+            Scope::Element(fixture.get("#panel").into()),
+        )
+        .unwrap();
+
+    press_on(&fixture.get("#inner"), "Escape", Modifiers::default());
+    press_on(&body(), "Escape", Modifiers::default());
+    assert_eq!(registered.calls(), ["panel", "global"]);
+}
+
+#[wasm_bindgen_test]
+fn shortcuts_are_listed_with_active_scopes() {
+    let fixture = Fixture::new(r#"<div id="panel"><button id="inner"></button></div>"#);
+    let mut registered = Registered::default();
+    let global = registered.global("mod+k", "palette");
+    let panel = registered
+        .register(
+            "delete",
+            "remove",
+            Scope::Element(fixture.get("#panel").into()),
+        )
+        .unwrap();
+
+    let listed = seigi_hotkeys::shortcuts()
+        .into_iter()
+        .filter(|v| [global, panel].contains(&v.handle))
+        .map(|v| (v.description.unwrap(), v.is_active))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        listed,
+        [("palette".to_string(), true), ("remove".to_string(), false)]
+    );
+
+    fixture.get("#inner").focus().unwrap();
+    assert!(
+        seigi_hotkeys::active_shortcuts()
+            .iter()
+            .any(|v| v.handle == panel)
+    );
+}
+
+#[wasm_bindgen_test]
+fn labels_follow_platform() {
+    let combo = Combo::parse("mod+shift+k").unwrap();
+    let sequence = Sequence::parse("g up").unwrap();
+    if combo::is_apple() {
+        assert_eq!(combo.label(), "⇧⌘K");
+    } else {
+        assert_eq!(combo.label(), "Ctrl+Shift+K");
+    }
+    assert_eq!(sequence.label(), "G ↑");
+    assert_eq!(Combo::new("space").label(), "Space");
+}
@@ -0,0 +1,72 @@
+//! The area a pointer may travel through from the trigger to the card without closing it
+
+pub(crate) type Point = (f64, f64);
+
+/// Returns the convex hull of the points in counter-clockwise order
+fn hull(mut points: Vec<Point>) -> Vec<Point> {
+    points.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    let cross =
+        |o: Point, a: Point, b: Point| (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0);
+    let mut lower = Vec::<Point>::new();
+    for point in &points {
+        while lower.len() >= 2
+            && cross(lower[lower.len() - 2], lower[lower.len() - 1], *point) <= 0.0
+        {
+            lower.pop();
+        }
+        lower.push(*point);
+    }
+    let mut upper = Vec::<Point>::new();
+    for point in points.iter().rev() {
+        while upper.len() >= 2
+            && cross(upper[upper.len() - 2], upper[upper.len() - 1], *point) <= 0.0
+        {
+            upper.pop();
+        }
+        upper.push(*point);
+    }
+    lower.pop();
+    upper.pop();
+    lower.extend(upper);
+    lower
+}
+
+/// Returns the polygon between the point the pointer left the trigger at and the card
+///
+/// The exit point is padded a little, so the pointer moving along the edge of the trigger
+/// doesn't leave it at once.
+pub(crate) fn polygon(exit: Point, card: (f64, f64, f64, f64)) -> Vec<Point> {
+    const PADDING: f64 = 5.0;
+    let (left, top, right, bottom) = card;
+    hull(vec![
+        (exit.0 - PADDING, exit.1 - PADDING),
+        (exit.0 + PADDING, exit.1 - PADDING),
+        (exit.0 - PADDING, exit.1 + PADDING),
+        (exit.0 + PADDING, exit.1 + PADDING),
+        (left, top),
+        (right, top),
+        (right, bottom),
+        (left, bottom),
+    ])
+}
+
+/// Returns whether the point is inside the polygon, by ray casting
+pub(crate) fn contains(polygon: &[Point], point: Point) -> bool {
+    let mut is_inside = false;
+    let mut j = polygon.len().wrapping_sub(1);
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[j]);
+        if (a.1 > point.1) != (b.1 > point.1)
+            && point.0 < (b.0 - a.0) * (point.1 - a.1) / (b.1 - a.1) + a.0
+        {
+            is_inside = !is_inside;
+        }
+        j = i;
+    }
+    is_inside
+}
@@ -0,0 +1,431 @@
+//! A hover card previewing the content of a link, with interactive content allowed
+
+mod grace;
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::{events::EventListener, timers::callback::Timeout, utils::document};
+use seigi_utils::{
+    id, outside,
+    top_layer::{self, Popover},
+};
+use wasm_bindgen::JsCast;
+use web_sys::{Event, FocusEvent, HtmlElement, KeyboardEvent, Node, PointerEvent};
+
+pub type OpenChangeHook = Box<dyn Fn(bool)>;
+
+/// Hooks to [HoverCard]
+#[derive(Default)]
+pub struct HoverCardHooks {
+    /// Called with whether the card is open when it changes
+    pub open_change: Option<OpenChangeHook>,
+}
+
+/// Options of [HoverCard]
+pub struct HoverCardOptions {
+    /// The element the card previews, usually a link
+    pub trigger: HtmlElement,
+    pub card: HtmlElement,
+    /// Milliseconds the pointer or focus has to stay on the trigger before the card opens
+    pub open_delay: u32,
+    /// Milliseconds the pointer or focus has to be away before the card closes
+    pub close_delay: u32,
+    /// Whether the card is shown as a popover in the top layer where the Popover API is
+    /// supported, rendering above stacking contexts of the page and light-dismissed by the
+    /// browser
+    pub top_layer: bool,
+    pub hooks: HoverCardHooks,
+}
+
+impl HoverCardOptions {
+    pub fn builder() -> HoverCardOptionsBuilder {
+        HoverCardOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [HoverCardOptions]
+pub struct HoverCardOptionsBuilder {
+    trigger: Option<HtmlElement>,
+    card: Option<HtmlElement>,
+    open_delay: u32,
+    close_delay: u32,
+    top_layer: bool,
+    hooks: HoverCardHooks,
+}
+
+impl Default for HoverCardOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            trigger: None,
+            card: None,
+            open_delay: 700,
+            close_delay: 300,
+            top_layer: false,
+            hooks: HoverCardHooks::default(),
+        }
+    }
+}
+
+impl HoverCardOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn trigger(mut self, trigger: HtmlElement) -> Self {
+        self.trigger = Some(trigger);
+        self
+    }
+
+    pub fn card(mut self, card: HtmlElement) -> Self {
+        self.card = Some(card);
+        self
+    }
+
+    pub fn open_delay(mut self, open_delay: u32) -> Self {
+        self.open_delay = open_delay;
+        self
+    }
+
+    pub fn close_delay(mut self, close_delay: u32) -> Self {
+        self.close_delay = close_delay;
+        self
+    }
+
+    pub fn top_layer(mut self, top_layer: bool) -> Self {
+        self.top_layer = top_layer;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: HoverCardHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [HoverCardOptions]
+    ///
+    /// # Panics
+    /// This method panics if trigger or card field is not set
+    pub fn build(self) -> HoverCardOptions {
+        HoverCardOptions {
+            trigger: self
+                .trigger
+                .expect("trigger must be set to build HoverCardOptions"),
+            card: self
+                .card
+                .expect("card must be set to build HoverCardOptions"),
+            open_delay: self.open_delay,
+            close_delay: self.close_delay,
+            top_layer: self.top_layer,
+            hooks: self.hooks,
+        }
+    }
+}
+
+struct Inner {
+    options: Rc<HoverCardOptions>,
+    is_open: bool,
+    /// The pending open or close
+    timer: Option<Timeout>,
+    /// The polygon the pointer travels through toward the card, and the listener following it
+    grace: Option<(Vec<grace::Point>, EventListener)>,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    /// Returns whether the node is the trigger, the card or inside them
+    fn contains(&self, node: Option<&Node>) -> bool {
+        self.options.trigger.contains(node) || self.options.card.contains(node)
+    }
+}
+
+/// An instance of hover card
+///
+/// The card opens after the pointer rests on the trigger or the trigger is focused, and closes
+/// after both the pointer and focus left the trigger and the card. The pointer can travel from
+/// the trigger to the card without closing it, and the card may contain links and buttons, so
+/// focus moving into it keeps it open. Escape and pointer presses outside close it at once.
+///
+/// Unlike a tooltip, the card is supplementary content for sighted pointer users, so it isn't
+/// announced as a description of the trigger, and touch input doesn't open it. Positioning of
+/// the card is left to user CSS.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-state** is set in the trigger and the card to `open` or `closed`. The card is also
+/// hidden with `hidden` attribute while closed, or is a `popover` with
+/// [HoverCardOptions::top_layer] where supported
+#[derive(Clone)]
+pub struct HoverCard(Rc<Mutex<Inner>>);
+
+impl HoverCard {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.0.lock().unwrap().is_open
+    }
+
+    pub fn open(&self) {
+        self.set_open(true);
+    }
+
+    pub fn close(&self) {
+        self.set_open(false);
+    }
+
+    pub fn set_open(&self, is_open: bool) {
+        let options = {
+            let mut inner = self.0.lock().unwrap();
+            inner.timer = None;
+            inner.grace = None;
+            if inner.is_open == is_open {
+                return;
+            }
+            inner.is_open = is_open;
+
+            let options = inner.options.clone();
+            let state = if is_open { "open" } else { "closed" };
+            let _ = options.trigger.set_attribute("data-state", state);
+            let _ = options.card.set_attribute("data-state", state);
+            if is_open {
+                top_layer::show(&options.card);
+            } else {
+                top_layer::hide(&options.card);
+            }
+            options
+        };
+
+        if let Some(hook) = &options.hooks.open_change {
+            hook(is_open);
+        }
+    }
+
+    /// Opens or closes after the delay, replacing a pending one
+    fn schedule(&self, is_open: bool) {
+        let mut inner = self.0.lock().unwrap();
+        inner.grace = None;
+        if inner.is_open == is_open {
+            inner.timer = None;
+            return;
+        }
+
+        let delay = if is_open {
+            inner.options.open_delay
+        } else {
+            inner.options.close_delay
+        };
+        let weak = Rc::downgrade(&self.0);
+        inner.timer = Some(Timeout::new(delay, move || {
+            if let Some(card) = HoverCard::from_weak(&weak) {
+                card.set_open(is_open);
+            }
+        }));
+    }
+
+    fn cancel(&self) {
+        let mut inner = self.0.lock().unwrap();
+        inner.timer = None;
+        inner.grace = None;
+    }
+
+    fn handle_trigger_leave(&self, event: &PointerEvent) {
+        let exit = (event.client_x() as f64, event.client_y() as f64);
+        let mut inner = self.0.lock().unwrap();
+        if !inner.is_open {
+            inner.timer = None;
+            return;
+        }
+        // Entering the card directly needs no grace
+        let to = event
+            .related_target()
+            .and_then(|v| v.dyn_into::<Node>().ok());
+        if inner.options.card.contains(to.as_ref()) {
+            return;
+        }
+
+        let rect = inner.options.card.get_bounding_client_rect();
+        let polygon = grace::polygon(exit, (rect.left(), rect.top(), rect.right(), rect.bottom()));
+        let weak = Rc::downgrade(&self.0);
+        let listener = EventListener::new(&document(), "pointermove", move |event| {
+            let (Some(card), Some(event)) =
+                (HoverCard::from_weak(&weak), event.dyn_ref::<PointerEvent>())
+            else {
+                return;
+            };
+            let point = (event.client_x() as f64, event.client_y() as f64);
+            let is_inside = card
+                .0
+                .lock()
+                .unwrap()
+                .grace
+                .as_ref()
+                .is_some_and(|(polygon, _)| grace::contains(polygon, point));
+            if !is_inside {
+                card.schedule(false);
+            }
+        });
+        inner.timer = None;
+        inner.grace = Some((polygon, listener));
+    }
+
+    fn handle_focus_out(&self, event: &FocusEvent) {
+        let to = event
+            .related_target()
+            .and_then(|v| v.dyn_into::<Node>().ok());
+        let is_outside = !self.0.lock().unwrap().contains(to.as_ref())
+            && !to.as_ref().is_some_and(outside::is_allowed);
+        if is_outside {
+            self.schedule(false);
+        }
+    }
+
+    fn handle_key_down(&self, event: &KeyboardEvent) {
+        if event.key() != "Escape" {
+            return;
+        }
+        let (trigger, has_focus) = {
+            let inner = self.0.lock().unwrap();
+            if !inner.is_open {
+                return;
+            }
+            let focused = document().active_element().map(Node::from);
+            (
+                inner.options.trigger.clone(),
+                inner.options.card.contains(focused.as_ref()),
+            )
+        };
+        self.close();
+        // Focus would otherwise be left on the hidden card
+        if has_focus {
+            let _ = trigger.focus();
+            // Focus returning to the trigger doesn't open the card again
+            self.cancel();
+        }
+    }
+
+    fn handle_pointer_down(&self, event: &PointerEvent) {
+        let target = event.target().and_then(|v| v.dyn_into::<Node>().ok());
+        let is_outside = {
+            let inner = self.0.lock().unwrap();
+            inner.is_open
+                && !inner.contains(target.as_ref())
+                && !target.as_ref().is_some_and(outside::is_allowed)
+        };
+        if is_outside {
+            self.close();
+        }
+    }
+}
+
+pub fn create_hover_card(options: HoverCardOptions) -> HoverCard {
+    let trigger = options.trigger.clone();
+    let card = options.card.clone();
+    id::ensure(&card, "hovercard");
+    let _ = trigger.set_attribute("data-state", "closed");
+    let _ = card.set_attribute("data-state", "closed");
+    // Popovers are hidden until shown, as is the card where the API is unsupported
+    let is_popover = options.top_layer && top_layer::make_popover(&card, Popover::Auto);
+    if !options.top_layer {
+        card.set_hidden(true);
+    }
+
+    let hover_card = HoverCard(Rc::new(Mutex::new(Inner {
+        options: Rc::new(options),
+        is_open: false,
+        timer: None,
+        grace: None,
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&hover_card.0);
+    let listener = |target: &HtmlElement, event_type: &'static str, f: fn(&HoverCard, &Event)| {
+        let weak = weak.clone();
+        EventListener::new(target, event_type, move |event| {
+            if let Some(card) = HoverCard::from_weak(&weak) {
+                f(&card, event);
+            }
+        })
+    };
+    let document = document();
+    let mut listeners = vec![
+        listener(&trigger, "pointerenter", |card, event| {
+            // Touch has no hover, and a tap follows the link instead
+            if !event
+                .dyn_ref::<PointerEvent>()
+                .is_some_and(|v| v.pointer_type() == "touch")
+            {
+                card.schedule(true);
+            }
+        }),
+        listener(&trigger, "pointerleave", |card, event| {
+            if let Some(event) = event.dyn_ref::<PointerEvent>() {
+                card.handle_trigger_leave(event);
+            }
+        }),
+        listener(&trigger, "focusin", |card, _| card.schedule(true)),
+        listener(&trigger, "focusout", |card, event| {
+            if let Some(event) = event.dyn_ref::<FocusEvent>() {
+                card.handle_focus_out(event);
+            }
+        }),
+        listener(&card, "pointerenter", |card, _| card.cancel()),
+        listener(&card, "pointerleave", |card, event| {
+            let to = event
+                .dyn_ref::<PointerEvent>()
+                .and_then(|v| v.related_target())
+                .and_then(|v| v.dyn_into::<Node>().ok());
+            // Moving back onto the trigger is handled by its pointerenter
+            if !card.0.lock().unwrap().options.trigger.contains(to.as_ref()) {
+                card.schedule(false);
+            }
+        }),
+        listener(&card, "focusin", |card, _| card.cancel()),
+        listener(&card, "focusout", |card, event| {
+            if let Some(event) = event.dyn_ref::<FocusEvent>() {
+                card.handle_focus_out(event);
+            }
+        }),
+        EventListener::new(&document, "keydown", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(card), Some(event)) = (
+                    HoverCard::from_weak(&weak),
+                    event.dyn_ref::<KeyboardEvent>(),
+                ) else {
+                    return;
+                };
+                card.handle_key_down(event);
+            }
+        }),
+        EventListener::new(&document, "pointerdown", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(card), Some(event)) =
+                    (HoverCard::from_weak(&weak), event.dyn_ref::<PointerEvent>())
+                else {
+                    return;
+                };
+                card.handle_pointer_down(event);
+            }
+        }),
+    ];
+
+    if is_popover {
+        // Escape and presses outside light-dismiss the card before the listeners above see them
+        let weak = weak.clone();
+        listeners.push(top_layer::on_dismiss(&card, move || {
+            if let Some(card) = HoverCard::from_weak(&weak) {
+                card.close();
+            }
+        }));
+    }
+
+    hover_card.0.lock().unwrap()._listeners = listeners;
+    hover_card
+}
@@ -0,0 +1,246 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use seigi_hovercard::{HoverCard, HoverCardHooks, HoverCardOptions, HoverCardOptionsBuilder};
+use seigi_testkit::{
+    Fixture, assert_focused,
+    events::{self, Modifiers},
+    pointer, wait,
+};
+use seigi_utils::top_layer;
+use wasm_bindgen_test::*;
+use web_sys::{EventTarget, PointerEvent, PointerEventInit};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r##"
+<a id="trigger" href="#profile" style="position: fixed; left: 0; top: 0; width: 100px; height: 20px">Profile</a>
+<div id="card" style="position: fixed; left: 0; top: 100px; width: 200px; height: 100px">
+    <button id="follow">Follow</button>
+</div>
+<button id="outside" style="position: fixed; left: 400px; top: 400px">Outside</button>
+"##;
+
+/// Milliseconds of both delays, and how long tests wait for them
+const DELAY: u32 = 20;
+const WAIT: u32 = 60;
+
+fn options(fixture: &Fixture) -> HoverCardOptionsBuilder {
+    HoverCardOptions::builder()
+        .trigger(fixture.get("#trigger"))
+        .card(fixture.get("#card"))
+        .open_delay(DELAY)
+        .close_delay(DELAY)
+}
+
+fn hover_card(options: HoverCardOptionsBuilder) -> (HoverCard, Rc<RefCell<Vec<bool>>>) {
+    let changes = Rc::new(RefCell::new(vec![]));
+    let card = seigi_hovercard::create_hover_card(
+        options
+            .hooks(HoverCardHooks {
+                open_change: Some(Box::new({
+                    let changes = changes.clone();
+                    move |is_open| changes.borrow_mut().push(is_open)
+                })),
+            })
+            .build(),
+    );
+    (card, changes)
+}
+
+/// Dispatches `pointerleave` at the position, toward the related element
+fn leave(target: &EventTarget, x: f64, y: f64, to: Option<&EventTarget>) {
+    let init = PointerEventInit::new();
+    init.set_client_x(x as i32);
+    init.set_client_y(y as i32);
+    init.set_pointer_id(1);
+    init.set_pointer_type("mouse");
+    init.set_related_target(to);
+    let event = PointerEvent::new_with_event_init_dict("pointerleave", &init).unwrap();
+    events::dispatch(target, &event);
+}
+
+fn move_to(x: f64, y: f64) {
+    events::dispatch(
+        &gloo::utils::body(),
+        &events::pointer_event("pointermove", x, y),
+    );
+}
+
+#[wasm_bindgen_test]
+async fn resting_pointer_opens_after_delay() {
+    let fixture = Fixture::new(MARKUP);
+    let (card, changes) = hover_card(options(&fixture));
+    let element = fixture.get("#card");
+    assert!(element.hidden());
+    assert_eq!(
+        fixture
+            .get("#trigger")
+            .get_attribute("data-state")
+            .as_deref(),
+        Some("closed")
+    );
+
+    pointer::hover(&fixture.get("#trigger"));
+    assert!(!card.is_open());
+    wait::sleep(WAIT).await;
+    assert!(card.is_open());
+    assert!(!element.hidden());
+    assert_eq!(element.get_attribute("data-state").as_deref(), Some("open"));
+    assert_eq!(
+        fixture
+            .get("#trigger")
+            .get_attribute("data-state")
+            .as_deref(),
+        Some("open")
+    );
+    assert_eq!(*changes.borrow(), [true]);
+}
+
+#[wasm_bindgen_test]
+async fn leaving_before_delay_cancels_opening() {
+    let fixture = Fixture::new(MARKUP);
+    let (card, changes) = hover_card(options(&fixture));
+    let trigger = fixture.get("#trigger");
+
+    pointer::hover(&trigger);
+    pointer::leave(&trigger);
+    wait::sleep(WAIT).await;
+    assert!(!card.is_open());
+    assert!(changes.borrow().is_empty());
+}
+
+#[wasm_bindgen_test]
+async fn touch_never_opens() {
+    let fixture = Fixture::new(MARKUP);
+    let (card, _) = hover_card(options(&fixture));
+
+    let init = PointerEventInit::new();
+    init.set_pointer_type("touch");
+    let event = PointerEvent::new_with_event_init_dict("pointerenter", &init).unwrap();
+    events::dispatch(&fixture.get("#trigger"), &event);
+    wait::sleep(WAIT).await;
+    assert!(!card.is_open());
+}
+
+#[wasm_bindgen_test]
+async fn pointer_travels_to_card_through_grace_area() {
+    let fixture = Fixture::new(MARKUP);
+    let (card, _) = hover_card(options(&fixture));
+    card.open();
+
+    // Leaving the trigger from its bottom edge toward the card below
+    leave(&fixture.get("#trigger"), 50.0, 20.0, None);
+    move_to(50.0, 60.0);
+    wait::sleep(WAIT).await;
+    assert!(card.is_open());
+
+    // Arriving at the card ends the grace
+    pointer::hover(&fixture.get("#card"));
+    move_to(300.0, 30.0);
+    wait::sleep(WAIT).await;
+    assert!(card.is_open());
+
+    // Moving away from the card closes it
+    leave(&fixture.get("#card"), 150.0, 150.0, None);
+    wait::sleep(WAIT).await;
+    assert!(!card.is_open());
+}
+
+#[wasm_bindgen_test]
+async fn pointer_leaving_grace_area_closes() {
+    let fixture = Fixture::new(MARKUP);
+    let (card, _) = hover_card(options(&fixture));
+    card.open();
+
+    leave(&fixture.get("#trigger"), 50.0, 20.0, None);
+    move_to(300.0, 30.0);
+    assert!(card.is_open());
+    wait::sleep(WAIT).await;
+    assert!(!card.is_open());
+}
+
+#[wasm_bindgen_test]
+async fn moving_between_card_and_trigger_keeps_it_open() {
+    let fixture = Fixture::new(MARKUP);
+    let (card, _) = hover_card(options(&fixture));
+    let trigger = fixture.get("#trigger");
+    let element = fixture.get("#card");
+    card.open();
+
+    leave(&element, 50.0, 100.0, Some(&trigger));
+    leave(&trigger, 50.0, 20.0, Some(&element));
+    wait::sleep(WAIT).await;
+    assert!(card.is_open());
+
+    // Coming back cancels a pending close
+    leave(&element, 150.0, 150.0, None);
+    pointer::hover(&element);
+    wait::sleep(WAIT).await;
+    assert!(card.is_open());
+}
+
+#[wasm_bindgen_test]
+async fn focus_opens_and_may_move_into_card() {
+    let fixture = Fixture::new(MARKUP);
+    let (card, _) = hover_card(options(&fixture));
+
+    fixture.get("#trigger").focus().unwrap();
+    wait::sleep(WAIT).await;
+    assert!(card.is_open());
+
+    fixture.get("#follow").focus().unwrap();
+    wait::sleep(WAIT).await;
+    assert!(card.is_open());
+
+    fixture.get("#outside").focus().unwrap();
+    wait::sleep(WAIT).await;
+    assert!(!card.is_open());
+}
+
+#[wasm_bindgen_test]
+async fn escape_closes_and_returns_focus() {
+    let fixture = Fixture::new(MARKUP);
+    let (card, _) = hover_card(options(&fixture));
+    card.open();
+    let follow = fixture.get("#follow");
+    follow.focus().unwrap();
+
+    events::key_down(&follow, "Escape", Modifiers::default());
+    assert!(!card.is_open());
+    assert!(fixture.get("#card").hidden());
+    assert_focused(&fixture.get("#trigger"));
+    wait::sleep(WAIT).await;
+    assert!(!card.is_open());
+}
+
+#[wasm_bindgen_test]
+fn presses_outside_close_at_once() {
+    let fixture = Fixture::new(MARKUP);
+    let (card, changes) = hover_card(options(&fixture));
+    card.open();
+
+    pointer::down(&fixture.get("#follow"));
+    assert!(card.is_open());
+    pointer::down(&fixture.get("#outside"));
+    assert!(!card.is_open());
+    assert_eq!(*changes.borrow(), [true, false]);
+}
+
+#[wasm_bindgen_test]
+fn top_layer_card_is_a_popover() {
+    if !top_layer::supports_popover() {
+        return;
+    }
+    let fixture = Fixture::new(MARKUP);
+    let (card, _) = hover_card(options(&fixture).top_layer(true));
+    let element = fixture.get("#card");
+    assert!(!element.hidden());
+    assert!(!element.matches(":popover-open").unwrap());
+
+    card.open();
+    assert!(element.matches(":popover-open").unwrap());
+    card.close();
+    assert!(!element.matches(":popover-open").unwrap());
+}
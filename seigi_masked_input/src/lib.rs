@@ -0,0 +1,366 @@
+//! An input formatting its value through a mask as it is typed
+
+pub mod mask;
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::events::{EventListener, EventListenerOptions};
+use wasm_bindgen::JsCast;
+use web_sys::{Event, EventInit, HtmlInputElement, InputEvent};
+
+pub use mask::{Mask, Token};
+
+pub type ChangeHook = Box<dyn Fn(&str, bool)>;
+
+/// Hooks to [MaskedInput]
+#[derive(Default)]
+pub struct MaskedInputHooks {
+    /// Called with the raw value and whether every slot is filled when the value changes
+    pub change: Option<ChangeHook>,
+}
+
+/// Options of [MaskedInput]
+pub struct MaskedInputOptions {
+    pub input: HtmlInputElement,
+    pub mask: Mask,
+    /// Whether empty slots are shown as placeholders while the input is focused
+    pub show_placeholders: bool,
+    pub hooks: MaskedInputHooks,
+}
+
+impl MaskedInputOptions {
+    pub fn builder() -> MaskedInputOptionsBuilder {
+        MaskedInputOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [MaskedInputOptions]
+#[derive(Default)]
+pub struct MaskedInputOptionsBuilder {
+    input: Option<HtmlInputElement>,
+    mask: Option<Mask>,
+    show_placeholders: bool,
+    hooks: MaskedInputHooks,
+}
+
+impl MaskedInputOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn input(mut self, input: HtmlInputElement) -> Self {
+        self.input = Some(input);
+        self
+    }
+
+    pub fn mask(mut self, mask: Mask) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    pub fn show_placeholders(mut self, show_placeholders: bool) -> Self {
+        self.show_placeholders = show_placeholders;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: MaskedInputHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [MaskedInputOptions]
+    ///
+    /// # Panics
+    /// This method panics if input or mask field is not set
+    pub fn build(self) -> MaskedInputOptions {
+        MaskedInputOptions {
+            input: self
+                .input
+                .expect("input must be set to build MaskedInputOptions"),
+            mask: self
+                .mask
+                .expect("mask must be set to build MaskedInputOptions"),
+            show_placeholders: self.show_placeholders,
+            hooks: self.hooks,
+        }
+    }
+}
+
+struct Inner {
+    options: Rc<MaskedInputOptions>,
+    raw: Vec<char>,
+    is_focused: bool,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn formatted(&self) -> String {
+        let fill = self.options.show_placeholders && self.is_focused && !self.raw.is_empty();
+        self.options.mask.format(&self.raw, fill)
+    }
+
+    /// Writes the formatted value to the input, placing the caret before the slot at the index
+    fn render(&self, caret: Option<usize>) {
+        let input = &self.options.input;
+        let formatted = self.formatted();
+        if input.value() != formatted {
+            input.set_value(&formatted);
+        }
+
+        let is_complete = self.raw.len() == self.options.mask.slots();
+        if is_complete {
+            let _ = input.set_attribute("data-complete", "");
+        } else {
+            let _ = input.remove_attribute("data-complete");
+        }
+
+        if let Some(index) = caret {
+            let position = self
+                .options
+                .mask
+                .position(index)
+                .min(formatted.chars().count()) as u32;
+            let _ = input.set_selection_range(position, position);
+        }
+    }
+
+    /// Returns the range of the raw value the selection covers
+    fn selection(&self) -> (usize, usize) {
+        let input = &self.options.input;
+        let mask = &self.options.mask;
+        let start = input.selection_start().ok().flatten().unwrap_or(0) as usize;
+        let end = input.selection_end().ok().flatten().unwrap_or(0) as usize;
+        (
+            mask.raw_index(start, self.raw.len()),
+            mask.raw_index(end, self.raw.len()),
+        )
+    }
+}
+
+/// An instance of masked input
+///
+/// Edits are intercepted before they reach the input and applied to the raw value, so typed
+/// characters not accepted by the next slot are ignored, deleting skips literals and the caret
+/// stays where it belongs in the formatted value. Pasted text is filtered through slots, so
+/// `555-123-4567` and `5551234567` paste the same. Edits that can't be intercepted, such as
+/// autofill, are parsed back from the value.
+///
+/// After an intercepted edit, an `input` event is dispatched to the input, so listeners such as
+/// form validation see the formatted value.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-complete** is set in the input while every slot is filled
+///
+/// The template of the mask is set as **placeholder** of the input if it has none
+#[derive(Clone)]
+pub struct MaskedInput(Rc<Mutex<Inner>>);
+
+impl MaskedInput {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    /// Returns the characters typed into slots, without literals
+    pub fn raw(&self) -> String {
+        self.0.lock().unwrap().raw.iter().collect()
+    }
+
+    /// Returns the formatted value
+    pub fn value(&self) -> String {
+        self.0.lock().unwrap().formatted()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        let inner = self.0.lock().unwrap();
+        inner.raw.len() == inner.options.mask.slots()
+    }
+
+    /// Sets the value, which may be raw or formatted, without calling the change hook
+    pub fn set_value(&self, value: &str) {
+        let mut inner = self.0.lock().unwrap();
+        inner.raw = inner.options.mask.unformat(value);
+        inner.render(None);
+    }
+
+    /// Replaces the raw value, moving the caret before the slot at the index
+    fn change(&self, raw: Vec<char>, caret: usize) {
+        let (options, raw) = {
+            let mut inner = self.0.lock().unwrap();
+            let is_changed = inner.raw != raw;
+            inner.raw = raw;
+            inner.render(Some(caret));
+            if !is_changed {
+                return;
+            }
+            (inner.options.clone(), inner.raw.iter().collect::<String>())
+        };
+
+        let init = EventInit::new();
+        init.set_bubbles(true);
+        if let Ok(event) = Event::new_with_event_init_dict("input", &init) {
+            let _ = options.input.dispatch_event(&event);
+        }
+        if let Some(hook) = &options.hooks.change {
+            hook(&raw, raw.chars().count() == options.mask.slots());
+        }
+    }
+
+    fn handle_before_input(&self, event: &InputEvent) {
+        let (raw, caret) = {
+            let inner = self.0.lock().unwrap();
+            let mask = &inner.options.mask;
+            let (start, end) = inner.selection();
+            let mut raw = inner.raw.clone();
+            let input_type = event.input_type();
+            match input_type.as_str() {
+                "insertText" | "insertFromPaste" | "insertFromDrop" | "insertReplacementText" => {
+                    let data = event
+                        .data()
+                        .or_else(|| event.data_transfer().and_then(|v| v.get_data("text").ok()))
+                        .unwrap_or_default();
+                    let accepted = mask.accept(start, &data);
+                    if accepted.is_empty() && start == end {
+                        event.prevent_default();
+                        return;
+                    }
+                    let tail = raw.split_off(end);
+                    raw.truncate(start);
+                    raw.extend(&accepted);
+                    raw.extend(tail);
+                    raw.truncate(mask.slots());
+                    (raw, start + accepted.len())
+                }
+                "deleteContentBackward" if start == end => {
+                    if start == 0 {
+                        event.prevent_default();
+                        return;
+                    }
+                    raw.remove(start - 1);
+                    (raw, start - 1)
+                }
+                "deleteContentForward" if start == end => {
+                    if start >= raw.len() {
+                        event.prevent_default();
+                        return;
+                    }
+                    raw.remove(start);
+                    (raw, start)
+                }
+                "deleteContentBackward" | "deleteContentForward" | "deleteByCut" => {
+                    raw.drain(start..end);
+                    (raw, start)
+                }
+                // Other edits, such as deleting words, are parsed back from the value
+                _ => return,
+            }
+        };
+        event.prevent_default();
+        self.change(raw, caret);
+    }
+
+    /// Parses edits that weren't intercepted back from the value
+    fn handle_input(&self, event: &Event) {
+        if !event.is_trusted() {
+            return;
+        }
+        let (raw, caret) = {
+            let inner = self.0.lock().unwrap();
+            let mask = &inner.options.mask;
+            let value = inner.options.input.value();
+            let position = inner
+                .options
+                .input
+                .selection_start()
+                .ok()
+                .flatten()
+                .unwrap_or(0) as usize;
+            let before = value.chars().take(position).collect::<String>();
+            let caret = mask.unformat(&before).len();
+            let mut raw = mask.unformat(&value);
+            raw.truncate(mask.slots());
+            (raw, caret)
+        };
+        self.change(raw, caret);
+    }
+
+    fn set_focused(&self, is_focused: bool) {
+        let mut inner = self.0.lock().unwrap();
+        inner.is_focused = is_focused;
+        if inner.options.show_placeholders {
+            let (start, _) = inner.selection();
+            inner.render(is_focused.then_some(start));
+        }
+    }
+}
+
+pub fn create_masked_input(options: MaskedInputOptions) -> MaskedInput {
+    let input = options.input.clone();
+    if !input.has_attribute("placeholder") {
+        input.set_placeholder(&options.mask.template());
+    }
+    let raw = options.mask.unformat(&input.value());
+
+    let masked_input = MaskedInput(Rc::new(Mutex::new(Inner {
+        options: Rc::new(options),
+        raw,
+        is_focused: false,
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&masked_input.0);
+    let listeners = vec![
+        EventListener::new_with_options(
+            &input,
+            "beforeinput",
+            EventListenerOptions::enable_prevent_default(),
+            {
+                let weak = weak.clone();
+                move |event| {
+                    let (Some(masked_input), Some(event)) =
+                        (MaskedInput::from_weak(&weak), event.dyn_ref::<InputEvent>())
+                    else {
+                        return;
+                    };
+                    masked_input.handle_before_input(event);
+                }
+            },
+        ),
+        EventListener::new(&input, "input", {
+            let weak = weak.clone();
+            move |event| {
+                if let Some(masked_input) = MaskedInput::from_weak(&weak) {
+                    masked_input.handle_input(event);
+                }
+            }
+        }),
+        EventListener::new(&input, "focus", {
+            let weak = weak.clone();
+            move |_| {
+                if let Some(masked_input) = MaskedInput::from_weak(&weak) {
+                    masked_input.set_focused(true);
+                }
+            }
+        }),
+        EventListener::new(&input, "blur", {
+            let weak = weak.clone();
+            move |_| {
+                if let Some(masked_input) = MaskedInput::from_weak(&weak) {
+                    masked_input.set_focused(false);
+                }
+            }
+        }),
+    ];
+
+    {
+        let mut inner = masked_input.0.lock().unwrap();
+        inner._listeners = listeners;
+        inner.render(None);
+    }
+    masked_input
+}
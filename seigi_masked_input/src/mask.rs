@@ -0,0 +1,204 @@
+//! Parsing masks and formatting values through them
+
+/// A token of a mask pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    /// `9`, an ASCII digit
+    Digit,
+    /// `a`, a letter
+    Letter,
+    /// `*`, a letter or a digit
+    Alphanumeric,
+    /// Any other character, or one escaped with `\`, inserted as is
+    Literal(char),
+}
+
+impl Token {
+    pub fn accepts(&self, c: char) -> bool {
+        match self {
+            Self::Digit => c.is_ascii_digit(),
+            Self::Letter => c.is_alphabetic(),
+            Self::Alphanumeric => c.is_alphanumeric(),
+            Self::Literal(_) => false,
+        }
+    }
+
+    pub fn is_slot(&self) -> bool {
+        !matches!(self, Self::Literal(_))
+    }
+}
+
+/// A mask, such as `(999) 999-9999`
+///
+/// Values are kept raw, as the characters typed into slots, and formatted by filling slots in
+/// order and inserting literals between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mask {
+    tokens: Vec<Token>,
+    placeholder: char,
+}
+
+impl Mask {
+    /// Parses the pattern, where `9` is a digit, `a` a letter, `*` a letter or a digit and `\`
+    /// escapes the next character as a literal
+    pub fn new(pattern: &str) -> Self {
+        let mut tokens = vec![];
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            tokens.push(match c {
+                '9' => Token::Digit,
+                'a' => Token::Letter,
+                '*' => Token::Alphanumeric,
+                '\\' => match chars.next() {
+                    Some(c) => Token::Literal(c),
+                    None => break,
+                },
+                c => Token::Literal(c),
+            });
+        }
+        Self {
+            tokens,
+            placeholder: '_',
+        }
+    }
+
+    /// Sets the character shown in empty slots, `_` by default
+    pub fn placeholder(mut self, placeholder: char) -> Self {
+        self.placeholder = placeholder;
+        self
+    }
+
+    /// A North American phone number, `(999) 999-9999`
+    pub fn phone() -> Self {
+        Self::new("(999) 999-9999")
+    }
+
+    /// A payment card number in groups of four, `9999 9999 9999 9999`
+    pub fn card() -> Self {
+        Self::new("9999 9999 9999 9999")
+    }
+
+    /// A date as `99/99/9999`, whose order of fields is up to the user
+    pub fn date() -> Self {
+        Self::new("99/99/9999")
+    }
+
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+
+    /// Returns the count of slots, which is the maximum length of raw values
+    pub fn slots(&self) -> usize {
+        self.tokens.iter().filter(|v| v.is_slot()).count()
+    }
+
+    /// Returns the pattern with placeholders in slots, as a hint of the format
+    pub fn template(&self) -> String {
+        self.tokens
+            .iter()
+            .map(|v| match v {
+                Token::Literal(c) => *c,
+                _ => self.placeholder,
+            })
+            .collect()
+    }
+
+    /// Fills slots with accepted characters from the start, skipping the rest
+    ///
+    /// # Returns
+    /// The raw value
+    pub fn accept(&self, from: usize, text: &str) -> Vec<char> {
+        let slots = self.tokens.iter().filter(|v| v.is_slot()).skip(from);
+        let mut accepted = vec![];
+        let mut chars = text.chars();
+        for slot in slots {
+            match chars.by_ref().find(|c| slot.accepts(*c)) {
+                Some(c) => accepted.push(c),
+                None => break,
+            }
+        }
+        accepted
+    }
+
+    /// Extracts the raw value from a formatted value, skipping literals and placeholders at
+    /// their positions, so values with or without formatting both parse
+    pub fn unformat(&self, value: &str) -> Vec<char> {
+        let mut raw = vec![];
+        let mut tokens = self.tokens.iter().peekable();
+        'chars: for c in value.chars() {
+            while let Some(Token::Literal(literal)) = tokens.peek() {
+                tokens.next();
+                if *literal == c {
+                    continue 'chars;
+                }
+            }
+            let Some(token) = tokens.peek() else {
+                break;
+            };
+            if token.accepts(c) {
+                raw.push(c);
+                tokens.next();
+            } else if c == self.placeholder {
+                tokens.next();
+            }
+        }
+        raw
+    }
+
+    /// Formats the raw value
+    ///
+    /// Literals after the last filled slot are included up to the next slot, so the caret
+    /// lands where the next character goes. If `fill` is true, empty slots are shown as
+    /// placeholders instead of ending the value.
+    pub fn format(&self, raw: &[char], fill: bool) -> String {
+        let mut formatted = String::new();
+        let mut raw = raw.iter();
+        let mut pending = String::new();
+        for token in &self.tokens {
+            match token {
+                Token::Literal(c) => pending.push(*c),
+                _ => match raw.next() {
+                    Some(c) => {
+                        formatted.push_str(&pending);
+                        pending.clear();
+                        formatted.push(*c);
+                    }
+                    None if fill => {
+                        formatted.push_str(&pending);
+                        pending.clear();
+                        formatted.push(self.placeholder);
+                    }
+                    None => {
+                        if !formatted.is_empty() {
+                            formatted.push_str(&pending);
+                        }
+                        return formatted;
+                    }
+                },
+            }
+        }
+        formatted.push_str(&pending);
+        formatted
+    }
+
+    /// Returns the count of slots before the position in formatted values
+    pub fn raw_index(&self, position: usize, len: usize) -> usize {
+        self.tokens
+            .iter()
+            .take(position)
+            .filter(|v| v.is_slot())
+            .count()
+            .min(len)
+    }
+
+    /// Returns the position in formatted values of the slot at the index, skipping literals
+    /// before it
+    pub fn position(&self, index: usize) -> usize {
+        self.tokens
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| v.is_slot())
+            .nth(index)
+            .map_or(self.tokens.len(), |(v, _)| v)
+    }
+}
@@ -0,0 +1,109 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use seigi_masked_input::{Mask, Token};
+
+fn chars(value: &str) -> Vec<char> {
+    value.chars().collect()
+}
+
+#[test]
+fn pattern_is_parsed() {
+    let mask = Mask::new(r"\9a*-");
+    assert_eq!(
+        mask.tokens(),
+        [
+            Token::Literal('9'),
+            Token::Letter,
+            Token::Alphanumeric,
+            Token::Literal('-'),
+        ]
+    );
+    assert_eq!(mask.slots(), 2);
+    // A trailing escape has nothing to escape
+    assert_eq!(Mask::new(r"99\").tokens(), [Token::Digit, Token::Digit]);
+    assert_eq!(Mask::phone().template(), "(___) ___-____");
+    assert_eq!(Mask::date().placeholder('#').template(), "##/##/####");
+}
+
+#[test]
+fn partial_input_is_formatted() {
+    let mask = Mask::phone();
+    assert_eq!(mask.format(&[], false), "");
+    assert_eq!(mask.format(&chars("55"), false), "(55");
+    // Literals up to the next slot are included, so the caret lands after them
+    assert_eq!(mask.format(&chars("555"), false), "(555) ");
+    assert_eq!(mask.format(&chars("555123"), false), "(555) 123-");
+    assert_eq!(mask.format(&chars("5551234567"), false), "(555) 123-4567");
+    assert_eq!(mask.format(&chars("55"), true), "(55_) ___-____");
+
+    assert_eq!(mask.unformat("(55"), chars("55"));
+    assert_eq!(mask.unformat("(55_) ___-____"), chars("55"));
+    assert_eq!(mask.unformat("555123"), chars("555123"));
+    assert_eq!(mask.unformat("(555) 123-4567"), chars("5551234567"));
+}
+
+#[test]
+fn literals_at_start_and_end() {
+    let mask = Mask::new("#999!");
+    assert_eq!(mask.format(&[], false), "");
+    assert_eq!(mask.format(&chars("1"), false), "#1");
+    assert_eq!(mask.format(&chars("123"), false), "#123!");
+    assert_eq!(mask.format(&[], true), "#___!");
+    assert_eq!(mask.unformat("#123!"), chars("123"));
+    assert_eq!(mask.unformat("12"), chars("12"));
+
+    // The caret skips the leading literal and stays after the trailing one
+    assert_eq!(mask.position(0), 1);
+    assert_eq!(mask.position(2), 3);
+    assert_eq!(mask.position(3), 5);
+    assert_eq!(mask.raw_index(0, 3), 0);
+    assert_eq!(mask.raw_index(1, 3), 0);
+    assert_eq!(mask.raw_index(4, 3), 3);
+    assert_eq!(mask.raw_index(5, 3), 3);
+    // Positions are clamped to the raw value
+    assert_eq!(mask.raw_index(4, 1), 1);
+}
+
+#[test]
+fn deleting_across_literal() {
+    let mask = Mask::phone();
+    let raw = chars("555123");
+    assert_eq!(mask.format(&raw, false), "(555) 123-");
+
+    // Backspace before "1" in "(555) |123" deletes the digit before the literals
+    let index = mask.raw_index(6, raw.len());
+    let mut deleted = raw.clone();
+    deleted.remove(index - 1);
+    assert_eq!(mask.format(&deleted, false), "(551) 23");
+    assert_eq!(mask.position(index - 1), 3);
+
+    // Delete after "5" in "(555|) 123" deletes the digit after the literals
+    let index = mask.raw_index(4, raw.len());
+    let mut deleted = raw.clone();
+    deleted.remove(index);
+    assert_eq!(mask.format(&deleted, false), "(555) 23");
+    assert_eq!(mask.position(index), 6);
+
+    // A selection of "55) 1" keeps the rest
+    let (start, end) = (mask.raw_index(2, raw.len()), mask.raw_index(7, raw.len()));
+    let mut deleted = raw.clone();
+    deleted.drain(start..end);
+    assert_eq!(mask.format(&deleted, false), "(523) ");
+    assert_eq!(mask.position(start), 2);
+}
+
+#[test]
+fn pasting_skips_characters_not_fitting() {
+    let mask = Mask::phone();
+    assert_eq!(mask.accept(0, "555-123-4567"), chars("5551234567"));
+    assert_eq!(mask.accept(0, "+1 (555) 123"), chars("1555123"));
+    assert_eq!(mask.accept(0, "abc"), []);
+    // Only the remaining slots are filled
+    assert_eq!(mask.accept(8, "123456"), chars("12"));
+    assert_eq!(mask.accept(10, "1"), []);
+
+    let mask = Mask::new("aa-99");
+    assert_eq!(mask.accept(0, "1a2b3c"), chars("ab3"));
+    assert_eq!(mask.accept(1, "x9"), chars("x9"));
+    assert_eq!(mask.unformat("ab-cd"), chars("ab"));
+}
@@ -0,0 +1,270 @@
+//! Meters and progress bars exposing their value to assistive technologies and CSS
+
+use std::rc::Rc;
+use std::sync::Mutex;
+
+use web_sys::{HtmlElement, HtmlInputElement};
+
+/// What the element measures
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// A scalar in a known range, such as disk usage
+    #[default]
+    Meter,
+    /// Progress of a task, which may be indeterminate
+    Progress,
+}
+
+pub type ValueTextHook = Box<dyn Fn(f64) -> String>;
+
+/// Options of [Meter]
+pub struct MeterOptions {
+    pub element: HtmlElement,
+    pub kind: Kind,
+    pub min: f64,
+    pub max: f64,
+    /// The initial value, where `None` is indeterminate
+    pub value: Option<f64>,
+    /// Values below this are low, for **data-level**
+    pub low: Option<f64>,
+    /// Values above this are high, for **data-level**
+    pub high: Option<f64>,
+    /// Formats the value for **aria-valuetext**, such as `"3 of 8 files"`
+    pub value_text: Option<ValueTextHook>,
+    /// An input the value is mirrored to, so the meter takes part in form submission
+    pub input: Option<HtmlInputElement>,
+}
+
+impl MeterOptions {
+    pub fn builder() -> MeterOptionsBuilder {
+        MeterOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [MeterOptions]
+pub struct MeterOptionsBuilder {
+    element: Option<HtmlElement>,
+    kind: Kind,
+    min: f64,
+    max: f64,
+    value: Option<f64>,
+    low: Option<f64>,
+    high: Option<f64>,
+    value_text: Option<ValueTextHook>,
+    input: Option<HtmlInputElement>,
+}
+
+impl Default for MeterOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            element: None,
+            kind: Kind::default(),
+            min: 0.0,
+            max: 100.0,
+            value: None,
+            low: None,
+            high: None,
+            value_text: None,
+            input: None,
+        }
+    }
+}
+
+impl MeterOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn element(mut self, element: HtmlElement) -> Self {
+        self.element = Some(element);
+        self
+    }
+
+    pub fn kind(mut self, kind: Kind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn min(mut self, min: f64) -> Self {
+        self.min = min;
+        self
+    }
+
+    pub fn max(mut self, max: f64) -> Self {
+        self.max = max;
+        self
+    }
+
+    pub fn value(mut self, value: f64) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    pub fn low(mut self, low: f64) -> Self {
+        self.low = Some(low);
+        self
+    }
+
+    pub fn high(mut self, high: f64) -> Self {
+        self.high = Some(high);
+        self
+    }
+
+    pub fn value_text(mut self, value_text: ValueTextHook) -> Self {
+        self.value_text = Some(value_text);
+        self
+    }
+
+    pub fn input(mut self, input: HtmlInputElement) -> Self {
+        self.input = Some(input);
+        self
+    }
+
+    /// Builds into [MeterOptions]
+    ///
+    /// # Panics
+    /// This method panics if element field is not set
+    pub fn build(self) -> MeterOptions {
+        MeterOptions {
+            element: self
+                .element
+                .expect("element must be set to build MeterOptions"),
+            kind: self.kind,
+            min: self.min,
+            max: self.max,
+            value: self.value,
+            low: self.low,
+            high: self.high,
+            value_text: self.value_text,
+            input: self.input,
+        }
+    }
+}
+
+struct Inner {
+    options: MeterOptions,
+    value: Option<f64>,
+}
+
+impl Inner {
+    fn render(&self) {
+        let options = &self.options;
+        let element = &options.element;
+        let _ = element.set_attribute("aria-valuemin", &options.min.to_string());
+        let _ = element.set_attribute("aria-valuemax", &options.max.to_string());
+
+        let Some(value) = self.value else {
+            // Indeterminate progress has no value to announce
+            let _ = element.remove_attribute("aria-valuenow");
+            let _ = element.remove_attribute("aria-valuetext");
+            let _ = element.remove_attribute("data-level");
+            let _ = element.set_attribute("data-state", "indeterminate");
+            let _ = element.style().remove_property("--seigi-meter-percentage");
+            if let Some(input) = &options.input {
+                input.set_value("");
+            }
+            return;
+        };
+
+        let _ = element.set_attribute("aria-valuenow", &value.to_string());
+        match &options.value_text {
+            Some(hook) => {
+                let _ = element.set_attribute("aria-valuetext", &hook(value));
+            }
+            None => {
+                let _ = element.remove_attribute("aria-valuetext");
+            }
+        }
+
+        let range = options.max - options.min;
+        let percentage = if range > 0.0 {
+            (value - options.min) / range * 100.0
+        } else {
+            0.0
+        };
+        let _ = element
+            .style()
+            .set_property("--seigi-meter-percentage", &format!("{percentage}%"));
+
+        let state = match options.kind {
+            Kind::Progress if value >= options.max => "complete",
+            Kind::Progress => "loading",
+            Kind::Meter => "determinate",
+        };
+        let _ = element.set_attribute("data-state", state);
+
+        let level = if options.low.is_some_and(|v| value < v) {
+            "low"
+        } else if options.high.is_some_and(|v| value > v) {
+            "high"
+        } else {
+            "medium"
+        };
+        let _ = element.set_attribute("data-level", level);
+
+        if let Some(input) = &options.input {
+            input.set_value(&value.to_string());
+        }
+    }
+}
+
+/// An instance of meter or progress bar
+///
+/// The value is exposed to assistive technologies through `meter` or `progressbar` role, and to
+/// CSS through the percentage variable, so the fill is drawn by user CSS.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-state** is set in the element to `determinate` for meters, and `loading`,
+/// `complete` or `indeterminate` for progress bars
+///
+/// **data-level** is set in the element to `low`, `medium` or `high` by the thresholds
+///
+/// # CSS Variables
+/// **--seigi-meter-percentage** is set in the element to the position of the value in the
+/// range, and removed while indeterminate
+#[derive(Clone)]
+pub struct Meter(Rc<Mutex<Inner>>);
+
+impl Meter {
+    pub fn value(&self) -> Option<f64> {
+        self.0.lock().unwrap().value
+    }
+
+    /// Sets the value clamped in the range, where `None` is indeterminate
+    pub fn set_value(&self, value: Option<f64>) {
+        let mut inner = self.0.lock().unwrap();
+        let (min, max) = (inner.options.min, inner.options.max);
+        inner.value = value.map(|v| v.clamp(min, max.max(min)));
+        inner.render();
+    }
+
+    /// Sets the range, clamping the value in it
+    pub fn set_range(&self, min: f64, max: f64) {
+        let value = {
+            let mut inner = self.0.lock().unwrap();
+            inner.options.min = min;
+            inner.options.max = max;
+            inner.value
+        };
+        self.set_value(value);
+    }
+}
+
+pub fn create_meter(options: MeterOptions) -> Meter {
+    let role = match options.kind {
+        Kind::Meter => "meter",
+        Kind::Progress => "progressbar",
+    };
+    let _ = options.element.set_attribute("role", role);
+
+    let value = options.value;
+    let meter = Meter(Rc::new(Mutex::new(Inner {
+        options,
+        value: None,
+    })));
+    meter.set_value(value);
+    meter
+}
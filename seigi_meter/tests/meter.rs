@@ -0,0 +1,137 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_meter::{Kind, MeterOptions};
+use seigi_testkit::Fixture;
+use wasm_bindgen_test::*;
+use web_sys::{HtmlElement, HtmlInputElement, wasm_bindgen::JsCast};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"<div id="meter"></div><input id="input" type="hidden">"#;
+
+fn attribute(element: &HtmlElement, name: &str) -> Option<String> {
+    element.get_attribute(name)
+}
+
+fn percentage(element: &HtmlElement) -> String {
+    element
+        .style()
+        .get_property_value("--seigi-meter-percentage")
+        .unwrap()
+}
+
+#[wasm_bindgen_test]
+fn meter_exposes_value() {
+    let fixture = Fixture::new(MARKUP);
+    let element = fixture.get("#meter");
+    let input = fixture.get("#input").unchecked_into::<HtmlInputElement>();
+    seigi_meter::create_meter(
+        MeterOptions::builder()
+            .element(element.clone())
+            .min(10.0)
+            .max(30.0)
+            .value(15.0)
+            .value_text(Box::new(|v| format!("{v} GB used")))
+            .input(input.clone())
+            .build(),
+    );
+
+    assert_eq!(attribute(&element, "role").as_deref(), Some("meter"));
+    assert_eq!(attribute(&element, "aria-valuemin").as_deref(), Some("10"));
+    assert_eq!(attribute(&element, "aria-valuemax").as_deref(), Some("30"));
+    assert_eq!(attribute(&element, "aria-valuenow").as_deref(), Some("15"));
+    assert_eq!(
+        attribute(&element, "aria-valuetext").as_deref(),
+        Some("15 GB used")
+    );
+    assert_eq!(
+        attribute(&element, "data-state").as_deref(),
+        Some("determinate")
+    );
+    // The percentage is the position in the range, not of the value
+    assert_eq!(percentage(&element), "25%");
+    assert_eq!(input.value(), "15");
+}
+
+#[wasm_bindgen_test]
+fn levels_follow_thresholds() {
+    let fixture = Fixture::new(MARKUP);
+    let element = fixture.get("#meter");
+    let meter = seigi_meter::create_meter(
+        MeterOptions::builder()
+            .element(element.clone())
+            .value(10.0)
+            .low(20.0)
+            .high(80.0)
+            .build(),
+    );
+
+    assert_eq!(attribute(&element, "data-level").as_deref(), Some("low"));
+    meter.set_value(Some(20.0));
+    assert_eq!(attribute(&element, "data-level").as_deref(), Some("medium"));
+    meter.set_value(Some(90.0));
+    assert_eq!(attribute(&element, "data-level").as_deref(), Some("high"));
+}
+
+#[wasm_bindgen_test]
+fn progress_moves_through_states() {
+    let fixture = Fixture::new(MARKUP);
+    let element = fixture.get("#meter");
+    let input = fixture.get("#input").unchecked_into::<HtmlInputElement>();
+    let progress = seigi_meter::create_meter(
+        MeterOptions::builder()
+            .element(element.clone())
+            .kind(Kind::Progress)
+            .input(input.clone())
+            .build(),
+    );
+
+    assert_eq!(attribute(&element, "role").as_deref(), Some("progressbar"));
+    assert_eq!(progress.value(), None);
+    assert_eq!(
+        attribute(&element, "data-state").as_deref(),
+        Some("indeterminate")
+    );
+    assert_eq!(attribute(&element, "aria-valuenow"), None);
+    assert_eq!(percentage(&element), "");
+    assert_eq!(input.value(), "");
+
+    progress.set_value(Some(40.0));
+    assert_eq!(
+        attribute(&element, "data-state").as_deref(),
+        Some("loading")
+    );
+    assert_eq!(percentage(&element), "40%");
+
+    // Values are clamped in the range
+    progress.set_value(Some(150.0));
+    assert_eq!(progress.value(), Some(100.0));
+    assert_eq!(
+        attribute(&element, "data-state").as_deref(),
+        Some("complete")
+    );
+
+    progress.set_value(None);
+    assert_eq!(
+        attribute(&element, "data-state").as_deref(),
+        Some("indeterminate")
+    );
+    assert_eq!(attribute(&element, "data-level"), None);
+}
+
+#[wasm_bindgen_test]
+fn set_range_clamps_value() {
+    let fixture = Fixture::new(MARKUP);
+    let element = fixture.get("#meter");
+    let meter = seigi_meter::create_meter(
+        MeterOptions::builder()
+            .element(element.clone())
+            .value(80.0)
+            .build(),
+    );
+
+    meter.set_range(0.0, 50.0);
+    assert_eq!(meter.value(), Some(50.0));
+    assert_eq!(attribute(&element, "aria-valuemax").as_deref(), Some("50"));
+    assert_eq!(percentage(&element), "100%");
+}
@@ -0,0 +1,637 @@
+//! Site navigation following the WAI-ARIA disclosure navigation pattern
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::{events::EventListener, timers::callback::Timeout, utils::window};
+use seigi_utils::{
+    direction,
+    id::{self, Relation},
+    outside,
+};
+use wasm_bindgen::JsCast;
+use web_sys::{
+    Element, FocusEvent, HtmlAnchorElement, HtmlElement, KeyboardEvent, Node, PointerEvent,
+};
+
+pub type OpenChangeHook = Box<dyn Fn(Option<usize>)>;
+
+/// Hooks to [NavigationMenu]
+#[derive(Default)]
+pub struct NavigationMenuHooks {
+    /// Called with the index of the open item, or None when every item closes
+    pub open_change: Option<OpenChangeHook>,
+}
+
+/// Options of [NavigationMenu]
+pub struct NavigationMenuOptions {
+    /// The element containing the items, usually a `<nav>`
+    pub root: HtmlElement,
+    /// Milliseconds the pointer has to rest on a trigger before its content opens, while no
+    /// content is open
+    pub open_delay: u32,
+    /// Milliseconds the pointer has to be outside the menu before the open content closes
+    pub close_delay: u32,
+    /// The path links are matched against for active states. Defaults to the path of the
+    /// current location
+    pub current: Option<String>,
+    pub hooks: NavigationMenuHooks,
+}
+
+impl NavigationMenuOptions {
+    pub fn builder() -> NavigationMenuOptionsBuilder {
+        NavigationMenuOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [NavigationMenuOptions]
+pub struct NavigationMenuOptionsBuilder {
+    root: Option<HtmlElement>,
+    open_delay: u32,
+    close_delay: u32,
+    current: Option<String>,
+    hooks: NavigationMenuHooks,
+}
+
+impl Default for NavigationMenuOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            root: None,
+            open_delay: 200,
+            close_delay: 150,
+            current: None,
+            hooks: NavigationMenuHooks::default(),
+        }
+    }
+}
+
+impl NavigationMenuOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn root(mut self, root: HtmlElement) -> Self {
+        self.root = Some(root);
+        self
+    }
+
+    pub fn open_delay(mut self, open_delay: u32) -> Self {
+        self.open_delay = open_delay;
+        self
+    }
+
+    pub fn close_delay(mut self, close_delay: u32) -> Self {
+        self.close_delay = close_delay;
+        self
+    }
+
+    pub fn current(mut self, current: impl Into<String>) -> Self {
+        self.current = Some(current.into());
+        self
+    }
+
+    pub fn hooks(mut self, hooks: NavigationMenuHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [NavigationMenuOptions]
+    ///
+    /// # Panics
+    /// This method panics if root field is not set
+    pub fn build(self) -> NavigationMenuOptions {
+        NavigationMenuOptions {
+            root: self
+                .root
+                .expect("root must be set to build NavigationMenuOptions"),
+            open_delay: self.open_delay,
+            close_delay: self.close_delay,
+            current: self.current,
+            hooks: self.hooks,
+        }
+    }
+}
+
+fn query(parent: &Element, selector: &str) -> Vec<HtmlElement> {
+    let Ok(nodes) = parent.query_selector_all(selector) else {
+        return vec![];
+    };
+    (0..nodes.length())
+        .filter_map(|v| nodes.get(v))
+        .filter_map(|v| v.dyn_into::<HtmlElement>().ok())
+        .collect()
+}
+
+/// A top-level item with a trigger and its content
+struct Item {
+    trigger: HtmlElement,
+    content: Option<HtmlElement>,
+}
+
+struct Inner {
+    options: Rc<NavigationMenuOptions>,
+    open: Option<usize>,
+    current: Option<String>,
+    /// The pending open or close
+    timer: Option<Timeout>,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn items(&self) -> Vec<Item> {
+        query(&self.options.root, "[data-seigi-nav-trigger]")
+            .into_iter()
+            .map(|trigger| {
+                let content = trigger
+                    .closest("[data-seigi-nav-item]")
+                    .ok()
+                    .flatten()
+                    .and_then(|v| v.query_selector("[data-seigi-nav-content]").ok().flatten())
+                    .and_then(|v| v.dyn_into::<HtmlElement>().ok());
+                Item { trigger, content }
+            })
+            .collect()
+    }
+
+    /// Returns triggers and links of the top level, which arrow keys move between
+    fn top_level(&self) -> Vec<HtmlElement> {
+        query(
+            &self.options.root,
+            "[data-seigi-nav-trigger], [data-seigi-nav-link]",
+        )
+        .into_iter()
+        .filter(|v| {
+            v.closest("[data-seigi-nav-content]")
+                .ok()
+                .flatten()
+                .is_none()
+        })
+        .collect()
+    }
+
+    fn viewport(&self) -> Option<HtmlElement> {
+        self.options
+            .root
+            .query_selector("[data-seigi-nav-viewport]")
+            .ok()
+            .flatten()
+            .and_then(|v| v.dyn_into::<HtmlElement>().ok())
+    }
+
+    /// Applies attributes of the open item, and motion from the previously open one
+    fn reflect(&self, previous: Option<usize>) {
+        let items = self.items();
+        for (index, item) in items.iter().enumerate() {
+            let is_open = self.open == Some(index);
+            let state = if is_open { "open" } else { "closed" };
+            let _ = item
+                .trigger
+                .set_attribute("aria-expanded", if is_open { "true" } else { "false" });
+            let _ = item.trigger.set_attribute("data-state", state);
+            let Some(content) = &item.content else {
+                continue;
+            };
+            let _ = content.set_attribute("data-state", state);
+            content.set_hidden(!is_open);
+
+            // Contents slide in the direction the pointer moved between triggers
+            let motion = match (self.open, previous) {
+                (Some(open), Some(previous)) if open != previous && index == open => {
+                    Some(if open > previous {
+                        "from-end"
+                    } else {
+                        "from-start"
+                    })
+                }
+                (Some(open), Some(previous)) if open != previous && index == previous => {
+                    Some(if open > previous {
+                        "to-start"
+                    } else {
+                        "to-end"
+                    })
+                }
+                _ => None,
+            };
+            match motion {
+                Some(motion) => {
+                    let _ = content.set_attribute("data-motion", motion);
+                }
+                None => {
+                    let _ = content.remove_attribute("data-motion");
+                }
+            }
+        }
+
+        let root_style = self.options.root.style();
+        if let Some(item) = self.open.and_then(|v| items.get(v)) {
+            let root = self.options.root.get_bounding_client_rect();
+            let trigger = item.trigger.get_bounding_client_rect();
+            let _ = root_style.set_property(
+                "--seigi-nav-indicator-left",
+                &format!("{}px", trigger.left() - root.left()),
+            );
+            let _ = root_style.set_property(
+                "--seigi-nav-indicator-width",
+                &format!("{}px", trigger.width()),
+            );
+        }
+
+        if let Some(viewport) = self.viewport() {
+            let content = self
+                .open
+                .and_then(|v| items.get(v))
+                .and_then(|v| v.content.as_ref());
+            let _ = viewport.set_attribute(
+                "data-state",
+                if content.is_some() { "open" } else { "closed" },
+            );
+            if let Some(content) = content {
+                // The content may be scaled by its opening animation, so its layout size is used
+                let style = viewport.style();
+                let _ = style.set_property(
+                    "--seigi-nav-viewport-width",
+                    &format!("{}px", content.offset_width()),
+                );
+                let _ = style.set_property(
+                    "--seigi-nav-viewport-height",
+                    &format!("{}px", content.offset_height()),
+                );
+            }
+        }
+    }
+
+    /// Marks links matching the current path, and triggers of items containing them
+    fn reflect_current(&self) {
+        let current = self
+            .current
+            .clone()
+            .or_else(|| window().location().pathname().ok())
+            .unwrap_or_default();
+        let normalize = |v: &str| v.trim_end_matches('/').to_string();
+        let current = normalize(&current);
+
+        for item in self.items() {
+            let _ = item.trigger.remove_attribute("data-active");
+        }
+        for link in query(&self.options.root, "[data-seigi-nav-link]") {
+            let path = match link.dyn_ref::<HtmlAnchorElement>() {
+                Some(anchor) => anchor.pathname(),
+                None => link.get_attribute("href").unwrap_or_default(),
+            };
+            if normalize(&path) == current {
+                let _ = link.set_attribute("aria-current", "page");
+                let _ = link.set_attribute("data-active", "");
+                if let Some(item) = link
+                    .closest("[data-seigi-nav-item]")
+                    .ok()
+                    .flatten()
+                    .and_then(|v| v.query_selector("[data-seigi-nav-trigger]").ok().flatten())
+                {
+                    let _ = item.set_attribute("data-active", "");
+                }
+            } else {
+                let _ = link.remove_attribute("aria-current");
+                let _ = link.remove_attribute("data-active");
+            }
+        }
+    }
+}
+
+/// An instance of navigation menu
+///
+/// Items are elements with `data-seigi-nav-item`, containing a button with
+/// `data-seigi-nav-trigger` and its content with `data-seigi-nav-content`. Links have
+/// `data-seigi-nav-link`, at the top level or inside contents. An optional element with
+/// `data-seigi-nav-viewport` is sized after the open content, so a single panel can animate
+/// between contents.
+///
+/// Clicking a trigger toggles its content. With a mouse, resting on a trigger opens its content,
+/// and moving to another trigger while one is open switches at once. Leaving the menu closes it.
+///
+/// Left and right arrow keys move focus between the top-level triggers and links, with Home and
+/// End. Down arrow on a trigger opens its content and focuses its first link, where up and down
+/// arrow keys move between links. Escape closes the content and focuses its trigger, and focus
+/// leaving the menu closes it.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **aria-expanded** and **data-state** are set in triggers, and **data-state** in contents and
+/// the viewport, to `open` or `closed`. Closed contents are hidden with `hidden` attribute
+///
+/// **data-motion** is set in the contents switched between to `from-start`, `from-end`,
+/// `to-start` or `to-end`
+///
+/// **aria-current** and **data-active** are set in links matching the current path, and
+/// **data-active** in triggers of items containing them
+///
+/// # CSS Variables
+/// **--seigi-nav-viewport-width** and **--seigi-nav-viewport-height** are set in the viewport to
+/// the size of the open content
+///
+/// **--seigi-nav-indicator-left** and **--seigi-nav-indicator-width** are set in the root to the
+/// position of the open trigger
+#[derive(Clone)]
+pub struct NavigationMenu(Rc<Mutex<Inner>>);
+
+impl NavigationMenu {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    /// Returns the index of the open item
+    pub fn open_item(&self) -> Option<usize> {
+        self.0.lock().unwrap().open
+    }
+
+    /// Opens the item, or closes every item with None
+    pub fn set_open(&self, open: Option<usize>) {
+        let options = {
+            let mut inner = self.0.lock().unwrap();
+            inner.timer = None;
+            let open = open.filter(|v| *v < inner.items().len());
+            if inner.open == open {
+                return;
+            }
+            let previous = inner.open;
+            inner.open = open;
+            inner.reflect(previous);
+            inner.options.clone()
+        };
+
+        if let Some(hook) = &options.hooks.open_change {
+            hook(open);
+        }
+    }
+
+    pub fn close(&self) {
+        self.set_open(None);
+    }
+
+    /// Sets the path links are matched against, or None to follow the current location
+    pub fn set_current(&self, current: Option<String>) {
+        let mut inner = self.0.lock().unwrap();
+        inner.current = current;
+        inner.reflect_current();
+    }
+
+    /// Reapplies attributes to items and links added since
+    pub fn refresh(&self) {
+        let inner = self.0.lock().unwrap();
+        for item in inner.items() {
+            let Some(content) = &item.content else {
+                continue;
+            };
+            id::ensure(content, "nav");
+            id::relate(&item.trigger, Relation::Controls, content);
+        }
+        inner.reflect(None);
+        inner.reflect_current();
+    }
+
+    fn schedule(&self, open: Option<usize>, delay: u32) {
+        let weak = Rc::downgrade(&self.0);
+        self.0.lock().unwrap().timer = Some(Timeout::new(delay, move || {
+            if let Some(menu) = NavigationMenu::from_weak(&weak) {
+                menu.set_open(open);
+            }
+        }));
+    }
+
+    fn trigger_index(&self, target: &Element) -> Option<usize> {
+        let trigger = target.closest("[data-seigi-nav-trigger]").ok().flatten()?;
+        self.0
+            .lock()
+            .unwrap()
+            .items()
+            .iter()
+            .position(|v| *v.trigger == trigger)
+    }
+
+    fn handle_pointer_enter(&self, event: &PointerEvent) {
+        if event.pointer_type() != "mouse" {
+            return;
+        }
+        let Some(target) = event.target().and_then(|v| v.dyn_into::<Element>().ok()) else {
+            return;
+        };
+        let (open, delay) = {
+            let mut inner = self.0.lock().unwrap();
+            inner.timer = None;
+            (inner.open, inner.options.open_delay)
+        };
+        let Some(index) = self.trigger_index(&target) else {
+            return;
+        };
+        if open.is_some() {
+            self.set_open(Some(index));
+        } else {
+            self.schedule(Some(index), delay);
+        }
+    }
+
+    fn handle_key_down(&self, event: &KeyboardEvent) {
+        let Some(target) = event
+            .target()
+            .and_then(|v| v.dyn_into::<HtmlElement>().ok())
+        else {
+            return;
+        };
+        let key = event.key();
+
+        let (top_level, items, open) = {
+            let inner = self.0.lock().unwrap();
+            (inner.top_level(), inner.items(), inner.open)
+        };
+        let content_index = items.iter().position(|v| {
+            v.content
+                .as_ref()
+                .is_some_and(|v| v.contains(Some(target.as_ref())))
+        });
+
+        if let Some(index) = content_index {
+            let content = items[index].content.clone().unwrap();
+            let links = query(&content, "[data-seigi-nav-link]");
+            let position = links.iter().position(|v| *v == target);
+            let next = match (key.as_str(), position) {
+                ("Escape", _) => {
+                    event.prevent_default();
+                    self.close();
+                    let _ = items[index].trigger.focus();
+                    return;
+                }
+                ("ArrowDown", Some(position)) => Some((position + 1) % links.len()),
+                ("ArrowUp", Some(position)) => Some((position + links.len() - 1) % links.len()),
+                ("Home", Some(_)) => Some(0),
+                ("End", Some(_)) => Some(links.len() - 1),
+                _ => None,
+            };
+            if let Some(link) = next.and_then(|v| links.get(v)) {
+                event.prevent_default();
+                let _ = link.focus();
+            }
+            return;
+        }
+
+        let Some(position) = top_level.iter().position(|v| *v == target) else {
+            return;
+        };
+        let len = top_level.len();
+        let (previous_key, next_key) = direction::resolve(&target).arrow_keys();
+        let next = match key.as_str() {
+            v if v == next_key => Some((position + 1) % len),
+            v if v == previous_key => Some((position + len - 1) % len),
+            "Home" => Some(0),
+            "End" => Some(len - 1),
+            "ArrowDown" => {
+                let Some(index) = items.iter().position(|v| v.trigger == target) else {
+                    return;
+                };
+                event.prevent_default();
+                self.set_open(Some(index));
+                if let Some(link) = items[index]
+                    .content
+                    .as_ref()
+                    .and_then(|v| query(v, "[data-seigi-nav-link]").into_iter().next())
+                {
+                    let _ = link.focus();
+                }
+                return;
+            }
+            "Escape" if open.is_some() => {
+                event.prevent_default();
+                self.close();
+                return;
+            }
+            _ => None,
+        };
+        if let Some(element) = next.and_then(|v| top_level.get(v)) {
+            event.prevent_default();
+            let _ = element.focus();
+        }
+    }
+}
+
+pub fn create_navigation_menu(options: NavigationMenuOptions) -> NavigationMenu {
+    let root = options.root.clone();
+    if !root.has_attribute("aria-label") && !root.has_attribute("aria-labelledby") {
+        let _ = root.set_attribute("aria-label", "Main");
+    }
+
+    let menu = NavigationMenu(Rc::new(Mutex::new(Inner {
+        current: options.current.clone(),
+        options: Rc::new(options),
+        open: None,
+        timer: None,
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&menu.0);
+    let listeners = vec![
+        EventListener::new(&root, "click", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(menu), Some(target)) = (
+                    NavigationMenu::from_weak(&weak),
+                    event.target().and_then(|v| v.dyn_into::<Element>().ok()),
+                ) else {
+                    return;
+                };
+                if let Some(index) = menu.trigger_index(&target) {
+                    let open = menu.open_item();
+                    menu.set_open(if open == Some(index) {
+                        None
+                    } else {
+                        Some(index)
+                    });
+                } else if target
+                    .closest("[data-seigi-nav-link]")
+                    .ok()
+                    .flatten()
+                    .is_some()
+                {
+                    menu.close();
+                }
+            }
+        }),
+        EventListener::new(&root, "pointerover", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(menu), Some(event)) = (
+                    NavigationMenu::from_weak(&weak),
+                    event.dyn_ref::<PointerEvent>(),
+                ) else {
+                    return;
+                };
+                menu.handle_pointer_enter(event);
+            }
+        }),
+        EventListener::new(&root, "pointerleave", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(menu), Some(event)) = (
+                    NavigationMenu::from_weak(&weak),
+                    event.dyn_ref::<PointerEvent>(),
+                ) else {
+                    return;
+                };
+                if event.pointer_type() != "mouse" {
+                    return;
+                }
+                let delay = menu.0.lock().unwrap().options.close_delay;
+                menu.schedule(None, delay);
+            }
+        }),
+        EventListener::new(&root, "keydown", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(menu), Some(event)) = (
+                    NavigationMenu::from_weak(&weak),
+                    event.dyn_ref::<KeyboardEvent>(),
+                ) else {
+                    return;
+                };
+                menu.handle_key_down(event);
+            }
+        }),
+        EventListener::new(&root, "focusout", {
+            let weak = weak.clone();
+            let root = root.clone();
+            move |event| {
+                let (Some(menu), Some(event)) = (
+                    NavigationMenu::from_weak(&weak),
+                    event.dyn_ref::<FocusEvent>(),
+                ) else {
+                    return;
+                };
+                let to = event
+                    .related_target()
+                    .and_then(|v| v.dyn_into::<Node>().ok());
+                // Focus lost to nothing, like a click on the page, is handled by pointerleave, and
+                // focus moving to allowed zones, like actions of toasts, keeps the menu open
+                if to
+                    .as_ref()
+                    .is_some_and(|v| !root.contains(Some(v)) && !outside::is_allowed(v))
+                {
+                    menu.close();
+                }
+            }
+        }),
+        EventListener::new(&window(), "popstate", {
+            let weak = weak.clone();
+            move |_| {
+                let Some(menu) = NavigationMenu::from_weak(&weak) else {
+                    return;
+                };
+                menu.0.lock().unwrap().reflect_current();
+            }
+        }),
+    ];
+
+    menu.0.lock().unwrap()._listeners = listeners;
+    menu.refresh();
+    menu
+}
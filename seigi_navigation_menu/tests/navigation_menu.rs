@@ -0,0 +1,297 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use seigi_navigation_menu::{
+    NavigationMenu, NavigationMenuHooks, NavigationMenuOptions, NavigationMenuOptionsBuilder,
+};
+use seigi_testkit::{
+    Fixture, assert_focused,
+    events::{self, Modifiers},
+    pointer, wait,
+};
+use wasm_bindgen_test::*;
+use web_sys::{PointerEvent, PointerEventInit};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<nav id="nav">
+    <ul>
+        <li data-seigi-nav-item>
+            <button id="products" data-seigi-nav-trigger>Products</button>
+            <div id="products-content" data-seigi-nav-content>
+                <a id="editor" href="/products/editor" data-seigi-nav-link>Editor</a>
+                <a id="viewer" href="/products/viewer" data-seigi-nav-link>Viewer</a>
+            </div>
+        </li>
+        <li data-seigi-nav-item>
+            <button id="docs" data-seigi-nav-trigger>Docs</button>
+            <div id="docs-content" data-seigi-nav-content>
+                <a id="guide" href="/docs/guide" data-seigi-nav-link>Guide</a>
+            </div>
+        </li>
+        <li><a id="blog" href="/blog" data-seigi-nav-link>Blog</a></li>
+    </ul>
+    <div id="viewport" data-seigi-nav-viewport></div>
+</nav>
+<button id="outside">Outside</button>
+"#;
+
+/// Milliseconds of both delays, and how long tests wait for them
+const DELAY: u32 = 20;
+const WAIT: u32 = 60;
+
+fn options(fixture: &Fixture) -> NavigationMenuOptionsBuilder {
+    NavigationMenuOptions::builder()
+        .root(fixture.get("#nav"))
+        .open_delay(DELAY)
+        .close_delay(DELAY)
+        .current("/elsewhere")
+}
+
+fn menu(
+    options: NavigationMenuOptionsBuilder,
+) -> (NavigationMenu, Rc<RefCell<Vec<Option<usize>>>>) {
+    let changes = Rc::new(RefCell::new(vec![]));
+    let menu = seigi_navigation_menu::create_navigation_menu(
+        options
+            .hooks(NavigationMenuHooks {
+                open_change: Some(Box::new({
+                    let changes = changes.clone();
+                    move |open| changes.borrow_mut().push(open)
+                })),
+            })
+            .build(),
+    );
+    (menu, changes)
+}
+
+/// Presses the key on the focused element
+fn press(fixture: &Fixture, key: &str) -> bool {
+    let target = fixture
+        .active_element()
+        .expect("an element must be focused");
+    events::key_down(&target, key, Modifiers::default())
+}
+
+#[wasm_bindgen_test]
+fn items_start_closed_and_related() {
+    let fixture = Fixture::new(MARKUP);
+    let (menu, _) = menu(options(&fixture));
+
+    assert_eq!(
+        fixture.get("#nav").get_attribute("aria-label").as_deref(),
+        Some("Main")
+    );
+    assert_eq!(menu.open_item(), None);
+    let trigger = fixture.get("#products");
+    assert_eq!(
+        trigger.get_attribute("aria-expanded").as_deref(),
+        Some("false")
+    );
+    assert_eq!(
+        trigger.get_attribute("aria-controls").as_deref(),
+        Some("products-content")
+    );
+    assert!(fixture.get("#products-content").hidden());
+    assert_eq!(
+        fixture
+            .get("#viewport")
+            .get_attribute("data-state")
+            .as_deref(),
+        Some("closed")
+    );
+}
+
+#[wasm_bindgen_test]
+fn clicks_toggle_and_switch_contents() {
+    let fixture = Fixture::new(MARKUP);
+    let (menu, changes) = menu(options(&fixture));
+    let products = fixture.get("#products-content");
+    let docs = fixture.get("#docs-content");
+
+    events::click(&fixture.get("#products"));
+    assert_eq!(menu.open_item(), Some(0));
+    assert_eq!(
+        fixture
+            .get("#products")
+            .get_attribute("aria-expanded")
+            .as_deref(),
+        Some("true")
+    );
+    assert!(!products.hidden());
+    assert_eq!(
+        products.get_attribute("data-state").as_deref(),
+        Some("open")
+    );
+    let viewport = fixture.get("#viewport");
+    assert_eq!(
+        viewport.get_attribute("data-state").as_deref(),
+        Some("open")
+    );
+    assert_eq!(
+        viewport
+            .style()
+            .get_property_value("--seigi-nav-viewport-width")
+            .unwrap(),
+        format!("{}px", products.offset_width())
+    );
+
+    // Contents slide in the direction of the switch
+    events::click(&fixture.get("#docs"));
+    assert_eq!(menu.open_item(), Some(1));
+    assert!(products.hidden());
+    assert_eq!(
+        docs.get_attribute("data-motion").as_deref(),
+        Some("from-end")
+    );
+    assert_eq!(
+        products.get_attribute("data-motion").as_deref(),
+        Some("to-start")
+    );
+    events::click(&fixture.get("#products"));
+    assert_eq!(
+        products.get_attribute("data-motion").as_deref(),
+        Some("from-start")
+    );
+    assert_eq!(docs.get_attribute("data-motion").as_deref(), Some("to-end"));
+
+    events::click(&fixture.get("#products"));
+    assert_eq!(menu.open_item(), None);
+
+    // Following a link closes the menu
+    menu.set_open(Some(0));
+    events::click(&fixture.get("#editor"));
+    assert_eq!(menu.open_item(), None);
+    assert_eq!(
+        *changes.borrow(),
+        [Some(0), Some(1), Some(0), None, Some(0), None]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn mouse_rests_to_open_and_leaves_to_close() {
+    let fixture = Fixture::new(MARKUP);
+    let (menu, _) = menu(options(&fixture));
+
+    pointer::hover(&fixture.get("#products"));
+    assert_eq!(menu.open_item(), None);
+    wait::sleep(WAIT).await;
+    assert_eq!(menu.open_item(), Some(0));
+
+    // Another trigger switches at once while a content is open
+    pointer::hover(&fixture.get("#docs"));
+    assert_eq!(menu.open_item(), Some(1));
+
+    events::dispatch(
+        &fixture.get("#nav"),
+        &events::pointer_event("pointerleave", 0.0, 0.0),
+    );
+    assert_eq!(menu.open_item(), Some(1));
+    wait::sleep(WAIT).await;
+    assert_eq!(menu.open_item(), None);
+}
+
+#[wasm_bindgen_test]
+async fn touch_doesnt_open_on_hover() {
+    let fixture = Fixture::new(MARKUP);
+    let (menu, _) = menu(options(&fixture));
+
+    let init = PointerEventInit::new();
+    init.set_bubbles(true);
+    init.set_pointer_type("touch");
+    let event = PointerEvent::new_with_event_init_dict("pointerover", &init).unwrap();
+    events::dispatch(&fixture.get("#products"), &event);
+    wait::sleep(WAIT).await;
+    assert_eq!(menu.open_item(), None);
+}
+
+#[wasm_bindgen_test]
+fn arrows_move_between_top_level_items() {
+    let fixture = Fixture::new(MARKUP);
+    let (_menu, _) = menu(options(&fixture));
+    fixture.get("#products").focus().unwrap();
+
+    assert!(!press(&fixture, "ArrowRight"));
+    assert_focused(&fixture.get("#docs"));
+    press(&fixture, "ArrowRight");
+    assert_focused(&fixture.get("#blog"));
+    press(&fixture, "ArrowRight");
+    assert_focused(&fixture.get("#products"));
+    press(&fixture, "ArrowLeft");
+    assert_focused(&fixture.get("#blog"));
+    press(&fixture, "Home");
+    assert_focused(&fixture.get("#products"));
+    press(&fixture, "End");
+    assert_focused(&fixture.get("#blog"));
+}
+
+#[wasm_bindgen_test]
+fn arrow_down_enters_content_and_escape_leaves() {
+    let fixture = Fixture::new(MARKUP);
+    let (menu, _) = menu(options(&fixture));
+    fixture.get("#products").focus().unwrap();
+
+    assert!(!press(&fixture, "ArrowDown"));
+    assert_eq!(menu.open_item(), Some(0));
+    assert_focused(&fixture.get("#editor"));
+    press(&fixture, "ArrowDown");
+    assert_focused(&fixture.get("#viewer"));
+    press(&fixture, "ArrowDown");
+    assert_focused(&fixture.get("#editor"));
+    press(&fixture, "ArrowUp");
+    assert_focused(&fixture.get("#viewer"));
+    press(&fixture, "Home");
+    assert_focused(&fixture.get("#editor"));
+
+    assert!(!press(&fixture, "Escape"));
+    assert_eq!(menu.open_item(), None);
+    assert_focused(&fixture.get("#products"));
+}
+
+#[wasm_bindgen_test]
+fn focus_leaving_the_menu_closes() {
+    let fixture = Fixture::new(MARKUP);
+    let (menu, _) = menu(options(&fixture));
+    menu.set_open(Some(1));
+    fixture.get("#guide").focus().unwrap();
+
+    fixture.get("#docs").focus().unwrap();
+    assert_eq!(menu.open_item(), Some(1));
+    fixture.get("#outside").focus().unwrap();
+    assert_eq!(menu.open_item(), None);
+}
+
+#[wasm_bindgen_test]
+fn links_matching_current_path_are_active() {
+    let fixture = Fixture::new(MARKUP);
+    let (menu, _) = menu(options(&fixture).current("/docs/guide/"));
+    let guide = fixture.get("#guide");
+
+    assert_eq!(guide.get_attribute("aria-current").as_deref(), Some("page"));
+    assert!(guide.has_attribute("data-active"));
+    assert!(fixture.get("#docs").has_attribute("data-active"));
+    assert!(!fixture.get("#products").has_attribute("data-active"));
+
+    menu.set_current(Some("/blog".to_string()));
+    assert!(!guide.has_attribute("aria-current"));
+    assert!(!fixture.get("#docs").has_attribute("data-active"));
+    assert_eq!(
+        fixture
+            .get("#blog")
+            .get_attribute("aria-current")
+            .as_deref(),
+        Some("page")
+    );
+}
+
+#[wasm_bindgen_test]
+fn out_of_range_items_are_ignored() {
+    let fixture = Fixture::new(MARKUP);
+    let (menu, changes) = menu(options(&fixture));
+
+    menu.set_open(Some(5));
+    assert_eq!(menu.open_item(), None);
+    assert!(changes.borrow().is_empty());
+}
@@ -0,0 +1,551 @@
+//! Headless pagination with a sibling/boundary ellipsis model
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::{events::EventListener, utils::document};
+use seigi_utils::i18n;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, KeyboardEvent};
+
+/// An item of a pagination list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageItem {
+    /// A page number, starting from 1
+    Page(usize),
+    /// A gap of skipped pages
+    Ellipsis,
+}
+
+/// Returns the number of pages needed for the items, which is at least 1
+pub fn page_count(total_items: usize, page_size: usize) -> usize {
+    total_items.div_ceil(page_size.max(1)).max(1)
+}
+
+/// Returns the list of pages and ellipses to show
+///
+/// The list always has the same length for the same total, so controls don't shift while paging.
+///
+/// # Arguments
+/// * `current` - The current page, starting from 1
+/// * `total` - The number of pages
+/// * `siblings` - The number of pages shown on each side of the current page
+/// * `boundaries` - The number of pages always shown at the start and the end
+pub fn page_items(
+    current: usize,
+    total: usize,
+    siblings: usize,
+    boundaries: usize,
+) -> Vec<PageItem> {
+    let (current, total, siblings, boundaries) = (
+        current.clamp(1, total.max(1)) as isize,
+        total as isize,
+        siblings as isize,
+        boundaries as isize,
+    );
+    let range = |start: isize, end: isize| {
+        (start.max(1)..=end.min(total)).map(|v| PageItem::Page(v as usize))
+    };
+
+    // Every page fits in the space boundaries, siblings, the current page and ellipses take
+    if total <= boundaries * 2 + siblings * 2 + 3 {
+        return range(1, total).collect();
+    }
+
+    let end_start = (total - boundaries + 1).max(boundaries + 1);
+    let siblings_start = (current - siblings)
+        .min(total - boundaries - siblings * 2 - 1)
+        .max(boundaries + 2);
+    let siblings_end = (current + siblings)
+        .max(boundaries + siblings * 2 + 2)
+        .min(end_start - 2);
+
+    let mut items = range(1, boundaries).collect::<Vec<_>>();
+    if siblings_start > boundaries + 2 {
+        items.push(PageItem::Ellipsis);
+    } else if boundaries + 1 < total - boundaries {
+        items.push(PageItem::Page((boundaries + 1) as usize));
+    }
+    items.extend(range(siblings_start, siblings_end));
+    if siblings_end < total - boundaries - 1 {
+        items.push(PageItem::Ellipsis);
+    } else if total - boundaries > boundaries {
+        items.push(PageItem::Page((total - boundaries) as usize));
+    }
+    items.extend(range(end_start, total));
+    items
+}
+
+pub type PageHook = Box<dyn Fn(usize)>;
+pub type RenderHook = Box<dyn Fn(&HtmlElement, &[PageItem], usize)>;
+
+/// Hooks to [Pagination]
+#[derive(Default)]
+pub struct PaginationHooks {
+    /// Called with the page when the current page changes
+    pub change: Option<PageHook>,
+}
+
+/// Options of [Pagination]
+pub struct PaginationOptions {
+    /// The element containing controls of the pagination
+    pub container: HtmlElement,
+    /// The number of pages
+    pub total: usize,
+    /// The initial page, starting from 1
+    pub page: usize,
+    pub siblings: usize,
+    pub boundaries: usize,
+    /// Renders page items into the element with `data-seigi-page-list`, with the list, the items
+    /// and the current page. Defaults to [render_default]
+    pub render: RenderHook,
+    pub hooks: PaginationHooks,
+}
+
+impl PaginationOptions {
+    pub fn builder() -> PaginationOptionsBuilder {
+        PaginationOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [PaginationOptions]
+pub struct PaginationOptionsBuilder {
+    container: Option<HtmlElement>,
+    total: usize,
+    page: usize,
+    siblings: usize,
+    boundaries: usize,
+    render: RenderHook,
+    hooks: PaginationHooks,
+}
+
+impl Default for PaginationOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            container: None,
+            total: 1,
+            page: 1,
+            siblings: 1,
+            boundaries: 1,
+            render: Box::new(render_default),
+            hooks: PaginationHooks::default(),
+        }
+    }
+}
+
+impl PaginationOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn container(mut self, container: HtmlElement) -> Self {
+        self.container = Some(container);
+        self
+    }
+
+    /// Sets the number of pages
+    pub fn total(mut self, total: usize) -> Self {
+        self.total = total.max(1);
+        self
+    }
+
+    /// Sets the number of pages from the number of items and the page size
+    pub fn total_items(mut self, total_items: usize, page_size: usize) -> Self {
+        self.total = page_count(total_items, page_size);
+        self
+    }
+
+    pub fn page(mut self, page: usize) -> Self {
+        self.page = page;
+        self
+    }
+
+    pub fn siblings(mut self, siblings: usize) -> Self {
+        self.siblings = siblings;
+        self
+    }
+
+    pub fn boundaries(mut self, boundaries: usize) -> Self {
+        self.boundaries = boundaries;
+        self
+    }
+
+    pub fn render(mut self, render: impl Fn(&HtmlElement, &[PageItem], usize) + 'static) -> Self {
+        self.render = Box::new(render);
+        self
+    }
+
+    pub fn hooks(mut self, hooks: PaginationHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [PaginationOptions]
+    ///
+    /// # Panics
+    /// This method panics if container field is not set
+    pub fn build(self) -> PaginationOptions {
+        PaginationOptions {
+            container: self
+                .container
+                .expect("container must be set to build PaginationOptions"),
+            total: self.total,
+            page: self.page.clamp(1, self.total),
+            siblings: self.siblings,
+            boundaries: self.boundaries,
+            render: self.render,
+            hooks: self.hooks,
+        }
+    }
+}
+
+/// Renders page items as buttons and ellipses as spans
+///
+/// Buttons have `data-seigi-page` of their page, which is how clicks are recognized, so custom
+/// renderers should set it too.
+pub fn render_default(list: &HtmlElement, items: &[PageItem], _current: usize) {
+    list.set_text_content(None);
+    for item in items {
+        let element = match item {
+            PageItem::Page(page) => {
+                let button = document()
+                    .create_element("button")
+                    .expect("failed to create element");
+                let _ = button.set_attribute("type", "button");
+                let _ = button.set_attribute("data-seigi-page", &page.to_string());
+                button.set_text_content(Some(&page.to_string()));
+                button
+            }
+            PageItem::Ellipsis => {
+                let span = document()
+                    .create_element("span")
+                    .expect("failed to create element");
+                let _ = span.set_attribute("data-seigi-page-ellipsis", "");
+                let _ = span.set_attribute("aria-hidden", "true");
+                span.set_text_content(Some("…"));
+                span
+            }
+        };
+        let _ = list.append_child(&element);
+    }
+}
+
+/// Controls recognized in the container and keys of their labels in [i18n]
+const CONTROLS: [(&str, &str); 4] = [
+    ("data-seigi-page-first", "pagination.first"),
+    ("data-seigi-page-prev", "pagination.previous"),
+    ("data-seigi-page-next", "pagination.next"),
+    ("data-seigi-page-last", "pagination.last"),
+];
+
+fn set_disabled(element: &Element, disabled: bool) {
+    if disabled {
+        let _ = element.set_attribute("data-disabled", "");
+        let _ = element.set_attribute("aria-disabled", "true");
+    } else {
+        let _ = element.remove_attribute("data-disabled");
+        let _ = element.remove_attribute("aria-disabled");
+    }
+}
+
+struct Inner {
+    container: HtmlElement,
+    total: usize,
+    page: usize,
+    siblings: usize,
+    boundaries: usize,
+    render: Rc<RenderHook>,
+    hooks: Rc<PaginationHooks>,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn query(&self, selector: &str) -> Vec<Element> {
+        let Ok(nodes) = self.container.query_selector_all(selector) else {
+            return vec![];
+        };
+        (0..nodes.length())
+            .filter_map(|v| nodes.get(v))
+            .filter_map(|v| v.dyn_into::<Element>().ok())
+            .collect()
+    }
+
+    /// Updates controls, and returns what is needed to render the list
+    fn reflect(&self) -> (Option<HtmlElement>, Vec<PageItem>, usize) {
+        let (page, total) = (self.page, self.total);
+        for (attribute, key) in CONTROLS {
+            for control in self.query(&format!("[{attribute}]")) {
+                if !control.has_attribute("aria-label") {
+                    let _ = control.set_attribute("aria-label", &i18n::translate(key, &[]));
+                }
+                let is_start = attribute.ends_with("first") || attribute.ends_with("prev");
+                set_disabled(&control, if is_start { page <= 1 } else { page >= total });
+            }
+        }
+
+        let list = self
+            .container
+            .query_selector("[data-seigi-page-list]")
+            .ok()
+            .flatten()
+            .and_then(|v| v.dyn_into::<HtmlElement>().ok());
+        (
+            list,
+            page_items(page, total, self.siblings, self.boundaries),
+            page,
+        )
+    }
+
+    /// Labels page buttons rendered into the list
+    fn label_pages(&self) {
+        for button in self.query("[data-seigi-page]") {
+            let Some(page) = button
+                .get_attribute("data-seigi-page")
+                .and_then(|v| v.parse::<usize>().ok())
+            else {
+                continue;
+            };
+            if page == self.page {
+                let _ = button.set_attribute("aria-current", "page");
+                let _ = button.set_attribute("data-current", "");
+            } else {
+                let _ = button.remove_attribute("aria-current");
+                let _ = button.remove_attribute("data-current");
+            }
+            if !button.has_attribute("aria-label") {
+                let _ = button.set_attribute(
+                    "aria-label",
+                    &i18n::translate("pagination.page", &[("page", &page)]),
+                );
+            }
+        }
+    }
+}
+
+/// An instance of pagination
+///
+/// The container holds optional controls with `data-seigi-page-first`, `data-seigi-page-prev`,
+/// `data-seigi-page-next` and `data-seigi-page-last`, and an element with
+/// `data-seigi-page-list` the page items are rendered into. Arrow keys move focus between page
+/// buttons, and Home/End move it to the first and last ones.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **aria-current** and **data-current** are set in the button of the current page
+///
+/// **data-disabled** and **aria-disabled** are set in controls that can't move further
+#[derive(Clone)]
+pub struct Pagination(Rc<Mutex<Inner>>);
+
+impl Pagination {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    pub fn page(&self) -> usize {
+        self.0.lock().unwrap().page
+    }
+
+    pub fn total(&self) -> usize {
+        self.0.lock().unwrap().total
+    }
+
+    /// Moves to the page, clamped to the pages
+    pub fn set_page(&self, page: usize) {
+        let hooks = {
+            let mut inner = self.0.lock().unwrap();
+            let page = page.clamp(1, inner.total);
+            if inner.page == page {
+                return;
+            }
+            inner.page = page;
+            inner.hooks.clone()
+        };
+
+        self.render();
+        if let Some(hook) = &hooks.change {
+            hook(self.page());
+        }
+    }
+
+    /// Changes the number of pages, moving to the last page if the current one is gone
+    pub fn set_total(&self, total: usize) {
+        let page = {
+            let mut inner = self.0.lock().unwrap();
+            inner.total = total.max(1);
+            inner.page
+        };
+
+        self.render();
+        self.set_page(page);
+    }
+
+    pub fn previous(&self) {
+        self.set_page(self.page().saturating_sub(1));
+    }
+
+    pub fn next(&self) {
+        self.set_page(self.page() + 1);
+    }
+
+    pub fn first(&self) {
+        self.set_page(1);
+    }
+
+    pub fn last(&self) {
+        self.set_page(self.total());
+    }
+
+    /// Rerenders the list and updates controls
+    pub fn render(&self) {
+        let (render, (list, items, page)) = {
+            let inner = self.0.lock().unwrap();
+            (inner.render.clone(), inner.reflect())
+        };
+
+        // The focused button is replaced on render, so focus moves to the new one of the page
+        let focused_page = document()
+            .active_element()
+            .filter(|v| list.as_ref().is_some_and(|list| list.contains(Some(v))))
+            .and_then(|v| v.get_attribute("data-seigi-page"));
+
+        if let Some(list) = &list {
+            render(list, &items, page);
+        }
+        self.0.lock().unwrap().label_pages();
+
+        if let (Some(list), Some(focused_page)) = (&list, focused_page) {
+            let selector = format!("[data-seigi-page=\"{focused_page}\"]");
+            if let Ok(Some(button)) = list.query_selector(&selector)
+                && let Ok(button) = button.dyn_into::<HtmlElement>()
+            {
+                let _ = button.focus();
+            }
+        }
+    }
+
+    fn handle_click(&self, target: &Element) {
+        if let Some(page) = target
+            .closest("[data-seigi-page]")
+            .ok()
+            .flatten()
+            .and_then(|v| v.get_attribute("data-seigi-page"))
+            .and_then(|v| v.parse::<usize>().ok())
+        {
+            self.set_page(page);
+            return;
+        }
+
+        let control = CONTROLS
+            .iter()
+            .find(|(attribute, _)| {
+                target
+                    .closest(&format!("[{attribute}]"))
+                    .ok()
+                    .flatten()
+                    .is_some()
+            })
+            .map(|(attribute, _)| *attribute);
+        match control {
+            Some("data-seigi-page-first") => self.first(),
+            Some("data-seigi-page-prev") => self.previous(),
+            Some("data-seigi-page-next") => self.next(),
+            Some("data-seigi-page-last") => self.last(),
+            _ => {}
+        }
+    }
+
+    fn handle_key_down(&self, event: &KeyboardEvent) {
+        let Some(target) = event.target().and_then(|v| v.dyn_into::<Element>().ok()) else {
+            return;
+        };
+        if !target.has_attribute("data-seigi-page") {
+            return;
+        }
+
+        let buttons = self
+            .0
+            .lock()
+            .unwrap()
+            .query("[data-seigi-page]")
+            .into_iter()
+            .filter_map(|v| v.dyn_into::<HtmlElement>().ok())
+            .collect::<Vec<_>>();
+        let Some(index) = buttons.iter().position(|v| **v == target) else {
+            return;
+        };
+
+        let next = match event.key().as_str() {
+            "ArrowLeft" => index.checked_sub(1),
+            "ArrowRight" => Some(index + 1),
+            "Home" => Some(0),
+            "End" => buttons.len().checked_sub(1),
+            _ => return,
+        };
+        event.prevent_default();
+        if let Some(button) = next.and_then(|v| buttons.get(v)) {
+            let _ = button.focus();
+        }
+    }
+}
+
+pub fn create_pagination(options: PaginationOptions) -> Pagination {
+    let container = options.container.clone();
+    if !container.has_attribute("role") {
+        let _ = container.set_attribute("role", "navigation");
+    }
+    if !container.has_attribute("aria-label") {
+        let _ = container.set_attribute("aria-label", &i18n::translate("pagination.label", &[]));
+    }
+
+    let pagination = Pagination(Rc::new(Mutex::new(Inner {
+        container: options.container,
+        total: options.total,
+        page: options.page,
+        siblings: options.siblings,
+        boundaries: options.boundaries,
+        render: Rc::new(options.render),
+        hooks: Rc::new(options.hooks),
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&pagination.0);
+    let listeners = vec![
+        EventListener::new(&container, "click", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(pagination), Some(target)) = (
+                    Pagination::from_weak(&weak),
+                    event.target().and_then(|v| v.dyn_into::<Element>().ok()),
+                ) else {
+                    return;
+                };
+                // Disabled controls stay focusable, so clicks on them are ignored here
+                if target.closest("[data-disabled]").ok().flatten().is_some() {
+                    return;
+                }
+                pagination.handle_click(&target);
+            }
+        }),
+        EventListener::new(&container, "keydown", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(pagination), Some(event)) = (
+                    Pagination::from_weak(&weak),
+                    event.dyn_ref::<KeyboardEvent>(),
+                ) else {
+                    return;
+                };
+                pagination.handle_key_down(event);
+            }
+        }),
+    ];
+
+    pagination.0.lock().unwrap()._listeners = listeners;
+    pagination.render();
+    pagination
+}
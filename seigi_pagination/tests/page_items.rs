@@ -0,0 +1,77 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use seigi_pagination::{PageItem, page_count, page_items};
+
+/// Parses a list like `"1 2 … 10"`, where `…` is an ellipsis
+fn items(list: &str) -> Vec<PageItem> {
+    list.split_whitespace()
+        .map(|v| match v {
+            "…" => PageItem::Ellipsis,
+            v => PageItem::Page(v.parse().unwrap()),
+        })
+        .collect()
+}
+
+/// Cases of `(current, total, siblings, boundaries, expected)`
+const CASES: &[(usize, usize, usize, usize, &str)] = &[
+    // A single page
+    (1, 1, 1, 1, "1"),
+    (0, 1, 1, 1, "1"),
+    (5, 1, 2, 2, "1"),
+    // Every page fits
+    (1, 7, 1, 1, "1 2 3 4 5 6 7"),
+    (4, 7, 1, 1, "1 2 3 4 5 6 7"),
+    // The first and the last page
+    (1, 10, 1, 1, "1 2 3 4 5 … 10"),
+    (10, 10, 1, 1, "1 … 6 7 8 9 10"),
+    (0, 10, 1, 1, "1 2 3 4 5 … 10"),
+    (11, 10, 1, 1, "1 … 6 7 8 9 10"),
+    // Next to the boundary pages
+    (2, 10, 1, 1, "1 2 3 4 5 … 10"),
+    (3, 10, 1, 1, "1 2 3 4 5 … 10"),
+    (4, 10, 1, 1, "1 2 3 4 5 … 10"),
+    (5, 10, 1, 1, "1 … 4 5 6 … 10"),
+    (6, 10, 1, 1, "1 … 5 6 7 … 10"),
+    (7, 10, 1, 1, "1 … 6 7 8 9 10"),
+    (9, 10, 1, 1, "1 … 6 7 8 9 10"),
+    (3, 20, 1, 2, "1 2 3 4 5 6 … 19 20"),
+    (10, 20, 1, 2, "1 2 … 9 10 11 … 19 20"),
+    (18, 20, 1, 2, "1 2 … 15 16 17 18 19 20"),
+    // Siblings and boundaries larger than the total
+    (3, 5, 10, 1, "1 2 3 4 5"),
+    (2, 5, 0, 10, "1 2 3 4 5"),
+    (1, 3, 100, 100, "1 2 3"),
+    // Without siblings and boundaries
+    (5, 10, 0, 0, "… 5 …"),
+];
+
+#[test]
+fn page_items_match_table() {
+    for &(current, total, siblings, boundaries, expected) in CASES {
+        assert_eq!(
+            page_items(current, total, siblings, boundaries),
+            items(expected),
+            "current: {current}, total: {total}, siblings: {siblings}, boundaries: {boundaries}"
+        );
+    }
+}
+
+#[test]
+fn page_items_keep_length_while_paging() {
+    for (total, siblings, boundaries) in [(10, 1, 1), (20, 1, 2), (30, 2, 1), (100, 0, 0)] {
+        let length = page_items(1, total, siblings, boundaries).len();
+        for current in 1..=total {
+            let items = page_items(current, total, siblings, boundaries);
+            assert_eq!(items.len(), length, "current: {current}, total: {total}");
+            assert!(items.contains(&PageItem::Page(current)));
+        }
+    }
+}
+
+#[test]
+fn page_count_is_at_least_one() {
+    assert_eq!(page_count(0, 10), 1);
+    assert_eq!(page_count(10, 10), 1);
+    assert_eq!(page_count(11, 10), 2);
+    assert_eq!(page_count(5, 0), 5);
+}
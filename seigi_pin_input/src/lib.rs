@@ -0,0 +1,540 @@
+//! Headless segmented one-time-code input
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::events::EventListener;
+use seigi_focus::candidates;
+use seigi_utils::i18n;
+use wasm_bindgen::JsCast;
+use web_sys::{ClipboardEvent, Event, HtmlElement, HtmlInputElement, KeyboardEvent};
+
+/// Characters a pin input accepts
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PinKind {
+    /// Only ascii digits are accepted
+    #[default]
+    Numeric,
+    /// Ascii letters and digits are accepted
+    Alphanumeric,
+}
+
+impl PinKind {
+    fn accepts(&self, c: char) -> bool {
+        match self {
+            PinKind::Numeric => c.is_ascii_digit(),
+            PinKind::Alphanumeric => c.is_ascii_alphanumeric(),
+        }
+    }
+
+    /// Returns the key of input labels in [i18n]
+    fn label(&self) -> &'static str {
+        match self {
+            PinKind::Numeric => "pin.digit",
+            PinKind::Alphanumeric => "pin.character",
+        }
+    }
+
+    fn input_mode(&self) -> &'static str {
+        match self {
+            PinKind::Numeric => "numeric",
+            PinKind::Alphanumeric => "text",
+        }
+    }
+}
+
+/// A hook called with the code of [PinInput]
+pub type CodeHook = Box<dyn Fn(&str)>;
+
+/// Hooks to [PinInput]
+#[derive(Default)]
+pub struct PinInputHooks {
+    /// Called with the current code whenever any of the segments change
+    ///
+    /// Empty segments are omitted from the code
+    pub change: Option<CodeHook>,
+    /// Called with the full code when every segment is filled
+    pub complete: Option<CodeHook>,
+}
+
+/// Options of [PinInput]
+pub struct PinInputOptions {
+    /// The element that contains the segment inputs
+    pub container: HtmlElement,
+    /// The characters the input accepts
+    pub kind: PinKind,
+    /// Whether the segments should mask the entered characters
+    pub mask: bool,
+    /// The hooks
+    pub hooks: PinInputHooks,
+}
+
+impl PinInputOptions {
+    pub fn builder() -> PinInputOptionsBuilder {
+        PinInputOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [PinInputOptions]
+#[derive(Default)]
+pub struct PinInputOptionsBuilder {
+    container: Option<HtmlElement>,
+    kind: PinKind,
+    mask: bool,
+    hooks: PinInputHooks,
+}
+
+impl PinInputOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn container(mut self, container: HtmlElement) -> Self {
+        self.container = Some(container);
+        self
+    }
+
+    pub fn kind(mut self, kind: PinKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn mask(mut self, mask: bool) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: PinInputHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [PinInputOptions]
+    ///
+    /// # Panics
+    /// This method panics if container field is not set
+    pub fn build(self) -> PinInputOptions {
+        PinInputOptions {
+            container: self
+                .container
+                .expect("container must be set to build PinInputOptions"),
+            kind: self.kind,
+            mask: self.mask,
+            hooks: self.hooks,
+        }
+    }
+}
+
+/// The event produced by a state change, published after the state is unlocked
+enum Notify {
+    Change(String),
+    Complete(String),
+}
+
+struct Inner {
+    options: Rc<PinInputOptions>,
+    inputs: Vec<HtmlInputElement>,
+    values: Vec<Option<char>>,
+    /// The segment to focus once the state is unlocked, as focusing fires `focusin` at once
+    focus: Option<HtmlInputElement>,
+    /// Kept to remove the listeners when the state drops
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn index_of(&self, target: &HtmlInputElement) -> Option<usize> {
+        self.inputs.iter().position(|v| v == target)
+    }
+
+    fn code(&self) -> String {
+        self.values.iter().flatten().collect()
+    }
+
+    fn is_complete(&self) -> bool {
+        self.values.iter().all(Option::is_some)
+    }
+
+    fn initialize(&mut self) {
+        let count = self.inputs.len();
+        for (index, input) in self.inputs.iter().enumerate() {
+            input.set_type(if self.options.mask {
+                "password"
+            } else {
+                "text"
+            });
+            let _ = input.set_attribute("inputmode", self.options.kind.input_mode());
+            let _ = input.set_attribute(
+                "autocomplete",
+                if index == 0 { "one-time-code" } else { "off" },
+            );
+            let _ = input.set_attribute(
+                "aria-label",
+                i18n::translate(
+                    self.options.kind.label(),
+                    &[("index", &(index + 1)), ("count", &count)],
+                )
+                .as_str(),
+            );
+            let _ = input.set_attribute("data-seigi-pin-index", index.to_string().as_str());
+        }
+        let _ = self.options.container.set_attribute("role", "group");
+        self.sync();
+    }
+
+    /// Writes the values to the inputs and updates the attributes
+    fn sync(&self) {
+        for (input, value) in self.inputs.iter().zip(self.values.iter()) {
+            let value = value.map(String::from).unwrap_or_default();
+            if input.value() != value {
+                input.set_value(value.as_str());
+            }
+
+            if value.is_empty() {
+                let _ = input.remove_attribute("data-seigi-pin-filled");
+            } else {
+                let _ = input.set_attribute("data-seigi-pin-filled", "");
+            }
+        }
+
+        if self.is_complete() {
+            let _ = self
+                .options
+                .container
+                .set_attribute("data-seigi-pin-complete", "");
+        } else {
+            let _ = self
+                .options
+                .container
+                .remove_attribute("data-seigi-pin-complete");
+        }
+    }
+
+    /// Focuses the segment after the state is unlocked by [PinInput::run]
+    fn focus(&mut self, index: usize) {
+        self.focus = self.inputs.get(index).cloned();
+    }
+
+    /// Writes characters of text into segments starting from index, dropping unaccepted ones
+    ///
+    /// # Returns
+    /// The index of the segment after the last written one
+    fn distribute(&mut self, index: usize, text: &str) -> usize {
+        let mut position = index;
+        for c in text.chars().filter(|c| self.options.kind.accepts(*c)) {
+            if position >= self.values.len() {
+                break;
+            }
+            self.values[position] = Some(c);
+            position += 1;
+        }
+
+        position
+    }
+
+    fn notifications(&self, previous: &str) -> Vec<Notify> {
+        let code = self.code();
+        if code == previous {
+            return vec![];
+        }
+
+        let mut notifications = vec![Notify::Change(code.clone())];
+        if self.is_complete() {
+            notifications.push(Notify::Complete(code));
+        }
+        notifications
+    }
+
+    fn handle_input(&mut self, event: &Event) -> Vec<Notify> {
+        let Some(input) = event
+            .target()
+            .and_then(|v| v.dyn_into::<HtmlInputElement>().ok())
+        else {
+            return vec![];
+        };
+        let Some(index) = self.index_of(&input) else {
+            return vec![];
+        };
+        let previous = self.code();
+
+        let value = input.value();
+        let next = if value.is_empty() {
+            self.values[index] = None;
+            index
+        } else {
+            // Typing into a filled segment or autofilling leaves more than one character in the
+            // input, so the last typed character wins unless it looks like a full code
+            let text = if value.chars().count() > 2 {
+                value.as_str()
+            } else {
+                &value[value.char_indices().last().map_or(0, |(i, _)| i)..]
+            };
+            self.distribute(index, text)
+        };
+
+        self.sync();
+        if next != index {
+            self.focus(next.min(self.inputs.len() - 1));
+        }
+
+        self.notifications(&previous)
+    }
+
+    fn handle_key_down(&mut self, event: &KeyboardEvent) -> Vec<Notify> {
+        let Some(input) = event
+            .target()
+            .and_then(|v| v.dyn_into::<HtmlInputElement>().ok())
+        else {
+            return vec![];
+        };
+        let Some(index) = self.index_of(&input) else {
+            return vec![];
+        };
+        let previous = self.code();
+
+        match event.key().as_str() {
+            "Backspace" => {
+                event.prevent_default();
+                if self.values[index].is_some() {
+                    self.values[index] = None;
+                } else if index > 0 {
+                    self.values[index - 1] = None;
+                    self.focus(index - 1);
+                }
+            }
+            "Delete" => {
+                event.prevent_default();
+                self.values[index] = None;
+            }
+            "ArrowLeft" => {
+                event.prevent_default();
+                self.focus(index.saturating_sub(1));
+            }
+            "ArrowRight" => {
+                event.prevent_default();
+                self.focus((index + 1).min(self.inputs.len() - 1));
+            }
+            "Home" => {
+                event.prevent_default();
+                self.focus(0);
+            }
+            "End" => {
+                event.prevent_default();
+                self.focus(self.inputs.len() - 1);
+            }
+            _ => return vec![],
+        }
+
+        self.sync();
+        self.notifications(&previous)
+    }
+
+    fn handle_paste(&mut self, event: &ClipboardEvent) -> Vec<Notify> {
+        let Some(input) = event
+            .target()
+            .and_then(|v| v.dyn_into::<HtmlInputElement>().ok())
+        else {
+            return vec![];
+        };
+        let Some(index) = self.index_of(&input) else {
+            return vec![];
+        };
+        let Some(text) = event.clipboard_data().and_then(|v| v.get_data("text").ok()) else {
+            return vec![];
+        };
+        event.prevent_default();
+        let previous = self.code();
+
+        let next = self.distribute(index, text.trim());
+        self.sync();
+        self.focus(next.min(self.inputs.len() - 1));
+
+        self.notifications(&previous)
+    }
+
+    fn set_value(&mut self, value: &str) -> Vec<Notify> {
+        let previous = self.code();
+        self.values.fill(None);
+        self.distribute(0, value);
+        self.sync();
+
+        self.notifications(&previous)
+    }
+}
+
+/// Publishes notifications to the hooks
+///
+/// This must be called after the state is unlocked so hooks can call back into [PinInput]
+fn publish(options: &PinInputOptions, notifications: Vec<Notify>) {
+    for notify in notifications {
+        match notify {
+            Notify::Change(code) => {
+                if let Some(hook) = &options.hooks.change {
+                    hook(&code);
+                }
+            }
+            Notify::Complete(code) => {
+                if let Some(hook) = &options.hooks.complete {
+                    hook(&code);
+                }
+            }
+        }
+    }
+}
+
+/// An instance of segmented one-time-code input
+///
+/// Every `<input>` inside the container becomes a segment holding a single character. Typing
+/// advances focus to the next segment, backspace on an empty segment moves back, and pasting
+/// splits the pasted code across segments.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation. Dropping every handle removes the attached listeners.
+///
+/// # Attributes
+/// **data-seigi-pin-index** is set in each segment input to its index
+///
+/// **data-seigi-pin-filled** is set in each segment input that holds a character
+///
+/// **data-seigi-pin-complete** is set in the container when every segment is filled
+#[derive(Clone)]
+pub struct PinInput(Rc<Mutex<Inner>>);
+
+impl PinInput {
+    fn run(&self, f: impl FnOnce(&mut Inner) -> Vec<Notify>) {
+        let (options, notifications, focus) = {
+            let mut inner = self.0.lock().unwrap();
+            let notifications = f(&mut inner);
+            (inner.options.clone(), notifications, inner.focus.take())
+        };
+        if let Some(input) = focus.filter(|v| candidates::is_focusable(v.unchecked_ref())) {
+            let _ = input.focus();
+            input.select();
+        }
+        publish(&options, notifications);
+    }
+
+    /// Returns the current code, omitting empty segments
+    pub fn value(&self) -> String {
+        self.0.lock().unwrap().code()
+    }
+
+    /// Returns true if every segment is filled
+    pub fn is_complete(&self) -> bool {
+        self.0.lock().unwrap().is_complete()
+    }
+
+    /// Returns the number of segments
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().inputs.len()
+    }
+
+    /// Returns true if the container has no segments
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Replaces the code, filling segments from the first one
+    ///
+    /// Characters that are not accepted by [PinKind] are dropped
+    pub fn set_value(&self, value: &str) {
+        self.run(|inner| inner.set_value(value));
+    }
+
+    /// Clears every segment and focuses the first one
+    pub fn clear(&self) {
+        self.run(|inner| {
+            let notifications = inner.set_value("");
+            inner.focus(0);
+            notifications
+        });
+    }
+
+    /// Focuses the first empty segment, or the last one if every segment is filled
+    pub fn focus(&self) {
+        self.run(|inner| {
+            let index = inner
+                .values
+                .iter()
+                .position(Option::is_none)
+                .unwrap_or(inner.inputs.len().saturating_sub(1));
+            inner.focus(index);
+            vec![]
+        });
+    }
+}
+
+fn listener(
+    container: &HtmlElement,
+    event_type: &'static str,
+    weak: Weak<Mutex<Inner>>,
+    handler: impl Fn(&mut Inner, &Event) -> Vec<Notify> + 'static,
+) -> EventListener {
+    EventListener::new(container, event_type, move |event| {
+        let Some(rc) = weak.upgrade() else {
+            return;
+        };
+        PinInput(rc).run(|inner| handler(inner, event));
+    })
+}
+
+pub fn create(options: PinInputOptions) -> PinInput {
+    let options = Rc::new(options);
+    let inputs = options
+        .container
+        .query_selector_all("input")
+        .map(|list| {
+            list.values()
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v.dyn_into::<HtmlInputElement>().ok())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    let state = Rc::new_cyclic(|weak: &Weak<Mutex<Inner>>| {
+        let container = &options.container;
+        let listeners = vec![
+            listener(container, "input", weak.clone(), |inner, event| {
+                inner.handle_input(event)
+            }),
+            listener(
+                container,
+                "keydown",
+                weak.clone(),
+                |inner, event| match event.dyn_ref::<KeyboardEvent>() {
+                    Some(event) => inner.handle_key_down(event),
+                    None => vec![],
+                },
+            ),
+            listener(container, "paste", weak.clone(), |inner, event| match event
+                .dyn_ref::<ClipboardEvent>()
+            {
+                Some(event) => inner.handle_paste(event),
+                None => vec![],
+            }),
+            listener(container, "focusin", weak.clone(), |_, event| {
+                if let Some(input) = event
+                    .target()
+                    .and_then(|v| v.dyn_into::<HtmlInputElement>().ok())
+                {
+                    input.select();
+                }
+                vec![]
+            }),
+        ];
+
+        Mutex::new(Inner {
+            values: vec![None; inputs.len()],
+            inputs,
+            focus: None,
+            options: options.clone(),
+            _listeners: listeners,
+        })
+    });
+
+    state.lock().unwrap().initialize();
+
+    PinInput(state)
+}
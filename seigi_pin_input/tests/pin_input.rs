@@ -0,0 +1,59 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_pin_input::PinInputOptions;
+use seigi_testkit::{
+    Fixture, assert_focused,
+    events::{self, Modifiers},
+    focus,
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::{HtmlElement, HtmlInputElement};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<div id="pin">
+    <input id="first">
+    <input id="second">
+    <input id="third">
+</div>
+"#;
+
+fn type_into(input: &HtmlElement, value: &str) {
+    input.unchecked_ref::<HtmlInputElement>().set_value(value);
+    events::fire(input, "input");
+}
+
+#[wasm_bindgen_test]
+async fn typing_advances_focus_to_next_segment() {
+    let fixture = Fixture::new(MARKUP);
+    let pin = seigi_pin_input::create(
+        PinInputOptions::builder()
+            .container(fixture.get("#pin"))
+            .build(),
+    );
+    let (first, second, third) = (
+        fixture.get("#first"),
+        fixture.get("#second"),
+        fixture.get("#third"),
+    );
+
+    focus::focus(&first).await;
+    type_into(&first, "1");
+    assert_focused(&second);
+    type_into(&second, "2");
+    assert_focused(&third);
+    assert_eq!(pin.value(), "12");
+
+    events::key_down(&third, "ArrowLeft", Modifiers::default());
+    assert_focused(&second);
+    events::key_down(&second, "Home", Modifiers::default());
+    assert_focused(&first);
+
+    pin.focus();
+    assert_focused(&third);
+    pin.clear();
+    assert_focused(&first);
+    assert_eq!(pin.value(), "");
+}
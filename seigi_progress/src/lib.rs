@@ -0,0 +1,290 @@
+//! A registry of long running tasks and their progress, shared by widgets showing it
+//!
+//! Tasks are started with [start], which returns a [TaskHandle] the task reports through.
+//! Widgets subscribe to the registry and render a single task or the [Summary] of all running
+//! tasks, such as "Uploading 3 files, 42%".
+
+use std::{
+    cell::OnceCell,
+    rc::Rc,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use seigi_meter::Meter;
+
+/// An identifier of a task in a registry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TaskId(u64);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskStatus {
+    Running,
+    Finished,
+    /// The task failed with the error message
+    Failed(String),
+    /// The handle of the task was dropped while it was running
+    Cancelled,
+}
+
+/// A snapshot of a task
+#[derive(Debug, Clone, PartialEq)]
+pub struct Task {
+    pub id: TaskId,
+    pub label: String,
+    /// Progress from 0 to 1, where `None` is indeterminate
+    pub progress: Option<f64>,
+    pub message: Option<String>,
+    pub status: TaskStatus,
+}
+
+/// Progress of all running tasks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub running: usize,
+    /// The mean progress of running tasks from 0 to 1, which is `None` if any of them is
+    /// indeterminate or none is running
+    pub progress: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub enum TaskEvent {
+    Start(Task),
+    Update(Task),
+    /// The task settled with a status other than [TaskStatus::Running], and is removed from the
+    /// registry
+    Settle(Task),
+}
+
+impl TaskEvent {
+    pub fn task(&self) -> &Task {
+        match self {
+            Self::Start(task) | Self::Update(task) | Self::Settle(task) => task,
+        }
+    }
+}
+
+struct Subscriber {
+    callback: Box<dyn Fn(&TaskEvent)>,
+    handle: u64,
+}
+
+#[derive(Default)]
+struct State {
+    tasks: Vec<Task>,
+    sequence: u64,
+    subscribers: Vec<Rc<Subscriber>>,
+}
+
+/// A registry of running tasks
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+#[derive(Clone, Default)]
+pub struct Registry(Rc<Mutex<State>>);
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a running task
+    ///
+    /// # Returns
+    /// The handle the task reports its progress through
+    pub fn start(&self, label: impl Into<String>) -> TaskHandle {
+        let task = {
+            let mut state = self.0.lock().unwrap();
+            let id = TaskId(state.sequence);
+            state.sequence += 1;
+            let task = Task {
+                id,
+                label: label.into(),
+                progress: None,
+                message: None,
+                status: TaskStatus::Running,
+            };
+            state.tasks.push(task.clone());
+            task
+        };
+
+        let id = task.id;
+        self.publish(TaskEvent::Start(task));
+        TaskHandle {
+            registry: self.clone(),
+            id,
+        }
+    }
+
+    /// Returns running tasks in the order they started
+    pub fn tasks(&self) -> Vec<Task> {
+        self.0.lock().unwrap().tasks.clone()
+    }
+
+    pub fn get(&self, id: TaskId) -> Option<Task> {
+        self.0
+            .lock()
+            .unwrap()
+            .tasks
+            .iter()
+            .find(|v| v.id == id)
+            .cloned()
+    }
+
+    pub fn summary(&self) -> Summary {
+        let state = self.0.lock().unwrap();
+        let running = state.tasks.len();
+        let progress = state
+            .tasks
+            .iter()
+            .map(|v| v.progress)
+            .sum::<Option<f64>>()
+            .filter(|_| running > 0)
+            .map(|v| v / running as f64);
+        Summary { running, progress }
+    }
+
+    /// Subscribes to tasks starting, updating and settling
+    ///
+    /// # Returns
+    /// A handle to unsubscribe with [Registry::unsubscribe]
+    pub fn subscribe(&self, callback: Box<dyn Fn(&TaskEvent)>) -> u64 {
+        static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+        let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .push(Rc::new(Subscriber { callback, handle }));
+        handle
+    }
+
+    pub fn unsubscribe(&self, handle: u64) {
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .retain(|v| v.handle != handle);
+    }
+
+    fn publish(&self, event: TaskEvent) {
+        let subscribers = self.0.lock().unwrap().subscribers.clone();
+        for subscriber in subscribers {
+            (subscriber.callback)(&event);
+        }
+    }
+
+    /// Applies the change to the running task and publishes it
+    fn update(&self, id: TaskId, f: impl FnOnce(&mut Task)) {
+        let event = {
+            let mut state = self.0.lock().unwrap();
+            let Some(index) = state.tasks.iter().position(|v| v.id == id) else {
+                return;
+            };
+            f(&mut state.tasks[index]);
+            if state.tasks[index].status == TaskStatus::Running {
+                TaskEvent::Update(state.tasks[index].clone())
+            } else {
+                TaskEvent::Settle(state.tasks.remove(index))
+            }
+        };
+        self.publish(event);
+    }
+}
+
+/// A handle a running task reports its progress through
+///
+/// Dropping the handle of a running task settles it as [TaskStatus::Cancelled], so tasks
+/// aborted early don't stay in the registry.
+pub struct TaskHandle {
+    registry: Registry,
+    id: TaskId,
+}
+
+impl TaskHandle {
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
+
+    /// Sets progress from 0 to 1, where `None` is indeterminate
+    pub fn set_progress(&self, progress: Option<f64>) {
+        self.registry.update(self.id, |task| {
+            task.progress = progress.map(|v| v.clamp(0.0, 1.0));
+        });
+    }
+
+    /// Sets progress from the count of done units, such as bytes or files
+    pub fn set_done(&self, done: u64, total: u64) {
+        let progress = (total > 0).then(|| done as f64 / total as f64);
+        self.set_progress(progress);
+    }
+
+    pub fn set_message(&self, message: impl Into<String>) {
+        let message = message.into();
+        self.registry.update(self.id, |task| {
+            task.message = Some(message);
+        });
+    }
+
+    pub fn finish(self) {
+        self.registry.update(self.id, |task| {
+            task.progress = Some(1.0);
+            task.status = TaskStatus::Finished;
+        });
+    }
+
+    pub fn fail(self, error: impl Into<String>) {
+        let error = error.into();
+        self.registry.update(self.id, |task| {
+            task.status = TaskStatus::Failed(error);
+        });
+    }
+}
+
+impl Drop for TaskHandle {
+    fn drop(&mut self) {
+        // Tasks settled by finish or fail are already removed, so this does nothing for them
+        self.registry.update(self.id, |task| {
+            task.status = TaskStatus::Cancelled;
+        });
+    }
+}
+
+thread_local! {
+    static GLOBAL_REGISTRY: OnceCell<Registry> = const { OnceCell::new() };
+}
+
+/// Returns the global registry
+pub fn global() -> Registry {
+    GLOBAL_REGISTRY.with(|cell| cell.get_or_init(Registry::new).clone())
+}
+
+/// Registers a running task to the global registry
+pub fn start(label: impl Into<String>) -> TaskHandle {
+    global().start(label)
+}
+
+/// Mirrors the summary of the registry to the meter, as a percentage while tasks are running
+///
+/// The meter should be created with [seigi_meter::Kind::Progress] and range from 0 to 100.
+///
+/// # Returns
+/// A handle to unsubscribe with [Registry::unsubscribe]
+pub fn bind_meter(registry: &Registry, meter: Meter) -> u64 {
+    // The subscriber is owned by the registry, so it refers back to it weakly
+    let weak = Rc::downgrade(&registry.0);
+    let update = move || {
+        if let Some(state) = weak.upgrade() {
+            let summary = Registry(state).summary();
+            let value = match summary {
+                Summary { running: 0, .. } => Some(100.0),
+                Summary { progress, .. } => progress.map(|v| v * 100.0),
+            };
+            meter.set_value(value);
+        }
+    };
+    update();
+    registry.subscribe(Box::new(move |_| update()))
+}
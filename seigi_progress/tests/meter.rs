@@ -0,0 +1,49 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_meter::{Kind, MeterOptions};
+use seigi_progress::Registry;
+use seigi_testkit::Fixture;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn meter_follows_summary() {
+    let fixture = Fixture::new(r#"<div id="progress"></div>"#);
+    let element = fixture.get("#progress");
+    let meter = seigi_meter::create_meter(
+        MeterOptions::builder()
+            .element(element.clone())
+            .kind(Kind::Progress)
+            .build(),
+    );
+    let registry = Registry::new();
+    let handle = seigi_progress::bind_meter(&registry, meter.clone());
+    // Nothing running is complete
+    assert_eq!(meter.value(), Some(100.0));
+
+    let a = registry.start("a");
+    assert_eq!(meter.value(), None);
+    assert_eq!(
+        element.get_attribute("data-state").as_deref(),
+        Some("indeterminate")
+    );
+
+    a.set_progress(Some(0.25));
+    assert_eq!(meter.value(), Some(25.0));
+    let b = registry.start("b");
+    b.set_progress(Some(0.75));
+    assert_eq!(meter.value(), Some(50.0));
+
+    a.finish();
+    b.finish();
+    assert_eq!(meter.value(), Some(100.0));
+    assert_eq!(
+        element.get_attribute("data-state").as_deref(),
+        Some("complete")
+    );
+
+    registry.unsubscribe(handle);
+    registry.start("c").set_progress(Some(0.5));
+    assert_eq!(meter.value(), Some(100.0));
+}
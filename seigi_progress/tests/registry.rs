@@ -0,0 +1,179 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::{cell::RefCell, rc::Rc};
+
+use seigi_progress::{Registry, Summary, TaskEvent, TaskStatus};
+
+/// Events recorded as their kind, label and status
+type Events = Rc<RefCell<Vec<(&'static str, String, TaskStatus)>>>;
+
+/// Subscribes to the registry, recording its events
+fn record(registry: &Registry) -> (u64, Events) {
+    let events = Rc::new(RefCell::new(vec![]));
+    let handle = registry.subscribe(Box::new({
+        let events = events.clone();
+        move |event| {
+            let kind = match event {
+                TaskEvent::Start(_) => "start",
+                TaskEvent::Update(_) => "update",
+                TaskEvent::Settle(_) => "settle",
+            };
+            let task = event.task();
+            events
+                .borrow_mut()
+                .push((kind, task.label.clone(), task.status.clone()));
+        }
+    }));
+    (handle, events)
+}
+
+#[test]
+fn started_tasks_are_listed() {
+    let registry = Registry::new();
+    let a = registry.start("a");
+    let b = registry.start("b");
+
+    assert_ne!(a.id(), b.id());
+    let tasks = registry.tasks();
+    assert_eq!(
+        tasks.iter().map(|v| v.label.as_str()).collect::<Vec<_>>(),
+        ["a", "b"]
+    );
+    assert_eq!(tasks[0].progress, None);
+    assert_eq!(tasks[0].status, TaskStatus::Running);
+    assert_eq!(registry.get(b.id()).map(|v| v.label), Some("b".into()));
+}
+
+#[test]
+fn progress_is_clamped() {
+    let registry = Registry::new();
+    let task = registry.start("upload");
+
+    task.set_progress(Some(1.5));
+    assert_eq!(registry.get(task.id()).unwrap().progress, Some(1.0));
+    task.set_progress(Some(-1.0));
+    assert_eq!(registry.get(task.id()).unwrap().progress, Some(0.0));
+    task.set_done(3, 4);
+    assert_eq!(registry.get(task.id()).unwrap().progress, Some(0.75));
+    // Nothing to do is indeterminate
+    task.set_done(0, 0);
+    assert_eq!(registry.get(task.id()).unwrap().progress, None);
+
+    task.set_message("3 of 4 files");
+    assert_eq!(
+        registry.get(task.id()).unwrap().message.as_deref(),
+        Some("3 of 4 files")
+    );
+}
+
+#[test]
+fn summary_averages_running_tasks() {
+    let registry = Registry::new();
+    assert_eq!(
+        registry.summary(),
+        Summary {
+            running: 0,
+            progress: None
+        }
+    );
+
+    let a = registry.start("a");
+    let b = registry.start("b");
+    a.set_progress(Some(0.2));
+    // An indeterminate task makes the summary indeterminate
+    assert_eq!(registry.summary().progress, None);
+
+    b.set_progress(Some(0.6));
+    let summary = registry.summary();
+    assert_eq!(summary.running, 2);
+    assert!((summary.progress.unwrap() - 0.4).abs() < 1e-9);
+
+    b.finish();
+    assert_eq!(
+        registry.summary(),
+        Summary {
+            running: 1,
+            progress: Some(0.2)
+        }
+    );
+}
+
+#[test]
+fn settled_tasks_are_removed() {
+    let registry = Registry::new();
+    let (_, events) = record(&registry);
+
+    let a = registry.start("a");
+    a.set_progress(Some(0.5));
+    a.finish();
+    registry.start("b").fail("offline");
+    drop(registry.start("c"));
+
+    assert!(registry.tasks().is_empty());
+    assert_eq!(
+        *events.borrow(),
+        [
+            ("start", "a".into(), TaskStatus::Running),
+            ("update", "a".into(), TaskStatus::Running),
+            ("settle", "a".into(), TaskStatus::Finished),
+            ("start", "b".into(), TaskStatus::Running),
+            ("settle", "b".into(), TaskStatus::Failed("offline".into())),
+            ("start", "c".into(), TaskStatus::Running),
+            ("settle", "c".into(), TaskStatus::Cancelled),
+        ]
+    );
+}
+
+#[test]
+fn finished_tasks_report_full_progress() {
+    let registry = Registry::new();
+    let settled = Rc::new(RefCell::new(None));
+    registry.subscribe(Box::new({
+        let settled = settled.clone();
+        move |event| {
+            if let TaskEvent::Settle(task) = event {
+                *settled.borrow_mut() = Some(task.progress);
+            }
+        }
+    }));
+
+    registry.start("a").finish();
+    assert_eq!(*settled.borrow(), Some(Some(1.0)));
+}
+
+#[test]
+fn unsubscribed_callbacks_are_not_called() {
+    let registry = Registry::new();
+    let (handle, events) = record(&registry);
+
+    registry.unsubscribe(handle);
+    registry.start("a").finish();
+    assert!(events.borrow().is_empty());
+}
+
+#[test]
+fn subscribers_can_read_registry() {
+    let registry = Registry::new();
+    let counts = Rc::new(RefCell::new(vec![]));
+    registry.subscribe(Box::new({
+        let (registry, counts) = (registry.clone(), counts.clone());
+        move |_| counts.borrow_mut().push(registry.summary().running)
+    }));
+
+    let task = registry.start("a");
+    drop(task);
+    assert_eq!(*counts.borrow(), [1, 0]);
+}
+
+#[test]
+fn global_registry_is_shared() {
+    let task = seigi_progress::start("global");
+    assert!(
+        seigi_progress::global()
+            .tasks()
+            .iter()
+            .any(|v| v.id == task.id())
+    );
+    drop(task);
+    assert!(seigi_progress::global().tasks().is_empty());
+}
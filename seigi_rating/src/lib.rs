@@ -0,0 +1,422 @@
+//! A rating input of items such as stars, with optional half steps
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::events::EventListener;
+use seigi_utils::{direction, i18n};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, HtmlInputElement, KeyboardEvent, MouseEvent, PointerEvent};
+
+pub type ChangeHook = Box<dyn Fn(f64)>;
+
+/// Hooks to [Rating]
+#[derive(Default)]
+pub struct RatingHooks {
+    /// Called with the value when it is changed by the user
+    pub change: Option<ChangeHook>,
+}
+
+/// Options of [Rating]
+pub struct RatingOptions {
+    /// The element containing items with `data-seigi-rating-item`, one for each point
+    pub container: HtmlElement,
+    /// The initial value, where 0 is no rating
+    pub value: f64,
+    /// Whether values can be halves, such as 3.5
+    pub allow_half: bool,
+    /// Whether choosing the current value again clears the rating
+    pub clearable: bool,
+    pub read_only: bool,
+    /// An input the value is mirrored to, so the rating takes part in form submission
+    pub input: Option<HtmlInputElement>,
+    pub hooks: RatingHooks,
+}
+
+impl RatingOptions {
+    pub fn builder() -> RatingOptionsBuilder {
+        RatingOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [RatingOptions]
+#[derive(Default)]
+pub struct RatingOptionsBuilder {
+    container: Option<HtmlElement>,
+    value: f64,
+    allow_half: bool,
+    clearable: bool,
+    read_only: bool,
+    input: Option<HtmlInputElement>,
+    hooks: RatingHooks,
+}
+
+impl RatingOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn container(mut self, container: HtmlElement) -> Self {
+        self.container = Some(container);
+        self
+    }
+
+    pub fn value(mut self, value: f64) -> Self {
+        self.value = value;
+        self
+    }
+
+    pub fn allow_half(mut self, allow_half: bool) -> Self {
+        self.allow_half = allow_half;
+        self
+    }
+
+    pub fn clearable(mut self, clearable: bool) -> Self {
+        self.clearable = clearable;
+        self
+    }
+
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub fn input(mut self, input: HtmlInputElement) -> Self {
+        self.input = Some(input);
+        self
+    }
+
+    pub fn hooks(mut self, hooks: RatingHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [RatingOptions]
+    ///
+    /// # Panics
+    /// This method panics if container field is not set
+    pub fn build(self) -> RatingOptions {
+        RatingOptions {
+            container: self
+                .container
+                .expect("container must be set to build RatingOptions"),
+            value: self.value,
+            allow_half: self.allow_half,
+            clearable: self.clearable,
+            read_only: self.read_only,
+            input: self.input,
+            hooks: self.hooks,
+        }
+    }
+}
+
+struct Inner {
+    options: Rc<RatingOptions>,
+    value: f64,
+    /// The value under the pointer, shown instead of the value while hovering
+    preview: Option<f64>,
+    read_only: bool,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn items(&self) -> Vec<HtmlElement> {
+        let Ok(nodes) = self
+            .options
+            .container
+            .query_selector_all("[data-seigi-rating-item]")
+        else {
+            return vec![];
+        };
+        (0..nodes.length())
+            .filter_map(|v| nodes.get(v))
+            .filter_map(|v| v.dyn_into::<HtmlElement>().ok())
+            .collect()
+    }
+
+    fn max(&self) -> f64 {
+        self.items().len() as f64
+    }
+
+    fn step(&self) -> f64 {
+        if self.options.allow_half { 0.5 } else { 1.0 }
+    }
+
+    /// Rounds the value to a step in bounds
+    fn snap(&self, value: f64) -> f64 {
+        let step = self.step();
+        ((value / step).round() * step).clamp(0.0, self.max())
+    }
+
+    fn is_rtl(&self) -> bool {
+        direction::is_rtl(&self.options.container)
+    }
+
+    /// Returns the value the pointer points, from the item under it and the side of the item
+    fn value_at(&self, event: &MouseEvent) -> Option<f64> {
+        let target = event.target()?.dyn_into::<Element>().ok()?;
+        let item = target.closest("[data-seigi-rating-item]").ok()??;
+        let index = self.items().iter().position(|v| **v == item)?;
+        if !self.options.allow_half {
+            return Some(index as f64 + 1.0);
+        }
+
+        let rect = item.get_bounding_client_rect();
+        let mut fraction = (event.client_x() as f64 - rect.left()) / rect.width().max(1.0);
+        if self.is_rtl() {
+            fraction = 1.0 - fraction;
+        }
+        Some(index as f64 + if fraction < 0.5 { 0.5 } else { 1.0 })
+    }
+
+    fn render(&self) {
+        let container = &self.options.container;
+        let max = self.max();
+        let shown = self.preview.unwrap_or(self.value);
+        for (index, item) in self.items().iter().enumerate() {
+            let state = if shown >= index as f64 + 1.0 {
+                "full"
+            } else if shown >= index as f64 + 0.5 {
+                "half"
+            } else {
+                "empty"
+            };
+            let _ = item.set_attribute("data-state", state);
+        }
+
+        let _ = container.set_attribute("aria-valuemax", &max.to_string());
+        let _ = container.set_attribute("aria-valuenow", &self.value.to_string());
+        let _ = container.set_attribute(
+            "aria-valuetext",
+            &i18n::translate("rating.value", &[("value", &self.value), ("max", &max)]),
+        );
+        let percentage = if max > 0.0 { shown / max * 100.0 } else { 0.0 };
+        let _ = container
+            .style()
+            .set_property("--seigi-rating-percentage", &format!("{percentage}%"));
+        if self.preview.is_some() {
+            let _ = container.set_attribute("data-hovering", "");
+        } else {
+            let _ = container.remove_attribute("data-hovering");
+        }
+        if self.read_only {
+            let _ = container.set_attribute("aria-readonly", "true");
+            let _ = container.set_attribute("data-readonly", "");
+        } else {
+            let _ = container.remove_attribute("aria-readonly");
+            let _ = container.remove_attribute("data-readonly");
+        }
+
+        if let Some(input) = &self.options.input {
+            input.set_value(&self.value.to_string());
+        }
+    }
+}
+
+/// An instance of rating
+///
+/// Items are chosen by pointer, previewing the value under the pointer while hovering, or by
+/// keyboard on the focused container: arrow keys step by a point or a half, following the
+/// direction in right-to-left, Home and End go to the ends and digits set whole values.
+///
+/// The container is exposed as a slider, as half values don't fit a group of radios.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-state** is set in items to `full`, `half` or `empty`, following the preview while
+/// hovering
+///
+/// **data-hovering** is set in the container while the pointer previews a value
+///
+/// **data-readonly** is set in the container while the rating is read only
+///
+/// # CSS Variables
+/// **--seigi-rating-percentage** is set in the container to the shown value over the count of
+/// items, for a single clipped fill instead of states of items
+#[derive(Clone)]
+pub struct Rating(Rc<Mutex<Inner>>);
+
+impl Rating {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    pub fn value(&self) -> f64 {
+        self.0.lock().unwrap().value
+    }
+
+    /// Sets the value, rounded to a step in bounds, without calling the change hook
+    pub fn set_value(&self, value: f64) {
+        let mut inner = self.0.lock().unwrap();
+        inner.value = inner.snap(value);
+        inner.render();
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.0.lock().unwrap().read_only
+    }
+
+    pub fn set_read_only(&self, read_only: bool) {
+        let mut inner = self.0.lock().unwrap();
+        inner.read_only = read_only;
+        inner.preview = None;
+        inner.render();
+    }
+
+    /// Rerenders items, after they are added or removed
+    pub fn refresh(&self) {
+        let mut inner = self.0.lock().unwrap();
+        inner.value = inner.snap(inner.value);
+        inner.render();
+    }
+
+    /// Changes the value by the user, calling the change hook
+    fn change(&self, value: f64) {
+        let (options, value) = {
+            let mut inner = self.0.lock().unwrap();
+            let value = inner.snap(value);
+            inner.preview = None;
+            if inner.value == value {
+                inner.render();
+                return;
+            }
+            inner.value = value;
+            inner.render();
+            (inner.options.clone(), value)
+        };
+
+        if let Some(hook) = &options.hooks.change {
+            hook(value);
+        }
+    }
+
+    fn handle_pointer_move(&self, event: &PointerEvent) {
+        let mut inner = self.0.lock().unwrap();
+        if inner.read_only || event.pointer_type() == "touch" {
+            return;
+        }
+        let preview = inner.value_at(event);
+        if inner.preview != preview {
+            inner.preview = preview;
+            inner.render();
+        }
+    }
+
+    fn handle_click(&self, event: &MouseEvent) {
+        let value = {
+            let inner = self.0.lock().unwrap();
+            if inner.read_only {
+                return;
+            }
+            let Some(value) = inner.value_at(event) else {
+                return;
+            };
+            if inner.options.clearable && value == inner.value {
+                0.0
+            } else {
+                value
+            }
+        };
+        self.change(value);
+    }
+
+    fn handle_key_down(&self, event: &KeyboardEvent) {
+        let value = {
+            let inner = self.0.lock().unwrap();
+            if inner.read_only {
+                return;
+            }
+            let (step, value, max) = (inner.step(), inner.value, inner.max());
+            let (decrease, increase) = if inner.is_rtl() {
+                ("ArrowRight", "ArrowLeft")
+            } else {
+                ("ArrowLeft", "ArrowRight")
+            };
+            let key = event.key();
+            match key.as_str() {
+                "ArrowUp" => value + step,
+                "ArrowDown" => value - step,
+                v if v == increase => value + step,
+                v if v == decrease => value - step,
+                "Home" => 0.0,
+                "End" => max,
+                v => match v.parse::<u8>() {
+                    Ok(v) if v as f64 <= max => v as f64,
+                    _ => return,
+                },
+            }
+        };
+        event.prevent_default();
+        self.change(value);
+    }
+}
+
+pub fn create_rating(options: RatingOptions) -> Rating {
+    let container = options.container.clone();
+    let _ = container.set_attribute("role", "slider");
+    let _ = container.set_attribute("aria-valuemin", "0");
+    if !container.has_attribute("tabindex") {
+        container.set_tab_index(0);
+    }
+
+    let read_only = options.read_only;
+    let rating = Rating(Rc::new(Mutex::new(Inner {
+        value: options.value,
+        options: Rc::new(options),
+        preview: None,
+        read_only,
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&rating.0);
+    let pointer = |event_type: &'static str, f: fn(&Rating, &PointerEvent)| {
+        let weak = weak.clone();
+        EventListener::new(&container, event_type, move |event| {
+            let (Some(rating), Some(event)) =
+                (Rating::from_weak(&weak), event.dyn_ref::<PointerEvent>())
+            else {
+                return;
+            };
+            f(&rating, event);
+        })
+    };
+    let listeners = vec![
+        pointer("pointermove", Rating::handle_pointer_move),
+        pointer("pointerleave", |rating, _| {
+            let mut inner = rating.0.lock().unwrap();
+            if inner.preview.take().is_some() {
+                inner.render();
+            }
+        }),
+        EventListener::new(&container, "click", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(rating), Some(event)) =
+                    (Rating::from_weak(&weak), event.dyn_ref::<MouseEvent>())
+                else {
+                    return;
+                };
+                rating.handle_click(event);
+            }
+        }),
+        EventListener::new(&container, "keydown", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(rating), Some(event)) =
+                    (Rating::from_weak(&weak), event.dyn_ref::<KeyboardEvent>())
+                else {
+                    return;
+                };
+                rating.handle_key_down(event);
+            }
+        }),
+    ];
+
+    rating.0.lock().unwrap()._listeners = listeners;
+    rating.refresh();
+    rating
+}
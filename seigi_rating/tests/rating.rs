@@ -0,0 +1,267 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use seigi_rating::{Rating, RatingHooks, RatingOptions, RatingOptionsBuilder};
+use seigi_testkit::{
+    Fixture,
+    events::{self, Modifiers},
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::HtmlInputElement;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<div id="rating">
+    <span data-seigi-rating-item style="display: inline-block; width: 20px; height: 20px"></span>
+    <span data-seigi-rating-item style="display: inline-block; width: 20px; height: 20px"></span>
+    <span data-seigi-rating-item style="display: inline-block; width: 20px; height: 20px"></span>
+    <span data-seigi-rating-item style="display: inline-block; width: 20px; height: 20px"></span>
+    <span data-seigi-rating-item style="display: inline-block; width: 20px; height: 20px"></span>
+</div>
+<input id="input" type="hidden">
+"#;
+
+fn options(fixture: &Fixture) -> RatingOptionsBuilder {
+    RatingOptions::builder().container(fixture.get("#rating"))
+}
+
+fn rating(options: RatingOptionsBuilder) -> (Rating, Rc<RefCell<Vec<f64>>>) {
+    let changes = Rc::new(RefCell::new(vec![]));
+    let rating = seigi_rating::create_rating(
+        options
+            .hooks(RatingHooks {
+                change: Some(Box::new({
+                    let changes = changes.clone();
+                    move |value| changes.borrow_mut().push(value)
+                })),
+            })
+            .build(),
+    );
+    (rating, changes)
+}
+
+fn states(fixture: &Fixture) -> Vec<String> {
+    fixture
+        .get_all("[data-seigi-rating-item]")
+        .iter()
+        .map(|v| v.get_attribute("data-state").unwrap_or_default())
+        .collect()
+}
+
+fn item(fixture: &Fixture, index: usize) -> web_sys::HtmlElement {
+    fixture.get_all("[data-seigi-rating-item]")[index].clone()
+}
+
+/// Clicks the item at the fraction of its width
+fn click(fixture: &Fixture, index: usize, fraction: f64) {
+    let item = item(fixture, index);
+    let rect = item.get_bounding_client_rect();
+    events::dispatch(
+        &item,
+        &events::mouse_event(
+            "click",
+            rect.left() + rect.width() * fraction,
+            rect.top() + 1.0,
+        ),
+    );
+}
+
+fn press(fixture: &Fixture, key: &str) -> bool {
+    events::key_down(&fixture.get("#rating"), key, Modifiers::default())
+}
+
+#[wasm_bindgen_test]
+fn container_is_described_as_slider() {
+    let fixture = Fixture::new(MARKUP);
+    let input = fixture.get("#input").unchecked_into::<HtmlInputElement>();
+    rating(options(&fixture).value(3.0).input(input.clone()));
+
+    let container = fixture.get("#rating");
+    assert_eq!(container.get_attribute("role").as_deref(), Some("slider"));
+    assert_eq!(container.tab_index(), 0);
+    assert_eq!(
+        container.get_attribute("aria-valuemin").as_deref(),
+        Some("0")
+    );
+    assert_eq!(
+        container.get_attribute("aria-valuemax").as_deref(),
+        Some("5")
+    );
+    assert_eq!(
+        container.get_attribute("aria-valuenow").as_deref(),
+        Some("3")
+    );
+    assert_eq!(
+        container.get_attribute("aria-valuetext").as_deref(),
+        Some("3 of 5")
+    );
+    assert_eq!(
+        container
+            .style()
+            .get_property_value("--seigi-rating-percentage")
+            .unwrap(),
+        "60%"
+    );
+    assert_eq!(states(&fixture), ["full", "full", "full", "empty", "empty"]);
+    assert_eq!(input.value(), "3");
+}
+
+#[wasm_bindgen_test]
+fn clicking_items_changes_value() {
+    let fixture = Fixture::new(MARKUP);
+    let (rating, changes) = rating(options(&fixture));
+
+    click(&fixture, 3, 0.5);
+    assert_eq!(rating.value(), 4.0);
+    // Choosing the value again keeps it unless clearable
+    click(&fixture, 3, 0.5);
+    assert_eq!(rating.value(), 4.0);
+    assert_eq!(*changes.borrow(), [4.0]);
+}
+
+#[wasm_bindgen_test]
+fn clearable_rating_clears_on_same_value() {
+    let fixture = Fixture::new(MARKUP);
+    let (rating, changes) = rating(options(&fixture).value(2.0).clearable(true));
+
+    click(&fixture, 1, 0.5);
+    assert_eq!(rating.value(), 0.0);
+    assert_eq!(states(&fixture), ["empty"; 5]);
+    assert_eq!(*changes.borrow(), [0.0]);
+}
+
+#[wasm_bindgen_test]
+fn halves_follow_side_of_item() {
+    let fixture = Fixture::new(MARKUP);
+    let (rating, _) = rating(options(&fixture).allow_half(true));
+
+    click(&fixture, 1, 0.25);
+    assert_eq!(rating.value(), 1.5);
+    assert_eq!(
+        states(&fixture),
+        ["full", "half", "empty", "empty", "empty"]
+    );
+    click(&fixture, 1, 0.75);
+    assert_eq!(rating.value(), 2.0);
+
+    // Right-to-left starts items from the right
+    fixture.get("#rating").set_attribute("dir", "rtl").unwrap();
+    click(&fixture, 3, 0.75);
+    assert_eq!(rating.value(), 3.5);
+}
+
+#[wasm_bindgen_test]
+fn hovering_previews_value() {
+    let fixture = Fixture::new(MARKUP);
+    let (rating, changes) = rating(options(&fixture).value(1.0));
+    let container = fixture.get("#rating");
+
+    let target = item(&fixture, 3);
+    let rect = target.get_bounding_client_rect();
+    events::dispatch(
+        &target,
+        &events::pointer_event("pointermove", rect.left() + 10.0, rect.top() + 1.0),
+    );
+    assert!(container.has_attribute("data-hovering"));
+    assert_eq!(states(&fixture), ["full", "full", "full", "full", "empty"]);
+    assert_eq!(
+        container.get_attribute("aria-valuenow").as_deref(),
+        Some("1")
+    );
+
+    events::dispatch(&container, &events::pointer_event("pointerleave", 0.0, 0.0));
+    assert!(!container.has_attribute("data-hovering"));
+    assert_eq!(
+        states(&fixture),
+        ["full", "empty", "empty", "empty", "empty"]
+    );
+    assert_eq!(rating.value(), 1.0);
+    assert!(changes.borrow().is_empty());
+}
+
+#[wasm_bindgen_test]
+fn keys_step_through_values() {
+    let fixture = Fixture::new(MARKUP);
+    let (rating, changes) = rating(options(&fixture).value(2.0));
+
+    assert!(!press(&fixture, "ArrowRight"));
+    assert_eq!(rating.value(), 3.0);
+    press(&fixture, "ArrowDown");
+    assert_eq!(rating.value(), 2.0);
+    press(&fixture, "End");
+    assert_eq!(rating.value(), 5.0);
+    // Values stay in bounds
+    press(&fixture, "ArrowUp");
+    assert_eq!(rating.value(), 5.0);
+    press(&fixture, "Home");
+    assert_eq!(rating.value(), 0.0);
+    press(&fixture, "4");
+    assert_eq!(rating.value(), 4.0);
+    // Digits past the count of items are left alone
+    assert!(press(&fixture, "9"));
+    assert_eq!(*changes.borrow(), [3.0, 2.0, 5.0, 0.0, 4.0]);
+}
+
+#[wasm_bindgen_test]
+fn keys_step_by_halves_and_follow_direction() {
+    let fixture = Fixture::new(MARKUP);
+    let (rating, _) = rating(options(&fixture).value(2.0).allow_half(true));
+
+    press(&fixture, "ArrowRight");
+    assert_eq!(rating.value(), 2.5);
+    fixture.get("#rating").set_attribute("dir", "rtl").unwrap();
+    press(&fixture, "ArrowRight");
+    assert_eq!(rating.value(), 2.0);
+    press(&fixture, "ArrowLeft");
+    assert_eq!(rating.value(), 2.5);
+}
+
+#[wasm_bindgen_test]
+fn read_only_rating_ignores_input() {
+    let fixture = Fixture::new(MARKUP);
+    let (rating, changes) = rating(options(&fixture).value(2.0).read_only(true));
+    let container = fixture.get("#rating");
+    assert!(rating.is_read_only());
+    assert_eq!(
+        container.get_attribute("aria-readonly").as_deref(),
+        Some("true")
+    );
+    assert!(container.has_attribute("data-readonly"));
+
+    click(&fixture, 4, 0.5);
+    assert!(press(&fixture, "End"));
+    assert_eq!(rating.value(), 2.0);
+    assert!(changes.borrow().is_empty());
+
+    rating.set_read_only(false);
+    assert!(!container.has_attribute("data-readonly"));
+    click(&fixture, 4, 0.5);
+    assert_eq!(rating.value(), 5.0);
+}
+
+#[wasm_bindgen_test]
+fn set_value_snaps_without_hook() {
+    let fixture = Fixture::new(MARKUP);
+    let (rating, changes) = rating(options(&fixture));
+
+    rating.set_value(2.3);
+    assert_eq!(rating.value(), 2.0);
+    rating.set_value(7.0);
+    assert_eq!(rating.value(), 5.0);
+    assert!(changes.borrow().is_empty());
+
+    // Removing items clamps the value to the new count
+    item(&fixture, 4).remove();
+    rating.refresh();
+    assert_eq!(rating.value(), 4.0);
+    assert_eq!(
+        fixture
+            .get("#rating")
+            .get_attribute("aria-valuemax")
+            .as_deref(),
+        Some("4")
+    );
+}
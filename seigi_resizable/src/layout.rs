@@ -0,0 +1,161 @@
+//! Sizes of panels in percentages and resizing them within constraints
+
+/// Size constraints of a panel in percentages of the group
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Constraints {
+    pub default_size: Option<f64>,
+    pub min_size: f64,
+    pub max_size: f64,
+    /// Whether the panel collapses when it is resized past half of its minimum size
+    pub collapsible: bool,
+    pub collapsed_size: f64,
+}
+
+impl Default for Constraints {
+    fn default() -> Self {
+        Self {
+            default_size: None,
+            min_size: 0.0,
+            max_size: 100.0,
+            collapsible: false,
+            collapsed_size: 0.0,
+        }
+    }
+}
+
+impl Constraints {
+    pub fn is_collapsed(&self, size: f64) -> bool {
+        self.collapsible && (size - self.collapsed_size).abs() < 0.01 && size < self.min_size
+    }
+}
+
+/// Returns the initial layout from default sizes, splitting the rest evenly between panels
+/// without one within their constraints
+pub fn initial(constraints: &[Constraints]) -> Vec<f64> {
+    let assigned = constraints
+        .iter()
+        .filter_map(|v| v.default_size)
+        .sum::<f64>();
+    let unassigned = constraints
+        .iter()
+        .filter(|v| v.default_size.is_none())
+        .count();
+    let share = if unassigned > 0 {
+        (100.0 - assigned).max(0.0) / unassigned as f64
+    } else {
+        0.0
+    };
+    let mut layout = constraints
+        .iter()
+        .map(|v| {
+            v.default_size
+                .unwrap_or(share)
+                .clamp(v.min_size, v.max_size)
+        })
+        .collect::<Vec<_>>();
+
+    // Clamped sizes leave the total off 100, which is made up by panels with room left. Each
+    // round either makes it up or clamps another panel
+    for _ in 0..layout.len() {
+        let rest = 100.0 - layout.iter().sum::<f64>();
+        if rest.abs() < 1e-9 {
+            break;
+        }
+        let open = (0..layout.len())
+            .filter(|&v| {
+                if rest > 0.0 {
+                    layout[v] < constraints[v].max_size
+                } else {
+                    layout[v] > constraints[v].min_size
+                }
+            })
+            .collect::<Vec<_>>();
+        if open.is_empty() {
+            break;
+        }
+        let share = rest / open.len() as f64;
+        for index in open {
+            let c = constraints[index];
+            layout[index] = (layout[index] + share).clamp(c.min_size, c.max_size);
+        }
+    }
+    // Constraints that can't sum to 100 are scaled
+    normalize(layout)
+}
+
+/// Scales the layout so it sums to 100
+pub fn normalize(layout: Vec<f64>) -> Vec<f64> {
+    let total = layout.iter().sum::<f64>();
+    if total <= 0.0 {
+        return layout;
+    }
+    layout.into_iter().map(|v| v * 100.0 / total).collect()
+}
+
+/// Moves the handle after the panel at the index by the delta in percentages
+///
+/// The panel on the side the handle moves away from grows, and panels on the other side shrink
+/// starting from the nearest one, each down to its minimum or collapsed size.
+pub fn resize(layout: &[f64], constraints: &[Constraints], handle: usize, delta: f64) -> Vec<f64> {
+    if delta == 0.0 || handle + 1 >= layout.len() {
+        return layout.to_vec();
+    }
+
+    let (grow, shrinking): (usize, Vec<usize>) = if delta > 0.0 {
+        (handle, (handle + 1..layout.len()).collect())
+    } else {
+        (handle + 1, (0..=handle).rev().collect())
+    };
+
+    let grow_constraints = constraints[grow];
+    let mut wanted = delta.abs().min(grow_constraints.max_size - layout[grow]);
+    if layout[grow] < grow_constraints.min_size {
+        // A collapsed panel expands only past half of its minimum, and to the minimum at once
+        let needed = grow_constraints.min_size - layout[grow];
+        if wanted < needed / 2.0 {
+            return layout.to_vec();
+        }
+        wanted = wanted.max(needed);
+    }
+    if wanted <= 0.0 {
+        return layout.to_vec();
+    }
+
+    let mut next = layout.to_vec();
+    let mut taken = 0.0;
+    for index in shrinking {
+        let remaining = wanted - taken;
+        if remaining <= 0.0 {
+            break;
+        }
+        let c = constraints[index];
+        let size = next[index];
+        let target = size - remaining;
+        if target >= c.min_size {
+            next[index] = target;
+            taken += remaining;
+            break;
+        }
+
+        let room = (size - c.min_size).max(0.0);
+        // Collapsing frees more than is asked for, which the growing panel has to take
+        let collapses = c.collapsible
+            && target < c.min_size / 2.0
+            && layout[grow] + taken + (size - c.collapsed_size) <= grow_constraints.max_size;
+        if collapses {
+            next[index] = c.collapsed_size;
+            taken += size - c.collapsed_size;
+        } else {
+            next[index] = size - room;
+            taken += room;
+        }
+    }
+
+    // Nothing to take was enough to expand a collapsed panel to its minimum
+    if layout[grow] < grow_constraints.min_size && layout[grow] + taken < grow_constraints.min_size
+    {
+        return layout.to_vec();
+    }
+    next[grow] = layout[grow] + taken;
+    normalize(next)
+}
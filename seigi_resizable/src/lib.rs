@@ -0,0 +1,603 @@
+//! Groups of panels resized by dragging or focusing handles between them
+
+pub mod layout;
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::events::EventListener;
+use seigi_utils::{
+    id,
+    persist::{Persisted, PersistedOptions, Value, create_persisted},
+};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, KeyboardEvent, PointerEvent};
+
+pub use layout::Constraints;
+
+/// The direction panels are laid out in
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+
+pub type LayoutHook = Box<dyn Fn(&[f64])>;
+
+/// Hooks to [PanelGroup]
+#[derive(Default)]
+pub struct PanelGroupHooks {
+    /// Called with sizes of panels in percentages when they change
+    pub layout: Option<LayoutHook>,
+}
+
+/// Options of [PanelGroup]
+pub struct PanelGroupOptions {
+    /// The element containing panels and handles as its children
+    pub group: HtmlElement,
+    pub orientation: Orientation,
+    /// The key of local storage the layout is saved to and restored from
+    pub storage_key: Option<String>,
+    /// The percentage arrow keys move a handle by
+    pub keyboard_step: f64,
+    pub hooks: PanelGroupHooks,
+}
+
+impl PanelGroupOptions {
+    pub fn builder() -> PanelGroupOptionsBuilder {
+        PanelGroupOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [PanelGroupOptions]
+pub struct PanelGroupOptionsBuilder {
+    group: Option<HtmlElement>,
+    orientation: Orientation,
+    storage_key: Option<String>,
+    keyboard_step: f64,
+    hooks: PanelGroupHooks,
+}
+
+impl Default for PanelGroupOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            group: None,
+            orientation: Orientation::default(),
+            storage_key: None,
+            keyboard_step: 5.0,
+            hooks: PanelGroupHooks::default(),
+        }
+    }
+}
+
+impl PanelGroupOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn group(mut self, group: HtmlElement) -> Self {
+        self.group = Some(group);
+        self
+    }
+
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn storage_key(mut self, storage_key: impl Into<String>) -> Self {
+        self.storage_key = Some(storage_key.into());
+        self
+    }
+
+    pub fn keyboard_step(mut self, keyboard_step: f64) -> Self {
+        self.keyboard_step = keyboard_step;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: PanelGroupHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [PanelGroupOptions]
+    ///
+    /// # Panics
+    /// This method panics if group field is not set
+    pub fn build(self) -> PanelGroupOptions {
+        PanelGroupOptions {
+            group: self
+                .group
+                .expect("group must be set to build PanelGroupOptions"),
+            orientation: self.orientation,
+            storage_key: self.storage_key,
+            keyboard_step: self.keyboard_step,
+            hooks: self.hooks,
+        }
+    }
+}
+
+fn children(group: &Element, selector: &str) -> Vec<HtmlElement> {
+    let Ok(nodes) = group.query_selector_all(&format!(":scope > {selector}")) else {
+        return vec![];
+    };
+    (0..nodes.length())
+        .filter_map(|v| nodes.get(v))
+        .filter_map(|v| v.dyn_into::<HtmlElement>().ok())
+        .collect()
+}
+
+/// Reads constraints of the panel from its attributes
+fn constraints_of(panel: &Element) -> Constraints {
+    let number = |name: &str| {
+        panel
+            .get_attribute(name)
+            .and_then(|v| v.trim().trim_end_matches('%').parse::<f64>().ok())
+    };
+    let defaults = Constraints::default();
+    Constraints {
+        default_size: number("data-default-size"),
+        min_size: number("data-min-size").unwrap_or(defaults.min_size),
+        max_size: number("data-max-size").unwrap_or(defaults.max_size),
+        collapsible: panel.has_attribute("data-collapsible"),
+        collapsed_size: number("data-collapsed-size").unwrap_or(defaults.collapsed_size),
+    }
+}
+
+struct Drag {
+    handle: usize,
+    pointer_id: i32,
+    origin: f64,
+    layout: Vec<f64>,
+}
+
+struct Inner {
+    options: Rc<PanelGroupOptions>,
+    constraints: Vec<Constraints>,
+    layout: Vec<f64>,
+    /// Sizes of panels before they collapsed, to expand them back to
+    expanded: Vec<Option<f64>>,
+    drag: Option<Drag>,
+    storage: Option<Persisted<Vec<f64>>>,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn panels(&self) -> Vec<HtmlElement> {
+        children(&self.options.group, "[data-seigi-panel]")
+    }
+
+    fn handles(&self) -> Vec<HtmlElement> {
+        children(&self.options.group, "[data-seigi-panel-handle]")
+    }
+
+    /// Returns the size in pixels panels share, excluding handles
+    fn panels_size(&self) -> f64 {
+        self.panels()
+            .iter()
+            .map(|v| {
+                let rect = v.get_bounding_client_rect();
+                match self.options.orientation {
+                    Orientation::Horizontal => rect.width(),
+                    Orientation::Vertical => rect.height(),
+                }
+            })
+            .sum()
+    }
+
+    fn load(&self) -> Option<Vec<f64>> {
+        let layout = self.storage.as_ref()?.get();
+        (layout.len() == self.constraints.len()).then_some(layout)
+    }
+
+    fn save(&self) {
+        if let Some(storage) = &self.storage {
+            storage.set(
+                self.layout
+                    .iter()
+                    .map(|v| (v * 1000.0).round() / 1000.0)
+                    .collect(),
+            );
+        }
+    }
+
+    /// Sets the layout, remembering sizes of panels that collapse
+    ///
+    /// # Returns
+    /// Whether the layout changed
+    fn set_layout(&mut self, layout: Vec<f64>) -> bool {
+        if layout.len() != self.layout.len()
+            || layout
+                .iter()
+                .zip(&self.layout)
+                .all(|(a, b)| (a - b).abs() < 0.001)
+        {
+            return false;
+        }
+        for (index, c) in self.constraints.iter().enumerate() {
+            if c.is_collapsed(layout[index]) && !c.is_collapsed(self.layout[index]) {
+                self.expanded[index] = Some(self.layout[index]);
+            }
+        }
+        self.layout = layout;
+        self.reflect();
+        true
+    }
+
+    fn reflect(&self) {
+        for (index, panel) in self.panels().iter().enumerate() {
+            let Some(size) = self.layout.get(index) else {
+                continue;
+            };
+            let style = panel.style();
+            let _ = style.set_property("flex", &format!("{size} 1 0px"));
+            let _ = style.set_property("--seigi-panel-size", &format!("{size}%"));
+            if self.constraints[index].is_collapsed(*size) {
+                let _ = panel.set_attribute("data-collapsed", "");
+            } else {
+                let _ = panel.remove_attribute("data-collapsed");
+            }
+        }
+        for (index, handle) in self.handles().iter().enumerate() {
+            let (Some(size), Some(c)) = (self.layout.get(index), self.constraints.get(index))
+            else {
+                continue;
+            };
+            let _ = handle.set_attribute("aria-valuenow", &format!("{}", size.round()));
+            let _ = handle.set_attribute("aria-valuemin", &format!("{}", c.min_size.round()));
+            let _ = handle.set_attribute("aria-valuemax", &format!("{}", c.max_size.round()));
+        }
+    }
+}
+
+/// An instance of panel group
+///
+/// Children of the group with `data-seigi-panel` are panels, and children with
+/// `data-seigi-panel-handle` between them are handles. Sizes of panels are percentages of the
+/// group, applied as `flex` of panels. A group can be nested in a panel of another group.
+///
+/// Constraints of panels are read from their attributes, in percentages:
+/// `data-default-size`, `data-min-size`, `data-max-size`, `data-collapsible` and
+/// `data-collapsed-size`. Panels without a default size share the rest evenly.
+///
+/// Handles are dragged with a pointer, or focused and moved with arrow keys, Home and End. Enter
+/// collapses the panel before a handle or expands it back.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-orientation** is set in the group and handles to `horizontal` or `vertical`
+///
+/// **data-resizing** is set in the group and the handle while a handle is dragged
+///
+/// **data-collapsed** is set in collapsed panels
+///
+/// Handles are given `role="separator"` with **aria-valuenow** of the size of the panel before
+///
+/// # CSS Variables
+/// **--seigi-panel-size** is set in panels to their size in percentages
+#[derive(Clone)]
+pub struct PanelGroup(Rc<Mutex<Inner>>);
+
+impl PanelGroup {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    /// Returns sizes of panels in percentages
+    pub fn layout(&self) -> Vec<f64> {
+        self.0.lock().unwrap().layout.clone()
+    }
+
+    /// Sets sizes of panels in percentages, which are scaled to sum to 100
+    pub fn set_layout(&self, layout: Vec<f64>) {
+        self.commit(layout::normalize(layout));
+    }
+
+    pub fn is_collapsed(&self, panel: usize) -> bool {
+        let inner = self.0.lock().unwrap();
+        inner
+            .constraints
+            .get(panel)
+            .zip(inner.layout.get(panel))
+            .is_some_and(|(c, size)| c.is_collapsed(*size))
+    }
+
+    /// Collapses the collapsible panel, giving its size to the neighbor
+    pub fn collapse(&self, panel: usize) {
+        let layout = {
+            let inner = self.0.lock().unwrap();
+            let Some(c) = inner.constraints.get(panel).filter(|v| v.collapsible) else {
+                return;
+            };
+            let delta = inner.layout[panel] - c.collapsed_size;
+            Self::resize_panel(&inner, panel, -delta)
+        };
+        self.commit(layout);
+    }
+
+    /// Expands the collapsed panel back to its size before collapsing
+    pub fn expand(&self, panel: usize) {
+        let layout = {
+            let inner = self.0.lock().unwrap();
+            let Some(c) = inner.constraints.get(panel) else {
+                return;
+            };
+            if !c.is_collapsed(inner.layout[panel]) {
+                return;
+            }
+            let size = inner.expanded[panel].unwrap_or(c.min_size).max(c.min_size);
+            Self::resize_panel(&inner, panel, size - inner.layout[panel])
+        };
+        self.commit(layout);
+    }
+
+    /// Rereads panels and their constraints, resetting the layout if the count changed
+    pub fn refresh(&self) {
+        let mut inner = self.0.lock().unwrap();
+        let orientation = match inner.options.orientation {
+            Orientation::Horizontal => "horizontal",
+            Orientation::Vertical => "vertical",
+        };
+        let _ = inner
+            .options
+            .group
+            .set_attribute("data-orientation", orientation);
+
+        let panels = inner.panels();
+        for panel in &panels {
+            id::ensure(panel, "panel");
+        }
+        for (index, handle) in inner.handles().iter().enumerate() {
+            let _ = handle.set_attribute("role", "separator");
+            let _ = handle.set_attribute("data-orientation", orientation);
+            // A separator is oriented across the panels it separates
+            let _ = handle.set_attribute(
+                "aria-orientation",
+                match inner.options.orientation {
+                    Orientation::Horizontal => "vertical",
+                    Orientation::Vertical => "horizontal",
+                },
+            );
+            if let Some(panel) = panels.get(index) {
+                let _ = handle.set_attribute("aria-controls", &panel.id());
+            }
+            if !handle.has_attribute("tabindex") {
+                handle.set_tab_index(0);
+            }
+        }
+
+        inner.constraints = panels.iter().map(|v| constraints_of(v)).collect();
+        if inner.layout.len() != panels.len() {
+            inner.layout = inner
+                .load()
+                .unwrap_or_else(|| layout::initial(&inner.constraints));
+            inner.expanded = vec![None; panels.len()];
+        }
+        inner.reflect();
+    }
+
+    /// Returns the layout with the panel resized by the delta, moving the handle after it or,
+    /// for the last panel, the handle before it
+    fn resize_panel(inner: &Inner, panel: usize, delta: f64) -> Vec<f64> {
+        if panel + 1 < inner.layout.len() {
+            layout::resize(&inner.layout, &inner.constraints, panel, delta)
+        } else if panel > 0 {
+            layout::resize(&inner.layout, &inner.constraints, panel - 1, -delta)
+        } else {
+            inner.layout.clone()
+        }
+    }
+
+    fn commit(&self, layout: Vec<f64>) {
+        let (options, layout) = {
+            let mut inner = self.0.lock().unwrap();
+            if !inner.set_layout(layout) {
+                return;
+            }
+            if inner.drag.is_none() {
+                inner.save();
+            }
+            (inner.options.clone(), inner.layout.clone())
+        };
+
+        if let Some(hook) = &options.hooks.layout {
+            hook(&layout);
+        }
+    }
+
+    fn handle_index(&self, target: &Element) -> Option<(usize, HtmlElement)> {
+        let handle = target.closest("[data-seigi-panel-handle]").ok().flatten()?;
+        self.0
+            .lock()
+            .unwrap()
+            .handles()
+            .into_iter()
+            .enumerate()
+            .find(|(_, v)| **v == handle)
+    }
+
+    fn position(&self, event: &PointerEvent) -> f64 {
+        match self.0.lock().unwrap().options.orientation {
+            Orientation::Horizontal => event.client_x() as f64,
+            Orientation::Vertical => event.client_y() as f64,
+        }
+    }
+
+    fn handle_pointer_down(&self, event: &PointerEvent) {
+        if event.button() != 0 {
+            return;
+        }
+        let Some((index, handle)) = event
+            .target()
+            .and_then(|v| v.dyn_into::<Element>().ok())
+            .and_then(|v| self.handle_index(&v))
+        else {
+            return;
+        };
+
+        // Text selection would follow the pointer otherwise
+        event.prevent_default();
+        let origin = self.position(event);
+        let mut inner = self.0.lock().unwrap();
+        let _ = handle.set_pointer_capture(event.pointer_id());
+        let _ = handle.set_attribute("data-resizing", "");
+        let _ = inner.options.group.set_attribute("data-resizing", "");
+        inner.drag = Some(Drag {
+            handle: index,
+            pointer_id: event.pointer_id(),
+            origin,
+            layout: inner.layout.clone(),
+        });
+    }
+
+    fn handle_pointer_move(&self, event: &PointerEvent) {
+        let position = self.position(event);
+        let layout = {
+            let inner = self.0.lock().unwrap();
+            let Some(drag) = inner
+                .drag
+                .as_ref()
+                .filter(|v| v.pointer_id == event.pointer_id())
+            else {
+                return;
+            };
+            let size = inner.panels_size();
+            if size <= 0.0 {
+                return;
+            }
+            let delta = (position - drag.origin) / size * 100.0;
+            layout::resize(&drag.layout, &inner.constraints, drag.handle, delta)
+        };
+        self.commit(layout);
+    }
+
+    fn handle_pointer_up(&self, event: &PointerEvent) {
+        let mut inner = self.0.lock().unwrap();
+        if inner
+            .drag
+            .take_if(|v| v.pointer_id == event.pointer_id())
+            .is_none()
+        {
+            return;
+        }
+        let _ = inner.options.group.remove_attribute("data-resizing");
+        for handle in inner.handles() {
+            let _ = handle.remove_attribute("data-resizing");
+        }
+        inner.save();
+    }
+
+    fn handle_key_down(&self, event: &KeyboardEvent) {
+        let Some((index, _)) = event
+            .target()
+            .and_then(|v| v.dyn_into::<Element>().ok())
+            .filter(|v| v.has_attribute("data-seigi-panel-handle"))
+            .and_then(|v| self.handle_index(&v))
+        else {
+            return;
+        };
+
+        let key = event.key();
+        if key == "Enter" {
+            event.prevent_default();
+            if self.is_collapsed(index) {
+                self.expand(index);
+            } else {
+                self.collapse(index);
+            }
+            return;
+        }
+
+        let layout = {
+            let inner = self.0.lock().unwrap();
+            let step = inner.options.keyboard_step;
+            let (size, c) = (inner.layout[index], inner.constraints[index]);
+            let delta = match (inner.options.orientation, key.as_str()) {
+                (Orientation::Horizontal, "ArrowLeft") | (Orientation::Vertical, "ArrowUp") => {
+                    -step
+                }
+                (Orientation::Horizontal, "ArrowRight") | (Orientation::Vertical, "ArrowDown") => {
+                    step
+                }
+                (_, "Home") => c.min_size - size,
+                (_, "End") => c.max_size - size,
+                _ => return,
+            };
+            event.prevent_default();
+            // Nested groups would otherwise move their handles too
+            event.stop_propagation();
+            layout::resize(&inner.layout, &inner.constraints, index, delta)
+        };
+        self.commit(layout);
+    }
+}
+
+pub fn create_panel_group(options: PanelGroupOptions) -> PanelGroup {
+    let element = options.group.clone();
+    let storage = options.storage_key.as_ref().map(|key| {
+        create_persisted(
+            PersistedOptions::builder()
+                .key(key)
+                .sync(false)
+                // Layouts were saved as comma separated sizes
+                .migration(Box::new(|value| match value {
+                    Value::String(v) => v
+                        .split(',')
+                        .map(|v| v.parse::<f64>().map(Value::from).unwrap_or_default())
+                        .collect(),
+                    value => value,
+                }))
+                .build(),
+        )
+    });
+    let group = PanelGroup(Rc::new(Mutex::new(Inner {
+        options: Rc::new(options),
+        constraints: vec![],
+        layout: vec![],
+        expanded: vec![],
+        drag: None,
+        storage,
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&group.0);
+    let pointer = |event_type: &'static str, f: fn(&PanelGroup, &PointerEvent)| {
+        let weak = weak.clone();
+        EventListener::new(&element, event_type, move |event| {
+            let (Some(group), Some(event)) = (
+                PanelGroup::from_weak(&weak),
+                event.dyn_ref::<PointerEvent>(),
+            ) else {
+                return;
+            };
+            f(&group, event);
+        })
+    };
+    let listeners = vec![
+        pointer("pointerdown", PanelGroup::handle_pointer_down),
+        pointer("pointermove", PanelGroup::handle_pointer_move),
+        pointer("pointerup", PanelGroup::handle_pointer_up),
+        pointer("pointercancel", PanelGroup::handle_pointer_up),
+        EventListener::new(&element, "keydown", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(group), Some(event)) = (
+                    PanelGroup::from_weak(&weak),
+                    event.dyn_ref::<KeyboardEvent>(),
+                ) else {
+                    return;
+                };
+                group.handle_key_down(event);
+            }
+        }),
+    ];
+
+    group.0.lock().unwrap()._listeners = listeners;
+    group.refresh();
+    group
+}
@@ -0,0 +1,218 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use seigi_resizable::layout::{self, Constraints};
+
+fn assert_layout(actual: Vec<f64>, expected: &[f64]) {
+    assert_eq!(actual.len(), expected.len(), "{actual:?} != {expected:?}");
+    for (a, b) in actual.iter().zip(expected) {
+        assert!((a - b).abs() < 1e-9, "{actual:?} != {expected:?}");
+    }
+}
+
+fn min(min_size: f64) -> Constraints {
+    Constraints {
+        min_size,
+        ..Constraints::default()
+    }
+}
+
+fn collapsible(min_size: f64) -> Constraints {
+    Constraints {
+        min_size,
+        collapsible: true,
+        ..Constraints::default()
+    }
+}
+
+#[test]
+fn initial_splits_the_rest_evenly() {
+    let constraints = [
+        Constraints {
+            default_size: Some(30.0),
+            ..Constraints::default()
+        },
+        Constraints::default(),
+        Constraints::default(),
+    ];
+    assert_layout(layout::initial(&constraints), &[30.0, 35.0, 35.0]);
+
+    // What a clamped share leaves is taken by panels with room
+    let constraints = [
+        Constraints {
+            max_size: 20.0,
+            ..Constraints::default()
+        },
+        Constraints::default(),
+    ];
+    assert_layout(layout::initial(&constraints), &[20.0, 80.0]);
+    let constraints = [
+        Constraints {
+            default_size: Some(50.0),
+            ..Constraints::default()
+        },
+        min(60.0),
+    ];
+    assert_layout(layout::initial(&constraints), &[40.0, 60.0]);
+
+    let constraints = [
+        Constraints {
+            default_size: Some(60.0),
+            ..Constraints::default()
+        },
+        Constraints {
+            default_size: Some(60.0),
+            ..Constraints::default()
+        },
+    ];
+    assert_layout(layout::initial(&constraints), &[50.0, 50.0]);
+
+    // Impossible constraints are scaled
+    assert_layout(layout::initial(&[min(80.0), min(80.0)]), &[50.0, 50.0]);
+}
+
+#[test]
+fn normalize_scales_to_100() {
+    assert_layout(layout::normalize(vec![1.0, 1.0, 2.0]), &[25.0, 25.0, 50.0]);
+    assert_layout(layout::normalize(vec![0.0, 0.0]), &[0.0, 0.0]);
+}
+
+#[test]
+fn resize_moves_the_handle() {
+    let constraints = [Constraints::default(); 2];
+    assert_layout(
+        layout::resize(&[50.0, 50.0], &constraints, 0, 10.0),
+        &[60.0, 40.0],
+    );
+    assert_layout(
+        layout::resize(&[50.0, 50.0], &constraints, 0, -10.0),
+        &[40.0, 60.0],
+    );
+    // Nothing to move
+    assert_layout(
+        layout::resize(&[50.0, 50.0], &constraints, 0, 0.0),
+        &[50.0, 50.0],
+    );
+    assert_layout(
+        layout::resize(&[50.0, 50.0], &constraints, 1, 10.0),
+        &[50.0, 50.0],
+    );
+}
+
+#[test]
+fn resize_stops_at_min_and_max_sizes() {
+    assert_layout(
+        layout::resize(&[50.0, 50.0], &[min(0.0), min(45.0)], 0, 10.0),
+        &[55.0, 45.0],
+    );
+
+    let constraints = [
+        Constraints {
+            max_size: 55.0,
+            ..Constraints::default()
+        },
+        Constraints::default(),
+    ];
+    assert_layout(
+        layout::resize(&[50.0, 50.0], &constraints, 0, 10.0),
+        &[55.0, 45.0],
+    );
+    assert_layout(
+        layout::resize(&[55.0, 45.0], &constraints, 0, 10.0),
+        &[55.0, 45.0],
+    );
+}
+
+#[test]
+fn resize_shrinks_nearest_panels_first() {
+    let constraints = [min(20.0); 3];
+    assert_layout(
+        layout::resize(&[40.0, 30.0, 30.0], &constraints, 0, 15.0),
+        &[55.0, 20.0, 25.0],
+    );
+    // Every panel on the other side at its minimum stops the handle
+    assert_layout(
+        layout::resize(&[40.0, 30.0, 30.0], &constraints, 0, 40.0),
+        &[60.0, 20.0, 20.0],
+    );
+    assert_layout(
+        layout::resize(&[30.0, 30.0, 40.0], &constraints, 1, -40.0),
+        &[20.0, 20.0, 60.0],
+    );
+}
+
+#[test]
+fn resize_collapses_past_half_of_minimum() {
+    let constraints = [Constraints::default(), collapsible(20.0)];
+    let collapsed = layout::resize(&[50.0, 50.0], &constraints, 0, 45.0);
+    assert_layout(collapsed.clone(), &[100.0, 0.0]);
+    assert!(constraints[1].is_collapsed(collapsed[1]));
+
+    // Short of half of the minimum, the panel stays at its minimum
+    let layout = layout::resize(&[50.0, 50.0], &constraints, 0, 35.0);
+    assert_layout(layout.clone(), &[80.0, 20.0]);
+    assert!(!constraints[1].is_collapsed(layout[1]));
+}
+
+#[test]
+fn resize_collapses_to_collapsed_size() {
+    let constraints = [
+        Constraints::default(),
+        Constraints {
+            collapsed_size: 5.0,
+            ..collapsible(20.0)
+        },
+    ];
+    let layout = layout::resize(&[50.0, 50.0], &constraints, 0, 45.0);
+    assert_layout(layout.clone(), &[95.0, 5.0]);
+    assert!(constraints[1].is_collapsed(layout[1]));
+}
+
+#[test]
+fn collapse_needs_room_in_the_growing_panel() {
+    // Collapsing would grow the first panel past its maximum, so the second stays at its minimum
+    let constraints = [
+        Constraints {
+            max_size: 80.0,
+            ..Constraints::default()
+        },
+        collapsible(60.0),
+    ];
+    assert_layout(
+        layout::resize(&[40.0, 60.0], &constraints, 0, 40.0),
+        &[40.0, 60.0],
+    );
+
+    let constraints = [Constraints::default(), collapsible(60.0)];
+    assert_layout(
+        layout::resize(&[40.0, 60.0], &constraints, 0, 40.0),
+        &[100.0, 0.0],
+    );
+}
+
+#[test]
+fn collapsed_panel_expands_past_half_of_minimum() {
+    let constraints = [Constraints::default(), collapsible(20.0)];
+
+    assert_layout(
+        layout::resize(&[100.0, 0.0], &constraints, 0, -5.0),
+        &[100.0, 0.0],
+    );
+    // Expanding jumps to the minimum at once
+    assert_layout(
+        layout::resize(&[100.0, 0.0], &constraints, 0, -12.0),
+        &[80.0, 20.0],
+    );
+    assert_layout(
+        layout::resize(&[100.0, 0.0], &constraints, 0, -30.0),
+        &[70.0, 30.0],
+    );
+}
+
+#[test]
+fn collapsed_panel_stays_without_room_to_expand() {
+    let constraints = [min(90.0), collapsible(20.0)];
+    assert_layout(
+        layout::resize(&[100.0, 0.0], &constraints, 0, -20.0),
+        &[100.0, 0.0],
+    );
+}
@@ -0,0 +1,185 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use gloo::utils::window;
+use seigi_resizable::{PanelGroup, PanelGroupHooks, PanelGroupOptions, PanelGroupOptionsBuilder};
+use seigi_testkit::{
+    Fixture,
+    events::{self, Modifiers},
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<div id="group" style="display: flex; width: 404px; height: 100px">
+    <div id="sidebar" data-seigi-panel data-default-size="40" data-min-size="20" data-collapsible></div>
+    <div id="handle" data-seigi-panel-handle style="width: 4px"></div>
+    <div id="main" data-seigi-panel></div>
+</div>
+"#;
+
+const STORAGE_KEY: &str = "seigi-test-panel-group";
+
+fn options(fixture: &Fixture) -> PanelGroupOptionsBuilder {
+    PanelGroupOptions::builder().group(fixture.get("#group"))
+}
+
+fn group(options: PanelGroupOptionsBuilder) -> (PanelGroup, Rc<RefCell<Vec<Vec<f64>>>>) {
+    let layouts = Rc::new(RefCell::new(vec![]));
+    let group = seigi_resizable::create_panel_group(
+        options
+            .hooks(PanelGroupHooks {
+                layout: Some(Box::new({
+                    let layouts = layouts.clone();
+                    move |layout| layouts.borrow_mut().push(layout.to_vec())
+                })),
+            })
+            .build(),
+    );
+    (group, layouts)
+}
+
+fn assert_layout(group: &PanelGroup, expected: &[f64]) {
+    let layout = group.layout();
+    assert_eq!(layout.len(), expected.len(), "{layout:?} != {expected:?}");
+    for (a, b) in layout.iter().zip(expected) {
+        assert!((a - b).abs() < 0.01, "{layout:?} != {expected:?}");
+    }
+}
+
+fn press(fixture: &Fixture, key: &str) -> bool {
+    events::key_down(&fixture.get("#handle"), key, Modifiers::default())
+}
+
+#[wasm_bindgen_test]
+fn panels_and_handles_are_described() {
+    let fixture = Fixture::new(MARKUP);
+    let (group, _) = group(options(&fixture));
+    assert_layout(&group, &[40.0, 60.0]);
+
+    assert_eq!(
+        fixture
+            .get("#group")
+            .get_attribute("data-orientation")
+            .as_deref(),
+        Some("horizontal")
+    );
+    let handle = fixture.get("#handle");
+    assert_eq!(handle.get_attribute("role").as_deref(), Some("separator"));
+    assert_eq!(
+        handle.get_attribute("aria-orientation").as_deref(),
+        Some("vertical")
+    );
+    assert_eq!(
+        handle.get_attribute("aria-controls").as_deref(),
+        Some("sidebar")
+    );
+    assert_eq!(handle.get_attribute("aria-valuenow").as_deref(), Some("40"));
+    assert_eq!(handle.get_attribute("aria-valuemin").as_deref(), Some("20"));
+    assert_eq!(handle.tab_index(), 0);
+
+    let style = fixture.get("#sidebar").style();
+    assert_eq!(style.get_property_value("flex").unwrap(), "40 1 0px");
+    assert_eq!(
+        style.get_property_value("--seigi-panel-size").unwrap(),
+        "40%"
+    );
+}
+
+#[wasm_bindgen_test]
+fn keys_move_the_handle() {
+    let fixture = Fixture::new(MARKUP);
+    let (group, layouts) = group(options(&fixture).keyboard_step(5.0));
+
+    assert!(!press(&fixture, "ArrowRight"));
+    assert_layout(&group, &[45.0, 55.0]);
+    press(&fixture, "ArrowLeft");
+    assert_layout(&group, &[40.0, 60.0]);
+    press(&fixture, "Home");
+    assert_layout(&group, &[20.0, 80.0]);
+    press(&fixture, "End");
+    assert_layout(&group, &[100.0, 0.0]);
+    assert_eq!(layouts.borrow().len(), 4);
+
+    // Keys along the other axis are left alone
+    assert!(press(&fixture, "ArrowDown"));
+}
+
+#[wasm_bindgen_test]
+fn enter_collapses_and_expands_back() {
+    let fixture = Fixture::new(MARKUP);
+    let (group, _) = group(options(&fixture));
+    let sidebar = fixture.get("#sidebar");
+
+    assert!(!press(&fixture, "Enter"));
+    assert!(group.is_collapsed(0));
+    assert_layout(&group, &[0.0, 100.0]);
+    assert!(sidebar.has_attribute("data-collapsed"));
+
+    press(&fixture, "Enter");
+    assert!(!group.is_collapsed(0));
+    assert_layout(&group, &[40.0, 60.0]);
+    assert!(!sidebar.has_attribute("data-collapsed"));
+
+    // Panels that aren't collapsible are left alone
+    group.collapse(1);
+    assert_layout(&group, &[40.0, 60.0]);
+}
+
+#[wasm_bindgen_test]
+fn dragging_handle_resizes_panels() {
+    let fixture = Fixture::new(MARKUP);
+    let (group, layouts) = group(options(&fixture));
+    let handle = fixture.get("#handle");
+
+    assert!(!events::dispatch(
+        &handle,
+        &events::pointer_event("pointerdown", 100.0, 50.0)
+    ));
+    assert!(handle.has_attribute("data-resizing"));
+    assert!(fixture.get("#group").has_attribute("data-resizing"));
+
+    // Panels share 400px, so 40px is 10%
+    events::dispatch(&handle, &events::pointer_event("pointermove", 140.0, 50.0));
+    assert_layout(&group, &[50.0, 50.0]);
+    // Moves are relative to where the drag started
+    events::dispatch(&handle, &events::pointer_event("pointermove", 120.0, 50.0));
+    assert_layout(&group, &[45.0, 55.0]);
+
+    events::dispatch(&handle, &events::pointer_event("pointerup", 120.0, 50.0));
+    assert!(!handle.has_attribute("data-resizing"));
+    assert!(!fixture.get("#group").has_attribute("data-resizing"));
+    events::dispatch(&handle, &events::pointer_event("pointermove", 200.0, 50.0));
+    assert_layout(&group, &[45.0, 55.0]);
+    assert_eq!(layouts.borrow().len(), 2);
+}
+
+#[wasm_bindgen_test]
+fn set_layout_scales_and_skips_unchanged() {
+    let fixture = Fixture::new(MARKUP);
+    let (group, layouts) = group(options(&fixture));
+
+    group.set_layout(vec![1.0, 3.0]);
+    assert_layout(&group, &[25.0, 75.0]);
+    group.set_layout(vec![25.0, 75.0]);
+    assert_eq!(layouts.borrow().len(), 1);
+}
+
+#[wasm_bindgen_test]
+fn layout_is_restored_from_storage() {
+    let storage = window().local_storage().unwrap().unwrap();
+    storage.remove_item(STORAGE_KEY).unwrap();
+
+    {
+        let fixture = Fixture::new(MARKUP);
+        let (group, _) = group(options(&fixture).storage_key(STORAGE_KEY));
+        group.set_layout(vec![30.0, 70.0]);
+    }
+
+    let fixture = Fixture::new(MARKUP);
+    let (group, _) = group(options(&fixture).storage_key(STORAGE_KEY));
+    assert_layout(&group, &[30.0, 70.0]);
+    storage.remove_item(STORAGE_KEY).unwrap();
+}
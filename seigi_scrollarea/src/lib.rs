@@ -0,0 +1,602 @@
+//! A scroll area with custom scrollbars overlaid on a natively scrolling viewport
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::{
+    events::{EventListener, EventListenerOptions},
+    timers::callback::Timeout,
+};
+use seigi_utils::{
+    diagnostics::{self, Resource, Tracked},
+    direction,
+};
+use wasm_bindgen::{JsCast, prelude::Closure};
+use web_sys::{Element, Event, HtmlElement, PointerEvent, ResizeObserver, WheelEvent};
+
+/// The smallest size of a thumb in pixels, so it stays grabbable on long contents
+const MIN_THUMB_SIZE: f64 = 16.0;
+
+/// When scrollbars are shown
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    /// Shown while the content overflows
+    #[default]
+    Auto,
+    /// Always shown, even if the content doesn't overflow
+    Always,
+    /// Shown while the pointer is over the area or it is scrolling
+    Hover,
+    /// Shown while the area is scrolling
+    Scroll,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+impl Axis {
+    fn parse(value: &str) -> Self {
+        match value {
+            "horizontal" => Self::Horizontal,
+            _ => Self::Vertical,
+        }
+    }
+
+    fn coordinate(&self, event: &PointerEvent) -> f64 {
+        match self {
+            Self::Horizontal => event.client_x() as f64,
+            Self::Vertical => event.client_y() as f64,
+        }
+    }
+}
+
+/// Options of [ScrollArea]
+pub struct ScrollAreaOptions {
+    /// The element containing the viewport and scrollbars
+    pub root: HtmlElement,
+    /// The element that scrolls natively, whose own scrollbars are hidden
+    pub viewport: HtmlElement,
+    pub visibility: Visibility,
+    /// Milliseconds scrollbars stay visible after scrolling or the pointer leaving
+    pub hide_delay: u32,
+}
+
+impl ScrollAreaOptions {
+    pub fn builder() -> ScrollAreaOptionsBuilder {
+        ScrollAreaOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [ScrollAreaOptions]
+pub struct ScrollAreaOptionsBuilder {
+    root: Option<HtmlElement>,
+    viewport: Option<HtmlElement>,
+    visibility: Visibility,
+    hide_delay: u32,
+}
+
+impl Default for ScrollAreaOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            root: None,
+            viewport: None,
+            visibility: Visibility::default(),
+            hide_delay: 600,
+        }
+    }
+}
+
+impl ScrollAreaOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn root(mut self, root: HtmlElement) -> Self {
+        self.root = Some(root);
+        self
+    }
+
+    pub fn viewport(mut self, viewport: HtmlElement) -> Self {
+        self.viewport = Some(viewport);
+        self
+    }
+
+    pub fn visibility(mut self, visibility: Visibility) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    pub fn hide_delay(mut self, hide_delay: u32) -> Self {
+        self.hide_delay = hide_delay;
+        self
+    }
+
+    /// Builds into [ScrollAreaOptions]
+    ///
+    /// # Panics
+    /// This method panics if root or viewport field is not set
+    pub fn build(self) -> ScrollAreaOptions {
+        ScrollAreaOptions {
+            root: self
+                .root
+                .expect("root must be set to build ScrollAreaOptions"),
+            viewport: self
+                .viewport
+                .expect("viewport must be set to build ScrollAreaOptions"),
+            visibility: self.visibility,
+            hide_delay: self.hide_delay,
+        }
+    }
+}
+
+/// Scroll metrics of the viewport along an axis
+struct Metrics {
+    client: f64,
+    scroll: f64,
+    /// The scroll position from the start of the axis, which is left even in right-to-left
+    position: f64,
+}
+
+impl Metrics {
+    fn max(&self) -> f64 {
+        (self.scroll - self.client).max(0.0)
+    }
+
+    fn overflows(&self) -> bool {
+        self.max() >= 1.0
+    }
+}
+
+struct Drag {
+    scrollbar: HtmlElement,
+    axis: Axis,
+    pointer_id: i32,
+    origin: f64,
+    position: f64,
+}
+
+struct Inner {
+    options: ScrollAreaOptions,
+    hovered: bool,
+    /// Whether the area scrolled or the pointer left recently
+    active: bool,
+    hide_timer: Option<Timeout>,
+    drag: Option<Drag>,
+    resize_observer: ResizeObserver,
+    _resize_callback: Closure<dyn Fn()>,
+    /// Counts the observer in diagnostics while alive
+    _tracked: Tracked,
+    _listeners: Vec<EventListener>,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.resize_observer.disconnect();
+    }
+}
+
+impl Inner {
+    fn scrollbars(&self) -> Vec<(Axis, HtmlElement)> {
+        let Ok(nodes) = self
+            .options
+            .root
+            .query_selector_all("[data-seigi-scrollbar]")
+        else {
+            return vec![];
+        };
+        (0..nodes.length())
+            .filter_map(|v| nodes.get(v))
+            .filter_map(|v| v.dyn_into::<HtmlElement>().ok())
+            // Scrollbars of nested scroll areas belong to them
+            .filter(|v| {
+                v.parent_element()
+                    .and_then(|v| v.closest("[data-seigi-scroll-area]").ok().flatten())
+                    .is_none_or(|v| v == *self.options.root)
+            })
+            .map(|v| {
+                let axis =
+                    Axis::parse(&v.get_attribute("data-seigi-scrollbar").unwrap_or_default());
+                (axis, v)
+            })
+            .collect()
+    }
+
+    fn is_rtl(&self) -> bool {
+        direction::is_rtl(&self.options.viewport)
+    }
+
+    fn metrics(&self, axis: Axis) -> Metrics {
+        let viewport = &self.options.viewport;
+        match axis {
+            Axis::Horizontal => {
+                let client = viewport.client_width() as f64;
+                let scroll = viewport.scroll_width() as f64;
+                let mut position = viewport.scroll_left() as f64;
+                // Right-to-left starts at 0 and scrolls into negative positions
+                if self.is_rtl() {
+                    position += (scroll - client).max(0.0);
+                }
+                Metrics {
+                    client,
+                    scroll,
+                    position,
+                }
+            }
+            Axis::Vertical => Metrics {
+                client: viewport.client_height() as f64,
+                scroll: viewport.scroll_height() as f64,
+                position: viewport.scroll_top() as f64,
+            },
+        }
+    }
+
+    fn set_position(&self, axis: Axis, position: f64) {
+        let metrics = self.metrics(axis);
+        let position = position.clamp(0.0, metrics.max());
+        let viewport = &self.options.viewport;
+        match axis {
+            Axis::Horizontal if self.is_rtl() => {
+                viewport.set_scroll_left((position - metrics.max()) as i32)
+            }
+            Axis::Horizontal => viewport.set_scroll_left(position as i32),
+            Axis::Vertical => viewport.set_scroll_top(position as i32),
+        }
+    }
+
+    /// Returns sizes of the track and the thumb of the scrollbar
+    fn track(scrollbar: &HtmlElement, axis: Axis, metrics: &Metrics) -> (f64, f64) {
+        let track = match axis {
+            Axis::Horizontal => scrollbar.client_width(),
+            Axis::Vertical => scrollbar.client_height(),
+        } as f64;
+        let thumb = if metrics.scroll > 0.0 {
+            (track * metrics.client / metrics.scroll).clamp(MIN_THUMB_SIZE.min(track), track)
+        } else {
+            track
+        };
+        (track, thumb)
+    }
+
+    fn is_visible(&self, overflows: bool) -> bool {
+        let is_active = self.active || self.drag.is_some();
+        match self.options.visibility {
+            Visibility::Always => true,
+            Visibility::Auto => overflows,
+            Visibility::Hover => overflows && (self.hovered || is_active),
+            Visibility::Scroll => overflows && is_active,
+        }
+    }
+
+    fn update(&self) {
+        let root = &self.options.root;
+        for (axis, scrollbar) in self.scrollbars() {
+            let metrics = self.metrics(axis);
+            let (track, thumb) = Self::track(&scrollbar, axis, &metrics);
+            let offset = if metrics.overflows() {
+                (track - thumb) * metrics.position / metrics.max()
+            } else {
+                0.0
+            };
+
+            let style = scrollbar.style();
+            let _ = style.set_property("--seigi-scroll-thumb-size", &format!("{thumb}px"));
+            let _ = style.set_property("--seigi-scroll-thumb-offset", &format!("{offset}px"));
+            let state = if self.is_visible(metrics.overflows()) {
+                "visible"
+            } else {
+                "hidden"
+            };
+            let _ = scrollbar.set_attribute("data-state", state);
+
+            let overflow = match axis {
+                Axis::Horizontal => "data-overflow-x",
+                Axis::Vertical => "data-overflow-y",
+            };
+            if metrics.overflows() {
+                let _ = root.set_attribute(overflow, "");
+            } else {
+                let _ = root.remove_attribute(overflow);
+            }
+        }
+    }
+}
+
+/// An instance of scroll area
+///
+/// The viewport scrolls natively, so wheel, touch and keyboard scrolling work as usual, and its
+/// own scrollbars are hidden with `scrollbar-width: none`. Scrollbars are elements in the root
+/// with `data-seigi-scrollbar` set to `vertical` or `horizontal`, each containing a thumb with
+/// `data-seigi-scroll-thumb`. They are hidden from assistive technologies, as the viewport is
+/// the scrollable element to them, and the viewport is made focusable for keyboard scrolling.
+///
+/// Thumbs can be dragged, pressing a track scrolls by a page toward the pointer, and the wheel
+/// over a scrollbar scrolls the viewport. Sizing and moving thumbs is left to user CSS with the
+/// variables below.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-seigi-scroll-area** is set in the root, which scopes scrollbars of nested areas
+///
+/// **data-state** is set in scrollbars to `visible` or `hidden` by [Visibility]
+///
+/// **data-orientation** is set in scrollbars to `vertical` or `horizontal`
+///
+/// **data-dragging** is set in the scrollbar whose thumb is dragged
+///
+/// **data-overflow-x** and **data-overflow-y** are set in the root while the content overflows
+/// on the axis
+///
+/// # CSS Variables
+/// **--seigi-scroll-thumb-size** is set in scrollbars to the length of the thumb
+///
+/// **--seigi-scroll-thumb-offset** is set in scrollbars to the distance of the thumb from the
+/// start of the track, which is the left even in right-to-left, e.g.
+/// `transform: translateX(var(--seigi-scroll-thumb-offset))`
+#[derive(Clone)]
+pub struct ScrollArea(Rc<Mutex<Inner>>);
+
+impl ScrollArea {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    /// Updates scrollbars, if the layout changed in a way not observed
+    pub fn update(&self) {
+        self.0.lock().unwrap().update();
+    }
+
+    /// Observes children of the viewport again and updates scrollbars, after the content is
+    /// replaced
+    pub fn refresh(&self) {
+        let inner = self.0.lock().unwrap();
+        inner.resize_observer.disconnect();
+        let viewport = &inner.options.viewport;
+        inner.resize_observer.observe(viewport);
+        let children = viewport.children();
+        for index in 0..children.length() {
+            if let Some(child) = children.item(index) {
+                inner.resize_observer.observe(&child);
+            }
+        }
+        for (axis, scrollbar) in inner.scrollbars() {
+            let orientation = match axis {
+                Axis::Horizontal => "horizontal",
+                Axis::Vertical => "vertical",
+            };
+            let _ = scrollbar.set_attribute("data-orientation", orientation);
+            let _ = scrollbar.set_attribute("aria-hidden", "true");
+        }
+        inner.update();
+    }
+
+    /// Marks the area active, and inactive again after the delay
+    fn activate(&self) {
+        let mut inner = self.0.lock().unwrap();
+        inner.active = true;
+        let weak = Rc::downgrade(&self.0);
+        inner.hide_timer = Some(Timeout::new(inner.options.hide_delay, move || {
+            if let Some(area) = ScrollArea::from_weak(&weak) {
+                let mut inner = area.0.lock().unwrap();
+                inner.active = false;
+                inner.hide_timer = None;
+                inner.update();
+            }
+        }));
+        inner.update();
+    }
+
+    fn set_hovered(&self, hovered: bool) {
+        self.0.lock().unwrap().hovered = hovered;
+        if hovered {
+            self.update();
+        } else {
+            // Scrollbars fade out after the delay rather than at once
+            self.activate();
+        }
+    }
+
+    fn scrollbar_of(&self, target: &Element) -> Option<(Axis, HtmlElement)> {
+        let scrollbar = target.closest("[data-seigi-scrollbar]").ok().flatten()?;
+        self.0
+            .lock()
+            .unwrap()
+            .scrollbars()
+            .into_iter()
+            .find(|(_, v)| **v == scrollbar)
+    }
+
+    fn handle_pointer_down(&self, event: &PointerEvent) {
+        if event.button() != 0 {
+            return;
+        }
+        let Some(target) = event.target().and_then(|v| v.dyn_into::<Element>().ok()) else {
+            return;
+        };
+        let Some((axis, scrollbar)) = self.scrollbar_of(&target) else {
+            return;
+        };
+        // Focus would otherwise leave the viewport, and keys stop scrolling it
+        event.prevent_default();
+
+        let mut inner = self.0.lock().unwrap();
+        let _ = inner.options.viewport.focus();
+        let metrics = inner.metrics(axis);
+        let thumb = target
+            .closest("[data-seigi-scroll-thumb]")
+            .ok()
+            .flatten()
+            .filter(|v| scrollbar.contains(Some(v)));
+        if thumb.is_some() {
+            let _ = scrollbar.set_pointer_capture(event.pointer_id());
+            let _ = scrollbar.set_attribute("data-dragging", "");
+            inner.drag = Some(Drag {
+                scrollbar,
+                axis,
+                pointer_id: event.pointer_id(),
+                origin: axis.coordinate(event),
+                position: metrics.position,
+            });
+            inner.update();
+            return;
+        }
+
+        // Pressing the track pages toward the pointer
+        let Ok(Some(thumb)) = scrollbar.query_selector("[data-seigi-scroll-thumb]") else {
+            return;
+        };
+        let rect = thumb.get_bounding_client_rect();
+        let (start, end) = match axis {
+            Axis::Horizontal => (rect.left(), rect.right()),
+            Axis::Vertical => (rect.top(), rect.bottom()),
+        };
+        let pointer = axis.coordinate(event);
+        let page = metrics.client * 0.9;
+        if pointer < start {
+            inner.set_position(axis, metrics.position - page);
+        } else if pointer > end {
+            inner.set_position(axis, metrics.position + page);
+        }
+    }
+
+    fn handle_pointer_move(&self, event: &PointerEvent) {
+        let inner = self.0.lock().unwrap();
+        let Some(drag) = inner
+            .drag
+            .as_ref()
+            .filter(|v| v.pointer_id == event.pointer_id())
+        else {
+            return;
+        };
+        let metrics = inner.metrics(drag.axis);
+        let (track, thumb) = Inner::track(&drag.scrollbar, drag.axis, &metrics);
+        if track - thumb <= 0.0 {
+            return;
+        }
+        let delta = drag.axis.coordinate(event) - drag.origin;
+        inner.set_position(
+            drag.axis,
+            drag.position + delta * metrics.max() / (track - thumb),
+        );
+    }
+
+    fn handle_pointer_up(&self, event: &PointerEvent) {
+        let mut inner = self.0.lock().unwrap();
+        let Some(drag) = inner.drag.take_if(|v| v.pointer_id == event.pointer_id()) else {
+            return;
+        };
+        let _ = drag.scrollbar.remove_attribute("data-dragging");
+        inner.update();
+    }
+
+    fn handle_wheel(&self, event: &WheelEvent) {
+        let Some(target) = event.target().and_then(|v| v.dyn_into::<Element>().ok()) else {
+            return;
+        };
+        if self.scrollbar_of(&target).is_none() {
+            return;
+        }
+        // Scrollbars are outside the viewport, so the wheel over them is passed to it
+        let viewport = self.0.lock().unwrap().options.viewport.clone();
+        let (x, y) = match event.delta_mode() {
+            WheelEvent::DOM_DELTA_LINE => (16.0, 16.0),
+            WheelEvent::DOM_DELTA_PAGE => (
+                viewport.client_width() as f64,
+                viewport.client_height() as f64,
+            ),
+            _ => (1.0, 1.0),
+        };
+        event.prevent_default();
+        viewport.scroll_by_with_x_and_y(event.delta_x() * x, event.delta_y() * y);
+    }
+}
+
+pub fn create_scroll_area(options: ScrollAreaOptions) -> ScrollArea {
+    let root = options.root.clone();
+    let viewport = options.viewport.clone();
+    let _ = root.set_attribute("data-seigi-scroll-area", "");
+    let _ = viewport.style().set_property("scrollbar-width", "none");
+    if !viewport.has_attribute("tabindex") {
+        viewport.set_tab_index(0);
+    }
+
+    let area = ScrollArea(Rc::new_cyclic(|weak: &Weak<Mutex<Inner>>| {
+        let resize_callback: Closure<dyn Fn()> = Closure::new({
+            let weak = weak.clone();
+            move || {
+                if let Some(area) = ScrollArea::from_weak(&weak) {
+                    area.update();
+                }
+            }
+        });
+        let resize_observer = ResizeObserver::new(resize_callback.as_ref().unchecked_ref())
+            .expect("failed to create resize observer");
+
+        let listener =
+            |target: &HtmlElement, event_type: &'static str, f: fn(&ScrollArea, &Event)| {
+                let weak = weak.clone();
+                EventListener::new(target, event_type, move |event| {
+                    if let Some(area) = ScrollArea::from_weak(&weak) {
+                        f(&area, event);
+                    }
+                })
+            };
+        let pointer = |event_type: &'static str, f: fn(&ScrollArea, &PointerEvent)| {
+            let weak = weak.clone();
+            EventListener::new(&root, event_type, move |event| {
+                let (Some(area), Some(event)) = (
+                    ScrollArea::from_weak(&weak),
+                    event.dyn_ref::<PointerEvent>(),
+                ) else {
+                    return;
+                };
+                f(&area, event);
+            })
+        };
+        let listeners = vec![
+            listener(&viewport, "scroll", |area, _| area.activate()),
+            listener(&root, "pointerenter", |area, _| area.set_hovered(true)),
+            listener(&root, "pointerleave", |area, _| area.set_hovered(false)),
+            pointer("pointerdown", ScrollArea::handle_pointer_down),
+            pointer("pointermove", ScrollArea::handle_pointer_move),
+            pointer("pointerup", ScrollArea::handle_pointer_up),
+            pointer("pointercancel", ScrollArea::handle_pointer_up),
+            EventListener::new_with_options(
+                &root,
+                "wheel",
+                EventListenerOptions::enable_prevent_default(),
+                {
+                    let weak = weak.clone();
+                    move |event| {
+                        let (Some(area), Some(event)) =
+                            (ScrollArea::from_weak(&weak), event.dyn_ref::<WheelEvent>())
+                        else {
+                            return;
+                        };
+                        area.handle_wheel(event);
+                    }
+                },
+            ),
+        ];
+
+        Mutex::new(Inner {
+            options,
+            hovered: false,
+            active: false,
+            hide_timer: None,
+            drag: None,
+            resize_observer,
+            _resize_callback: resize_callback,
+            _tracked: diagnostics::track(Resource::Observer),
+            _listeners: listeners,
+        })
+    }));
+    area.refresh();
+    area
+}
@@ -0,0 +1,205 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_scrollarea::{ScrollArea, ScrollAreaOptions, Visibility};
+use seigi_testkit::{Fixture, assert_focused, events, wait};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const DELAY: u32 = 20;
+const WAIT: u32 = 60;
+
+/// A 100px viewport over the content, with a 100px vertical track
+fn markup(content: f64) -> String {
+    format!(
+        r#"
+<div id="root" style="position: relative; width: 100px; height: 100px">
+    <div id="viewport" style="width: 100px; height: 100px; overflow: auto">
+        <div style="width: 100px; height: {content}px"></div>
+    </div>
+    <div id="scrollbar" data-seigi-scrollbar="vertical"
+        style="position: absolute; top: 0; right: 0; width: 10px; height: 100px">
+        <div id="thumb" data-seigi-scroll-thumb
+            style="height: var(--seigi-scroll-thumb-size); transform: translateY(var(--seigi-scroll-thumb-offset))"></div>
+    </div>
+</div>
+"#
+    )
+}
+
+fn area(fixture: &Fixture, visibility: Visibility) -> ScrollArea {
+    seigi_scrollarea::create_scroll_area(
+        ScrollAreaOptions::builder()
+            .root(fixture.get("#root"))
+            .viewport(fixture.get("#viewport"))
+            .visibility(visibility)
+            .hide_delay(DELAY)
+            .build(),
+    )
+}
+
+fn property(fixture: &Fixture, name: &str) -> String {
+    fixture
+        .get("#scrollbar")
+        .style()
+        .get_property_value(name)
+        .unwrap()
+}
+
+fn state(fixture: &Fixture) -> Option<String> {
+    fixture.get("#scrollbar").get_attribute("data-state")
+}
+
+#[wasm_bindgen_test]
+fn scrollbars_are_described() {
+    let fixture = Fixture::new(&markup(400.0));
+    let _area = area(&fixture, Visibility::Auto);
+
+    let root = fixture.get("#root");
+    let viewport = fixture.get("#viewport");
+    let scrollbar = fixture.get("#scrollbar");
+    assert!(root.has_attribute("data-seigi-scroll-area"));
+    assert!(root.has_attribute("data-overflow-y"));
+    assert!(!root.has_attribute("data-overflow-x"));
+    assert_eq!(viewport.tab_index(), 0);
+    assert_eq!(
+        viewport
+            .style()
+            .get_property_value("scrollbar-width")
+            .unwrap(),
+        "none"
+    );
+    assert_eq!(
+        scrollbar.get_attribute("data-orientation").as_deref(),
+        Some("vertical")
+    );
+    assert_eq!(
+        scrollbar.get_attribute("aria-hidden").as_deref(),
+        Some("true")
+    );
+    assert_eq!(state(&fixture).as_deref(), Some("visible"));
+    // A quarter of the content is in view
+    assert_eq!(property(&fixture, "--seigi-scroll-thumb-size"), "25px");
+    assert_eq!(property(&fixture, "--seigi-scroll-thumb-offset"), "0px");
+}
+
+#[wasm_bindgen_test]
+fn thumb_follows_scrolling() {
+    let fixture = Fixture::new(&markup(400.0));
+    let area = area(&fixture, Visibility::Auto);
+
+    fixture.get("#viewport").set_scroll_top(150);
+    area.update();
+    // Halfway through 300px of scrolling moves the thumb halfway through 75px of track
+    assert_eq!(property(&fixture, "--seigi-scroll-thumb-offset"), "37.5px");
+}
+
+#[wasm_bindgen_test]
+fn content_without_overflow_hides_scrollbars() {
+    let fixture = Fixture::new(&markup(50.0));
+    let _area = area(&fixture, Visibility::Auto);
+    assert!(!fixture.get("#root").has_attribute("data-overflow-y"));
+    assert_eq!(state(&fixture).as_deref(), Some("hidden"));
+
+    let fixture = Fixture::new(&markup(50.0));
+    area(&fixture, Visibility::Always);
+    assert_eq!(state(&fixture).as_deref(), Some("visible"));
+}
+
+#[wasm_bindgen_test]
+fn dragging_thumb_scrolls_viewport() {
+    let fixture = Fixture::new(&markup(400.0));
+    let _area = area(&fixture, Visibility::Auto);
+    let (viewport, scrollbar, thumb) = (
+        fixture.get("#viewport"),
+        fixture.get("#scrollbar"),
+        fixture.get("#thumb"),
+    );
+
+    assert!(!events::dispatch(
+        &thumb,
+        &events::pointer_event("pointerdown", 95.0, 10.0)
+    ));
+    assert!(scrollbar.has_attribute("data-dragging"));
+    assert_focused(&viewport);
+
+    // 75px of track covers 300px of scrolling
+    events::dispatch(&thumb, &events::pointer_event("pointermove", 95.0, 25.0));
+    assert_eq!(viewport.scroll_top(), 60);
+    // Positions are clamped to the content
+    events::dispatch(&thumb, &events::pointer_event("pointermove", 95.0, 200.0));
+    assert_eq!(viewport.scroll_top(), 300);
+
+    events::dispatch(&thumb, &events::pointer_event("pointerup", 95.0, 200.0));
+    assert!(!scrollbar.has_attribute("data-dragging"));
+    events::dispatch(&thumb, &events::pointer_event("pointermove", 95.0, 10.0));
+    assert_eq!(viewport.scroll_top(), 300);
+}
+
+#[wasm_bindgen_test]
+fn pressing_track_pages_toward_pointer() {
+    let fixture = Fixture::new(&markup(400.0));
+    let area = area(&fixture, Visibility::Auto);
+    let (viewport, scrollbar) = (fixture.get("#viewport"), fixture.get("#scrollbar"));
+    let rect = scrollbar.get_bounding_client_rect();
+    let x = rect.left() + 5.0;
+
+    // Below the thumb, which spans the first 25px
+    events::dispatch(
+        &scrollbar,
+        &events::pointer_event("pointerdown", x, rect.top() + 90.0),
+    );
+    assert_eq!(viewport.scroll_top(), 90);
+    assert!(!scrollbar.has_attribute("data-dragging"));
+
+    area.update();
+    // Above the thumb, which moved 22.5px down
+    events::dispatch(
+        &scrollbar,
+        &events::pointer_event("pointerdown", x, rect.top() + 10.0),
+    );
+    assert_eq!(viewport.scroll_top(), 0);
+}
+
+#[wasm_bindgen_test]
+async fn scroll_visibility_hides_after_delay() {
+    let fixture = Fixture::new(&markup(400.0));
+    let _area = area(&fixture, Visibility::Scroll);
+    assert_eq!(state(&fixture).as_deref(), Some("hidden"));
+
+    events::fire(&fixture.get("#viewport"), "scroll");
+    assert_eq!(state(&fixture).as_deref(), Some("visible"));
+    wait::sleep(WAIT).await;
+    assert_eq!(state(&fixture).as_deref(), Some("hidden"));
+}
+
+#[wasm_bindgen_test]
+async fn hover_visibility_follows_pointer() {
+    let fixture = Fixture::new(&markup(400.0));
+    let _area = area(&fixture, Visibility::Hover);
+    let root = fixture.get("#root");
+    assert_eq!(state(&fixture).as_deref(), Some("hidden"));
+
+    events::dispatch(&root, &events::pointer_event("pointerenter", 50.0, 50.0));
+    assert_eq!(state(&fixture).as_deref(), Some("visible"));
+    // Scrollbars fade out after the delay rather than at once
+    events::dispatch(&root, &events::pointer_event("pointerleave", 200.0, 50.0));
+    assert_eq!(state(&fixture).as_deref(), Some("visible"));
+    wait::sleep(WAIT).await;
+    assert_eq!(state(&fixture).as_deref(), Some("hidden"));
+}
+
+#[wasm_bindgen_test]
+fn scrollbars_of_nested_areas_are_left_alone() {
+    let fixture = Fixture::new(&markup(400.0));
+    fixture
+        .get("#root")
+        .insert_adjacent_html(
+            "beforeend",
+            r#"<div data-seigi-scroll-area><div id="nested" data-seigi-scrollbar="horizontal"></div></div>"#,
+        )
+        .unwrap();
+    let _area = area(&fixture, Visibility::Auto);
+    assert!(!fixture.get("#nested").has_attribute("data-orientation"));
+    assert!(fixture.get("#scrollbar").has_attribute("data-orientation"));
+}
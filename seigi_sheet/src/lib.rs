@@ -0,0 +1,719 @@
+//! A sheet sliding in from an edge of the viewport, dragged to snap points or closed
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::{events::EventListener, utils::window};
+use seigi_focus::{FocusTrap, FocusTrapOptions, InitialFocus};
+use seigi_utils::{
+    audit,
+    presence::is_animated,
+    scroll_lock::{self, ALLOW_ATTRIBUTE, ScrollLock},
+    top_layer,
+};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, Event, HtmlDialogElement, MouseEvent, PointerEvent};
+
+/// The edge of the viewport a sheet is attached to
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Top,
+    Right,
+    #[default]
+    Bottom,
+    Left,
+}
+
+impl Side {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Side::Top => "top",
+            Side::Right => "right",
+            Side::Bottom => "bottom",
+            Side::Left => "left",
+        }
+    }
+
+    fn is_vertical(&self) -> bool {
+        matches!(self, Side::Top | Side::Bottom)
+    }
+
+    /// Returns the distance of the pointer moved toward the edge, and across it
+    fn project(&self, dx: f64, dy: f64) -> (f64, f64) {
+        match self {
+            Side::Top => (-dy, dx),
+            Side::Right => (dx, dy),
+            Side::Bottom => (dy, dx),
+            Side::Left => (-dx, dy),
+        }
+    }
+
+    /// Returns whether the element is scrolled to the end where moving toward the edge no longer
+    /// scrolls it
+    fn is_scrolled_to_edge(&self, element: &Element) -> bool {
+        match self {
+            Side::Bottom => element.scroll_top() <= 0,
+            Side::Top => {
+                element.scroll_top() + element.client_height() >= element.scroll_height() - 1
+            }
+            Side::Right => element.scroll_left() <= 0,
+            Side::Left => {
+                element.scroll_left() + element.client_width() >= element.scroll_width() - 1
+            }
+        }
+    }
+}
+
+pub type OpenChangeHook = Box<dyn Fn(bool)>;
+pub type SnapHook = Box<dyn Fn(usize)>;
+
+/// Hooks to [Sheet]
+#[derive(Default)]
+pub struct SheetHooks {
+    /// Called with whether the sheet is open when it changes
+    pub open_change: Option<OpenChangeHook>,
+    /// Called with the index of the snap point the sheet settles at
+    pub snap: Option<SnapHook>,
+}
+
+/// Options of [Sheet]
+pub struct SheetOptions {
+    /// The dialog that is the sheet, opened as a modal
+    pub dialog: HtmlDialogElement,
+    pub side: Side,
+    /// Fractions of the sheet shown at each snap point, from 0 to 1
+    pub snap_points: Vec<f64>,
+    /// The index of the snap point the sheet opens at. Defaults to the last one
+    pub initial_snap: Option<usize>,
+    /// Whether the sheet can be dragged
+    pub draggable: bool,
+    /// The speed in pixels per millisecond a release has to exceed to fling the sheet to the next
+    /// snap point or close it
+    pub velocity_threshold: f64,
+    /// Whether escape and clicks on the backdrop close the sheet
+    pub dismissible: bool,
+    /// Whether the document can't be scrolled while the sheet is open
+    pub scroll_lock: bool,
+    pub hooks: SheetHooks,
+}
+
+impl SheetOptions {
+    pub fn builder() -> SheetOptionsBuilder {
+        SheetOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [SheetOptions]
+pub struct SheetOptionsBuilder {
+    dialog: Option<HtmlDialogElement>,
+    side: Side,
+    snap_points: Vec<f64>,
+    initial_snap: Option<usize>,
+    draggable: bool,
+    velocity_threshold: f64,
+    dismissible: bool,
+    scroll_lock: bool,
+    hooks: SheetHooks,
+}
+
+impl Default for SheetOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            dialog: None,
+            side: Side::default(),
+            snap_points: vec![1.0],
+            initial_snap: None,
+            draggable: true,
+            velocity_threshold: 0.5,
+            dismissible: true,
+            scroll_lock: true,
+            hooks: SheetHooks::default(),
+        }
+    }
+}
+
+impl SheetOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn dialog(mut self, dialog: HtmlDialogElement) -> Self {
+        self.dialog = Some(dialog);
+        self
+    }
+
+    pub fn side(mut self, side: Side) -> Self {
+        self.side = side;
+        self
+    }
+
+    pub fn snap_points(mut self, snap_points: Vec<f64>) -> Self {
+        self.snap_points = snap_points;
+        self
+    }
+
+    pub fn initial_snap(mut self, initial_snap: usize) -> Self {
+        self.initial_snap = Some(initial_snap);
+        self
+    }
+
+    pub fn draggable(mut self, draggable: bool) -> Self {
+        self.draggable = draggable;
+        self
+    }
+
+    pub fn velocity_threshold(mut self, velocity_threshold: f64) -> Self {
+        self.velocity_threshold = velocity_threshold;
+        self
+    }
+
+    pub fn dismissible(mut self, dismissible: bool) -> Self {
+        self.dismissible = dismissible;
+        self
+    }
+
+    pub fn scroll_lock(mut self, scroll_lock: bool) -> Self {
+        self.scroll_lock = scroll_lock;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: SheetHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [SheetOptions]
+    ///
+    /// Snap points are clamped to 0 to 1 and sorted
+    ///
+    /// # Panics
+    /// This method panics if dialog field is not set
+    pub fn build(self) -> SheetOptions {
+        let mut snap_points = self
+            .snap_points
+            .into_iter()
+            .map(|v| v.clamp(0.0, 1.0))
+            .collect::<Vec<_>>();
+        snap_points.sort_by(f64::total_cmp);
+        if snap_points.is_empty() {
+            snap_points.push(1.0);
+        }
+
+        SheetOptions {
+            dialog: self
+                .dialog
+                .expect("dialog must be set to build SheetOptions"),
+            side: self.side,
+            snap_points,
+            initial_snap: self.initial_snap,
+            draggable: self.draggable,
+            velocity_threshold: self.velocity_threshold,
+            dismissible: self.dismissible,
+            scroll_lock: self.scroll_lock,
+            hooks: self.hooks,
+        }
+    }
+}
+
+/// A pointer pressed on the sheet, which becomes a drag once it moves toward or away from the
+/// edge
+struct Gesture {
+    pointer_id: i32,
+    origin: (f64, f64),
+    /// The offset of the sheet when the pointer was pressed
+    offset: f64,
+    /// The scroll container between the pressed element and the sheet
+    scroller: Option<Element>,
+    is_dragging: bool,
+    /// The last two positions toward the edge and their times, to compute the velocity
+    samples: [(f64, f64); 2],
+}
+
+struct Inner {
+    options: Rc<SheetOptions>,
+    trap: FocusTrap,
+    is_open: bool,
+    /// Index of the snap point the sheet is at
+    snap: usize,
+    /// Distance in pixels the sheet is moved toward the edge from fully shown
+    offset: f64,
+    gesture: Option<Gesture>,
+    _scroll_lock: Option<ScrollLock>,
+    /// Listeners closing the dialog once the closing animation ends
+    _closing: Vec<EventListener>,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn size(&self) -> f64 {
+        let rect = self.options.dialog.get_bounding_client_rect();
+        if self.options.side.is_vertical() {
+            rect.height()
+        } else {
+            rect.width()
+        }
+    }
+
+    fn snap_offset(&self, index: usize) -> f64 {
+        self.size() * (1.0 - self.options.snap_points[index])
+    }
+
+    fn set_offset(&mut self, offset: f64) {
+        self.offset = offset;
+        let _ = self
+            .options
+            .dialog
+            .style()
+            .set_property("--seigi-sheet-offset", &format!("{offset}px"));
+    }
+
+    /// Returns the nearest scroll container from the element up to the sheet along its axis
+    fn scroller(&self, element: &Element) -> Option<Element> {
+        let dialog: &Element = self.options.dialog.as_ref();
+        let is_vertical = self.options.side.is_vertical();
+        let mut current = Some(element.clone());
+        while let Some(element) = current {
+            if element == *dialog {
+                return None;
+            }
+            let overflows = if is_vertical {
+                element.scroll_height() > element.client_height()
+            } else {
+                element.scroll_width() > element.client_width()
+            };
+            if overflows
+                && let Ok(Some(style)) = window().get_computed_style(&element)
+                && let Ok(overflow) = style.get_property_value(if is_vertical {
+                    "overflow-y"
+                } else {
+                    "overflow-x"
+                })
+                && matches!(overflow.as_str(), "auto" | "scroll")
+            {
+                return Some(element);
+            }
+            current = element.parent_element();
+        }
+        None
+    }
+}
+
+/// An instance of sheet
+///
+/// The sheet is a modal dialog, so the page behind is inert and `::backdrop` can be styled. Focus
+/// is trapped inside and returned when it closes. Escape and clicks on the backdrop close it
+/// unless it is not dismissible.
+///
+/// Pressing the sheet and moving toward its edge drags it. Releasing settles it at the nearest
+/// snap point, or closes it if it is nearer to hidden than to the first snap point; a fast
+/// enough release flings it to the next one instead. Inside a scroll container, the gesture
+/// scrolls the container until it is scrolled to the end, and drags the sheet after. Elements
+/// with `data-seigi-sheet-no-drag` and form controls never start a drag.
+///
+/// On touch screens, browsers take over gestures allowed by `touch-action`, so give the sheet
+/// `touch-action: none` outside its scroll containers.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-side** is set in the dialog to the side
+///
+/// **data-state** is set in the dialog to `open` or `closed`. The dialog is closed after the
+/// animation or transition started by `closed` ends
+///
+/// **data-dragging** is set in the dialog while it is dragged, to disable transitions of the
+/// offset
+///
+/// **data-snap** is set in the dialog to the index of the snap point it is at
+///
+/// # CSS Variables
+/// **--seigi-sheet-offset** is set in the dialog to the distance it is moved toward its edge,
+/// like `transform: translateY(var(--seigi-sheet-offset))` for a bottom sheet or
+/// `translateX(calc(var(--seigi-sheet-offset) * -1))` for a left one
+#[derive(Clone)]
+pub struct Sheet(Rc<Mutex<Inner>>);
+
+impl Sheet {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    fn options(&self) -> Rc<SheetOptions> {
+        self.0.lock().unwrap().options.clone()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.0.lock().unwrap().is_open
+    }
+
+    /// Returns the index of the snap point the sheet is at
+    pub fn snap(&self) -> usize {
+        self.0.lock().unwrap().snap
+    }
+
+    pub fn open(&self) {
+        let (options, trap) = {
+            let mut inner = self.0.lock().unwrap();
+            if inner.is_open {
+                return;
+            }
+            inner.is_open = true;
+            inner._closing.clear();
+
+            let options = inner.options.clone();
+            top_layer::show_modal(&options.dialog);
+            if options.scroll_lock {
+                inner._scroll_lock = Some(scroll_lock::lock());
+            }
+            inner.snap = options
+                .initial_snap
+                .unwrap_or(usize::MAX)
+                .min(options.snap_points.len() - 1);
+            let offset = inner.snap_offset(inner.snap);
+            inner.set_offset(offset);
+            let _ = options.dialog.set_attribute("data-state", "open");
+            let _ = options
+                .dialog
+                .set_attribute("data-snap", &inner.snap.to_string());
+            (options, inner.trap.clone())
+        };
+
+        audit::dialog("seigi_sheet", &options.dialog);
+        trap.activate();
+        if let Some(hook) = &options.hooks.open_change {
+            hook(true);
+        }
+    }
+
+    pub fn close(&self) {
+        let (options, trap) = {
+            let mut inner = self.0.lock().unwrap();
+            if !inner.is_open {
+                return;
+            }
+            inner.is_open = false;
+            inner.gesture = None;
+            inner._scroll_lock = None;
+
+            let options = inner.options.clone();
+            let dialog = options.dialog.clone();
+            let _ = dialog.remove_attribute("data-dragging");
+            let _ = dialog.set_attribute("data-state", "closed");
+            let size = inner.size();
+            inner.set_offset(size);
+
+            if is_animated(&dialog) {
+                let weak = Rc::downgrade(&self.0);
+                let finish = move |event: &Event| {
+                    let Some(sheet) = Sheet::from_weak(&weak) else {
+                        return;
+                    };
+                    let mut inner = sheet.0.lock().unwrap();
+                    // Animations of descendants bubble up too
+                    if event.target().as_ref() != Some(inner.options.dialog.as_ref()) {
+                        return;
+                    }
+                    if !inner.is_open {
+                        top_layer::close_modal(&inner.options.dialog);
+                    }
+                    inner._closing.clear();
+                };
+                inner._closing = vec![
+                    EventListener::new(&dialog, "animationend", finish.clone()),
+                    EventListener::new(&dialog, "transitionend", finish),
+                ];
+            } else {
+                top_layer::close_modal(&dialog);
+            }
+            (options, inner.trap.clone())
+        };
+
+        trap.deactivate();
+        if let Some(hook) = &options.hooks.open_change {
+            hook(false);
+        }
+    }
+
+    /// Moves the open sheet to the snap point
+    pub fn snap_to(&self, index: usize) {
+        let options = {
+            let mut inner = self.0.lock().unwrap();
+            if !inner.is_open || index >= inner.options.snap_points.len() {
+                return;
+            }
+            inner.snap = index;
+            let offset = inner.snap_offset(index);
+            inner.set_offset(offset);
+            let _ = inner
+                .options
+                .dialog
+                .set_attribute("data-snap", &index.to_string());
+            inner.options.clone()
+        };
+
+        if let Some(hook) = &options.hooks.snap {
+            hook(index);
+        }
+    }
+
+    fn handle_pointer_down(&self, event: &PointerEvent) {
+        if event.button() != 0 {
+            return;
+        }
+        let Some(target) = event.target().and_then(|v| v.dyn_into::<Element>().ok()) else {
+            return;
+        };
+        if target
+            .closest("[data-seigi-sheet-no-drag], input, textarea, select, [contenteditable]")
+            .ok()
+            .flatten()
+            .is_some()
+        {
+            return;
+        }
+
+        let mut inner = self.0.lock().unwrap();
+        if !inner.is_open || !inner.options.draggable {
+            return;
+        }
+        let scroller = inner.scroller(&target);
+        let time = event.time_stamp();
+        inner.gesture = Some(Gesture {
+            pointer_id: event.pointer_id(),
+            origin: (event.client_x() as f64, event.client_y() as f64),
+            offset: inner.offset,
+            scroller,
+            is_dragging: false,
+            samples: [(0.0, time), (0.0, time)],
+        });
+    }
+
+    fn handle_pointer_move(&self, event: &PointerEvent) {
+        let mut inner = self.0.lock().unwrap();
+        let side = inner.options.side;
+        let dialog = inner.options.dialog.clone();
+        let is_fully_open = inner.snap == inner.options.snap_points.len() - 1;
+        let Some(gesture) = inner
+            .gesture
+            .as_mut()
+            .filter(|v| v.pointer_id == event.pointer_id())
+        else {
+            return;
+        };
+
+        let (distance, across) = side.project(
+            event.client_x() as f64 - gesture.origin.0,
+            event.client_y() as f64 - gesture.origin.1,
+        );
+        if !gesture.is_dragging {
+            if distance.abs() < 4.0 {
+                return;
+            }
+            // Moves across the sheet, and moves the scroll container can still take, are left
+            // to the browser
+            let is_scrolling = gesture.scroller.as_ref().is_some_and(|scroller| {
+                (distance > 0.0 && !side.is_scrolled_to_edge(scroller))
+                    || (distance < 0.0 && is_fully_open)
+            });
+            if across.abs() > distance.abs() || is_scrolling {
+                inner.gesture = None;
+                return;
+            }
+            gesture.is_dragging = true;
+            let _ = dialog.set_pointer_capture(event.pointer_id());
+            let _ = dialog.set_attribute("data-dragging", "");
+        }
+
+        event.prevent_default();
+        gesture.samples = [gesture.samples[1], (distance, event.time_stamp())];
+        let offset = gesture.offset + distance;
+        // Dragging past fully shown is resisted
+        let offset = if offset < 0.0 { offset * 0.25 } else { offset };
+        inner.set_offset(offset);
+    }
+
+    fn handle_pointer_up(&self, event: &PointerEvent) {
+        let (target, should_close) = {
+            let mut inner = self.0.lock().unwrap();
+            let Some(gesture) = inner
+                .gesture
+                .take()
+                .filter(|v| v.pointer_id == event.pointer_id())
+            else {
+                return;
+            };
+            if !gesture.is_dragging {
+                return;
+            }
+            let _ = inner.options.dialog.remove_attribute("data-dragging");
+
+            let [(from, from_time), (to, to_time)] = gesture.samples;
+            let velocity = if to_time > from_time {
+                (to - from) / (to_time - from_time)
+            } else {
+                0.0
+            };
+
+            // Offsets of snap points, followed by the offset of being hidden
+            let size = inner.size();
+            let mut offsets = (0..inner.options.snap_points.len())
+                .map(|v| inner.snap_offset(v))
+                .collect::<Vec<_>>();
+            offsets.push(size);
+
+            let offset = inner.offset;
+            let target = if velocity.abs() > inner.options.velocity_threshold {
+                // The nearest offset in the direction of the fling, or the end of that direction
+                let is_ahead = |v: f64| {
+                    if velocity > 0.0 {
+                        v > offset
+                    } else {
+                        v < offset
+                    }
+                };
+                offsets
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, v)| is_ahead(**v))
+                    .min_by(|a, b| (a.1 - offset).abs().total_cmp(&(b.1 - offset).abs()))
+                    .map(|(index, _)| index)
+                    .unwrap_or(if velocity > 0.0 {
+                        offsets.len() - 1
+                    } else {
+                        offsets.len() - 2
+                    })
+            } else {
+                offsets
+                    .iter()
+                    .enumerate()
+                    .min_by(|a, b| (a.1 - offset).abs().total_cmp(&(b.1 - offset).abs()))
+                    .map(|(index, _)| index)
+                    .unwrap_or(0)
+            };
+            (target, target == offsets.len() - 1)
+        };
+
+        if should_close {
+            self.close();
+        } else {
+            self.snap_to(target);
+        }
+    }
+}
+
+pub fn create_sheet(options: SheetOptions) -> Sheet {
+    let dialog = options.dialog.clone();
+    let _ = dialog.set_attribute("data-side", options.side.as_str());
+    let _ = dialog.set_attribute("data-state", "closed");
+    // Contents of the sheet still scroll by touch while the document is locked
+    let _ = dialog.set_attribute(ALLOW_ATTRIBUTE, "");
+
+    let trap = seigi_focus::create(
+        FocusTrapOptions::builder()
+            .target(dialog.clone().into())
+            .initial_focus(InitialFocus::Auto)
+            .build(),
+    );
+
+    let sheet = Sheet(Rc::new(Mutex::new(Inner {
+        options: Rc::new(options),
+        trap,
+        is_open: false,
+        snap: 0,
+        offset: 0.0,
+        gesture: None,
+        _scroll_lock: None,
+        _closing: vec![],
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&sheet.0);
+    let pointer = |event_type: &'static str, f: fn(&Sheet, &PointerEvent)| {
+        let weak = weak.clone();
+        EventListener::new(&dialog, event_type, move |event| {
+            let (Some(sheet), Some(event)) =
+                (Sheet::from_weak(&weak), event.dyn_ref::<PointerEvent>())
+            else {
+                return;
+            };
+            f(&sheet, event);
+        })
+    };
+    let listeners = vec![
+        pointer("pointerdown", Sheet::handle_pointer_down),
+        pointer("pointermove", Sheet::handle_pointer_move),
+        pointer("pointerup", Sheet::handle_pointer_up),
+        pointer("pointercancel", |sheet, _| {
+            let snap = {
+                let mut inner = sheet.0.lock().unwrap();
+                inner.gesture = None;
+                let _ = inner.options.dialog.remove_attribute("data-dragging");
+                inner.snap
+            };
+            sheet.snap_to(snap);
+        }),
+        EventListener::new(&dialog, "click", {
+            let weak = weak.clone();
+            let dialog = dialog.clone();
+            move |event| {
+                let Some(sheet) = Sheet::from_weak(&weak) else {
+                    return;
+                };
+                // Clicks on the backdrop target the dialog itself, but so do clicks on its
+                // padding, so the position decides
+                let Some(event) = event.dyn_ref::<MouseEvent>() else {
+                    return;
+                };
+                if event.target().as_ref() != Some(dialog.as_ref()) {
+                    return;
+                }
+                let rect = dialog.get_bounding_client_rect();
+                let (x, y) = (event.client_x() as f64, event.client_y() as f64);
+                let is_outside =
+                    x < rect.left() || x > rect.right() || y < rect.top() || y > rect.bottom();
+                if is_outside && sheet.options().dismissible {
+                    sheet.close();
+                }
+            }
+        }),
+        EventListener::new_with_options(
+            &dialog,
+            "cancel",
+            gloo::events::EventListenerOptions::enable_prevent_default(),
+            {
+                let weak = weak.clone();
+                move |event| {
+                    let Some(sheet) = Sheet::from_weak(&weak) else {
+                        return;
+                    };
+                    // The dialog is closed after the closing animation instead
+                    event.prevent_default();
+                    if sheet.options().dismissible {
+                        sheet.close();
+                    }
+                }
+            },
+        ),
+        EventListener::new(&dialog, "close", {
+            let weak = weak.clone();
+            let dialog = dialog.clone();
+            move |_| {
+                let Some(sheet) = Sheet::from_weak(&weak) else {
+                    return;
+                };
+                // The event is queued, so the sheet might be open again by the time it fires
+                if dialog.open() {
+                    return;
+                }
+                // Closed by a form with method="dialog" or by calling close on the dialog
+                sheet.close();
+            }
+        }),
+    ];
+
+    sheet.0.lock().unwrap()._listeners = listeners;
+    sheet
+}
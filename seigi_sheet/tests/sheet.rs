@@ -0,0 +1,298 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use seigi_sheet::{Sheet, SheetHooks, SheetOptions, SheetOptionsBuilder, Side};
+use seigi_testkit::{Fixture, assert_focus_within, events, wait};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::HtmlDialogElement;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<dialog id="sheet" style="height: 400px; width: 300px; max-height: none; margin: 50px; padding: 0; border: 0">
+    <button id="action">Action</button>
+    <div id="content" style="height: 100px">Content</div>
+    <div id="handle" data-seigi-sheet-no-drag>No drag</div>
+    <div id="scroller" style="height: 100px; overflow-y: auto">
+        <div id="scrolled" style="height: 400px">Scrolled</div>
+    </div>
+</dialog>
+"#;
+
+/// Hook calls, as `open` or `close` for open changes and the index for snaps
+type Calls = Rc<RefCell<Vec<String>>>;
+
+fn dialog(fixture: &Fixture) -> HtmlDialogElement {
+    fixture.get("#sheet").unchecked_into()
+}
+
+fn options(fixture: &Fixture) -> SheetOptionsBuilder {
+    SheetOptions::builder().dialog(dialog(fixture))
+}
+
+fn sheet(options: SheetOptionsBuilder) -> (Sheet, Calls) {
+    let calls = Rc::new(RefCell::new(vec![]));
+    let sheet = seigi_sheet::create_sheet(
+        options
+            .hooks(SheetHooks {
+                open_change: Some(Box::new({
+                    let calls = calls.clone();
+                    move |is_open| {
+                        calls
+                            .borrow_mut()
+                            .push(if is_open { "open" } else { "close" }.to_string())
+                    }
+                })),
+                snap: Some(Box::new({
+                    let calls = calls.clone();
+                    move |index| calls.borrow_mut().push(index.to_string())
+                })),
+            })
+            .build(),
+    );
+    (sheet, calls)
+}
+
+fn offset(fixture: &Fixture) -> String {
+    dialog(fixture)
+        .style()
+        .get_property_value("--seigi-sheet-offset")
+        .unwrap_or_default()
+}
+
+fn pointer(target: &web_sys::HtmlElement, event_type: &str, y: f64) {
+    events::dispatch(target, &events::pointer_event(event_type, 100.0, y));
+}
+
+#[wasm_bindgen_test]
+async fn opens_at_snap_point_and_closes() {
+    let fixture = Fixture::new(MARKUP);
+    let (sheet, calls) = sheet(
+        options(&fixture)
+            .snap_points(vec![1.0, 0.25, 0.5])
+            .initial_snap(1),
+    );
+    let dialog = dialog(&fixture);
+    assert_eq!(dialog.get_attribute("data-side").as_deref(), Some("bottom"));
+    assert_eq!(
+        dialog.get_attribute("data-state").as_deref(),
+        Some("closed")
+    );
+
+    sheet.open();
+    wait::tick().await;
+    assert!(sheet.is_open());
+    assert!(dialog.open());
+    assert_eq!(dialog.get_attribute("data-state").as_deref(), Some("open"));
+    // Snap points are sorted, so 0.5 is the second one
+    assert_eq!(sheet.snap(), 1);
+    assert_eq!(dialog.get_attribute("data-snap").as_deref(), Some("1"));
+    assert_eq!(offset(&fixture), "200px");
+    assert_focus_within(&dialog);
+
+    sheet.snap_to(0);
+    assert_eq!(offset(&fixture), "300px");
+    assert_eq!(dialog.get_attribute("data-snap").as_deref(), Some("0"));
+    // Snap points out of range are ignored
+    sheet.snap_to(3);
+    assert_eq!(sheet.snap(), 0);
+
+    sheet.close();
+    assert!(!sheet.is_open());
+    assert!(!dialog.open());
+    assert_eq!(
+        dialog.get_attribute("data-state").as_deref(),
+        Some("closed")
+    );
+    assert_eq!(offset(&fixture), "400px");
+    assert_eq!(*calls.borrow(), ["open", "0", "close"]);
+}
+
+#[wasm_bindgen_test]
+fn escape_and_backdrop_close_dismissible_sheets() {
+    let fixture = Fixture::new(MARKUP);
+    let (sheet, _) = sheet(options(&fixture));
+    let dialog = dialog(&fixture);
+
+    sheet.open();
+    assert!(!events::fire(&dialog, "cancel"));
+    assert!(!sheet.is_open());
+
+    sheet.open();
+    // Clicks on the padding of the dialog target it as well
+    events::dispatch(&dialog, &events::mouse_event("click", 60.0, 60.0));
+    assert!(sheet.is_open());
+    events::dispatch(&dialog, &events::mouse_event("click", 10.0, 10.0));
+    assert!(!sheet.is_open());
+}
+
+#[wasm_bindgen_test]
+fn undismissible_sheets_stay_open() {
+    let fixture = Fixture::new(MARKUP);
+    let (sheet, _) = sheet(options(&fixture).dismissible(false));
+    let dialog = dialog(&fixture);
+
+    sheet.open();
+    // The dialog isn't closed by the browser either
+    assert!(!events::fire(&dialog, "cancel"));
+    events::dispatch(&dialog, &events::mouse_event("click", 10.0, 10.0));
+    assert!(sheet.is_open());
+    assert!(dialog.open());
+}
+
+#[wasm_bindgen_test]
+async fn slow_release_settles_at_nearest_snap_point() {
+    let fixture = Fixture::new(MARKUP);
+    let (sheet, calls) = sheet(options(&fixture).snap_points(vec![0.5, 1.0]));
+    let content = fixture.get("#content");
+    sheet.open();
+
+    pointer(&content, "pointerdown", 100.0);
+    pointer(&content, "pointermove", 250.0);
+    assert!(dialog(&fixture).has_attribute("data-dragging"));
+    assert_eq!(offset(&fixture), "150px");
+    wait::sleep(50).await;
+    pointer(&content, "pointermove", 260.0);
+    pointer(&content, "pointerup", 260.0);
+
+    assert!(!dialog(&fixture).has_attribute("data-dragging"));
+    assert_eq!(sheet.snap(), 0);
+    assert_eq!(offset(&fixture), "200px");
+    assert_eq!(calls.borrow().last().map(String::as_str), Some("0"));
+
+    // Nearer to hidden than to the first snap point closes
+    pointer(&content, "pointerdown", 100.0);
+    pointer(&content, "pointermove", 240.0);
+    wait::sleep(50).await;
+    pointer(&content, "pointermove", 250.0);
+    pointer(&content, "pointerup", 250.0);
+    assert!(!sheet.is_open());
+}
+
+#[wasm_bindgen_test]
+async fn fling_moves_to_adjacent_snap_point() {
+    let fixture = Fixture::new(MARKUP);
+    let (sheet, _) = sheet(options(&fixture).snap_points(vec![0.5, 1.0]));
+    let content = fixture.get("#content");
+    sheet.open();
+
+    // A short fling down from fully shown stops at the next snap point instead of closing
+    pointer(&content, "pointerdown", 100.0);
+    pointer(&content, "pointermove", 110.0);
+    wait::sleep(20).await;
+    pointer(&content, "pointermove", 150.0);
+    pointer(&content, "pointerup", 150.0);
+    assert!(sheet.is_open());
+    assert_eq!(sheet.snap(), 0);
+
+    // And a fling up returns to fully shown
+    pointer(&content, "pointerdown", 100.0);
+    pointer(&content, "pointermove", 90.0);
+    wait::sleep(20).await;
+    pointer(&content, "pointermove", 50.0);
+    pointer(&content, "pointerup", 50.0);
+    assert_eq!(sheet.snap(), 1);
+    assert_eq!(offset(&fixture), "0px");
+
+    // Flinging down from the first snap point closes
+    sheet.snap_to(0);
+    pointer(&content, "pointerdown", 100.0);
+    pointer(&content, "pointermove", 110.0);
+    wait::sleep(20).await;
+    pointer(&content, "pointermove", 150.0);
+    pointer(&content, "pointerup", 150.0);
+    assert!(!sheet.is_open());
+}
+
+#[wasm_bindgen_test]
+fn dragging_past_fully_shown_is_resisted() {
+    let fixture = Fixture::new(MARKUP);
+    let (sheet, _) = sheet(options(&fixture));
+    let content = fixture.get("#content");
+    sheet.open();
+
+    pointer(&content, "pointerdown", 100.0);
+    pointer(&content, "pointermove", 60.0);
+    assert_eq!(offset(&fixture), "-10px");
+
+    pointer(&content, "pointercancel", 60.0);
+    assert!(!dialog(&fixture).has_attribute("data-dragging"));
+    assert_eq!(offset(&fixture), "0px");
+}
+
+#[wasm_bindgen_test]
+fn some_presses_never_drag() {
+    let fixture = Fixture::new(MARKUP);
+    let (sheet, _) = sheet(options(&fixture));
+    sheet.open();
+
+    let handle = fixture.get("#handle");
+    pointer(&handle, "pointerdown", 100.0);
+    pointer(&handle, "pointermove", 200.0);
+    assert!(!dialog(&fixture).has_attribute("data-dragging"));
+
+    // Moves across the sheet are left to the browser
+    let content = fixture.get("#content");
+    pointer(&content, "pointerdown", 100.0);
+    events::dispatch(
+        &content,
+        &events::pointer_event("pointermove", 200.0, 110.0),
+    );
+    pointer(&content, "pointermove", 200.0);
+    assert!(!dialog(&fixture).has_attribute("data-dragging"));
+
+    // Scroll containers scroll until they are scrolled to the end
+    fixture.get("#scroller").set_scroll_top(100);
+    let scrolled = fixture.get("#scrolled");
+    pointer(&scrolled, "pointerdown", 100.0);
+    pointer(&scrolled, "pointermove", 200.0);
+    assert!(!dialog(&fixture).has_attribute("data-dragging"));
+
+    fixture.get("#scroller").set_scroll_top(0);
+    pointer(&scrolled, "pointerdown", 100.0);
+    pointer(&scrolled, "pointermove", 200.0);
+    assert!(dialog(&fixture).has_attribute("data-dragging"));
+}
+
+#[wasm_bindgen_test]
+fn side_is_reflected_and_drags_follow_it() {
+    let fixture = Fixture::new(MARKUP);
+    let (sheet, _) = sheet(options(&fixture).side(Side::Left));
+    assert_eq!(
+        dialog(&fixture).get_attribute("data-side").as_deref(),
+        Some("left")
+    );
+    sheet.open();
+
+    // Left sheets are dragged toward the left
+    let content = fixture.get("#content");
+    events::dispatch(
+        &content,
+        &events::pointer_event("pointerdown", 200.0, 100.0),
+    );
+    events::dispatch(
+        &content,
+        &events::pointer_event("pointermove", 150.0, 100.0),
+    );
+    assert_eq!(offset(&fixture), "50px");
+}
+
+#[wasm_bindgen_test]
+async fn reopening_survives_queued_close_event() {
+    let fixture = Fixture::new(MARKUP);
+    let (sheet, _) = sheet(options(&fixture));
+
+    sheet.open();
+    sheet.close();
+    sheet.open();
+    wait::tick().await;
+    assert!(sheet.is_open());
+    assert!(dialog(&fixture).open());
+
+    // Closing the dialog itself still closes the sheet
+    dialog(&fixture).close();
+    wait::tick().await;
+    assert!(!sheet.is_open());
+}
@@ -0,0 +1,375 @@
+//! Step indicators of a multi staged form
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::{events::EventListener, utils::document};
+pub use seigi_form::multi_stage::Orientation;
+use seigi_form::multi_stage::{Form, StageMeta};
+use seigi_utils::direction;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, KeyboardEvent, MouseEvent};
+
+/// Renders steps into the list from metadata of stages
+pub type RenderHook = Box<dyn Fn(&HtmlElement, &[StageMeta])>;
+
+/// Options of [Stepper]
+pub struct StepperOptions {
+    pub form: Form,
+    /// The element steps are rendered into, usually `<ol>`
+    pub list: HtmlElement,
+    pub orientation: Orientation,
+    pub render: RenderHook,
+}
+
+impl StepperOptions {
+    pub fn builder() -> StepperOptionsBuilder {
+        StepperOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [StepperOptions]
+pub struct StepperOptionsBuilder {
+    form: Option<Form>,
+    list: Option<HtmlElement>,
+    orientation: Option<Orientation>,
+    render: RenderHook,
+}
+
+impl Default for StepperOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            form: None,
+            list: None,
+            orientation: None,
+            render: Box::new(render_default),
+        }
+    }
+}
+
+impl StepperOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn form(mut self, form: Form) -> Self {
+        self.form = Some(form);
+        self
+    }
+
+    pub fn list(mut self, list: HtmlElement) -> Self {
+        self.list = Some(list);
+        self
+    }
+
+    /// Sets the direction steps are laid out in, which defaults to [Form::orientation]
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = Some(orientation);
+        self
+    }
+
+    pub fn render(mut self, render: RenderHook) -> Self {
+        self.render = render;
+        self
+    }
+
+    /// Builds into [StepperOptions]
+    ///
+    /// # Panics
+    /// This method panics if form or list field is not set
+    pub fn build(self) -> StepperOptions {
+        let form = self.form.expect("form must be set to build StepperOptions");
+        StepperOptions {
+            orientation: self.orientation.unwrap_or_else(|| form.orientation()),
+            form,
+            list: self.list.expect("list must be set to build StepperOptions"),
+            render: self.render,
+        }
+    }
+}
+
+/// Renders each stage as a list item containing a button with its number, label and
+/// description
+pub fn render_default(list: &HtmlElement, stages: &[StageMeta]) {
+    list.set_text_content(None);
+    let create = |tag: &str| {
+        document()
+            .create_element(tag)
+            .expect("failed to create element")
+    };
+    for (index, meta) in stages.iter().enumerate() {
+        let item = create("li");
+        let button = create("button");
+        let _ = button.set_attribute("type", "button");
+        let _ = button.set_attribute("data-seigi-step", &index.to_string());
+
+        let number = create("span");
+        let _ = number.set_attribute("data-seigi-step-number", "");
+        number.set_text_content(Some(&(index + 1).to_string()));
+        let _ = button.append_child(&number);
+        for (part, text) in [
+            ("data-seigi-step-label", &meta.label),
+            ("data-seigi-step-description", &meta.description),
+        ] {
+            if let Some(text) = text {
+                let element = create("span");
+                let _ = element.set_attribute(part, "");
+                element.set_text_content(Some(text));
+                let _ = button.append_child(&element);
+            }
+        }
+
+        let _ = item.append_child(&button);
+        let _ = list.append_child(&item);
+    }
+}
+
+struct Inner {
+    options: Rc<StepperOptions>,
+    subscription: u64,
+    _listeners: Vec<EventListener>,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.options.form.unsubscribe(self.subscription);
+    }
+}
+
+impl Inner {
+    fn steps(&self) -> Vec<(usize, HtmlElement)> {
+        let Ok(nodes) = self.options.list.query_selector_all("[data-seigi-step]") else {
+            return vec![];
+        };
+        (0..nodes.length())
+            .filter_map(|v| nodes.get(v))
+            .filter_map(|v| v.dyn_into::<HtmlElement>().ok())
+            .filter_map(|v| {
+                let index = v.get_attribute("data-seigi-step")?.parse().ok()?;
+                Some((index, v))
+            })
+            .collect()
+    }
+}
+
+/// An instance of stepper
+///
+/// Steps are rendered from metadata of stages given with [seigi_form::multi_stage::Stage::label]
+/// and reflect states of the form, updating as its stage changes, stages are marked complete
+/// and controls in it are edited. Pressing a step moves the form to it if
+/// [Form::can_navigate] allows. Steps are a single tab stop, navigated with arrow keys, Home and
+/// End.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-seigi-step** is set in steps to the index of their stage by [render_default]; custom
+/// renderers should set it too
+///
+/// **data-state** is set in steps to `current`, `complete`, `visited` or `upcoming`
+///
+/// **data-invalid** is set in steps of visited stages, other than the current one, with controls
+/// failing constraint validation
+///
+/// **data-disabled** and **aria-disabled** are set in steps the form can't move to
+///
+/// **aria-current** is set in the step of the current stage to `step`
+#[derive(Clone)]
+pub struct Stepper(Rc<Mutex<Inner>>);
+
+impl Stepper {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    /// Rerenders steps from metadata of stages
+    pub fn render(&self) {
+        {
+            let inner = self.0.lock().unwrap();
+            let options = &inner.options;
+            let stages = (0..options.form.len())
+                .filter_map(|v| options.form.meta(v))
+                .collect::<Vec<_>>();
+            (options.render)(&options.list, &stages);
+        }
+        self.update();
+    }
+
+    /// Updates states of steps from the form
+    pub fn update(&self) {
+        let inner = self.0.lock().unwrap();
+        let form = &inner.options.form;
+        let current = form.current();
+        let focused = document().active_element();
+        let steps = inner.steps();
+        // The focused step keeps the tab stop while navigating with keys
+        let has_focus = steps
+            .iter()
+            .any(|(_, v)| focused.as_ref().is_some_and(|f| f == &**v));
+
+        for (index, step) in &steps {
+            let index = *index;
+            let is_current = index == current;
+            let state = if is_current {
+                "current"
+            } else if form.is_complete(index) {
+                "complete"
+            } else if form.is_visited(index) {
+                "visited"
+            } else {
+                "upcoming"
+            };
+            let _ = step.set_attribute("data-state", state);
+
+            if is_current {
+                let _ = step.set_attribute("aria-current", "step");
+            } else {
+                let _ = step.remove_attribute("aria-current");
+            }
+
+            if !is_current && form.is_visited(index) && !form.is_valid(index) {
+                let _ = step.set_attribute("data-invalid", "");
+            } else {
+                let _ = step.remove_attribute("data-invalid");
+            }
+
+            // Disabled steps stay focusable, so they are still announced while navigating
+            if !is_current && !form.can_navigate(index) {
+                let _ = step.set_attribute("aria-disabled", "true");
+                let _ = step.set_attribute("data-disabled", "");
+            } else {
+                let _ = step.remove_attribute("aria-disabled");
+                let _ = step.remove_attribute("data-disabled");
+            }
+
+            if !has_focus {
+                step.set_tab_index(if is_current { 0 } else { -1 });
+            }
+        }
+    }
+
+    fn step_of(&self, target: &Element) -> Option<(usize, HtmlElement)> {
+        let step = target.closest("[data-seigi-step]").ok().flatten()?;
+        self.0
+            .lock()
+            .unwrap()
+            .steps()
+            .into_iter()
+            .find(|(_, v)| **v == step)
+    }
+
+    fn handle_click(&self, event: &MouseEvent) {
+        let Some((index, _)) = event
+            .target()
+            .and_then(|v| v.dyn_into::<Element>().ok())
+            .and_then(|v| self.step_of(&v))
+        else {
+            return;
+        };
+        let form = self.0.lock().unwrap().options.form.clone();
+        form.navigate(index);
+    }
+
+    fn handle_key_down(&self, event: &KeyboardEvent) {
+        let Some((_, step)) = event
+            .target()
+            .and_then(|v| v.dyn_into::<Element>().ok())
+            .and_then(|v| self.step_of(&v))
+        else {
+            return;
+        };
+        let (steps, orientation) = {
+            let inner = self.0.lock().unwrap();
+            (inner.steps(), inner.options.orientation)
+        };
+        let Some(position) = steps.iter().position(|(_, v)| *v == step) else {
+            return;
+        };
+        let (previous, next) = match orientation {
+            Orientation::Horizontal => direction::resolve(&step).arrow_keys(),
+            Orientation::Vertical => ("ArrowUp", "ArrowDown"),
+        };
+
+        let key = event.key();
+        let target = match key.as_str() {
+            v if v == previous => position.saturating_sub(1),
+            v if v == next => (position + 1).min(steps.len() - 1),
+            "Home" => 0,
+            "End" => steps.len() - 1,
+            _ => return,
+        };
+        event.prevent_default();
+        for (index, (_, step)) in steps.iter().enumerate() {
+            step.set_tab_index(if index == target { 0 } else { -1 });
+        }
+        let _ = steps[target].1.focus();
+    }
+}
+
+pub fn create_stepper(options: StepperOptions) -> Stepper {
+    let list = options.list.clone();
+    let container = options.form.container();
+    let form = options.form.clone();
+
+    let stepper = Stepper(Rc::new(Mutex::new(Inner {
+        options: Rc::new(options),
+        subscription: 0,
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&stepper.0);
+    let subscription = form.subscribe(Box::new({
+        let weak = weak.clone();
+        move |_| {
+            if let Some(stepper) = Stepper::from_weak(&weak) {
+                stepper.update();
+            }
+        }
+    }));
+
+    let update = |event_type: &'static str| {
+        let weak = weak.clone();
+        EventListener::new(&container, event_type, move |_| {
+            if let Some(stepper) = Stepper::from_weak(&weak) {
+                stepper.update();
+            }
+        })
+    };
+    let listeners = vec![
+        update("input"),
+        update("change"),
+        EventListener::new(&list, "click", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(stepper), Some(event)) =
+                    (Stepper::from_weak(&weak), event.dyn_ref::<MouseEvent>())
+                else {
+                    return;
+                };
+                stepper.handle_click(event);
+            }
+        }),
+        EventListener::new(&list, "keydown", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(stepper), Some(event)) =
+                    (Stepper::from_weak(&weak), event.dyn_ref::<KeyboardEvent>())
+                else {
+                    return;
+                };
+                stepper.handle_key_down(event);
+            }
+        }),
+    ];
+
+    {
+        let mut inner = stepper.0.lock().unwrap();
+        inner.subscription = subscription;
+        inner._listeners = listeners;
+    }
+    stepper.render();
+    stepper
+}
@@ -0,0 +1,219 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_form::multi_stage::{Form, Stage};
+use seigi_stepper::{Orientation, Stepper, StepperOptions};
+use seigi_testkit::{
+    Fixture, assert_focused,
+    events::{self, Modifiers},
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::{HtmlElement, HtmlInputElement};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<ol id="steps"></ol>
+<div id="form">
+    <div data-seigi-stage><input name="name"></div>
+    <div data-seigi-stage><input id="email" name="email" required></div>
+    <div data-seigi-stage><button>Submit</button></div>
+</div>
+"#;
+
+fn form(fixture: &Fixture) -> Form {
+    let labels = [
+        ("Account", Some("Who you are")),
+        ("Contact", None),
+        ("Review", None),
+    ];
+    let form = Form::builder()
+        .container(fixture.get("#form"))
+        .add_stages(
+            fixture
+                .get_all("[data-seigi-stage]")
+                .into_iter()
+                .zip(labels)
+                .map(|(container, (label, description))| {
+                    let stage = Stage::from_container(container).label(label);
+                    match description {
+                        Some(v) => stage.description(v),
+                        None => stage,
+                    }
+                }),
+        )
+        .build()
+        .unwrap();
+    form.initialize();
+    form.activate();
+    form
+}
+
+fn stepper(fixture: &Fixture, form: &Form, orientation: Option<Orientation>) -> Stepper {
+    let builder = StepperOptions::builder()
+        .form(form.clone())
+        .list(fixture.get("#steps"));
+    let builder = match orientation {
+        Some(v) => builder.orientation(v),
+        None => builder,
+    };
+    seigi_stepper::create_stepper(builder.build())
+}
+
+fn steps(fixture: &Fixture) -> Vec<HtmlElement> {
+    fixture.get_all("[data-seigi-step]")
+}
+
+fn states(fixture: &Fixture) -> Vec<String> {
+    steps(fixture)
+        .iter()
+        .map(|v| v.get_attribute("data-state").unwrap_or_default())
+        .collect()
+}
+
+fn press(target: &HtmlElement, key: &str) -> bool {
+    events::key_down(target, key, Modifiers::default())
+}
+
+#[wasm_bindgen_test]
+fn steps_are_rendered_from_stages() {
+    let fixture = Fixture::new(MARKUP);
+    let form = form(&fixture);
+    let _stepper = stepper(&fixture, &form, None);
+
+    let steps = steps(&fixture);
+    assert_eq!(steps.len(), 3);
+    assert_eq!(
+        fixture
+            .get_all("[data-seigi-step-label]")
+            .iter()
+            .map(|v| v.text_content().unwrap_or_default())
+            .collect::<Vec<_>>(),
+        ["Account", "Contact", "Review"]
+    );
+    assert_eq!(fixture.get_all("[data-seigi-step-description]").len(), 1);
+    assert_eq!(
+        steps[1]
+            .query_selector("[data-seigi-step-number]")
+            .unwrap()
+            .unwrap()
+            .text_content()
+            .as_deref(),
+        Some("2")
+    );
+
+    assert_eq!(states(&fixture), ["current", "upcoming", "upcoming"]);
+    assert_eq!(
+        steps[0].get_attribute("aria-current").as_deref(),
+        Some("step")
+    );
+    assert_eq!(
+        steps.iter().map(|v| v.tab_index()).collect::<Vec<_>>(),
+        [0, -1, -1]
+    );
+    // Only the next stage can be moved to
+    assert!(!steps[1].has_attribute("aria-disabled"));
+    assert_eq!(
+        steps[2].get_attribute("aria-disabled").as_deref(),
+        Some("true")
+    );
+    assert!(steps[2].has_attribute("data-disabled"));
+}
+
+#[wasm_bindgen_test]
+fn steps_follow_form() {
+    let fixture = Fixture::new(MARKUP);
+    let form = form(&fixture);
+    let _stepper = stepper(&fixture, &form, None);
+
+    events::click(&steps(&fixture)[1]);
+    assert_eq!(form.current(), 1);
+    assert_eq!(states(&fixture), ["visited", "current", "upcoming"]);
+    assert_eq!(steps(&fixture)[1].tab_index(), 0);
+
+    form.set_complete(0, true);
+    assert_eq!(states(&fixture), ["complete", "current", "upcoming"]);
+}
+
+#[wasm_bindgen_test]
+fn disabled_steps_keep_stage() {
+    let fixture = Fixture::new(MARKUP);
+    let form = form(&fixture);
+    let _stepper = stepper(&fixture, &form, None);
+
+    events::click(&steps(&fixture)[2]);
+    assert_eq!(form.current(), 0);
+    assert_eq!(states(&fixture), ["current", "upcoming", "upcoming"]);
+}
+
+#[wasm_bindgen_test]
+fn visited_stages_with_invalid_controls_are_marked() {
+    let fixture = Fixture::new(MARKUP);
+    let form = form(&fixture);
+    let _stepper = stepper(&fixture, &form, None);
+
+    form.stage(1);
+    form.previous();
+    let step = &steps(&fixture)[1];
+    assert!(step.has_attribute("data-invalid"));
+    assert!(!steps(&fixture)[0].has_attribute("data-invalid"));
+
+    // Editing controls updates steps
+    let email = fixture.get("#email");
+    email.unchecked_ref::<HtmlInputElement>().set_value("a@b.c");
+    events::fire(&email, "input");
+    assert!(!step.has_attribute("data-invalid"));
+}
+
+#[wasm_bindgen_test]
+fn arrow_keys_move_between_steps() {
+    let fixture = Fixture::new(MARKUP);
+    let form = form(&fixture);
+    let _stepper = stepper(&fixture, &form, None);
+    let steps = steps(&fixture);
+    let _ = steps[0].focus();
+
+    assert!(!press(&steps[0], "ArrowRight"));
+    assert_focused(&steps[1]);
+    assert_eq!(
+        steps.iter().map(|v| v.tab_index()).collect::<Vec<_>>(),
+        [-1, 0, -1]
+    );
+    press(&steps[1], "End");
+    assert_focused(&steps[2]);
+    // The last step stays focused
+    press(&steps[2], "ArrowRight");
+    assert_focused(&steps[2]);
+    press(&steps[2], "Home");
+    assert_focused(&steps[0]);
+
+    // Navigating doesn't move the form
+    assert_eq!(form.current(), 0);
+    // Keys along the other axis are left alone
+    assert!(press(&steps[0], "ArrowDown"));
+}
+
+#[wasm_bindgen_test]
+fn vertical_steps_move_with_up_and_down() {
+    let fixture = Fixture::new(MARKUP);
+    let form = form(&fixture);
+    let _stepper = stepper(&fixture, &form, Some(Orientation::Vertical));
+    let steps = steps(&fixture);
+    let _ = steps[0].focus();
+
+    assert!(!press(&steps[0], "ArrowDown"));
+    assert_focused(&steps[1]);
+    press(&steps[1], "ArrowUp");
+    assert_focused(&steps[0]);
+    assert!(press(&steps[0], "ArrowRight"));
+}
+
+#[wasm_bindgen_test]
+fn dropped_stepper_stops_following_form() {
+    let fixture = Fixture::new(MARKUP);
+    let form = form(&fixture);
+    drop(stepper(&fixture, &form, None));
+
+    form.stage(1);
+    assert_eq!(states(&fixture), ["current", "upcoming", "upcoming"]);
+}
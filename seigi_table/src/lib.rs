@@ -0,0 +1,79 @@
+//! Headless data grid interactions on an existing table
+//!
+//! Each primitive works on a `<table>` or on any element using ARIA grid roles (`row`,
+//! `columnheader`, `rowheader`, `gridcell`), so rows can be rendered by anything as long as the
+//! primitive is refreshed after rows change.
+
+pub mod navigation;
+pub mod resize;
+pub mod selection;
+pub mod sort;
+pub mod sticky;
+
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement};
+
+const ROW_SELECTOR: &str = "tr, [role=row]";
+const CELL_SELECTOR: &str = "td, th, [role=gridcell], [role=columnheader], [role=rowheader]";
+
+/// Returns every row of the table in DOM order, including header rows
+pub fn rows(table: &Element) -> Vec<HtmlElement> {
+    let Ok(nodes) = table.query_selector_all(ROW_SELECTOR) else {
+        return vec![];
+    };
+    (0..nodes.length())
+        .filter_map(|v| nodes.get(v))
+        .filter_map(|v| v.dyn_into::<HtmlElement>().ok())
+        .collect()
+}
+
+/// Returns whether the row is a header row
+pub fn is_header_row(row: &Element) -> bool {
+    row.closest("thead").ok().flatten().is_some()
+        || row
+            .query_selector(":scope > [role=columnheader]")
+            .ok()
+            .flatten()
+            .is_some()
+}
+
+/// Returns rows of the table that are not header rows
+pub fn body_rows(table: &Element) -> Vec<HtmlElement> {
+    rows(table)
+        .into_iter()
+        .filter(|v| !is_header_row(v))
+        .collect()
+}
+
+/// Returns cells of the row in DOM order
+pub fn cells(row: &Element) -> Vec<HtmlElement> {
+    let children = row.children();
+    (0..children.length())
+        .filter_map(|v| children.item(v))
+        .filter(|v| v.matches(CELL_SELECTOR).unwrap_or(false))
+        .filter_map(|v| v.dyn_into::<HtmlElement>().ok())
+        .collect()
+}
+
+/// Returns the closest cell containing the element, if it is in the table
+pub(crate) fn closest_cell(table: &Element, element: &Element) -> Option<HtmlElement> {
+    let cell = element.closest(CELL_SELECTOR).ok().flatten()?;
+    table
+        .contains(Some(&cell))
+        .then(|| cell.dyn_into::<HtmlElement>().ok())
+        .flatten()
+}
+
+/// Returns the closest row containing the element, if it is in the table
+pub(crate) fn closest_row(table: &Element, element: &Element) -> Option<HtmlElement> {
+    let row = element.closest(ROW_SELECTOR).ok().flatten()?;
+    table
+        .contains(Some(&row))
+        .then(|| row.dyn_into::<HtmlElement>().ok())
+        .flatten()
+}
+
+/// Returns the element the event is dispatched to
+pub(crate) fn event_target(event: &web_sys::Event) -> Option<Element> {
+    event.target().and_then(|v| v.dyn_into::<Element>().ok())
+}
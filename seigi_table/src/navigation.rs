@@ -0,0 +1,263 @@
+//! Keyboard cell navigation of ARIA grids
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, KeyboardEvent};
+
+use crate::{cells, closest_cell, event_target, rows};
+
+/// Options of [GridNavigation]
+pub struct GridNavigationOptions {
+    /// The table or element with `role="grid"`
+    pub table: HtmlElement,
+    /// The number of rows PageUp/PageDown move by
+    pub page_size: usize,
+}
+
+impl GridNavigationOptions {
+    pub fn builder() -> GridNavigationOptionsBuilder {
+        GridNavigationOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [GridNavigationOptions]
+pub struct GridNavigationOptionsBuilder {
+    table: Option<HtmlElement>,
+    page_size: usize,
+}
+
+impl Default for GridNavigationOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            table: None,
+            page_size: 10,
+        }
+    }
+}
+
+impl GridNavigationOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn table(mut self, table: HtmlElement) -> Self {
+        self.table = Some(table);
+        self
+    }
+
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Builds into [GridNavigationOptions]
+    ///
+    /// # Panics
+    /// This method panics if table field is not set
+    pub fn build(self) -> GridNavigationOptions {
+        GridNavigationOptions {
+            table: self
+                .table
+                .expect("table must be set to build GridNavigationOptions"),
+            page_size: self.page_size.max(1),
+        }
+    }
+}
+
+struct Inner {
+    options: GridNavigationOptions,
+    /// Row and column of the cell in the tab sequence
+    active: (usize, usize),
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn grid(&self) -> Vec<Vec<HtmlElement>> {
+        rows(&self.options.table)
+            .iter()
+            .map(|v| cells(v))
+            .filter(|v| !v.is_empty())
+            .collect()
+    }
+
+    fn position_of(&self, cell: &HtmlElement) -> Option<(usize, usize)> {
+        self.grid().iter().enumerate().find_map(|(row, cells)| {
+            cells
+                .iter()
+                .position(|v| v == cell)
+                .map(|column| (row, column))
+        })
+    }
+
+    /// Puts the active cell in the tab sequence and every other cell out of it
+    fn reflect(&mut self) {
+        let grid = self.grid();
+        if grid.is_empty() {
+            return;
+        }
+
+        let row = self.active.0.min(grid.len() - 1);
+        let column = self.active.1.min(grid[row].len() - 1);
+        self.active = (row, column);
+        for (index, cell) in grid
+            .iter()
+            .enumerate()
+            .flat_map(|(r, v)| v.iter().enumerate().map(move |(c, cell)| ((r, c), cell)))
+        {
+            cell.set_tab_index(if index == (row, column) { 0 } else { -1 });
+        }
+    }
+}
+
+/// An instance of grid navigation
+///
+/// The grid is a single tab stop, and arrow keys move focus between cells following the ARIA grid
+/// pattern. Home/End move to the ends of the row, Ctrl+Home/Ctrl+End to the ends of the grid, and
+/// PageUp/PageDown by page_size rows. Keys pressed in text fields inside cells are left to them.
+///
+/// Call [GridNavigation::refresh] after rows are added or removed.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+#[derive(Clone)]
+pub struct GridNavigation(Rc<Mutex<Inner>>);
+
+impl GridNavigation {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    /// Returns the row and column of the cell in the tab sequence
+    pub fn active(&self) -> (usize, usize) {
+        self.0.lock().unwrap().active
+    }
+
+    /// Moves the tab sequence to the cell and focuses it
+    pub fn focus_cell(&self, row: usize, column: usize) {
+        let cell = {
+            let mut inner = self.0.lock().unwrap();
+            inner.active = (row, column);
+            inner.reflect();
+            let (row, column) = inner.active;
+            inner.grid().get(row).and_then(|v| v.get(column)).cloned()
+        };
+
+        if let Some(cell) = cell {
+            let _ = cell.focus();
+        }
+    }
+
+    /// Reapplies the tab sequence to rows added or removed since
+    pub fn refresh(&self) {
+        self.0.lock().unwrap().reflect();
+    }
+
+    fn handle_key_down(&self, event: &KeyboardEvent) {
+        let Some(target) = event_target(event) else {
+            return;
+        };
+        // Text fields need arrow keys to move the caret
+        if target
+            .matches("input, textarea, select, [contenteditable]")
+            .unwrap_or(false)
+        {
+            return;
+        }
+
+        let (position, grid, page_size) = {
+            let inner = self.0.lock().unwrap();
+            let Some(cell) = closest_cell(&inner.options.table, &target) else {
+                return;
+            };
+            let Some(position) = inner.position_of(&cell) else {
+                return;
+            };
+            (position, inner.grid(), inner.options.page_size)
+        };
+
+        let (row, column) = position;
+        let last_row = grid.len() - 1;
+        let is_control = event.ctrl_key() || event.meta_key();
+        let next = match event.key().as_str() {
+            "ArrowUp" => (row.saturating_sub(1), column),
+            "ArrowDown" => ((row + 1).min(last_row), column),
+            "ArrowLeft" => (row, column.saturating_sub(1)),
+            "ArrowRight" => (row, column + 1),
+            "PageUp" => (row.saturating_sub(page_size), column),
+            "PageDown" => ((row + page_size).min(last_row), column),
+            "Home" if is_control => (0, 0),
+            "End" if is_control => (last_row, usize::MAX),
+            "Home" => (row, 0),
+            "End" => (row, usize::MAX),
+            _ => return,
+        };
+
+        event.prevent_default();
+        // Rows might have fewer cells, so the column is clamped per row
+        let column = next.1.min(grid[next.0].len() - 1);
+        self.focus_cell(next.0, column);
+    }
+}
+
+pub fn create_grid_navigation(options: GridNavigationOptions) -> GridNavigation {
+    let table = options.table.clone();
+    if !table.has_attribute("role") {
+        let _ = table.set_attribute("role", "grid");
+    }
+
+    let navigation = GridNavigation(Rc::new(Mutex::new(Inner {
+        options,
+        active: (0, 0),
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&navigation.0);
+    let listeners = vec![
+        EventListener::new(&table, "keydown", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(navigation), Some(event)) = (
+                    GridNavigation::from_weak(&weak),
+                    event.dyn_ref::<KeyboardEvent>(),
+                ) else {
+                    return;
+                };
+                navigation.handle_key_down(event);
+            }
+        }),
+        // Cells focused by clicks or by elements inside them become the tab stop
+        EventListener::new(&table, "focusin", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(navigation), Some(target)) =
+                    (GridNavigation::from_weak(&weak), event_target(event))
+                else {
+                    return;
+                };
+                let mut inner = navigation.0.lock().unwrap();
+                let Some(position) = closest_cell(&inner.options.table, &target)
+                    .and_then(|cell| inner.position_of(&cell))
+                else {
+                    return;
+                };
+                if inner.active != position {
+                    inner.active = position;
+                    inner.reflect();
+                }
+            }
+        }),
+    ];
+
+    {
+        let mut inner = navigation.0.lock().unwrap();
+        inner._listeners = listeners;
+        inner.reflect();
+    }
+
+    navigation
+}
@@ -0,0 +1,354 @@
+//! Column resize handles
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, KeyboardEvent, PointerEvent};
+
+use crate::{cells, event_target};
+
+pub type ResizeHook = Box<dyn Fn(usize, f64)>;
+
+/// Hooks to [ColumnResize]
+#[derive(Default)]
+pub struct ColumnResizeHooks {
+    /// Called with the column index and the new width in pixels while a column is resized
+    pub resize: Option<ResizeHook>,
+}
+
+/// Options of [ColumnResize]
+pub struct ColumnResizeOptions {
+    /// The table containing handles with `data-seigi-column-resize` inside header cells
+    pub table: HtmlElement,
+    pub min_width: f64,
+    pub max_width: Option<f64>,
+    /// The width arrow keys change a column by
+    pub step: f64,
+    pub hooks: ColumnResizeHooks,
+}
+
+impl ColumnResizeOptions {
+    pub fn builder() -> ColumnResizeOptionsBuilder {
+        ColumnResizeOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [ColumnResizeOptions]
+pub struct ColumnResizeOptionsBuilder {
+    table: Option<HtmlElement>,
+    min_width: f64,
+    max_width: Option<f64>,
+    step: f64,
+    hooks: ColumnResizeHooks,
+}
+
+impl Default for ColumnResizeOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            table: None,
+            min_width: 40.0,
+            max_width: None,
+            step: 10.0,
+            hooks: ColumnResizeHooks::default(),
+        }
+    }
+}
+
+impl ColumnResizeOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn table(mut self, table: HtmlElement) -> Self {
+        self.table = Some(table);
+        self
+    }
+
+    pub fn min_width(mut self, min_width: f64) -> Self {
+        self.min_width = min_width;
+        self
+    }
+
+    pub fn max_width(mut self, max_width: f64) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    pub fn step(mut self, step: f64) -> Self {
+        self.step = step;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: ColumnResizeHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [ColumnResizeOptions]
+    ///
+    /// # Panics
+    /// This method panics if table field is not set
+    pub fn build(self) -> ColumnResizeOptions {
+        ColumnResizeOptions {
+            table: self
+                .table
+                .expect("table must be set to build ColumnResizeOptions"),
+            min_width: self.min_width,
+            max_width: self.max_width,
+            step: self.step,
+            hooks: self.hooks,
+        }
+    }
+}
+
+struct Drag {
+    handle: HtmlElement,
+    pointer_id: i32,
+    origin: f64,
+    width: f64,
+}
+
+struct Inner {
+    options: Rc<ColumnResizeOptions>,
+    drag: Option<Drag>,
+    _listeners: Vec<EventListener>,
+}
+
+/// Returns the header cell of the handle and its column index
+fn column_of(handle: &Element) -> Option<(HtmlElement, usize)> {
+    let cell = handle
+        .closest("th, [role=columnheader]")
+        .ok()
+        .flatten()?
+        .dyn_into::<HtmlElement>()
+        .ok()?;
+    let index = cells(&cell.parent_element()?)
+        .iter()
+        .position(|v| *v == cell)?;
+    Some((cell, index))
+}
+
+/// An instance of column resize
+///
+/// Handles are dragged with a pointer, or focused and moved with arrow keys. The width is set in
+/// the header cell, so the table should use `table-layout: fixed` for it to apply exactly.
+///
+/// Call [ColumnResize::refresh] after handles are added.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-resizing** is set in the handle and the header cell while the handle is dragged
+///
+/// Handles are given `role="separator"` with **aria-valuenow** of the column width
+#[derive(Clone)]
+pub struct ColumnResize(Rc<Mutex<Inner>>);
+
+impl ColumnResize {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    fn options(&self) -> Rc<ColumnResizeOptions> {
+        self.0.lock().unwrap().options.clone()
+    }
+
+    /// Sets the width of the column in pixels, clamped to min/max width
+    pub fn set_width(&self, column: usize, width: f64) {
+        let options = self.options();
+        let Some(handle) = self
+            .handles()
+            .into_iter()
+            .find(|v| column_of(v).is_some_and(|(_, index)| index == column))
+        else {
+            return;
+        };
+        self.apply(&options, &handle, width);
+    }
+
+    /// Applies accessibility attributes to handles added since
+    pub fn refresh(&self) {
+        let options = self.options();
+        for handle in self.handles() {
+            let _ = handle.set_attribute("role", "separator");
+            let _ = handle.set_attribute("aria-orientation", "vertical");
+            let _ = handle.set_attribute("aria-valuemin", &options.min_width.to_string());
+            if let Some(max_width) = options.max_width {
+                let _ = handle.set_attribute("aria-valuemax", &max_width.to_string());
+            }
+            if !handle.has_attribute("tabindex") {
+                handle.set_tab_index(0);
+            }
+            if let Some((cell, _)) = column_of(&handle) {
+                let width = cell.get_bounding_client_rect().width().round();
+                let _ = handle.set_attribute("aria-valuenow", &width.to_string());
+            }
+        }
+    }
+
+    fn handles(&self) -> Vec<HtmlElement> {
+        let options = self.options();
+        let Ok(nodes) = options
+            .table
+            .query_selector_all("[data-seigi-column-resize]")
+        else {
+            return vec![];
+        };
+        (0..nodes.length())
+            .filter_map(|v| nodes.get(v))
+            .filter_map(|v| v.dyn_into::<HtmlElement>().ok())
+            .collect()
+    }
+
+    fn apply(&self, options: &ColumnResizeOptions, handle: &HtmlElement, width: f64) {
+        let Some((cell, column)) = column_of(handle) else {
+            return;
+        };
+        let width = width
+            .max(options.min_width)
+            .min(options.max_width.unwrap_or(f64::INFINITY))
+            .round();
+
+        let _ = cell.style().set_property("width", &format!("{width}px"));
+        let _ = handle.set_attribute("aria-valuenow", &width.to_string());
+        if let Some(hook) = &options.hooks.resize {
+            hook(column, width);
+        }
+    }
+
+    fn handle_pointer_down(&self, event: &PointerEvent) {
+        let Some(handle) = event_target(event)
+            .and_then(|v| v.closest("[data-seigi-column-resize]").ok().flatten())
+            .and_then(|v| v.dyn_into::<HtmlElement>().ok())
+        else {
+            return;
+        };
+        let Some((cell, _)) = column_of(&handle) else {
+            return;
+        };
+        if event.button() != 0 {
+            return;
+        }
+
+        // Text selection would follow the pointer otherwise
+        event.prevent_default();
+        let _ = handle.set_pointer_capture(event.pointer_id());
+        let _ = handle.set_attribute("data-resizing", "");
+        let _ = cell.set_attribute("data-resizing", "");
+        self.0.lock().unwrap().drag = Some(Drag {
+            handle,
+            pointer_id: event.pointer_id(),
+            origin: event.client_x() as f64,
+            width: cell.get_bounding_client_rect().width(),
+        });
+    }
+
+    fn handle_pointer_move(&self, event: &PointerEvent) {
+        let (options, handle, width) = {
+            let inner = self.0.lock().unwrap();
+            let Some(drag) = inner
+                .drag
+                .as_ref()
+                .filter(|v| v.pointer_id == event.pointer_id())
+            else {
+                return;
+            };
+            (
+                inner.options.clone(),
+                drag.handle.clone(),
+                drag.width + event.client_x() as f64 - drag.origin,
+            )
+        };
+        self.apply(&options, &handle, width);
+    }
+
+    fn handle_pointer_up(&self) {
+        let Some(drag) = self.0.lock().unwrap().drag.take() else {
+            return;
+        };
+        let _ = drag.handle.remove_attribute("data-resizing");
+        if let Some((cell, _)) = column_of(&drag.handle) {
+            let _ = cell.remove_attribute("data-resizing");
+        }
+    }
+
+    fn handle_key_down(&self, event: &KeyboardEvent) {
+        let Some(handle) = event_target(event)
+            .filter(|v| v.has_attribute("data-seigi-column-resize"))
+            .and_then(|v| v.dyn_into::<HtmlElement>().ok())
+        else {
+            return;
+        };
+        let Some((cell, _)) = column_of(&handle) else {
+            return;
+        };
+
+        let options = self.options();
+        let width = cell.get_bounding_client_rect().width();
+        let width = match event.key().as_str() {
+            "ArrowLeft" => width - options.step,
+            "ArrowRight" => width + options.step,
+            "Home" => options.min_width,
+            "End" => match options.max_width {
+                Some(max_width) => max_width,
+                None => return,
+            },
+            _ => return,
+        };
+
+        event.prevent_default();
+        // Grid navigation would otherwise move focus away from the handle
+        event.stop_propagation();
+        self.apply(&options, &handle, width);
+    }
+}
+
+pub fn create_column_resize(options: ColumnResizeOptions) -> ColumnResize {
+    let table = options.table.clone();
+    let resize = ColumnResize(Rc::new(Mutex::new(Inner {
+        options: Rc::new(options),
+        drag: None,
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&resize.0);
+    let pointer = |event_type: &'static str, f: fn(&ColumnResize, &PointerEvent)| {
+        let weak = weak.clone();
+        EventListener::new(&table, event_type, move |event| {
+            let (Some(resize), Some(event)) = (
+                ColumnResize::from_weak(&weak),
+                event.dyn_ref::<PointerEvent>(),
+            ) else {
+                return;
+            };
+            f(&resize, event);
+        })
+    };
+    let listeners = vec![
+        pointer("pointerdown", ColumnResize::handle_pointer_down),
+        pointer("pointermove", ColumnResize::handle_pointer_move),
+        pointer("pointerup", |resize, _| resize.handle_pointer_up()),
+        pointer("pointercancel", |resize, _| resize.handle_pointer_up()),
+        EventListener::new(&table, "keydown", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(resize), Some(event)) = (
+                    ColumnResize::from_weak(&weak),
+                    event.dyn_ref::<KeyboardEvent>(),
+                ) else {
+                    return;
+                };
+                resize.handle_key_down(event);
+            }
+        }),
+    ];
+
+    resize.0.lock().unwrap()._listeners = listeners;
+    resize.refresh();
+    resize
+}
@@ -0,0 +1,431 @@
+//! Row selection
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, HtmlInputElement, KeyboardEvent, MouseEvent};
+
+use crate::{body_rows, closest_row, event_target, is_header_row};
+
+/// How many rows a [RowSelection] selects
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionMode {
+    Single,
+    #[default]
+    Multiple,
+}
+
+pub type SelectionHook = Box<dyn Fn(&[String])>;
+
+/// Hooks to [RowSelection]
+#[derive(Default)]
+pub struct RowSelectionHooks {
+    /// Called with ids of selected rows when the selection changes
+    pub change: Option<SelectionHook>,
+}
+
+/// Options of [RowSelection]
+pub struct RowSelectionOptions {
+    pub table: HtmlElement,
+    pub mode: SelectionMode,
+    /// Whether clicking a row selects it. If disabled, rows are only selected with checkboxes
+    /// and the space key
+    pub select_on_click: bool,
+    pub hooks: RowSelectionHooks,
+}
+
+impl RowSelectionOptions {
+    pub fn builder() -> RowSelectionOptionsBuilder {
+        RowSelectionOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [RowSelectionOptions]
+pub struct RowSelectionOptionsBuilder {
+    table: Option<HtmlElement>,
+    mode: SelectionMode,
+    select_on_click: bool,
+    hooks: RowSelectionHooks,
+}
+
+impl Default for RowSelectionOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            table: None,
+            mode: SelectionMode::default(),
+            select_on_click: true,
+            hooks: RowSelectionHooks::default(),
+        }
+    }
+}
+
+impl RowSelectionOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn table(mut self, table: HtmlElement) -> Self {
+        self.table = Some(table);
+        self
+    }
+
+    pub fn mode(mut self, mode: SelectionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn select_on_click(mut self, select_on_click: bool) -> Self {
+        self.select_on_click = select_on_click;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: RowSelectionHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [RowSelectionOptions]
+    ///
+    /// # Panics
+    /// This method panics if table field is not set
+    pub fn build(self) -> RowSelectionOptions {
+        RowSelectionOptions {
+            table: self
+                .table
+                .expect("table must be set to build RowSelectionOptions"),
+            mode: self.mode,
+            select_on_click: self.select_on_click,
+            hooks: self.hooks,
+        }
+    }
+}
+
+/// Returns the id of the row, which is `data-seigi-row-id` or its index among body rows
+fn row_id(row: &Element, index: usize) -> String {
+    row.get_attribute("data-seigi-row-id")
+        .unwrap_or_else(|| index.to_string())
+}
+
+fn query_inputs(parent: &Element, selector: &str) -> Vec<HtmlInputElement> {
+    let Ok(nodes) = parent.query_selector_all(selector) else {
+        return vec![];
+    };
+    (0..nodes.length())
+        .filter_map(|v| nodes.get(v))
+        .filter_map(|v| v.dyn_into::<HtmlInputElement>().ok())
+        .collect()
+}
+
+struct Inner {
+    table: HtmlElement,
+    mode: SelectionMode,
+    select_on_click: bool,
+    hooks: Rc<RowSelectionHooks>,
+    selected: Vec<String>,
+    /// The row shift-click ranges start from
+    anchor: Option<String>,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn rows(&self) -> Vec<(String, HtmlElement)> {
+        body_rows(&self.table)
+            .into_iter()
+            .enumerate()
+            .map(|(index, row)| (row_id(&row, index), row))
+            .collect()
+    }
+
+    fn reflect(&self) {
+        let rows = self.rows();
+        for (id, row) in &rows {
+            let is_selected = self.selected.contains(id);
+            let _ = row.set_attribute("aria-selected", if is_selected { "true" } else { "false" });
+            if is_selected {
+                let _ = row.set_attribute("data-selected", "");
+            } else {
+                let _ = row.remove_attribute("data-selected");
+            }
+            for checkbox in query_inputs(row, "input[data-seigi-row-select]") {
+                checkbox.set_checked(is_selected);
+            }
+        }
+
+        let count = rows
+            .iter()
+            .filter(|(id, _)| self.selected.contains(id))
+            .count();
+        for checkbox in query_inputs(&self.table, "input[data-seigi-select-all]") {
+            checkbox.set_checked(count > 0 && count == rows.len());
+            checkbox.set_indeterminate(count > 0 && count < rows.len());
+        }
+    }
+}
+
+/// An instance of row selection
+///
+/// Rows are identified by `data-seigi-row-id`, or by their index among body rows. In multiple
+/// mode, Ctrl/Cmd+click toggles a row and Shift+click selects a range. Space toggles the row
+/// containing the focus.
+///
+/// Checkboxes with `data-seigi-row-select` inside rows toggle their row, and a checkbox with
+/// `data-seigi-select-all` toggles every row and shows an indeterminate state for partial
+/// selections.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **aria-selected** is set in body rows to `true` or `false`
+///
+/// **data-selected** is set in selected rows
+///
+/// **aria-multiselectable** is set in the table in multiple mode
+#[derive(Clone)]
+pub struct RowSelection(Rc<Mutex<Inner>>);
+
+impl RowSelection {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    /// Returns ids of selected rows
+    pub fn selected(&self) -> Vec<String> {
+        self.0.lock().unwrap().selected.clone()
+    }
+
+    pub fn is_selected(&self, id: &str) -> bool {
+        self.0.lock().unwrap().selected.iter().any(|v| v == id)
+    }
+
+    /// Replaces the selection. Only the first id is kept in single mode
+    pub fn set_selected(&self, mut ids: Vec<String>) {
+        let hooks = {
+            let mut inner = self.0.lock().unwrap();
+            if inner.mode == SelectionMode::Single {
+                ids.truncate(1);
+            }
+            if inner.selected == ids {
+                return;
+            }
+            inner.selected = ids.clone();
+            inner.reflect();
+            inner.hooks.clone()
+        };
+
+        if let Some(hook) = &hooks.change {
+            hook(&ids);
+        }
+    }
+
+    /// Toggles the row, replacing the selection in single mode
+    pub fn toggle(&self, id: &str) {
+        let (mode, mut selected) = {
+            let mut inner = self.0.lock().unwrap();
+            inner.anchor = Some(id.to_string());
+            (inner.mode, inner.selected.clone())
+        };
+
+        if let Some(index) = selected.iter().position(|v| v == id) {
+            selected.remove(index);
+        } else if mode == SelectionMode::Single {
+            selected = vec![id.to_string()];
+        } else {
+            selected.push(id.to_string());
+        }
+        self.set_selected(selected);
+    }
+
+    /// Selects every row in multiple mode
+    pub fn select_all(&self) {
+        let ids = {
+            let inner = self.0.lock().unwrap();
+            if inner.mode == SelectionMode::Single {
+                return;
+            }
+            inner.rows().into_iter().map(|(id, _)| id).collect()
+        };
+        self.set_selected(ids);
+    }
+
+    pub fn clear(&self) {
+        self.set_selected(vec![]);
+    }
+
+    /// Drops ids of removed rows and reapplies attributes to rows added since
+    pub fn refresh(&self) {
+        let ids = {
+            let inner = self.0.lock().unwrap();
+            let rows = inner.rows();
+            inner
+                .selected
+                .iter()
+                .filter(|v| rows.iter().any(|(id, _)| id == *v))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        self.set_selected(ids);
+        self.0.lock().unwrap().reflect();
+    }
+
+    fn handle_click(&self, event: &MouseEvent) {
+        let Some(target) = event_target(event) else {
+            return;
+        };
+        // Checkboxes are handled by change events, and other controls do their own thing
+        if target
+            .closest("a, button, input, select, textarea, label, [contenteditable]")
+            .ok()
+            .flatten()
+            .is_some()
+        {
+            return;
+        }
+
+        let (row, rows, mode, anchor) = {
+            let inner = self.0.lock().unwrap();
+            if !inner.select_on_click {
+                return;
+            }
+            let Some(row) = closest_row(&inner.table, &target) else {
+                return;
+            };
+            (row, inner.rows(), inner.mode, inner.anchor.clone())
+        };
+        let Some(index) = rows.iter().position(|(_, v)| *v == row) else {
+            return;
+        };
+        let id = rows[index].0.clone();
+
+        if mode == SelectionMode::Multiple && event.shift_key() {
+            let anchor = anchor
+                .and_then(|anchor| rows.iter().position(|(id, _)| *id == anchor))
+                .unwrap_or(index);
+            let range = anchor.min(index)..=anchor.max(index);
+            self.set_selected(rows[range].iter().map(|(id, _)| id.clone()).collect());
+        } else if mode == SelectionMode::Multiple && (event.ctrl_key() || event.meta_key()) {
+            self.toggle(&id);
+        } else {
+            self.0.lock().unwrap().anchor = Some(id.clone());
+            self.set_selected(vec![id]);
+        }
+    }
+
+    fn handle_change(&self, target: &Element) {
+        let Some(input) = target.dyn_ref::<HtmlInputElement>() else {
+            return;
+        };
+
+        if input.has_attribute("data-seigi-select-all") {
+            if input.checked() {
+                self.select_all();
+            } else {
+                self.clear();
+            }
+            return;
+        }
+
+        if input.has_attribute("data-seigi-row-select") {
+            let (row, rows) = {
+                let inner = self.0.lock().unwrap();
+                (closest_row(&inner.table, target), inner.rows())
+            };
+            if let Some((id, _)) = rows.iter().find(|(_, v)| Some(v) == row.as_ref()) {
+                self.toggle(id);
+            }
+        }
+    }
+
+    fn handle_key_down(&self, event: &KeyboardEvent) {
+        let Some(target) = event_target(event) else {
+            return;
+        };
+        if event.key() != " "
+            || target
+                .matches("a, button, input, select, textarea, [contenteditable]")
+                .unwrap_or(false)
+        {
+            return;
+        }
+
+        let (row, rows) = {
+            let inner = self.0.lock().unwrap();
+            (closest_row(&inner.table, &target), inner.rows())
+        };
+        let Some(row) = row.filter(|v| !is_header_row(v)) else {
+            return;
+        };
+        if let Some((id, _)) = rows.iter().find(|(_, v)| *v == row) {
+            event.prevent_default();
+            self.toggle(id);
+        }
+    }
+}
+
+pub fn create_row_selection(options: RowSelectionOptions) -> RowSelection {
+    let table = options.table.clone();
+    if options.mode == SelectionMode::Multiple {
+        let _ = table.set_attribute("aria-multiselectable", "true");
+    }
+
+    let selection = RowSelection(Rc::new(Mutex::new(Inner {
+        table: options.table,
+        mode: options.mode,
+        select_on_click: options.select_on_click,
+        hooks: Rc::new(options.hooks),
+        selected: vec![],
+        anchor: None,
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&selection.0);
+    let listeners = vec![
+        EventListener::new(&table, "click", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(selection), Some(event)) = (
+                    RowSelection::from_weak(&weak),
+                    event.dyn_ref::<MouseEvent>(),
+                ) else {
+                    return;
+                };
+                selection.handle_click(event);
+            }
+        }),
+        EventListener::new(&table, "change", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(selection), Some(target)) =
+                    (RowSelection::from_weak(&weak), event_target(event))
+                else {
+                    return;
+                };
+                selection.handle_change(&target);
+            }
+        }),
+        EventListener::new(&table, "keydown", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(selection), Some(event)) = (
+                    RowSelection::from_weak(&weak),
+                    event.dyn_ref::<KeyboardEvent>(),
+                ) else {
+                    return;
+                };
+                selection.handle_key_down(event);
+            }
+        }),
+    ];
+
+    {
+        let mut inner = selection.0.lock().unwrap();
+        inner._listeners = listeners;
+        inner.reflect();
+    }
+
+    selection
+}
@@ -0,0 +1,338 @@
+//! Column sorting state
+
+use std::{
+    cmp::Ordering,
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, KeyboardEvent};
+
+use crate::{body_rows, cells, event_target};
+
+/// Direction of a [Sort]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+/// A column the table is sorted by
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sort {
+    /// The key in `data-seigi-sort` of the column header
+    pub column: String,
+    pub direction: SortDirection,
+}
+
+pub type SortHook = Box<dyn Fn(Option<&Sort>)>;
+
+/// Hooks to [Sorting]
+#[derive(Default)]
+pub struct SortingHooks {
+    /// Called when the sort changes. None if the table is unsorted
+    pub change: Option<SortHook>,
+}
+
+/// Options of [Sorting]
+pub struct SortingOptions {
+    /// The table containing column headers with `data-seigi-sort` of their key
+    pub table: HtmlElement,
+    pub initial: Option<Sort>,
+    /// Whether body rows are reordered in the DOM by the text of the sorted column. Disable
+    /// this if the rows are rendered from sorted data in the change hook
+    pub sort_rows: bool,
+    pub hooks: SortingHooks,
+}
+
+impl SortingOptions {
+    pub fn builder() -> SortingOptionsBuilder {
+        SortingOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [SortingOptions]
+#[derive(Default)]
+pub struct SortingOptionsBuilder {
+    table: Option<HtmlElement>,
+    initial: Option<Sort>,
+    sort_rows: bool,
+    hooks: SortingHooks,
+}
+
+impl SortingOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn table(mut self, table: HtmlElement) -> Self {
+        self.table = Some(table);
+        self
+    }
+
+    pub fn initial(mut self, initial: Sort) -> Self {
+        self.initial = Some(initial);
+        self
+    }
+
+    pub fn sort_rows(mut self, sort_rows: bool) -> Self {
+        self.sort_rows = sort_rows;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: SortingHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [SortingOptions]
+    ///
+    /// # Panics
+    /// This method panics if table field is not set
+    pub fn build(self) -> SortingOptions {
+        SortingOptions {
+            table: self
+                .table
+                .expect("table must be set to build SortingOptions"),
+            initial: self.initial,
+            sort_rows: self.sort_rows,
+            hooks: self.hooks,
+        }
+    }
+}
+
+/// Compares cell values numerically if both are numbers, and as text otherwise
+fn compare_values(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        _ => a.trim().to_lowercase().cmp(&b.trim().to_lowercase()),
+    }
+}
+
+/// Returns the sort value of a cell, which is `data-sort-value` or its text
+fn cell_value(cell: Option<&HtmlElement>) -> String {
+    cell.map(|v| {
+        v.get_attribute("data-sort-value")
+            .unwrap_or_else(|| v.text_content().unwrap_or_default())
+    })
+    .unwrap_or_default()
+}
+
+/// Returns the header cell containing the element with `data-seigi-sort`
+fn header_cell(header: &Element) -> Element {
+    header
+        .closest("th, [role=columnheader]")
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| header.clone())
+}
+
+struct Inner {
+    table: HtmlElement,
+    sort_rows: bool,
+    hooks: Rc<SortingHooks>,
+    sort: Option<Sort>,
+    _listeners: Vec<EventListener>,
+}
+
+/// An instance of column sorting
+///
+/// Activating a column header cycles it through ascending, descending and unsorted. Only a single
+/// column is sorted at a time.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **aria-sort** is set in sortable column header cells to `ascending`, `descending` or `none`
+///
+/// **data-sort** is set in the sorted column header cell to `ascending` or `descending`
+#[derive(Clone)]
+pub struct Sorting(Rc<Mutex<Inner>>);
+
+impl Sorting {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    pub fn sort(&self) -> Option<Sort> {
+        self.0.lock().unwrap().sort.clone()
+    }
+
+    pub fn set_sort(&self, sort: Option<Sort>) {
+        let hooks = {
+            let mut inner = self.0.lock().unwrap();
+            if inner.sort == sort {
+                return;
+            }
+            inner.sort = sort.clone();
+            inner.reflect();
+            inner.hooks.clone()
+        };
+
+        if let Some(hook) = &hooks.change {
+            hook(sort.as_ref());
+        }
+    }
+
+    /// Cycles the column through ascending, descending and unsorted
+    pub fn toggle(&self, column: &str) {
+        let direction = match self.sort() {
+            Some(sort) if sort.column == column => match sort.direction {
+                SortDirection::Ascending => Some(SortDirection::Descending),
+                SortDirection::Descending => None,
+            },
+            _ => Some(SortDirection::Ascending),
+        };
+
+        self.set_sort(direction.map(|direction| Sort {
+            column: column.to_string(),
+            direction,
+        }));
+    }
+
+    /// Reapplies the sort to rows added or changed since
+    pub fn refresh(&self) {
+        self.0.lock().unwrap().reflect();
+    }
+}
+
+impl Inner {
+    fn headers(&self) -> Vec<Element> {
+        let Ok(nodes) = self.table.query_selector_all("[data-seigi-sort]") else {
+            return vec![];
+        };
+        (0..nodes.length())
+            .filter_map(|v| nodes.get(v))
+            .filter_map(|v| v.dyn_into::<Element>().ok())
+            .collect()
+    }
+
+    fn reflect(&self) {
+        let mut sorted_header = None;
+        for header in self.headers() {
+            let key = header.get_attribute("data-seigi-sort").unwrap_or_default();
+            let direction = self
+                .sort
+                .as_ref()
+                .filter(|v| v.column == key)
+                .map(|v| v.direction);
+            let value = match direction {
+                Some(SortDirection::Ascending) => "ascending",
+                Some(SortDirection::Descending) => "descending",
+                None => "none",
+            };
+
+            // aria-sort belongs to the header cell even if the key is set on a button in it
+            let cell = header_cell(&header);
+            let _ = cell.set_attribute("aria-sort", value);
+            if direction.is_some() {
+                let _ = cell.set_attribute("data-sort", value);
+                sorted_header = Some(cell);
+            } else {
+                let _ = cell.remove_attribute("data-sort");
+            }
+        }
+
+        if self.sort_rows
+            && let (Some(sort), Some(header)) = (&self.sort, sorted_header)
+        {
+            self.sort_rows(sort, &header);
+        }
+    }
+
+    fn sort_rows(&self, sort: &Sort, cell: &Element) {
+        let Some(column) = cell
+            .parent_element()
+            .and_then(|row| cells(&row).iter().position(|v| **v == *cell))
+        else {
+            return;
+        };
+
+        let mut rows = body_rows(&self.table)
+            .into_iter()
+            .map(|row| (cell_value(cells(&row).get(column)), row))
+            .collect::<Vec<_>>();
+        // Stable, so equal rows keep their order
+        rows.sort_by(|(a, _), (b, _)| match sort.direction {
+            SortDirection::Ascending => compare_values(a, b),
+            SortDirection::Descending => compare_values(b, a),
+        });
+
+        for (_, row) in rows {
+            if let Some(parent) = row.parent_node() {
+                let _ = parent.append_child(&row);
+            }
+        }
+    }
+}
+
+pub fn create_sorting(options: SortingOptions) -> Sorting {
+    let table = options.table.clone();
+    let sorting = Sorting(Rc::new(Mutex::new(Inner {
+        table: options.table,
+        sort_rows: options.sort_rows,
+        hooks: Rc::new(options.hooks),
+        sort: options.initial,
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&sorting.0);
+    let find_header = |event: &web_sys::Event| {
+        let target = event_target(event)?;
+        // Resize handles usually sit inside sortable headers
+        if target
+            .closest("[data-seigi-column-resize]")
+            .ok()
+            .flatten()
+            .is_some()
+        {
+            return None;
+        }
+        Some(target)
+            .and_then(|v| v.closest("[data-seigi-sort]").ok().flatten())
+            .and_then(|v| v.get_attribute("data-seigi-sort"))
+    };
+    let listeners = vec![
+        EventListener::new(&table, "click", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(sorting), Some(column)) = (Sorting::from_weak(&weak), find_header(event))
+                else {
+                    return;
+                };
+                sorting.toggle(&column);
+            }
+        }),
+        EventListener::new(&table, "keydown", {
+            let weak = weak.clone();
+            move |event| {
+                let Some(key_event) = event.dyn_ref::<KeyboardEvent>() else {
+                    return;
+                };
+                // Buttons already turn Enter into clicks
+                let is_button = event_target(event)
+                    .is_some_and(|v| v.matches("button, [role=button]").unwrap_or(false));
+                if key_event.key() != "Enter" || is_button {
+                    return;
+                }
+                let (Some(sorting), Some(column)) = (Sorting::from_weak(&weak), find_header(event))
+                else {
+                    return;
+                };
+                key_event.prevent_default();
+                sorting.toggle(&column);
+            }
+        }),
+    ];
+
+    {
+        let mut inner = sorting.0.lock().unwrap();
+        inner._listeners = listeners;
+        inner.reflect();
+    }
+
+    sorting
+}
@@ -0,0 +1,165 @@
+//! Sticky header coordination
+
+use std::rc::Rc;
+
+use gloo::events::{EventListener, EventListenerOptions};
+use wasm_bindgen::{JsCast, prelude::Closure};
+use web_sys::{Element, HtmlElement, ResizeObserver};
+
+/// Options of [StickyHeader]
+pub struct StickyHeaderOptions {
+    /// The header stuck with `position: sticky`, such as `<thead>`
+    pub header: HtmlElement,
+    /// The element the header height is exposed to. Defaults to the closest table or grid
+    pub table: Option<HtmlElement>,
+}
+
+impl StickyHeaderOptions {
+    pub fn builder() -> StickyHeaderOptionsBuilder {
+        StickyHeaderOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [StickyHeaderOptions]
+#[derive(Default)]
+pub struct StickyHeaderOptionsBuilder {
+    header: Option<HtmlElement>,
+    table: Option<HtmlElement>,
+}
+
+impl StickyHeaderOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn header(mut self, header: HtmlElement) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    pub fn table(mut self, table: HtmlElement) -> Self {
+        self.table = Some(table);
+        self
+    }
+
+    /// Builds into [StickyHeaderOptions]
+    ///
+    /// # Panics
+    /// This method panics if header field is not set
+    pub fn build(self) -> StickyHeaderOptions {
+        StickyHeaderOptions {
+            header: self
+                .header
+                .expect("header must be set to build StickyHeaderOptions"),
+            table: self.table,
+        }
+    }
+}
+
+struct Inner {
+    header: HtmlElement,
+    table: Element,
+    resize_observer: ResizeObserver,
+    _resize_callback: Closure<dyn Fn()>,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn update(&self) {
+        let header = self.header.get_bounding_client_rect();
+        let _ = self
+            .table
+            .unchecked_ref::<HtmlElement>()
+            .style()
+            .set_property(
+                "--seigi-table-header-height",
+                &format!("{}px", header.height()),
+            );
+
+        // A stuck header is pushed down from where the table starts
+        let table = self.table.get_bounding_client_rect();
+        if header.top() - table.top() > 0.5 {
+            let _ = self.header.set_attribute("data-stuck", "");
+        } else {
+            let _ = self.header.remove_attribute("data-stuck");
+        }
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.resize_observer.disconnect();
+    }
+}
+
+/// An instance of sticky header
+///
+/// Sticking itself is done with `position: sticky` in CSS; this tracks whether the header is
+/// stuck and its height, so shadows can be shown and other sticky elements offset below it.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-stuck** is set in the header while it is stuck
+///
+/// **--seigi-table-header-height** CSS variable is set in the table to the header height
+#[derive(Clone)]
+pub struct StickyHeader(Rc<Inner>);
+
+impl StickyHeader {
+    /// Updates attributes, if the layout changed without a scroll or resize
+    pub fn update(&self) {
+        self.0.update();
+    }
+}
+
+pub fn create_sticky_header(options: StickyHeaderOptions) -> StickyHeader {
+    let header = options.header;
+    let table = options
+        .table
+        .map(Element::from)
+        .or_else(|| header.closest("table, [role=grid]").ok().flatten())
+        .or_else(|| header.parent_element())
+        .unwrap_or_else(|| header.clone().into());
+
+    let inner = Rc::new_cyclic(|weak: &std::rc::Weak<Inner>| {
+        let resize_callback: Closure<dyn Fn()> = Closure::new({
+            let weak = weak.clone();
+            move || {
+                if let Some(inner) = weak.upgrade() {
+                    inner.update();
+                }
+            }
+        });
+        let resize_observer = ResizeObserver::new(resize_callback.as_ref().unchecked_ref())
+            .expect("failed to create resize observer");
+        resize_observer.observe(&header);
+
+        // Scroll events don't bubble, so they are captured from every scroll container
+        let scroll = EventListener::new_with_options(
+            &gloo::utils::document(),
+            "scroll",
+            EventListenerOptions::run_in_capture_phase(),
+            {
+                let weak = weak.clone();
+                move |_| {
+                    if let Some(inner) = weak.upgrade() {
+                        inner.update();
+                    }
+                }
+            },
+        );
+
+        Inner {
+            header,
+            table,
+            resize_observer,
+            _resize_callback: resize_callback,
+            _listeners: vec![scroll],
+        }
+    });
+    inner.update();
+
+    StickyHeader(inner)
+}
@@ -0,0 +1,198 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use seigi_table::{
+    resize::{self, ColumnResize, ColumnResizeHooks, ColumnResizeOptions},
+    sticky::{self, StickyHeaderOptions},
+};
+use seigi_testkit::{
+    Fixture,
+    events::{self, Modifiers},
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<style>
+    #table { border-collapse: collapse; }
+    #table th, #table td { padding: 0; border: 0; }
+</style>
+<table id="table">
+    <thead>
+        <tr>
+            <th id="name" style="width: 120px">Name<span id="handle" data-seigi-column-resize></span></th>
+            <th id="size" style="width: 80px">Size<span data-seigi-column-resize></span></th>
+        </tr>
+    </thead>
+    <tbody>
+        <tr><td>a</td><td>1</td></tr>
+    </tbody>
+</table>
+"#;
+
+/// Columns and widths the resize hook is called with
+type Resizes = Rc<RefCell<Vec<(usize, f64)>>>;
+
+fn column_resize(fixture: &Fixture) -> (ColumnResize, Resizes) {
+    let resizes = Rc::new(RefCell::new(vec![]));
+    let resize = resize::create_column_resize(
+        ColumnResizeOptions::builder()
+            .table(fixture.get("#table"))
+            .min_width(40.0)
+            .max_width(300.0)
+            .step(10.0)
+            .hooks(ColumnResizeHooks {
+                resize: Some(Box::new({
+                    let resizes = resizes.clone();
+                    move |column, width| resizes.borrow_mut().push((column, width))
+                })),
+            })
+            .build(),
+    );
+    (resize, resizes)
+}
+
+fn width_of(fixture: &Fixture, selector: &str) -> String {
+    fixture
+        .get(selector)
+        .style()
+        .get_property_value("width")
+        .unwrap_or_default()
+}
+
+#[wasm_bindgen_test]
+fn handles_become_separators() {
+    let fixture = Fixture::new(MARKUP);
+    let _resize = column_resize(&fixture);
+    let handle = fixture.get("#handle");
+
+    assert_eq!(handle.get_attribute("role").as_deref(), Some("separator"));
+    assert_eq!(
+        handle.get_attribute("aria-orientation").as_deref(),
+        Some("vertical")
+    );
+    assert_eq!(handle.get_attribute("aria-valuemin").as_deref(), Some("40"));
+    assert_eq!(
+        handle.get_attribute("aria-valuemax").as_deref(),
+        Some("300")
+    );
+    assert_eq!(
+        handle.get_attribute("aria-valuenow").as_deref(),
+        Some("120")
+    );
+    assert_eq!(handle.tab_index(), 0);
+}
+
+#[wasm_bindgen_test]
+fn set_width_clamps_to_limits() {
+    let fixture = Fixture::new(MARKUP);
+    let (resize, resizes) = column_resize(&fixture);
+
+    resize.set_width(1, 10.0);
+    assert_eq!(width_of(&fixture, "#size"), "40px");
+    resize.set_width(0, 500.0);
+    assert_eq!(width_of(&fixture, "#name"), "300px");
+    assert_eq!(
+        fixture
+            .get("#handle")
+            .get_attribute("aria-valuenow")
+            .as_deref(),
+        Some("300")
+    );
+    // Columns without a handle are left alone
+    resize.set_width(2, 100.0);
+    assert_eq!(*resizes.borrow(), [(1, 40.0), (0, 300.0)]);
+}
+
+#[wasm_bindgen_test]
+fn keys_resize_focused_handle() {
+    let fixture = Fixture::new(MARKUP);
+    let (_resize, resizes) = column_resize(&fixture);
+    let handle = fixture.get("#handle");
+
+    assert!(!events::key_down(
+        &handle,
+        "ArrowRight",
+        Modifiers::default()
+    ));
+    assert_eq!(width_of(&fixture, "#name"), "130px");
+    events::key_down(&handle, "ArrowLeft", Modifiers::default());
+    assert_eq!(width_of(&fixture, "#name"), "120px");
+    events::key_down(&handle, "End", Modifiers::default());
+    assert_eq!(width_of(&fixture, "#name"), "300px");
+    events::key_down(&handle, "Home", Modifiers::default());
+    assert_eq!(width_of(&fixture, "#name"), "40px");
+    assert_eq!(resizes.borrow().len(), 4);
+}
+
+#[wasm_bindgen_test]
+fn dragging_handle_resizes_column() {
+    let fixture = Fixture::new(MARKUP);
+    let (_resize, resizes) = column_resize(&fixture);
+    let handle = fixture.get("#handle");
+    let name = fixture.get("#name");
+
+    assert!(!events::dispatch(
+        &handle,
+        &events::pointer_event("pointerdown", 100.0, 10.0)
+    ));
+    assert!(handle.has_attribute("data-resizing"));
+    assert!(name.has_attribute("data-resizing"));
+
+    events::dispatch(&handle, &events::pointer_event("pointermove", 130.0, 10.0));
+    assert_eq!(width_of(&fixture, "#name"), "150px");
+    events::dispatch(&handle, &events::pointer_event("pointermove", 0.0, 10.0));
+    assert_eq!(width_of(&fixture, "#name"), "40px");
+
+    events::dispatch(&handle, &events::pointer_event("pointerup", 0.0, 10.0));
+    assert!(!handle.has_attribute("data-resizing"));
+    assert!(!name.has_attribute("data-resizing"));
+
+    // Moves after release don't resize
+    events::dispatch(&handle, &events::pointer_event("pointermove", 200.0, 10.0));
+    assert_eq!(*resizes.borrow(), [(0, 150.0), (0, 40.0)]);
+}
+
+#[wasm_bindgen_test]
+fn sticky_header_tracks_height_and_stuck_state() {
+    let fixture = Fixture::new(
+        r#"
+        <div id="scroller" style="height: 100px; overflow: auto">
+            <table id="table" style="border-collapse: collapse">
+                <thead id="head" style="position: sticky; top: 0">
+                    <tr><th style="height: 30px; padding: 0">Name</th></tr>
+                </thead>
+                <tbody>
+                    <tr><td style="height: 200px">a</td></tr>
+                </tbody>
+            </table>
+        </div>
+        "#,
+    );
+    let header = fixture.get("#head");
+    let sticky = sticky::create_sticky_header(
+        StickyHeaderOptions::builder()
+            .header(header.clone())
+            .build(),
+    );
+
+    assert_eq!(
+        fixture
+            .get("#table")
+            .style()
+            .get_property_value("--seigi-table-header-height")
+            .unwrap(),
+        "30px"
+    );
+    assert!(!header.has_attribute("data-stuck"));
+
+    fixture.get("#scroller").set_scroll_top(50);
+    sticky.update();
+    assert!(header.has_attribute("data-stuck"));
+
+    fixture.get("#scroller").set_scroll_top(0);
+    sticky.update();
+    assert!(!header.has_attribute("data-stuck"));
+}
@@ -0,0 +1,150 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_table::navigation::{self, GridNavigation, GridNavigationOptions};
+use seigi_testkit::{
+    Fixture, assert_focused,
+    events::{self, Modifiers},
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::HtmlElement;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<table id="table">
+    <thead>
+        <tr><th>A</th><th>B</th><th>C</th></tr>
+    </thead>
+    <tbody>
+        <tr><td>1</td><td>2</td><td>3</td></tr>
+        <tr><td>4</td><td>5</td><td><input id="field"></td></tr>
+        <tr><td>7</td><td>8</td></tr>
+    </tbody>
+</table>
+"#;
+
+fn navigation(fixture: &Fixture) -> GridNavigation {
+    navigation::create_grid_navigation(
+        GridNavigationOptions::builder()
+            .table(fixture.get("#table"))
+            .page_size(2)
+            .build(),
+    )
+}
+
+/// Returns the cell at the row and column, counting the header row
+fn cell(fixture: &Fixture, row: usize, column: usize) -> HtmlElement {
+    fixture.get_all("tr")[row]
+        .children()
+        .item(column as u32)
+        .expect("no cell at the position")
+        .unchecked_into()
+}
+
+fn press(fixture: &Fixture, key: &str, modifiers: Modifiers) -> bool {
+    let target = fixture.active_element().expect("a cell must be focused");
+    events::key_down(&target, key, modifiers)
+}
+
+#[wasm_bindgen_test]
+fn grid_is_a_single_tab_stop() {
+    let fixture = Fixture::new(MARKUP);
+    let navigation = navigation(&fixture);
+
+    assert_eq!(
+        fixture.get("#table").get_attribute("role").as_deref(),
+        Some("grid")
+    );
+    assert_eq!(navigation.active(), (0, 0));
+    let tab_stops = fixture
+        .get_all("th, td")
+        .into_iter()
+        .filter(|v| v.tab_index() == 0)
+        .collect::<Vec<_>>();
+    assert_eq!(tab_stops, [fixture.get("th")]);
+
+    navigation.focus_cell(1, 1);
+    assert_eq!(navigation.active(), (1, 1));
+    assert_focused(&cell(&fixture, 1, 1));
+    assert_eq!(fixture.get("th").tab_index(), -1);
+    assert_eq!(cell(&fixture, 1, 1).tab_index(), 0);
+}
+
+#[wasm_bindgen_test]
+fn arrow_keys_move_between_cells() {
+    let fixture = Fixture::new(MARKUP);
+    let navigation = navigation(&fixture);
+    navigation.focus_cell(1, 1);
+
+    assert!(!press(&fixture, "ArrowRight", Modifiers::default()));
+    assert_eq!(navigation.active(), (1, 2));
+    // Edges keep the focus in place
+    press(&fixture, "ArrowRight", Modifiers::default());
+    assert_eq!(navigation.active(), (1, 2));
+
+    press(&fixture, "ArrowUp", Modifiers::default());
+    assert_eq!(navigation.active(), (0, 2));
+    press(&fixture, "ArrowLeft", Modifiers::default());
+    assert_eq!(navigation.active(), (0, 1));
+    press(&fixture, "ArrowDown", Modifiers::default());
+    assert_focused(&cell(&fixture, 1, 1));
+}
+
+#[wasm_bindgen_test]
+fn shorter_rows_clamp_the_column() {
+    let fixture = Fixture::new(MARKUP);
+    let navigation = navigation(&fixture);
+    navigation.focus_cell(1, 2);
+
+    press(&fixture, "PageDown", Modifiers::default());
+    assert_eq!(navigation.active(), (3, 1));
+    assert_focused(&cell(&fixture, 3, 1));
+}
+
+#[wasm_bindgen_test]
+fn home_and_end_move_to_the_ends() {
+    let fixture = Fixture::new(MARKUP);
+    let navigation = navigation(&fixture);
+    let control = Modifiers {
+        ctrl: true,
+        ..Modifiers::default()
+    };
+    navigation.focus_cell(1, 1);
+
+    press(&fixture, "End", Modifiers::default());
+    assert_eq!(navigation.active(), (1, 2));
+    press(&fixture, "Home", Modifiers::default());
+    assert_eq!(navigation.active(), (1, 0));
+    press(&fixture, "End", control);
+    assert_eq!(navigation.active(), (3, 1));
+    press(&fixture, "PageUp", Modifiers::default());
+    assert_eq!(navigation.active(), (1, 1));
+    press(&fixture, "Home", control);
+    assert_eq!(navigation.active(), (0, 0));
+}
+
+#[wasm_bindgen_test]
+fn text_fields_keep_arrow_keys() {
+    let fixture = Fixture::new(MARKUP);
+    let navigation = navigation(&fixture);
+    let field = fixture.get("#field");
+    field.focus().unwrap();
+
+    // Focusing something inside a cell makes the cell the tab stop
+    assert_eq!(navigation.active(), (2, 2));
+    assert!(events::key_down(&field, "ArrowLeft", Modifiers::default()));
+    assert_focused(&field);
+}
+
+#[wasm_bindgen_test]
+fn refresh_clamps_to_removed_rows() {
+    let fixture = Fixture::new(MARKUP);
+    let navigation = navigation(&fixture);
+    navigation.focus_cell(3, 1);
+
+    fixture.get("tbody tr:last-child").remove();
+    navigation.refresh();
+    assert_eq!(navigation.active(), (2, 1));
+    assert_eq!(cell(&fixture, 2, 1).tab_index(), 0);
+}
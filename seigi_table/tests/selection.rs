@@ -0,0 +1,205 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use seigi_table::selection::{
+    self, RowSelection, RowSelectionHooks, RowSelectionOptions, SelectionMode,
+};
+use seigi_testkit::{
+    Fixture,
+    events::{self, Modifiers},
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::{HtmlInputElement, MouseEvent, MouseEventInit};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<table id="table">
+    <thead>
+        <tr><th><input id="all" type="checkbox" data-seigi-select-all></th><th id="header">Name</th></tr>
+    </thead>
+    <tbody>
+        <tr data-seigi-row-id="a"><td><input type="checkbox" data-seigi-row-select></td><td id="a" tabindex="-1">A</td></tr>
+        <tr data-seigi-row-id="b"><td><input type="checkbox" data-seigi-row-select></td><td id="b" tabindex="-1">B</td></tr>
+        <tr data-seigi-row-id="c"><td><input id="check-c" type="checkbox" data-seigi-row-select></td><td id="c" tabindex="-1">C</td></tr>
+        <tr data-seigi-row-id="d"><td><input type="checkbox" data-seigi-row-select></td><td id="d" tabindex="-1">D</td></tr>
+    </tbody>
+</table>
+"#;
+
+fn selection(
+    fixture: &Fixture,
+    mode: SelectionMode,
+) -> (RowSelection, Rc<RefCell<Vec<Vec<String>>>>) {
+    let changes = Rc::new(RefCell::new(vec![]));
+    let selection = selection::create_row_selection(
+        RowSelectionOptions::builder()
+            .table(fixture.get("#table"))
+            .mode(mode)
+            .hooks(RowSelectionHooks {
+                change: Some(Box::new({
+                    let changes = changes.clone();
+                    move |ids| changes.borrow_mut().push(ids.to_vec())
+                })),
+            })
+            .build(),
+    );
+    (selection, changes)
+}
+
+fn click(target: &web_sys::HtmlElement, modifiers: Modifiers) {
+    let init = MouseEventInit::new();
+    init.set_bubbles(true);
+    init.set_cancelable(true);
+    init.set_shift_key(modifiers.shift);
+    init.set_ctrl_key(modifiers.ctrl);
+    let event = MouseEvent::new_with_mouse_event_init_dict("click", &init).unwrap();
+    events::dispatch(target, &event);
+}
+
+fn checkbox(fixture: &Fixture, selector: &str) -> HtmlInputElement {
+    fixture.get(selector).unchecked_into()
+}
+
+#[wasm_bindgen_test]
+fn clicks_select_toggle_and_extend() {
+    let fixture = Fixture::new(MARKUP);
+    let (selection, _) = selection(&fixture, SelectionMode::Multiple);
+    assert_eq!(
+        fixture
+            .get("#table")
+            .get_attribute("aria-multiselectable")
+            .as_deref(),
+        Some("true")
+    );
+
+    click(&fixture.get("#b"), Modifiers::default());
+    assert_eq!(selection.selected(), ["b"]);
+    let row = fixture.get("[data-seigi-row-id=b]");
+    assert_eq!(row.get_attribute("aria-selected").as_deref(), Some("true"));
+    assert!(row.has_attribute("data-selected"));
+
+    click(
+        &fixture.get("#d"),
+        Modifiers {
+            ctrl: true,
+            ..Modifiers::default()
+        },
+    );
+    assert_eq!(selection.selected(), ["b", "d"]);
+
+    // The range starts from the row toggled last
+    click(&fixture.get("#a"), Modifiers::shift());
+    assert_eq!(selection.selected(), ["a", "b", "c", "d"]);
+
+    click(&fixture.get("#c"), Modifiers::default());
+    assert_eq!(selection.selected(), ["c"]);
+    assert_eq!(row.get_attribute("aria-selected").as_deref(), Some("false"));
+    assert!(!row.has_attribute("data-selected"));
+}
+
+#[wasm_bindgen_test]
+fn checkboxes_toggle_rows_and_reflect_partial_selection() {
+    let fixture = Fixture::new(MARKUP);
+    let (selection, changes) = selection(&fixture, SelectionMode::Multiple);
+    let all = checkbox(&fixture, "#all");
+
+    let row = checkbox(&fixture, "#check-c");
+    row.set_checked(true);
+    events::fire(&row, "change");
+    assert_eq!(selection.selected(), ["c"]);
+    assert!(all.indeterminate());
+    assert!(!all.checked());
+
+    all.set_checked(true);
+    events::fire(&all, "change");
+    assert_eq!(selection.selected(), ["a", "b", "c", "d"]);
+    assert!(all.checked());
+    assert!(!all.indeterminate());
+    assert!(checkbox(&fixture, "#check-c").checked());
+
+    all.set_checked(false);
+    events::fire(&all, "change");
+    assert!(selection.selected().is_empty());
+    assert_eq!(changes.borrow().len(), 3);
+}
+
+#[wasm_bindgen_test]
+fn space_toggles_the_focused_row() {
+    let fixture = Fixture::new(MARKUP);
+    let (selection, _) = selection(&fixture, SelectionMode::Multiple);
+
+    assert!(!events::key_down(
+        &fixture.get("#b"),
+        " ",
+        Modifiers::default()
+    ));
+    assert!(selection.is_selected("b"));
+    events::key_down(&fixture.get("#b"), " ", Modifiers::default());
+    assert!(!selection.is_selected("b"));
+
+    // Header rows aren't selectable
+    assert!(events::key_down(
+        &fixture.get("#header"),
+        " ",
+        Modifiers::default()
+    ));
+    assert!(selection.selected().is_empty());
+}
+
+#[wasm_bindgen_test]
+fn single_mode_keeps_one_row() {
+    let fixture = Fixture::new(MARKUP);
+    let (selection, _) = selection(&fixture, SelectionMode::Single);
+    assert!(!fixture.get("#table").has_attribute("aria-multiselectable"));
+
+    selection.toggle("a");
+    selection.toggle("b");
+    assert_eq!(selection.selected(), ["b"]);
+
+    // Modifiers don't extend the selection
+    click(
+        &fixture.get("#d"),
+        Modifiers {
+            ctrl: true,
+            ..Modifiers::default()
+        },
+    );
+    assert_eq!(selection.selected(), ["d"]);
+
+    selection.set_selected(vec!["a".to_string(), "c".to_string()]);
+    assert_eq!(selection.selected(), ["a"]);
+    selection.select_all();
+    assert_eq!(selection.selected(), ["a"]);
+}
+
+#[wasm_bindgen_test]
+fn checkbox_only_selection() {
+    let fixture = Fixture::new(MARKUP);
+    let selection = selection::create_row_selection(
+        RowSelectionOptions::builder()
+            .table(fixture.get("#table"))
+            .select_on_click(false)
+            .build(),
+    );
+
+    click(&fixture.get("#a"), Modifiers::default());
+    assert!(selection.selected().is_empty());
+}
+
+#[wasm_bindgen_test]
+fn refresh_drops_removed_rows() {
+    let fixture = Fixture::new(MARKUP);
+    let (selection, changes) = selection(&fixture, SelectionMode::Multiple);
+    selection.set_selected(vec!["a".to_string(), "c".to_string()]);
+
+    fixture.get("[data-seigi-row-id=c]").remove();
+    selection.refresh();
+    assert_eq!(selection.selected(), ["a"]);
+    assert_eq!(changes.borrow().last().unwrap(), &["a"]);
+
+    selection.clear();
+    assert!(selection.selected().is_empty());
+}
@@ -0,0 +1,194 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use seigi_table::sort::{self, Sort, SortDirection, Sorting, SortingHooks, SortingOptions};
+use seigi_testkit::{
+    Fixture,
+    events::{self, Modifiers},
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<table id="table">
+    <thead>
+        <tr>
+            <th id="name" data-seigi-sort="name" tabindex="0">Name</th>
+            <th id="size"><button id="size-button" data-seigi-sort="size">Size</button></th>
+        </tr>
+    </thead>
+    <tbody>
+        <tr><td>banana</td><td>10</td></tr>
+        <tr><td>Apple</td><td>9</td></tr>
+        <tr><td>cherry</td><td data-sort-value="100">big</td></tr>
+    </tbody>
+</table>
+"#;
+
+fn sorting(fixture: &Fixture) -> (Sorting, Rc<RefCell<Vec<Option<Sort>>>>) {
+    let changes = Rc::new(RefCell::new(vec![]));
+    let sorting = sort::create_sorting(
+        SortingOptions::builder()
+            .table(fixture.get("#table"))
+            .sort_rows(true)
+            .hooks(SortingHooks {
+                change: Some(Box::new({
+                    let changes = changes.clone();
+                    move |sort| changes.borrow_mut().push(sort.cloned())
+                })),
+            })
+            .build(),
+    );
+    (sorting, changes)
+}
+
+/// Returns the text of the first cell of each body row
+fn names(fixture: &Fixture) -> Vec<String> {
+    fixture
+        .get_all("tbody tr td:first-child")
+        .iter()
+        .map(|v| v.text_content().unwrap_or_default())
+        .collect()
+}
+
+#[wasm_bindgen_test]
+fn header_click_cycles_directions() {
+    let fixture = Fixture::new(MARKUP);
+    let (sorting, changes) = sorting(&fixture);
+    let name = fixture.get("#name");
+    assert_eq!(name.get_attribute("aria-sort").as_deref(), Some("none"));
+
+    events::click(&name);
+    assert_eq!(
+        sorting.sort(),
+        Some(Sort {
+            column: "name".to_string(),
+            direction: SortDirection::Ascending,
+        })
+    );
+    assert_eq!(
+        name.get_attribute("aria-sort").as_deref(),
+        Some("ascending")
+    );
+    assert_eq!(
+        name.get_attribute("data-sort").as_deref(),
+        Some("ascending")
+    );
+    // Text is compared without case
+    assert_eq!(names(&fixture), ["Apple", "banana", "cherry"]);
+
+    events::click(&name);
+    assert_eq!(
+        name.get_attribute("aria-sort").as_deref(),
+        Some("descending")
+    );
+    assert_eq!(names(&fixture), ["cherry", "banana", "Apple"]);
+
+    events::click(&name);
+    assert_eq!(sorting.sort(), None);
+    assert_eq!(name.get_attribute("aria-sort").as_deref(), Some("none"));
+    assert!(!name.has_attribute("data-sort"));
+    assert_eq!(changes.borrow().len(), 3);
+    assert_eq!(changes.borrow().last(), Some(&None));
+}
+
+#[wasm_bindgen_test]
+fn numbers_and_sort_values_compare_numerically() {
+    let fixture = Fixture::new(MARKUP);
+    let (sorting, _) = sorting(&fixture);
+
+    // The key is on a button, while aria-sort belongs to its header cell
+    events::click(&fixture.get("#size-button"));
+    assert_eq!(sorting.sort().map(|v| v.column).as_deref(), Some("size"));
+    let size = fixture.get("#size");
+    assert_eq!(
+        size.get_attribute("aria-sort").as_deref(),
+        Some("ascending")
+    );
+    assert_eq!(
+        fixture.get("#name").get_attribute("aria-sort").as_deref(),
+        Some("none")
+    );
+    assert_eq!(names(&fixture), ["Apple", "banana", "cherry"]);
+}
+
+#[wasm_bindgen_test]
+fn enter_on_header_cell_toggles() {
+    let fixture = Fixture::new(MARKUP);
+    let (sorting, _) = sorting(&fixture);
+
+    assert!(!events::key_down(
+        &fixture.get("#name"),
+        "Enter",
+        Modifiers::default()
+    ));
+    assert_eq!(
+        sorting.sort().map(|v| v.direction),
+        Some(SortDirection::Ascending)
+    );
+
+    // Buttons turn Enter into clicks themselves
+    assert!(events::key_down(
+        &fixture.get("#size-button"),
+        "Enter",
+        Modifiers::default()
+    ));
+    assert_eq!(sorting.sort().map(|v| v.column).as_deref(), Some("name"));
+}
+
+#[wasm_bindgen_test]
+fn resize_handles_in_headers_dont_sort() {
+    let fixture = Fixture::new(MARKUP);
+    let name = fixture.get("#name");
+    name.set_inner_html(r#"Name<span id="handle" data-seigi-column-resize></span>"#);
+    let (sorting, _) = sorting(&fixture);
+
+    events::click(&fixture.get("#handle"));
+    assert_eq!(sorting.sort(), None);
+}
+
+#[wasm_bindgen_test]
+fn set_sort_applies_and_skips_unchanged() {
+    let fixture = Fixture::new(MARKUP);
+    let (sorting, changes) = sorting(&fixture);
+    let sort = Sort {
+        column: "name".to_string(),
+        direction: SortDirection::Descending,
+    };
+
+    sorting.set_sort(Some(sort.clone()));
+    sorting.set_sort(Some(sort));
+    assert_eq!(changes.borrow().len(), 1);
+    assert_eq!(names(&fixture), ["cherry", "banana", "Apple"]);
+
+    // Rows added later are placed by refresh
+    let row = gloo::utils::document().create_element("tr").unwrap();
+    row.set_inner_html("<td>durian</td><td>1</td>");
+    fixture.get("tbody").append_child(&row).unwrap();
+    sorting.refresh();
+    assert_eq!(names(&fixture), ["durian", "cherry", "banana", "Apple"]);
+}
+
+#[wasm_bindgen_test]
+fn initial_sort_without_reordering() {
+    let fixture = Fixture::new(MARKUP);
+    let sorting = sort::create_sorting(
+        SortingOptions::builder()
+            .table(fixture.get("#table"))
+            .initial(Sort {
+                column: "name".to_string(),
+                direction: SortDirection::Ascending,
+            })
+            .build(),
+    );
+
+    assert_eq!(
+        fixture.get("#name").get_attribute("aria-sort").as_deref(),
+        Some("ascending")
+    );
+    // Rows are rendered from sorted data by the user
+    assert_eq!(names(&fixture), ["banana", "Apple", "cherry"]);
+    drop(sorting);
+}
@@ -0,0 +1,640 @@
+//! An input of tags, which are entered as text and shown as removable chips
+
+use std::{
+    fmt::Display,
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::{events::EventListener, utils::document};
+use seigi_utils::{direction, i18n, ime};
+use wasm_bindgen::JsCast;
+use web_sys::{ClipboardEvent, Element, HtmlElement, HtmlInputElement, KeyboardEvent, MouseEvent};
+
+/// A reason a tag was not added
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rejection {
+    /// The tag is already added
+    Duplicate,
+    /// The maximum count of tags is reached
+    Max,
+    /// The validation hook returned the error message
+    Invalid(String),
+}
+
+impl Display for Rejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Duplicate => write!(f, "The tag is already added"),
+            Self::Max => write!(f, "No more tags can be added"),
+            Self::Invalid(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+/// A validation hook returning an error message for invalid tags
+pub type Validator = Box<dyn Fn(&str) -> Result<(), String>>;
+pub type ChangeHook = Box<dyn Fn(&[String])>;
+pub type RejectHook = Box<dyn Fn(&str, &Rejection)>;
+/// Renders tags into the list
+pub type RenderHook = Box<dyn Fn(&HtmlElement, &[String])>;
+
+/// Hooks to [TagsInput]
+#[derive(Default)]
+pub struct TagsInputHooks {
+    /// Called with tags when they change
+    pub change: Option<ChangeHook>,
+    /// Called with the text and the reason when a tag is not added
+    pub reject: Option<RejectHook>,
+}
+
+/// Options of [TagsInput]
+pub struct TagsInputOptions {
+    /// The element containing the list and the input, which focuses the input when pressed
+    pub container: HtmlElement,
+    /// The element tags are rendered into
+    pub list: HtmlElement,
+    /// The input tags are typed into
+    pub input: HtmlInputElement,
+    pub tags: Vec<String>,
+    /// The maximum count of tags
+    pub max: Option<usize>,
+    /// Characters that end a tag as they are typed or pasted, in addition to Enter
+    pub delimiters: Vec<char>,
+    pub validate: Option<Validator>,
+    /// An input tags are mirrored to, separated by commas, so they take part in form submission
+    pub value_input: Option<HtmlInputElement>,
+    pub render: RenderHook,
+    pub hooks: TagsInputHooks,
+}
+
+impl TagsInputOptions {
+    pub fn builder() -> TagsInputOptionsBuilder {
+        TagsInputOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [TagsInputOptions]
+pub struct TagsInputOptionsBuilder {
+    container: Option<HtmlElement>,
+    list: Option<HtmlElement>,
+    input: Option<HtmlInputElement>,
+    tags: Vec<String>,
+    max: Option<usize>,
+    delimiters: Vec<char>,
+    validate: Option<Validator>,
+    value_input: Option<HtmlInputElement>,
+    render: RenderHook,
+    hooks: TagsInputHooks,
+}
+
+impl Default for TagsInputOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            container: None,
+            list: None,
+            input: None,
+            tags: vec![],
+            max: None,
+            delimiters: vec![','],
+            validate: None,
+            value_input: None,
+            render: Box::new(render_default),
+            hooks: TagsInputHooks::default(),
+        }
+    }
+}
+
+impl TagsInputOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn container(mut self, container: HtmlElement) -> Self {
+        self.container = Some(container);
+        self
+    }
+
+    pub fn list(mut self, list: HtmlElement) -> Self {
+        self.list = Some(list);
+        self
+    }
+
+    pub fn input(mut self, input: HtmlInputElement) -> Self {
+        self.input = Some(input);
+        self
+    }
+
+    pub fn tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn max(mut self, max: usize) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    pub fn delimiters(mut self, delimiters: Vec<char>) -> Self {
+        self.delimiters = delimiters;
+        self
+    }
+
+    pub fn validate(mut self, validate: Validator) -> Self {
+        self.validate = Some(validate);
+        self
+    }
+
+    pub fn value_input(mut self, value_input: HtmlInputElement) -> Self {
+        self.value_input = Some(value_input);
+        self
+    }
+
+    pub fn render(mut self, render: RenderHook) -> Self {
+        self.render = render;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: TagsInputHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [TagsInputOptions]
+    ///
+    /// # Panics
+    /// This method panics if container, list or input field is not set
+    pub fn build(self) -> TagsInputOptions {
+        TagsInputOptions {
+            container: self
+                .container
+                .expect("container must be set to build TagsInputOptions"),
+            list: self
+                .list
+                .expect("list must be set to build TagsInputOptions"),
+            input: self
+                .input
+                .expect("input must be set to build TagsInputOptions"),
+            tags: self.tags,
+            max: self.max,
+            delimiters: self.delimiters,
+            validate: self.validate,
+            value_input: self.value_input,
+            render: self.render,
+            hooks: self.hooks,
+        }
+    }
+}
+
+/// Renders each tag as a focusable chip with a remove button
+pub fn render_default(list: &HtmlElement, tags: &[String]) {
+    list.set_text_content(None);
+    for tag in tags {
+        let chip = document()
+            .create_element("span")
+            .expect("failed to create element");
+        let _ = chip.set_attribute("data-seigi-tag", tag);
+        let label = document()
+            .create_element("span")
+            .expect("failed to create element");
+        label.set_text_content(Some(tag));
+        let remove = document()
+            .create_element("button")
+            .expect("failed to create element");
+        let _ = remove.set_attribute("type", "button");
+        let _ = remove.set_attribute("data-seigi-tag-remove", "");
+        let _ = remove.set_attribute(
+            "aria-label",
+            &i18n::translate("tags.remove", &[("tag", &tag)]),
+        );
+        remove.set_text_content(Some("×"));
+        let _ = chip.append_child(&label);
+        let _ = chip.append_child(&remove);
+        let _ = list.append_child(&chip);
+    }
+}
+
+struct Inner {
+    options: Rc<TagsInputOptions>,
+    tags: Vec<String>,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn chips(&self) -> Vec<HtmlElement> {
+        let Ok(nodes) = self.options.list.query_selector_all("[data-seigi-tag]") else {
+            return vec![];
+        };
+        (0..nodes.length())
+            .filter_map(|v| nodes.get(v))
+            .filter_map(|v| v.dyn_into::<HtmlElement>().ok())
+            .collect()
+    }
+
+    fn check(&self, tag: &str) -> Result<(), Rejection> {
+        if self.tags.iter().any(|v| v == tag) {
+            return Err(Rejection::Duplicate);
+        }
+        if self.options.max.is_some_and(|v| self.tags.len() >= v) {
+            return Err(Rejection::Max);
+        }
+        if let Some(validate) = &self.options.validate {
+            validate(tag).map_err(Rejection::Invalid)?;
+        }
+        Ok(())
+    }
+
+    fn render(&self) {
+        let options = &self.options;
+        (options.render)(&options.list, &self.tags);
+        for chip in self.chips() {
+            // Chips are reached with arrow keys from the input rather than with Tab
+            chip.set_tab_index(-1);
+        }
+
+        let is_full = options.max.is_some_and(|v| self.tags.len() >= v);
+        if is_full {
+            let _ = options.container.set_attribute("data-full", "");
+        } else {
+            let _ = options.container.remove_attribute("data-full");
+        }
+        if let Some(input) = &options.value_input {
+            input.set_value(&self.tags.join(","));
+        }
+    }
+
+    fn set_invalid(&self, rejection: Option<&Rejection>) {
+        let options = &self.options;
+        match rejection {
+            Some(rejection) => {
+                let _ = options.container.set_attribute("data-invalid", "");
+                let _ = options.input.set_attribute("aria-invalid", "true");
+                options.input.set_custom_validity(&rejection.to_string());
+            }
+            None => {
+                let _ = options.container.remove_attribute("data-invalid");
+                let _ = options.input.remove_attribute("aria-invalid");
+                options.input.set_custom_validity("");
+            }
+        }
+    }
+
+    fn is_rtl(&self) -> bool {
+        direction::is_rtl(&self.options.container)
+    }
+}
+
+/// An instance of tags input
+///
+/// Text typed into the input becomes a tag on Enter or a delimiter, and pasted text is split
+/// into tags by delimiters and lines. Tags are trimmed, empty ones are ignored, and duplicates,
+/// tags past the maximum and tags failing validation are rejected.
+///
+/// Backspace in the empty input takes the last tag back into the input for editing. Moving the
+/// caret before the start of the input focuses chips, which are navigated with arrow keys and
+/// removed with Backspace or Delete.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-seigi-tag** is set in chips to their tag by [render_default]; custom renderers should
+/// set it too, and may put a button with **data-seigi-tag-remove** in chips
+///
+/// **data-invalid** is set in the container while the text in the input was rejected, along
+/// with **aria-invalid** and the custom validity message of the input
+///
+/// **data-full** is set in the container while the maximum count of tags is reached
+#[derive(Clone)]
+pub struct TagsInput(Rc<Mutex<Inner>>);
+
+impl TagsInput {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    pub fn tags(&self) -> Vec<String> {
+        self.0.lock().unwrap().tags.clone()
+    }
+
+    /// Sets tags without checking them or calling the change hook
+    pub fn set_tags(&self, tags: Vec<String>) {
+        let mut inner = self.0.lock().unwrap();
+        inner.tags = tags;
+        inner.render();
+    }
+
+    /// Adds the tag, calling the reject hook if it is rejected
+    pub fn add(&self, tag: &str) -> Result<(), Rejection> {
+        let tag = tag.trim();
+        let (options, result) = {
+            let mut inner = self.0.lock().unwrap();
+            let result = inner.check(tag);
+            if result.is_ok() {
+                inner.tags.push(tag.to_string());
+                inner.render();
+            }
+            (inner.options.clone(), result)
+        };
+
+        match &result {
+            Ok(()) => self.changed(&options),
+            Err(rejection) => {
+                if let Some(hook) = &options.hooks.reject {
+                    hook(tag, rejection);
+                }
+            }
+        }
+        result
+    }
+
+    /// Removes the tag at the index, returning it
+    pub fn remove(&self, index: usize) -> Option<String> {
+        let (options, tag) = {
+            let mut inner = self.0.lock().unwrap();
+            if index >= inner.tags.len() {
+                return None;
+            }
+            let tag = inner.tags.remove(index);
+            inner.render();
+            (inner.options.clone(), tag)
+        };
+        self.changed(&options);
+        Some(tag)
+    }
+
+    pub fn clear(&self) {
+        let options = {
+            let mut inner = self.0.lock().unwrap();
+            if inner.tags.is_empty() {
+                return;
+            }
+            inner.tags.clear();
+            inner.render();
+            inner.options.clone()
+        };
+        self.changed(&options);
+    }
+
+    fn changed(&self, options: &TagsInputOptions) {
+        if let Some(hook) = &options.hooks.change {
+            let tags = self.tags();
+            hook(&tags);
+        }
+    }
+
+    /// Adds the text in the input as a tag, keeping the text if it is rejected
+    fn commit(&self) {
+        let input = self.0.lock().unwrap().options.input.clone();
+        let value = input.value();
+        if value.trim().is_empty() {
+            return;
+        }
+        let result = self.add(&value);
+        if result.is_ok() {
+            input.set_value("");
+        }
+        self.0.lock().unwrap().set_invalid(result.err().as_ref());
+    }
+
+    fn focus_chip(&self, index: usize) {
+        let chips = self.0.lock().unwrap().chips();
+        match chips.get(index) {
+            Some(chip) => {
+                let _ = chip.focus();
+            }
+            None => {
+                let input = self.0.lock().unwrap().options.input.clone();
+                let _ = input.focus();
+            }
+        }
+    }
+
+    fn handle_input_key_down(&self, event: &KeyboardEvent) {
+        let (input, delimiters, is_rtl) = {
+            let inner = self.0.lock().unwrap();
+            (
+                inner.options.input.clone(),
+                inner.options.delimiters.clone(),
+                inner.is_rtl(),
+            )
+        };
+        let key = event.key();
+        let is_delimiter = key.chars().count() == 1 && key.chars().all(|v| delimiters.contains(&v));
+        if (key == "Enter" && !ime::is_composing(event)) || is_delimiter {
+            // Enter would otherwise submit the form
+            if key == "Enter" && input.value().trim().is_empty() {
+                return;
+            }
+            event.prevent_default();
+            self.commit();
+            return;
+        }
+
+        let at_start = input.selection_start().ok().flatten() == Some(0)
+            && input.selection_end().ok().flatten() == Some(0);
+        if !at_start {
+            return;
+        }
+        let count = self.0.lock().unwrap().tags.len();
+        let backward = if is_rtl { "ArrowRight" } else { "ArrowLeft" };
+        if key == "Backspace" && input.value().is_empty() && count > 0 {
+            event.prevent_default();
+            if let Some(tag) = self.remove(count - 1) {
+                input.set_value(&tag);
+            }
+        } else if key == backward && count > 0 {
+            event.prevent_default();
+            self.focus_chip(count - 1);
+        }
+    }
+
+    fn handle_chip_key_down(&self, event: &KeyboardEvent) {
+        let Some(chip) = event
+            .target()
+            .and_then(|v| v.dyn_into::<Element>().ok())
+            .filter(|v| v.has_attribute("data-seigi-tag"))
+        else {
+            return;
+        };
+        let (index, count, is_rtl) = {
+            let inner = self.0.lock().unwrap();
+            let Some(index) = inner.chips().iter().position(|v| **v == chip) else {
+                return;
+            };
+            (index, inner.tags.len(), inner.is_rtl())
+        };
+        let (backward, forward) = if is_rtl {
+            ("ArrowRight", "ArrowLeft")
+        } else {
+            ("ArrowLeft", "ArrowRight")
+        };
+
+        let key = event.key();
+        match key.as_str() {
+            v if v == backward => self.focus_chip(index.saturating_sub(1)),
+            v if v == forward => self.focus_chip(index + 1),
+            "Home" => self.focus_chip(0),
+            "End" => self.focus_chip(count),
+            "Backspace" | "Delete" => {
+                self.remove(index);
+                // Focus stays near the removed chip, or goes back to the input past the last
+                if key == "Backspace" && index > 0 {
+                    self.focus_chip(index - 1);
+                } else {
+                    self.focus_chip(index);
+                }
+            }
+            _ => return,
+        }
+        event.prevent_default();
+    }
+
+    fn handle_paste(&self, event: &ClipboardEvent) {
+        let Some(text) = event.clipboard_data().and_then(|v| v.get_data("text").ok()) else {
+            return;
+        };
+        let (input, delimiters) = {
+            let inner = self.0.lock().unwrap();
+            (
+                inner.options.input.clone(),
+                inner.options.delimiters.clone(),
+            )
+        };
+        let is_separated = |v: char| v == '\n' || v == '\r' || delimiters.contains(&v);
+        if !text.contains(is_separated) {
+            return;
+        }
+
+        event.prevent_default();
+        let text = format!("{}{text}", input.value());
+        let mut rejection = None;
+        for tag in text.split(is_separated).filter(|v| !v.trim().is_empty()) {
+            if let Err(v) = self.add(tag) {
+                rejection = Some(v);
+            }
+        }
+        input.set_value("");
+        self.0.lock().unwrap().set_invalid(rejection.as_ref());
+    }
+
+    fn handle_click(&self, event: &MouseEvent) {
+        let Some(target) = event.target().and_then(|v| v.dyn_into::<Element>().ok()) else {
+            return;
+        };
+        let (list, input) = {
+            let inner = self.0.lock().unwrap();
+            (inner.options.list.clone(), inner.options.input.clone())
+        };
+
+        let remove = target
+            .closest("[data-seigi-tag-remove]")
+            .ok()
+            .flatten()
+            .and_then(|v| v.closest("[data-seigi-tag]").ok().flatten())
+            .filter(|v| list.contains(Some(v)));
+        if let Some(chip) = remove {
+            let index = self
+                .0
+                .lock()
+                .unwrap()
+                .chips()
+                .iter()
+                .position(|v| **v == chip);
+            if let Some(index) = index {
+                self.remove(index);
+            }
+            let _ = input.focus();
+            return;
+        }
+
+        // Pressing the container around chips focuses the input, as if it were one field
+        if target.closest("[data-seigi-tag]").ok().flatten().is_none() {
+            let _ = input.focus();
+        }
+    }
+}
+
+pub fn create_tags_input(options: TagsInputOptions) -> TagsInput {
+    let container = options.container.clone();
+    let list = options.list.clone();
+    let input = options.input.clone();
+    let tags = options
+        .tags
+        .iter()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect();
+
+    let tags_input = TagsInput(Rc::new(Mutex::new(Inner {
+        options: Rc::new(options),
+        tags,
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&tags_input.0);
+    let listeners = vec![
+        EventListener::new(&input, "keydown", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(tags_input), Some(event)) = (
+                    TagsInput::from_weak(&weak),
+                    event.dyn_ref::<KeyboardEvent>(),
+                ) else {
+                    return;
+                };
+                tags_input.handle_input_key_down(event);
+            }
+        }),
+        EventListener::new(&input, "input", {
+            let weak = weak.clone();
+            move |_| {
+                if let Some(tags_input) = TagsInput::from_weak(&weak) {
+                    tags_input.0.lock().unwrap().set_invalid(None);
+                }
+            }
+        }),
+        EventListener::new(&input, "paste", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(tags_input), Some(event)) = (
+                    TagsInput::from_weak(&weak),
+                    event.dyn_ref::<ClipboardEvent>(),
+                ) else {
+                    return;
+                };
+                tags_input.handle_paste(event);
+            }
+        }),
+        EventListener::new(&list, "keydown", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(tags_input), Some(event)) = (
+                    TagsInput::from_weak(&weak),
+                    event.dyn_ref::<KeyboardEvent>(),
+                ) else {
+                    return;
+                };
+                tags_input.handle_chip_key_down(event);
+            }
+        }),
+        EventListener::new(&container, "click", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(tags_input), Some(event)) =
+                    (TagsInput::from_weak(&weak), event.dyn_ref::<MouseEvent>())
+                else {
+                    return;
+                };
+                tags_input.handle_click(event);
+            }
+        }),
+    ];
+
+    {
+        let mut inner = tags_input.0.lock().unwrap();
+        inner._listeners = listeners;
+        inner.render();
+    }
+    tags_input
+}
@@ -0,0 +1,311 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use seigi_tags_input::{
+    Rejection, TagsInput, TagsInputHooks, TagsInputOptions, TagsInputOptionsBuilder,
+};
+use seigi_testkit::{
+    Fixture, assert_focused,
+    events::{self, Modifiers},
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::{ClipboardEvent, ClipboardEventInit, DataTransfer, HtmlInputElement};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<div id="container">
+    <div id="list"></div>
+    <input id="input" type="text">
+</div>
+<input id="value" type="hidden">
+"#;
+
+#[derive(Default)]
+struct Calls {
+    changes: Vec<Vec<String>>,
+    rejections: Vec<(String, Rejection)>,
+}
+
+fn input(fixture: &Fixture) -> HtmlInputElement {
+    fixture.get("#input").unchecked_into()
+}
+
+fn options(fixture: &Fixture, tags: &[&str]) -> TagsInputOptionsBuilder {
+    TagsInputOptions::builder()
+        .container(fixture.get("#container"))
+        .list(fixture.get("#list"))
+        .input(input(fixture))
+        .tags(tags.iter().map(|v| v.to_string()).collect())
+}
+
+fn tags_input(options: TagsInputOptionsBuilder) -> (TagsInput, Rc<RefCell<Calls>>) {
+    let calls = Rc::new(RefCell::new(Calls::default()));
+    let tags_input = seigi_tags_input::create_tags_input(
+        options
+            .hooks(TagsInputHooks {
+                change: Some(Box::new({
+                    let calls = calls.clone();
+                    move |tags| calls.borrow_mut().changes.push(tags.to_vec())
+                })),
+                reject: Some(Box::new({
+                    let calls = calls.clone();
+                    move |tag, rejection| {
+                        calls
+                            .borrow_mut()
+                            .rejections
+                            .push((tag.to_string(), rejection.clone()))
+                    }
+                })),
+            })
+            .build(),
+    );
+    (tags_input, calls)
+}
+
+fn chips(fixture: &Fixture) -> Vec<web_sys::HtmlElement> {
+    fixture.get_all("[data-seigi-tag]")
+}
+
+/// Types the text into the input and presses the key
+fn enter(fixture: &Fixture, text: &str, key: &str) -> bool {
+    let input = input(fixture);
+    input.set_value(text);
+    events::key_down(&input, key, Modifiers::default())
+}
+
+fn press(target: &web_sys::HtmlElement, key: &str) -> bool {
+    events::key_down(target, key, Modifiers::default())
+}
+
+fn paste(fixture: &Fixture, text: &str) -> bool {
+    let transfer = DataTransfer::new().unwrap();
+    transfer.set_data("text", text).unwrap();
+    let init = ClipboardEventInit::new();
+    init.set_bubbles(true);
+    init.set_cancelable(true);
+    init.set_clipboard_data(Some(&transfer));
+    let event = ClipboardEvent::new_with_event_init_dict("paste", &init).unwrap();
+    events::dispatch(&input(fixture), &event)
+}
+
+#[wasm_bindgen_test]
+fn tags_are_rendered_as_chips() {
+    let fixture = Fixture::new(MARKUP);
+    let value = fixture.get("#value").unchecked_into::<HtmlInputElement>();
+    let (tags_input, _) =
+        tags_input(options(&fixture, &[" rust ", "", "wasm"]).value_input(value.clone()));
+
+    assert_eq!(tags_input.tags(), ["rust", "wasm"]);
+    let chips = chips(&fixture);
+    assert_eq!(chips.len(), 2);
+    assert_eq!(
+        chips[0].get_attribute("data-seigi-tag").as_deref(),
+        Some("rust")
+    );
+    assert_eq!(chips[0].tab_index(), -1);
+    let remove = chips[0]
+        .query_selector("[data-seigi-tag-remove]")
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        remove.get_attribute("aria-label").as_deref(),
+        Some("Remove rust")
+    );
+    assert_eq!(value.value(), "rust,wasm");
+}
+
+#[wasm_bindgen_test]
+fn enter_and_delimiters_add_tags() {
+    let fixture = Fixture::new(MARKUP);
+    let (tags_input, calls) = tags_input(options(&fixture, &[]));
+
+    assert!(!enter(&fixture, " rust ", "Enter"));
+    assert_eq!(tags_input.tags(), ["rust"]);
+    assert_eq!(input(&fixture).value(), "");
+    assert!(!enter(&fixture, "wasm", ","));
+    assert_eq!(tags_input.tags(), ["rust", "wasm"]);
+
+    // Enter in the empty input is left to submit the form
+    assert!(enter(&fixture, " ", "Enter"));
+    assert_eq!(tags_input.tags(), ["rust", "wasm"]);
+    assert_eq!(calls.borrow().changes, [vec!["rust"], vec!["rust", "wasm"]]);
+}
+
+#[wasm_bindgen_test]
+fn rejected_text_stays_in_input() {
+    let fixture = Fixture::new(MARKUP);
+    let (tags_input, calls) = tags_input(options(&fixture, &["rust"]));
+    let (container, input) = (fixture.get("#container"), input(&fixture));
+
+    enter(&fixture, "rust", "Enter");
+    assert_eq!(tags_input.tags(), ["rust"]);
+    assert_eq!(input.value(), "rust");
+    assert!(container.has_attribute("data-invalid"));
+    assert_eq!(input.get_attribute("aria-invalid").as_deref(), Some("true"));
+    assert_eq!(
+        input.validation_message().unwrap(),
+        "The tag is already added"
+    );
+    assert_eq!(
+        calls.borrow().rejections,
+        [("rust".to_string(), Rejection::Duplicate)]
+    );
+
+    // Typing again clears the rejection
+    events::fire(&input, "input");
+    assert!(!container.has_attribute("data-invalid"));
+    assert_eq!(input.get_attribute("aria-invalid"), None);
+    assert!(input.check_validity());
+}
+
+#[wasm_bindgen_test]
+fn max_and_validation_reject_tags() {
+    let fixture = Fixture::new(MARKUP);
+    let (tags_input, _) = tags_input(options(&fixture, &["a"]).max(2).validate(Box::new(|tag| {
+        if tag.len() > 3 {
+            Err("Too long".to_string())
+        } else {
+            Ok(())
+        }
+    })));
+    let container = fixture.get("#container");
+
+    assert_eq!(
+        tags_input.add("long"),
+        Err(Rejection::Invalid("Too long".to_string()))
+    );
+    assert_eq!(tags_input.add("b"), Ok(()));
+    assert!(container.has_attribute("data-full"));
+    assert_eq!(tags_input.add("c"), Err(Rejection::Max));
+
+    tags_input.remove(0);
+    assert!(!container.has_attribute("data-full"));
+}
+
+#[wasm_bindgen_test]
+fn backspace_takes_last_tag_back() {
+    let fixture = Fixture::new(MARKUP);
+    let (tags_input, calls) = tags_input(options(&fixture, &["rust", "wasm"]));
+    let input = input(&fixture);
+
+    assert!(!press(&input, "Backspace"));
+    assert_eq!(tags_input.tags(), ["rust"]);
+    assert_eq!(input.value(), "wasm");
+    assert_eq!(calls.borrow().changes, [vec!["rust"]]);
+
+    // Backspace in text edits the text
+    input.set_selection_range(4, 4).unwrap();
+    assert!(press(&input, "Backspace"));
+    assert_eq!(tags_input.tags(), ["rust"]);
+}
+
+#[wasm_bindgen_test]
+fn arrow_keys_move_through_chips() {
+    let fixture = Fixture::new(MARKUP);
+    let _tags_input = tags_input(options(&fixture, &["a", "b", "c"]));
+    let input = input(&fixture);
+    let chips = chips(&fixture);
+
+    assert!(!press(&input, "ArrowLeft"));
+    assert_focused(&chips[2]);
+    press(&chips[2], "ArrowLeft");
+    assert_focused(&chips[1]);
+    press(&chips[1], "Home");
+    assert_focused(&chips[0]);
+    // The first chip stays focused
+    press(&chips[0], "ArrowLeft");
+    assert_focused(&chips[0]);
+    press(&chips[0], "ArrowRight");
+    assert_focused(&chips[1]);
+    press(&chips[1], "End");
+    assert_focused(&input);
+
+    // Right-to-left goes backward with ArrowRight
+    fixture
+        .get("#container")
+        .set_attribute("dir", "rtl")
+        .unwrap();
+    assert!(!press(&input, "ArrowRight"));
+    assert_focused(&chips[2]);
+}
+
+#[wasm_bindgen_test]
+fn chips_are_removed_by_keys() {
+    let fixture = Fixture::new(MARKUP);
+    let (tags_input, _) = tags_input(options(&fixture, &["a", "b", "c", "d"]));
+    let input = input(&fixture);
+
+    let _ = chips(&fixture)[1].focus();
+    assert!(!press(&chips(&fixture)[1], "Delete"));
+    assert_eq!(tags_input.tags(), ["a", "c", "d"]);
+    // Delete keeps focus at the index, on the chip after
+    assert_focused(&chips(&fixture)[1]);
+
+    press(&chips(&fixture)[1], "Backspace");
+    assert_eq!(tags_input.tags(), ["a", "d"]);
+    // Backspace moves focus to the chip before
+    assert_focused(&chips(&fixture)[0]);
+
+    let _ = chips(&fixture)[1].focus();
+    press(&chips(&fixture)[1], "Delete");
+    assert_eq!(tags_input.tags(), ["a"]);
+    // Past the last chip, focus goes back to the input
+    assert_focused(&input);
+}
+
+#[wasm_bindgen_test]
+fn pasted_text_is_split_into_tags() {
+    let fixture = Fixture::new(MARKUP);
+    let (tags_input, _) = tags_input(options(&fixture, &["rust"]));
+    let input = input(&fixture);
+
+    input.set_value("we");
+    assert!(!paste(&fixture, "b, css\n\nrust\r\nhtml"));
+    // The text in the input is joined with the pasted text
+    assert_eq!(tags_input.tags(), ["rust", "web", "css", "html"]);
+    assert_eq!(input.value(), "");
+    // A rejected tag among them marks the input invalid
+    assert!(fixture.get("#container").has_attribute("data-invalid"));
+
+    // Text without delimiters is pasted as usual
+    assert!(paste(&fixture, "plain"));
+}
+
+#[wasm_bindgen_test]
+fn pressing_remove_button_removes_tag() {
+    let fixture = Fixture::new(MARKUP);
+    let (tags_input, _) = tags_input(options(&fixture, &["a", "b"]));
+    let input = input(&fixture);
+
+    let remove = chips(&fixture)[0]
+        .query_selector("[data-seigi-tag-remove]")
+        .unwrap()
+        .unwrap();
+    events::click(&remove);
+    assert_eq!(tags_input.tags(), ["b"]);
+    assert_focused(&input);
+
+    // Pressing around chips focuses the input
+    let _ = chips(&fixture)[0].focus();
+    events::click(&fixture.get("#list"));
+    assert_focused(&input);
+}
+
+#[wasm_bindgen_test]
+fn tags_are_changed_by_methods() {
+    let fixture = Fixture::new(MARKUP);
+    let (tags_input, calls) = tags_input(options(&fixture, &["a"]));
+
+    assert_eq!(tags_input.remove(3), None);
+    tags_input.set_tags(vec!["x".to_string(), "y".to_string()]);
+    assert_eq!(chips(&fixture).len(), 2);
+    assert!(calls.borrow().changes.is_empty());
+
+    tags_input.clear();
+    tags_input.clear();
+    assert!(chips(&fixture).is_empty());
+    assert_eq!(calls.borrow().changes, [Vec::<String>::new()]);
+}
@@ -0,0 +1,69 @@
+//! Assertions of focus, failing with descriptions of the elements involved
+
+use web_sys::{Element, HtmlElement};
+
+use crate::focus::active_element;
+
+/// Describes the element as a selector-like string, such as `button#submit.primary`
+pub fn describe(element: &Element) -> String {
+    let mut description = element.tag_name().to_lowercase();
+    let id = element.id();
+    if !id.is_empty() {
+        description.push('#');
+        description.push_str(&id);
+    }
+    for class in element.class_name().split_whitespace() {
+        description.push('.');
+        description.push_str(class);
+    }
+    description
+}
+
+fn describe_active() -> String {
+    active_element().map_or_else(|| "nothing".to_string(), |v| describe(&v))
+}
+
+/// Asserts that the element is focused
+///
+/// # Panics
+/// This function panics if another element is focused, naming both
+#[track_caller]
+pub fn assert_focused(element: &HtmlElement) {
+    if active_element().as_ref() != Some(element) {
+        panic!(
+            "expected {} to be focused, but {} is",
+            describe(element),
+            describe_active()
+        );
+    }
+}
+
+/// Asserts that the element or one of its descendants is focused, such as a dialog trapping focus
+///
+/// # Panics
+/// This function panics if focus is outside the element, naming the focused element
+#[track_caller]
+pub fn assert_focus_within(element: &Element) {
+    if !crate::focus::is_focus_within(element) {
+        panic!(
+            "expected focus within {}, but {} is focused",
+            describe(element),
+            describe_active()
+        );
+    }
+}
+
+/// Asserts that neither the element nor its descendants are focused, such as a closed dialog
+///
+/// # Panics
+/// This function panics if focus is inside the element, naming the focused element
+#[track_caller]
+pub fn assert_focus_outside(element: &Element) {
+    if crate::focus::is_focus_within(element) {
+        panic!(
+            "expected focus outside {}, but {} is focused",
+            describe(element),
+            describe_active()
+        );
+    }
+}
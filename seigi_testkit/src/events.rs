@@ -0,0 +1,133 @@
+//! Events dispatched to elements as users would cause them
+//!
+//! Dispatched events are untrusted, so browsers run listeners but not default actions. A
+//! synthesized Tab doesn't move focus by itself, for example, so tests check what listeners
+//! did rather than what browsers would do next. [crate::keyboard] and [crate::pointer] add the
+//! default actions on top of these.
+
+use web_sys::{
+    Event, EventInit, EventTarget, FocusEvent, FocusEventInit, KeyboardEvent, KeyboardEventInit,
+    MouseEvent, MouseEventInit, PointerEvent, PointerEventInit,
+};
+
+/// Modifier keys held during an event
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+impl Modifiers {
+    pub fn shift() -> Self {
+        Self {
+            shift: true,
+            ..Self::default()
+        }
+    }
+}
+
+/// Dispatches the event to the target
+///
+/// # Returns
+/// Whether the event wasn't cancelled by a listener
+pub fn dispatch(target: &EventTarget, event: &Event) -> bool {
+    target.dispatch_event(event).unwrap_or(false)
+}
+
+/// Dispatches a bubbling and cancelable event of the type, like `input` or `change`
+pub fn fire(target: &EventTarget, event_type: &str) -> bool {
+    let init = EventInit::new();
+    init.set_bubbles(true);
+    init.set_cancelable(true);
+    let event = Event::new_with_event_init_dict(event_type, &init).expect("failed to create Event");
+    dispatch(target, &event)
+}
+
+/// Creates a keyboard event of the type and `key`, like `Tab` or `a`
+pub fn keyboard_event(event_type: &str, key: &str, modifiers: Modifiers) -> KeyboardEvent {
+    let init = KeyboardEventInit::new();
+    init.set_bubbles(true);
+    init.set_cancelable(true);
+    init.set_composed(true);
+    init.set_key(key);
+    init.set_shift_key(modifiers.shift);
+    init.set_ctrl_key(modifiers.ctrl);
+    init.set_alt_key(modifiers.alt);
+    init.set_meta_key(modifiers.meta);
+    KeyboardEvent::new_with_keyboard_event_init_dict(event_type, &init)
+        .expect("failed to create KeyboardEvent")
+}
+
+/// Dispatches `keydown` of the key to the target
+///
+/// # Returns
+/// Whether the event wasn't cancelled by a listener
+pub fn key_down(target: &EventTarget, key: &str, modifiers: Modifiers) -> bool {
+    dispatch(target, &keyboard_event("keydown", key, modifiers))
+}
+
+/// Dispatches `keydown` of the key to the target as a press picking or confirming candidates
+/// of an IME composing text
+///
+/// # Returns
+/// Whether the event wasn't cancelled by a listener
+pub fn composing_key_down(target: &EventTarget, key: &str) -> bool {
+    let init = KeyboardEventInit::new();
+    init.set_bubbles(true);
+    init.set_cancelable(true);
+    init.set_composed(true);
+    init.set_key(key);
+    init.set_is_composing(true);
+    let event = KeyboardEvent::new_with_keyboard_event_init_dict("keydown", &init)
+        .expect("failed to create KeyboardEvent");
+    dispatch(target, &event)
+}
+
+/// Dispatches `keyup` of the key to the target
+pub fn key_up(target: &EventTarget, key: &str, modifiers: Modifiers) -> bool {
+    dispatch(target, &keyboard_event("keyup", key, modifiers))
+}
+
+/// Creates a mouse event of the type at the client position
+pub fn mouse_event(event_type: &str, x: f64, y: f64) -> MouseEvent {
+    let init = MouseEventInit::new();
+    init.set_bubbles(true);
+    init.set_cancelable(true);
+    init.set_composed(true);
+    init.set_client_x(x as i32);
+    init.set_client_y(y as i32);
+    MouseEvent::new_with_mouse_event_init_dict(event_type, &init)
+        .expect("failed to create MouseEvent")
+}
+
+/// Dispatches `click` to the target
+pub fn click(target: &EventTarget) -> bool {
+    dispatch(target, &mouse_event("click", 0.0, 0.0))
+}
+
+/// Creates a pointer event of the type at the client position, from the primary mouse pointer
+pub fn pointer_event(event_type: &str, x: f64, y: f64) -> PointerEvent {
+    let init = PointerEventInit::new();
+    init.set_bubbles(true);
+    init.set_cancelable(true);
+    init.set_composed(true);
+    init.set_client_x(x as i32);
+    init.set_client_y(y as i32);
+    init.set_pointer_id(1);
+    init.set_pointer_type("mouse");
+    init.set_is_primary(true);
+    PointerEvent::new_with_event_init_dict(event_type, &init)
+        .expect("failed to create PointerEvent")
+}
+
+/// Creates a focus event of the type, like `focusin` or `focusout`, related to the other element
+pub fn focus_event(event_type: &str, related: Option<&EventTarget>) -> FocusEvent {
+    let init = FocusEventInit::new();
+    init.set_bubbles(matches!(event_type, "focusin" | "focusout"));
+    init.set_composed(true);
+    init.set_related_target(related);
+    FocusEvent::new_with_focus_event_init_dict(event_type, &init)
+        .expect("failed to create FocusEvent")
+}
@@ -0,0 +1,74 @@
+//! Markup mounted for a test
+
+use gloo::utils::{body, document};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+/// Markup mounted in a container appended to the body, which is removed when dropped
+///
+/// Elements are looked up within the container, so fixtures of tests running one after another
+/// don't see each other.
+pub struct Fixture {
+    root: HtmlElement,
+}
+
+impl Fixture {
+    /// Mounts the markup
+    ///
+    /// # Panics
+    /// This method panics if the container can't be created, which only happens outside browsers
+    pub fn new(html: &str) -> Self {
+        let root = document()
+            .create_element("div")
+            .expect("failed to create fixture")
+            .unchecked_into::<HtmlElement>();
+        let _ = root.set_attribute("data-seigi-fixture", "");
+        root.set_inner_html(html);
+        body().append_child(&root).expect("failed to mount fixture");
+        Self { root }
+    }
+
+    /// Returns the container of the markup
+    pub fn root(&self) -> HtmlElement {
+        self.root.clone()
+    }
+
+    /// Returns the first element matching the selector
+    ///
+    /// # Panics
+    /// This method panics if no element matches, naming the selector
+    pub fn get(&self, selector: &str) -> HtmlElement {
+        self.find(selector)
+            .unwrap_or_else(|| panic!("no element matches {selector:?} in the fixture"))
+    }
+
+    /// Returns the first element matching the selector, or None if no element matches
+    pub fn find(&self, selector: &str) -> Option<HtmlElement> {
+        self.root
+            .query_selector(selector)
+            .ok()
+            .flatten()
+            .and_then(|v| v.dyn_into::<HtmlElement>().ok())
+    }
+
+    /// Returns every element matching the selector in document order
+    pub fn get_all(&self, selector: &str) -> Vec<HtmlElement> {
+        let Ok(list) = self.root.query_selector_all(selector) else {
+            return vec![];
+        };
+        (0..list.length())
+            .filter_map(|index| list.get(index)?.dyn_into::<HtmlElement>().ok())
+            .collect()
+    }
+
+    /// Returns the focused element of the document
+    pub fn active_element(&self) -> Option<HtmlElement> {
+        crate::focus::active_element()
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        self.root.remove();
+    }
+}
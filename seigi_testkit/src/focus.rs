@@ -0,0 +1,65 @@
+//! Focus transitions and queries of the focused element
+
+use gloo::utils::document;
+use seigi_focus::candidates;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement};
+
+use crate::wait;
+
+/// Returns the focused element of the document
+pub fn active_element() -> Option<HtmlElement> {
+    document()
+        .active_element()
+        .and_then(|v| v.dyn_into::<HtmlElement>().ok())
+}
+
+/// Returns whether the focused element is the element or its descendant
+pub fn is_focus_within(element: &Element) -> bool {
+    active_element().is_some_and(|v| element.contains(Some(&v)))
+}
+
+/// Focuses the element and waits for focus moved by listeners in return, like focus traps
+/// pulling focus back in
+///
+/// # Returns
+/// The focused element afterwards
+pub async fn focus(element: &HtmlElement) -> Option<HtmlElement> {
+    let _ = element.focus();
+    wait::tick().await;
+    active_element()
+}
+
+/// Blurs the focused element and waits for focus moved by listeners in return
+///
+/// # Returns
+/// The focused element afterwards
+pub async fn blur() -> Option<HtmlElement> {
+    if let Some(element) = active_element() {
+        let _ = element.blur();
+    }
+    wait::tick().await;
+    active_element()
+}
+
+/// Focuses the nearest focusable ancestor of the target, as browsers do on `mousedown`
+pub(crate) fn focus_nearest(target: &HtmlElement) {
+    let mut current = Some(target.clone());
+    while let Some(element) = current {
+        if element
+            .matches(":is(a[href], button, input, select, textarea, [tabindex], [contenteditable])")
+            .unwrap_or(false)
+            && candidates::is_focusable(&element)
+        {
+            let _ = element.focus();
+            return;
+        }
+        current = element
+            .parent_element()
+            .and_then(|v| v.dyn_into::<HtmlElement>().ok());
+    }
+    // Clicking nothing focusable moves focus to the body
+    if let Some(element) = active_element() {
+        let _ = element.blur();
+    }
+}
@@ -0,0 +1,94 @@
+//! Keys pressed on the focused element, with the navigation browsers would do by default
+//!
+//! Unlike [events::key_down], Tab here also moves focus as browsers would when no listener
+//! cancels it, so tests can walk through a page the way keyboard users do.
+
+use gloo::utils::{body, document};
+use seigi_focus::candidates;
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlElement};
+
+use crate::{
+    events::{self, Modifiers},
+    focus, wait,
+};
+
+/// Returns the element keys are pressed on, which is the focused element or the body
+fn key_target() -> EventTarget {
+    document()
+        .active_element()
+        .map(|v| v.unchecked_into())
+        .unwrap_or_else(|| body().unchecked_into())
+}
+
+/// Presses the key on the focused element and waits for focus scheduled by listeners
+///
+/// # Returns
+/// Whether `keydown` wasn't cancelled by a listener
+pub async fn press(key: &str, modifiers: Modifiers) -> bool {
+    let target = key_target();
+    let not_cancelled = events::key_down(&target, key, modifiers);
+    events::key_up(&target, key, modifiers);
+    wait::tick().await;
+    not_cancelled
+}
+
+/// Presses Tab, moving focus to the next tabbable element unless a listener cancels it
+///
+/// # Returns
+/// The focused element afterwards
+pub async fn tab() -> Option<HtmlElement> {
+    navigate(false).await
+}
+
+/// Presses Shift+Tab, moving focus to the previous tabbable element unless a listener cancels it
+///
+/// # Returns
+/// The focused element afterwards
+pub async fn shift_tab() -> Option<HtmlElement> {
+    navigate(true).await
+}
+
+/// Presses Escape
+///
+/// # Returns
+/// Whether `keydown` wasn't cancelled by a listener
+pub async fn escape() -> bool {
+    press("Escape", Modifiers::default()).await
+}
+
+/// Presses Enter
+///
+/// # Returns
+/// Whether `keydown` wasn't cancelled by a listener
+pub async fn enter() -> bool {
+    press("Enter", Modifiers::default()).await
+}
+
+async fn navigate(is_backward: bool) -> Option<HtmlElement> {
+    let target = key_target();
+    let modifiers = Modifiers {
+        shift: is_backward,
+        ..Modifiers::default()
+    };
+    if events::key_down(&target, "Tab", modifiers) {
+        let tabbables = candidates::tab_candidates(body().unchecked_ref());
+        let position = focus::active_element().and_then(|v| tabbables.iter().position(|t| *t == v));
+        // Browsers leave the page past either end, which wraps around in a single page
+        let next = match (position, is_backward) {
+            (Some(position), false) => tabbables.get(position + 1).or(tabbables.first()),
+            (Some(position), true) => position
+                .checked_sub(1)
+                .and_then(|v| tabbables.get(v))
+                .or(tabbables.last()),
+            (None, false) => tabbables.first(),
+            (None, true) => tabbables.last(),
+        };
+        if let Some(next) = next {
+            let _ = next.focus();
+        }
+    }
+    events::key_up(&key_target(), "Tab", modifiers);
+    wait::tick().await;
+    focus::active_element()
+}
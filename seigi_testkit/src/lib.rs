@@ -0,0 +1,41 @@
+//! Test support of seigi widgets in browsers
+//!
+//! Tests run in headless browsers with `wasm-bindgen-test`. [Fixture] mounts markup for a test
+//! and removes it afterwards, [wait] waits for timers and frames widgets schedule work to, and
+//! [events] dispatches events as users would.
+//!
+//! Applications using seigi can write accessibility regression tests of their own widgets with
+//! [keyboard] and [pointer], which simulate what users do including the focus browsers move by
+//! default, and assertions like [assert_focus_within].
+//!
+//! [perf] measures widgets on large documents against performance budgets, for benchmarks.
+//!
+//! [replay] re-drives inputs recorded by focus traps, to reproduce focus bugs users report.
+//!
+//! # Examples
+//! A test mounting a fixture and waiting for a scheduled focus
+//! ```ignore
+//! let fixture = Fixture::new(r#"<div id="trap"><button>First</button></div>"#);
+//! trap.activate();
+//! wait::tick().await;
+//! assert_focused(&fixture.get("button"));
+//!
+//! // Tab doesn't leave the dialog, and Escape closes it
+//! keyboard::tab().await;
+//! assert_focus_within(&fixture.get("#trap"));
+//! keyboard::escape().await;
+//! assert_focus_outside(&fixture.get("#trap"));
+//! ```
+
+pub mod assert;
+pub mod events;
+mod fixture;
+pub mod focus;
+pub mod keyboard;
+pub mod perf;
+pub mod pointer;
+pub mod replay;
+pub mod wait;
+
+pub use assert::{assert_focus_outside, assert_focus_within, assert_focused};
+pub use fixture::Fixture;
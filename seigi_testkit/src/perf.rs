@@ -0,0 +1,70 @@
+//! Performance budgets of widgets on large documents
+//!
+//! Benchmarks of seigi measure hot paths with `wasm-bindgen-test`'s Criterion for detailed
+//! numbers, and check them against budgets with [assert_budget]. Budgets are meant for optimized
+//! builds, like `cargo bench`, and are ceilings rather than expectations, so they hold on slow
+//! machines of CI.
+
+use std::time::Duration;
+
+/// Returns the current time of the page
+pub fn now() -> Duration {
+    let millis = gloo::utils::window().performance().map_or(0.0, |v| v.now());
+    Duration::from_secs_f64(millis / 1000.0)
+}
+
+/// Returns the median of the samples, or zero if there are none
+pub fn median_of(mut samples: Vec<Duration>) -> Duration {
+    samples.sort();
+    samples.get(samples.len() / 2).copied().unwrap_or_default()
+}
+
+/// Runs the routine once to warm up and then the number of times, returning the median duration
+/// of a run
+pub fn median(runs: usize, mut routine: impl FnMut()) -> Duration {
+    routine();
+    median_of(
+        (0..runs)
+            .map(|_| {
+                let start = now();
+                routine();
+                now() - start
+            })
+            .collect(),
+    )
+}
+
+/// Asserts that the measured duration is within the budget
+///
+/// # Panics
+/// This function panics if the duration exceeds the budget, naming both
+#[track_caller]
+pub fn assert_budget(name: &str, budget: Duration, measured: Duration) {
+    if measured > budget {
+        panic!("{name} took {measured:?}, which exceeds the budget of {budget:?}");
+    }
+}
+
+/// Returns markup of a large document with about the number of tab candidates
+///
+/// Rows mix tabbable elements with ones that aren't, like disabled buttons, hidden inputs and
+/// inert subtrees, so scans take every rule into account. Each row is a `section` with an id of
+/// its index, like `#row-12`.
+pub fn large_document(candidates: usize) -> String {
+    const ROW: &str = r##"<section id="row-{index}">
+    <h2>Row {index}</h2>
+    <p>Some text in row {index}, which is not focusable</p>
+    <a href="#row-{index}">Link</a>
+    <button>Button</button>
+    <input type="text">
+    <input type="hidden">
+    <button disabled="true">Disabled</button>
+    <div tabindex="-1">Programmatically focusable</div>
+    <div tabindex="0">Tabbable</div>
+    <div inert><button>Inert</button></div>
+</section>"##;
+    // Rows have 4 tab candidates
+    (0..candidates.div_ceil(4))
+        .map(|index| ROW.replace("{index}", &index.to_string()))
+        .collect()
+}
@@ -0,0 +1,77 @@
+//! Pointer sequences as a mouse would cause them
+//!
+//! A click by a user is a sequence of `pointerdown`, `mousedown`, `pointerup`, `mouseup` and
+//! `click`, and widgets often listen to an early one of them, like focus traps blocking
+//! `mousedown` outside. These functions dispatch the whole sequence, including the focus
+//! browsers move on `mousedown` unless it is cancelled. Cancelling `pointerdown` suppresses
+//! `mousedown` as browsers do, while focus still moves.
+
+use web_sys::HtmlElement;
+
+use crate::{events, focus, wait};
+
+/// What listeners did to a pointer sequence
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// Whether neither `pointerdown` nor `mousedown` was cancelled
+    pub pressed: bool,
+    /// Whether `click` wasn't cancelled, so its default action would run
+    pub clicked: bool,
+}
+
+/// Presses the primary button on the target, moving focus to it unless `mousedown` is cancelled
+///
+/// # Returns
+/// Whether neither `pointerdown` nor `mousedown` was cancelled
+pub fn down(target: &HtmlElement) -> bool {
+    let (x, y) = center(target);
+    if !events::dispatch(target, &events::pointer_event("pointerdown", x, y)) {
+        focus::focus_nearest(target);
+        return false;
+    }
+    let pressed = events::dispatch(target, &events::mouse_event("mousedown", x, y));
+    if pressed {
+        focus::focus_nearest(target);
+    }
+    pressed
+}
+
+/// Releases the primary button on the target
+pub fn up(target: &HtmlElement) {
+    let (x, y) = center(target);
+    events::dispatch(target, &events::pointer_event("pointerup", x, y));
+    events::dispatch(target, &events::mouse_event("mouseup", x, y));
+}
+
+/// Clicks the target with the whole sequence, then waits for focus scheduled by listeners
+pub async fn click(target: &HtmlElement) -> Outcome {
+    let pressed = down(target);
+    up(target);
+    let (x, y) = center(target);
+    let clicked = events::dispatch(target, &events::mouse_event("click", x, y));
+    wait::tick().await;
+    Outcome { pressed, clicked }
+}
+
+/// Moves the pointer over the target, dispatching `pointerover`, `pointerenter` and `pointermove`
+pub fn hover(target: &HtmlElement) {
+    let (x, y) = center(target);
+    events::dispatch(target, &events::pointer_event("pointerover", x, y));
+    events::dispatch(target, &events::pointer_event("pointerenter", x, y));
+    events::dispatch(target, &events::pointer_event("pointermove", x, y));
+}
+
+/// Moves the pointer out of the target, dispatching `pointerout` and `pointerleave`
+pub fn leave(target: &HtmlElement) {
+    let (x, y) = center(target);
+    events::dispatch(target, &events::pointer_event("pointerout", x, y));
+    events::dispatch(target, &events::pointer_event("pointerleave", x, y));
+}
+
+fn center(target: &HtmlElement) -> (f64, f64) {
+    let rect = target.get_bounding_client_rect();
+    (
+        rect.left() + rect.width() / 2.0,
+        rect.top() + rect.height() / 2.0,
+    )
+}
@@ -0,0 +1,85 @@
+//! Replays of inputs recorded by focus traps, to reproduce focus bugs reported by users
+//!
+//! A [Recording] of [seigi_focus::recorder] is replayed against the markup of the scope of the
+//! recorded trap, mounted in a [crate::Fixture] with a trap created like the original. Recording
+//! the replay in turn and comparing [Recording::decisions] shows where the trap decides otherwise.
+//!
+//! # Examples
+//! ```ignore
+//! let recording = Recording::from_json(REPORTED)?;
+//! let fixture = Fixture::new(MARKUP);
+//! let trap = seigi_focus::create(options.scope(fixture.root()).build());
+//! trap.activate();
+//! trap.start_recording();
+//! replay::replay(&fixture.root(), &recording).await;
+//! assert_eq!(trap.take_recording().unwrap().decisions(), recording.decisions());
+//! ```
+
+use seigi_focus::recorder::{self, Input, Recording};
+use web_sys::HtmlElement;
+
+use crate::{
+    events::Modifiers,
+    focus::{self, active_element},
+    keyboard, pointer,
+};
+
+/// Re-drives the inputs of the recording one after another against the root, which has the
+/// markup of the scope the recording was made in
+///
+/// Focus moves marked as [seigi_focus::recorder::Entry::follow_up] are skipped, as the inputs
+/// before them cause them again. Other focus moves focus the target, pointer presses click it
+/// with [pointer::click], and keys are pressed with [keyboard] on the target, focusing it first
+/// if focus is elsewhere. Each input waits for focus scheduled by listeners rather than for its
+/// recorded time.
+///
+/// # Panics
+/// This function panics if the root has no element at the path of an entry, naming the entry
+pub async fn replay(root: &HtmlElement, recording: &Recording) {
+    for (index, entry) in recording.entries.iter().enumerate() {
+        if entry.follow_up {
+            continue;
+        }
+        let target = recorder::resolve(root, &entry.target).unwrap_or_else(|| {
+            panic!(
+                "entry {index} targets {:?}, which the markup doesn't have",
+                entry.target
+            )
+        });
+        match &entry.input {
+            Input::FocusIn => {
+                focus::focus(&target).await;
+            }
+            Input::PointerDown => {
+                pointer::click(&target).await;
+            }
+            Input::KeyDown { key, shift } => {
+                if active_element().as_ref() != Some(&target) {
+                    let _ = target.focus();
+                }
+                press(key, *shift).await;
+            }
+        }
+    }
+}
+
+async fn press(key: &str, shift: bool) {
+    match (key, shift) {
+        ("Tab", false) => {
+            keyboard::tab().await;
+        }
+        ("Tab", true) => {
+            keyboard::shift_tab().await;
+        }
+        _ => {
+            keyboard::press(
+                key,
+                Modifiers {
+                    shift,
+                    ..Modifiers::default()
+                },
+            )
+            .await;
+        }
+    }
+}
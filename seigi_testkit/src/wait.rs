@@ -0,0 +1,44 @@
+//! Waiting for work widgets schedule to timers and frames
+
+use gloo::{render::request_animation_frame, timers::callback::Timeout};
+use js_sys::Promise;
+use wasm_bindgen_futures::JsFuture;
+
+/// Waits for the timeout in milliseconds
+pub async fn sleep(millis: u32) {
+    let (resolve, resolved) = signal();
+    Timeout::new(millis, resolve).forget();
+    let _ = resolved.await;
+}
+
+/// Waits for timeouts of 0ms scheduled before, such as focus moved by focus traps
+pub async fn tick() {
+    sleep(0).await;
+}
+
+/// Waits for pending microtasks, such as resolved promises
+pub async fn microtask() {
+    let _ = JsFuture::from(Promise::resolve(&wasm_bindgen::JsValue::UNDEFINED)).await;
+}
+
+/// Waits for the next animation frame, after callbacks batched into it have run
+pub async fn frame() {
+    let (resolve, resolved) = signal();
+    let _frame = request_animation_frame(move |_| resolve());
+    let _ = resolved.await;
+    // Callbacks requested before run in the same frame, so yield once more to run after them
+    tick().await;
+}
+
+/// A one-shot signal made of a promise, resolved by calling the function
+fn signal() -> (impl FnOnce(), JsFuture) {
+    let mut resolve = None;
+    let promise = Promise::new(&mut |v, _| resolve = Some(v));
+    let resolve = resolve.unwrap();
+    (
+        move || {
+            let _ = resolve.call0(&wasm_bindgen::JsValue::NULL);
+        },
+        JsFuture::from(promise),
+    )
+}
@@ -0,0 +1,405 @@
+//! Themes of design tokens applied as CSS custom properties, with light, dark and system color
+//! schemes
+
+pub mod theme;
+
+use std::{
+    rc::{Rc, Weak},
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use gloo::utils::document_element;
+use seigi_utils::{
+    media,
+    persist::{Persisted, PersistedOptions, Value, create_persisted},
+};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+pub use theme::Theme;
+
+/// The color scheme a theme is applied in
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Light,
+    Dark,
+    /// Follows the color scheme the user prefers
+    #[default]
+    System,
+}
+
+impl Mode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Light => "light",
+            Self::Dark => "dark",
+            Self::System => "system",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "light" => Some(Self::Light),
+            "dark" => Some(Self::Dark),
+            "system" => Some(Self::System),
+            _ => None,
+        }
+    }
+}
+
+/// A snapshot of [ThemeManager] passed to subscribers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThemeState {
+    pub theme: String,
+    pub mode: Mode,
+    /// Whether the dark color scheme is used, resolved from the system in [Mode::System]
+    pub is_dark: bool,
+}
+
+/// Options of [ThemeManager]
+pub struct ThemeManagerOptions {
+    /// The element tokens are applied to, which defaults to the document element
+    pub scope: Option<HtmlElement>,
+    /// Themes to choose from, where the first is used initially
+    pub themes: Vec<Theme>,
+    pub mode: Mode,
+    /// The key of local storage the theme and the mode are saved to and restored from
+    pub storage_key: Option<String>,
+}
+
+impl ThemeManagerOptions {
+    pub fn builder() -> ThemeManagerOptionsBuilder {
+        ThemeManagerOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [ThemeManagerOptions]
+#[derive(Default)]
+pub struct ThemeManagerOptionsBuilder {
+    scope: Option<HtmlElement>,
+    themes: Vec<Theme>,
+    mode: Mode,
+    storage_key: Option<String>,
+}
+
+impl ThemeManagerOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn scope(mut self, scope: HtmlElement) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    /// Adds the theme, replacing one with the same name
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.themes.retain(|v| v.name != theme.name);
+        self.themes.push(theme);
+        self
+    }
+
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn storage_key(mut self, storage_key: impl Into<String>) -> Self {
+        self.storage_key = Some(storage_key.into());
+        self
+    }
+
+    /// Builds into [ThemeManagerOptions], with [Theme::default] if no theme is added
+    pub fn build(self) -> ThemeManagerOptions {
+        let themes = if self.themes.is_empty() {
+            vec![Theme::default()]
+        } else {
+            self.themes
+        };
+        ThemeManagerOptions {
+            scope: self.scope,
+            themes,
+            mode: self.mode,
+            storage_key: self.storage_key,
+        }
+    }
+}
+
+/// Names of the theme and the mode, as saved to storage
+type Saved = (String, String);
+
+struct Subscriber {
+    callback: Box<dyn Fn(&ThemeState)>,
+    handle: u64,
+}
+
+struct Inner {
+    options: ThemeManagerOptions,
+    scope: HtmlElement,
+    theme: usize,
+    mode: Mode,
+    /// Names of tokens currently applied, removed when the theme changes
+    applied: Vec<String>,
+    subscribers: Vec<Rc<Subscriber>>,
+    system_subscription: u64,
+    storage: Option<Persisted<Saved>>,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        media::dark_scheme().unsubscribe(self.system_subscription);
+    }
+}
+
+impl Inner {
+    fn state(&self) -> ThemeState {
+        ThemeState {
+            theme: self.options.themes[self.theme].name.clone(),
+            mode: self.mode,
+            is_dark: match self.mode {
+                Mode::Light => false,
+                Mode::Dark => true,
+                Mode::System => media::color_scheme() == media::ColorScheme::Dark,
+            },
+        }
+    }
+
+    fn apply(&mut self) {
+        let state = self.state();
+        let style = self.scope.style();
+        let tokens = self.options.themes[self.theme].resolve(state.is_dark);
+        for name in &self.applied {
+            if !tokens.iter().any(|(v, _)| v == name) {
+                let _ = style.remove_property(&format!("--seigi-{name}"));
+            }
+        }
+        for (name, value) in &tokens {
+            let _ = style.set_property(&format!("--seigi-{name}"), value);
+        }
+        self.applied = tokens.into_iter().map(|(v, _)| v).collect();
+
+        let scheme = if state.is_dark { "dark" } else { "light" };
+        let _ = style.set_property("color-scheme", scheme);
+        let _ = self.scope.set_attribute("data-theme", &state.theme);
+        let _ = self.scope.set_attribute("data-color-scheme", scheme);
+    }
+
+    /// Restores the theme and the mode saved as their names
+    ///
+    /// # Returns
+    /// Whether either changed
+    fn restore(&mut self, (theme, mode): &Saved) -> bool {
+        let (before_theme, before_mode) = (self.theme, self.mode);
+        if let Some(index) = self.options.themes.iter().position(|v| v.name == *theme) {
+            self.theme = index;
+        }
+        if let Some(mode) = Mode::parse(mode) {
+            self.mode = mode;
+        }
+        self.theme != before_theme || self.mode != before_mode
+    }
+
+    /// Returns the storage with the theme and the mode to save to it, which is saved after the
+    /// lock is released as saving notifies subscribers
+    fn saved(&self) -> Option<(Persisted<Saved>, Saved)> {
+        let storage = self.storage.clone()?;
+        let value = (
+            self.options.themes[self.theme].name.clone(),
+            self.mode.as_str().to_string(),
+        );
+        Some((storage, value))
+    }
+}
+
+/// An instance of theme manager
+///
+/// Tokens of the current theme are applied to the scope as CSS custom properties prefixed with
+/// `--seigi-`, resolved for the color scheme of the mode. In [Mode::System], they follow the
+/// color scheme the user prefers as it changes. The theme and the mode are restored from local
+/// storage if a key is given, and follow changes made in other tabs.
+///
+/// Default styles of seigi crates read tokens of [Theme::default] with fallbacks, so they work
+/// without a theme manager too.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-theme** is set in the scope to the name of the theme
+///
+/// **data-color-scheme** is set in the scope to `light` or `dark`, which **color-scheme** style
+/// is set to as well
+#[derive(Clone)]
+pub struct ThemeManager(Rc<Mutex<Inner>>);
+
+impl ThemeManager {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    pub fn state(&self) -> ThemeState {
+        self.0.lock().unwrap().state()
+    }
+
+    /// Returns names of themes in the order they were added
+    pub fn themes(&self) -> Vec<String> {
+        self.0
+            .lock()
+            .unwrap()
+            .options
+            .themes
+            .iter()
+            .map(|v| v.name.clone())
+            .collect()
+    }
+
+    /// Sets the theme of the name
+    ///
+    /// # Returns
+    /// Whether a theme of the name exists
+    pub fn set_theme(&self, name: &str) -> bool {
+        {
+            let mut inner = self.0.lock().unwrap();
+            let Some(index) = inner.options.themes.iter().position(|v| v.name == name) else {
+                return false;
+            };
+            if inner.theme == index {
+                return true;
+            }
+            inner.theme = index;
+        }
+        self.save();
+        self.update();
+        true
+    }
+
+    pub fn set_mode(&self, mode: Mode) {
+        {
+            let mut inner = self.0.lock().unwrap();
+            if inner.mode == mode {
+                return;
+            }
+            inner.mode = mode;
+        }
+        self.save();
+        self.update();
+    }
+
+    /// Switches between light and dark modes, from the color scheme currently used
+    pub fn toggle(&self) {
+        let is_dark = self.state().is_dark;
+        self.set_mode(if is_dark { Mode::Light } else { Mode::Dark });
+    }
+
+    /// Subscribes to changes of the theme and the color scheme
+    ///
+    /// # Returns
+    /// A handle to unsubscribe with [ThemeManager::unsubscribe]
+    pub fn subscribe(&self, callback: Box<dyn Fn(&ThemeState)>) -> u64 {
+        static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+        let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .push(Rc::new(Subscriber { callback, handle }));
+        handle
+    }
+
+    pub fn unsubscribe(&self, handle: u64) {
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .retain(|v| v.handle != handle);
+    }
+
+    fn save(&self) {
+        let saved = self.0.lock().unwrap().saved();
+        if let Some((storage, value)) = saved {
+            storage.set(value);
+        }
+    }
+
+    /// Applies tokens again and notifies subscribers
+    fn update(&self) {
+        let (subscribers, state) = {
+            let mut inner = self.0.lock().unwrap();
+            inner.apply();
+            (inner.subscribers.clone(), inner.state())
+        };
+        for subscriber in subscribers {
+            (subscriber.callback)(&state);
+        }
+    }
+}
+
+pub fn create_theme_manager(options: ThemeManagerOptions) -> ThemeManager {
+    let scope = options
+        .scope
+        .clone()
+        .unwrap_or_else(|| document_element().unchecked_into());
+    let mode = options.mode;
+    let storage_key = options.storage_key.clone();
+
+    let manager = ThemeManager(Rc::new(Mutex::new(Inner {
+        options,
+        scope,
+        theme: 0,
+        mode,
+        applied: vec![],
+        subscribers: vec![],
+        system_subscription: 0,
+        storage: None,
+    })));
+
+    let weak = Rc::downgrade(&manager.0);
+    let system_subscription = media::dark_scheme().subscribe(Box::new(move |_| {
+        if let Some(manager) = ThemeManager::from_weak(&weak)
+            && manager.state().mode == Mode::System
+        {
+            manager.update();
+        }
+    }));
+
+    let storage = storage_key.map(|key| {
+        let storage = create_persisted(
+            PersistedOptions::<Saved>::builder()
+                .key(key)
+                // The theme and the mode were saved as comma separated names
+                .migration(Box::new(|value| match value {
+                    Value::String(v) => match v.split_once(',') {
+                        Some((theme, mode)) => Value::from(vec![theme, mode]),
+                        None => Value::Null,
+                    },
+                    value => value,
+                }))
+                .build(),
+        );
+        let weak = Rc::downgrade(&manager.0);
+        storage.subscribe(Box::new(move |saved| {
+            let Some(manager) = ThemeManager::from_weak(&weak) else {
+                return;
+            };
+            let is_changed = manager.0.lock().unwrap().restore(saved);
+            if is_changed {
+                manager.update();
+            }
+        }));
+        storage
+    });
+
+    {
+        let mut inner = manager.0.lock().unwrap();
+        inner.system_subscription = system_subscription;
+        if let Some(storage) = &storage {
+            inner.restore(&storage.get());
+        }
+        inner.storage = storage;
+        inner.apply();
+    }
+    manager
+}
@@ -0,0 +1,80 @@
+//! Named sets of design tokens
+
+/// A named set of design tokens, applied as CSS custom properties
+///
+/// Tokens are named without the `--seigi-` prefix they are applied with, so `color-background`
+/// is applied as `--seigi-color-background`. Dark tokens override light ones while the dark
+/// color scheme is used.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    pub tokens: Vec<(String, String)>,
+    pub dark_tokens: Vec<(String, String)>,
+}
+
+impl Theme {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            tokens: vec![],
+            dark_tokens: vec![],
+        }
+    }
+
+    /// Sets the token, used in both color schemes unless overridden by [Theme::dark_token]
+    pub fn token(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        set(&mut self.tokens, name.into(), value.into());
+        self
+    }
+
+    /// Sets the token used in the dark color scheme
+    pub fn dark_token(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        set(&mut self.dark_tokens, name.into(), value.into());
+        self
+    }
+
+    /// Returns tokens of the color scheme, with dark ones overriding light ones if `is_dark`
+    pub fn resolve(&self, is_dark: bool) -> Vec<(String, String)> {
+        let mut tokens = self.tokens.clone();
+        if is_dark {
+            for (name, value) in &self.dark_tokens {
+                set(&mut tokens, name.clone(), value.clone());
+            }
+        }
+        tokens
+    }
+}
+
+fn set(tokens: &mut Vec<(String, String)>, name: String, value: String) {
+    match tokens.iter_mut().find(|(v, _)| *v == name) {
+        Some(token) => token.1 = value,
+        None => tokens.push((name, value)),
+    }
+}
+
+/// The theme default styles of seigi crates are written against
+///
+/// # Tokens
+/// **color-background**, **color-foreground**, **color-muted**, **color-border**,
+/// **color-accent** and **color-shadow** for colors
+///
+/// **radius** for rounded corners, and **spacing** for the base unit of paddings and gaps
+impl Default for Theme {
+    fn default() -> Self {
+        Self::new("default")
+            .token("color-background", "#fff")
+            .token("color-foreground", "oklch(21% 0.006 285.885)")
+            .token("color-muted", "oklch(55.2% 0.016 285.938)")
+            .token("color-border", "oklch(98.462% 0.001 247.838)")
+            .token("color-accent", "oklch(54.6% 0.245 262.881)")
+            .token("color-shadow", "rgba(0, 0, 0, 0.1)")
+            .token("radius", "0.75rem")
+            .token("spacing", "4px")
+            .dark_token("color-background", "oklch(21% 0.006 285.885)")
+            .dark_token("color-foreground", "oklch(98.5% 0 0)")
+            .dark_token("color-muted", "oklch(70.5% 0.015 286.067)")
+            .dark_token("color-border", "oklch(27.4% 0.006 286.033)")
+            .dark_token("color-accent", "oklch(62.3% 0.214 259.815)")
+            .dark_token("color-shadow", "rgba(0, 0, 0, 0.4)")
+    }
+}
@@ -0,0 +1,206 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use gloo::utils::window;
+use seigi_testkit::Fixture;
+use seigi_theme::{Mode, Theme, ThemeManager, ThemeManagerOptions, ThemeState};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::HtmlElement;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const STORAGE_KEY: &str = "seigi-theme-test";
+
+fn scope(fixture: &Fixture) -> HtmlElement {
+    fixture.get("#scope").unchecked_into()
+}
+
+fn options(fixture: &Fixture) -> ThemeManagerOptions {
+    ThemeManagerOptions::builder()
+        .scope(scope(fixture))
+        .theme(
+            Theme::new("paper")
+                .token("color-background", "white")
+                .token("radius", "2px")
+                .dark_token("color-background", "black"),
+        )
+        .theme(Theme::new("ink").token("color-background", "gray"))
+        .mode(Mode::Light)
+        .storage_key(STORAGE_KEY)
+        .build()
+}
+
+fn token(fixture: &Fixture, name: &str) -> String {
+    scope(fixture)
+        .style()
+        .get_property_value(&format!("--seigi-{name}"))
+        .unwrap()
+}
+
+fn attribute(fixture: &Fixture, name: &str) -> Option<String> {
+    fixture.get("#scope").get_attribute(name)
+}
+
+fn clear_storage() {
+    let storage = window().local_storage().unwrap().unwrap();
+    storage.remove_item(STORAGE_KEY).unwrap();
+}
+
+fn record(manager: &ThemeManager) -> Rc<RefCell<Vec<ThemeState>>> {
+    let states = Rc::new(RefCell::new(vec![]));
+    manager.subscribe(Box::new({
+        let states = states.clone();
+        move |state| states.borrow_mut().push(state.clone())
+    }));
+    states
+}
+
+#[wasm_bindgen_test]
+fn first_theme_is_applied() {
+    clear_storage();
+    let fixture = Fixture::new(r#"<div id="scope"></div>"#);
+    let manager = seigi_theme::create_theme_manager(options(&fixture));
+
+    assert_eq!(manager.themes(), ["paper", "ink"]);
+    assert_eq!(
+        manager.state(),
+        ThemeState {
+            theme: "paper".into(),
+            mode: Mode::Light,
+            is_dark: false,
+        }
+    );
+    assert_eq!(token(&fixture, "color-background"), "white");
+    assert_eq!(token(&fixture, "radius"), "2px");
+    assert_eq!(attribute(&fixture, "data-theme").as_deref(), Some("paper"));
+    assert_eq!(
+        attribute(&fixture, "data-color-scheme").as_deref(),
+        Some("light")
+    );
+    assert_eq!(
+        scope(&fixture)
+            .style()
+            .get_property_value("color-scheme")
+            .unwrap(),
+        "light"
+    );
+}
+
+#[wasm_bindgen_test]
+fn themes_are_switched_by_name() {
+    clear_storage();
+    let fixture = Fixture::new(r#"<div id="scope"></div>"#);
+    let manager = seigi_theme::create_theme_manager(options(&fixture));
+    let states = record(&manager);
+
+    assert!(!manager.set_theme("missing"));
+    assert!(manager.set_theme("ink"));
+    assert_eq!(token(&fixture, "color-background"), "gray");
+    // Tokens the theme doesn't have are removed
+    assert_eq!(token(&fixture, "radius"), "");
+    assert_eq!(attribute(&fixture, "data-theme").as_deref(), Some("ink"));
+
+    // Setting the current theme again doesn't notify
+    assert!(manager.set_theme("ink"));
+    assert_eq!(
+        states
+            .borrow()
+            .iter()
+            .map(|v| v.theme.as_str())
+            .collect::<Vec<_>>(),
+        ["ink"]
+    );
+    clear_storage();
+}
+
+#[wasm_bindgen_test]
+fn modes_resolve_color_scheme() {
+    clear_storage();
+    let fixture = Fixture::new(r#"<div id="scope"></div>"#);
+    let manager = seigi_theme::create_theme_manager(options(&fixture));
+    let states = record(&manager);
+
+    manager.set_mode(Mode::Dark);
+    assert!(manager.state().is_dark);
+    assert_eq!(token(&fixture, "color-background"), "black");
+    assert_eq!(token(&fixture, "radius"), "2px");
+    assert_eq!(
+        attribute(&fixture, "data-color-scheme").as_deref(),
+        Some("dark")
+    );
+
+    manager.toggle();
+    assert_eq!(manager.state().mode, Mode::Light);
+    assert_eq!(token(&fixture, "color-background"), "white");
+    manager.set_mode(Mode::Light);
+    assert_eq!(
+        states
+            .borrow()
+            .iter()
+            .map(|v| v.is_dark)
+            .collect::<Vec<_>>(),
+        [true, false]
+    );
+    clear_storage();
+}
+
+#[wasm_bindgen_test]
+fn unsubscribed_callbacks_are_not_called() {
+    clear_storage();
+    let fixture = Fixture::new(r#"<div id="scope"></div>"#);
+    let manager = seigi_theme::create_theme_manager(options(&fixture));
+    let called = Rc::new(RefCell::new(0));
+    let handle = manager.subscribe(Box::new({
+        let called = called.clone();
+        move |_| *called.borrow_mut() += 1
+    }));
+
+    manager.unsubscribe(handle);
+    manager.set_mode(Mode::Dark);
+    assert_eq!(*called.borrow(), 0);
+    clear_storage();
+}
+
+#[wasm_bindgen_test]
+fn theme_and_mode_are_restored_from_storage() {
+    clear_storage();
+    let fixture = Fixture::new(r#"<div id="scope"></div>"#);
+    {
+        let manager = seigi_theme::create_theme_manager(options(&fixture));
+        manager.set_theme("ink");
+        manager.set_mode(Mode::Dark);
+    }
+
+    let manager = seigi_theme::create_theme_manager(options(&fixture));
+    assert_eq!(
+        manager.state(),
+        ThemeState {
+            theme: "ink".into(),
+            mode: Mode::Dark,
+            is_dark: true,
+        }
+    );
+    assert_eq!(attribute(&fixture, "data-theme").as_deref(), Some("ink"));
+    clear_storage();
+}
+
+#[wasm_bindgen_test]
+fn names_saved_before_versioning_are_migrated() {
+    let storage = window().local_storage().unwrap().unwrap();
+    storage.set_item(STORAGE_KEY, "ink,dark").unwrap();
+    let fixture = Fixture::new(r#"<div id="scope"></div>"#);
+
+    let manager = seigi_theme::create_theme_manager(options(&fixture));
+    assert_eq!(manager.state().theme, "ink");
+    assert_eq!(manager.state().mode, Mode::Dark);
+    clear_storage();
+
+    // Unknown names are ignored
+    storage.set_item(STORAGE_KEY, "missing,sepia").unwrap();
+    let manager = seigi_theme::create_theme_manager(options(&fixture));
+    assert_eq!(manager.state().theme, "paper");
+    assert_eq!(manager.state().mode, Mode::Light);
+    clear_storage();
+}
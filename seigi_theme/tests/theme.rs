@@ -0,0 +1,86 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use seigi_theme::{Mode, Theme, ThemeManagerOptions};
+
+fn pairs(tokens: &[(&str, &str)]) -> Vec<(String, String)> {
+    tokens
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn tokens_are_replaced_in_place() {
+    let theme = Theme::new("brand")
+        .token("color-accent", "red")
+        .token("radius", "0")
+        .token("color-accent", "blue");
+    assert_eq!(
+        theme.tokens,
+        pairs(&[("color-accent", "blue"), ("radius", "0")])
+    );
+}
+
+#[test]
+fn dark_tokens_override_light_ones() {
+    let theme = Theme::new("brand")
+        .token("color-background", "white")
+        .token("radius", "0")
+        .dark_token("color-background", "black")
+        .dark_token("color-glow", "white");
+
+    assert_eq!(
+        theme.resolve(false),
+        pairs(&[("color-background", "white"), ("radius", "0")])
+    );
+    assert_eq!(
+        theme.resolve(true),
+        pairs(&[
+            ("color-background", "black"),
+            ("radius", "0"),
+            ("color-glow", "white")
+        ])
+    );
+}
+
+#[test]
+fn default_theme_has_every_token_in_both_schemes() {
+    let theme = Theme::default();
+    assert_eq!(theme.name, "default");
+    let names = |is_dark| {
+        theme
+            .resolve(is_dark)
+            .into_iter()
+            .map(|(v, _)| v)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(false), names(true));
+    assert!(
+        theme
+            .dark_tokens
+            .iter()
+            .all(|(v, _)| names(false).contains(v))
+    );
+}
+
+#[test]
+fn modes_round_trip_through_names() {
+    for mode in [Mode::Light, Mode::Dark, Mode::System] {
+        assert_eq!(Mode::parse(mode.as_str()), Some(mode));
+    }
+    assert_eq!(Mode::parse("Dark"), None);
+    assert_eq!(Mode::default(), Mode::System);
+}
+
+#[test]
+fn themes_are_added_by_name() {
+    let options = ThemeManagerOptions::builder().build();
+    assert_eq!(options.themes, [Theme::default()]);
+
+    let options = ThemeManagerOptions::builder()
+        .theme(Theme::new("a").token("radius", "0"))
+        .theme(Theme::new("b"))
+        .theme(Theme::new("a"))
+        .build();
+    assert_eq!(options.themes, [Theme::new("b"), Theme::new("a")]);
+}
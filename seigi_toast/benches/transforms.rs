@@ -0,0 +1,81 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+use gloo::render::request_animation_frame;
+use seigi_testkit::{perf, wait};
+use seigi_toast::{Toast, Toaster, ToasterOptions};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Toasts on screen while measuring, far more than applications show at once
+const STANDING: usize = 100;
+
+fn toaster() -> Toaster {
+    seigi_toast::toaster().unwrap_or_else(|| {
+        seigi_toast::initialize_unchecked(ToasterOptions::default().without_timeout())
+    })
+}
+
+/// Measures callbacks of the next frame scheduled by the routine, between callbacks requested
+/// right before and after it
+async fn measure_frame(routine: impl FnOnce()) -> Duration {
+    let start = Rc::new(Cell::new(Duration::ZERO));
+    let end = Rc::new(Cell::new(Duration::ZERO));
+    let _start = request_animation_frame({
+        let start = start.clone();
+        move |_| start.set(perf::now())
+    });
+    routine();
+    let _end = request_animation_frame({
+        let end = end.clone();
+        move |_| end.set(perf::now())
+    });
+    wait::frame().await;
+    end.get().saturating_sub(start.get())
+}
+
+/// Creates and dismisses a toast, which moves every standing toast in the next frame
+fn create_and_dismiss() {
+    let handle = seigi_toast::create_toast_unchecked(Toast::builder().title("bench").build());
+    seigi_toast::dismiss_toast(handle);
+}
+
+#[wasm_bindgen_bench]
+async fn transforms(c: &mut Criterion) {
+    toaster();
+    let standing = (0..STANDING)
+        .map(|index| {
+            seigi_toast::create_toast_unchecked(
+                Toast::builder().title(format!("standing {index}")).build(),
+            )
+        })
+        .collect::<Vec<_>>();
+    wait::frame().await;
+
+    c.bench_async_function("transforms", |b| {
+        Box::pin(b.iter_custom_future(|iters| async move {
+            let mut total = Duration::ZERO;
+            for _ in 0..iters {
+                total += measure_frame(create_and_dismiss).await;
+            }
+            total
+        }))
+    })
+    .await;
+
+    let mut samples = vec![];
+    for _ in 0..11 {
+        samples.push(measure_frame(create_and_dismiss).await);
+    }
+    perf::assert_budget(
+        "transforms",
+        Duration::from_millis(4),
+        perf::median_of(samples),
+    );
+
+    for handle in standing {
+        seigi_toast::dismiss_toast(handle);
+    }
+}
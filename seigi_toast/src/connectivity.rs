@@ -0,0 +1,243 @@
+//! Connectivity of the browser, followed through `online` and `offline` events
+//!
+//! [watch] tells subscribers whenever the browser goes offline or back online, so apps can pause
+//! background work like polling and syncing meanwhile. Unless disabled, a pinned toast stays in
+//! front of the others while offline, and is dismissed with [DismissReason::Resolved] once the
+//! browser is back online.
+
+use std::{
+    rc::{Rc, Weak},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use gloo::utils::window;
+use parking_lot::Mutex;
+use seigi_utils::{i18n, listener::Listener};
+
+use crate::{DismissReason, Error, Toast, ToastHandle, ToastTimeout, Toaster};
+
+/// Returns whether the browser is online, as told by `navigator.onLine`
+///
+/// Browsers only know whether they are connected to a network, so being online doesn't mean
+/// servers can be reached.
+pub fn is_online() -> bool {
+    window().navigator().on_line()
+}
+
+/// Options of [watch]
+#[derive(Debug, Clone)]
+pub struct ConnectivityOptions {
+    /// Whether a pinned toast is shown while offline
+    pub toast: bool,
+    /// The title of the toast, which is the `toast.offline` message of [i18n] by default
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
+
+impl ConnectivityOptions {
+    pub fn builder() -> ConnectivityOptionsBuilder {
+        ConnectivityOptionsBuilder::new()
+    }
+}
+
+impl Default for ConnectivityOptions {
+    fn default() -> Self {
+        Self {
+            toast: true,
+            title: None,
+            description: None,
+        }
+    }
+}
+
+/// A builder struct of [ConnectivityOptions]
+#[derive(Default)]
+pub struct ConnectivityOptionsBuilder {
+    options: ConnectivityOptions,
+}
+
+impl ConnectivityOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether a pinned toast is shown while offline
+    pub fn toast(mut self, toast: bool) -> Self {
+        self.options.toast = toast;
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.options.title = Some(title.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.options.description = Some(description.into());
+        self
+    }
+
+    pub fn build(self) -> ConnectivityOptions {
+        self.options
+    }
+}
+
+struct Subscriber {
+    callback: Box<dyn Fn(bool)>,
+    handle: u64,
+}
+
+/// Actual implementation of [Connectivity]
+struct Inner {
+    toaster: Toaster,
+    options: ConnectivityOptions,
+    is_online: bool,
+    /// The toast shown while offline
+    toast: Option<ToastHandle>,
+    subscribers: Vec<Rc<Subscriber>>,
+    _listener: Listener,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        if let Some(handle) = self.toast.take() {
+            self.toaster.dismiss_toast(handle, DismissReason::Resolved);
+        }
+    }
+}
+
+/// A watch over connectivity of the browser
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation. Watching stops once every handle is dropped, dismissing the toast.
+#[derive(Clone)]
+pub struct Connectivity(Rc<Mutex<Inner>>);
+
+impl Connectivity {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    /// Returns whether the browser was online as of the last `online` or `offline` event
+    pub fn is_online(&self) -> bool {
+        self.0.lock().is_online
+    }
+
+    /// Subscribes to changes of connectivity
+    ///
+    /// The callback is called with whether the browser is online, after the toast is shown or
+    /// dismissed.
+    ///
+    /// # Returns
+    /// A handle to unsubscribe with [Connectivity::unsubscribe]
+    pub fn subscribe(&self, callback: Box<dyn Fn(bool)>) -> u64 {
+        static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+        let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        self.0
+            .lock()
+            .subscribers
+            .push(Rc::new(Subscriber { callback, handle }));
+        handle
+    }
+
+    pub fn unsubscribe(&self, handle: u64) {
+        self.0.lock().subscribers.retain(|v| v.handle != handle);
+    }
+
+    fn update(&self, is_online: bool) {
+        let subscribers = {
+            let mut inner = self.0.lock();
+            if inner.is_online == is_online {
+                return;
+            }
+            inner.is_online = is_online;
+            inner.subscribers.clone()
+        };
+        seigi_utils::debug!("connectivity changed to online: {is_online}");
+        if is_online {
+            self.dismiss_toast();
+        } else {
+            self.show_toast();
+        }
+        for subscriber in subscribers {
+            (subscriber.callback)(is_online);
+        }
+    }
+
+    fn show_toast(&self) {
+        let (toaster, toast) = {
+            let inner = self.0.lock();
+            if !inner.options.toast || inner.toast.is_some() {
+                return;
+            }
+            let title = inner
+                .options
+                .title
+                .clone()
+                .unwrap_or_else(|| i18n::translate("toast.offline", &[]));
+            let toast = Toast {
+                title,
+                description: inner.options.description.clone(),
+                dismiss: None,
+                timeout: ToastTimeout::None,
+                group: None,
+                pinned: true,
+                origin: None,
+            };
+            (inner.toaster.clone(), toast)
+        };
+        // The toaster calls its subscribers, which may read the watch
+        let handle = toaster.add_toast(toast);
+        self.0.lock().toast = Some(handle);
+    }
+
+    fn dismiss_toast(&self) {
+        let (toaster, handle) = {
+            let mut inner = self.0.lock();
+            let Some(handle) = inner.toast.take() else {
+                return;
+            };
+            (inner.toaster.clone(), handle)
+        };
+        toaster.dismiss_toast(handle, DismissReason::Resolved);
+    }
+}
+
+/// Watches connectivity of the browser, showing toasts of the options in the toaster
+///
+/// The toast is shown at once if the browser is offline already.
+pub fn watch(toaster: Toaster, options: ConnectivityOptions) -> Connectivity {
+    let is_online = is_online();
+    let connectivity = Connectivity(Rc::new_cyclic(|weak: &Weak<Mutex<Inner>>| {
+        let mut listener = Listener::new();
+        for (event, is_online) in [("online", true), ("offline", false)] {
+            let weak = weak.clone();
+            listener.on(&window(), event, move |_| {
+                if let Some(connectivity) = Connectivity::from_weak(&weak) {
+                    connectivity.update(is_online);
+                }
+            });
+        }
+        Mutex::new(Inner {
+            toaster,
+            options,
+            is_online,
+            toast: None,
+            subscribers: vec![],
+            _listener: listener,
+        })
+    }));
+    if !is_online {
+        connectivity.show_toast();
+    }
+    connectivity
+}
+
+/// Watches connectivity of the browser, showing toasts in the global toaster
+///
+/// # Errors
+/// Returns an error if the global toaster isn't initialized
+pub fn watch_global(options: ConnectivityOptions) -> Result<Connectivity, Error> {
+    let toaster = crate::toaster().ok_or(Error::NotInitialized)?;
+    Ok(watch(toaster, options))
+}
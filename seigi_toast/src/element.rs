@@ -0,0 +1,234 @@
+//! `<seigi-toaster>`, a custom element rendering the global toaster, for pages written in plain
+//! HTML
+//!
+//! Once [define]d, the first `<seigi-toaster>` connected to the document initializes styles and
+//! the global toaster, rendering toasts inside itself. Other elements are left empty while the
+//! global toaster is initialized, whether by another element or by [crate::initialize].
+//!
+//! ```html
+//! <seigi-toaster position="top-right" visible="5" gap="10" theme="system"></seigi-toaster>
+//! <button onclick="this.dispatchEvent(new CustomEvent('seigi-toast', {
+//!   bubbles: true,
+//!   detail: { title: 'Saved', description: 'Changes are saved' },
+//! }))">Save</button>
+//! ```
+//!
+//! # Attributes
+//! **position** is where toasts are placed, named like [Position::as_str]
+//!
+//! **visible** is the max count of visible toasts, like [RendererOptions::visible]
+//!
+//! **gap** is the gap between toasts in pixels, like [RendererOptions::gap]
+//!
+//! **theme** is `light`, `dark` or `system` which follows the color scheme the user prefers,
+//! reflected as **data-theme** to pick tokens of the default styles
+//!
+//! Only **theme** is followed as it changes. Others are read when the toaster is initialized.
+//!
+//! # Events
+//! **seigi-toast** events bubbling up to the document create a toast from their detail, with
+//! `title`, and optional `description`, `group`, `pinned`, `origin` and `timeout` in
+//! milliseconds, which is `null` for no timeout. `origin` is an element or an object of `x` and
+//! `y` the toast is animated in from, like the button dispatching the event.
+//!
+//! **seigi-toast-create** is dispatched on the element with `handle`, `title` and
+//! `description` in its detail when a toast is created.
+//!
+//! **seigi-toast-dismiss** is dispatched on the element with `handle` and `reason`, which is
+//! `timeout`, `user`, `resolved` or `swipe`, in its detail when a toast is dismissed. Swiped
+//! toasts come with `gesture` of `direction`, `distance`, `velocity` and `isFlick`, which is
+//! `null` for other reasons.
+//!
+//! Both events bubble, so they can be listened to on the document as well.
+
+use std::{cell::RefCell, sync::Arc, time::Duration};
+
+use gloo::utils::document;
+use js_sys::{Object, Reflect};
+use seigi_components::Component;
+use seigi_utils::{config::Position, listener::Listener};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CustomEvent, CustomEventInit, Event, HtmlElement};
+
+use crate::{
+    DismissReason, Error, RendererOptions, Toast, ToastEvent, ToastOrigin, ToastTimeout, Toaster,
+    ToasterOptions,
+};
+
+/// The tag [define] defines the element as
+pub const TAG: &str = "seigi-toaster";
+
+/// Defines `<seigi-toaster>`
+///
+/// # Errors
+/// Returns an error if the browser rejects the definition, like when the tag is already defined
+pub fn define() -> Result<(), seigi_components::Error> {
+    seigi_components::define::<ToasterElement>(TAG)
+}
+
+/// The component of `<seigi-toaster>`
+pub struct ToasterElement {
+    /// Listener of toasts requested by `seigi-toast` events, which is Some while the element
+    /// renders the global toaster
+    listener: RefCell<Option<Listener>>,
+}
+
+impl Component for ToasterElement {
+    fn construct() -> Self {
+        Self {
+            listener: RefCell::new(None),
+        }
+    }
+
+    fn observed_attributes() -> Vec<&'static str> {
+        vec!["theme"]
+    }
+
+    fn connected(self: &Arc<Self>, element: &HtmlElement) {
+        // The toaster outlives disconnection, rendering into the element again once it's back
+        if self.listener.borrow().is_some() {
+            return;
+        }
+        if crate::toaster().is_some() {
+            seigi_utils::warn!("<{TAG}> is left empty, as the global toaster is initialized");
+            return;
+        }
+        match initialize(element) {
+            Ok(listener) => *self.listener.borrow_mut() = Some(listener),
+            Err(error) => seigi_utils::warn!("failed to initialize <{TAG}>: {error}"),
+        }
+    }
+
+    fn attribute_changed(
+        self: &Arc<Self>,
+        element: &HtmlElement,
+        name: String,
+        _old: Option<String>,
+        new: Option<String>,
+    ) {
+        if name == "theme" {
+            reflect_theme(element, new.as_deref());
+        }
+    }
+}
+
+fn reflect_theme(element: &HtmlElement, theme: Option<&str>) {
+    match theme {
+        Some(theme) => {
+            let _ = element.set_attribute("data-theme", theme);
+        }
+        None => {
+            let _ = element.remove_attribute("data-theme");
+        }
+    }
+}
+
+/// Initializes styles and the global toaster, rendering into the element with its attributes
+fn initialize(element: &HtmlElement) -> Result<Listener, Error> {
+    let attribute = |name: &str| element.get_attribute(name);
+    let mut options = ToasterOptions::default();
+    if let Some(position) = attribute("position") {
+        match Position::parse(&position) {
+            Some(position) => options = options.with_position(position),
+            None => seigi_utils::warn!("<{TAG}> has an unknown position `{position}`"),
+        }
+    }
+    let mut renderer = RendererOptions::default();
+    if let Some(visible) = attribute("visible").and_then(|v| v.parse().ok()) {
+        renderer.visible = visible;
+    }
+    if let Some(gap) = attribute("gap").and_then(|v| v.parse().ok()) {
+        renderer.gap = gap;
+    }
+    reflect_theme(element, attribute("theme").as_deref());
+
+    crate::initialize_styles()?;
+    let placement = crate::placement(&options)?;
+    let toaster = crate::initialize_global_in(element.clone(), options, renderer, placement)?;
+    dispatch_events(&toaster, element.clone());
+
+    let mut listener = Listener::new();
+    listener.on(&document(), "seigi-toast", move |event| {
+        let Some(toast) = requested_toast(event) else {
+            seigi_utils::warn!("skipped a seigi-toast event without a title in its detail");
+            return;
+        };
+        toaster.add_toast(toast);
+    });
+    Ok(listener)
+}
+
+/// Dispatches events of toasts created and dismissed on the element
+fn dispatch_events(toaster: &Toaster, element: HtmlElement) {
+    let source = toaster.clone();
+    toaster.subscribe(Box::new(move |event: &ToastEvent| {
+        let detail = Object::new();
+        let kind = match event {
+            ToastEvent::Create { handle } => {
+                let Some(toast) = source.get(*handle) else {
+                    return;
+                };
+                let description = match &toast.description {
+                    Some(description) => JsValue::from_str(description),
+                    None => JsValue::NULL,
+                };
+                let _ = Reflect::set(&detail, &"title".into(), &toast.title.as_str().into());
+                let _ = Reflect::set(&detail, &"description".into(), &description);
+                let _ = Reflect::set(&detail, &"handle".into(), &handle.0.into());
+                "seigi-toast-create"
+            }
+            ToastEvent::Update { .. } => return,
+            ToastEvent::Dismiss { handle, reason } => {
+                let _ = Reflect::set(&detail, &"handle".into(), &handle.0.into());
+                let _ = Reflect::set(&detail, &"reason".into(), &reason.as_str().into());
+                let _ = Reflect::set(
+                    &detail,
+                    &"gesture".into(),
+                    &match reason {
+                        DismissReason::Swipe(gesture) => gesture.to_js(),
+                        _ => JsValue::NULL,
+                    },
+                );
+                "seigi-toast-dismiss"
+            }
+        };
+        let init = CustomEventInit::new();
+        init.set_bubbles(true);
+        init.set_detail(&detail);
+        if let Ok(event) = CustomEvent::new_with_event_init_dict(kind, &init) {
+            let _ = element.dispatch_event(&event);
+        }
+    }));
+}
+
+/// Takes the toast from the detail of a `seigi-toast` event
+///
+/// # Returns
+/// None if the event isn't a custom event or its detail has no title
+fn requested_toast(event: &Event) -> Option<Toast> {
+    let detail = event.dyn_ref::<CustomEvent>()?.detail();
+    let property = |name: &str| {
+        Reflect::get(&detail, &JsValue::from_str(name))
+            .ok()
+            .filter(|v| !v.is_undefined())
+    };
+    let timeout = match property("timeout") {
+        Some(v) if v.is_null() => ToastTimeout::None,
+        Some(v) => match v.as_f64() {
+            Some(millis) => ToastTimeout::Duration(Duration::from_secs_f64(millis / 1000.0)),
+            None => ToastTimeout::Default,
+        },
+        None => ToastTimeout::Default,
+    };
+    Some(Toast {
+        title: property("title")?.as_string()?,
+        description: property("description").and_then(|v| v.as_string()),
+        dismiss: None,
+        timeout,
+        group: property("group").and_then(|v| v.as_string()),
+        pinned: property("pinned")
+            .and_then(|v| v.as_bool())
+            .unwrap_or_default(),
+        origin: property("origin").and_then(|v| ToastOrigin::from_js(&v)),
+    })
+}
@@ -0,0 +1,435 @@
+//! Records of every event of a toaster with the time it was published, and a panel inspecting
+//! them, for debugging timing of toasts in development builds
+//!
+//! ```ignore
+//! let inspector = Inspector::attach(&seigi_toast::toaster().unwrap());
+//! inspector.show();
+//! ```
+//!
+//! [Inspector::attach] records each [ToastEvent] of the toaster from then on, along with what
+//! the toasts were like. [Inspector::show] injects a panel listing toasts live and dismissed,
+//! with when they were created, when their timeouts were due and when they were dismissed
+//! actually, above the log of events. Its replay button creates the recorded toasts in the
+//! toaster again and dismisses them at the same pace, so that a sequence going wrong can be
+//! watched again.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+use gloo::{
+    timers::callback::Timeout,
+    utils::{body, document, window},
+};
+use seigi_utils::{listener::Listener, outside};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+use crate::{DismissReason, SubscriberPriority, Toast, ToastEvent, ToastHandle, Toaster};
+
+/// Records events before any other subscriber acts on them
+const PRIORITY: SubscriberPriority = SubscriberPriority(i32::MAX);
+
+/// An event of the toaster and the time it was published
+#[derive(Debug, Clone)]
+pub struct Record {
+    /// Milliseconds since the inspector was attached
+    pub time: f64,
+    pub event: ToastEvent,
+    /// Whether the event was caused by [Inspector::replay]
+    pub is_replayed: bool,
+}
+
+/// A toast created while the inspector was attached
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub handle: ToastHandle,
+    pub title: String,
+    pub description: Option<String>,
+    pub group: Option<String>,
+    pub pinned: bool,
+    /// Milliseconds since the inspector was attached, when the toast was created
+    pub created: f64,
+    /// When the timeout of the toast is due, or None without a timeout
+    pub due: Option<f64>,
+    /// When and why the toast was dismissed, or None while live
+    pub dismissed: Option<(f64, DismissReason)>,
+}
+
+impl Snapshot {
+    /// Returns how many milliseconds after its due the toast timed out, or None unless it was
+    /// dismissed by [DismissReason::Timeout]
+    pub fn lateness(&self) -> Option<f64> {
+        match (&self.dismissed, self.due) {
+            (Some((time, DismissReason::Timeout)), Some(due)) => Some(time - due),
+            _ => None,
+        }
+    }
+
+    /// Creates the toast again, which doesn't time out on its own
+    fn toast(&self) -> Toast {
+        let mut builder = Toast::builder().title(&self.title).timeout_none();
+        if let Some(description) = &self.description {
+            builder = builder.description(description);
+        }
+        if let Some(group) = &self.group {
+            builder = builder.group(group);
+        }
+        if self.pinned {
+            builder = builder.pinned();
+        }
+        builder.build()
+    }
+
+    fn describe(&self) -> String {
+        let mut text = format!(
+            "#{} \"{}\" created at {:.1} ms",
+            self.handle.0, self.title, self.created
+        );
+        if let Some(due) = self.due {
+            text += &format!(", due at {due:.1} ms");
+        }
+        match &self.dismissed {
+            Some((time, reason)) => {
+                text += &format!(", dismissed by {} at {time:.1} ms", reason.as_str());
+                if let Some(lateness) = self.lateness() {
+                    text += &format!(" ({lateness:.1} ms late)");
+                }
+            }
+            None => text += ", live",
+        }
+        text
+    }
+}
+
+/// The panel injected by [Inspector::show]
+struct Panel {
+    element: HtmlElement,
+    /// Keeps the panel usable beside focus traps
+    outside_handle: u64,
+    toasts: HtmlElement,
+    log: HtmlElement,
+    /// Listeners of the buttons, removed with the panel
+    _listener: Listener,
+}
+
+impl Panel {
+    fn remove(self) {
+        outside::disallow(self.outside_handle);
+        self.element.remove();
+    }
+}
+
+struct Inner {
+    toaster: Toaster,
+    /// `performance.now()` when attached
+    origin: f64,
+    subscription: Cell<u64>,
+    records: RefCell<Vec<Record>>,
+    /// Toasts in the order they were created
+    toasts: RefCell<Vec<Snapshot>>,
+    is_replaying: Cell<bool>,
+    /// Pending steps of the replay, cancelled once dropped
+    replay: RefCell<Vec<Timeout>>,
+    panel: RefCell<Option<Panel>>,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.toaster.unsubscribe(self.subscription.get());
+        if let Some(panel) = self.panel.get_mut().take() {
+            panel.remove();
+        }
+    }
+}
+
+/// Recorder of events of a toaster
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation, and recording stops and the panel is removed once every clone is
+/// dropped.
+#[derive(Clone)]
+pub struct Inspector(Rc<Inner>);
+
+impl Inspector {
+    /// Starts recording events of the toaster
+    pub fn attach(toaster: &Toaster) -> Self {
+        let inner = Rc::new(Inner {
+            toaster: toaster.clone(),
+            origin: now(),
+            subscription: Cell::new(0),
+            records: RefCell::new(vec![]),
+            toasts: RefCell::new(vec![]),
+            is_replaying: Cell::new(false),
+            replay: RefCell::new(vec![]),
+            panel: RefCell::new(None),
+        });
+        let weak = Rc::downgrade(&inner);
+        let subscription = toaster.subscribe_with_priority(
+            Box::new(move |event: &ToastEvent| {
+                if let Some(inspector) = Inspector::from_weak(&weak) {
+                    inspector.record(event);
+                }
+            }),
+            PRIORITY,
+        );
+        inner.subscription.set(subscription);
+        Self(inner)
+    }
+
+    fn from_weak(weak: &Weak<Inner>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    /// Returns events recorded so far, oldest first
+    pub fn records(&self) -> Vec<Record> {
+        self.0.records.borrow().clone()
+    }
+
+    /// Returns toasts created while recording, oldest first
+    pub fn toasts(&self) -> Vec<Snapshot> {
+        self.0.toasts.borrow().clone()
+    }
+
+    /// Forgets events and toasts recorded so far
+    pub fn clear(&self) {
+        self.0.records.borrow_mut().clear();
+        self.0.toasts.borrow_mut().clear();
+        self.render();
+    }
+
+    /// Creates the recorded toasts in the toaster again at the same pace as they were recorded,
+    /// and dismisses them at the times and for the reasons they were
+    ///
+    /// Replayed toasts don't time out on their own, but are dismissed by
+    /// [DismissReason::Timeout] when recorded so. Events of the replay are recorded as well,
+    /// marked by [Record::is_replayed], and replaying again cancels the replay in progress.
+    pub fn replay(&self) {
+        let records = self
+            .0
+            .records
+            .borrow()
+            .iter()
+            .filter(|v| !v.is_replayed)
+            .cloned()
+            .collect::<Vec<_>>();
+        let Some(start) = records.first().map(|v| v.time) else {
+            return;
+        };
+        // Handles of recorded toasts to those of replayed ones
+        let handles = Rc::new(RefCell::new(HashMap::<ToastHandle, ToastHandle>::new()));
+        let steps = records
+            .into_iter()
+            .filter_map(|record| {
+                let toast = match &record.event {
+                    ToastEvent::Create { handle } => Some(
+                        self.0
+                            .toasts
+                            .borrow()
+                            .iter()
+                            .find(|v| v.handle == *handle)?
+                            .toast(),
+                    ),
+                    ToastEvent::Dismiss { .. } => None,
+                    ToastEvent::Update { .. } => return None,
+                };
+                let weak = Rc::downgrade(&self.0);
+                let handles = handles.clone();
+                Some(Timeout::new((record.time - start) as u32, move || {
+                    let Some(inspector) = Inspector::from_weak(&weak) else {
+                        return;
+                    };
+                    let toaster = &inspector.0.toaster;
+                    inspector.0.is_replaying.set(true);
+                    match (record.event, toast) {
+                        (ToastEvent::Create { handle }, Some(toast)) => {
+                            let replayed = toaster.add_toast(toast);
+                            handles.borrow_mut().insert(handle, replayed);
+                        }
+                        (ToastEvent::Dismiss { handle, reason }, _) => {
+                            let replayed = handles.borrow().get(&handle).copied();
+                            if let Some(replayed) = replayed {
+                                toaster.dismiss_toast(replayed, reason);
+                            }
+                        }
+                        _ => {}
+                    }
+                    inspector.0.is_replaying.set(false);
+                }))
+            })
+            .collect();
+        self.0.replay.replace(steps);
+    }
+
+    /// Injects the panel into the page, updated as events are recorded
+    pub fn show(&self) {
+        if self.0.panel.borrow().is_some() {
+            return;
+        }
+        let Some(panel) = self.create_panel() else {
+            return;
+        };
+        self.0.panel.replace(Some(panel));
+        self.render();
+    }
+
+    /// Removes the panel from the page, while recording goes on
+    pub fn hide(&self) {
+        if let Some(panel) = self.0.panel.take() {
+            panel.remove();
+        }
+    }
+
+    fn record(&self, event: &ToastEvent) {
+        let time = now() - self.0.origin;
+        let toaster = &self.0.toaster;
+        match event {
+            ToastEvent::Create { handle } => {
+                if let Some(toast) = toaster.get(*handle) {
+                    let due = toaster
+                        .timeout_of(&toast)
+                        .map(|v| time + v.as_secs_f64() * 1000.0);
+                    self.0.toasts.borrow_mut().push(Snapshot {
+                        handle: *handle,
+                        title: toast.title.clone(),
+                        description: toast.description.clone(),
+                        group: toast.group.clone(),
+                        pinned: toast.pinned,
+                        created: time,
+                        due,
+                        dismissed: None,
+                    });
+                }
+            }
+            ToastEvent::Update { handle } => {
+                let mut toasts = self.0.toasts.borrow_mut();
+                let snapshot = toasts.iter_mut().find(|v| v.handle == *handle);
+                if let (Some(snapshot), Some(toast)) = (snapshot, toaster.get(*handle)) {
+                    snapshot.title = toast.title.clone();
+                    snapshot.description = toast.description.clone();
+                }
+            }
+            ToastEvent::Dismiss { handle, reason } => {
+                let mut toasts = self.0.toasts.borrow_mut();
+                if let Some(snapshot) = toasts.iter_mut().find(|v| v.handle == *handle) {
+                    snapshot.dismissed = Some((time, reason.clone()));
+                }
+            }
+        }
+        self.0.records.borrow_mut().push(Record {
+            time,
+            event: event.clone(),
+            is_replayed: self.0.is_replaying.get(),
+        });
+        self.render();
+    }
+
+    fn create_panel(&self) -> Option<Panel> {
+        let document = document();
+        let create = |tag: &str, attribute: &str| -> Option<HtmlElement> {
+            let element = document.create_element(tag).ok()?;
+            let _ = element.set_attribute(attribute, "");
+            Some(element.unchecked_into())
+        };
+
+        let element = create("aside", "data-seigi-toast-inspector")?;
+        let _ = element.set_attribute("aria-label", "Toast inspector");
+        let _ = element.set_attribute(
+            "style",
+            "position: fixed; left: 8px; bottom: 8px; z-index: 2147483647; width: 420px; \
+             max-height: 50vh; overflow: auto; padding: 8px; font: 12px/1.4 monospace; \
+             color: #eee; background: rgba(24, 24, 27, 0.92); border-radius: 6px;",
+        );
+        let replay = create("button", "data-seigi-toast-inspector-replay")?;
+        let _ = replay.set_attribute("type", "button");
+        replay.set_text_content(Some("Replay"));
+        let clear = create("button", "data-seigi-toast-inspector-clear")?;
+        let _ = clear.set_attribute("type", "button");
+        clear.set_text_content(Some("Clear"));
+        let toasts = create("ul", "data-seigi-toast-inspector-toasts")?;
+        let log = create("ol", "data-seigi-toast-inspector-log")?;
+        element
+            .append_with_node_4(&replay, &clear, &toasts, &log)
+            .ok()?;
+        body().append_child(&element).ok()?;
+
+        let mut listener = Listener::new();
+        let weak = Rc::downgrade(&self.0);
+        listener
+            .on(&replay, "click", {
+                let weak = weak.clone();
+                move |_| {
+                    if let Some(inspector) = Inspector::from_weak(&weak) {
+                        inspector.replay();
+                    }
+                }
+            })
+            .on(&clear, "click", move |_| {
+                if let Some(inspector) = Inspector::from_weak(&weak) {
+                    inspector.clear();
+                }
+            });
+
+        Some(Panel {
+            outside_handle: outside::allow(&element),
+            element,
+            toasts,
+            log,
+            _listener: listener,
+        })
+    }
+
+    /// Lists toasts, newest first, and events of the panel again
+    fn render(&self) {
+        let panel = self.0.panel.borrow();
+        let Some(panel) = panel.as_ref() else {
+            return;
+        };
+        let document = document();
+        let item = |text: &str, state: &str| {
+            let item = document.create_element("li").ok()?;
+            let _ = item.set_attribute("data-state", state);
+            item.set_text_content(Some(text));
+            Some(item)
+        };
+
+        panel.toasts.set_text_content(None);
+        for toast in self.0.toasts.borrow().iter().rev() {
+            let state = if toast.dismissed.is_some() {
+                "dismissed"
+            } else {
+                "live"
+            };
+            if let Some(item) = item(&toast.describe(), state) {
+                let _ = panel.toasts.append_child(&item);
+            }
+        }
+
+        panel.log.set_text_content(None);
+        for record in self.0.records.borrow().iter() {
+            let (kind, handle) = match &record.event {
+                ToastEvent::Create { handle } => ("create".to_string(), handle),
+                ToastEvent::Update { handle } => ("update".to_string(), handle),
+                ToastEvent::Dismiss { handle, reason } => {
+                    (format!("dismiss by {}", reason.as_str()), handle)
+                }
+            };
+            let state = if record.is_replayed {
+                "replayed"
+            } else {
+                "recorded"
+            };
+            let text = format!("{:.1} ms {kind} #{}", record.time, handle.0);
+            if let Some(item) = item(&text, state) {
+                let _ = panel.log.append_child(&item);
+            }
+        }
+    }
+}
+
+/// Returns milliseconds since the time origin of the page
+fn now() -> f64 {
+    window().performance().map_or(0.0, |v| v.now())
+}
@@ -1,76 +1,327 @@
 //! Ready-to-use global toasts with predefined styles
 
+pub mod connectivity;
+#[cfg(feature = "element")]
+pub mod element;
+#[cfg(feature = "inspector")]
+pub mod inspector;
+#[cfg(feature = "remote")]
+pub mod remote;
 mod renderer;
 mod toast;
 mod toaster;
-use std::cell::OnceCell;
+use std::{
+    cell::OnceCell,
+    fmt::{self, Display},
+};
 
-use gloo::utils::{body, document, head};
+use gloo::{
+    events::EventListener,
+    utils::{body, document, head, window},
+};
+pub use renderer::{RenderFn, RendererOptions, SwipeOptions};
+pub use seigi_utils::config::Position;
+use seigi_utils::{
+    config,
+    diagnostics::{self, Resource},
+    media::MediaQuery,
+    top_layer::{self, Popover},
+};
 pub use toast::*;
 pub use toaster::*;
-use wasm_bindgen::JsCast;
-use web_sys::{HtmlElement, HtmlStyleElement};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{
+    CssStyleSheet, HtmlElement, HtmlStyleElement, ShadowRoot, ShadowRootInit, ShadowRootMode,
+};
 
-use crate::renderer::{RendererOptions, create_renderer};
+use crate::renderer::{Placement, create_renderer};
+
+/// An error of the global toasts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The global toaster isn't initialized with [initialize] or [initialize_global], and
+    /// [GlobalConfig::toast_lazy_initialize](seigi_utils::config::GlobalConfig) is disabled
+    NotInitialized,
+    /// The global toaster is initialized already
+    AlreadyInitialized,
+    /// The browser failed to render the toaster, described by [seigi_utils::error::describe]
+    Dom(String),
+    /// A media query of [ToasterOptions::with_position_when] can't be matched
+    InvalidMediaQuery(String),
+    /// The browser refused to connect to a source of remote toasts, described by
+    /// [seigi_utils::error::describe]
+    Connect(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotInitialized => write!(f, "global toaster is not initialized"),
+            Self::AlreadyInitialized => write!(f, "global toaster is already initialized"),
+            Self::Dom(message) => write!(f, "failed to render global toaster: {message}"),
+            Self::InvalidMediaQuery(query) => write!(f, "invalid media query: {query}"),
+            Self::Connect(message) => write!(f, "failed to connect to remote toasts: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<JsValue> for Error {
+    fn from(value: JsValue) -> Self {
+        Self::Dom(seigi_utils::error::describe(&value))
+    }
+}
 
 thread_local! {
     static GLOBAL_TOASTS: OnceCell<Toaster> = const { OnceCell::new() };
 }
 
-fn global() -> Toaster {
-    GLOBAL_TOASTS.with(|toaster| toaster.get().unwrap().clone())
+/// Returns the global toaster, or None if it is not initialized
+pub fn toaster() -> Option<Toaster> {
+    GLOBAL_TOASTS.with(|toaster| toaster.get().cloned())
 }
 
 /// Initialize styles and global
-pub fn initialize(options: ToasterOptions) {
-    initialize_styles();
-    initialize_global(options);
+///
+/// # Errors
+/// Returns an error if the global toaster is initialized already, without adding styles again,
+/// or if the browser fails to render it
+pub fn initialize(options: ToasterOptions) -> Result<Toaster, Error> {
+    initialize_with_renderer(options, RendererOptions::default())
+}
+
+/// Initialize styles and global, rendering toasts with the options
+///
+/// # Errors
+/// Returns an error like [initialize]
+pub fn initialize_with_renderer(
+    options: ToasterOptions,
+    renderer: RendererOptions,
+) -> Result<Toaster, Error> {
+    if toaster().is_some() {
+        return Err(Error::AlreadyInitialized);
+    }
+    initialize_styles()?;
+    initialize_global_with_renderer(options, renderer)
 }
 
+/// Initialize styles and global, like [initialize]
+///
+/// # Panics
+/// This function panics if [initialize] returns an error
+pub fn initialize_unchecked(options: ToasterOptions) -> Toaster {
+    initialize(options).unwrap_or_else(|error| panic!("{error}"))
+}
+
+/// The default stylesheet
+const STYLES: &str = include_str!("styles.css");
+
 /// Add default stylesheet to document head
-pub fn initialize_styles() {
-    let styles = include_str!("styles.css");
+///
+/// # Errors
+/// Returns an error if the browser fails to add the stylesheet
+pub fn initialize_styles() -> Result<(), Error> {
     let element = document()
-        .create_element("style")
-        .unwrap()
+        .create_element("style")?
         .unchecked_into::<HtmlStyleElement>();
-    head().append_child(element.unchecked_ref()).unwrap();
+    head().append_child(element.unchecked_ref())?;
 
     element.set_type("text/css");
-    element
-        .append_child(document().create_text_node(styles).unchecked_ref())
-        .unwrap();
+    element.append_child(document().create_text_node(STYLES).unchecked_ref())?;
+    Ok(())
 }
 
 /// Initialize global state and renderer
-pub fn initialize_global(options: ToasterOptions) {
+///
+/// # Errors
+/// Returns an error if the global toaster is initialized already or if the browser fails to
+/// render it
+pub fn initialize_global(options: ToasterOptions) -> Result<Toaster, Error> {
+    initialize_global_with_renderer(options, RendererOptions::default())
+}
+
+/// Initialize global state and renderer, rendering toasts with the options
+///
+/// # Errors
+/// Returns an error like [initialize_global]
+pub fn initialize_global_with_renderer(
+    options: ToasterOptions,
+    renderer: RendererOptions,
+) -> Result<Toaster, Error> {
+    if toaster().is_some() {
+        return Err(Error::AlreadyInitialized);
+    }
+
+    let container = document()
+        .create_element("ol")?
+        .unchecked_into::<HtmlElement>();
+    let placement = placement(&options)?;
+
+    match &renderer.shadow_host {
+        Some(host) => shadow_root(host)?.append_child(container.unchecked_ref())?,
+        None => body().append_child(container.unchecked_ref())?,
+    };
+    initialize_global_in(container, options, renderer, placement)
+}
+
+/// Returns the shadow root of the host, attaching an open one if it has none, with the default
+/// stylesheet adopted
+///
+/// The document keeps its stylesheet from [initialize_styles] too, as custom properties are
+/// registered by `@property` of documents only.
+fn shadow_root(host: &HtmlElement) -> Result<ShadowRoot, Error> {
+    let root = match host.shadow_root() {
+        Some(root) => root,
+        None => host.attach_shadow(&ShadowRootInit::new(ShadowRootMode::Open))?,
+    };
+    let sheet = CssStyleSheet::new()?;
+    sheet.replace_sync(STYLES)?;
+    let sheets = root.adopted_style_sheets();
+    sheets.push(&sheet);
+    root.set_adopted_style_sheets(&sheets);
+    Ok(root)
+}
+
+/// Takes the placement of the container from the options
+fn placement(options: &ToasterOptions) -> Result<Placement, Error> {
+    let mut responsive = Vec::with_capacity(options.responsive.len());
+    for (query, position) in &options.responsive {
+        if window().match_media(query)?.is_none() {
+            return Err(Error::InvalidMediaQuery(query.clone()));
+        }
+        responsive.push((MediaQuery::new(query), *position));
+    }
+    Ok(Placement {
+        position: options.position,
+        responsive,
+    })
+}
+
+/// Initialize global state, rendering toasts in the container which is in the document already
+fn initialize_global_in(
+    container: HtmlElement,
+    options: ToasterOptions,
+    renderer: RendererOptions,
+    placement: Placement,
+) -> Result<Toaster, Error> {
+    let top_layer = options.top_layer;
+
     // Initialize global state
-    GLOBAL_TOASTS.with(|cell| {
-        let toaster = Toaster::new(options);
-        cell.get_or_init(|| toaster.clone());
+    let toaster = Toaster::new(options);
+    GLOBAL_TOASTS
+        .with(|cell| cell.set(toaster.clone()))
+        .map_err(|_| Error::AlreadyInitialized)?;
+    render_in(&toaster, container, renderer, placement, top_layer);
+    Ok(toaster)
+}
+
+/// Renders toasts of the toaster in the container, including those created already
+fn render_in(
+    toaster: &Toaster,
+    container: HtmlElement,
+    renderer: RendererOptions,
+    placement: Placement,
+    top_layer: bool,
+) {
+    if top_layer && top_layer::make_popover(&container, Popover::Manual) {
+        top_layer::show(&container);
+    }
+    create_renderer(toaster.clone(), container, renderer, placement).render_existing();
+}
+
+/// Returns the global toaster, initializing it with [ToasterOptions::default] if it isn't and
+/// [GlobalConfig::toast_lazy_initialize](seigi_utils::config::GlobalConfig) is enabled
+///
+/// Before the body exists, like in scripts of the head reporting errors of startup, the toaster
+/// keeps toasts until the document is parsed, rendering them then.
+///
+/// # Errors
+/// Returns an error if the global toaster isn't initialized and lazy initialization is disabled,
+/// or if the browser fails to render it
+pub(crate) fn global() -> Result<Toaster, Error> {
+    if let Some(toaster) = toaster() {
+        return Ok(toaster);
+    }
+    if !config::config().toast_lazy_initialize {
+        return Err(Error::NotInitialized);
+    }
+    seigi_utils::debug!("global toaster initialized lazily by the first toast");
+    if document().body().is_some() {
+        return initialize(ToasterOptions::default());
+    }
 
-        let container = document()
-            .create_element("ol")
-            .unwrap()
-            .unchecked_into::<HtmlElement>();
-        body().append_child(container.unchecked_ref()).unwrap();
-        create_renderer(toaster, container, RendererOptions::default());
-    });
+    let options = ToasterOptions::default();
+    let toaster = Toaster::new(options.clone());
+    GLOBAL_TOASTS
+        .with(|cell| cell.set(toaster.clone()))
+        .map_err(|_| Error::AlreadyInitialized)?;
+    let deferred = toaster.clone();
+    EventListener::once(&document(), "DOMContentLoaded", move |_| {
+        let render = || -> Result<(), Error> {
+            initialize_styles()?;
+            let container = document()
+                .create_element("ol")?
+                .unchecked_into::<HtmlElement>();
+            let placement = placement(&options)?;
+            body().append_child(container.unchecked_ref())?;
+            let top_layer = options.top_layer;
+            render_in(
+                &deferred,
+                container,
+                RendererOptions::default(),
+                placement,
+                top_layer,
+            );
+            Ok(())
+        };
+        if let Err(error) = render() {
+            seigi_utils::warn!("failed to render the global toaster: {error}");
+        }
+    })
+    .forget();
+    diagnostics::track(Resource::Listener).forget();
+    Ok(toaster)
 }
 
 /// Add toast to global state
 ///
+/// The first toast initializes the global toaster with [ToasterOptions::default] if it isn't
+/// initialized yet, unless
+/// [GlobalConfig::toast_lazy_initialize](seigi_utils::config::GlobalConfig) is disabled.
+/// Applications with options of their own [initialize] before any toast.
+///
 /// # Returns
 /// Handle to the toast
-pub fn create_toast(toast: impl Into<Toast>) -> ToastHandle {
-    let toast = toast.into();
-    global().add_toast(toast)
+///
+/// # Errors
+/// Returns an error if the global toaster isn't initialized and can't be initialized lazily
+pub fn create_toast(toast: impl Into<Toast>) -> Result<ToastHandle, Error> {
+    let toaster = global()?;
+    Ok(toaster.add_toast(toast.into()))
+}
+
+/// Add toast to global state, like [create_toast]
+///
+/// # Panics
+/// This function panics if [create_toast] returns an error
+pub fn create_toast_unchecked(toast: impl Into<Toast>) -> ToastHandle {
+    create_toast(toast).unwrap_or_else(|error| panic!("{error}"))
 }
 
 /// Dismiss a toast of handle with given reason from global toast state
 ///
 /// # Returns
-/// True if toast has been set to be dismissed, false if no toast of handle was found
+/// True if toast has been set to be dismissed, false if no toast of handle was found, including
+/// when the global toaster isn't initialized
 pub fn dismiss_toast(handle: ToastHandle) -> bool {
-    global().dismiss_toast(handle, DismissReason::User)
+    toaster().is_some_and(|v| v.dismiss_toast(handle, DismissReason::User))
+}
+
+/// Dismiss every toast of the group in global state
+///
+/// # Returns
+/// The count of dismissed toasts, which is 0 if the global toaster isn't initialized
+pub fn dismiss_group(group: &str) -> usize {
+    toaster().map_or(0, |v| v.dismiss_group(group, DismissReason::User))
 }
@@ -0,0 +1,370 @@
+//! Toasts pushed by servers over Server-Sent Events or WebSockets
+//!
+//! [connect] feeds toasts sent as JSON, like `{"title": "Deployed", "timeout": 8000}`, into a
+//! [Toaster], reconnecting with exponential backoff whenever the connection is lost. Messages
+//! which aren't toasts are skipped with a warning.
+
+use std::{
+    rc::{Rc, Weak},
+    time::Duration,
+};
+
+use gloo::timers::callback::Timeout;
+use parking_lot::Mutex;
+use seigi_utils::{
+    diagnostics::{self, Resource},
+    listener::Listener,
+};
+use serde::{Deserialize, Deserializer};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{EventSource, EventTarget, MessageEvent, WebSocket};
+
+use crate::{Error, Toast, Toaster};
+
+/// A toast sent by a server
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RemoteToast {
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Milliseconds until dismissed, where null never dismisses and absence takes the default
+    /// timeout of the toaster
+    #[serde(default, deserialize_with = "nullable")]
+    pub timeout: Option<Option<u64>>,
+    #[serde(default)]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// Tells null apart from absence, which is None by `#[serde(default)]`
+fn nullable<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Option<u64>>, D::Error> {
+    Option::<u64>::deserialize(deserializer).map(Some)
+}
+
+impl From<RemoteToast> for Toast {
+    fn from(value: RemoteToast) -> Self {
+        let mut builder = Toast::builder().title(value.title);
+        if let Some(description) = value.description {
+            builder = builder.description(description);
+        }
+        builder = match value.timeout {
+            None => builder.timeout_default(),
+            Some(None) => builder.timeout_none(),
+            Some(Some(millis)) => builder.timeout(Duration::from_millis(millis)),
+        };
+        if let Some(group) = value.group {
+            builder = builder.group(group);
+        }
+        if value.pinned {
+            builder = builder.pinned();
+        }
+        builder.build()
+    }
+}
+
+/// Where toasts are received from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// Server-Sent Events of the type from the url
+    EventSource { url: String, event: String },
+    /// Messages of a WebSocket to the url
+    WebSocket { url: String },
+}
+
+/// Options of [connect]
+#[derive(Debug, Clone)]
+pub struct RemoteOptions {
+    pub source: Source,
+    /// The delay before reconnecting the first time, doubled for each failed attempt after
+    pub min_backoff: Duration,
+    /// The longest delay before reconnecting
+    pub max_backoff: Duration,
+}
+
+impl RemoteOptions {
+    pub fn builder() -> RemoteOptionsBuilder {
+        RemoteOptionsBuilder::new()
+    }
+
+    fn backoff(&self, attempts: u32) -> Duration {
+        self.min_backoff
+            .saturating_mul(2u32.saturating_pow(attempts))
+            .min(self.max_backoff)
+    }
+}
+
+/// A builder struct of [RemoteOptions]
+pub struct RemoteOptionsBuilder {
+    source: Option<Source>,
+    min_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for RemoteOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            source: None,
+            min_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RemoteOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Receives toasts from Server-Sent Events of the url, of the `message` type unless set by
+    /// [RemoteOptionsBuilder::event]
+    pub fn event_source(mut self, url: impl Into<String>) -> Self {
+        self.source = Some(Source::EventSource {
+            url: url.into(),
+            event: String::from("message"),
+        });
+        self
+    }
+
+    /// Sets the type of Server-Sent Events toasts are sent as, which WebSockets ignore
+    pub fn event(mut self, event: impl Into<String>) -> Self {
+        if let Some(Source::EventSource { event: current, .. }) = &mut self.source {
+            *current = event.into();
+        }
+        self
+    }
+
+    /// Receives toasts from messages of a WebSocket to the url
+    pub fn web_socket(mut self, url: impl Into<String>) -> Self {
+        self.source = Some(Source::WebSocket { url: url.into() });
+        self
+    }
+
+    pub fn min_backoff(mut self, min_backoff: Duration) -> Self {
+        self.min_backoff = min_backoff;
+        self
+    }
+
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Builds into [RemoteOptions]
+    ///
+    /// # Panics
+    /// This method panics if no source is set
+    pub fn build(self) -> RemoteOptions {
+        RemoteOptions {
+            source: self
+                .source
+                .expect("source must be set to build RemoteOptions"),
+            min_backoff: self.min_backoff,
+            max_backoff: self.max_backoff,
+        }
+    }
+}
+
+enum Connection {
+    EventSource(EventSource),
+    WebSocket(WebSocket),
+}
+
+impl Connection {
+    fn target(&self) -> &EventTarget {
+        match self {
+            Self::EventSource(source) => source,
+            Self::WebSocket(socket) => socket,
+        }
+    }
+
+    fn is_open(&self) -> bool {
+        match self {
+            Self::EventSource(source) => source.ready_state() == EventSource::OPEN,
+            Self::WebSocket(socket) => socket.ready_state() == WebSocket::OPEN,
+        }
+    }
+
+    fn close(&self) {
+        match self {
+            Self::EventSource(source) => source.close(),
+            Self::WebSocket(socket) => {
+                let _ = socket.close();
+            }
+        }
+    }
+}
+
+/// Actual implementation of [Remote]
+struct Inner {
+    toaster: Toaster,
+    options: RemoteOptions,
+    connection: Option<Connection>,
+    /// Listeners of the connection, replaced with it
+    listener: Listener,
+    /// Failed attempts since the connection was last open
+    attempts: u32,
+    is_closed: bool,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        if let Some(connection) = &self.connection {
+            connection.close();
+        }
+    }
+}
+
+/// A connection receiving toasts from a server
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation. The connection is closed once every handle is dropped.
+#[derive(Clone)]
+pub struct Remote(Rc<Mutex<Inner>>);
+
+impl Remote {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    /// Returns whether the connection is open, rather than connecting or waiting to reconnect
+    pub fn is_connected(&self) -> bool {
+        self.0
+            .lock()
+            .connection
+            .as_ref()
+            .is_some_and(|v| v.is_open())
+    }
+
+    /// Closes the connection without reconnecting
+    pub fn close(&self) {
+        let mut inner = self.0.lock();
+        inner.is_closed = true;
+        inner.listener.abort();
+        if let Some(connection) = inner.connection.take() {
+            connection.close();
+        }
+    }
+
+    fn open(&self) -> Result<(), Error> {
+        let mut inner = self.0.lock();
+        inner.listener.abort();
+        if let Some(connection) = inner.connection.take() {
+            connection.close();
+        }
+
+        let error = |value: JsValue| Error::Connect(seigi_utils::error::describe(&value));
+        let (connection, message, lost) = match &inner.options.source {
+            Source::EventSource { url, event } => (
+                Connection::EventSource(EventSource::new(url).map_err(error)?),
+                event.clone(),
+                "error",
+            ),
+            Source::WebSocket { url } => (
+                Connection::WebSocket(WebSocket::new(url).map_err(error)?),
+                String::from("message"),
+                "close",
+            ),
+        };
+
+        let weak = Rc::downgrade(&self.0);
+        let target = connection.target().clone();
+        inner
+            .listener
+            .on(&target, &message, {
+                let weak = weak.clone();
+                move |event| {
+                    let Some(data) = event.unchecked_ref::<MessageEvent>().data().as_string()
+                    else {
+                        return;
+                    };
+                    if let Some(remote) = Remote::from_weak(&weak) {
+                        remote.receive(&data);
+                    }
+                }
+            })
+            .on(&target, "open", {
+                let weak = weak.clone();
+                move |_| {
+                    if let Some(remote) = Remote::from_weak(&weak) {
+                        remote.0.lock().attempts = 0;
+                    }
+                }
+            })
+            .on(&target, lost, move |_| {
+                if let Some(remote) = Remote::from_weak(&weak) {
+                    remote.lose();
+                }
+            });
+        inner.connection = Some(connection);
+        Ok(())
+    }
+
+    /// Reconnects after the backoff of failed attempts
+    fn lose(&self) {
+        let mut inner = self.0.lock();
+        if inner.is_closed {
+            return;
+        }
+        // EventSource reconnects by itself, unless the server refused it
+        if let Some(Connection::EventSource(source)) = &inner.connection
+            && source.ready_state() != EventSource::CLOSED
+        {
+            return;
+        }
+
+        let delay = inner.options.backoff(inner.attempts);
+        inner.attempts = inner.attempts.saturating_add(1);
+        seigi_utils::debug!("remote toasts lost, reconnecting in {delay:?}");
+        let weak = Rc::downgrade(&self.0);
+        Timeout::new(delay.as_millis() as u32, move || {
+            let Some(remote) = Remote::from_weak(&weak) else {
+                return;
+            };
+            if remote.0.lock().is_closed {
+                return;
+            }
+            if remote.open().is_err() {
+                remote.lose();
+            }
+        })
+        .forget();
+        diagnostics::track(Resource::Timeout).forget();
+    }
+
+    fn receive(&self, data: &str) {
+        match serde_json::from_str::<RemoteToast>(data) {
+            Ok(toast) => {
+                let toaster = self.0.lock().toaster.clone();
+                toaster.add_toast(toast.into());
+            }
+            Err(error) => seigi_utils::warn!("skipped a remote toast: {error}"),
+        }
+    }
+}
+
+/// Connects to the source of the options, adding toasts received to the toaster
+///
+/// # Errors
+/// Returns an error if the browser refuses the source, such as for a malformed url
+pub fn connect(toaster: Toaster, options: RemoteOptions) -> Result<Remote, Error> {
+    let remote = Remote(Rc::new(Mutex::new(Inner {
+        toaster,
+        options,
+        connection: None,
+        listener: Listener::new(),
+        attempts: 0,
+        is_closed: false,
+    })));
+    remote.open()?;
+    Ok(remote)
+}
+
+/// Connects to the source of the options, adding toasts received to the global toaster
+///
+/// # Errors
+/// Returns an error if the global toaster isn't initialized, or like [connect]
+pub fn connect_global(options: RemoteOptions) -> Result<Remote, Error> {
+    let toaster = crate::toaster().ok_or(Error::NotInitialized)?;
+    connect(toaster, options)
+}
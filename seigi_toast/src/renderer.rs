@@ -1,31 +1,162 @@
-use std::{collections::VecDeque, ops::Deref, rc::Rc};
+use std::{
+    cell::Cell,
+    collections::{HashMap, VecDeque, hash_map::Entry},
+    rc::{Rc, Weak},
+};
 
-use gloo::{console::info, utils::document};
-use parking_lot::{Mutex, MutexGuard};
+use gloo::utils::document;
+use parking_lot::Mutex;
+use seigi_utils::{
+    announcer,
+    config::Position,
+    i18n,
+    listener::Listener,
+    media::{self, MediaQuery},
+    outside,
+    presence::{Presence, PresenceOptions, create_presence},
+    schedule::{self, Frame},
+    top_layer,
+};
 use wasm_bindgen::JsCast;
-use web_sys::{HtmlElement, ResizeObserver};
+use web_sys::{Element, Event, HtmlElement, PointerEvent, ShadowRoot};
 
-use crate::{DismissReason, ToastEvent, ToastHandle, Toaster};
+use crate::{
+    DismissReason, SubscriberPriority, SwipeDirection, SwipeGesture, Toast, ToastEvent,
+    ToastHandle, Toaster,
+};
+
+/// Distance in pixels a pointer moves before dragging the toast, which keeps clicks of its
+/// buttons from turning into swipes
+const SWIPE_SLOP: f64 = 10.0;
 
 /// Instance of rendered toast
 #[derive(Clone)]
 struct Rendered {
     handle: ToastHandle,
     element: HtmlElement,
+    /// Removes the element once its dismissing transition ends
+    presence: Presence,
+    group: Option<String>,
+    /// Listeners of pointers swiping the toast, removed with the toast
+    _swipe: Option<Rc<Listener>>,
+}
+
+/// Where a pointer started swiping a toast
+#[derive(Clone, Copy)]
+struct SwipeStart {
+    pointer: i32,
+    x: f64,
+    y: f64,
+    /// Time stamp of the event in milliseconds
+    time: f64,
+}
+
+/// Summary of toasts sharing a group, rendered while there are several of them
+struct Group {
+    element: HtmlElement,
+    count: HtmlElement,
+    toggle: HtmlElement,
+    /// Count of rendered toasts in the group
+    len: usize,
+    is_expanded: bool,
+    /// Listeners of the buttons, removed with the summary
+    _listener: Listener,
+}
+
+impl Group {
+    fn reflect(&self) {
+        self.count.set_text_content(Some(&self.len.to_string()));
+        let label = if self.is_expanded {
+            i18n::translate("toast.group.collapse", &[])
+        } else {
+            i18n::translate("toast.group.expand", &[("count", &self.len)])
+        };
+        self.toggle.set_text_content(Some(&label));
+        let _ = self
+            .toggle
+            .set_attribute("aria-expanded", &self.is_expanded.to_string());
+        if self.is_expanded {
+            let _ = self.element.set_attribute("data-expanded", "");
+        } else {
+            let _ = self.element.remove_attribute("data-expanded");
+        }
+    }
 }
 
 struct Impl {
     toaster: Toaster,
     container: HtmlElement,
+    /// Toasts flowing in the stack, newest first
     rendered: Mutex<VecDeque<Rendered>>,
+    /// Pinned toasts in front of the stack, oldest first
+    pinned: Mutex<Vec<Rendered>>,
+    /// Summaries of groups with several rendered toasts
+    groups: Mutex<HashMap<String, Group>>,
     options: RendererOptions,
+    placement: Placement,
+    /// Updates transforms once per frame, however many toasts come and go in it
+    transforms: Frame,
 }
 
+/// A factory of the markup inside each toast
+pub type RenderFn = Box<dyn Fn(&Toast) -> HtmlElement>;
+
+/// Options of the renderer of global toasts
 pub struct RendererOptions {
     /// Gap between rendered toasts
     pub gap: i32,
-    /// Max visible toasts at the time
+    /// Max visible toasts at the time, besides pinned toasts
     pub visible: usize,
+    /// Renders the markup inside each toast instead of its title, again whenever the toast is
+    /// updated
+    ///
+    /// The renderer keeps stacking, offsets and dismissal of the toast elements wrapping the
+    /// markup. The factory is called while the toast is borrowed from the toaster, so it must not
+    /// call the toaster.
+    pub render: Option<RenderFn>,
+    /// Dismisses toasts swiped by pointers with [DismissReason::Swipe], or None to keep toasts
+    /// in place
+    pub swipe: Option<SwipeOptions>,
+    /// Renders the container inside the shadow root of the host instead of the body, attaching
+    /// an open one if it has none, so styles of the page can't reach toasts
+    ///
+    /// Default styles are adopted by the shadow root as a constructed stylesheet. Elements of the
+    /// renderer are exposed as parts for styling from outside, like `::part(toast)`: `toaster`,
+    /// `toast`, and `group`, `group-label`, `group-count`, `group-toggle` and `group-dismiss` of
+    /// group summaries, which are toasts too. Only used by [crate::initialize_with_renderer] and
+    /// [crate::initialize_global_with_renderer].
+    pub shadow_host: Option<HtmlElement>,
+}
+
+/// How far or fast toasts are swiped to be dismissed, in [RendererOptions]
+///
+/// While dragged, toasts have `data-swiping` and the distance dragged in
+/// `--seigi-toast-swipe-x` and `--seigi-toast-swipe-y`, which styles translate them by.
+#[derive(Debug, Clone, Copy)]
+pub struct SwipeOptions {
+    /// Distance in pixels a toast is dismissed at when released after dragged past it
+    pub distance: f64,
+    /// Speed in pixels per millisecond a toast is dismissed at when released short of the
+    /// distance
+    pub velocity: f64,
+}
+
+impl Default for SwipeOptions {
+    fn default() -> Self {
+        Self {
+            distance: 45.0,
+            velocity: 0.11,
+        }
+    }
+}
+
+/// Where the container is placed, taken from [crate::ToasterOptions]
+pub(crate) struct Placement {
+    /// Position of the container in the viewport
+    pub position: Position,
+    /// Positions replacing the position above while their queries match, where later ones take
+    /// precedence
+    pub responsive: Vec<(MediaQuery, Position)>,
 }
 
 impl Default for RendererOptions {
@@ -33,6 +164,9 @@ impl Default for RendererOptions {
         Self {
             gap: 14,
             visible: 3,
+            render: None,
+            swipe: Some(SwipeOptions::default()),
+            shadow_host: None,
         }
     }
 }
@@ -41,11 +175,52 @@ impl Default for RendererOptions {
 pub struct Renderer(Rc<Impl>);
 
 impl Renderer {
+    fn from_weak(weak: &Weak<Impl>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
     fn initialize(&self) {
-        self.0
-            .container
-            .set_attribute("data-seigi-toaster", "")
-            .unwrap();
+        let container = &self.0.container;
+        let _ = container.set_attribute("data-seigi-toaster", "");
+        let _ = container.set_attribute("part", "toaster");
+        let _ = container.set_attribute("role", "region");
+        if !container.has_attribute("aria-label") {
+            let _ = container.set_attribute("aria-label", &i18n::translate("toast.region", &[]));
+        }
+        // Actions of toasts stay reachable while focus traps of dialogs are active
+        outside::allow(container);
+        // Events from inside shadow roots are retargeted to their hosts
+        if let Ok(root) = container.get_root_node().dyn_into::<ShadowRoot>() {
+            outside::allow(&root.host());
+        }
+
+        // Toasts move between offsets as others come and go, which users preferring reduced
+        // motion opt out of, unless the configured policy decides otherwise
+        let reduced_motion = media::reduced_motion();
+        let reflect_motion = {
+            let container = self.0.container.clone();
+            move |is_reduced: bool| {
+                if is_reduced {
+                    let _ = container.set_attribute("data-reduced-motion", "");
+                } else {
+                    let _ = container.remove_attribute("data-reduced-motion");
+                }
+            }
+        };
+        reflect_motion(media::prefers_reduced_motion());
+        reduced_motion.subscribe(Box::new(move |_| {
+            reflect_motion(media::prefers_reduced_motion())
+        }));
+
+        self.place();
+        for (query, _) in &self.0.placement.responsive {
+            let weak = Rc::downgrade(&self.0);
+            query.subscribe(Box::new(move |_| {
+                if let Some(renderer) = Renderer::from_weak(&weak) {
+                    renderer.place();
+                }
+            }));
+        }
 
         let callback = Box::new({
             let this = self.clone();
@@ -54,60 +229,362 @@ impl Renderer {
                 ToastEvent::Create { handle } => {
                     this.on_toast_create(*handle);
                 }
-                ToastEvent::Update { handle: _ } => todo!(),
+                ToastEvent::Update { handle } => this.on_toast_update(*handle),
                 ToastEvent::Dismiss { handle, reason } => {
                     this.on_toast_dismiss(*handle, reason.clone())
                 }
             }
         });
-        self.0.toaster.subscribe(callback);
+        self.0
+            .toaster
+            .subscribe_with_priority(callback, SubscriberPriority::RENDERER);
+    }
+
+    /// Renders toasts created before the renderer, oldest first like they were created
+    pub(crate) fn render_existing(&self) {
+        for handle in self.0.toaster.iter_handles() {
+            self.on_toast_create(handle);
+        }
     }
 
     fn on_toast_create(&self, handle: ToastHandle) {
-        let toast = self.0.toaster.get(handle).unwrap();
+        // Toasts may be dismissed by subscribers of higher priorities before reaching the
+        // renderer
+        let Some(toast) = self.0.toaster.get(handle) else {
+            return;
+        };
+        if toast.dismiss.is_some() {
+            return;
+        }
 
-        let element = document().create_element("li").unwrap();
-        element.set_attribute("data-seigi-toast", "").unwrap();
-        element
-            .append_child(
-                document()
-                    .create_text_node(toast.title.as_str())
-                    .unchecked_ref(),
-            )
-            .unwrap();
-        self.0
+        let Ok(element) = document().create_element("li") else {
+            return;
+        };
+        let _ = element.set_attribute("data-seigi-toast", "");
+        let _ = element.set_attribute("part", "toast");
+        self.fill(&element, &toast);
+        if self
+            .0
             .container
             .append_child(element.unchecked_ref())
-            .unwrap();
+            .is_err()
+        {
+            return;
+        }
+        // Modal dialogs opened since the last toast are above the container otherwise
+        top_layer::raise(&self.0.container);
+        if let Some(origin) = &toast.origin {
+            enter_from(element.unchecked_ref(), origin.point());
+        }
+
+        // Toasts are announced through the shared live region, as the container isn't one
+        let announcement = match &toast.description {
+            Some(description) => format!("{}\n{description}", toast.title),
+            None => toast.title.clone(),
+        };
+        let group = toast.group.clone().filter(|_| !toast.pinned);
+        let is_pinned = toast.pinned;
+        drop(toast);
+        announcer::polite(announcement);
 
-        self.0.rendered.lock().push_front(Rendered {
+        let element = element.unchecked_into::<HtmlElement>();
+        let presence = create_presence(
+            PresenceOptions::builder()
+                .element(element.clone())
+                .open(true)
+                .build(),
+        );
+        let swipe = self
+            .0
+            .options
+            .swipe
+            .map(|options| Rc::new(self.listen_swipe(&element, handle, options)));
+        let rendered = Rendered {
             handle,
-            element: element.unchecked_into(),
-        });
+            element,
+            presence,
+            group: group.clone(),
+            _swipe: swipe,
+        };
+        if is_pinned {
+            let _ = rendered.element.set_attribute("data-pinned", "");
+            self.0.pinned.lock().push(rendered);
+        } else {
+            self.0.rendered.lock().push_front(rendered);
+        }
+        if let Some(group) = group {
+            self.update_group(&group);
+        }
+
+        self.0.transforms.schedule();
+    }
+
+    /// Drags the toast along with pointers, dismissing it once swiped far or fast enough
+    fn listen_swipe(
+        &self,
+        element: &HtmlElement,
+        handle: ToastHandle,
+        options: SwipeOptions,
+    ) -> Listener {
+        let start = Rc::new(Cell::new(None::<SwipeStart>));
+        let weak = Rc::downgrade(&self.0);
+        let mut listener = Listener::new();
+        listener
+            .on(element, "pointerdown", {
+                let start = start.clone();
+                move |event| {
+                    let Some(event) = event.dyn_ref::<PointerEvent>() else {
+                        return;
+                    };
+                    if !event.is_primary() || event.button() != 0 {
+                        return;
+                    }
+                    start.set(Some(SwipeStart {
+                        pointer: event.pointer_id(),
+                        x: event.client_x() as f64,
+                        y: event.client_y() as f64,
+                        time: event.time_stamp(),
+                    }));
+                }
+            })
+            .on(element, "pointermove", {
+                let start = start.clone();
+                let element = element.clone();
+                move |event| {
+                    let Some((start, event)) = swiping(&start, event) else {
+                        return;
+                    };
+                    let x = event.client_x() as f64 - start.x;
+                    let y = event.client_y() as f64 - start.y;
+                    if !element.has_attribute("data-swiping") {
+                        if x.hypot(y) < SWIPE_SLOP {
+                            return;
+                        }
+                        // Keeps the toast dragged while the pointer leaves it
+                        let _ = element.set_pointer_capture(start.pointer);
+                        let _ = element.set_attribute("data-swiping", "");
+                    }
+                    let style = element.style();
+                    let _ = style.set_property("--seigi-toast-swipe-x", &format!("{x}px"));
+                    let _ = style.set_property("--seigi-toast-swipe-y", &format!("{y}px"));
+                }
+            })
+            .on(element, "pointerup", {
+                let start = start.clone();
+                let element = element.clone();
+                move |event| {
+                    let Some((swipe, event)) = swiping(&start, event) else {
+                        return;
+                    };
+                    start.set(None);
+                    let gesture = element
+                        .has_attribute("data-swiping")
+                        .then(|| gesture(swipe, event, options))
+                        .flatten();
+                    let _ = element.remove_attribute("data-swiping");
+                    let Some(gesture) = gesture else {
+                        reset_swipe(&element);
+                        return;
+                    };
+                    // Swiped toasts leave from where they were dragged to
+                    let _ =
+                        element.set_attribute("data-swipe-direction", gesture.direction.as_str());
+                    if let Some(renderer) = Renderer::from_weak(&weak) {
+                        renderer
+                            .0
+                            .toaster
+                            .dismiss_toast(handle, DismissReason::Swipe(gesture));
+                    }
+                }
+            })
+            .on(element, "pointercancel", {
+                let element = element.clone();
+                move |event| {
+                    if swiping(&start, event).is_some() {
+                        start.set(None);
+                        let _ = element.remove_attribute("data-swiping");
+                        reset_swipe(&element);
+                    }
+                }
+            });
+        listener
+    }
+
+    /// Fills the element of the toast with its markup, or its title without a factory
+    fn fill(&self, element: &Element, toast: &Toast) {
+        match &self.0.options.render {
+            Some(render) => element.replace_children_with_node_1(&render(toast)),
+            None => element.set_text_content(Some(toast.title.as_str())),
+        }
+    }
+
+    fn on_toast_update(&self, handle: ToastHandle) {
+        let Some(toast) = self.0.toaster.get(handle) else {
+            return;
+        };
+        let element = {
+            let rendered = self.0.rendered.lock();
+            let pinned = self.0.pinned.lock();
+            let mut sections = rendered.iter().chain(pinned.iter());
+            match sections.find(|v| v.handle == handle) {
+                Some(rendered) => rendered.element.clone(),
+                None => return,
+            }
+        };
+        self.fill(&element, &toast);
+        drop(toast);
 
-        self.update_transforms();
+        // Titles may wrap into more lines, moving the toasts below
+        self.0.transforms.schedule();
     }
 
     fn on_toast_dismiss(&self, handle: ToastHandle, _reason: DismissReason) {
-        let Some(position) = self
+        let rendered = {
+            let mut flowing = self.0.rendered.lock();
+            let mut pinned = self.0.pinned.lock();
+            match flowing.iter().position(|v| v.handle == handle) {
+                Some(position) => flowing.remove(position),
+                None => pinned
+                    .iter()
+                    .position(|v| v.handle == handle)
+                    .map(|position| pinned.remove(position)),
+            }
+        };
+        let Some(rendered) = rendered else {
+            return;
+        };
+
+        let element = rendered.element;
+        let _ = element.set_attribute("data-dismissed", "");
+        let _ = element.remove_attribute("data-visible");
+        rendered.presence.hide();
+        if let Some(group) = rendered.group {
+            self.update_group(&group);
+        }
+
+        self.0.transforms.schedule();
+    }
+
+    /// Renders the summary of the group while it has several toasts, and removes it otherwise
+    fn update_group(&self, name: &str) {
+        let len = self
             .0
             .rendered
             .lock()
             .iter()
-            .position(|v| v.handle == handle)
-        else {
+            .filter(|v| v.group.as_deref() == Some(name))
+            .count();
+        let mut groups = self.0.groups.lock();
+        if len < 2 {
+            if let Some(group) = groups.remove(name) {
+                group.element.remove();
+            }
             return;
+        }
+
+        let group = match groups.entry(name.to_string()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => match self.create_group(name) {
+                Some(group) => entry.insert(group),
+                None => return,
+            },
         };
+        group.len = len;
+        group.reflect();
+    }
 
-        let Some(rendered) = self.0.rendered.lock().remove(position) else {
-            return;
+    fn create_group(&self, name: &str) -> Option<Group> {
+        let document = document();
+        let create = |tag: &str, attribute: &str, part: &str| -> Option<HtmlElement> {
+            let element = document.create_element(tag).ok()?;
+            let _ = element.set_attribute(attribute, "");
+            let _ = element.set_attribute("part", part);
+            Some(element.unchecked_into())
         };
 
-        let element = rendered.element;
-        let _ = element.set_attribute("data-dismissed", "");
-        let _ = element.remove_attribute("data-visible");
+        let element = create("li", "data-seigi-toast", "toast group")?;
+        let _ = element.set_attribute("data-seigi-toast-group", name);
+        let label = create("span", "data-seigi-toast-group-label", "group-label")?;
+        label.set_text_content(Some(name));
+        let count = create("span", "data-seigi-toast-count", "group-count")?;
+        let toggle = create("button", "data-seigi-toast-group-toggle", "group-toggle")?;
+        let _ = toggle.set_attribute("type", "button");
+        let dismiss = create("button", "data-seigi-toast-group-dismiss", "group-dismiss")?;
+        let _ = dismiss.set_attribute("type", "button");
+        dismiss.set_text_content(Some(&i18n::translate("toast.group.dismiss", &[])));
+        element
+            .append_with_node_4(&label, &count, &toggle, &dismiss)
+            .ok()?;
+        self.0.container.append_child(&element).ok()?;
+
+        let mut listener = Listener::new();
+        let weak = Rc::downgrade(&self.0);
+        listener
+            .on(&toggle, "click", {
+                let weak = weak.clone();
+                let name = name.to_string();
+                move |_| {
+                    if let Some(renderer) = Renderer::from_weak(&weak) {
+                        renderer.toggle_group(&name);
+                    }
+                }
+            })
+            .on(&dismiss, "click", {
+                let name = name.to_string();
+                move |_| {
+                    if let Some(renderer) = Renderer::from_weak(&weak) {
+                        renderer.0.toaster.dismiss_group(&name, DismissReason::User);
+                    }
+                }
+            });
 
-        self.update_transforms();
+        Some(Group {
+            element,
+            count,
+            toggle,
+            len: 0,
+            is_expanded: false,
+            _listener: listener,
+        })
+    }
+
+    /// Expands the summary of the group into its toasts, or collapses them back
+    fn toggle_group(&self, name: &str) {
+        {
+            let mut groups = self.0.groups.lock();
+            let Some(group) = groups.get_mut(name) else {
+                return;
+            };
+            group.is_expanded = !group.is_expanded;
+            group.reflect();
+        }
+        self.0.transforms.schedule();
+    }
+
+    /// Moves the container to the position of the last matching query, or the base one
+    ///
+    /// Toasts stay in the container as they are, only jumping to their new offsets instead of
+    /// transitioning across the viewport
+    fn place(&self) {
+        let placement = &self.0.placement;
+        let position = placement
+            .responsive
+            .iter()
+            .rev()
+            .find(|(query, _)| query.matches())
+            .map_or(placement.position, |(_, position)| *position);
+
+        let container = &self.0.container;
+        let current = container.get_attribute("data-position");
+        if current.as_deref() == Some(position.as_str()) {
+            return;
+        }
+
+        let _ = container.set_attribute("data-repositioning", "");
+        let _ = container.set_attribute("data-position", position.as_str());
+        // Applies the position while transitions are disabled
+        let _ = container.offset_height();
+        let _ = container.remove_attribute("data-repositioning");
     }
 
     fn update_transforms(&self) {
@@ -116,24 +593,87 @@ impl Renderer {
             let guard = self.0.rendered.lock();
             guard.clone()
         };
+        let pinned = self
+            .0
+            .pinned
+            .lock()
+            .iter()
+            .map(|v| v.element.clone())
+            .collect::<Vec<_>>();
+        let summaries = self
+            .0
+            .groups
+            .lock()
+            .iter()
+            .map(|(name, group)| (name.clone(), (group.element.clone(), group.is_expanded)))
+            .collect::<HashMap<_, _>>();
+        let visible = self.0.options.visible;
+
+        // Summaries are stacked in place of the newest toasts of their groups, which follow them
+        // only while expanded
+        let mut items = Vec::with_capacity(indices.len());
+        let mut grouped = vec![];
+        let mut summarized = vec![];
+        for rendered in &indices {
+            let summary = rendered
+                .group
+                .as_ref()
+                .and_then(|name| Some((name, summaries.get(name)?)));
+            let Some((name, (summary, is_expanded))) = summary else {
+                items.push(&rendered.element);
+                continue;
+            };
+            if !summarized.contains(&name) {
+                summarized.push(name);
+                items.push(summary);
+            }
+            if *is_expanded {
+                items.push(&rendered.element);
+            } else {
+                grouped.push(&rendered.element);
+            }
+        }
+
+        // Heights are read before any attribute is written, as reading after a write forces the
+        // browser to lay out the page again for every toast
+        let pinned_heights = pinned.iter().map(|v| v.offset_height()).collect::<Vec<_>>();
+        let heights = items
+            .iter()
+            .take(visible.saturating_sub(1))
+            .map(|v| v.offset_height())
+            .collect::<Vec<_>>();
 
-        // summed heights until now
+        for element in grouped {
+            let _ = element.set_attribute("data-grouped", "");
+            let _ = element.remove_attribute("data-visible");
+        }
+
+        let _ = self.0.container.style().set_property(
+            "--seigi-toasts-count",
+            &(pinned.len() + items.len()).to_string(),
+        );
+
+        // summed heights until now, starting with pinned toasts in front of the others
         let mut heights_offset = 0;
-        for (index, rendered) in indices.iter().enumerate() {
-            let element = &rendered.element;
-            let _ = element.set_attribute("data-offset", format!("{heights_offset}").as_str());
+        for (index, (element, height)) in pinned.iter().zip(pinned_heights).enumerate() {
+            stack(element, heights_offset, index);
+            let _ = element.set_attribute("data-visible", "");
+            heights_offset += height + self.0.options.gap;
+        }
+
+        for (index, element) in items.into_iter().enumerate() {
+            let _ = element.remove_attribute("data-grouped");
+            stack(element, heights_offset, pinned.len() + index);
 
-            if index < self.0.options.visible - 1 {
-                heights_offset += element.offset_height() + self.0.options.gap;
+            if let Some(height) = heights.get(index) {
+                heights_offset += height + self.0.options.gap;
             }
 
             let _ = element.set_attribute("data-visible", "");
 
-            if index >= self.0.options.visible {
-                let _ = element.set_attribute(
-                    "data-collapsed",
-                    format!("{}", index - self.0.options.visible).as_str(),
-                );
+            if index >= visible {
+                let _ = element
+                    .set_attribute("data-collapsed", format!("{}", index - visible).as_str());
             } else {
                 let _ = element.remove_attribute("data-collapsed");
             }
@@ -141,16 +681,104 @@ impl Renderer {
     }
 }
 
+/// Tells styles the distance from the toast, before placed in the stack, to the point it enters
+/// from
+fn enter_from(element: &HtmlElement, (x, y): (f64, f64)) {
+    let style = element.style();
+    // Measuring applies styles of the toast, which would transition to the origin otherwise
+    let _ = style.set_property("transition", "none");
+    let rect = element.get_bounding_client_rect();
+    let x = x - (rect.left() + rect.width() / 2.0);
+    let y = y - (rect.top() + rect.height() / 2.0);
+    let _ = element.set_attribute("data-origin", "");
+    let _ = style.set_property("--seigi-toast-origin-x", &format!("{x}px"));
+    let _ = style.set_property("--seigi-toast-origin-y", &format!("{y}px"));
+    let _ = element.offset_height();
+    let _ = style.remove_property("transition");
+}
+
+/// Returns where the pointer of the event started swiping, unless it's another pointer
+fn swiping<'a>(
+    start: &Cell<Option<SwipeStart>>,
+    event: &'a Event,
+) -> Option<(SwipeStart, &'a PointerEvent)> {
+    let event = event.dyn_ref::<PointerEvent>()?;
+    let start = start.get().filter(|v| v.pointer == event.pointer_id())?;
+    Some((start, event))
+}
+
+/// Returns the gesture of the pointer released at the event, if the toast is swiped far or fast
+/// enough to be dismissed
+fn gesture(start: SwipeStart, event: &PointerEvent, options: SwipeOptions) -> Option<SwipeGesture> {
+    let x = event.client_x() as f64 - start.x;
+    let y = event.client_y() as f64 - start.y;
+    let (direction, distance) = if x.abs() >= y.abs() {
+        let direction = if x < 0.0 {
+            SwipeDirection::Left
+        } else {
+            SwipeDirection::Right
+        };
+        (direction, x.abs())
+    } else {
+        let direction = if y < 0.0 {
+            SwipeDirection::Up
+        } else {
+            SwipeDirection::Down
+        };
+        (direction, y.abs())
+    };
+    // Events dispatched in the same millisecond would make the speed infinite
+    let velocity = distance / (event.time_stamp() - start.time).max(1.0);
+    let is_flick = distance < options.distance;
+    if is_flick && velocity < options.velocity {
+        return None;
+    }
+    Some(SwipeGesture {
+        direction,
+        distance,
+        velocity,
+        is_flick,
+    })
+}
+
+/// Moves the toast back to where it was before dragged
+fn reset_swipe(element: &HtmlElement) {
+    let style = element.style();
+    let _ = style.remove_property("--seigi-toast-swipe-x");
+    let _ = style.remove_property("--seigi-toast-swipe-y");
+}
+
+/// Places the toast at the offset in pixels and the index in the stack, as `data-offset` and
+/// custom properties which styles can transition between
+fn stack(element: &HtmlElement, offset: i32, index: usize) {
+    let _ = element.set_attribute("data-offset", &offset.to_string());
+    let style = element.style();
+    let _ = style.set_property("--seigi-toast-offset", &format!("{offset}px"));
+    let _ = style.set_property("--seigi-toast-index", &index.to_string());
+}
+
 pub fn create_renderer(
     toaster: Toaster,
     container: HtmlElement,
     options: RendererOptions,
+    placement: Placement,
 ) -> Renderer {
-    let renderer = Renderer(Rc::new(Impl {
-        toaster,
-        container,
-        rendered: Mutex::new(VecDeque::new()),
-        options,
+    let renderer = Renderer(Rc::new_cyclic(|weak: &Weak<Impl>| {
+        let weak = weak.clone();
+        Impl {
+            toaster,
+            container,
+            rendered: Mutex::new(VecDeque::new()),
+            pinned: Mutex::new(vec![]),
+            groups: Mutex::new(HashMap::new()),
+            options,
+            placement,
+            transforms: schedule::frame(move || {
+                if let Some(renderer) = Renderer::from_weak(&weak) {
+                    renderer.update_transforms();
+                }
+            }),
+        }
     }));
 
     renderer.initialize();
@@ -1,5 +1,9 @@
 use std::time::Duration;
 
+use js_sys::Reflect;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Element, HtmlElement};
+
 use crate::DismissReason;
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
@@ -25,6 +29,79 @@ pub struct Toast {
     pub dismiss: Option<DismissReason>,
     /// The timeout where toast should automatically be dismissed after
     pub timeout: ToastTimeout,
+    /// The group toasts are summarized by while several of them are shown
+    pub group: Option<String>,
+    /// Whether the toast stays in front of the stack, out of visible and collapsed toasts
+    pub pinned: bool,
+    /// Where the toast comes from, like the button which caused it
+    pub origin: Option<ToastOrigin>,
+}
+
+/// Where a toast comes from, which the renderer tells styles to animate the toast in from
+#[derive(Debug, Clone)]
+pub enum ToastOrigin {
+    /// The center of the element, measured when the toast is rendered
+    Element(Element),
+    /// A point in the viewport in pixels, like where a pointer was clicked
+    Point { x: f64, y: f64 },
+}
+
+impl ToastOrigin {
+    /// Takes the origin from an element or an object of `x` and `y`, as told by JavaScript
+    pub fn from_js(value: &JsValue) -> Option<Self> {
+        if let Some(element) = value.dyn_ref::<Element>() {
+            return Some(Self::Element(element.clone()));
+        }
+        let coordinate = |name: &str| Reflect::get(value, &JsValue::from_str(name)).ok()?.as_f64();
+        Some(Self::Point {
+            x: coordinate("x")?,
+            y: coordinate("y")?,
+        })
+    }
+
+    /// Returns the point in the viewport the toast comes from
+    pub fn point(&self) -> (f64, f64) {
+        match self {
+            Self::Element(element) => {
+                let rect = element.get_bounding_client_rect();
+                (
+                    rect.left() + rect.width() / 2.0,
+                    rect.top() + rect.height() / 2.0,
+                )
+            }
+            Self::Point { x, y } => (*x, *y),
+        }
+    }
+}
+
+impl From<Element> for ToastOrigin {
+    fn from(value: Element) -> Self {
+        Self::Element(value)
+    }
+}
+
+impl From<&Element> for ToastOrigin {
+    fn from(value: &Element) -> Self {
+        Self::Element(value.clone())
+    }
+}
+
+impl From<HtmlElement> for ToastOrigin {
+    fn from(value: HtmlElement) -> Self {
+        Self::Element(value.into())
+    }
+}
+
+impl From<&HtmlElement> for ToastOrigin {
+    fn from(value: &HtmlElement) -> Self {
+        Self::Element(value.clone().into())
+    }
+}
+
+impl From<(f64, f64)> for ToastOrigin {
+    fn from((x, y): (f64, f64)) -> Self {
+        Self::Point { x, y }
+    }
 }
 
 impl Toast {
@@ -44,6 +121,9 @@ pub struct ToastBuilder {
     title: String,
     description: Option<String>,
     timeout: ToastTimeout,
+    group: Option<String>,
+    pinned: bool,
+    origin: Option<ToastOrigin>,
 }
 
 impl ToastBuilder {
@@ -52,6 +132,9 @@ impl ToastBuilder {
             title: String::new(),
             description: None,
             timeout: ToastTimeout::default(),
+            group: None,
+            pinned: false,
+            origin: None,
         }
     }
 
@@ -84,12 +167,46 @@ impl ToastBuilder {
         self
     }
 
+    /// Groups the toast with others of the same name, like notifications of a chat or a build
+    ///
+    /// While several toasts of a group are shown, the renderer stacks a summary with their count
+    /// in their place, which expands into them and dismisses them at once.
+    pub fn group(mut self, group: impl Into<String>) -> ToastBuilder {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// Pins the toast in front of the stack, like an indicator of being offline
+    ///
+    /// Pinned toasts are stacked in the order they were created, before every other toast, and
+    /// are neither counted in [RendererOptions::visible](crate::RendererOptions::visible) nor
+    /// collapsed or grouped. They are usually created with [ToastBuilder::timeout_none] and
+    /// dismissed once they no longer apply.
+    pub fn pinned(mut self) -> ToastBuilder {
+        self.pinned = true;
+        self
+    }
+
+    /// Animates the toast in from the origin, like the button which caused it
+    ///
+    /// The renderer sets `data-origin` on the toast and the distance from the toast to the
+    /// origin in `--seigi-toast-origin-x` and `--seigi-toast-origin-y`, which styles translate
+    /// the toast by before it becomes visible. Elements are measured as the toast is rendered,
+    /// so they must still be in the document by then.
+    pub fn origin(mut self, origin: impl Into<ToastOrigin>) -> ToastBuilder {
+        self.origin = Some(origin.into());
+        self
+    }
+
     pub fn build(self) -> Toast {
         Toast {
             title: self.title,
             description: self.description,
             dismiss: None,
             timeout: self.timeout,
+            group: self.group,
+            pinned: self.pinned,
+            origin: self.origin,
         }
     }
 }
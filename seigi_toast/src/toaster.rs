@@ -1,5 +1,6 @@
 use std::{
-    collections::HashMap,
+    cell::{Cell, RefCell},
+    collections::{HashMap, VecDeque},
     rc::Rc,
     sync::{
         Arc,
@@ -9,13 +10,35 @@ use std::{
 };
 
 use gloo::timers::callback::Timeout;
+use js_sys::{Object, Reflect};
 use parking_lot::{MappedMutexGuard, Mutex, MutexGuard, RwLock};
+use seigi_utils::{
+    config::{self, Position},
+    diagnostics::{self, Resource},
+};
+
+use wasm_bindgen::JsValue;
 
 use crate::{Toast, ToastHandle};
 
 struct EventSubscriber {
-    callback: Box<dyn Fn(&ToastEvent)>,
+    /// Shared with the JS function each event is delivered through, see [notify]
+    callback: Rc<dyn Fn(&ToastEvent)>,
     handle: u64,
+    priority: SubscriberPriority,
+}
+
+/// The order subscribers of a [Toaster] are called in, where higher priorities are called
+/// first, and equal ones in the order they subscribed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SubscriberPriority(pub i32);
+
+impl SubscriberPriority {
+    /// The priority of [Toaster::subscribe]
+    pub const DEFAULT: Self = Self(0);
+    /// The priority of the renderer, so subscribers of the default priority observe toasts
+    /// rendered already, while higher ones can act before, like dismissing duplicates
+    pub const RENDERER: Self = Self(100);
 }
 
 struct State {
@@ -38,14 +61,30 @@ impl State {
 
 #[derive(Default)]
 struct Observer {
+    /// Sorted by priority, highest first
     subscribers: Vec<EventSubscriber>,
+    /// Events published by subscribers, delivered once the current event reaches every one
+    queue: RefCell<VecDeque<ToastEvent>>,
+    is_publishing: Cell<bool>,
 }
 
 impl Observer {
-    fn subscribe(&mut self, callback: Box<dyn Fn(&ToastEvent)>) -> u64 {
+    fn subscribe(
+        &mut self,
+        callback: Box<dyn Fn(&ToastEvent)>,
+        priority: SubscriberPriority,
+    ) -> u64 {
         static SEQUENCE: AtomicU64 = AtomicU64::new(0);
         let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
-        self.subscribers.push(EventSubscriber { callback, handle });
+        let index = self.subscribers.partition_point(|v| v.priority >= priority);
+        self.subscribers.insert(
+            index,
+            EventSubscriber {
+                callback: callback.into(),
+                handle,
+                priority,
+            },
+        );
 
         handle
     }
@@ -55,16 +94,59 @@ impl Observer {
     }
 
     fn publish(&self, event: ToastEvent) {
-        for subscriber in self.subscribers.iter() {
-            let callback = &subscriber.callback;
-            callback(&event);
+        self.queue.borrow_mut().push_back(event);
+        if self.is_publishing.replace(true) {
+            return;
+        }
+        loop {
+            let Some(event) = self.queue.borrow_mut().pop_front() else {
+                break;
+            };
+            for subscriber in self.subscribers.iter() {
+                if !notify(&subscriber.callback, &event) {
+                    seigi_utils::warn!("toast subscriber panicked handling {event:?}");
+                }
+            }
         }
+        self.is_publishing.set(false);
     }
 }
 
+/// Calls the subscriber with the event, isolating a panic of it from other subscribers
+///
+/// Panics abort on wasm32 instead of unwinding, trapping up to the nearest JS frame, so the
+/// subscriber is called through a JS function whose call catches the trap.
+///
+/// # Returns
+/// Whether the subscriber returned without panicking
+#[cfg(target_arch = "wasm32")]
+fn notify(callback: &Rc<dyn Fn(&ToastEvent)>, event: &ToastEvent) -> bool {
+    let callback = callback.clone();
+    let event = event.clone();
+    use wasm_bindgen::{JsCast, prelude::Closure};
+
+    Closure::once_into_js(move || callback(&event))
+        .unchecked_into::<js_sys::Function>()
+        .call0(&JsValue::UNDEFINED)
+        .is_ok()
+}
+
+/// Calls the subscriber with the event, isolating a panic of it from other subscribers
+///
+/// # Returns
+/// Whether the subscriber returned without panicking
+#[cfg(not(target_arch = "wasm32"))]
+fn notify(callback: &Rc<dyn Fn(&ToastEvent)>, event: &ToastEvent) -> bool {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| callback(event))).is_ok()
+}
+
 #[derive(Debug, Clone)]
 pub struct ToasterOptions {
     timeout: Option<Duration>,
+    pub(crate) position: Position,
+    /// Positions replacing [ToasterOptions::position] while their media queries match
+    pub(crate) responsive: Vec<(String, Position)>,
+    pub(crate) top_layer: bool,
 }
 
 impl ToasterOptions {
@@ -81,12 +163,39 @@ impl ToasterOptions {
         self.timeout = None;
         self
     }
+
+    /// Places toasts at the position in the viewport
+    pub fn with_position(mut self, position: Position) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Places toasts at the position while the media query matches, like
+    /// `(max-width: 640px)`, taking precedence over queries added before
+    ///
+    /// Toasts on the screen move along when the placement changes, keeping their timers
+    pub fn with_position_when(mut self, query: impl Into<String>, position: Position) -> Self {
+        self.responsive.push((query.into(), position));
+        self
+    }
+
+    /// Renders toasts in a popover in the top layer where the Popover API is supported, above
+    /// stacking contexts of the page and modal dialogs opened before each toast
+    pub fn with_top_layer(mut self) -> Self {
+        self.top_layer = true;
+        self
+    }
 }
 
+/// Starts from the timeout and the position of [config::configure]
 impl Default for ToasterOptions {
     fn default() -> Self {
+        let config = config::config();
         Self {
-            timeout: Some(Duration::from_secs(4)),
+            timeout: config.toast_timeout,
+            position: config.toast_position,
+            responsive: vec![],
+            top_layer: false,
         }
     }
 }
@@ -112,6 +221,15 @@ impl Toaster {
         MutexGuard::try_map(state, |v| v.get(handle)).ok()
     }
 
+    /// Returns how long the toast stays until it times out, taking the default of the toaster
+    pub(crate) fn timeout_of(&self, toast: &Toast) -> Option<Duration> {
+        match toast.timeout {
+            crate::ToastTimeout::None => None,
+            crate::ToastTimeout::Default => self.options.timeout,
+            crate::ToastTimeout::Duration(duration) => Some(duration),
+        }
+    }
+
     /// Add toast to state
     ///
     /// # Returns
@@ -121,12 +239,7 @@ impl Toaster {
         let handle = ToastHandle(state.sequence);
         state.sequence += 1;
 
-        let timeout = match toast.timeout {
-            crate::ToastTimeout::None => None,
-            crate::ToastTimeout::Default => self.options.timeout,
-            crate::ToastTimeout::Duration(duration) => Some(duration),
-        };
-
+        let timeout = self.timeout_of(&toast);
         if let Some(timeout) = timeout {
             Timeout::new(timeout.as_millis() as u32, {
                 let this = self.clone();
@@ -135,8 +248,10 @@ impl Toaster {
                 }
             })
             .forget();
+            diagnostics::track(Resource::Timeout).forget();
         }
 
+        seigi_utils::debug!("toast {handle:?} created with timeout {timeout:?}");
         state.toasts.insert(handle, toast);
         drop(state);
 
@@ -149,15 +264,21 @@ impl Toaster {
     /// Dismiss a toast of handle with given reason
     ///
     /// # Returns
-    /// True if toast has been set to be dismissed, false if no toast of handle was found
+    /// True if toast has been set to be dismissed, false if no toast of handle was found or it
+    /// has been dismissed already, keeping the first reason
     pub fn dismiss_toast(&self, handle: ToastHandle, reason: DismissReason) -> bool {
         let mut state = self.state.lock();
         let Some(toast) = state.toasts.get_mut(&handle) else {
             return false;
         };
+        // Timeouts aren't cancelled, so they fire for toasts dismissed before as well
+        if toast.dismiss.is_some() {
+            return false;
+        }
 
         toast.dismiss = Some(reason.clone());
         drop(state);
+        seigi_utils::debug!("toast {handle:?} dismissed by {reason:?}");
 
         let observer = self.observer.read();
         observer.publish(ToastEvent::Dismiss { handle, reason });
@@ -165,13 +286,91 @@ impl Toaster {
         true
     }
 
+    /// Dismiss every toast of the group not dismissed yet with given reason
+    ///
+    /// # Returns
+    /// The count of dismissed toasts
+    pub fn dismiss_group(&self, group: &str, reason: DismissReason) -> usize {
+        let handles = self
+            .state
+            .lock()
+            .toasts
+            .iter()
+            .filter(|(_, toast)| toast.dismiss.is_none() && toast.group.as_deref() == Some(group))
+            .map(|(handle, _)| *handle)
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .filter(|handle| self.dismiss_toast(*handle, reason.clone()))
+            .count()
+    }
+
+    /// Returns counts of toasts in the toaster, live and dismissed by each reason
+    ///
+    /// There is no queued count, as toasts aren't queued: every toast is shown once created.
+    pub fn stats(&self) -> ToasterStats {
+        let state = self.state.lock();
+        let mut stats = ToasterStats::default();
+        for toast in state.toasts.values() {
+            match toast.dismiss {
+                None => stats.live += 1,
+                Some(DismissReason::Timeout) => stats.timed_out += 1,
+                Some(DismissReason::User | DismissReason::Swipe(_)) => stats.dismissed_by_user += 1,
+                Some(DismissReason::Resolved) => stats.resolved += 1,
+            }
+        }
+        stats
+    }
+
+    /// Returns an iterator over handles of toasts not dismissed yet, oldest first
+    ///
+    /// The handles are collected before the iterator is returned, so the toaster may be called
+    /// while iterating.
+    pub fn iter_handles(&self) -> impl Iterator<Item = ToastHandle> + use<> {
+        let mut handles = self
+            .state
+            .lock()
+            .toasts
+            .iter()
+            .filter(|(_, toast)| toast.dismiss.is_none())
+            .map(|(handle, _)| *handle)
+            .collect::<Vec<_>>();
+        handles.sort_by_key(|v| v.0);
+        handles.into_iter()
+    }
+
     /// Add subscriber to state and return handle to it
     ///
+    /// The subscriber is called after the renderer, so toasts are rendered by the time it
+    /// observes them. See [Toaster::subscribe_with_priority] for the order of subscribers.
+    ///
     /// # Returns
     /// Handle of added subscriber
     pub fn subscribe(&self, callback: Box<dyn Fn(&ToastEvent)>) -> u64 {
+        self.subscribe_with_priority(callback, SubscriberPriority::DEFAULT)
+    }
+
+    /// Add subscriber called in the order of the priority, and return handle to it
+    ///
+    /// Subscribers of higher priorities are called first, and equal ones in the order they
+    /// subscribed. Events published while subscribers are called, like a toast created in a
+    /// callback, are delivered once the current event reaches every subscriber, so each one
+    /// observes events in the order they happened.
+    ///
+    /// A subscriber panicking is logged, and the event still reaches the others. On wasm32,
+    /// where panics abort, the panicking subscriber is never unwound: what its frames own is
+    /// leaked, and locks or borrows they hold stay taken. Treat a panic as a bug to fix rather
+    /// than an error to recover from.
+    ///
+    /// # Returns
+    /// Handle of added subscriber
+    pub fn subscribe_with_priority(
+        &self,
+        callback: Box<dyn Fn(&ToastEvent)>,
+        priority: SubscriberPriority,
+    ) -> u64 {
         let mut observer = self.observer.write();
-        observer.subscribe(callback)
+        observer.subscribe(callback, priority)
     }
 
     /// Remove subscriber from state
@@ -187,7 +386,27 @@ impl Default for Toaster {
     }
 }
 
-#[derive(Debug)]
+/// Counts of toasts in a [Toaster], returned by [Toaster::stats]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ToasterStats {
+    /// Toasts not dismissed yet
+    pub live: usize,
+    /// Toasts dismissed by [DismissReason::Timeout]
+    pub timed_out: usize,
+    /// Toasts dismissed by [DismissReason::User] and [DismissReason::Swipe]
+    pub dismissed_by_user: usize,
+    /// Toasts dismissed by [DismissReason::Resolved]
+    pub resolved: usize,
+}
+
+impl ToasterStats {
+    /// Returns the count of dismissed toasts, by any reason
+    pub fn dismissed(&self) -> usize {
+        self.timed_out + self.dismissed_by_user + self.resolved
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum ToastEvent {
     Create {
         handle: ToastHandle,
@@ -208,4 +427,73 @@ pub enum DismissReason {
     Timeout,
     /// The user manually dismissed the toast
     User,
+    /// What the toast reported is over, like being offline in [crate::connectivity]
+    Resolved,
+    /// The user swiped the rendered toast away, with how the gesture went
+    Swipe(SwipeGesture),
+}
+
+impl DismissReason {
+    /// Returns the name of the reason, like `timeout`, as told to JavaScript
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Timeout => "timeout",
+            Self::User => "user",
+            Self::Resolved => "resolved",
+            Self::Swipe(_) => "swipe",
+        }
+    }
+}
+
+/// A swipe dismissing a toast, in [DismissReason::Swipe]
+///
+/// Toasts are dismissed once dragged far enough or flicked fast enough. Subscribers can tell
+/// the two apart with `is_flick`, like to offer undoing flicks which might have been accidental.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwipeGesture {
+    pub direction: SwipeDirection,
+    /// Distance the toast was dragged along the direction in pixels
+    pub distance: f64,
+    /// Speed of the toast from the start of the gesture to its release in pixels per
+    /// millisecond
+    pub velocity: f64,
+    /// Whether the toast was released short of the distance dismissing it, but fast enough
+    pub is_flick: bool,
+}
+
+impl SwipeGesture {
+    /// Converts the gesture into an object of `direction`, `distance`, `velocity` and `isFlick`,
+    /// as told to JavaScript
+    pub fn to_js(&self) -> JsValue {
+        let object = Object::new();
+        let _ = Reflect::set(
+            &object,
+            &"direction".into(),
+            &self.direction.as_str().into(),
+        );
+        let _ = Reflect::set(&object, &"distance".into(), &self.distance.into());
+        let _ = Reflect::set(&object, &"velocity".into(), &self.velocity.into());
+        let _ = Reflect::set(&object, &"isFlick".into(), &self.is_flick.into());
+        object.into()
+    }
+}
+
+/// The direction a toast is swiped in, along the axis it moved most on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwipeDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl SwipeDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Left => "left",
+            Self::Right => "right",
+        }
+    }
 }
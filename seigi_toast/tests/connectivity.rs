@@ -0,0 +1,63 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use gloo::utils::window;
+use seigi_testkit::events;
+use seigi_toast::{
+    DismissReason, Toaster, ToasterOptions,
+    connectivity::{self, ConnectivityOptions},
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn offline_shows_pinned_toast_until_online() {
+    // Test browsers are online, and events stand in for losing the network
+    assert!(connectivity::is_online());
+    let toaster = Toaster::new(ToasterOptions::default().without_timeout());
+    let watch = connectivity::watch(
+        toaster.clone(),
+        ConnectivityOptions::builder().title("Offline").build(),
+    );
+    let changes = Rc::new(RefCell::new(vec![]));
+    watch.subscribe(Box::new({
+        let changes = changes.clone();
+        move |is_online| changes.borrow_mut().push(is_online)
+    }));
+
+    events::fire(&window(), "offline");
+    assert!(!watch.is_online());
+    let handle = toaster.iter_handles().next().unwrap();
+    {
+        let toast = toaster.get(handle).unwrap();
+        assert_eq!(toast.title, "Offline");
+        assert!(toast.pinned);
+    }
+
+    // Repeated events don't add toasts
+    events::fire(&window(), "offline");
+    assert_eq!(toaster.stats().live, 1);
+
+    events::fire(&window(), "online");
+    assert!(watch.is_online());
+    assert!(matches!(
+        toaster.get(handle).and_then(|v| v.dismiss.clone()),
+        Some(DismissReason::Resolved)
+    ));
+    assert_eq!(*changes.borrow(), [false, true]);
+}
+
+#[wasm_bindgen_test]
+fn toast_can_be_disabled() {
+    let toaster = Toaster::new(ToasterOptions::default().without_timeout());
+    let watch = connectivity::watch(
+        toaster.clone(),
+        ConnectivityOptions::builder().toast(false).build(),
+    );
+    events::fire(&window(), "offline");
+    assert!(!watch.is_online());
+    assert_eq!(toaster.stats().live, 0);
+    events::fire(&window(), "online");
+}
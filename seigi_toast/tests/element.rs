@@ -0,0 +1,88 @@
+#![cfg(all(target_arch = "wasm32", feature = "element"))]
+
+use std::{cell::RefCell, rc::Rc};
+
+use gloo::{events::EventListener, utils::document};
+use js_sys::{Object, Reflect};
+use seigi_testkit::Fixture;
+use seigi_toast::{DismissReason, element};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::*;
+use web_sys::{CustomEvent, CustomEventInit};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Returns the property of the detail of the custom event
+fn detail(event: &web_sys::Event, name: &str) -> JsValue {
+    let detail = event.unchecked_ref::<CustomEvent>().detail();
+    Reflect::get(&detail, &name.into()).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn element_renders_toasts_requested_by_events() {
+    element::define().unwrap();
+    let fixture = Fixture::new(
+        r#"<seigi-toaster position="top-left" visible="2" theme="dark"></seigi-toaster>
+        <button id="save">Save</button>"#,
+    );
+    let toaster_element = fixture.get(element::TAG);
+    let toaster = seigi_toast::toaster().expect("the element initializes the global toaster");
+    assert!(toaster_element.has_attribute("data-seigi-toaster"));
+    assert_eq!(
+        toaster_element.get_attribute("data-position").as_deref(),
+        Some("top-left")
+    );
+    assert_eq!(
+        toaster_element.get_attribute("data-theme").as_deref(),
+        Some("dark")
+    );
+
+    let events = Rc::new(RefCell::new(vec![]));
+    let _listeners = ["seigi-toast-create", "seigi-toast-dismiss"].map(|kind| {
+        let events = events.clone();
+        EventListener::new(&document(), kind, move |event| {
+            let field = if event.type_() == "seigi-toast-create" {
+                "title"
+            } else {
+                "reason"
+            };
+            events
+                .borrow_mut()
+                .push((event.type_(), detail(event, field).as_string().unwrap()));
+        })
+    });
+
+    let request = Object::new();
+    Reflect::set(&request, &"title".into(), &"Saved".into()).unwrap();
+    Reflect::set(&request, &"timeout".into(), &JsValue::NULL).unwrap();
+    let init = CustomEventInit::new();
+    init.set_bubbles(true);
+    init.set_detail(&request);
+    let event = CustomEvent::new_with_event_init_dict("seigi-toast", &init).unwrap();
+    fixture.get("#save").dispatch_event(&event).unwrap();
+
+    let handle = toaster
+        .iter_handles()
+        .next()
+        .expect("the request creates a toast");
+    assert!(
+        fixture
+            .find("seigi-toaster [data-seigi-toast]")
+            .is_some_and(|v| v.text_content().unwrap_or_default().contains("Saved"))
+    );
+    toaster.dismiss_toast(handle, DismissReason::User);
+    assert_eq!(
+        *events.borrow(),
+        [
+            ("seigi-toast-create".to_string(), "Saved".to_string()),
+            ("seigi-toast-dismiss".to_string(), "user".to_string()),
+        ]
+    );
+
+    // Theme follows the attribute as it changes
+    toaster_element.set_attribute("theme", "system").unwrap();
+    assert_eq!(
+        toaster_element.get_attribute("data-theme").as_deref(),
+        Some("system")
+    );
+}
@@ -0,0 +1,81 @@
+#![cfg(all(target_arch = "wasm32", feature = "inspector"))]
+
+use std::time::Duration;
+
+use gloo::utils::document;
+use seigi_testkit::{pointer, wait};
+use seigi_toast::{
+    DismissReason, Toast, ToastEvent, Toaster, ToasterOptions, inspector::Inspector,
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::HtmlElement;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn inspector_records_and_replays_events() {
+    let toaster = Toaster::new(ToasterOptions::default().without_timeout());
+    let inspector = Inspector::attach(&toaster);
+
+    let timed = toaster.add_toast(
+        Toast::builder()
+            .title("timed")
+            .timeout(Duration::from_millis(50))
+            .build(),
+    );
+    let closed = toaster.add_toast(Toast::builder().title("closed").build());
+    toaster.dismiss_toast(closed, DismissReason::User);
+    wait::sleep(100).await;
+
+    let toasts = inspector.toasts();
+    assert_eq!(toasts.len(), 2);
+    assert_eq!(toasts[0].handle, timed);
+    assert!(toasts[0].due.is_some());
+    assert!(toasts[0].lateness().is_some_and(|v| v >= 0.0));
+    assert!(matches!(
+        toasts[1].dismissed,
+        Some((_, DismissReason::User))
+    ));
+    let records = inspector.records();
+    assert_eq!(records.len(), 4);
+    assert!(records.windows(2).all(|v| v[0].time <= v[1].time));
+    assert!(matches!(
+        records[3].event,
+        ToastEvent::Dismiss {
+            reason: DismissReason::Timeout,
+            ..
+        }
+    ));
+
+    inspector.show();
+    let panel = || {
+        document()
+            .query_selector("[data-seigi-toast-inspector]")
+            .unwrap()
+    };
+    let items = panel()
+        .expect("the panel is injected")
+        .query_selector_all("[data-seigi-toast-inspector-toasts] > li")
+        .unwrap();
+    assert_eq!(items.length(), 2);
+
+    let replay = document()
+        .query_selector("[data-seigi-toast-inspector-replay]")
+        .unwrap()
+        .unwrap()
+        .unchecked_into::<HtmlElement>();
+    pointer::click(&replay).await;
+    wait::sleep(100).await;
+    let replayed = inspector
+        .records()
+        .into_iter()
+        .filter(|v| v.is_replayed)
+        .collect::<Vec<_>>();
+    assert_eq!(replayed.len(), 4);
+    // Replayed toasts are dismissed at the times recorded, timeouts included
+    assert!(toaster.iter_handles().next().is_none());
+
+    drop(inspector);
+    assert!(panel().is_none());
+}
@@ -0,0 +1,40 @@
+#![cfg(target_arch = "wasm32")]
+
+use gloo::utils::document;
+use seigi_testkit::wait;
+use seigi_toast::{Error, Toast, ToasterOptions};
+use seigi_utils::config::{self, GlobalConfig};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn first_toast_initializes_global_toaster() {
+    let previous = config::config();
+    config::configure(GlobalConfig {
+        toast_lazy_initialize: false,
+        ..previous.clone()
+    });
+    assert_eq!(
+        seigi_toast::create_toast(Toast::builder().title("early")),
+        Err(Error::NotInitialized)
+    );
+    assert!(seigi_toast::toaster().is_none());
+    config::configure(previous);
+
+    let handle = seigi_toast::create_toast(Toast::builder().title("lazy").timeout_none()).unwrap();
+    let toaster = seigi_toast::toaster().expect("the toast initializes the global toaster");
+    assert_eq!(toaster.iter_handles().collect::<Vec<_>>(), [handle]);
+    assert_eq!(
+        seigi_toast::initialize(ToasterOptions::default()).err(),
+        Some(Error::AlreadyInitialized)
+    );
+
+    wait::frame().await;
+    let rendered = document()
+        .query_selector("[data-seigi-toaster] [data-seigi-toast]")
+        .unwrap()
+        .expect("the toast is rendered");
+    assert_eq!(rendered.text_content().as_deref(), Some("lazy"));
+    seigi_toast::dismiss_toast(handle);
+}
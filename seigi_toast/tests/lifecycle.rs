@@ -0,0 +1,475 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use gloo::utils::document;
+use seigi_testkit::wait;
+use seigi_toast::{
+    DismissReason, Position, SubscriberPriority, Toast, ToastEvent, ToastHandle, Toaster,
+    ToasterOptions, ToasterStats,
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::HtmlElement;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Returns the global toaster, which tests running in the same page share
+fn toaster() -> Toaster {
+    seigi_toast::toaster().unwrap_or_else(|| {
+        seigi_toast::initialize(
+            ToasterOptions::default()
+                .without_timeout()
+                .with_position(Position::BottomRight)
+                // Matches in any viewport, replacing the position above
+                .with_position_when("(min-width: 0px)", Position::TopCenter),
+        )
+        .unwrap()
+    })
+}
+
+/// Returns the rendered element of the toast titled the title, which tests keep unique
+fn rendered(title: &str) -> Option<HtmlElement> {
+    let list = document()
+        .query_selector_all("[data-seigi-toaster] [data-seigi-toast]")
+        .unwrap();
+    (0..list.length())
+        .filter_map(|index| list.get(index)?.dyn_into::<HtmlElement>().ok())
+        .find(|v| v.text_content().as_deref() == Some(title))
+}
+
+/// Records events of the toaster until dropped
+struct Recorder {
+    toaster: Toaster,
+    subscription: u64,
+    events: Rc<RefCell<Vec<ToastEvent>>>,
+}
+
+impl Recorder {
+    fn new(toaster: &Toaster) -> Self {
+        let events = Rc::new(RefCell::new(vec![]));
+        let subscription = toaster.subscribe(Box::new({
+            let events = events.clone();
+            move |event: &ToastEvent| {
+                let event = match event {
+                    ToastEvent::Create { handle } => ToastEvent::Create { handle: *handle },
+                    ToastEvent::Update { handle } => ToastEvent::Update { handle: *handle },
+                    ToastEvent::Dismiss { handle, reason } => ToastEvent::Dismiss {
+                        handle: *handle,
+                        reason: reason.clone(),
+                    },
+                };
+                events.borrow_mut().push(event);
+            }
+        }));
+        Self {
+            toaster: toaster.clone(),
+            subscription,
+            events,
+        }
+    }
+
+    fn dismissed(&self, handle: ToastHandle) -> Option<DismissReason> {
+        self.events.borrow().iter().find_map(|v| match v {
+            ToastEvent::Dismiss { handle: h, reason } if *h == handle => Some(reason.clone()),
+            _ => None,
+        })
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        self.toaster.unsubscribe(self.subscription);
+    }
+}
+
+#[wasm_bindgen_test]
+async fn create_renders_and_shows_toast() {
+    let toaster = toaster();
+    let recorder = Recorder::new(&toaster);
+
+    let handle = seigi_toast::create_toast_unchecked(Toast::builder().title("create").build());
+    assert!(matches!(
+        recorder.events.borrow().as_slice(),
+        [ToastEvent::Create { handle: h }] if *h == handle
+    ));
+
+    let element = rendered("create").expect("toast is rendered at once");
+    wait::frame().await;
+    assert!(element.has_attribute("data-visible"));
+    assert_eq!(element.get_attribute("data-offset").as_deref(), Some("0"));
+
+    seigi_toast::dismiss_toast(handle);
+}
+
+#[wasm_bindgen_test]
+async fn dismiss_marks_toast_dismissed() {
+    let toaster = toaster();
+    let recorder = Recorder::new(&toaster);
+
+    let handle = seigi_toast::create_toast_unchecked(Toast::builder().title("dismiss").build());
+    wait::frame().await;
+    let element = rendered("dismiss").unwrap();
+    assert!(seigi_toast::dismiss_toast(handle));
+
+    assert!(matches!(
+        recorder.dismissed(handle),
+        Some(DismissReason::User)
+    ));
+    assert!(matches!(
+        toaster.get(handle).and_then(|v| v.dismiss.clone()),
+        Some(DismissReason::User)
+    ));
+    assert!(element.has_attribute("data-dismissed"));
+    assert!(!element.has_attribute("data-visible"));
+}
+
+#[wasm_bindgen_test]
+async fn dismiss_of_unknown_handle_fails() {
+    toaster();
+    assert!(!seigi_toast::dismiss_toast(ToastHandle(u32::MAX)));
+}
+
+#[wasm_bindgen_test]
+fn stats_count_toasts_by_state() {
+    // Unrendered, so the shared global toaster doesn't skew the counts
+    let toaster = Toaster::new(ToasterOptions::default().without_timeout());
+    let first = toaster.add_toast(Toast::builder().title("first").build());
+    let second = toaster.add_toast(Toast::builder().title("second").build());
+    let third = toaster.add_toast(Toast::builder().title("third").build());
+    assert_eq!(
+        toaster.iter_handles().collect::<Vec<_>>(),
+        [first, second, third]
+    );
+
+    toaster.dismiss_toast(second, DismissReason::User);
+    toaster.dismiss_toast(third, DismissReason::Timeout);
+    assert_eq!(
+        toaster.stats(),
+        ToasterStats {
+            live: 1,
+            timed_out: 1,
+            dismissed_by_user: 1,
+            resolved: 0,
+        }
+    );
+    assert_eq!(toaster.stats().dismissed(), 2);
+    assert_eq!(toaster.iter_handles().collect::<Vec<_>>(), [first]);
+}
+
+#[wasm_bindgen_test]
+async fn dismissed_toast_keeps_first_reason() {
+    let toaster = Toaster::new(ToasterOptions::default().without_timeout());
+    let dismissals = Rc::new(RefCell::new(0));
+    toaster.subscribe(Box::new({
+        let dismissals = dismissals.clone();
+        move |event| {
+            if matches!(event, ToastEvent::Dismiss { .. }) {
+                *dismissals.borrow_mut() += 1;
+            }
+        }
+    }));
+
+    let handle = toaster.add_toast(Toast::builder().title("first reason").build());
+    assert!(toaster.dismiss_toast(handle, DismissReason::User));
+    assert!(!toaster.dismiss_toast(handle, DismissReason::Timeout));
+    assert_eq!(toaster.stats().dismissed_by_user, 1);
+    assert_eq!(toaster.stats().timed_out, 0);
+
+    // The timeout of a toast dismissed before it fires changes nothing
+    let handle = toaster.add_toast(
+        Toast::builder()
+            .title("first reason")
+            .timeout(Duration::from_millis(10))
+            .build(),
+    );
+    assert!(toaster.dismiss_toast(handle, DismissReason::Resolved));
+    wait::sleep(50).await;
+    assert!(matches!(
+        toaster.get(handle).and_then(|v| v.dismiss.clone()),
+        Some(DismissReason::Resolved)
+    ));
+    assert_eq!(
+        toaster.stats(),
+        ToasterStats {
+            live: 0,
+            timed_out: 0,
+            dismissed_by_user: 1,
+            resolved: 1,
+        }
+    );
+    assert_eq!(*dismissals.borrow(), 2);
+}
+
+#[wasm_bindgen_test]
+async fn timeout_dismisses_toast() {
+    let toaster = toaster();
+    let recorder = Recorder::new(&toaster);
+
+    let handle = seigi_toast::create_toast_unchecked(
+        Toast::builder()
+            .title("timeout")
+            .timeout(Duration::from_millis(10))
+            .build(),
+    );
+    assert!(recorder.dismissed(handle).is_none());
+
+    wait::sleep(50).await;
+    assert!(matches!(
+        recorder.dismissed(handle),
+        Some(DismissReason::Timeout)
+    ));
+}
+
+#[wasm_bindgen_test]
+async fn toasts_stack_newest_first() {
+    toaster();
+    let handles = (0..5)
+        .map(|index| {
+            seigi_toast::create_toast_unchecked(
+                Toast::builder().title(format!("stack {index}")).build(),
+            )
+        })
+        .collect::<Vec<_>>();
+    wait::frame().await;
+
+    let offset = |index: usize| -> i32 {
+        rendered(&format!("stack {index}"))
+            .and_then(|v| v.get_attribute("data-offset"))
+            .and_then(|v| v.parse().ok())
+            .unwrap()
+    };
+    assert_eq!(offset(4), 0);
+    assert!(offset(3) > offset(4));
+    assert!(offset(2) > offset(3));
+
+    // Only the newest toasts are visible, while the older are collapsed behind them
+    let collapsed = (0..5)
+        .filter(|index| {
+            rendered(&format!("stack {index}")).is_some_and(|v| v.has_attribute("data-collapsed"))
+        })
+        .count();
+    assert!(collapsed > 0);
+    assert!(!rendered("stack 4").unwrap().has_attribute("data-collapsed"));
+
+    for handle in handles {
+        seigi_toast::dismiss_toast(handle);
+    }
+}
+
+#[wasm_bindgen_test]
+async fn pinned_toast_stays_in_front() {
+    toaster();
+    let pinned =
+        seigi_toast::create_toast_unchecked(Toast::builder().title("pinned").pinned().build());
+    let handles = (0..5)
+        .map(|index| {
+            seigi_toast::create_toast_unchecked(
+                Toast::builder().title(format!("flowing {index}")).build(),
+            )
+        })
+        .collect::<Vec<_>>();
+    wait::frame().await;
+
+    let pinned_element = rendered("pinned").unwrap();
+    assert!(pinned_element.has_attribute("data-pinned"));
+    assert_eq!(
+        pinned_element.get_attribute("data-offset").as_deref(),
+        Some("0")
+    );
+    assert!(!pinned_element.has_attribute("data-collapsed"));
+    // The newest flowing toast follows the pinned one, and visible toasts exclude it
+    let newest = rendered("flowing 4").unwrap();
+    assert_ne!(newest.get_attribute("data-offset").as_deref(), Some("0"));
+    assert!(!newest.has_attribute("data-collapsed"));
+    // Stacking is exposed to styles as custom properties as well
+    let property = |element: &HtmlElement, name: &str| {
+        element.style().get_property_value(name).unwrap_or_default()
+    };
+    assert_eq!(property(&pinned_element, "--seigi-toast-offset"), "0px");
+    assert_eq!(property(&pinned_element, "--seigi-toast-index"), "0");
+    assert_eq!(
+        property(&newest, "--seigi-toast-offset"),
+        format!("{}px", newest.get_attribute("data-offset").unwrap())
+    );
+    assert_eq!(property(&newest, "--seigi-toast-index"), "1");
+    let container = newest
+        .parent_element()
+        .unwrap()
+        .unchecked_into::<HtmlElement>();
+    // Toasts of other tests may still be leaving
+    let count = property(&container, "--seigi-toasts-count");
+    assert!(count.parse::<usize>().is_ok_and(|v| v >= 6));
+    assert!(
+        !rendered("flowing 2")
+            .unwrap()
+            .has_attribute("data-collapsed")
+    );
+    assert!(
+        rendered("flowing 1")
+            .unwrap()
+            .has_attribute("data-collapsed")
+    );
+
+    for handle in handles {
+        seigi_toast::dismiss_toast(handle);
+    }
+    seigi_toast::dismiss_toast(pinned);
+}
+
+#[wasm_bindgen_test]
+async fn initialize_twice_fails() {
+    toaster();
+    assert_eq!(
+        seigi_toast::initialize(ToasterOptions::default()).err(),
+        Some(seigi_toast::Error::AlreadyInitialized)
+    );
+    assert_eq!(
+        document()
+            .query_selector_all("[data-seigi-toaster]")
+            .unwrap()
+            .length(),
+        1
+    );
+}
+
+#[wasm_bindgen_test]
+async fn matching_query_replaces_position() {
+    toaster();
+    let container = document()
+        .query_selector("[data-seigi-toaster]")
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        container.get_attribute("data-position").as_deref(),
+        Some("top-center")
+    );
+    assert!(!container.has_attribute("data-repositioning"));
+}
+
+#[wasm_bindgen_test]
+async fn group_summarizes_and_dismisses_toasts() {
+    toaster();
+    let handles = (0..3)
+        .map(|index| {
+            seigi_toast::create_toast_unchecked(
+                Toast::builder()
+                    .title(format!("build {index}"))
+                    .group("build")
+                    .build(),
+            )
+        })
+        .collect::<Vec<_>>();
+    wait::frame().await;
+
+    let summary = || {
+        document()
+            .query_selector("[data-seigi-toast-group=build]")
+            .unwrap()
+            .map(|v| v.unchecked_into::<HtmlElement>())
+    };
+    let part = |selector: &str| {
+        summary()
+            .unwrap()
+            .query_selector(selector)
+            .unwrap()
+            .unwrap()
+            .unchecked_into::<HtmlElement>()
+    };
+    assert_eq!(
+        part("[data-seigi-toast-count]").text_content().as_deref(),
+        Some("3")
+    );
+    assert!(summary().unwrap().has_attribute("data-visible"));
+    assert!(rendered("build 0").unwrap().has_attribute("data-grouped"));
+
+    part("[data-seigi-toast-group-toggle]").click();
+    wait::frame().await;
+    assert!(summary().unwrap().has_attribute("data-expanded"));
+    assert!(!rendered("build 0").unwrap().has_attribute("data-grouped"));
+
+    part("[data-seigi-toast-group-dismiss]").click();
+    assert!(summary().is_none());
+    assert!(
+        handles
+            .into_iter()
+            .all(|v| toaster().get(v).is_some_and(|v| v.dismiss.is_some()))
+    );
+}
+
+#[wasm_bindgen_test]
+fn subscribers_are_called_in_priority_order() {
+    let toaster = toaster();
+    let observed = Rc::new(RefCell::new(vec![]));
+    let observe = |name: &'static str| {
+        let observed = observed.clone();
+        let source = toaster.clone();
+        Box::new(move |event: &ToastEvent| {
+            if let ToastEvent::Create { handle } = event {
+                let title = source.get(*handle).unwrap().title.clone();
+                observed
+                    .borrow_mut()
+                    .push((name, title.clone(), rendered(&title).is_some()));
+            }
+        })
+    };
+    // Creates a toast while the first one is published
+    let chained = toaster.subscribe(Box::new({
+        let source = toaster.clone();
+        move |event: &ToastEvent| {
+            let ToastEvent::Create { handle } = event else {
+                return;
+            };
+            let title = source.get(*handle).unwrap().title.clone();
+            if title == "first" {
+                source.add_toast(Toast::builder().title("second").build());
+            }
+        }
+    }));
+    let late = toaster.subscribe(observe("late"));
+    let early = toaster.subscribe_with_priority(observe("early"), SubscriberPriority(200));
+
+    let first = toaster.add_toast(Toast::builder().title("first").build());
+    assert_eq!(
+        *observed.borrow(),
+        [
+            ("early", "first".to_string(), false),
+            ("late", "first".to_string(), true),
+            ("early", "second".to_string(), false),
+            ("late", "second".to_string(), true),
+        ]
+    );
+
+    for handle in [chained, late, early] {
+        toaster.unsubscribe(handle);
+    }
+    toaster.dismiss_toast(first, DismissReason::User);
+    if let Some(second) = toaster.iter_handles().last() {
+        toaster.dismiss_toast(second, DismissReason::User);
+    }
+}
+
+#[wasm_bindgen_test]
+fn panicking_subscriber_leaves_others_notified() {
+    let toaster = Toaster::new(ToasterOptions::default().without_timeout());
+    let observed = Rc::new(RefCell::new(vec![]));
+    toaster.subscribe_with_priority(
+        Box::new(|event: &ToastEvent| {
+            if matches!(event, ToastEvent::Create { .. }) {
+                panic!("subscriber failed");
+            }
+        }),
+        SubscriberPriority(200),
+    );
+    toaster.subscribe(Box::new({
+        let observed = observed.clone();
+        move |event: &ToastEvent| observed.borrow_mut().push(event.clone())
+    }));
+
+    let handle = toaster.add_toast(Toast::builder().title("first").build());
+    toaster.dismiss_toast(handle, DismissReason::User);
+    assert!(matches!(
+        observed.borrow().as_slice(),
+        [ToastEvent::Create { .. }, ToastEvent::Dismiss { .. }]
+    ));
+}
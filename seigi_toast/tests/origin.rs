@@ -0,0 +1,44 @@
+#![cfg(target_arch = "wasm32")]
+
+use gloo::utils::document;
+use seigi_testkit::Fixture;
+use seigi_toast::{Toast, ToasterOptions};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::Element;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn toasts_enter_from_their_origins() {
+    seigi_toast::initialize(ToasterOptions::default().without_timeout()).unwrap();
+    let fixture = Fixture::new(
+        r#"<button style="position: fixed; left: 40px; top: 60px; width: 100px; height: 20px">Save</button>"#,
+    );
+    let button = fixture.get("button");
+
+    let from_button =
+        seigi_toast::create_toast_unchecked(Toast::builder().title("saved").origin(&button));
+    let from_point = seigi_toast::create_toast_unchecked(
+        Toast::builder().title("copied").origin((300.0, 200.0)),
+    );
+
+    // Until shown, toasts are translated onto the centers of their origins
+    let toasts = document()
+        .query_selector_all("[data-seigi-toast][data-origin]")
+        .unwrap();
+    assert_eq!(toasts.length(), 2);
+    let center = |index: u32| {
+        let toast = toasts.item(index).unwrap();
+        let rect = toast.unchecked_ref::<Element>().get_bounding_client_rect();
+        (
+            (rect.left() + rect.width() / 2.0).round(),
+            (rect.top() + rect.height() / 2.0).round(),
+        )
+    };
+    assert_eq!(center(0), (90.0, 70.0));
+    assert_eq!(center(1), (300.0, 200.0));
+
+    seigi_toast::dismiss_toast(from_button);
+    seigi_toast::dismiss_toast(from_point);
+}
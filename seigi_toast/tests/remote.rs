@@ -0,0 +1,45 @@
+#![cfg(all(target_arch = "wasm32", feature = "remote"))]
+
+use seigi_toast::{
+    Error, ToastTimeout, Toaster,
+    remote::{self, RemoteOptions, RemoteToast},
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn payloads_tell_timeouts_apart() {
+    let toast = |json: &str| serde_json::from_str::<RemoteToast>(json).unwrap();
+
+    let default = toast(r#"{"title": "Deployed"}"#);
+    assert_eq!(default.timeout, None);
+    assert!(matches!(
+        seigi_toast::Toast::from(default.clone()).timeout,
+        ToastTimeout::Default
+    ));
+
+    assert!(!default.pinned);
+
+    let persistent = toast(r#"{"title": "Failed", "timeout": null, "group": "build"}"#);
+    assert_eq!(persistent.timeout, Some(None));
+    assert_eq!(persistent.group.as_deref(), Some("build"));
+
+    let offline = toast(r#"{"title": "Offline", "pinned": true}"#);
+    assert!(seigi_toast::Toast::from(offline).pinned);
+
+    let timed = toast(r#"{"title": "Saved", "description": "Draft", "timeout": 8000}"#);
+    assert_eq!(timed.timeout, Some(Some(8000)));
+    assert_eq!(timed.description.as_deref(), Some("Draft"));
+
+    assert!(serde_json::from_str::<RemoteToast>(r#"{"message": "hello"}"#).is_err());
+}
+
+#[wasm_bindgen_test]
+fn malformed_url_fails_to_connect() {
+    let result = remote::connect(
+        Toaster::default(),
+        RemoteOptions::builder().web_socket("not a url").build(),
+    );
+    assert!(matches!(result.err(), Some(Error::Connect(_))));
+}
@@ -0,0 +1,49 @@
+#![cfg(target_arch = "wasm32")]
+
+use gloo::utils::document;
+use seigi_testkit::wait;
+use seigi_toast::{RendererOptions, Toast, ToasterOptions};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::HtmlElement;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn render_hook_fills_toasts() {
+    seigi_toast::initialize_with_renderer(
+        ToasterOptions::default().without_timeout(),
+        RendererOptions {
+            render: Some(Box::new(|toast: &Toast| {
+                let element = document()
+                    .create_element("strong")
+                    .unwrap()
+                    .unchecked_into::<HtmlElement>();
+                element.set_class_name("custom");
+                element.set_text_content(Some(&toast.title));
+                element
+            })),
+            ..RendererOptions::default()
+        },
+    )
+    .unwrap();
+
+    let handle = seigi_toast::create_toast_unchecked(Toast::builder().title("rendered").build());
+    wait::frame().await;
+    let custom = || {
+        document()
+            .query_selector("[data-seigi-toast] > strong.custom")
+            .unwrap()
+            .unwrap()
+    };
+    assert_eq!(custom().text_content().as_deref(), Some("rendered"));
+    // The renderer still stacks the toast wrapping the markup
+    assert!(
+        custom()
+            .parent_element()
+            .unwrap()
+            .has_attribute("data-offset")
+    );
+
+    seigi_toast::dismiss_toast(handle);
+}
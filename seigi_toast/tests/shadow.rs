@@ -0,0 +1,61 @@
+#![cfg(target_arch = "wasm32")]
+
+use gloo::utils::{document, window};
+use seigi_testkit::{Fixture, wait};
+use seigi_toast::{RendererOptions, Toast, ToasterOptions};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<style>ol, li { position: static !important; }</style>
+<div id="host"></div>
+"#;
+
+#[wasm_bindgen_test]
+async fn toasts_render_inside_shadow_root_of_host() {
+    let fixture = Fixture::new(MARKUP);
+    let host = fixture.get("#host");
+    seigi_toast::initialize_with_renderer(
+        ToasterOptions::default().without_timeout(),
+        RendererOptions {
+            shadow_host: Some(host.clone()),
+            ..RendererOptions::default()
+        },
+    )
+    .unwrap();
+
+    let handle = seigi_toast::create_toast_unchecked(Toast::builder().title("shadowed").build());
+    wait::frame().await;
+
+    assert!(
+        document()
+            .query_selector("[data-seigi-toast]")
+            .unwrap()
+            .is_none()
+    );
+    let root = host.shadow_root().expect("a shadow root is attached");
+    assert_eq!(root.adopted_style_sheets().length(), 1);
+    let toaster = root
+        .query_selector("[data-seigi-toaster]")
+        .unwrap()
+        .unwrap();
+    let toast = root.query_selector("[data-seigi-toast]").unwrap().unwrap();
+    assert_eq!(toaster.get_attribute("part").as_deref(), Some("toaster"));
+    assert_eq!(toast.get_attribute("part").as_deref(), Some("toast"));
+    assert_eq!(toast.text_content().as_deref(), Some("shadowed"));
+
+    // Resets of the page don't reach the toaster
+    let position = |element| {
+        window()
+            .get_computed_style(&element)
+            .unwrap()
+            .unwrap()
+            .get_property_value("position")
+            .unwrap()
+    };
+    assert_eq!(position(toaster), "fixed");
+    assert_eq!(position(toast), "absolute");
+
+    seigi_toast::dismiss_toast(handle);
+}
@@ -0,0 +1,90 @@
+#![cfg(target_arch = "wasm32")]
+
+use gloo::utils::document;
+use seigi_testkit::{events, wait};
+use seigi_toast::{DismissReason, SwipeDirection, Toast, ToastHandle, ToasterOptions};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::HtmlElement;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn element(title: &str) -> HtmlElement {
+    let list = document().query_selector_all("[data-seigi-toast]").unwrap();
+    (0..list.length())
+        .filter_map(|v| list.item(v))
+        .find(|v| v.text_content().as_deref() == Some(title))
+        .expect("toast isn't rendered")
+        .unchecked_into()
+}
+
+fn swipe(element: &HtmlElement, to: (f64, f64)) {
+    events::dispatch(element, &events::pointer_event("pointerdown", 100.0, 100.0));
+    events::dispatch(element, &events::pointer_event("pointermove", to.0, to.1));
+    events::dispatch(element, &events::pointer_event("pointerup", to.0, to.1));
+}
+
+fn dismissed(handle: ToastHandle) -> Option<DismissReason> {
+    let toaster = seigi_toast::toaster().unwrap();
+    toaster.get(handle).and_then(|v| v.dismiss.clone())
+}
+
+#[wasm_bindgen_test]
+async fn swiped_toasts_are_dismissed_with_gesture() {
+    seigi_toast::initialize(ToasterOptions::default().without_timeout()).unwrap();
+
+    let dragged = seigi_toast::create_toast_unchecked(Toast::builder().title("dragged").build());
+    let flicked = seigi_toast::create_toast_unchecked(Toast::builder().title("flicked").build());
+    let kept = seigi_toast::create_toast_unchecked(Toast::builder().title("kept").build());
+    wait::frame().await;
+
+    swipe(&element("dragged"), (220.0, 110.0));
+    assert!(matches!(
+        dismissed(dragged),
+        Some(DismissReason::Swipe(gesture))
+            if gesture.direction == SwipeDirection::Right
+                && gesture.distance == 120.0
+                && !gesture.is_flick
+    ));
+
+    // Released at once, short of the distance but fast
+    swipe(&element("flicked"), (100.0, 80.0));
+    assert!(matches!(
+        dismissed(flicked),
+        Some(DismissReason::Swipe(gesture))
+            if gesture.direction == SwipeDirection::Up && gesture.is_flick
+    ));
+
+    // Dragged slowly and released short of the distance
+    let element = element("kept");
+    events::dispatch(
+        &element,
+        &events::pointer_event("pointerdown", 100.0, 100.0),
+    );
+    events::dispatch(&element, &events::pointer_event("pointermove", 70.0, 100.0));
+    assert!(element.has_attribute("data-swiping"));
+    assert_eq!(
+        element
+            .style()
+            .get_property_value("--seigi-toast-swipe-x")
+            .as_deref(),
+        Ok("-30px")
+    );
+    wait::sleep(500).await;
+    events::dispatch(&element, &events::pointer_event("pointerup", 70.0, 100.0));
+    assert!(dismissed(kept).is_none());
+    assert!(!element.has_attribute("data-swiping"));
+    assert_eq!(
+        element
+            .style()
+            .get_property_value("--seigi-toast-swipe-x")
+            .as_deref(),
+        Ok("")
+    );
+
+    // Pointers barely moving stay clicks
+    swipe(&element, (104.0, 100.0));
+    assert!(dismissed(kept).is_none());
+
+    seigi_toast::dismiss_toast(kept);
+}
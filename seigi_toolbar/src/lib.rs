@@ -0,0 +1,325 @@
+//! Toolbar and toggle group primitives sharing roving tabindex navigation
+
+pub mod toggle_group;
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::events::EventListener;
+use seigi_utils::direction;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, KeyboardEvent};
+
+/// The direction items are laid out in
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+
+impl Orientation {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Orientation::Horizontal => "horizontal",
+            Orientation::Vertical => "vertical",
+        }
+    }
+}
+
+pub(crate) fn query(parent: &Element, selector: &str) -> Vec<HtmlElement> {
+    let Ok(nodes) = parent.query_selector_all(selector) else {
+        return vec![];
+    };
+    (0..nodes.length())
+        .filter_map(|v| nodes.get(v))
+        .filter_map(|v| v.dyn_into::<HtmlElement>().ok())
+        .collect()
+}
+
+/// Returns the index of the item a roving key moves focus to
+///
+/// Horizontal arrows are reversed in right-to-left content.
+pub(crate) fn roving_index(
+    container: &Element,
+    len: usize,
+    current: usize,
+    key: &str,
+    orientation: Orientation,
+    looping: bool,
+) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    let (previous, next) = match orientation {
+        Orientation::Vertical => ("ArrowUp", "ArrowDown"),
+        Orientation::Horizontal => direction::resolve(container).arrow_keys(),
+    };
+
+    match key {
+        "Home" => Some(0),
+        "End" => Some(len - 1),
+        _ if key == previous => match current.checked_sub(1) {
+            Some(index) => Some(index),
+            None if looping => Some(len - 1),
+            None => Some(current),
+        },
+        _ if key == next => match current + 1 {
+            index if index < len => Some(index),
+            _ if looping => Some(0),
+            _ => Some(current),
+        },
+        _ => None,
+    }
+}
+
+/// Makes only the item at the index tabbable
+pub(crate) fn set_roving(items: &[HtmlElement], active: usize) {
+    for (index, item) in items.iter().enumerate() {
+        item.set_tab_index(if index == active { 0 } else { -1 });
+    }
+}
+
+/// Items of toolbars, unless they are disabled
+const ITEM_SELECTOR: &str = "button, input, select, textarea, a[href], [data-seigi-toolbar-item]";
+
+/// Options of [Toolbar]
+pub struct ToolbarOptions {
+    pub element: HtmlElement,
+    pub orientation: Orientation,
+    /// Whether focus moves from the last item to the first and vice versa
+    pub looping: bool,
+}
+
+impl ToolbarOptions {
+    pub fn builder() -> ToolbarOptionsBuilder {
+        ToolbarOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [ToolbarOptions]
+pub struct ToolbarOptionsBuilder {
+    element: Option<HtmlElement>,
+    orientation: Orientation,
+    looping: bool,
+}
+
+impl Default for ToolbarOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            element: None,
+            orientation: Orientation::default(),
+            looping: true,
+        }
+    }
+}
+
+impl ToolbarOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn element(mut self, element: HtmlElement) -> Self {
+        self.element = Some(element);
+        self
+    }
+
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Builds into [ToolbarOptions]
+    ///
+    /// # Panics
+    /// This method panics if element field is not set
+    pub fn build(self) -> ToolbarOptions {
+        ToolbarOptions {
+            element: self
+                .element
+                .expect("element must be set to build ToolbarOptions"),
+            orientation: self.orientation,
+            looping: self.looping,
+        }
+    }
+}
+
+struct Inner {
+    options: ToolbarOptions,
+    /// The item that is tabbable
+    active: Option<HtmlElement>,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn items(&self) -> Vec<HtmlElement> {
+        query(&self.options.element, ITEM_SELECTOR)
+            .into_iter()
+            .filter(|v| !v.has_attribute("disabled"))
+            .collect()
+    }
+
+    fn reflect(&mut self) {
+        let items = self.items();
+        let index = self
+            .active
+            .as_ref()
+            .and_then(|active| items.iter().position(|v| v == active))
+            .unwrap_or(0);
+        set_roving(&items, index);
+        self.active = items.get(index).cloned();
+    }
+}
+
+/// Returns whether arrow keys in the element move the caret rather than focus
+fn is_text_field(element: &Element) -> bool {
+    match element.tag_name().to_lowercase().as_str() {
+        "textarea" => true,
+        "input" => !matches!(
+            element.get_attribute("type").as_deref(),
+            Some("button" | "checkbox" | "radio" | "reset" | "submit" | "image" | "color")
+        ),
+        _ => element.has_attribute("contenteditable"),
+    }
+}
+
+/// An instance of toolbar
+///
+/// Only one item of the toolbar is in the tab sequence, and arrow keys along the orientation,
+/// Home and End move focus between items. Items are buttons, form controls, links and elements
+/// with `data-seigi-toolbar-item`, except disabled ones. In text fields, arrow keys along the
+/// text keep moving the caret.
+///
+/// Call [Toolbar::refresh] after items are added or removed.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **role** is set to `toolbar` and **aria-orientation** to the orientation in the element
+#[derive(Clone)]
+pub struct Toolbar(Rc<Mutex<Inner>>);
+
+impl Toolbar {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    /// Reapplies tabindex to items added or removed since
+    pub fn refresh(&self) {
+        self.0.lock().unwrap().reflect();
+    }
+
+    /// Focuses the item, making it the tabbable one
+    pub fn focus(&self, item: &HtmlElement) {
+        {
+            let mut inner = self.0.lock().unwrap();
+            inner.active = Some(item.clone());
+            inner.reflect();
+        }
+        let _ = item.focus();
+    }
+
+    fn handle_key_down(&self, event: &KeyboardEvent) {
+        let Some(target) = event.target().and_then(|v| v.dyn_into::<Element>().ok()) else {
+            return;
+        };
+        let (items, next) = {
+            let inner = self.0.lock().unwrap();
+            let items = inner.items();
+            let Some(current) = items.iter().position(|v| **v == target) else {
+                return;
+            };
+            let orientation = inner.options.orientation;
+            let key = event.key();
+            // Keys moving the caret are left to text fields
+            let is_caret_key = match orientation {
+                Orientation::Horizontal => {
+                    matches!(key.as_str(), "ArrowLeft" | "ArrowRight" | "Home" | "End")
+                }
+                Orientation::Vertical => matches!(key.as_str(), "Home" | "End"),
+            };
+            if is_caret_key && is_text_field(&target) {
+                return;
+            }
+            let next = roving_index(
+                &inner.options.element,
+                items.len(),
+                current,
+                &key,
+                orientation,
+                inner.options.looping,
+            );
+            (items, next)
+        };
+
+        let Some(item) = next.and_then(|v| items.get(v)) else {
+            return;
+        };
+        event.prevent_default();
+        self.focus(item);
+    }
+
+    fn handle_focus_in(&self, target: &Element) {
+        let mut inner = self.0.lock().unwrap();
+        let Some(item) = inner.items().into_iter().find(|v| **v == *target) else {
+            return;
+        };
+        inner.active = Some(item);
+        inner.reflect();
+    }
+}
+
+pub fn create_toolbar(options: ToolbarOptions) -> Toolbar {
+    let element = options.element.clone();
+    let _ = element.set_attribute("role", "toolbar");
+    let _ = element.set_attribute("aria-orientation", options.orientation.as_str());
+
+    let toolbar = Toolbar(Rc::new(Mutex::new(Inner {
+        options,
+        active: None,
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&toolbar.0);
+    let listeners = vec![
+        EventListener::new(&element, "keydown", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(toolbar), Some(event)) =
+                    (Toolbar::from_weak(&weak), event.dyn_ref::<KeyboardEvent>())
+                else {
+                    return;
+                };
+                toolbar.handle_key_down(event);
+            }
+        }),
+        EventListener::new(&element, "focusin", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(toolbar), Some(target)) = (
+                    Toolbar::from_weak(&weak),
+                    event.target().and_then(|v| v.dyn_into::<Element>().ok()),
+                ) else {
+                    return;
+                };
+                toolbar.handle_focus_in(&target);
+            }
+        }),
+    ];
+
+    {
+        let mut inner = toolbar.0.lock().unwrap();
+        inner._listeners = listeners;
+        inner.reflect();
+    }
+
+    toolbar
+}
@@ -0,0 +1,380 @@
+//! A group of toggle buttons with single or multiple pressed state
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, KeyboardEvent};
+
+use crate::{Orientation, query, roving_index, set_roving};
+
+/// How many items of a [ToggleGroup] can be pressed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ToggleMode {
+    #[default]
+    Single,
+    Multiple,
+}
+
+pub type PressedHook = Box<dyn Fn(&[String])>;
+
+/// Hooks to [ToggleGroup]
+#[derive(Default)]
+pub struct ToggleGroupHooks {
+    /// Called with values of pressed items when they change
+    pub change: Option<PressedHook>,
+}
+
+/// Options of [ToggleGroup]
+pub struct ToggleGroupOptions {
+    /// The element containing items with `data-seigi-toggle`
+    pub element: HtmlElement,
+    pub mode: ToggleMode,
+    /// Values of initially pressed items
+    pub pressed: Vec<String>,
+    pub orientation: Orientation,
+    /// Whether the group moves focus between its items with arrow keys. Disable this when the
+    /// group is inside a [crate::Toolbar], which moves focus across every item instead
+    pub roving: bool,
+    /// Whether focus moves from the last item to the first and vice versa
+    pub looping: bool,
+    pub hooks: ToggleGroupHooks,
+}
+
+impl ToggleGroupOptions {
+    pub fn builder() -> ToggleGroupOptionsBuilder {
+        ToggleGroupOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [ToggleGroupOptions]
+pub struct ToggleGroupOptionsBuilder {
+    element: Option<HtmlElement>,
+    mode: ToggleMode,
+    pressed: Vec<String>,
+    orientation: Orientation,
+    roving: bool,
+    looping: bool,
+    hooks: ToggleGroupHooks,
+}
+
+impl Default for ToggleGroupOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            element: None,
+            mode: ToggleMode::default(),
+            pressed: vec![],
+            orientation: Orientation::default(),
+            roving: true,
+            looping: true,
+            hooks: ToggleGroupHooks::default(),
+        }
+    }
+}
+
+impl ToggleGroupOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn element(mut self, element: HtmlElement) -> Self {
+        self.element = Some(element);
+        self
+    }
+
+    pub fn mode(mut self, mode: ToggleMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn pressed(mut self, pressed: Vec<String>) -> Self {
+        self.pressed = pressed;
+        self
+    }
+
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn roving(mut self, roving: bool) -> Self {
+        self.roving = roving;
+        self
+    }
+
+    pub fn looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: ToggleGroupHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [ToggleGroupOptions]
+    ///
+    /// # Panics
+    /// This method panics if element field is not set
+    pub fn build(self) -> ToggleGroupOptions {
+        ToggleGroupOptions {
+            element: self
+                .element
+                .expect("element must be set to build ToggleGroupOptions"),
+            mode: self.mode,
+            pressed: self.pressed,
+            orientation: self.orientation,
+            roving: self.roving,
+            looping: self.looping,
+            hooks: self.hooks,
+        }
+    }
+}
+
+struct Inner {
+    element: HtmlElement,
+    mode: ToggleMode,
+    orientation: Orientation,
+    roving: bool,
+    looping: bool,
+    hooks: Rc<ToggleGroupHooks>,
+    pressed: Vec<String>,
+    /// Value of the item that is tabbable
+    active: Option<String>,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn items(&self) -> Vec<(String, HtmlElement)> {
+        query(&self.element, "[data-seigi-toggle]")
+            .into_iter()
+            .filter_map(|v| Some((v.get_attribute("data-seigi-toggle")?, v)))
+            .collect()
+    }
+
+    fn enabled_items(&self) -> Vec<(String, HtmlElement)> {
+        self.items()
+            .into_iter()
+            .filter(|(_, v)| !v.has_attribute("disabled") && !v.has_attribute("data-disabled"))
+            .collect()
+    }
+
+    fn reflect(&mut self) {
+        for (value, item) in self.items() {
+            let is_pressed = self.pressed.contains(&value);
+            let _ = item.set_attribute("aria-pressed", if is_pressed { "true" } else { "false" });
+            let _ = item.set_attribute("data-state", if is_pressed { "on" } else { "off" });
+            if is_pressed {
+                let _ = item.set_attribute("data-pressed", "");
+            } else {
+                let _ = item.remove_attribute("data-pressed");
+            }
+        }
+
+        if self.roving {
+            // The tabbable item is the last focused one, then the first pressed one
+            let items = self.enabled_items();
+            let index = self
+                .active
+                .iter()
+                .chain(self.pressed.iter())
+                .find_map(|value| items.iter().position(|(v, _)| v == value))
+                .unwrap_or(0);
+            set_roving(
+                &items.iter().map(|(_, v)| v.clone()).collect::<Vec<_>>(),
+                index,
+            );
+        }
+    }
+}
+
+/// An instance of toggle group
+///
+/// Items are elements with `data-seigi-toggle` of their value. Clicking an item toggles it. In
+/// single mode pressing an item releases the others, and the pressed item can be released to
+/// leave none pressed.
+///
+/// Call [ToggleGroup::refresh] after items are added or removed.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **aria-pressed** is set in items to `true` or `false`
+///
+/// **data-state** is set in items to `on` or `off`, and **data-pressed** in pressed items
+///
+/// **role** is set to `group` in the element
+#[derive(Clone)]
+pub struct ToggleGroup(Rc<Mutex<Inner>>);
+
+impl ToggleGroup {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    /// Returns values of pressed items
+    pub fn pressed(&self) -> Vec<String> {
+        self.0.lock().unwrap().pressed.clone()
+    }
+
+    pub fn is_pressed(&self, value: &str) -> bool {
+        self.0.lock().unwrap().pressed.iter().any(|v| v == value)
+    }
+
+    /// Replaces pressed items. Only the first value is kept in single mode
+    pub fn set_pressed(&self, mut pressed: Vec<String>) {
+        let hooks = {
+            let mut inner = self.0.lock().unwrap();
+            if inner.mode == ToggleMode::Single {
+                pressed.truncate(1);
+            }
+            if inner.pressed == pressed {
+                return;
+            }
+            inner.pressed = pressed.clone();
+            inner.reflect();
+            inner.hooks.clone()
+        };
+
+        if let Some(hook) = &hooks.change {
+            hook(&pressed);
+        }
+    }
+
+    /// Toggles the item, releasing the others in single mode
+    pub fn toggle(&self, value: &str) {
+        let (mode, mut pressed) = {
+            let inner = self.0.lock().unwrap();
+            (inner.mode, inner.pressed.clone())
+        };
+
+        if let Some(index) = pressed.iter().position(|v| v == value) {
+            pressed.remove(index);
+        } else if mode == ToggleMode::Single {
+            pressed = vec![value.to_string()];
+        } else {
+            pressed.push(value.to_string());
+        }
+        self.set_pressed(pressed);
+    }
+
+    /// Reapplies attributes to items added or removed since
+    pub fn refresh(&self) {
+        self.0.lock().unwrap().reflect();
+    }
+
+    fn item_of(&self, target: &Element) -> Option<(String, HtmlElement)> {
+        let item = target.closest("[data-seigi-toggle]").ok().flatten()?;
+        self.0
+            .lock()
+            .unwrap()
+            .enabled_items()
+            .into_iter()
+            .find(|(_, v)| **v == item)
+    }
+
+    fn handle_key_down(&self, event: &KeyboardEvent) {
+        let Some(target) = event.target().and_then(|v| v.dyn_into::<Element>().ok()) else {
+            return;
+        };
+        let (items, next) = {
+            let inner = self.0.lock().unwrap();
+            if !inner.roving {
+                return;
+            }
+            let items = inner.enabled_items();
+            let Some(current) = items.iter().position(|(_, v)| **v == target) else {
+                return;
+            };
+            let next = roving_index(
+                &inner.element,
+                items.len(),
+                current,
+                &event.key(),
+                inner.orientation,
+                inner.looping,
+            );
+            (items, next)
+        };
+
+        let Some((value, item)) = next.and_then(|v| items.get(v)) else {
+            return;
+        };
+        event.prevent_default();
+        {
+            let mut inner = self.0.lock().unwrap();
+            inner.active = Some(value.clone());
+            inner.reflect();
+        }
+        let _ = item.focus();
+    }
+}
+
+pub fn create_toggle_group(options: ToggleGroupOptions) -> ToggleGroup {
+    let element = options.element.clone();
+    if !element.has_attribute("role") {
+        let _ = element.set_attribute("role", "group");
+    }
+
+    let mut pressed = options.pressed;
+    if options.mode == ToggleMode::Single {
+        pressed.truncate(1);
+    }
+
+    let group = ToggleGroup(Rc::new(Mutex::new(Inner {
+        element: options.element,
+        mode: options.mode,
+        orientation: options.orientation,
+        roving: options.roving,
+        looping: options.looping,
+        hooks: Rc::new(options.hooks),
+        pressed,
+        active: None,
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&group.0);
+    let listeners = vec![
+        EventListener::new(&element, "click", {
+            let weak = weak.clone();
+            move |event| {
+                let Some(group) = ToggleGroup::from_weak(&weak) else {
+                    return;
+                };
+                let Some((value, _)) = event
+                    .target()
+                    .and_then(|v| v.dyn_into::<Element>().ok())
+                    .and_then(|v| group.item_of(&v))
+                else {
+                    return;
+                };
+                group.0.lock().unwrap().active = Some(value.clone());
+                group.toggle(&value);
+            }
+        }),
+        EventListener::new(&element, "keydown", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(group), Some(event)) = (
+                    ToggleGroup::from_weak(&weak),
+                    event.dyn_ref::<KeyboardEvent>(),
+                ) else {
+                    return;
+                };
+                group.handle_key_down(event);
+            }
+        }),
+    ];
+
+    {
+        let mut inner = group.0.lock().unwrap();
+        inner._listeners = listeners;
+        inner.reflect();
+    }
+
+    group
+}
@@ -0,0 +1,143 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use seigi_testkit::{
+    Fixture, assert_focused,
+    events::{self, Modifiers},
+};
+use seigi_toolbar::toggle_group::{
+    self, ToggleGroup, ToggleGroupHooks, ToggleGroupOptions, ToggleGroupOptionsBuilder, ToggleMode,
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<div id="group">
+    <button id="left" data-seigi-toggle="left">Left</button>
+    <button id="center" data-seigi-toggle="center">Center</button>
+    <button id="right" data-seigi-toggle="right" data-disabled>Right</button>
+    <button id="justify" data-seigi-toggle="justify">Justify</button>
+</div>
+"#;
+
+fn options(fixture: &Fixture) -> ToggleGroupOptionsBuilder {
+    ToggleGroupOptions::builder().element(fixture.get("#group"))
+}
+
+fn group(options: ToggleGroupOptionsBuilder) -> (ToggleGroup, Rc<RefCell<Vec<Vec<String>>>>) {
+    let changes = Rc::new(RefCell::new(vec![]));
+    let group = toggle_group::create_toggle_group(
+        options
+            .hooks(ToggleGroupHooks {
+                change: Some(Box::new({
+                    let changes = changes.clone();
+                    move |pressed| changes.borrow_mut().push(pressed.to_vec())
+                })),
+            })
+            .build(),
+    );
+    (group, changes)
+}
+
+#[wasm_bindgen_test]
+fn single_mode_releases_others() {
+    let fixture = Fixture::new(MARKUP);
+    let (group, changes) = group(options(&fixture).pressed(vec!["center".to_string()]));
+    let center = fixture.get("#center");
+
+    assert_eq!(
+        fixture.get("#group").get_attribute("role").as_deref(),
+        Some("group")
+    );
+    assert_eq!(
+        center.get_attribute("aria-pressed").as_deref(),
+        Some("true")
+    );
+    assert_eq!(center.get_attribute("data-state").as_deref(), Some("on"));
+    assert!(center.has_attribute("data-pressed"));
+
+    events::click(&fixture.get("#left"));
+    assert_eq!(group.pressed(), ["left"]);
+    assert_eq!(
+        center.get_attribute("aria-pressed").as_deref(),
+        Some("false")
+    );
+    assert_eq!(center.get_attribute("data-state").as_deref(), Some("off"));
+    assert!(!center.has_attribute("data-pressed"));
+
+    // The pressed item can be released
+    events::click(&fixture.get("#left"));
+    assert!(group.pressed().is_empty());
+    assert_eq!(changes.borrow().len(), 2);
+
+    group.set_pressed(vec!["left".to_string(), "justify".to_string()]);
+    assert_eq!(group.pressed(), ["left"]);
+}
+
+#[wasm_bindgen_test]
+fn multiple_mode_toggles_independently() {
+    let fixture = Fixture::new(MARKUP);
+    let (group, changes) = group(options(&fixture).mode(ToggleMode::Multiple));
+
+    events::click(&fixture.get("#left"));
+    events::click(&fixture.get("#justify"));
+    assert_eq!(group.pressed(), ["left", "justify"]);
+    assert!(group.is_pressed("justify"));
+
+    group.toggle("left");
+    assert_eq!(group.pressed(), ["justify"]);
+
+    // Unchanged values don't call the hook
+    group.set_pressed(vec!["justify".to_string()]);
+    assert_eq!(changes.borrow().len(), 3);
+}
+
+#[wasm_bindgen_test]
+fn disabled_items_ignore_clicks() {
+    let fixture = Fixture::new(MARKUP);
+    let (group, changes) = group(options(&fixture));
+
+    events::click(&fixture.get("#right"));
+    assert!(group.pressed().is_empty());
+    assert!(changes.borrow().is_empty());
+}
+
+#[wasm_bindgen_test]
+fn pressed_item_is_tabbable_and_arrows_rove() {
+    let fixture = Fixture::new(MARKUP);
+    let (group, _) = group(options(&fixture).pressed(vec!["center".to_string()]));
+    let center = fixture.get("#center");
+    assert_eq!(center.tab_index(), 0);
+    assert_eq!(fixture.get("#left").tab_index(), -1);
+
+    center.focus().unwrap();
+    assert!(!events::key_down(
+        &center,
+        "ArrowRight",
+        Modifiers::default()
+    ));
+    // Disabled items are skipped
+    let justify = fixture.get("#justify");
+    assert_focused(&justify);
+    assert_eq!(justify.tab_index(), 0);
+    assert_eq!(center.tab_index(), -1);
+
+    events::key_down(&justify, "ArrowRight", Modifiers::default());
+    assert_focused(&fixture.get("#left"));
+    // Moving focus doesn't press items
+    assert_eq!(group.pressed(), ["center"]);
+}
+
+#[wasm_bindgen_test]
+fn groups_inside_toolbars_leave_roving_to_them() {
+    let fixture = Fixture::new(MARKUP);
+    let (_group, _) = group(options(&fixture).roving(false));
+    let left = fixture.get("#left");
+    left.focus().unwrap();
+
+    assert!(events::key_down(&left, "ArrowRight", Modifiers::default()));
+    assert_focused(&left);
+    assert!(!left.has_attribute("tabindex"));
+}
@@ -0,0 +1,148 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_testkit::{
+    Fixture, assert_focused,
+    events::{self, Modifiers},
+};
+use seigi_toolbar::{Orientation, Toolbar, ToolbarOptions};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r##"
+<div id="toolbar">
+    <button id="bold">B</button>
+    <button id="italic" disabled>I</button>
+    <input id="field">
+    <a id="link" href="#link">Link</a>
+    <span id="custom" data-seigi-toolbar-item>Custom</span>
+</div>
+"##;
+
+fn toolbar(fixture: &Fixture, orientation: Orientation, looping: bool) -> Toolbar {
+    seigi_toolbar::create_toolbar(
+        ToolbarOptions::builder()
+            .element(fixture.get("#toolbar"))
+            .orientation(orientation)
+            .looping(looping)
+            .build(),
+    )
+}
+
+/// Presses the key on the focused element
+fn press(fixture: &Fixture, key: &str) -> bool {
+    let target = fixture.active_element().expect("an item must be focused");
+    events::key_down(&target, key, Modifiers::default())
+}
+
+#[wasm_bindgen_test]
+fn only_first_item_is_tabbable() {
+    let fixture = Fixture::new(MARKUP);
+    let _toolbar = toolbar(&fixture, Orientation::Horizontal, true);
+    let element = fixture.get("#toolbar");
+
+    assert_eq!(element.get_attribute("role").as_deref(), Some("toolbar"));
+    assert_eq!(
+        element.get_attribute("aria-orientation").as_deref(),
+        Some("horizontal")
+    );
+    assert_eq!(fixture.get("#bold").tab_index(), 0);
+    for id in ["#field", "#link", "#custom"] {
+        assert_eq!(fixture.get(id).tab_index(), -1, "{id} must not be tabbable");
+    }
+}
+
+#[wasm_bindgen_test]
+fn arrows_skip_disabled_items_and_loop() {
+    let fixture = Fixture::new(MARKUP);
+    let _toolbar = toolbar(&fixture, Orientation::Horizontal, true);
+    fixture.get("#link").focus().unwrap();
+
+    // Focusing an item makes it the tabbable one
+    assert_eq!(fixture.get("#link").tab_index(), 0);
+    assert_eq!(fixture.get("#bold").tab_index(), -1);
+
+    assert!(!press(&fixture, "ArrowRight"));
+    assert_focused(&fixture.get("#custom"));
+    press(&fixture, "ArrowRight");
+    assert_focused(&fixture.get("#bold"));
+    press(&fixture, "ArrowLeft");
+    assert_focused(&fixture.get("#custom"));
+    press(&fixture, "Home");
+    assert_focused(&fixture.get("#bold"));
+    // Disabled items are skipped
+    press(&fixture, "ArrowRight");
+    assert_focused(&fixture.get("#field"));
+    assert_eq!(fixture.get("#field").tab_index(), 0);
+}
+
+#[wasm_bindgen_test]
+fn text_fields_keep_caret_keys() {
+    let fixture = Fixture::new(MARKUP);
+    let _toolbar = toolbar(&fixture, Orientation::Horizontal, true);
+    let field = fixture.get("#field");
+    field.focus().unwrap();
+
+    for key in ["ArrowLeft", "ArrowRight", "Home", "End"] {
+        assert!(press(&fixture, key), "{key} must be left to the field");
+        assert_focused(&field);
+    }
+}
+
+#[wasm_bindgen_test]
+fn ends_stop_without_looping() {
+    let fixture = Fixture::new(MARKUP);
+    let _toolbar = toolbar(&fixture, Orientation::Horizontal, false);
+    fixture.get("#bold").focus().unwrap();
+
+    press(&fixture, "ArrowLeft");
+    assert_focused(&fixture.get("#bold"));
+    press(&fixture, "End");
+    assert_focused(&fixture.get("#custom"));
+    press(&fixture, "ArrowRight");
+    assert_focused(&fixture.get("#custom"));
+}
+
+#[wasm_bindgen_test]
+fn vertical_toolbar_uses_up_and_down() {
+    let fixture = Fixture::new(MARKUP);
+    let _toolbar = toolbar(&fixture, Orientation::Vertical, true);
+    fixture.get("#bold").focus().unwrap();
+
+    assert!(press(&fixture, "ArrowRight"));
+    assert_focused(&fixture.get("#bold"));
+    press(&fixture, "ArrowDown");
+    assert_focused(&fixture.get("#field"));
+    // Vertical arrows don't move the caret of single line fields
+    press(&fixture, "ArrowDown");
+    assert_focused(&fixture.get("#link"));
+    press(&fixture, "ArrowUp");
+    assert_focused(&fixture.get("#field"));
+}
+
+#[wasm_bindgen_test]
+fn right_to_left_reverses_horizontal_arrows() {
+    let fixture = Fixture::new(MARKUP);
+    fixture.get("#toolbar").set_attribute("dir", "rtl").unwrap();
+    let _toolbar = toolbar(&fixture, Orientation::Horizontal, true);
+    fixture.get("#link").focus().unwrap();
+
+    press(&fixture, "ArrowLeft");
+    assert_focused(&fixture.get("#custom"));
+    press(&fixture, "ArrowRight");
+    assert_focused(&fixture.get("#link"));
+}
+
+#[wasm_bindgen_test]
+fn refresh_moves_tab_stop_off_removed_items() {
+    let fixture = Fixture::new(MARKUP);
+    let toolbar = toolbar(&fixture, Orientation::Horizontal, true);
+
+    fixture.get("#bold").remove();
+    toolbar.refresh();
+    assert_eq!(fixture.get("#field").tab_index(), 0);
+
+    toolbar.focus(&fixture.get("#custom"));
+    assert_focused(&fixture.get("#custom"));
+    assert_eq!(fixture.get("#field").tab_index(), -1);
+}
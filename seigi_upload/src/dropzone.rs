@@ -0,0 +1,588 @@
+//! File dropzone accepting dropped, pasted and picked files
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::events::{EventListener, EventListenerOptions};
+use js_sys::{Array, Promise};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    ClipboardEvent, DataTransfer, DragEvent, File, FileList, FileSystemDirectoryEntry,
+    FileSystemEntry, FileSystemFileEntry, HtmlElement, HtmlInputElement, KeyboardEvent,
+};
+
+/// Why a file was rejected by a [Dropzone]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectionReason {
+    /// The file matches none of the accepted types
+    Type,
+    /// The file is larger than max_size
+    Size,
+    /// The file exceeds max_files
+    Count,
+}
+
+/// A file rejected by a [Dropzone]
+#[derive(Debug, Clone)]
+pub struct Rejection {
+    pub file: File,
+    pub reason: RejectionReason,
+}
+
+pub type FilesHook = Box<dyn Fn(&[File])>;
+pub type RejectionsHook = Box<dyn Fn(&[Rejection])>;
+
+/// Hooks to [Dropzone]
+#[derive(Default)]
+pub struct DropzoneHooks {
+    /// Called with files that passed validation
+    pub accept: Option<FilesHook>,
+    /// Called with files that failed validation, if any
+    pub reject: Option<RejectionsHook>,
+}
+
+/// Options of [Dropzone]
+pub struct DropzoneOptions {
+    /// The element files are dropped on
+    pub target: HtmlElement,
+    /// A file input opened when the target is clicked or activated with Enter/Space. Files
+    /// picked with it are validated the same way as dropped files
+    pub input: Option<HtmlInputElement>,
+    /// Accepted types in the format of the `accept` attribute, such as `image/*`,
+    /// `application/pdf` or `.png`. Every type is accepted if empty
+    pub accept: Vec<String>,
+    /// The largest accepted file size in bytes
+    pub max_size: Option<f64>,
+    /// The largest number of files accepted at once
+    pub max_files: Option<usize>,
+    /// Whether files pasted while the target is focused are accepted
+    pub paste: bool,
+    /// Whether dropped directories are traversed for files
+    pub directories: bool,
+    pub hooks: DropzoneHooks,
+}
+
+impl DropzoneOptions {
+    pub fn builder() -> DropzoneOptionsBuilder {
+        DropzoneOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [DropzoneOptions]
+pub struct DropzoneOptionsBuilder {
+    target: Option<HtmlElement>,
+    input: Option<HtmlInputElement>,
+    accept: Vec<String>,
+    max_size: Option<f64>,
+    max_files: Option<usize>,
+    paste: bool,
+    directories: bool,
+    hooks: DropzoneHooks,
+}
+
+impl Default for DropzoneOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            target: None,
+            input: None,
+            accept: vec![],
+            max_size: None,
+            max_files: None,
+            paste: true,
+            directories: true,
+            hooks: DropzoneHooks::default(),
+        }
+    }
+}
+
+impl DropzoneOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn target(mut self, target: HtmlElement) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    pub fn input(mut self, input: HtmlInputElement) -> Self {
+        self.input = Some(input);
+        self
+    }
+
+    /// Adds an accepted type
+    pub fn accept(mut self, accept: impl Into<String>) -> Self {
+        self.accept.push(accept.into());
+        self
+    }
+
+    pub fn max_size(mut self, max_size: f64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    pub fn max_files(mut self, max_files: usize) -> Self {
+        self.max_files = Some(max_files);
+        self
+    }
+
+    pub fn paste(mut self, paste: bool) -> Self {
+        self.paste = paste;
+        self
+    }
+
+    pub fn directories(mut self, directories: bool) -> Self {
+        self.directories = directories;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: DropzoneHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [DropzoneOptions]
+    ///
+    /// # Panics
+    /// This method panics if target field is not set
+    pub fn build(self) -> DropzoneOptions {
+        DropzoneOptions {
+            target: self
+                .target
+                .expect("target must be set to build DropzoneOptions"),
+            input: self.input,
+            accept: self.accept,
+            max_size: self.max_size,
+            max_files: self.max_files,
+            paste: self.paste,
+            directories: self.directories,
+            hooks: self.hooks,
+        }
+    }
+}
+
+/// Returns whether the file name or MIME type matches one of the accepted types
+fn is_type_accepted(accept: &[String], name: &str, mime: &str) -> bool {
+    if accept.is_empty() {
+        return true;
+    }
+
+    let name = name.to_ascii_lowercase();
+    let mime = mime.to_ascii_lowercase();
+    accept.iter().any(|v| {
+        let v = v.trim().to_ascii_lowercase();
+        if v.starts_with('.') {
+            name.ends_with(v.as_str())
+        } else if let Some(prefix) = v.strip_suffix("/*") {
+            mime.split('/').next() == Some(prefix)
+        } else {
+            mime == v
+        }
+    })
+}
+
+fn file_list(list: Option<FileList>) -> Vec<File> {
+    let Some(list) = list else {
+        return vec![];
+    };
+    (0..list.length()).filter_map(|v| list.get(v)).collect()
+}
+
+async fn read_file(entry: &FileSystemFileEntry) -> Option<File> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        entry.file_with_callback_and_callback(&resolve, &reject);
+    });
+    JsFuture::from(promise).await.ok()?.dyn_into::<File>().ok()
+}
+
+async fn read_entries(directory: &FileSystemDirectoryEntry) -> Vec<FileSystemEntry> {
+    let reader = directory.create_reader();
+    let mut entries = vec![];
+    // readEntries yields entries in batches, and an empty batch marks the end
+    loop {
+        let promise = Promise::new(&mut |resolve, reject| {
+            if let Err(error) = reader.read_entries_with_callback_and_callback(&resolve, &reject) {
+                let _ = reject.call1(&JsValue::NULL, &error);
+            }
+        });
+        let Ok(batch) = JsFuture::from(promise).await else {
+            break;
+        };
+        let batch = Array::from(&batch);
+        if batch.length() == 0 {
+            break;
+        }
+        entries.extend(
+            batch
+                .iter()
+                .filter_map(|v| v.dyn_into::<FileSystemEntry>().ok()),
+        );
+    }
+    entries
+}
+
+/// Collects files of the entry, traversing directories depth-first
+async fn collect_entry(entry: FileSystemEntry, files: &mut Vec<File>) {
+    let mut stack = vec![entry];
+    while let Some(entry) = stack.pop() {
+        if entry.is_file() {
+            if let Some(file) = read_file(entry.unchecked_ref()).await {
+                files.push(file);
+            }
+        } else if entry.is_directory() {
+            let mut entries = read_entries(entry.unchecked_ref()).await;
+            entries.reverse();
+            stack.extend(entries);
+        }
+    }
+}
+
+struct State {
+    /// Depth of nested dragenter events, as they fire for every child the pointer crosses
+    depth: u32,
+    disabled: bool,
+    _listeners: Vec<EventListener>,
+}
+
+/// An instance of dropzone
+///
+/// The dropzone validates files dropped on the target, pasted while it is focused or picked with
+/// the input, and reports them to the accept/reject hooks.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-seigi-dropzone** is set in the target
+///
+/// **data-dragging** is set in the target while files are dragged over it
+///
+/// **data-drag-reject** is set in the target while dragged files include a type that is not
+/// accepted
+///
+/// **data-disabled** is set in the target while the dropzone is disabled
+#[derive(Clone)]
+pub struct Dropzone {
+    options: Rc<DropzoneOptions>,
+    state: Rc<Mutex<State>>,
+}
+
+impl Dropzone {
+    fn from_weak(options: &Weak<DropzoneOptions>, state: &Weak<Mutex<State>>) -> Option<Self> {
+        Some(Self {
+            options: options.upgrade()?,
+            state: state.upgrade()?,
+        })
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.state.lock().unwrap().disabled
+    }
+
+    pub fn set_disabled(&self, disabled: bool) {
+        self.state.lock().unwrap().disabled = disabled;
+        let target = &self.options.target;
+        if disabled {
+            let _ = target.set_attribute("data-disabled", "");
+            let _ = target.set_attribute("aria-disabled", "true");
+            self.reset_drag();
+        } else {
+            let _ = target.remove_attribute("data-disabled");
+            let _ = target.remove_attribute("aria-disabled");
+        }
+        if let Some(input) = &self.options.input {
+            input.set_disabled(disabled);
+        }
+    }
+
+    /// Opens the file picker of the input, if any
+    pub fn open(&self) {
+        if self.is_disabled() {
+            return;
+        }
+        if let Some(input) = &self.options.input {
+            input.click();
+        }
+    }
+
+    /// Validates the files and reports them to the hooks
+    pub fn handle_files(&self, files: Vec<File>) {
+        if self.is_disabled() || files.is_empty() {
+            return;
+        }
+
+        let options = &self.options;
+        let mut accepted = vec![];
+        let mut rejected = vec![];
+        for file in files {
+            let reason = if !is_type_accepted(&options.accept, &file.name(), &file.type_()) {
+                Some(RejectionReason::Type)
+            } else if options.max_size.is_some_and(|v| file.size() > v) {
+                Some(RejectionReason::Size)
+            } else if options.max_files.is_some_and(|v| accepted.len() >= v) {
+                Some(RejectionReason::Count)
+            } else {
+                None
+            };
+
+            match reason {
+                Some(reason) => rejected.push(Rejection { file, reason }),
+                None => accepted.push(file),
+            }
+        }
+
+        if !accepted.is_empty()
+            && let Some(hook) = &options.hooks.accept
+        {
+            hook(&accepted);
+        }
+        if !rejected.is_empty()
+            && let Some(hook) = &options.hooks.reject
+        {
+            hook(&rejected);
+        }
+    }
+
+    fn reset_drag(&self) {
+        self.state.lock().unwrap().depth = 0;
+        let _ = self.options.target.remove_attribute("data-dragging");
+        let _ = self.options.target.remove_attribute("data-drag-reject");
+    }
+
+    fn handle_drag_enter(&self, transfer: &DataTransfer) {
+        let depth = {
+            let mut state = self.state.lock().unwrap();
+            state.depth += 1;
+            state.depth
+        };
+        if depth > 1 {
+            return;
+        }
+
+        // Only types are readable while dragging, so lists with extensions can't be judged
+        let accept = &self.options.accept;
+        let items = transfer.items();
+        let is_rejected = !accept.iter().any(|v| v.starts_with('.'))
+            && (0..items.length())
+                .filter_map(|v| items.get(v))
+                .filter(|v| v.kind() == "file" && !v.type_().is_empty())
+                .any(|v| !is_type_accepted(accept, "", &v.type_()));
+
+        let target = &self.options.target;
+        let _ = target.set_attribute("data-dragging", "");
+        if is_rejected {
+            let _ = target.set_attribute("data-drag-reject", "");
+        }
+    }
+
+    fn handle_drag_leave(&self) {
+        let depth = {
+            let mut state = self.state.lock().unwrap();
+            state.depth = state.depth.saturating_sub(1);
+            state.depth
+        };
+        if depth == 0 {
+            self.reset_drag();
+        }
+    }
+
+    fn handle_drop(&self, transfer: DataTransfer) {
+        self.reset_drag();
+        if !self.options.directories {
+            self.handle_files(file_list(transfer.files()));
+            return;
+        }
+
+        // Entries must be taken synchronously, as the data transfer is emptied after the event
+        let items = transfer.items();
+        let entries = (0..items.length())
+            .filter_map(|v| items.get(v))
+            .filter(|v| v.kind() == "file")
+            .filter_map(|v| v.webkit_get_as_entry().ok().flatten())
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            self.handle_files(file_list(transfer.files()));
+            return;
+        }
+
+        let dropzone = self.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let mut files = vec![];
+            for entry in entries {
+                collect_entry(entry, &mut files).await;
+            }
+            dropzone.handle_files(files);
+        });
+    }
+}
+
+pub fn create_dropzone(options: DropzoneOptions) -> Dropzone {
+    let options = Rc::new(options);
+    let state = Rc::new(Mutex::new(State {
+        depth: 0,
+        disabled: false,
+        _listeners: vec![],
+    }));
+    let target = options.target.clone();
+    let _ = target.set_attribute("data-seigi-dropzone", "");
+
+    let weak_options = Rc::downgrade(&options);
+    let weak_state = Rc::downgrade(&state);
+    macro_rules! callback {
+        (|$dropzone:ident, $event:ident| $body:block) => {{
+            let weak_options = weak_options.clone();
+            let weak_state = weak_state.clone();
+            move |$event: &web_sys::Event| {
+                let Some($dropzone) = Dropzone::from_weak(&weak_options, &weak_state) else {
+                    return;
+                };
+                $body
+            }
+        }};
+    }
+
+    let active = EventListenerOptions::enable_prevent_default();
+    let mut listeners = vec![
+        EventListener::new_with_options(
+            &target,
+            "dragenter",
+            active,
+            callback!(|dropzone, event| {
+                let Some(transfer) = event.dyn_ref::<DragEvent>().and_then(|v| v.data_transfer())
+                else {
+                    return;
+                };
+                if dropzone.is_disabled() || !transfer.types().includes(&"Files".into(), 0) {
+                    return;
+                }
+                event.prevent_default();
+                dropzone.handle_drag_enter(&transfer);
+            }),
+        ),
+        EventListener::new_with_options(
+            &target,
+            "dragover",
+            active,
+            callback!(|dropzone, event| {
+                let Some(transfer) = event.dyn_ref::<DragEvent>().and_then(|v| v.data_transfer())
+                else {
+                    return;
+                };
+                if dropzone.is_disabled() || !transfer.types().includes(&"Files".into(), 0) {
+                    return;
+                }
+                // Cancelling dragover is what makes the target a drop target
+                event.prevent_default();
+                transfer.set_drop_effect("copy");
+            }),
+        ),
+        EventListener::new(
+            &target,
+            "dragleave",
+            callback!(|dropzone, _event| {
+                dropzone.handle_drag_leave();
+            }),
+        ),
+        EventListener::new_with_options(
+            &target,
+            "drop",
+            active,
+            callback!(|dropzone, event| {
+                let Some(transfer) = event.dyn_ref::<DragEvent>().and_then(|v| v.data_transfer())
+                else {
+                    return;
+                };
+                if dropzone.is_disabled() {
+                    return;
+                }
+                event.prevent_default();
+                dropzone.handle_drop(transfer);
+            }),
+        ),
+    ];
+
+    if options.paste {
+        listeners.push(EventListener::new(
+            &target,
+            "paste",
+            callback!(|dropzone, event| {
+                let Some(transfer) = event
+                    .dyn_ref::<ClipboardEvent>()
+                    .and_then(|v| v.clipboard_data())
+                else {
+                    return;
+                };
+                let files = file_list(transfer.files());
+                if files.is_empty() {
+                    return;
+                }
+                event.prevent_default();
+                dropzone.handle_files(files);
+            }),
+        ));
+    }
+
+    if let Some(input) = &options.input {
+        if !options.accept.is_empty() {
+            input.set_accept(options.accept.join(",").as_str());
+        }
+        input.set_multiple(options.max_files != Some(1));
+
+        listeners.push(EventListener::new(
+            input,
+            "change",
+            callback!(|dropzone, _event| {
+                let Some(input) = &dropzone.options.input else {
+                    return;
+                };
+                let files = file_list(input.files());
+                // Clearing the value lets the same file be picked again
+                input.set_value("");
+                dropzone.handle_files(files);
+            }),
+        ));
+
+        // Clicks on the input itself already open the picker
+        if !JsValue::from(input).eq(&JsValue::from(&target)) {
+            listeners.push(EventListener::new(
+                &target,
+                "click",
+                callback!(|dropzone, event| {
+                    let Some(input) = &dropzone.options.input else {
+                        return;
+                    };
+                    let is_input = event
+                        .target()
+                        .is_some_and(|v| JsValue::from(v).eq(&JsValue::from(input)));
+                    if !is_input {
+                        dropzone.open();
+                    }
+                }),
+            ));
+            listeners.push(EventListener::new(
+                &target,
+                "keydown",
+                callback!(|dropzone, event| {
+                    let Some(event) = event.dyn_ref::<KeyboardEvent>() else {
+                        return;
+                    };
+                    if matches!(event.key().as_str(), "Enter" | " ") {
+                        event.prevent_default();
+                        dropzone.open();
+                    }
+                }),
+            ));
+        }
+    }
+
+    if (options.paste || options.input.is_some()) && !target.has_attribute("tabindex") {
+        target.set_tab_index(0);
+    }
+
+    state.lock().unwrap()._listeners = listeners;
+
+    Dropzone { options, state }
+}
@@ -0,0 +1,7 @@
+//! Headless file dropzone and upload queue
+//!
+//! [Dropzone](dropzone::Dropzone) validates files and hands them to hooks, which can feed them to
+//! an [UploadQueue](queue::UploadQueue) or handle them in any other way.
+
+pub mod dropzone;
+pub mod queue;
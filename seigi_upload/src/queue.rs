@@ -0,0 +1,598 @@
+//! Upload queue sending files with XMLHttpRequest
+
+use std::{
+    rc::{Rc, Weak},
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use gloo::events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::{File, FormData, ProgressEvent, XmlHttpRequest};
+
+/// Status of an [Upload]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UploadStatus {
+    /// Waiting for a free slot in the queue
+    Pending,
+    Uploading,
+    /// The server responded with a 2xx status
+    Complete,
+    /// The request failed, with the reason
+    Failed(String),
+    Cancelled,
+}
+
+impl UploadStatus {
+    /// Returns whether the upload will not change anymore without a retry
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            UploadStatus::Complete | UploadStatus::Failed(_) | UploadStatus::Cancelled
+        )
+    }
+}
+
+/// A file in an [UploadQueue]
+#[derive(Debug, Clone)]
+pub struct Upload {
+    pub id: u64,
+    pub file: File,
+    pub status: UploadStatus,
+    /// Bytes sent so far
+    pub loaded: f64,
+    /// Bytes to send. This is the file size until the browser reports the request size
+    pub total: f64,
+    /// The HTTP status of a finished request
+    pub response_status: Option<u16>,
+    /// The response body of a finished request
+    pub response: Option<String>,
+}
+
+impl Upload {
+    /// Returns the progress in 0-1
+    pub fn progress(&self) -> f64 {
+        if self.status == UploadStatus::Complete {
+            1.0
+        } else if self.total > 0.0 {
+            (self.loaded / self.total).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// An event emitted by an [UploadQueue] with the id of the upload
+#[derive(Debug, Clone, PartialEq)]
+pub enum UploadEvent {
+    Add(u64),
+    Start(u64),
+    Progress { id: u64, loaded: f64, total: f64 },
+    Complete(u64),
+    Fail(u64),
+    Cancel(u64),
+    Remove(u64),
+}
+
+/// Options of [UploadQueue]
+pub struct UploadQueueOptions {
+    /// The URL files are sent to
+    pub url: String,
+    /// The HTTP method. Defaults to `POST`
+    pub method: String,
+    /// The form field name of the file. Defaults to `file`
+    pub field: String,
+    /// Additional form fields sent with every file
+    pub fields: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    /// Whether cookies are sent with cross-origin requests
+    pub with_credentials: bool,
+    /// The largest number of concurrent uploads. Defaults to 2
+    pub concurrency: usize,
+}
+
+impl UploadQueueOptions {
+    pub fn builder() -> UploadQueueOptionsBuilder {
+        UploadQueueOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [UploadQueueOptions]
+pub struct UploadQueueOptionsBuilder {
+    url: Option<String>,
+    method: String,
+    field: String,
+    fields: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    with_credentials: bool,
+    concurrency: usize,
+}
+
+impl Default for UploadQueueOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            url: None,
+            method: "POST".to_string(),
+            field: "file".to_string(),
+            fields: vec![],
+            headers: vec![],
+            with_credentials: false,
+            concurrency: 2,
+        }
+    }
+}
+
+impl UploadQueueOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn method(mut self, method: impl Into<String>) -> Self {
+        self.method = method.into();
+        self
+    }
+
+    pub fn field(mut self, field: impl Into<String>) -> Self {
+        self.field = field.into();
+        self
+    }
+
+    /// Adds a form field sent with every file
+    pub fn add_field(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn add_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_credentials(mut self, with_credentials: bool) -> Self {
+        self.with_credentials = with_credentials;
+        self
+    }
+
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Builds into [UploadQueueOptions]
+    ///
+    /// # Panics
+    /// This method panics if url field is not set
+    pub fn build(self) -> UploadQueueOptions {
+        UploadQueueOptions {
+            url: self
+                .url
+                .expect("url must be set to build UploadQueueOptions"),
+            method: self.method,
+            field: self.field,
+            fields: self.fields,
+            headers: self.headers,
+            with_credentials: self.with_credentials,
+            concurrency: self.concurrency.max(1),
+        }
+    }
+}
+
+struct Request {
+    xhr: XmlHttpRequest,
+    _listeners: Vec<EventListener>,
+}
+
+impl Request {
+    /// Aborts the request without dispatching its events to the queue
+    fn abort(self) {
+        let Request { xhr, _listeners } = self;
+        drop(_listeners);
+        let _ = xhr.abort();
+    }
+}
+
+struct Entry {
+    upload: Upload,
+    request: Option<Request>,
+}
+
+struct Subscriber {
+    callback: Box<dyn Fn(&UploadEvent)>,
+    handle: u64,
+}
+
+struct Inner {
+    options: UploadQueueOptions,
+    entries: Vec<Entry>,
+    subscribers: Vec<Rc<Subscriber>>,
+}
+
+/// A queue uploading files with limited concurrency
+///
+/// Added files are sent as multipart form data in the order they were added. Subscribers are
+/// notified of every change with [UploadEvent], and the current state of an upload can be read
+/// with [UploadQueue::get].
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+#[derive(Clone)]
+pub struct UploadQueue(Rc<Mutex<Inner>>);
+
+impl UploadQueue {
+    pub fn new(options: UploadQueueOptions) -> Self {
+        Self(Rc::new(Mutex::new(Inner {
+            options,
+            entries: vec![],
+            subscribers: vec![],
+        })))
+    }
+
+    fn emit(&self, event: UploadEvent) {
+        let subscribers = self.0.lock().unwrap().subscribers.clone();
+        for subscriber in subscribers {
+            (subscriber.callback)(&event);
+        }
+    }
+
+    /// Add subscriber to queue and return handle to it
+    ///
+    /// # Returns
+    /// Handle of added subscriber
+    pub fn subscribe(&self, callback: Box<dyn Fn(&UploadEvent)>) -> u64 {
+        static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+        let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .push(Rc::new(Subscriber { callback, handle }));
+
+        handle
+    }
+
+    /// Remove subscriber from queue
+    pub fn unsubscribe(&self, handle: u64) {
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .retain(|v| v.handle != handle);
+    }
+
+    /// Returns a snapshot of the upload
+    pub fn get(&self, id: u64) -> Option<Upload> {
+        self.0
+            .lock()
+            .unwrap()
+            .entries
+            .iter()
+            .find(|v| v.upload.id == id)
+            .map(|v| v.upload.clone())
+    }
+
+    /// Returns snapshots of every upload in the queue
+    pub fn uploads(&self) -> Vec<Upload> {
+        self.0
+            .lock()
+            .unwrap()
+            .entries
+            .iter()
+            .map(|v| v.upload.clone())
+            .collect()
+    }
+
+    /// Returns the progress of every unfinished and completed upload in 0-1
+    pub fn progress(&self) -> f64 {
+        let inner = self.0.lock().unwrap();
+        let (loaded, total) = inner
+            .entries
+            .iter()
+            .filter(|v| {
+                !matches!(
+                    v.upload.status,
+                    UploadStatus::Failed(_) | UploadStatus::Cancelled
+                )
+            })
+            .fold((0.0, 0.0), |(loaded, total), v| {
+                (
+                    loaded + v.upload.progress() * v.upload.total,
+                    total + v.upload.total,
+                )
+            });
+
+        if total > 0.0 { loaded / total } else { 0.0 }
+    }
+
+    /// Adds the file to the queue and starts uploading it if a slot is free
+    ///
+    /// # Returns
+    /// Id of added upload
+    pub fn add(&self, file: File) -> u64 {
+        static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+        let id = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        let total = file.size();
+        self.0.lock().unwrap().entries.push(Entry {
+            upload: Upload {
+                id,
+                file,
+                status: UploadStatus::Pending,
+                loaded: 0.0,
+                total,
+                response_status: None,
+                response: None,
+            },
+            request: None,
+        });
+
+        self.emit(UploadEvent::Add(id));
+        self.pump();
+        id
+    }
+
+    /// Adds every file to the queue
+    ///
+    /// # Returns
+    /// Ids of added uploads
+    pub fn add_all(&self, files: impl IntoIterator<Item = File>) -> Vec<u64> {
+        files.into_iter().map(|v| self.add(v)).collect()
+    }
+
+    /// Cancels a pending or running upload
+    pub fn cancel(&self, id: u64) {
+        let request = {
+            let mut inner = self.0.lock().unwrap();
+            let Some(entry) = inner.entries.iter_mut().find(|v| v.upload.id == id) else {
+                return;
+            };
+            if entry.upload.status.is_finished() {
+                return;
+            }
+            entry.upload.status = UploadStatus::Cancelled;
+            entry.request.take()
+        };
+
+        if let Some(request) = request {
+            request.abort();
+        }
+        self.emit(UploadEvent::Cancel(id));
+        self.pump();
+    }
+
+    /// Queues a failed or cancelled upload again
+    pub fn retry(&self, id: u64) {
+        {
+            let mut inner = self.0.lock().unwrap();
+            let Some(entry) = inner.entries.iter_mut().find(|v| v.upload.id == id) else {
+                return;
+            };
+            if !matches!(
+                entry.upload.status,
+                UploadStatus::Failed(_) | UploadStatus::Cancelled
+            ) {
+                return;
+            }
+            entry.upload.status = UploadStatus::Pending;
+            entry.upload.loaded = 0.0;
+            entry.upload.response_status = None;
+            entry.upload.response = None;
+        }
+
+        self.emit(UploadEvent::Add(id));
+        self.pump();
+    }
+
+    /// Cancels the upload if running and removes it from the queue
+    pub fn remove(&self, id: u64) {
+        let request = {
+            let mut inner = self.0.lock().unwrap();
+            let Some(index) = inner.entries.iter().position(|v| v.upload.id == id) else {
+                return;
+            };
+            inner.entries.remove(index).request
+        };
+
+        if let Some(request) = request {
+            request.abort();
+        }
+        self.emit(UploadEvent::Remove(id));
+        self.pump();
+    }
+
+    /// Removes every finished upload from the queue
+    pub fn clear_finished(&self) {
+        let removed = {
+            let mut inner = self.0.lock().unwrap();
+            let removed = inner
+                .entries
+                .iter()
+                .filter(|v| v.upload.status.is_finished())
+                .map(|v| v.upload.id)
+                .collect::<Vec<_>>();
+            inner.entries.retain(|v| !v.upload.status.is_finished());
+            removed
+        };
+
+        for id in removed {
+            self.emit(UploadEvent::Remove(id));
+        }
+    }
+
+    /// Starts pending uploads while there are free slots
+    fn pump(&self) {
+        loop {
+            let next = {
+                let inner = self.0.lock().unwrap();
+                let running = inner
+                    .entries
+                    .iter()
+                    .filter(|v| v.upload.status == UploadStatus::Uploading)
+                    .count();
+                if running >= inner.options.concurrency {
+                    return;
+                }
+                inner
+                    .entries
+                    .iter()
+                    .find(|v| v.upload.status == UploadStatus::Pending)
+                    .map(|v| v.upload.id)
+            };
+
+            let Some(id) = next else {
+                return;
+            };
+            self.start(id);
+        }
+    }
+
+    fn start(&self, id: u64) {
+        let weak = Rc::downgrade(&self.0);
+        let result = {
+            let mut inner = self.0.lock().unwrap();
+            let Inner {
+                options, entries, ..
+            } = &mut *inner;
+            let Some(entry) = entries.iter_mut().find(|v| v.upload.id == id) else {
+                return;
+            };
+
+            entry.upload.status = UploadStatus::Uploading;
+            match send(options, &entry.upload.file, &weak, id) {
+                Ok(request) => {
+                    entry.request = Some(request);
+                    Ok(())
+                }
+                Err(reason) => {
+                    entry.upload.status = UploadStatus::Failed(reason);
+                    Err(())
+                }
+            }
+        };
+
+        match result {
+            Ok(()) => self.emit(UploadEvent::Start(id)),
+            Err(()) => self.emit(UploadEvent::Fail(id)),
+        }
+    }
+
+    fn handle_progress(&self, id: u64, loaded: f64, total: f64) {
+        {
+            let mut inner = self.0.lock().unwrap();
+            let Some(entry) = inner.entries.iter_mut().find(|v| v.upload.id == id) else {
+                return;
+            };
+            entry.upload.loaded = loaded;
+            entry.upload.total = total;
+        }
+
+        self.emit(UploadEvent::Progress { id, loaded, total });
+    }
+
+    fn handle_finish(&self, id: u64, status: Result<u16, String>) {
+        let (request, event) = {
+            let mut inner = self.0.lock().unwrap();
+            let Some(entry) = inner.entries.iter_mut().find(|v| v.upload.id == id) else {
+                return;
+            };
+            let request = entry.request.take();
+            let upload = &mut entry.upload;
+
+            let event = match status {
+                Ok(status) => {
+                    upload.response_status = Some(status);
+                    upload.response = request
+                        .as_ref()
+                        .and_then(|v| v.xhr.response_text().ok().flatten());
+                    if (200..300).contains(&status) {
+                        upload.status = UploadStatus::Complete;
+                        upload.loaded = upload.total;
+                        UploadEvent::Complete(id)
+                    } else {
+                        upload.status = UploadStatus::Failed(format!("HTTP {status}"));
+                        UploadEvent::Fail(id)
+                    }
+                }
+                Err(reason) => {
+                    upload.status = UploadStatus::Failed(reason);
+                    UploadEvent::Fail(id)
+                }
+            };
+            (request, event)
+        };
+
+        drop(request);
+        self.emit(event);
+        self.pump();
+    }
+}
+
+fn send(
+    options: &UploadQueueOptions,
+    file: &File,
+    weak: &Weak<Mutex<Inner>>,
+    id: u64,
+) -> Result<Request, String> {
+    let to_string = |v: wasm_bindgen::JsValue| format!("{v:?}");
+
+    let data = FormData::new().map_err(to_string)?;
+    for (name, value) in &options.fields {
+        data.append_with_str(name, value).map_err(to_string)?;
+    }
+    data.append_with_blob_and_filename(&options.field, file, &file.name())
+        .map_err(to_string)?;
+
+    let xhr = XmlHttpRequest::new().map_err(to_string)?;
+    xhr.open(&options.method, &options.url).map_err(to_string)?;
+    for (name, value) in &options.headers {
+        xhr.set_request_header(name, value).map_err(to_string)?;
+    }
+    xhr.set_with_credentials(options.with_credentials);
+
+    let upload = xhr.upload().map_err(to_string)?;
+    let queue = |weak: &Weak<Mutex<Inner>>| weak.upgrade().map(UploadQueue);
+    let listeners = vec![
+        EventListener::new(&upload, "progress", {
+            let weak = weak.clone();
+            move |event| {
+                let (Some(queue), Some(event)) = (queue(&weak), event.dyn_ref::<ProgressEvent>())
+                else {
+                    return;
+                };
+                if event.length_computable() {
+                    queue.handle_progress(id, event.loaded(), event.total());
+                }
+            }
+        }),
+        EventListener::new(&xhr, "load", {
+            let weak = weak.clone();
+            let xhr = xhr.clone();
+            move |_| {
+                if let Some(queue) = queue(&weak) {
+                    queue.handle_finish(id, Ok(xhr.status().unwrap_or(0)));
+                }
+            }
+        }),
+        EventListener::new(&xhr, "error", {
+            let weak = weak.clone();
+            move |_| {
+                if let Some(queue) = queue(&weak) {
+                    queue.handle_finish(id, Err("Network error".to_string()));
+                }
+            }
+        }),
+    ];
+
+    xhr.send_with_opt_form_data(Some(&data))
+        .map_err(to_string)?;
+
+    Ok(Request {
+        xhr,
+        _listeners: listeners,
+    })
+}
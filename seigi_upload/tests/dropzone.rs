@@ -0,0 +1,261 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use js_sys::Array;
+use seigi_testkit::{Fixture, events};
+use seigi_upload::dropzone::{
+    self, Dropzone, DropzoneHooks, DropzoneOptions, DropzoneOptionsBuilder, RejectionReason,
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::{
+    ClipboardEvent, ClipboardEventInit, DataTransfer, DragEvent, DragEventInit, File,
+    FilePropertyBag, HtmlInputElement,
+};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<div id="zone">
+    <span id="label">Drop files</span>
+    <input id="input" type="file" hidden>
+</div>
+"#;
+
+/// Creates a file of the size in bytes
+fn file(name: &str, mime: &str, size: usize) -> File {
+    let bag = FilePropertyBag::new();
+    bag.set_type(mime);
+    File::new_with_str_sequence_and_options(&Array::of1(&"x".repeat(size).into()), name, &bag)
+        .unwrap()
+}
+
+fn transfer(files: &[File]) -> DataTransfer {
+    let transfer = DataTransfer::new().unwrap();
+    for file in files {
+        transfer.items().add_with_file(file).unwrap();
+    }
+    transfer
+}
+
+fn drag(target: &web_sys::EventTarget, event_type: &str, transfer: &DataTransfer) -> bool {
+    let init = DragEventInit::new();
+    init.set_bubbles(true);
+    init.set_cancelable(true);
+    init.set_data_transfer(Some(transfer));
+    let event = DragEvent::new_with_event_init_dict(event_type, &init).unwrap();
+    events::dispatch(target, &event)
+}
+
+/// Names of accepted files and rejected ones with their reasons
+#[derive(Default)]
+struct Results {
+    accepted: Vec<String>,
+    rejected: Vec<(String, RejectionReason)>,
+}
+
+fn create(
+    fixture: &Fixture,
+    builder: impl FnOnce(DropzoneOptionsBuilder) -> DropzoneOptionsBuilder,
+) -> (Dropzone, Rc<RefCell<Results>>) {
+    let results = Rc::new(RefCell::new(Results::default()));
+    let hooks = DropzoneHooks {
+        accept: Some(Box::new({
+            let results = results.clone();
+            move |files| {
+                results
+                    .borrow_mut()
+                    .accepted
+                    .extend(files.iter().map(|v| v.name()))
+            }
+        })),
+        reject: Some(Box::new({
+            let results = results.clone();
+            move |rejections| {
+                results
+                    .borrow_mut()
+                    .rejected
+                    .extend(rejections.iter().map(|v| (v.file.name(), v.reason)))
+            }
+        })),
+    };
+    let options = builder(
+        DropzoneOptions::builder()
+            .target(fixture.get("#zone"))
+            .hooks(hooks),
+    );
+    (dropzone::create_dropzone(options.build()), results)
+}
+
+#[wasm_bindgen_test]
+fn files_are_validated() {
+    let fixture = Fixture::new(MARKUP);
+    let (dropzone, results) = create(&fixture, |v| {
+        v.accept("image/*")
+            .accept(".PDF")
+            .max_size(10.0)
+            .max_files(2)
+    });
+
+    dropzone.handle_files(vec![
+        file("photo.png", "image/png", 5),
+        file("notes.txt", "text/plain", 5),
+        file("large.jpg", "image/jpeg", 11),
+        file("paper.pdf", "", 10),
+        file("extra.gif", "image/gif", 1),
+    ]);
+    let results = results.borrow();
+    assert_eq!(results.accepted, ["photo.png", "paper.pdf"]);
+    assert_eq!(
+        results.rejected,
+        [
+            ("notes.txt".to_string(), RejectionReason::Type),
+            ("large.jpg".to_string(), RejectionReason::Size),
+            ("extra.gif".to_string(), RejectionReason::Count),
+        ]
+    );
+}
+
+#[wasm_bindgen_test]
+fn exact_types_match_mime() {
+    let fixture = Fixture::new(MARKUP);
+    let (dropzone, results) = create(&fixture, |v| v.accept("application/json"));
+
+    dropzone.handle_files(vec![
+        file("data.json", "application/json", 1),
+        file("data.txt", "application/jsonl", 1),
+    ]);
+    assert_eq!(results.borrow().accepted, ["data.json"]);
+    assert_eq!(results.borrow().rejected.len(), 1);
+}
+
+#[wasm_bindgen_test]
+fn disabled_dropzone_ignores_files() {
+    let fixture = Fixture::new(MARKUP);
+    let (dropzone, results) = create(&fixture, |v| {
+        v.input(fixture.get("#input").unchecked_into())
+    });
+    let zone = fixture.get("#zone");
+
+    dropzone.set_disabled(true);
+    assert!(dropzone.is_disabled());
+    assert!(zone.has_attribute("data-disabled"));
+    assert_eq!(zone.get_attribute("aria-disabled").as_deref(), Some("true"));
+    assert!(
+        fixture
+            .get("#input")
+            .unchecked_into::<HtmlInputElement>()
+            .disabled()
+    );
+
+    dropzone.handle_files(vec![file("photo.png", "image/png", 1)]);
+    assert!(drag(
+        &zone,
+        "drop",
+        &transfer(&[file("photo.png", "image/png", 1)])
+    ));
+    assert!(results.borrow().accepted.is_empty());
+
+    dropzone.set_disabled(false);
+    assert!(!zone.has_attribute("data-disabled"));
+    dropzone.handle_files(vec![file("photo.png", "image/png", 1)]);
+    assert_eq!(results.borrow().accepted, ["photo.png"]);
+}
+
+#[wasm_bindgen_test]
+fn dragging_sets_attributes_across_children() {
+    let fixture = Fixture::new(MARKUP);
+    create(&fixture, |v| v.accept("image/*"));
+    let (zone, label) = (fixture.get("#zone"), fixture.get("#label"));
+    let transfer = transfer(&[file("photo.png", "image/png", 1)]);
+
+    // Cancelled, which makes the zone a drop target
+    assert!(!drag(&zone, "dragenter", &transfer));
+    assert!(!drag(&zone, "dragover", &transfer));
+    assert!(zone.has_attribute("data-dragging"));
+    assert!(!zone.has_attribute("data-drag-reject"));
+
+    // Entering a child leaves the zone, which still has the files over it
+    drag(&label, "dragenter", &transfer);
+    drag(&zone, "dragleave", &transfer);
+    assert!(zone.has_attribute("data-dragging"));
+    drag(&label, "dragleave", &transfer);
+    assert!(!zone.has_attribute("data-dragging"));
+}
+
+#[wasm_bindgen_test]
+fn dragging_rejected_type_is_marked() {
+    let fixture = Fixture::new(MARKUP);
+    create(&fixture, |v| v.accept("image/*"));
+    let zone = fixture.get("#zone");
+    let transfer = transfer(&[file("notes.txt", "text/plain", 1)]);
+
+    drag(&zone, "dragenter", &transfer);
+    assert!(zone.has_attribute("data-dragging"));
+    assert!(zone.has_attribute("data-drag-reject"));
+    drag(&zone, "drop", &transfer);
+    assert!(!zone.has_attribute("data-dragging"));
+    assert!(!zone.has_attribute("data-drag-reject"));
+}
+
+#[wasm_bindgen_test]
+fn dropped_files_are_accepted() {
+    let fixture = Fixture::new(MARKUP);
+    let (_dropzone, results) = create(&fixture, |v| v.directories(false));
+    let zone = fixture.get("#zone");
+
+    let transfer = transfer(&[
+        file("a.txt", "text/plain", 1),
+        file("b.txt", "text/plain", 1),
+    ]);
+    drag(&zone, "dragenter", &transfer);
+    assert!(!drag(&zone, "drop", &transfer));
+    assert_eq!(results.borrow().accepted, ["a.txt", "b.txt"]);
+    assert!(!zone.has_attribute("data-dragging"));
+}
+
+#[wasm_bindgen_test]
+fn pasted_files_are_accepted() {
+    let fixture = Fixture::new(MARKUP);
+    let (_dropzone, results) = create(&fixture, |v| v);
+    let zone = fixture.get("#zone");
+    assert_eq!(zone.tab_index(), 0);
+
+    let init = ClipboardEventInit::new();
+    init.set_bubbles(true);
+    init.set_cancelable(true);
+    init.set_clipboard_data(Some(&transfer(&[file("shot.png", "image/png", 1)])));
+    let event = ClipboardEvent::new_with_event_init_dict("paste", &init).unwrap();
+    assert!(!events::dispatch(&zone, &event));
+    assert_eq!(results.borrow().accepted, ["shot.png"]);
+}
+
+#[wasm_bindgen_test]
+fn paste_can_be_disabled() {
+    let fixture = Fixture::new(MARKUP);
+    let (_dropzone, results) = create(&fixture, |v| v.paste(false));
+    let zone = fixture.get("#zone");
+    assert!(!zone.has_attribute("tabindex"));
+
+    let init = ClipboardEventInit::new();
+    init.set_cancelable(true);
+    init.set_clipboard_data(Some(&transfer(&[file("shot.png", "image/png", 1)])));
+    let event = ClipboardEvent::new_with_event_init_dict("paste", &init).unwrap();
+    assert!(events::dispatch(&zone, &event));
+    assert!(results.borrow().accepted.is_empty());
+}
+
+#[wasm_bindgen_test]
+fn input_follows_options() {
+    let fixture = Fixture::new(MARKUP);
+    create(&fixture, |v| {
+        v.input(fixture.get("#input").unchecked_into())
+            .accept("image/*")
+            .accept(".pdf")
+            .max_files(1)
+    });
+    let input = fixture.get("#input").unchecked_into::<HtmlInputElement>();
+    assert_eq!(input.accept(), "image/*,.pdf");
+    assert!(!input.multiple());
+}
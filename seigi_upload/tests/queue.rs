@@ -0,0 +1,131 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use js_sys::Array;
+use seigi_upload::queue::{UploadEvent, UploadQueue, UploadQueueOptions, UploadStatus};
+use wasm_bindgen_test::*;
+use web_sys::File;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn file(name: &str, size: usize) -> File {
+    File::new_with_str_sequence(&Array::of1(&"x".repeat(size).into()), name).unwrap()
+}
+
+fn queue(url: &str, concurrency: usize) -> (UploadQueue, Rc<RefCell<Vec<UploadEvent>>>) {
+    let queue = UploadQueue::new(
+        UploadQueueOptions::builder()
+            .url(url)
+            .concurrency(concurrency)
+            .build(),
+    );
+    let events = Rc::new(RefCell::new(vec![]));
+    queue.subscribe(Box::new({
+        let events = events.clone();
+        move |event| events.borrow_mut().push(event.clone())
+    }));
+    (queue, events)
+}
+
+fn status(queue: &UploadQueue, id: u64) -> UploadStatus {
+    queue.get(id).unwrap().status
+}
+
+#[wasm_bindgen_test]
+fn uploads_wait_for_free_slot() {
+    let (queue, events) = queue("/upload", 1);
+    let first = queue.add(file("first.txt", 4));
+    let second = queue.add(file("second.txt", 4));
+    assert_eq!(status(&queue, first), UploadStatus::Uploading);
+    assert_eq!(status(&queue, second), UploadStatus::Pending);
+    assert_eq!(
+        *events.borrow(),
+        [
+            UploadEvent::Add(first),
+            UploadEvent::Start(first),
+            UploadEvent::Add(second),
+        ]
+    );
+
+    // Cancelling frees the slot for the next upload
+    queue.cancel(first);
+    assert_eq!(status(&queue, first), UploadStatus::Cancelled);
+    assert_eq!(status(&queue, second), UploadStatus::Uploading);
+    assert_eq!(
+        events.borrow()[3..],
+        [UploadEvent::Cancel(first), UploadEvent::Start(second)]
+    );
+
+    // Finished uploads can't be cancelled again
+    queue.cancel(first);
+    assert_eq!(events.borrow().len(), 5);
+
+    queue.remove(second);
+    assert!(queue.get(second).is_none());
+    assert_eq!(events.borrow().last(), Some(&UploadEvent::Remove(second)));
+}
+
+#[wasm_bindgen_test]
+fn concurrency_limits_running_uploads() {
+    let (queue, _) = queue("/upload", 2);
+    let ids = queue.add_all([file("a.txt", 1), file("b.txt", 1), file("c.txt", 1)]);
+    let statuses = ids.iter().map(|v| status(&queue, *v)).collect::<Vec<_>>();
+    assert_eq!(
+        statuses,
+        [
+            UploadStatus::Uploading,
+            UploadStatus::Uploading,
+            UploadStatus::Pending,
+        ]
+    );
+
+    queue.remove(ids[0]);
+    assert_eq!(status(&queue, ids[2]), UploadStatus::Uploading);
+    for id in &ids[1..] {
+        queue.cancel(*id);
+    }
+}
+
+#[wasm_bindgen_test]
+fn failed_upload_can_be_retried() {
+    // Opening a request to an invalid URL throws, failing the upload right away
+    let (queue, events) = queue("http://[invalid", 1);
+    let id = queue.add(file("a.txt", 4));
+    assert!(matches!(status(&queue, id), UploadStatus::Failed(_)));
+    assert!(status(&queue, id).is_finished());
+    assert_eq!(events.borrow().last(), Some(&UploadEvent::Fail(id)));
+
+    events.borrow_mut().clear();
+    queue.retry(id);
+    assert_eq!(
+        *events.borrow(),
+        [UploadEvent::Add(id), UploadEvent::Fail(id)]
+    );
+
+    queue.clear_finished();
+    assert!(queue.uploads().is_empty());
+    assert_eq!(events.borrow().last(), Some(&UploadEvent::Remove(id)));
+}
+
+#[wasm_bindgen_test]
+fn progress_skips_failed_and_cancelled_uploads() {
+    let (queue, _) = queue("/upload", 1);
+    assert_eq!(queue.progress(), 0.0);
+
+    let running = queue.add(file("running.txt", 10));
+    let cancelled = queue.add(file("cancelled.txt", 30));
+    queue.cancel(cancelled);
+    assert_eq!(queue.get(running).unwrap().progress(), 0.0);
+    assert_eq!(queue.get(running).unwrap().total, 10.0);
+    assert_eq!(queue.progress(), 0.0);
+
+    // Only failed and cancelled uploads can be retried
+    queue.retry(running);
+    assert_eq!(status(&queue, running), UploadStatus::Uploading);
+    queue.retry(cancelled);
+    assert_eq!(status(&queue, cancelled), UploadStatus::Pending);
+
+    queue.remove(running);
+    queue.remove(cancelled);
+}
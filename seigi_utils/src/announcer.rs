@@ -0,0 +1,152 @@
+//! Announcing messages to screen readers through shared live regions
+//!
+//! Live regions are created in the body on the first announcement, one for each [Politeness],
+//! and shared by every widget. Messages announced within [DEBOUNCE] milliseconds of each other
+//! are announced together once, with duplicates among them dropped.
+
+use std::cell::RefCell;
+
+use gloo::{timers::callback::Timeout, utils::document};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+/// The inline style hiding an element visually while keeping it in the accessibility tree
+pub const VISUALLY_HIDDEN: &str = "position:absolute;width:1px;height:1px;padding:0;margin:-1px;\
+    overflow:hidden;clip:rect(0,0,0,0);white-space:nowrap;border:0";
+
+/// Milliseconds queued messages wait for others before they are announced
+pub const DEBOUNCE: u32 = 150;
+
+/// How urgently a message is announced
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Politeness {
+    /// Announced after the screen reader finishes what it is reading
+    #[default]
+    Polite,
+    /// Announced at once, interrupting what the screen reader is reading
+    Assertive,
+}
+
+impl Politeness {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Polite => "polite",
+            Self::Assertive => "assertive",
+        }
+    }
+}
+
+#[derive(Default)]
+struct State {
+    polite: Option<HtmlElement>,
+    assertive: Option<HtmlElement>,
+    queue: Vec<(Politeness, String)>,
+    timer: Option<Timeout>,
+}
+
+impl State {
+    fn region(&mut self, politeness: Politeness) -> Option<HtmlElement> {
+        let region = match politeness {
+            Politeness::Polite => &mut self.polite,
+            Politeness::Assertive => &mut self.assertive,
+        };
+        // Regions removed from the document by the page are created again
+        if let Some(element) = region
+            && element.is_connected()
+        {
+            return Some(element.clone());
+        }
+
+        let element = document()
+            .create_element("div")
+            .ok()?
+            .unchecked_into::<HtmlElement>();
+        let _ = element.set_attribute("style", VISUALLY_HIDDEN);
+        let _ = element.set_attribute("data-seigi-announcer", politeness.as_str());
+        let _ = element.set_attribute(
+            "role",
+            match politeness {
+                Politeness::Polite => "status",
+                Politeness::Assertive => "alert",
+            },
+        );
+        let _ = element.set_attribute("aria-live", politeness.as_str());
+        let _ = element.set_attribute("aria-atomic", "true");
+        let _ = document().body()?.append_child(&element);
+        *region = Some(element.clone());
+        Some(element)
+    }
+
+    fn flush(&mut self) {
+        self.timer = None;
+        let queue = std::mem::take(&mut self.queue);
+        for politeness in [Politeness::Assertive, Politeness::Polite] {
+            let mut messages: Vec<&str> = vec![];
+            for (_, message) in queue.iter().filter(|(v, _)| *v == politeness) {
+                if !messages.contains(&message.as_str()) {
+                    messages.push(message);
+                }
+            }
+            if messages.is_empty() {
+                continue;
+            }
+            let Some(region) = self.region(politeness) else {
+                continue;
+            };
+
+            let mut text = messages.join("\n");
+            // Screen readers skip regions whose content didn't change, so a message repeated
+            // after a while is altered invisibly to be announced again
+            if region.text_content().is_some_and(|v| v == text) {
+                text.push('\u{a0}');
+            }
+            region.set_text_content(Some(&text));
+        }
+    }
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+/// Queues the message to be announced
+pub fn announce(message: impl Into<String>, politeness: Politeness) {
+    let message = message.into();
+    if message.trim().is_empty() {
+        return;
+    }
+    STATE.with_borrow_mut(|state| {
+        state.queue.push((politeness, message));
+        state.timer = Some(Timeout::new(DEBOUNCE, || {
+            STATE.with_borrow_mut(State::flush);
+            // Pages may strip attributes of regions, like sanitizers rendering the body again
+            if crate::audit::is_enabled() {
+                let regions = STATE.with_borrow(|v| [v.polite.clone(), v.assertive.clone()]);
+                for region in regions.iter().flatten() {
+                    crate::audit::live_region("seigi_utils", region);
+                }
+            }
+        }));
+    });
+}
+
+/// Queues the message to be announced politely
+pub fn polite(message: impl Into<String>) {
+    announce(message, Politeness::Polite);
+}
+
+/// Queues the message to be announced assertively
+pub fn assertive(message: impl Into<String>) {
+    announce(message, Politeness::Assertive);
+}
+
+/// Drops queued messages and empties live regions
+pub fn clear() {
+    STATE.with_borrow_mut(|state| {
+        state.queue.clear();
+        state.timer = None;
+        for region in [&state.polite, &state.assertive].into_iter().flatten() {
+            region.set_text_content(None);
+        }
+    });
+}
@@ -0,0 +1,288 @@
+//! Accessibility audit of live widgets, for development builds
+//!
+//! With the `audit` feature, widgets inspect their elements as they are created and shown, and
+//! report what assistive technologies would trip on, such as a dialog without an accessible name
+//! or a focus trap with nothing to focus. Reports reach subscribers of [subscribe], or the
+//! browser console as warnings while there are none. [document] audits the whole page at once,
+//! which suits tests.
+//!
+//! Without the feature, [is_enabled] is false and widgets skip their checks entirely, so release
+//! builds pay nothing for them.
+
+use std::{
+    cell::RefCell,
+    fmt::{self, Display},
+    rc::Rc,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use gloo::utils;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement};
+
+/// What an audit found wrong in an element
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    /// A dialog has no accessible name from **aria-label** or **aria-labelledby**
+    MissingLabel,
+    /// **aria-labelledby** or **aria-describedby** refers to an id no element has
+    MissingReference(String),
+    /// A region messages are written to isn't a live region, so they aren't announced
+    MissingPoliteness,
+    /// A focus trap has nothing focusable in its target, so focus is lost once activated
+    NoFocusCandidates,
+    /// Several elements in the document have the id, so references to it are ambiguous
+    DuplicateId(String),
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingLabel => write!(f, "has no accessible name"),
+            Self::MissingReference(id) => write!(f, "refers to #{id}, which doesn't exist"),
+            Self::MissingPoliteness => write!(f, "is not a live region"),
+            Self::NoFocusCandidates => write!(f, "has nothing focusable"),
+            Self::DuplicateId(id) => write!(f, "has the id #{id} shared by other elements"),
+        }
+    }
+}
+
+/// A problem found by an audit
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// The crate of the widget reporting, like `seigi_focus`
+    pub widget: &'static str,
+    pub issue: Issue,
+    /// The element the issue is found in
+    pub element: Element,
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {} {}",
+            self.widget,
+            describe(&self.element),
+            self.issue
+        )
+    }
+}
+
+/// Describes the element as a selector-like string, such as `dialog#settings`
+fn describe(element: &Element) -> String {
+    let tag = element.tag_name().to_lowercase();
+    let id = element.id();
+    if id.is_empty() {
+        tag
+    } else {
+        format!("{tag}#{id}")
+    }
+}
+
+struct Subscriber {
+    callback: Box<dyn Fn(&Report)>,
+    handle: u64,
+}
+
+thread_local! {
+    static SUBSCRIBERS: RefCell<Vec<Rc<Subscriber>>> = const { RefCell::new(vec![]) };
+}
+
+/// Returns whether widgets audit themselves, which is whether the `audit` feature is enabled
+pub const fn is_enabled() -> bool {
+    cfg!(feature = "audit")
+}
+
+/// Subscribes to reports, which stop being printed to the console while subscribed
+///
+/// # Returns
+/// A handle to unsubscribe with [unsubscribe]
+pub fn subscribe(callback: Box<dyn Fn(&Report)>) -> u64 {
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+    let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    SUBSCRIBERS.with_borrow_mut(|v| v.push(Rc::new(Subscriber { callback, handle })));
+    handle
+}
+
+pub fn unsubscribe(handle: u64) {
+    SUBSCRIBERS.with_borrow_mut(|v| v.retain(|v| v.handle != handle));
+}
+
+/// Publishes the report to subscribers, or prints it to the console if there are none
+///
+/// Does nothing unless [is_enabled].
+pub fn report(report: Report) {
+    if !is_enabled() {
+        return;
+    }
+    let subscribers = SUBSCRIBERS.with_borrow(|v| v.clone());
+    if subscribers.is_empty() {
+        web_sys::console::warn_2(&report.to_string().into(), &report.element);
+        return;
+    }
+    for subscriber in subscribers {
+        (subscriber.callback)(&report);
+    }
+}
+
+fn check(widget: &'static str, element: &Element, issues: Vec<Issue>) {
+    for issue in issues {
+        report(Report {
+            widget,
+            issue,
+            element: element.clone(),
+        });
+    }
+}
+
+/// Returns issues of the dialog, checking its accessible name and ids of its subtree
+pub fn dialog_issues(dialog: &Element) -> Vec<Issue> {
+    let mut issues = vec![];
+    let label = dialog
+        .get_attribute("aria-label")
+        .is_some_and(|v| !v.trim().is_empty());
+    let labelled_by = crate::id::related(dialog, crate::id::Relation::LabelledBy);
+    if !label && labelled_by.is_empty() {
+        issues.push(Issue::MissingLabel);
+    }
+    issues.extend(reference_issues(dialog));
+    issues.extend(duplicate_id_issues(dialog));
+    issues
+}
+
+/// Audits the dialog as it is shown, checking its accessible name and ids of its subtree
+pub fn dialog(widget: &'static str, dialog: &Element) {
+    if is_enabled() {
+        check(widget, dialog, dialog_issues(dialog));
+    }
+}
+
+/// Returns issues of the region messages are written to, which must be a live region
+pub fn live_region_issues(region: &Element) -> Vec<Issue> {
+    let is_live = region
+        .get_attribute("aria-live")
+        .is_some_and(|v| v == "polite" || v == "assertive")
+        || region
+            .get_attribute("role")
+            .is_some_and(|v| matches!(v.as_str(), "status" | "alert" | "log"));
+    if is_live {
+        vec![]
+    } else {
+        vec![Issue::MissingPoliteness]
+    }
+}
+
+/// Audits the region messages are written to, which must be a live region
+pub fn live_region(widget: &'static str, region: &Element) {
+    if is_enabled() {
+        check(widget, region, live_region_issues(region));
+    }
+}
+
+/// Audits the target of a focus trap, given whether anything in it is focusable
+pub fn focus_target(widget: &'static str, target: &Element, has_candidates: bool) {
+    if is_enabled() && !has_candidates {
+        check(widget, target, vec![Issue::NoFocusCandidates]);
+    }
+}
+
+/// Returns the number of elements with the id in the document
+fn count_id(id: &str) -> u32 {
+    // Ids may contain characters special to selectors
+    let selector = format!("[id=\"{}\"]", id.replace('\\', "\\\\").replace('"', "\\\""));
+    utils::document()
+        .query_selector_all(&selector)
+        .map_or(0, |v| v.length())
+}
+
+/// Returns issues of relations in the subtree referring to ids no element has
+fn reference_issues(root: &Element) -> Vec<Issue> {
+    let document = utils::document();
+    let mut issues = vec![];
+    for element in subtree(root, "[aria-labelledby], [aria-describedby]") {
+        for relation in [
+            crate::id::Relation::LabelledBy,
+            crate::id::Relation::DescribedBy,
+        ] {
+            for id in crate::id::related(&element, relation) {
+                if document.get_element_by_id(&id).is_none() {
+                    issues.push(Issue::MissingReference(id));
+                }
+            }
+        }
+    }
+    issues
+}
+
+/// Returns issues of ids in the subtree that other elements of the document also have
+fn duplicate_id_issues(root: &Element) -> Vec<Issue> {
+    let mut issues = vec![];
+    for element in subtree(root, "[id]") {
+        let id = element.id();
+        if !id.is_empty() && count_id(&id) > 1 {
+            issues.push(Issue::DuplicateId(id));
+        }
+    }
+    issues
+}
+
+/// Returns the root and its descendants matching the selector
+fn subtree(root: &Element, selector: &str) -> Vec<Element> {
+    let mut elements = vec![];
+    if root.matches(selector).unwrap_or(false) {
+        elements.push(root.clone());
+    }
+    if let Ok(list) = root.query_selector_all(selector) {
+        elements.extend((0..list.length()).filter_map(|index| list.get(index)?.dyn_into().ok()));
+    }
+    elements
+}
+
+/// Audits the whole document, returning every report instead of publishing them
+///
+/// Dialogs, live regions of [crate::announcer] and ids are checked regardless of [is_enabled],
+/// so tests can assert that a page is free of issues.
+pub fn document() -> Vec<Report> {
+    let document = utils::document();
+    let mut reports = vec![];
+    let mut push = |widget, element: &Element, issues: Vec<Issue>| {
+        reports.extend(issues.into_iter().map(|issue| Report {
+            widget,
+            issue,
+            element: element.clone(),
+        }));
+    };
+
+    let Some(root) = document.document_element() else {
+        return reports;
+    };
+    for dialog in subtree(&root, "dialog, [role=dialog], [role=alertdialog]") {
+        let is_shown = dialog.dyn_ref::<HtmlElement>().is_none_or(|v| !v.hidden())
+            && (dialog.tag_name() != "DIALOG" || dialog.has_attribute("open"));
+        if is_shown {
+            let mut issues = vec![];
+            if dialog_issues(&dialog).contains(&Issue::MissingLabel) {
+                issues.push(Issue::MissingLabel);
+            }
+            push("seigi", &dialog, issues);
+        }
+    }
+    for region in subtree(&root, "[data-seigi-announcer]") {
+        push("seigi_utils", &region, live_region_issues(&region));
+    }
+    push("seigi", &root, reference_issues(&root));
+
+    let mut seen: Vec<String> = vec![];
+    for element in subtree(&root, "[id]") {
+        let id = element.id();
+        if id.is_empty() || seen.contains(&id) {
+            continue;
+        }
+        if count_id(&id) > 1 {
+            push("seigi", &element, vec![Issue::DuplicateId(id.clone())]);
+        }
+        seen.push(id);
+    }
+    reports
+}
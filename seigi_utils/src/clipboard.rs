@@ -0,0 +1,503 @@
+//! Copying to and reading from the clipboard
+//!
+//! The async Clipboard API is used where available, which requires a secure context. Writes fall
+//! back to `document.execCommand("copy")` otherwise, which still works from user gestures in
+//! older browsers and plain http pages.
+//!
+//! Successful and failed writes are published to subscribers, so a single place can show toasts
+//! like "Copied!" for every copy button.
+
+use std::{
+    cell::RefCell,
+    error::Error,
+    fmt::Display,
+    rc::{Rc, Weak},
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use gloo::{
+    events::{EventListener, EventListenerOptions},
+    timers::callback::Timeout,
+    utils::{body, document, window},
+};
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    Blob, BlobPropertyBag, ClipboardEvent, ClipboardItem, HtmlDocument, HtmlElement,
+    HtmlTextAreaElement, PermissionState, PermissionStatus,
+};
+
+/// The duration in milliseconds a copy button stays copied by default
+pub const COPIED_DURATION: u32 = 2000;
+
+/// An error of clipboard operations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClipboardError {
+    /// Neither the Clipboard API nor the fallback is available, like reading in an insecure
+    /// context
+    Unsupported,
+    /// The browser refused the operation, mostly for lack of permission or a user gesture
+    Rejected(String),
+}
+
+impl Display for ClipboardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsupported => write!(f, "clipboard is not supported"),
+            Self::Rejected(reason) => write!(f, "clipboard operation was rejected: {reason}"),
+        }
+    }
+}
+
+impl Error for ClipboardError {}
+
+impl From<JsValue> for ClipboardError {
+    fn from(value: JsValue) -> Self {
+        let reason = value
+            .dyn_ref::<js_sys::Error>()
+            .map(|v| String::from(v.message()))
+            .or_else(|| value.as_string())
+            .unwrap_or_default();
+        Self::Rejected(reason)
+    }
+}
+
+/// Content written to the clipboard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Content {
+    Text(String),
+    /// Rich text, with plain text for targets not accepting html
+    Html {
+        html: String,
+        text: String,
+    },
+}
+
+impl Content {
+    /// Returns the plain text of the content
+    pub fn text(&self) -> &str {
+        match self {
+            Self::Text(text) => text,
+            Self::Html { text, .. } => text,
+        }
+    }
+}
+
+/// A write to the clipboard, published to subscribers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CopyEvent {
+    Copied(Content),
+    Failed {
+        content: Content,
+        error: ClipboardError,
+    },
+}
+
+/// A clipboard permission queried with [permission]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+}
+
+impl Access {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "clipboard-read",
+            Self::Write => "clipboard-write",
+        }
+    }
+}
+
+/// The state of a clipboard permission
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    Granted,
+    /// The user will be asked when the clipboard is accessed
+    Prompt,
+    Denied,
+    /// The browser doesn't expose the permission, like Firefox and Safari, where access is
+    /// allowed from user gestures
+    Unknown,
+}
+
+struct Subscriber {
+    callback: Box<dyn Fn(&CopyEvent)>,
+    handle: u64,
+}
+
+thread_local! {
+    static SUBSCRIBERS: RefCell<Vec<Rc<Subscriber>>> = const { RefCell::new(vec![]) };
+}
+
+/// Subscribes to writes to the clipboard made through this module
+///
+/// # Returns
+/// A handle to unsubscribe with [unsubscribe]
+pub fn subscribe(callback: Box<dyn Fn(&CopyEvent)>) -> u64 {
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+    let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    SUBSCRIBERS.with_borrow_mut(|v| v.push(Rc::new(Subscriber { callback, handle })));
+    handle
+}
+
+pub fn unsubscribe(handle: u64) {
+    SUBSCRIBERS.with_borrow_mut(|v| v.retain(|v| v.handle != handle));
+}
+
+fn publish(event: &CopyEvent) {
+    let subscribers = SUBSCRIBERS.with_borrow(|v| v.clone());
+    for subscriber in subscribers {
+        (subscriber.callback)(event);
+    }
+}
+
+/// Returns the Clipboard API, which is undefined in insecure contexts
+fn clipboard() -> Option<web_sys::Clipboard> {
+    let navigator = window().navigator();
+    Reflect::get(&navigator, &JsValue::from_str("clipboard"))
+        .ok()
+        .filter(|v| !v.is_undefined() && !v.is_null())
+        .map(|v| v.unchecked_into())
+}
+
+/// Returns whether the async Clipboard API is available, without which reads fail and writes
+/// fall back to `execCommand`
+pub fn is_supported() -> bool {
+    clipboard().is_some()
+}
+
+/// Queries the state of the clipboard permission
+pub async fn permission(access: Access) -> Permission {
+    let Ok(permissions) = window().navigator().permissions() else {
+        return Permission::Unknown;
+    };
+    let descriptor = Object::new();
+    let _ = Reflect::set(
+        &descriptor,
+        &JsValue::from_str("name"),
+        &JsValue::from_str(access.as_str()),
+    );
+    let Ok(promise) = permissions.query(&descriptor) else {
+        return Permission::Unknown;
+    };
+    // Browsers not knowing the permission name reject the query
+    let Ok(status) = JsFuture::from(promise).await else {
+        return Permission::Unknown;
+    };
+    match status.unchecked_into::<PermissionStatus>().state() {
+        PermissionState::Granted => Permission::Granted,
+        PermissionState::Prompt => Permission::Prompt,
+        PermissionState::Denied => Permission::Denied,
+        _ => Permission::Unknown,
+    }
+}
+
+/// Copies through a hidden textarea and `execCommand`, setting html with a copy listener as the
+/// textarea only holds plain text
+fn copy_fallback(content: &Content) -> Result<(), ClipboardError> {
+    let document = document();
+    let active = document
+        .active_element()
+        .and_then(|v| v.dyn_into::<HtmlElement>().ok());
+
+    let textarea = document
+        .create_element("textarea")
+        .expect("failed to create element")
+        .unchecked_into::<HtmlTextAreaElement>();
+    textarea.set_value(content.text());
+    let _ = textarea.set_attribute("readonly", "");
+    let _ = textarea.set_attribute("aria-hidden", "true");
+    let _ = textarea.set_attribute(
+        "style",
+        "position: fixed; top: 0; left: 0; opacity: 0; pointer-events: none;",
+    );
+    let _ = body().append_child(&textarea);
+    textarea.select();
+
+    let _listener = match content {
+        Content::Html { html, text } => {
+            let (html, text) = (html.clone(), text.clone());
+            Some(EventListener::new_with_options(
+                &document,
+                "copy",
+                EventListenerOptions::enable_prevent_default(),
+                move |event| {
+                    let event = event.unchecked_ref::<ClipboardEvent>();
+                    if let Some(data) = event.clipboard_data() {
+                        let _ = data.set_data("text/html", &html);
+                        let _ = data.set_data("text/plain", &text);
+                        event.prevent_default();
+                    }
+                },
+            ))
+        }
+        Content::Text(_) => None,
+    };
+    let result = document
+        .unchecked_ref::<HtmlDocument>()
+        .exec_command("copy");
+
+    textarea.remove();
+    if let Some(active) = active {
+        let _ = active.focus();
+    }
+    match result {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(ClipboardError::Unsupported),
+        Err(error) => Err(error.into()),
+    }
+}
+
+fn blob(value: &str, kind: &str) -> Result<JsValue, ClipboardError> {
+    let options = BlobPropertyBag::new();
+    options.set_type(kind);
+    let blob =
+        Blob::new_with_str_sequence_and_options(&Array::of1(&JsValue::from_str(value)), &options)?;
+    Ok(blob.into())
+}
+
+async fn copy_async(content: &Content) -> Result<(), ClipboardError> {
+    let Some(clipboard) = clipboard() else {
+        return copy_fallback(content);
+    };
+    let promise = match content {
+        Content::Text(text) => clipboard.write_text(text),
+        Content::Html { html, text } => {
+            let items = Object::new();
+            Reflect::set(&items, &"text/html".into(), &blob(html, "text/html")?)?;
+            Reflect::set(&items, &"text/plain".into(), &blob(text, "text/plain")?)?;
+            let item = ClipboardItem::new_with_record_from_str_to_blob_promise(&items)?;
+            clipboard.write(&Array::of1(&item))
+        }
+    };
+    // The API rejects writes without focus in the document or, in Safari, outside user
+    // gestures, where the fallback still succeeds
+    if JsFuture::from(promise).await.is_ok() {
+        return Ok(());
+    }
+    copy_fallback(content)
+}
+
+/// Writes the content to the clipboard, and publishes the result to subscribers
+pub async fn copy(content: Content) -> Result<(), ClipboardError> {
+    let result = copy_async(&content).await;
+    publish(&match &result {
+        Ok(()) => CopyEvent::Copied(content),
+        Err(error) => CopyEvent::Failed {
+            content,
+            error: error.clone(),
+        },
+    });
+    result
+}
+
+/// Writes the text to the clipboard
+pub async fn copy_text(text: impl Into<String>) -> Result<(), ClipboardError> {
+    copy(Content::Text(text.into())).await
+}
+
+/// Writes rich text to the clipboard, with the plain text for targets not accepting html
+pub async fn copy_html(
+    html: impl Into<String>,
+    text: impl Into<String>,
+) -> Result<(), ClipboardError> {
+    copy(Content::Html {
+        html: html.into(),
+        text: text.into(),
+    })
+    .await
+}
+
+/// Reads the text from the clipboard, which may ask the user for permission
+pub async fn read_text() -> Result<String, ClipboardError> {
+    let clipboard = clipboard().ok_or(ClipboardError::Unsupported)?;
+    let text = JsFuture::from(clipboard.read_text()).await?;
+    text.as_string().ok_or(ClipboardError::Unsupported)
+}
+
+pub type ContentProvider = Box<dyn Fn() -> Content>;
+pub type CopyHook = Box<dyn Fn(&Content)>;
+pub type ErrorHook = Box<dyn Fn(&ClipboardError)>;
+
+/// Hooks to [CopyButton]
+#[derive(Default)]
+pub struct CopyButtonHooks {
+    /// Called after the content is copied
+    pub copy: Option<CopyHook>,
+    /// Called if copying failed
+    pub error: Option<ErrorHook>,
+}
+
+/// Options of [CopyButton]
+pub struct CopyButtonOptions {
+    pub element: HtmlElement,
+    /// Returns the content to copy on each click
+    pub content: ContentProvider,
+    /// The duration in milliseconds the button stays copied, which defaults to
+    /// [COPIED_DURATION]
+    pub duration: u32,
+    pub hooks: CopyButtonHooks,
+}
+
+impl CopyButtonOptions {
+    pub fn builder() -> CopyButtonOptionsBuilder {
+        CopyButtonOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [CopyButtonOptions]
+pub struct CopyButtonOptionsBuilder {
+    element: Option<HtmlElement>,
+    content: Option<ContentProvider>,
+    duration: u32,
+    hooks: CopyButtonHooks,
+}
+
+impl Default for CopyButtonOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            element: None,
+            content: None,
+            duration: COPIED_DURATION,
+            hooks: CopyButtonHooks::default(),
+        }
+    }
+}
+
+impl CopyButtonOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn element(mut self, element: HtmlElement) -> Self {
+        self.element = Some(element);
+        self
+    }
+
+    pub fn content(mut self, content: ContentProvider) -> Self {
+        self.content = Some(content);
+        self
+    }
+
+    /// Copies the fixed text
+    pub fn text(self, text: impl Into<String>) -> Self {
+        let text = text.into();
+        self.content(Box::new(move || Content::Text(text.clone())))
+    }
+
+    pub fn duration(mut self, duration: u32) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: CopyButtonHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [CopyButtonOptions]
+    ///
+    /// # Panics
+    /// This method panics if element or content field is not set
+    pub fn build(self) -> CopyButtonOptions {
+        CopyButtonOptions {
+            element: self
+                .element
+                .expect("element must be set to build CopyButtonOptions"),
+            content: self
+                .content
+                .expect("content must be set to build CopyButtonOptions"),
+            duration: self.duration,
+            hooks: self.hooks,
+        }
+    }
+}
+
+struct Inner {
+    options: Rc<CopyButtonOptions>,
+    _listener: Option<EventListener>,
+    /// Resets the state after the duration
+    timeout: Option<Timeout>,
+}
+
+/// An instance of copy button, which copies its content when clicked
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-state** is set to `idle`, `copied` or `failed`, and goes back to `idle` after the
+/// duration
+#[derive(Clone)]
+pub struct CopyButton(Rc<Mutex<Inner>>);
+
+impl CopyButton {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    pub fn element(&self) -> HtmlElement {
+        self.0.lock().unwrap().options.element.clone()
+    }
+
+    /// Copies the content as if the button was clicked
+    pub async fn copy(&self) -> Result<(), ClipboardError> {
+        let options = self.0.lock().unwrap().options.clone();
+        let content = (options.content)();
+        let result = copy(content.clone()).await;
+        let state = match &result {
+            Ok(()) => "copied",
+            Err(_) => "failed",
+        };
+        let _ = options.element.set_attribute("data-state", state);
+
+        let weak = Rc::downgrade(&self.0);
+        self.0.lock().unwrap().timeout = Some(Timeout::new(options.duration, move || {
+            if let Some(button) = CopyButton::from_weak(&weak) {
+                let _ = button.element().set_attribute("data-state", "idle");
+                button.0.lock().unwrap().timeout = None;
+            }
+        }));
+
+        match &result {
+            Ok(()) => {
+                if let Some(hook) = &options.hooks.copy {
+                    hook(&content);
+                }
+            }
+            Err(error) => {
+                if let Some(hook) = &options.hooks.error {
+                    hook(error);
+                }
+            }
+        }
+        result
+    }
+}
+
+pub fn create_copy_button(options: CopyButtonOptions) -> CopyButton {
+    let element = options.element.clone();
+    let _ = element.set_attribute("data-state", "idle");
+
+    let button = CopyButton(Rc::new(Mutex::new(Inner {
+        options: Rc::new(options),
+        _listener: None,
+        timeout: None,
+    })));
+
+    let weak = Rc::downgrade(&button.0);
+    let listener = EventListener::new(&element, "click", move |_| {
+        if let Some(button) = CopyButton::from_weak(&weak) {
+            wasm_bindgen_futures::spawn_local(async move {
+                let _ = button.copy().await;
+            });
+        }
+    });
+    button.0.lock().unwrap()._listener = Some(listener);
+    button
+}
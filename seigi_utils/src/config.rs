@@ -0,0 +1,183 @@
+//! Defaults of widgets, set once by applications
+//!
+//! [configure] sets a [GlobalConfig] whose values builders of widgets start from, instead of
+//! constants of each crate. Builders still override them one by one, and widgets built before
+//! configuring keep what they were built with, so applications configure before creating any.
+//!
+//! The id prefix and the locale are applied to [crate::id] and [crate::i18n], which remain where
+//! they are read from.
+
+use std::{cell::RefCell, time::Duration};
+
+use crate::{i18n, id};
+
+/// Where toasts are placed in the viewport
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Position {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    BottomCenter,
+    #[default]
+    BottomRight,
+}
+
+impl Position {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TopLeft => "top-left",
+            Self::TopCenter => "top-center",
+            Self::TopRight => "top-right",
+            Self::BottomLeft => "bottom-left",
+            Self::BottomCenter => "bottom-center",
+            Self::BottomRight => "bottom-right",
+        }
+    }
+
+    /// Parses the position from its name made by [Position::as_str], like `top-left`
+    pub fn parse(value: &str) -> Option<Self> {
+        [
+            Self::TopLeft,
+            Self::TopCenter,
+            Self::TopRight,
+            Self::BottomLeft,
+            Self::BottomCenter,
+            Self::BottomRight,
+        ]
+        .into_iter()
+        .find(|v| v.as_str() == value)
+    }
+
+    /// Returns whether the position is at the top edge, where toasts stack downward
+    pub fn is_top(&self) -> bool {
+        matches!(self, Self::TopLeft | Self::TopCenter | Self::TopRight)
+    }
+}
+
+/// Whether widgets reduce motion, like animating view transitions and moving toasts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MotionPolicy {
+    /// Follows `prefers-reduced-motion` of the user
+    #[default]
+    System,
+    /// Always reduces motion
+    Reduce,
+    /// Never reduces motion, for applications with a preference of their own
+    Allow,
+}
+
+/// Defaults of widgets, set with [configure]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlobalConfig {
+    /// How long toasts stay before they are dismissed, or None if they stay until dismissed
+    pub toast_timeout: Option<Duration>,
+    pub toast_position: Position,
+    /// Whether the first toast initializes the global toaster with defaults, if the application
+    /// hasn't initialized it yet
+    pub toast_lazy_initialize: bool,
+    /// Whether focus traps deactivate when the user presses Escape
+    pub deactivate_on_escape: bool,
+    pub motion: MotionPolicy,
+    /// The prefix of generated ids, see [id::set_prefix]
+    pub id_prefix: String,
+    /// The locale of messages, see [i18n::set_locale], or None to keep the current one
+    pub locale: Option<String>,
+}
+
+impl GlobalConfig {
+    pub fn builder() -> GlobalConfigBuilder {
+        GlobalConfigBuilder::new()
+    }
+}
+
+impl Default for GlobalConfig {
+    fn default() -> Self {
+        Self {
+            toast_timeout: Some(Duration::from_secs(4)),
+            toast_position: Position::default(),
+            toast_lazy_initialize: true,
+            deactivate_on_escape: false,
+            motion: MotionPolicy::default(),
+            id_prefix: String::from("seigi"),
+            locale: None,
+        }
+    }
+}
+
+/// A builder struct of [GlobalConfig], starting from the defaults of seigi
+#[derive(Default)]
+pub struct GlobalConfigBuilder {
+    config: GlobalConfig,
+}
+
+impl GlobalConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn toast_timeout(mut self, toast_timeout: Option<Duration>) -> Self {
+        self.config.toast_timeout = toast_timeout;
+        self
+    }
+
+    pub fn toast_position(mut self, toast_position: Position) -> Self {
+        self.config.toast_position = toast_position;
+        self
+    }
+
+    pub fn toast_lazy_initialize(mut self, toast_lazy_initialize: bool) -> Self {
+        self.config.toast_lazy_initialize = toast_lazy_initialize;
+        self
+    }
+
+    pub fn deactivate_on_escape(mut self, deactivate_on_escape: bool) -> Self {
+        self.config.deactivate_on_escape = deactivate_on_escape;
+        self
+    }
+
+    pub fn motion(mut self, motion: MotionPolicy) -> Self {
+        self.config.motion = motion;
+        self
+    }
+
+    pub fn id_prefix(mut self, id_prefix: impl Into<String>) -> Self {
+        self.config.id_prefix = id_prefix.into();
+        self
+    }
+
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.config.locale = Some(locale.into());
+        self
+    }
+
+    pub fn build(self) -> GlobalConfig {
+        self.config
+    }
+}
+
+thread_local! {
+    static CONFIG: RefCell<GlobalConfig> = RefCell::new(GlobalConfig::default());
+}
+
+/// Sets defaults of widgets built afterwards, applying the id prefix and the locale at once
+pub fn configure(config: GlobalConfig) {
+    id::set_prefix(config.id_prefix.clone());
+    if let Some(locale) = &config.locale {
+        i18n::set_locale(locale.clone());
+    }
+    CONFIG.with_borrow_mut(|v| *v = config);
+}
+
+/// Returns the current configuration, with the id prefix and the locale in effect
+pub fn config() -> GlobalConfig {
+    let mut config = CONFIG.with_borrow(|v| v.clone());
+    config.id_prefix = id::prefix();
+    config.locale = Some(i18n::locale());
+    config
+}
+
+/// Returns the policy of motion, which [crate::media::prefers_reduced_motion] follows
+pub fn motion() -> MotionPolicy {
+    CONFIG.with_borrow(|v| v.motion)
+}
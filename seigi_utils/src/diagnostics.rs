@@ -0,0 +1,208 @@
+//! Counts of live resources created by seigi subsystems, for finding leaks in development builds
+//!
+//! With the `diagnostics` feature, subsystems count the closures, event listeners, timeouts and
+//! observers they create with [track], and [report] tells how many of each are alive in every
+//! subsystem. Counts growing as widgets are created and destroyed over and over point at widgets
+//! never dropped, often kept alive by closures `forget`ed in integration code, which can be
+//! counted alike with [track_in].
+//!
+//! Listeners registered with [crate::listener::Listener] and focus moves of [crate::schedule]
+//! are counted in the subsystem calling them, along with observers of widgets and timeouts they
+//! forget. Listeners of `gloo` created by widgets directly aren't counted.
+//!
+//! Without the feature, [Tracked] is empty and counting does nothing, so release builds pay
+//! nothing for it.
+
+use std::fmt::{self, Display};
+
+#[cfg(feature = "diagnostics")]
+use std::{cell::RefCell, collections::BTreeMap};
+
+/// A kind of resource counted by [track]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Resource {
+    /// A closure passed to JavaScript, other than those of the resources below
+    Closure,
+    /// An event listener, along with its closure
+    Listener,
+    /// A timeout or an interval
+    Timeout,
+    /// An observer, like `MutationObserver` and `ResizeObserver`
+    Observer,
+}
+
+impl Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Closure => "closure",
+            Self::Listener => "listener",
+            Self::Timeout => "timeout",
+            Self::Observer => "observer",
+        })
+    }
+}
+
+/// Counts of a resource of a subsystem
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Counts {
+    /// Alive right now, including forgotten ones
+    pub live: u64,
+    /// Created since the page loaded
+    pub created: u64,
+    /// Forgotten by [Tracked::forget], which stay alive for good
+    pub forgotten: u64,
+}
+
+#[cfg(feature = "diagnostics")]
+thread_local! {
+    static COUNTS: RefCell<BTreeMap<(&'static str, Resource), Counts>> =
+        const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Returns whether resources are counted, which is whether the `diagnostics` feature is enabled
+pub const fn is_enabled() -> bool {
+    cfg!(feature = "diagnostics")
+}
+
+/// A resource counted as alive until this is dropped, kept along with the resource
+#[must_use = "the resource is counted as alive only while this is kept"]
+pub struct Tracked {
+    #[cfg(feature = "diagnostics")]
+    key: (&'static str, Resource),
+}
+
+impl Tracked {
+    /// Counts the resource as alive for good, like a closure forgotten along with this
+    pub fn forget(self) {
+        #[cfg(feature = "diagnostics")]
+        COUNTS.with_borrow_mut(|v| v.entry(self.key).or_default().forgotten += 1);
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        #[cfg(feature = "diagnostics")]
+        COUNTS.with_borrow_mut(|v| {
+            let counts = v.entry(self.key).or_default();
+            counts.live = counts.live.saturating_sub(1);
+        });
+    }
+}
+
+/// Names the subsystem of the source file by the package it is in, like `seigi_focus` for
+/// `seigi_focus/src/lib.rs` and for a copy of the package in the registry, or else the file
+fn subsystem(file: &'static str) -> &'static str {
+    let mut package = None;
+    let mut previous = None;
+    for component in file.split(['/', '\\']) {
+        if component == "src" {
+            package = previous;
+        }
+        previous = Some(component);
+    }
+    let Some(package) = package else {
+        return file;
+    };
+    // Packages in the registry are suffixed by their versions, like `seigi_focus-0.3.0`
+    match package.rsplit_once('-') {
+        Some((name, version)) if version.starts_with(|v: char| v.is_ascii_digit()) => name,
+        _ => package,
+    }
+}
+
+/// Counts the resource as created by the subsystem of the caller, named by its package
+#[track_caller]
+pub fn track(resource: Resource) -> Tracked {
+    track_in(subsystem(std::panic::Location::caller().file()), resource)
+}
+
+/// Counts the resource as created by the subsystem
+pub fn track_in(subsystem: &'static str, resource: Resource) -> Tracked {
+    #[cfg(feature = "diagnostics")]
+    {
+        let key = (subsystem, resource);
+        COUNTS.with_borrow_mut(|v| {
+            let counts = v.entry(key).or_default();
+            counts.live += 1;
+            counts.created += 1;
+        });
+        Tracked { key }
+    }
+    #[cfg(not(feature = "diagnostics"))]
+    {
+        let _ = (subsystem, resource);
+        Tracked {}
+    }
+}
+
+/// Counts of a resource of a subsystem in [Report]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub subsystem: &'static str,
+    pub resource: Resource,
+    pub counts: Counts,
+}
+
+/// Counts of resources of every subsystem, taken by [report]
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    /// Sorted by subsystems and then resources
+    pub entries: Vec<Entry>,
+}
+
+impl Report {
+    /// Returns how many of the resource of the subsystem are alive
+    pub fn live(&self, subsystem: &str, resource: Resource) -> u64 {
+        self.entries
+            .iter()
+            .find(|v| v.subsystem == subsystem && v.resource == resource)
+            .map_or(0, |v| v.counts.live)
+    }
+
+    /// Returns how many resources are alive in total
+    pub fn total_live(&self) -> u64 {
+        self.entries.iter().map(|v| v.counts.live).sum()
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            let Counts {
+                live,
+                created,
+                forgotten,
+            } = entry.counts;
+            write!(
+                f,
+                "{} {}: {live} live of {created} created",
+                entry.subsystem, entry.resource
+            )?;
+            if forgotten > 0 {
+                write!(f, ", {forgotten} forgotten")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Takes counts of resources of every subsystem, which are empty unless [is_enabled]
+pub fn report() -> Report {
+    #[cfg(feature = "diagnostics")]
+    {
+        let entries = COUNTS.with_borrow(|v| {
+            v.iter()
+                .map(|(&(subsystem, resource), &counts)| Entry {
+                    subsystem,
+                    resource,
+                    counts,
+                })
+                .collect()
+        });
+        Report { entries }
+    }
+    #[cfg(not(feature = "diagnostics"))]
+    Report::default()
+}
@@ -0,0 +1,172 @@
+//! Resolving the text direction of elements, so arrow keys follow it
+//!
+//! Directions are cached until a `dir` attribute in the document changes, which subscribers are
+//! notified of.
+
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use gloo::utils::{document_element, window};
+use js_sys::Array;
+use wasm_bindgen::{JsCast, prelude::Closure};
+use web_sys::{Element, MutationObserver, MutationObserverInit};
+
+/// The maximum count of elements whose directions are cached
+const CACHE_SIZE: usize = 64;
+
+/// A text direction
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
+impl Direction {
+    pub fn is_rtl(&self) -> bool {
+        *self == Self::Rtl
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ltr => "ltr",
+            Self::Rtl => "rtl",
+        }
+    }
+
+    /// Returns horizontal arrow keys moving to the previous and the next item in the direction
+    ///
+    /// # Examples
+    /// `Direction::Rtl.arrow_keys()` is `("ArrowRight", "ArrowLeft")`
+    pub fn arrow_keys(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::Ltr => ("ArrowLeft", "ArrowRight"),
+            Self::Rtl => ("ArrowRight", "ArrowLeft"),
+        }
+    }
+}
+
+struct Subscriber {
+    callback: Box<dyn Fn()>,
+    handle: u64,
+}
+
+#[derive(Default)]
+struct State {
+    cache: Vec<(Element, Direction)>,
+    subscribers: Vec<Rc<Subscriber>>,
+    /// Observes changes of `dir` attributes, which invalidate the cache
+    observer: Option<MutationObserver>,
+    _callback: Option<Closure<dyn Fn(Array)>>,
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        if let Some(observer) = &self.observer {
+            observer.disconnect();
+        }
+    }
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+fn observe(state: &mut State) {
+    if state.observer.is_some() {
+        return;
+    }
+    let callback = Closure::<dyn Fn(Array)>::new(|_| {
+        let subscribers = STATE.with_borrow_mut(|state| {
+            state.cache.clear();
+            state.subscribers.clone()
+        });
+        for subscriber in subscribers {
+            (subscriber.callback)();
+        }
+    });
+    let Ok(observer) = MutationObserver::new(callback.as_ref().unchecked_ref()) else {
+        return;
+    };
+    let init = MutationObserverInit::new();
+    init.set_attributes(true);
+    init.set_subtree(true);
+    init.set_attribute_filter(&Array::of1(&"dir".into()));
+    let _ = observer.observe_with_options(&document_element(), &init);
+    state.observer = Some(observer);
+    state._callback = Some(callback);
+}
+
+/// Returns the direction of the element from the closest `dir` attribute, or its computed style
+/// if there is none or it is `auto`
+pub fn resolve(element: &Element) -> Direction {
+    if let Some(direction) = STATE.with_borrow(|state| {
+        state
+            .cache
+            .iter()
+            .find(|(v, _)| v == element)
+            .map(|(_, v)| *v)
+    }) {
+        return direction;
+    }
+
+    let attribute = element
+        .closest("[dir]")
+        .ok()
+        .flatten()
+        .and_then(|v| v.get_attribute("dir"))
+        .map(|v| v.to_ascii_lowercase());
+    let is_rtl = match attribute.as_deref() {
+        Some("rtl") => true,
+        Some("ltr") => false,
+        _ => window()
+            .get_computed_style(element)
+            .ok()
+            .flatten()
+            .and_then(|v| v.get_property_value("direction").ok())
+            .is_some_and(|v| v == "rtl"),
+    };
+    let direction = if is_rtl {
+        Direction::Rtl
+    } else {
+        Direction::Ltr
+    };
+
+    STATE.with_borrow_mut(|state| {
+        observe(state);
+        if state.cache.len() >= CACHE_SIZE {
+            state.cache.remove(0);
+        }
+        state.cache.push((element.clone(), direction));
+    });
+    direction
+}
+
+/// Returns whether the element is laid out right-to-left
+pub fn is_rtl(element: &Element) -> bool {
+    resolve(element).is_rtl()
+}
+
+/// Subscribes to changes of `dir` attributes in the document, after which directions should be
+/// resolved again
+///
+/// # Returns
+/// A handle to unsubscribe with [unsubscribe]
+pub fn subscribe(callback: Box<dyn Fn()>) -> u64 {
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+    let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    STATE.with_borrow_mut(|state| {
+        observe(state);
+        state
+            .subscribers
+            .push(Rc::new(Subscriber { callback, handle }));
+    });
+    handle
+}
+
+pub fn unsubscribe(handle: u64) {
+    STATE.with_borrow_mut(|state| state.subscribers.retain(|v| v.handle != handle));
+}
@@ -0,0 +1,13 @@
+//! Errors thrown by browser APIs
+
+use wasm_bindgen::{JsCast, JsValue};
+
+/// Describes a value thrown by a browser API, like `NotSupportedError: ...`
+///
+/// Errors are described by their names and messages, and other values by their string forms.
+pub fn describe(value: &JsValue) -> String {
+    if let Some(error) = value.dyn_ref::<js_sys::Error>() {
+        return format!("{}: {}", error.name(), error.message());
+    }
+    value.as_string().unwrap_or_else(|| format!("{value:?}"))
+}
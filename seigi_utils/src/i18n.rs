@@ -0,0 +1,267 @@
+//! Message catalogs of built-in strings, such as labels and announcements for screen readers
+//!
+//! Widgets look their strings up with [translate] by keys like `pagination.next`. English
+//! messages are built in, and catalogs of other locales are added with [register]. Messages are
+//! templates with arguments in braces, like `Page {page}`.
+//!
+//! Strings are looked up as widgets render them, so widgets created before the locale changes
+//! keep their strings until they render again.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt::Display,
+    rc::Rc,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use gloo::utils::window;
+
+/// The locale of built-in messages, which every lookup falls back to
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Messages of a locale
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Catalog {
+    pub locale: String,
+    pub messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    pub fn new(locale: impl Into<String>) -> Self {
+        Self {
+            locale: locale.into(),
+            messages: HashMap::new(),
+        }
+    }
+
+    /// Sets the message template of the key
+    pub fn message(mut self, key: impl Into<String>, template: impl Into<String>) -> Self {
+        self.messages.insert(key.into(), template.into());
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages.get(key).map(String::as_str)
+    }
+
+    /// Returns the built-in English catalog, listing every key widgets look up
+    pub fn english() -> Self {
+        Self::new(DEFAULT_LOCALE)
+            .message("avatar.overflow", "{count} more")
+            .message("color.area", "Saturation and brightness")
+            .message("color.slider", "2D slider")
+            .message(
+                "dnd.instructions",
+                "To pick up a draggable item, press space or enter. While dragging, use the \
+                arrow keys to move the item. Press space or enter again to drop the item in its \
+                new position, or press escape to cancel.",
+            )
+            .message("dnd.start", "Picked up draggable item {active}.")
+            .message(
+                "dnd.over",
+                "Draggable item {active} was moved over droppable area {over}.",
+            )
+            .message(
+                "dnd.leave",
+                "Draggable item {active} is no longer over a droppable area.",
+            )
+            .message(
+                "dnd.drop",
+                "Draggable item {active} was dropped over droppable area {over}.",
+            )
+            .message("dnd.end", "Draggable item {active} was dropped.")
+            .message(
+                "dnd.cancel",
+                "Dragging was cancelled. Draggable item {active} was dropped.",
+            )
+            .message(
+                "form.error_summary",
+                "There is a problem. Errors to fix: {count}",
+            )
+            .message("form.invalid", "Fields to fix: {count}")
+            .message("pagination.label", "Pagination")
+            .message("pagination.first", "First page")
+            .message("pagination.previous", "Previous page")
+            .message("pagination.next", "Next page")
+            .message("pagination.last", "Last page")
+            .message("pagination.page", "Page {page}")
+            .message("pin.digit", "Digit {index} of {count}")
+            .message("pin.character", "Character {index} of {count}")
+            .message("rating.value", "{value} of {max}")
+            .message("tags.remove", "Remove {tag}")
+            .message("toast.region", "Notifications")
+            .message("toast.group.expand", "Show all {count}")
+            .message("toast.group.collapse", "Show less")
+            .message("toast.group.dismiss", "Dismiss all")
+            .message("toast.offline", "You are offline")
+            .message("validate.required", "This field is required")
+            .message("validate.min_length", "Enter at least {min} characters")
+            .message("validate.max_length", "Enter at most {max} characters")
+            .message("validate.pattern", "Enter a value in the requested format")
+            .message("validate.matches", "Values don't match")
+    }
+}
+
+struct Subscriber {
+    callback: Box<dyn Fn(&str)>,
+    handle: u64,
+}
+
+struct State {
+    catalogs: Vec<Catalog>,
+    locale: String,
+    subscribers: Vec<Rc<Subscriber>>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State {
+        catalogs: vec![Catalog::english()],
+        locale: DEFAULT_LOCALE.to_string(),
+        subscribers: vec![],
+    });
+}
+
+/// Returns the language subtag of the locale, like `pt` of `pt-BR`
+fn language(locale: &str) -> &str {
+    locale.split(['-', '_']).next().unwrap_or(locale)
+}
+
+/// Chooses the available locale best matching requested ones, in the order of preference
+///
+/// A requested locale matches an available one exactly, or by its language, so `pt-BR` matches
+/// `pt` and `en` matches `en-GB`.
+pub fn negotiate<S: AsRef<str>>(requested: &[S], available: &[S]) -> Option<String> {
+    for requested in requested {
+        let requested = requested.as_ref();
+        if let Some(locale) = available
+            .iter()
+            .find(|v| v.as_ref().eq_ignore_ascii_case(requested))
+        {
+            return Some(locale.as_ref().to_string());
+        }
+        if let Some(locale) = available
+            .iter()
+            .find(|v| language(v.as_ref()).eq_ignore_ascii_case(language(requested)))
+        {
+            return Some(locale.as_ref().to_string());
+        }
+    }
+    None
+}
+
+/// Adds the catalog, merging it into one of the same locale
+pub fn register(catalog: Catalog) {
+    STATE.with_borrow_mut(|state| {
+        match state
+            .catalogs
+            .iter_mut()
+            .find(|v| v.locale.eq_ignore_ascii_case(&catalog.locale))
+        {
+            Some(existing) => existing.messages.extend(catalog.messages),
+            None => state.catalogs.push(catalog),
+        }
+    });
+}
+
+/// Returns locales of registered catalogs
+pub fn locales() -> Vec<String> {
+    STATE.with_borrow(|state| state.catalogs.iter().map(|v| v.locale.clone()).collect())
+}
+
+pub fn locale() -> String {
+    STATE.with_borrow(|state| state.locale.clone())
+}
+
+/// Sets the locale messages are looked up in, which needs no catalog of its own as lookups fall
+/// back to its language and then to [DEFAULT_LOCALE]
+pub fn set_locale(locale: impl Into<String>) {
+    let locale = locale.into();
+    let subscribers = STATE.with_borrow_mut(|state| {
+        if state.locale == locale {
+            return vec![];
+        }
+        state.locale = locale.clone();
+        state.subscribers.clone()
+    });
+    for subscriber in subscribers {
+        (subscriber.callback)(&locale);
+    }
+}
+
+/// Sets the locale to the registered one best matching languages of the browser
+pub fn detect() {
+    let languages = window()
+        .navigator()
+        .languages()
+        .iter()
+        .filter_map(|v| v.as_string())
+        .collect::<Vec<_>>();
+    if let Some(locale) = negotiate(&languages, &locales()) {
+        set_locale(locale);
+    }
+}
+
+/// Subscribes to changes of the locale
+///
+/// # Returns
+/// A handle to unsubscribe with [unsubscribe]
+pub fn subscribe(callback: Box<dyn Fn(&str)>) -> u64 {
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+    let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    STATE.with_borrow_mut(|state| {
+        state
+            .subscribers
+            .push(Rc::new(Subscriber { callback, handle }))
+    });
+    handle
+}
+
+pub fn unsubscribe(handle: u64) {
+    STATE.with_borrow_mut(|state| state.subscribers.retain(|v| v.handle != handle));
+}
+
+/// Replaces arguments in braces of the template, leaving unknown ones as they are
+pub fn interpolate(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+        let name = &rest[start + 1..start + end];
+        match args.iter().find(|(v, _)| *v == name) {
+            Some((_, value)) => result.push_str(&value.to_string()),
+            None => result.push_str(&rest[start..=start + end]),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Returns the message of the key in the current locale with arguments replaced
+///
+/// The message is looked up in the locale, its language and [DEFAULT_LOCALE] in order, and the
+/// key itself is returned if none has it.
+pub fn translate(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let template = STATE.with_borrow(|state| {
+        let find = |locale: &str| {
+            state
+                .catalogs
+                .iter()
+                .find(|v| v.locale.eq_ignore_ascii_case(locale))
+                .and_then(|v| v.get(key))
+                .map(String::from)
+        };
+        find(&state.locale)
+            .or_else(|| find(language(&state.locale)))
+            .or_else(|| find(DEFAULT_LOCALE))
+    });
+    match template {
+        Some(template) => interpolate(&template, args),
+        None => key.to_string(),
+    }
+}
@@ -0,0 +1,137 @@
+//! Generating unique ids and wiring aria relationships between elements
+//!
+//! Ids are generated as `{prefix}-{name}-{count}` from a counter shared by every widget. Ids
+//! already in the document, such as those rendered by a server, are skipped, so generated ids
+//! never collide with them.
+
+use std::cell::{Cell, RefCell};
+
+use gloo::utils::document;
+use web_sys::Element;
+
+thread_local! {
+    static PREFIX: RefCell<String> = RefCell::new(String::from("seigi"));
+    static COUNTER: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Sets the prefix of generated ids, which is `seigi` by default
+///
+/// Pages with several apps generating ids should give each a different prefix.
+pub fn set_prefix(prefix: impl Into<String>) {
+    PREFIX.with_borrow_mut(|v| *v = prefix.into());
+}
+
+pub fn prefix() -> String {
+    PREFIX.with_borrow(|v| v.clone())
+}
+
+/// Restarts the counter, so ids are generated in the same sequence again
+pub fn reset() {
+    COUNTER.set(0);
+}
+
+/// Generates an id unused in the document
+///
+/// # Examples
+/// `generate("dialog")` is `"seigi-dialog-0"`, and then `"seigi-dialog-1"`
+pub fn generate(name: &str) -> String {
+    let prefix = prefix();
+    let document = document();
+    loop {
+        let count = COUNTER.get();
+        COUNTER.set(count + 1);
+        let id = format!("{prefix}-{name}-{count}");
+        if document.get_element_by_id(&id).is_none() {
+            return id;
+        }
+    }
+}
+
+/// Returns the id of the element, setting a generated one if it has none
+pub fn ensure(element: &Element, name: &str) -> String {
+    let id = element.id();
+    if !id.is_empty() {
+        return id;
+    }
+    let id = generate(name);
+    element.set_id(&id);
+    id
+}
+
+/// An aria attribute referring to other elements by their ids
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    /// **aria-labelledby**, to elements naming the element
+    LabelledBy,
+    /// **aria-describedby**, to elements describing the element
+    DescribedBy,
+    /// **aria-controls**, to elements whose content or presence the element controls
+    Controls,
+    /// **aria-owns**, to elements that are children of the element outside its subtree
+    Owns,
+    /// **aria-errormessage**, to elements with the error message of the element
+    ErrorMessage,
+    /// **aria-details**, to elements with extended descriptions of the element
+    Details,
+}
+
+impl Relation {
+    pub fn attribute(&self) -> &'static str {
+        match self {
+            Self::LabelledBy => "aria-labelledby",
+            Self::DescribedBy => "aria-describedby",
+            Self::Controls => "aria-controls",
+            Self::Owns => "aria-owns",
+            Self::ErrorMessage => "aria-errormessage",
+            Self::Details => "aria-details",
+        }
+    }
+
+    /// Returns the name ids generated for targets of the relation have
+    fn name(&self) -> &'static str {
+        match self {
+            Self::LabelledBy => "label",
+            Self::DescribedBy => "description",
+            Self::Controls => "controlled",
+            Self::Owns => "owned",
+            Self::ErrorMessage => "error",
+            Self::Details => "details",
+        }
+    }
+}
+
+/// Returns ids the element refers to with the relation
+pub fn related(element: &Element, relation: Relation) -> Vec<String> {
+    element
+        .get_attribute(relation.attribute())
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(String::from)
+        .collect()
+}
+
+/// Adds the target to elements the element refers to with the relation, setting a generated
+/// id to the target if it has none
+///
+/// Targets already referred to aren't added again, so this is safe to call repeatedly.
+pub fn relate(element: &Element, relation: Relation, target: &Element) {
+    let id = ensure(target, relation.name());
+    let mut ids = related(element, relation);
+    if !ids.contains(&id) {
+        ids.push(id);
+        let _ = element.set_attribute(relation.attribute(), &ids.join(" "));
+    }
+}
+
+/// Removes the target from elements the element refers to with the relation, removing the
+/// attribute when none is left
+pub fn unrelate(element: &Element, relation: Relation, target: &Element) {
+    let id = target.id();
+    let mut ids = related(element, relation);
+    ids.retain(|v| *v != id);
+    if ids.is_empty() {
+        let _ = element.remove_attribute(relation.attribute());
+    } else {
+        let _ = element.set_attribute(relation.attribute(), &ids.join(" "));
+    }
+}
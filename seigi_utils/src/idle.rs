@@ -0,0 +1,377 @@
+//! Detecting inactivity of the user, such as for session timeouts
+//!
+//! Activities are pointer, keyboard, wheel and scroll events in the document, and returning to
+//! the page. An [IdleTimer] can prompt before going idle, so a dialog asks whether the user is
+//! still there.
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::{
+    events::{EventListener, EventListenerOptions, EventListenerPhase},
+    timers::callback::Timeout,
+    utils::document,
+};
+
+/// Events counted as activities by default
+pub const ACTIVITY_EVENTS: [&str; 6] = [
+    "pointerdown",
+    "pointermove",
+    "keydown",
+    "wheel",
+    "touchstart",
+    "scroll",
+];
+
+/// A state of [IdleTimer]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum IdleState {
+    #[default]
+    Active,
+    /// The user is about to go idle, and activities no longer reset the timer until
+    /// [IdleTimer::reset] is called
+    Prompted,
+    Idle,
+}
+
+impl IdleState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Prompted => "prompted",
+            Self::Idle => "idle",
+        }
+    }
+}
+
+/// Called with the remaining milliseconds before going idle
+pub type PromptHook = Box<dyn Fn(u32)>;
+pub type IdleHook = Box<dyn Fn()>;
+pub type ActiveHook = Box<dyn Fn()>;
+
+/// Hooks to [IdleTimer]
+#[derive(Default)]
+pub struct IdleHooks {
+    /// Called when the timer starts prompting
+    pub prompt: Option<PromptHook>,
+    /// Called when the user goes idle
+    pub idle: Option<IdleHook>,
+    /// Called when the user becomes active again after prompting or going idle
+    pub active: Option<ActiveHook>,
+}
+
+/// Options of [IdleTimer]
+pub struct IdleTimerOptions {
+    /// The duration in milliseconds without activities before going idle
+    pub threshold: u32,
+    /// The duration in milliseconds before going idle to prompt at, if any
+    pub prompt: Option<u32>,
+    /// Events counted as activities, which defaults to [ACTIVITY_EVENTS]
+    pub events: Vec<String>,
+    /// Whether returning to the page counts as an activity
+    pub visibility: bool,
+    pub hooks: IdleHooks,
+}
+
+impl IdleTimerOptions {
+    pub fn builder() -> IdleTimerOptionsBuilder {
+        IdleTimerOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [IdleTimerOptions]
+pub struct IdleTimerOptionsBuilder {
+    threshold: Option<u32>,
+    prompt: Option<u32>,
+    events: Vec<String>,
+    visibility: bool,
+    hooks: IdleHooks,
+}
+
+impl Default for IdleTimerOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            threshold: None,
+            prompt: None,
+            events: ACTIVITY_EVENTS.iter().map(|v| v.to_string()).collect(),
+            visibility: true,
+            hooks: IdleHooks::default(),
+        }
+    }
+}
+
+impl IdleTimerOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn threshold(mut self, threshold: u32) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    pub fn prompt(mut self, prompt: u32) -> Self {
+        self.prompt = Some(prompt);
+        self
+    }
+
+    pub fn events(mut self, events: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.events = events.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn visibility(mut self, visibility: bool) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: IdleHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [IdleTimerOptions]
+    ///
+    /// # Panics
+    /// This method panics if threshold field is not set, or prompt is longer than threshold
+    pub fn build(self) -> IdleTimerOptions {
+        let threshold = self
+            .threshold
+            .expect("threshold must be set to build IdleTimerOptions");
+        assert!(
+            self.prompt.is_none_or(|v| v <= threshold),
+            "prompt must not be longer than threshold"
+        );
+        IdleTimerOptions {
+            threshold,
+            prompt: self.prompt,
+            events: self.events,
+            visibility: self.visibility,
+            hooks: self.hooks,
+        }
+    }
+}
+
+struct Inner {
+    options: Rc<IdleTimerOptions>,
+    state: IdleState,
+    is_paused: bool,
+    /// The time of the last activity, from `Date.now()`
+    last_activity: f64,
+    timeout: Option<Timeout>,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    /// Returns milliseconds until the next transition, or None if there is no more
+    fn next_transition(&self, now: f64) -> Option<f64> {
+        let elapsed = now - self.last_activity;
+        let threshold = self.options.threshold as f64;
+        match self.state {
+            IdleState::Active => {
+                let at = threshold - self.options.prompt.unwrap_or(0) as f64;
+                Some((at - elapsed).max(0.0))
+            }
+            IdleState::Prompted => Some((threshold - elapsed).max(0.0)),
+            IdleState::Idle => None,
+        }
+    }
+}
+
+/// An instance of idle timer
+///
+/// Timeouts run late in background tabs, so the timer catches up when the page is visible
+/// again, going straight to idle if the threshold passed meanwhile.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+#[derive(Clone)]
+pub struct IdleTimer(Rc<Mutex<Inner>>);
+
+impl IdleTimer {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    pub fn state(&self) -> IdleState {
+        self.0.lock().unwrap().state
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.state() == IdleState::Idle
+    }
+
+    /// Returns the time of the last activity, from `Date.now()`
+    pub fn last_activity(&self) -> f64 {
+        self.0.lock().unwrap().last_activity
+    }
+
+    /// Returns milliseconds remaining before going idle
+    pub fn remaining(&self) -> u32 {
+        let inner = self.0.lock().unwrap();
+        let elapsed = js_sys::Date::now() - inner.last_activity;
+        (inner.options.threshold as f64 - elapsed).max(0.0) as u32
+    }
+
+    /// Marks the user active and restarts the timer, which dismisses a prompt
+    pub fn reset(&self) {
+        let was_active = {
+            let mut inner = self.0.lock().unwrap();
+            inner.last_activity = js_sys::Date::now();
+            std::mem::replace(&mut inner.state, IdleState::Active) == IdleState::Active
+        };
+        self.schedule();
+        if !was_active {
+            let options = self.0.lock().unwrap().options.clone();
+            if let Some(hook) = &options.hooks.active {
+                hook();
+            }
+        }
+    }
+
+    /// Stops the timer, until [IdleTimer::resume]
+    pub fn pause(&self) {
+        let mut inner = self.0.lock().unwrap();
+        inner.is_paused = true;
+        inner.timeout = None;
+    }
+
+    /// Restarts the timer stopped with [IdleTimer::pause], from the time it is resumed
+    pub fn resume(&self) {
+        {
+            let mut inner = self.0.lock().unwrap();
+            if !inner.is_paused {
+                return;
+            }
+            inner.is_paused = false;
+        }
+        self.reset();
+    }
+
+    fn activity(&self) {
+        let state = {
+            let mut inner = self.0.lock().unwrap();
+            if inner.is_paused {
+                return;
+            }
+            if inner.state == IdleState::Active {
+                // The timeout checks the time once it fires, so frequent activities don't
+                // restart it
+                inner.last_activity = js_sys::Date::now();
+                return;
+            }
+            inner.state
+        };
+        if state == IdleState::Idle {
+            self.reset();
+        }
+    }
+
+    /// Catches up with the time, as timeouts may have run late
+    fn check(&self) {
+        let now = js_sys::Date::now();
+        let transitions = {
+            let mut inner = self.0.lock().unwrap();
+            if inner.is_paused {
+                return;
+            }
+            let mut transitions = vec![];
+            while let Some(remaining) = inner.next_transition(now)
+                && remaining <= 0.0
+            {
+                inner.state = match inner.state {
+                    IdleState::Active if inner.options.prompt.is_some() => IdleState::Prompted,
+                    _ => IdleState::Idle,
+                };
+                transitions.push(inner.state);
+            }
+            transitions
+        };
+        self.schedule();
+
+        let options = self.0.lock().unwrap().options.clone();
+        for state in transitions {
+            match state {
+                IdleState::Prompted => {
+                    if let Some(hook) = &options.hooks.prompt {
+                        hook(self.remaining());
+                    }
+                }
+                IdleState::Idle => {
+                    if let Some(hook) = &options.hooks.idle {
+                        hook();
+                    }
+                }
+                IdleState::Active => {}
+            }
+        }
+    }
+
+    fn schedule(&self) {
+        let weak = Rc::downgrade(&self.0);
+        let mut inner = self.0.lock().unwrap();
+        inner.timeout = inner.next_transition(js_sys::Date::now()).map(|remaining| {
+            Timeout::new(remaining.ceil() as u32, move || {
+                if let Some(timer) = IdleTimer::from_weak(&weak) {
+                    timer.check();
+                }
+            })
+        });
+    }
+}
+
+pub fn create_idle_timer(options: IdleTimerOptions) -> IdleTimer {
+    let options = Rc::new(options);
+    let timer = IdleTimer(Rc::new(Mutex::new(Inner {
+        options: options.clone(),
+        state: IdleState::Active,
+        is_paused: false,
+        last_activity: js_sys::Date::now(),
+        timeout: None,
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&timer.0);
+    let document = document();
+    let mut listeners = options
+        .events
+        .iter()
+        .map(|event| {
+            let weak = weak.clone();
+            EventListener::new_with_options(
+                &document,
+                event.clone(),
+                EventListenerOptions {
+                    phase: EventListenerPhase::Capture,
+                    passive: true,
+                },
+                move |_| {
+                    if let Some(timer) = IdleTimer::from_weak(&weak) {
+                        timer.activity();
+                    }
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+    listeners.push(EventListener::new(&document, "visibilitychange", {
+        let document = document.clone();
+        move |_| {
+            let Some(timer) = IdleTimer::from_weak(&weak) else {
+                return;
+            };
+            if document.hidden() {
+                return;
+            }
+            timer.check();
+            if timer.0.lock().unwrap().options.visibility {
+                timer.activity();
+            }
+        }
+    }));
+
+    timer.0.lock().unwrap()._listeners = listeners;
+    timer.schedule();
+    timer
+}
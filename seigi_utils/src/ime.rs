@@ -0,0 +1,14 @@
+//! Text composed with input method editors, like Japanese and Korean input
+//!
+//! While an IME composes text, key presses like Enter, Escape and arrow keys pick and confirm
+//! candidates instead of reaching the page as usual, so widgets handling keys must leave them be.
+
+use web_sys::KeyboardEvent;
+
+/// Returns whether the key press is part of IME composition
+///
+/// Besides `isComposing`, Safari reports the key press confirming the composition with the
+/// legacy `keyCode` 229 alone.
+pub fn is_composing(event: &KeyboardEvent) -> bool {
+    event.is_composing() || event.key_code() == 229
+}
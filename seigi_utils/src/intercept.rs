@@ -0,0 +1,23 @@
+//! Intercepting events primitives handle, ahead of their own logic
+//!
+//! Primitives taking an [Interceptor], like focus traps, call it with each event they are about
+//! to handle. Consuming the event leaves it to the interceptor, which builds behaviors the
+//! primitive has no option for, like Tab on the last field submitting a form, without forking
+//! the primitive. Interceptors are called while primitives are unlocked, so they can call back
+//! into them.
+
+use web_sys::Event;
+
+/// What an [Interceptor] did with an event
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EventOutcome {
+    /// The primitive handles the event as usual
+    #[default]
+    Continue,
+    /// The interceptor handled the event, so the primitive leaves it alone, including its
+    /// default action unless the interceptor prevents it
+    Consumed,
+}
+
+/// A hook called with events before primitives handle them
+pub type Interceptor = Box<dyn Fn(&Event) -> EventOutcome>;
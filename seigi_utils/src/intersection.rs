@@ -0,0 +1,346 @@
+//! Watching elements enter and leave the viewport or a scroll container
+//!
+//! An [Intersection] wraps IntersectionObserver to call hooks when observed elements enter and
+//! leave the root, instead of leaving callers to compare entries against previous ones.
+
+use std::{
+    fmt::Display,
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use js_sys::Array;
+use wasm_bindgen::{JsCast, JsValue, prelude::Closure};
+use web_sys::{Element, IntersectionObserver, IntersectionObserverEntry, IntersectionObserverInit};
+
+use crate::diagnostics::{self, Resource, Tracked};
+
+/// A length of [RootMargin]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+    Px(f64),
+    /// Percentage of the size of the root
+    Percent(f64),
+}
+
+impl Default for Length {
+    fn default() -> Self {
+        Self::Px(0.0)
+    }
+}
+
+impl Display for Length {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Px(v) => write!(f, "{v}px"),
+            Self::Percent(v) => write!(f, "{v}%"),
+        }
+    }
+}
+
+/// Margins growing the root, or shrinking it if negative, before intersections are computed
+///
+/// Positive margins report elements shortly before they scroll into view, which gives lazily
+/// loaded content time to load.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RootMargin {
+    pub top: Length,
+    pub right: Length,
+    pub bottom: Length,
+    pub left: Length,
+}
+
+impl RootMargin {
+    pub fn new(top: Length, right: Length, bottom: Length, left: Length) -> Self {
+        Self {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+
+    pub fn all(length: Length) -> Self {
+        Self::new(length, length, length, length)
+    }
+
+    pub fn symmetric(vertical: Length, horizontal: Length) -> Self {
+        Self::new(vertical, horizontal, vertical, horizontal)
+    }
+
+    /// Grows the root only along the vertical axis, such as for lists scrolling vertically
+    pub fn vertical(length: Length) -> Self {
+        Self::symmetric(length, Length::default())
+    }
+
+    /// Grows the root only along the horizontal axis, such as for carousels
+    pub fn horizontal(length: Length) -> Self {
+        Self::symmetric(Length::default(), length)
+    }
+}
+
+impl Display for RootMargin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.top, self.right, self.bottom, self.left
+        )
+    }
+}
+
+pub type IntersectionHook = Box<dyn Fn(&IntersectionObserverEntry)>;
+
+/// Hooks to [Intersection]
+#[derive(Default)]
+pub struct IntersectionHooks {
+    /// Called when an element enters the root
+    pub enter: Option<IntersectionHook>,
+    /// Called when an element that entered leaves the root
+    pub leave: Option<IntersectionHook>,
+    /// Called with every entry, including crossings of thresholds while inside the root
+    pub change: Option<IntersectionHook>,
+}
+
+/// Options of [Intersection]
+pub struct IntersectionOptions {
+    /// The scroll container intersections are computed against, which defaults to the viewport
+    pub root: Option<Element>,
+    pub root_margin: RootMargin,
+    /// Ratios of visible area of elements at which entries are reported, where the smallest is
+    /// the ratio elements enter at
+    pub thresholds: Vec<f64>,
+    /// Whether elements are unobserved once they entered, such as for lazy loading
+    pub once: bool,
+    pub hooks: IntersectionHooks,
+}
+
+impl IntersectionOptions {
+    pub fn builder() -> IntersectionOptionsBuilder {
+        IntersectionOptionsBuilder::new()
+    }
+}
+
+impl Default for IntersectionOptions {
+    fn default() -> Self {
+        IntersectionOptionsBuilder::new().build()
+    }
+}
+
+/// A builder struct of [IntersectionOptions]
+#[derive(Default)]
+pub struct IntersectionOptionsBuilder {
+    root: Option<Element>,
+    root_margin: RootMargin,
+    thresholds: Vec<f64>,
+    once: bool,
+    hooks: IntersectionHooks,
+}
+
+impl IntersectionOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn root(mut self, root: Element) -> Self {
+        self.root = Some(root);
+        self
+    }
+
+    pub fn root_margin(mut self, root_margin: RootMargin) -> Self {
+        self.root_margin = root_margin;
+        self
+    }
+
+    pub fn threshold(mut self, threshold: f64) -> Self {
+        self.thresholds.push(threshold.clamp(0.0, 1.0));
+        self
+    }
+
+    pub fn once(mut self, once: bool) -> Self {
+        self.once = once;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: IntersectionHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [IntersectionOptions], with a threshold of 0 if none is added
+    pub fn build(self) -> IntersectionOptions {
+        let mut thresholds = self.thresholds;
+        if thresholds.is_empty() {
+            thresholds.push(0.0);
+        }
+        thresholds.sort_by(f64::total_cmp);
+        IntersectionOptions {
+            root: self.root,
+            root_margin: self.root_margin,
+            thresholds,
+            once: self.once,
+            hooks: self.hooks,
+        }
+    }
+}
+
+struct Inner {
+    options: Rc<IntersectionOptions>,
+    observer: IntersectionObserver,
+    /// Observed elements currently inside the root
+    intersecting: Vec<Element>,
+    _callback: Closure<dyn Fn(Array)>,
+    /// Counts the observer in diagnostics while alive
+    _tracked: Tracked,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
+
+/// An instance of intersection observer
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+#[derive(Clone)]
+pub struct Intersection(Rc<Mutex<Inner>>);
+
+impl Intersection {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    pub fn observe(&self, element: &Element) {
+        self.0.lock().unwrap().observer.observe(element);
+    }
+
+    pub fn unobserve(&self, element: &Element) {
+        let mut inner = self.0.lock().unwrap();
+        inner.observer.unobserve(element);
+        inner.intersecting.retain(|v| v != element);
+    }
+
+    /// Observes the element again, so it enters again if it is still inside the root
+    ///
+    /// Observers only report changes, so an element kept inside the root as content around it
+    /// grows never enters again otherwise.
+    pub fn refresh(&self, element: &Element) {
+        let mut inner = self.0.lock().unwrap();
+        inner.observer.unobserve(element);
+        inner.intersecting.retain(|v| v != element);
+        inner.observer.observe(element);
+    }
+
+    /// Stops observing every element
+    pub fn disconnect(&self) {
+        let mut inner = self.0.lock().unwrap();
+        inner.observer.disconnect();
+        inner.intersecting.clear();
+    }
+
+    pub fn is_intersecting(&self, element: &Element) -> bool {
+        self.0.lock().unwrap().intersecting.contains(element)
+    }
+
+    /// Returns observed elements currently inside the root
+    pub fn intersecting(&self) -> Vec<Element> {
+        self.0.lock().unwrap().intersecting.clone()
+    }
+
+    fn handle(&self, entries: Array) {
+        let options = self.0.lock().unwrap().options.clone();
+        let threshold = options.thresholds[0];
+        for entry in entries.iter() {
+            let entry = entry.unchecked_into::<IntersectionObserverEntry>();
+            let target = entry.target();
+            let is_inside = entry.is_intersecting() && entry.intersection_ratio() >= threshold;
+            let was_inside = {
+                let mut inner = self.0.lock().unwrap();
+                let was_inside = inner.intersecting.contains(&target);
+                if is_inside && !was_inside {
+                    inner.intersecting.push(target.clone());
+                } else if !is_inside && was_inside {
+                    inner.intersecting.retain(|v| *v != target);
+                }
+                was_inside
+            };
+
+            if let Some(hook) = &options.hooks.change {
+                hook(&entry);
+            }
+            if is_inside && !was_inside {
+                if options.once {
+                    self.unobserve(&target);
+                }
+                if let Some(hook) = &options.hooks.enter {
+                    hook(&entry);
+                }
+            } else if !is_inside
+                && was_inside
+                && let Some(hook) = &options.hooks.leave
+            {
+                hook(&entry);
+            }
+        }
+    }
+}
+
+pub fn create_intersection(options: IntersectionOptions) -> Intersection {
+    let inner = Rc::new_cyclic(|weak: &Weak<Mutex<Inner>>| {
+        let callback: Closure<dyn Fn(Array)> = Closure::new({
+            let weak = weak.clone();
+            move |entries: Array| {
+                if let Some(intersection) = Intersection::from_weak(&weak) {
+                    intersection.handle(entries);
+                }
+            }
+        });
+
+        let init = IntersectionObserverInit::new();
+        init.set_root(options.root.as_ref());
+        init.set_root_margin(&options.root_margin.to_string());
+        let thresholds = options
+            .thresholds
+            .iter()
+            .map(|v| JsValue::from_f64(*v))
+            .collect::<Array>();
+        init.set_threshold(&thresholds);
+        let observer =
+            IntersectionObserver::new_with_options(callback.as_ref().unchecked_ref(), &init)
+                .expect("failed to create intersection observer");
+
+        Mutex::new(Inner {
+            options: Rc::new(options),
+            observer,
+            intersecting: vec![],
+            _callback: callback,
+            _tracked: diagnostics::track(Resource::Observer),
+        })
+    });
+    Intersection(inner)
+}
+
+/// Calls the callback once the element enters the viewport, grown by the margin
+///
+/// # Returns
+/// The intersection observing the element, which stops observing when dropped
+pub fn on_visible(
+    element: &Element,
+    root_margin: RootMargin,
+    callback: Box<dyn Fn()>,
+) -> Intersection {
+    let intersection = create_intersection(
+        IntersectionOptions::builder()
+            .root_margin(root_margin)
+            .once(true)
+            .hooks(IntersectionHooks {
+                enter: Some(Box::new(move |_| callback())),
+                ..Default::default()
+            })
+            .build(),
+    );
+    intersection.observe(element);
+    intersection
+}
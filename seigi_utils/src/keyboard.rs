@@ -0,0 +1,136 @@
+//! The on-screen keyboard of touch devices, which covers the bottom of the page while shown
+//!
+//! The height of the keyboard is read from the VirtualKeyboard API where it is supported, and
+//! otherwise from how much the visual viewport is shorter than the layout viewport. Widgets
+//! subscribe to it to keep focused elements visible above the keyboard.
+
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use gloo::utils::window;
+use js_sys::Reflect;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::DomRect;
+
+use crate::listener::Listener;
+
+struct Subscriber {
+    callback: Box<dyn Fn(f64)>,
+    handle: u64,
+}
+
+struct State {
+    subscribers: Vec<Rc<Subscriber>>,
+    /// The height last published
+    height: f64,
+    /// Listens to changes while there are subscribers
+    listener: Option<Listener>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = const {
+        RefCell::new(State {
+            subscribers: vec![],
+            height: 0.0,
+            listener: None,
+        })
+    };
+}
+
+/// Returns `navigator.virtualKeyboard`, where supported
+fn virtual_keyboard() -> Option<JsValue> {
+    Reflect::get(&window().navigator(), &JsValue::from_str("virtualKeyboard"))
+        .ok()
+        .filter(|v| v.is_object())
+}
+
+/// Returns the height of the on-screen keyboard in CSS pixels, which is 0 while it is hidden
+pub fn height() -> f64 {
+    let rect = virtual_keyboard()
+        .and_then(|v| Reflect::get(&v, &JsValue::from_str("boundingRect")).ok())
+        .and_then(|v| v.dyn_into::<DomRect>().ok());
+    if let Some(rect) = rect
+        && rect.height() > 0.0
+    {
+        return rect.height();
+    }
+
+    let window = window();
+    let Some(viewport) = window.visual_viewport() else {
+        return 0.0;
+    };
+    let Some(layout) = window.inner_height().ok().and_then(|v| v.as_f64()) else {
+        return 0.0;
+    };
+    // The visual viewport shrinks by the scale while pinch-zoomed too, which isn't the keyboard
+    let inset = layout - viewport.height() * viewport.scale();
+    if inset < 1.0 { 0.0 } else { inset.round() }
+}
+
+/// Returns whether the on-screen keyboard is shown
+pub fn is_visible() -> bool {
+    height() > 0.0
+}
+
+fn publish() {
+    let height = height();
+    let subscribers = STATE.with_borrow_mut(|state| {
+        if state.height == height {
+            return vec![];
+        }
+        state.height = height;
+        state.subscribers.clone()
+    });
+    for subscriber in subscribers {
+        (subscriber.callback)(height);
+    }
+}
+
+fn listen() -> Listener {
+    let mut listener = Listener::new();
+    if let Some(viewport) = window().visual_viewport() {
+        listener.on(&viewport, "resize", |_| publish());
+    }
+    if let Some(keyboard) = virtual_keyboard() {
+        listener.on(keyboard.unchecked_ref(), "geometrychange", |_| publish());
+    }
+    listener
+}
+
+/// Subscribes to changes of the height of the on-screen keyboard
+///
+/// The callback is called with the height, which is 0 once the keyboard is hidden.
+///
+/// # Returns
+/// A handle to unsubscribe with [unsubscribe]
+pub fn subscribe(callback: Box<dyn Fn(f64)>) -> u64 {
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+    let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    let is_first = STATE.with_borrow_mut(|state| {
+        state
+            .subscribers
+            .push(Rc::new(Subscriber { callback, handle }));
+        state.listener.is_none()
+    });
+    if is_first {
+        let listener = listen();
+        let height = height();
+        STATE.with_borrow_mut(|state| {
+            state.listener = Some(listener);
+            state.height = height;
+        });
+    }
+    handle
+}
+
+pub fn unsubscribe(handle: u64) {
+    STATE.with_borrow_mut(|state| {
+        state.subscribers.retain(|v| v.handle != handle);
+        if state.subscribers.is_empty() {
+            state.listener = None;
+        }
+    });
+}
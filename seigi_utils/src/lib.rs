@@ -0,0 +1,28 @@
+//! Utilities shared by seigi crates
+
+pub mod announcer;
+pub mod audit;
+pub mod clipboard;
+pub mod config;
+pub mod diagnostics;
+pub mod direction;
+pub mod error;
+pub mod i18n;
+pub mod id;
+pub mod idle;
+pub mod ime;
+pub mod intercept;
+pub mod intersection;
+pub mod keyboard;
+pub mod listener;
+pub mod media;
+pub mod outside;
+pub mod persist;
+pub mod position;
+pub mod presence;
+pub mod refs;
+pub mod schedule;
+pub mod scroll_lock;
+pub mod top_layer;
+pub mod trace;
+pub mod view_transitions;
@@ -0,0 +1,143 @@
+//! DOM event listeners removed together with an AbortSignal
+//!
+//! A [Listener] registers any number of listeners on any targets with the signal of its own
+//! AbortController, and aborting it removes them all at once. It owns the closures of the
+//! listeners, so they live exactly as long as their registrations, without `forget`ting them
+//! or keeping each of them around to remove it later.
+
+use wasm_bindgen::{JsCast, closure::Closure};
+use web_sys::{AbortController, AbortSignal, AddEventListenerOptions, Event, EventTarget};
+
+use crate::diagnostics::{self, Resource, Tracked};
+
+/// Options of a listener registered by [Listener::on_with_options]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ListenerOptions {
+    /// Whether the listener is called in the capture phase
+    pub capture: bool,
+    /// Whether the listener never prevents default, which lets browsers scroll without waiting
+    /// for it
+    pub passive: bool,
+    /// Whether the listener is removed after it is called once
+    pub once: bool,
+}
+
+impl ListenerOptions {
+    /// Returns options of a listener called in the capture phase
+    pub fn capture() -> Self {
+        Self {
+            capture: true,
+            ..Self::default()
+        }
+    }
+
+    /// Returns options of a passive listener
+    pub fn passive() -> Self {
+        Self {
+            passive: true,
+            ..Self::default()
+        }
+    }
+}
+
+type Callback = Closure<dyn FnMut(&Event)>;
+
+fn controller() -> AbortController {
+    AbortController::new().expect("failed to create AbortController")
+}
+
+/// A set of DOM event listeners, which are removed when aborted or dropped
+///
+/// # Examples
+/// A widget keeps a [Listener] in its state, registers listeners when activated, and aborts it
+/// when deactivated.
+pub struct Listener {
+    controller: AbortController,
+    closures: Vec<(Callback, Tracked)>,
+}
+
+impl Default for Listener {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Listener {
+    /// Creates a listener without registrations
+    ///
+    /// # Panics
+    /// This method panics if AbortController can't be created, which every browser supporting
+    /// WebAssembly can
+    pub fn new() -> Self {
+        Self {
+            controller: controller(),
+            closures: vec![],
+        }
+    }
+
+    /// Registers the callback to the event of the target
+    #[track_caller]
+    pub fn on(
+        &mut self,
+        target: &EventTarget,
+        event_type: &str,
+        callback: impl FnMut(&Event) + 'static,
+    ) -> &mut Self {
+        self.on_with_options(target, event_type, ListenerOptions::default(), callback)
+    }
+
+    /// Registers the callback to the event of the target with the options
+    ///
+    /// The listener is counted by [diagnostics] in the subsystem of the caller.
+    #[track_caller]
+    pub fn on_with_options(
+        &mut self,
+        target: &EventTarget,
+        event_type: &str,
+        options: ListenerOptions,
+        callback: impl FnMut(&Event) + 'static,
+    ) -> &mut Self {
+        let closure: Callback = Closure::new(callback);
+        let init = AddEventListenerOptions::new();
+        init.set_capture(options.capture);
+        init.set_passive(options.passive);
+        init.set_once(options.once);
+        init.set_signal(&self.controller.signal());
+        let _ = target.add_event_listener_with_callback_and_add_event_listener_options(
+            event_type,
+            closure.as_ref().unchecked_ref(),
+            &init,
+        );
+        self.closures
+            .push((closure, diagnostics::track(Resource::Listener)));
+        self
+    }
+
+    /// Returns the signal listeners are registered with, which can also cancel other work tied
+    /// to them such as fetches
+    ///
+    /// The signal is replaced by a new one when aborted.
+    pub fn signal(&self) -> AbortSignal {
+        self.controller.signal()
+    }
+
+    /// Returns whether there are listeners registered since created or last aborted
+    pub fn is_empty(&self) -> bool {
+        self.closures.is_empty()
+    }
+
+    /// Removes every listener, while new ones can still be registered
+    pub fn abort(&mut self) {
+        self.controller.abort();
+        self.controller = controller();
+        // Closures running right now, such as one aborting its own listener, are freed by
+        // wasm-bindgen once they return
+        self.closures.clear();
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        self.controller.abort();
+    }
+}
@@ -0,0 +1,194 @@
+//! Subscribing to media queries, such as user preferences of motion and color scheme
+//!
+//! Queries of user preferences are shared, so widgets consulting them don't each match the
+//! media again.
+
+use std::{
+    cell::OnceCell,
+    rc::{Rc, Weak},
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use gloo::{events::EventListener, utils::window};
+use web_sys::MediaQueryList;
+
+use crate::config::{self, MotionPolicy};
+
+struct Subscriber {
+    callback: Box<dyn Fn(bool)>,
+    handle: u64,
+}
+
+struct Inner {
+    list: MediaQueryList,
+    subscribers: Vec<Rc<Subscriber>>,
+    /// Listens to changes while there are subscribers
+    _listener: Option<EventListener>,
+}
+
+/// A media query whose matching is subscribed to
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+#[derive(Clone)]
+pub struct MediaQuery(Rc<Mutex<Inner>>);
+
+impl MediaQuery {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    /// Matches the media query, like `(min-width: 768px)`
+    ///
+    /// # Panics
+    /// This method panics if the query can't be matched, which browsers only refuse for invalid
+    /// syntax
+    pub fn new(query: &str) -> Self {
+        let list = window()
+            .match_media(query)
+            .ok()
+            .flatten()
+            .expect("failed to match media");
+        Self(Rc::new(Mutex::new(Inner {
+            list,
+            subscribers: vec![],
+            _listener: None,
+        })))
+    }
+
+    /// Returns the query, serialized by the browser
+    pub fn query(&self) -> String {
+        self.0.lock().unwrap().list.media()
+    }
+
+    pub fn matches(&self) -> bool {
+        self.0.lock().unwrap().list.matches()
+    }
+
+    /// Subscribes to changes of whether the query matches
+    ///
+    /// # Returns
+    /// A handle to unsubscribe with [MediaQuery::unsubscribe]
+    pub fn subscribe(&self, callback: Box<dyn Fn(bool)>) -> u64 {
+        static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+        let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        let mut inner = self.0.lock().unwrap();
+        inner
+            .subscribers
+            .push(Rc::new(Subscriber { callback, handle }));
+        if inner._listener.is_none() {
+            let weak = Rc::downgrade(&self.0);
+            inner._listener = Some(EventListener::new(&inner.list, "change", move |_| {
+                if let Some(query) = MediaQuery::from_weak(&weak) {
+                    query.publish();
+                }
+            }));
+        }
+        handle
+    }
+
+    pub fn unsubscribe(&self, handle: u64) {
+        let mut inner = self.0.lock().unwrap();
+        inner.subscribers.retain(|v| v.handle != handle);
+        if inner.subscribers.is_empty() {
+            inner._listener = None;
+        }
+    }
+
+    fn publish(&self) {
+        let (subscribers, matches) = {
+            let inner = self.0.lock().unwrap();
+            (inner.subscribers.clone(), inner.list.matches())
+        };
+        for subscriber in subscribers {
+            (subscriber.callback)(matches);
+        }
+    }
+}
+
+thread_local! {
+    static REDUCED_MOTION: OnceCell<MediaQuery> = const { OnceCell::new() };
+    static DARK_SCHEME: OnceCell<MediaQuery> = const { OnceCell::new() };
+    static COARSE_POINTER: OnceCell<MediaQuery> = const { OnceCell::new() };
+}
+
+/// Returns the query matching while the user prefers reduced motion
+pub fn reduced_motion() -> MediaQuery {
+    REDUCED_MOTION.with(|cell| {
+        cell.get_or_init(|| MediaQuery::new("(prefers-reduced-motion: reduce)"))
+            .clone()
+    })
+}
+
+/// Returns whether the user prefers reduced motion, or whether motion is reduced regardless by
+/// [config::MotionPolicy]
+pub fn prefers_reduced_motion() -> bool {
+    match config::motion() {
+        MotionPolicy::System => reduced_motion().matches(),
+        MotionPolicy::Reduce => true,
+        MotionPolicy::Allow => false,
+    }
+}
+
+/// A color scheme the user prefers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+impl ColorScheme {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Light => "light",
+            Self::Dark => "dark",
+        }
+    }
+}
+
+/// Returns the query matching while the user prefers the dark color scheme
+pub fn dark_scheme() -> MediaQuery {
+    DARK_SCHEME.with(|cell| {
+        cell.get_or_init(|| MediaQuery::new("(prefers-color-scheme: dark)"))
+            .clone()
+    })
+}
+
+/// Returns the color scheme the user prefers
+pub fn color_scheme() -> ColorScheme {
+    if dark_scheme().matches() {
+        ColorScheme::Dark
+    } else {
+        ColorScheme::Light
+    }
+}
+
+/// Subscribes to changes of the color scheme the user prefers
+///
+/// # Returns
+/// A handle to unsubscribe with [MediaQuery::unsubscribe] of [dark_scheme]
+pub fn subscribe_color_scheme(callback: Box<dyn Fn(ColorScheme)>) -> u64 {
+    dark_scheme().subscribe(Box::new(move |is_dark| {
+        callback(if is_dark {
+            ColorScheme::Dark
+        } else {
+            ColorScheme::Light
+        })
+    }))
+}
+
+/// Returns the query matching while the primary pointer is coarse, such as a finger
+pub fn coarse_pointer() -> MediaQuery {
+    COARSE_POINTER.with(|cell| {
+        cell.get_or_init(|| MediaQuery::new("(pointer: coarse)"))
+            .clone()
+    })
+}
+
+/// Returns whether the primary pointer is coarse, such as a finger
+pub fn is_coarse_pointer() -> bool {
+    coarse_pointer().matches()
+}
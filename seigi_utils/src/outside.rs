@@ -0,0 +1,169 @@
+//! Zones which stay interactive beside modal and light-dismissed widgets
+//!
+//! Focus traps keep focus and pointer presses inside their targets, and widgets like hover cards
+//! close on presses outside them. Regions shown beside every widget, such as the toast region,
+//! are registered with [allow] so interacting with them is neither blocked by traps nor dismisses
+//! anything.
+//!
+//! The registry is shared by every trap and dismissable widget, so an application registers
+//! such a region once. Regions inserted by other scripts, like cookie banners, are registered
+//! ahead by selectors with [allow_selector], and regions in markup are marked with
+//! [TRUSTED_ATTRIBUTE] instead.
+//!
+//! Modal widgets may also hide the rest of the page from assistive technologies with
+//! [hide_others]. Allowed zones and live regions, like those of [crate::announcer], are left in
+//! the accessibility tree, so toasts shown beside a dialog are still announced.
+
+use std::{
+    cell::RefCell,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use wasm_bindgen::JsCast;
+use web_sys::{Element, Node};
+
+/// Live regions, which [hide_others] leaves announcing beside modal widgets
+const LIVE_REGION_SELECTOR: &str =
+    "[aria-live]:not([aria-live=off]), [role=status], [role=alert], [role=log]";
+
+/// Marks elements in markup as allowed zones, like [allow] does
+pub const TRUSTED_ATTRIBUTE: &str = "data-seigi-trusted";
+
+/// An allowed zone
+enum Zone {
+    Element(Element),
+    /// Every element matching the selector, including those inserted later
+    Selector(String),
+}
+
+impl Zone {
+    fn contains(&self, element: &Element) -> bool {
+        match self {
+            Self::Element(zone) => zone.contains(Some(element)),
+            Self::Selector(selector) => element.closest(selector).ok().flatten().is_some(),
+        }
+    }
+
+    /// Returns whether a zone is inside the element, without being the element itself
+    fn is_inside(&self, element: &Element) -> bool {
+        match self {
+            Self::Element(zone) => zone != element && element.contains(Some(zone)),
+            Self::Selector(selector) => element.query_selector(selector).ok().flatten().is_some(),
+        }
+    }
+}
+
+thread_local! {
+    static ZONES: RefCell<Vec<(u64, Zone)>> = const { RefCell::new(vec![]) };
+}
+
+fn register(zone: Zone) -> u64 {
+    static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+    let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    ZONES.with_borrow_mut(|v| v.push((handle, zone)));
+    handle
+}
+
+/// Allows interacting with the element and its descendants while widgets are modal
+///
+/// # Returns
+/// A handle to disallow it again with [disallow]
+pub fn allow(element: &Element) -> u64 {
+    register(Zone::Element(element.clone()))
+}
+
+/// Allows interacting with elements matching the selector and their descendants while widgets
+/// are modal, including elements inserted after, like a cookie banner of another script
+///
+/// # Returns
+/// A handle to disallow it again with [disallow]
+pub fn allow_selector(selector: impl Into<String>) -> u64 {
+    register(Zone::Selector(selector.into()))
+}
+
+/// Disallows the zone of the handle returned by [allow] or [allow_selector]
+pub fn disallow(handle: u64) {
+    ZONES.with_borrow_mut(|v| v.retain(|(zone, _)| *zone != handle));
+}
+
+/// Returns whether the node is in a zone allowed by [allow] or [allow_selector], or marked with
+/// [TRUSTED_ATTRIBUTE]
+pub fn is_allowed(node: &Node) -> bool {
+    let element = match node.dyn_ref::<Element>() {
+        Some(element) => element.clone(),
+        None => match node.parent_element() {
+            Some(parent) => parent,
+            None => return false,
+        },
+    };
+    element
+        .closest(&format!("[{TRUSTED_ATTRIBUTE}]"))
+        .ok()
+        .flatten()
+        .is_some()
+        || ZONES.with_borrow(|v| v.iter().any(|(_, zone)| zone.contains(&element)))
+}
+
+/// Elements hidden by [hide_others], which are shown to assistive technologies again when dropped
+#[must_use = "elements are shown again when dropped"]
+pub struct HiddenOthers {
+    hidden: Vec<Element>,
+}
+
+impl Drop for HiddenOthers {
+    fn drop(&mut self) {
+        for element in &self.hidden {
+            let _ = element.remove_attribute("aria-hidden");
+        }
+    }
+}
+
+/// Hides every element outside the element from assistive technologies with `aria-hidden`,
+/// walking up to the body
+///
+/// Zones allowed by [allow] and live regions stay in the accessibility tree, along with the
+/// ancestors they are in. Elements hidden already, like those of another modal widget hiding
+/// others before, are left as they are.
+pub fn hide_others(element: &Element) -> HiddenOthers {
+    let mut hidden = vec![];
+    let mut current = element.clone();
+    while let Some(parent) = current.parent_element() {
+        let children = parent.children();
+        for sibling in (0..children.length()).filter_map(|v| children.item(v)) {
+            if sibling != current {
+                hide(&sibling, &mut hidden);
+            }
+        }
+        if parent.tag_name().eq_ignore_ascii_case("body") {
+            break;
+        }
+        current = parent;
+    }
+    HiddenOthers { hidden }
+}
+
+fn hide(element: &Element, hidden: &mut Vec<Element>) {
+    if element.get_attribute("aria-hidden").as_deref() == Some("true")
+        || is_allowed(element)
+        || element.matches(LIVE_REGION_SELECTOR).unwrap_or(false)
+    {
+        return;
+    }
+    let has_exempted = ZONES.with_borrow(|v| v.iter().any(|(_, zone)| zone.is_inside(element)))
+        || element
+            .query_selector(&format!("{LIVE_REGION_SELECTOR}, [{TRUSTED_ATTRIBUTE}]"))
+            .ok()
+            .flatten()
+            .is_some();
+    if has_exempted {
+        // Hiding the element would hide the exempted ones inside too
+        let children = element.children();
+        for child in (0..children.length()).filter_map(|v| children.item(v)) {
+            hide(&child, hidden);
+        }
+        return;
+    }
+    if element.set_attribute("aria-hidden", "true").is_ok() {
+        hidden.push(element.clone());
+    }
+}
@@ -0,0 +1,333 @@
+//! State persisted to Web Storage, with versioned migrations and synchronization across tabs
+//!
+//! Values are saved as JSON with the version they were written in, as
+//! `{"version":1,"value":...}`. Values of older versions are migrated when read, and values
+//! that can't be read fall back to the default. Values saved without the envelope, like those
+//! saved before a widget moved to this module, are read as version 0.
+
+use std::{
+    rc::{Rc, Weak},
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use gloo::{events::EventListener, utils::window};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::json;
+use wasm_bindgen::JsCast;
+use web_sys::{Storage, StorageEvent};
+
+pub use serde_json::Value;
+
+/// A Web Storage area
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StorageKind {
+    /// Kept across sessions and shared by tabs of the origin
+    #[default]
+    Local,
+    /// Kept for the tab only
+    Session,
+}
+
+impl StorageKind {
+    /// Returns the storage area, or None if storage is disabled such as in some private modes
+    pub fn storage(&self) -> Option<Storage> {
+        match self {
+            Self::Local => window().local_storage().ok().flatten(),
+            Self::Session => window().session_storage().ok().flatten(),
+        }
+    }
+}
+
+/// Converts a value of a version into the next version
+pub type Migration = Box<dyn Fn(Value) -> Value>;
+
+/// Options of [Persisted]
+pub struct PersistedOptions<T> {
+    pub key: String,
+    pub storage: StorageKind,
+    /// The version values are written in, which migrations bring older values up to
+    pub version: u32,
+    /// Migrations indexed by the version they convert from, so the first converts version 0
+    /// into 1
+    pub migrations: Vec<Migration>,
+    /// The value used if nothing is saved or the saved value can't be read
+    pub default: T,
+    /// Whether changes saved in other tabs are picked up, which only happens in local storage
+    pub sync: bool,
+}
+
+impl<T: Default> PersistedOptions<T> {
+    pub fn builder() -> PersistedOptionsBuilder<T> {
+        PersistedOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [PersistedOptions]
+pub struct PersistedOptionsBuilder<T> {
+    key: Option<String>,
+    storage: StorageKind,
+    version: u32,
+    migrations: Vec<Migration>,
+    default: T,
+    sync: bool,
+}
+
+impl<T: Default> Default for PersistedOptionsBuilder<T> {
+    fn default() -> Self {
+        Self {
+            key: None,
+            storage: StorageKind::default(),
+            version: 0,
+            migrations: vec![],
+            default: T::default(),
+            sync: true,
+        }
+    }
+}
+
+impl<T: Default> PersistedOptionsBuilder<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T> PersistedOptionsBuilder<T> {
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    pub fn storage(mut self, storage: StorageKind) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// Adds the migration converting the current version into the next, and bumps the version
+    pub fn migration(mut self, migration: Migration) -> Self {
+        self.migrations.push(migration);
+        self.version += 1;
+        self
+    }
+
+    pub fn default_value(mut self, default: T) -> Self {
+        self.default = default;
+        self
+    }
+
+    pub fn sync(mut self, sync: bool) -> Self {
+        self.sync = sync;
+        self
+    }
+
+    /// Builds into [PersistedOptions]
+    ///
+    /// # Panics
+    /// This method panics if key field is not set
+    pub fn build(self) -> PersistedOptions<T> {
+        PersistedOptions {
+            key: self.key.expect("key must be set to build PersistedOptions"),
+            storage: self.storage,
+            version: self.version,
+            migrations: self.migrations,
+            default: self.default,
+            sync: self.sync,
+        }
+    }
+}
+
+struct Subscriber<T> {
+    callback: Box<dyn Fn(&T)>,
+    handle: u64,
+}
+
+struct Inner<T> {
+    options: PersistedOptions<T>,
+    value: T,
+    subscribers: Vec<Rc<Subscriber<T>>>,
+    _listener: Option<EventListener>,
+}
+
+impl<T: DeserializeOwned + Clone> Inner<T> {
+    /// Reads the saved text, migrating it to the current version
+    fn read(&self, text: Option<&str>) -> T {
+        let Some(text) = text else {
+            return self.options.default.clone();
+        };
+        let value = serde_json::from_str::<Value>(text).unwrap_or_else(|_| json!(text));
+        let (mut version, mut value) = match value {
+            Value::Object(mut object) if object.len() == 2 && object.contains_key("value") => {
+                let version = object.get("version").and_then(Value::as_u64);
+                match version {
+                    Some(version) => (version as u32, object.remove("value").unwrap()),
+                    None => (0, Value::Object(object)),
+                }
+            }
+            value => (0, value),
+        };
+        if version > self.options.version {
+            return self.options.default.clone();
+        }
+        while version < self.options.version {
+            let Some(migration) = self.options.migrations.get(version as usize) else {
+                return self.options.default.clone();
+            };
+            value = migration(value);
+            version += 1;
+        }
+        serde_json::from_value(value).unwrap_or_else(|_| self.options.default.clone())
+    }
+
+    fn load(&self) -> T {
+        let text = self
+            .options
+            .storage
+            .storage()
+            .and_then(|v| v.get_item(&self.options.key).ok().flatten());
+        self.read(text.as_deref())
+    }
+}
+
+/// A value persisted to Web Storage
+///
+/// The value is read once when created and kept in memory, so reading it is cheap. Setting it
+/// saves it at once.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+pub struct Persisted<T>(Rc<Mutex<Inner<T>>>);
+
+impl<T> Clone for Persisted<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: Serialize + DeserializeOwned + Clone + 'static> Persisted<T> {
+    fn from_weak(weak: &Weak<Mutex<Inner<T>>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    pub fn key(&self) -> String {
+        self.0.lock().unwrap().options.key.clone()
+    }
+
+    pub fn get(&self) -> T {
+        self.0.lock().unwrap().value.clone()
+    }
+
+    /// Sets and saves the value
+    ///
+    /// The value is kept in memory even if it can't be saved, such as when storage is full or
+    /// disabled.
+    pub fn set(&self, value: T) {
+        {
+            let mut inner = self.0.lock().unwrap();
+            let envelope = json!({ "version": inner.options.version, "value": &value });
+            if let Some(storage) = inner.options.storage.storage() {
+                let _ = storage.set_item(&inner.options.key, &envelope.to_string());
+            }
+            inner.value = value;
+        }
+        self.publish();
+    }
+
+    /// Updates the value with the function, and saves it
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        let mut value = self.get();
+        f(&mut value);
+        self.set(value);
+    }
+
+    /// Removes the saved value, which resets the value to the default
+    pub fn remove(&self) {
+        {
+            let mut inner = self.0.lock().unwrap();
+            if let Some(storage) = inner.options.storage.storage() {
+                let _ = storage.remove_item(&inner.options.key);
+            }
+            inner.value = inner.options.default.clone();
+        }
+        self.publish();
+    }
+
+    /// Subscribes to changes of the value, including those from other tabs
+    ///
+    /// # Returns
+    /// A handle to unsubscribe with [Persisted::unsubscribe]
+    pub fn subscribe(&self, callback: Box<dyn Fn(&T)>) -> u64 {
+        static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+        let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .push(Rc::new(Subscriber { callback, handle }));
+        handle
+    }
+
+    pub fn unsubscribe(&self, handle: u64) {
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .retain(|v| v.handle != handle);
+    }
+
+    fn publish(&self) {
+        let (subscribers, value) = {
+            let inner = self.0.lock().unwrap();
+            (inner.subscribers.clone(), inner.value.clone())
+        };
+        for subscriber in subscribers {
+            (subscriber.callback)(&value);
+        }
+    }
+}
+
+pub fn create_persisted<T: Serialize + DeserializeOwned + Clone + 'static>(
+    options: PersistedOptions<T>,
+) -> Persisted<T> {
+    let (kind, sync) = (options.storage, options.sync);
+    let persisted = Persisted(Rc::new(Mutex::new(Inner {
+        value: options.default.clone(),
+        options,
+        subscribers: vec![],
+        _listener: None,
+    })));
+    {
+        let mut inner = persisted.0.lock().unwrap();
+        inner.value = inner.load();
+    }
+
+    // Storage events are only dispatched to other tabs sharing the storage area
+    if sync && kind == StorageKind::Local {
+        let weak = Rc::downgrade(&persisted.0);
+        let listener = EventListener::new(&window(), "storage", move |event| {
+            let Some(persisted) = Persisted::from_weak(&weak) else {
+                return;
+            };
+            let event = event.unchecked_ref::<StorageEvent>();
+            {
+                let mut inner = persisted.0.lock().unwrap();
+                // A null key means the whole storage was cleared
+                if event.key().is_some_and(|v| v != inner.options.key) {
+                    return;
+                }
+                let is_local = event
+                    .storage_area()
+                    .zip(kind.storage())
+                    .is_some_and(|(a, b)| a == b);
+                if !is_local {
+                    return;
+                }
+                inner.value = inner.read(event.new_value().as_deref());
+            }
+            persisted.publish();
+        });
+        persisted.0.lock().unwrap()._listener = Some(listener);
+    }
+    persisted
+}
@@ -0,0 +1,430 @@
+//! Positioning floating elements, like popovers and tooltips, next to their anchors
+//!
+//! [compute] places a floating element on a side of its anchor, flipping it to the opposite
+//! side when it overflows the collision [Boundary] and shifting it along the side to stay
+//! inside. Coordinates are relative to the viewport, for floating elements with
+//! `position: fixed`.
+//!
+//! The default boundary is made of the ancestors clipping the anchor, such as scrollable panels,
+//! which are detected from their computed `overflow`. [auto_update] places the element again
+//! whenever any of them or the page scrolls, or the viewport is resized.
+
+use gloo::utils::{document, window};
+use wasm_bindgen::JsCast;
+use web_sys::{DomRect, Element, HtmlElement};
+
+use crate::listener::{Listener, ListenerOptions};
+
+/// A rectangle relative to the viewport
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the border box of the element
+    pub fn of(element: &Element) -> Self {
+        Self::from(element.get_bounding_client_rect())
+    }
+
+    pub fn right(&self) -> f64 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+
+    /// Returns the area both rectangles cover, which is empty if they don't overlap
+    pub fn intersect(&self, other: &Rect) -> Rect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        Rect::new(
+            x,
+            y,
+            (self.right().min(other.right()) - x).max(0.0),
+            (self.bottom().min(other.bottom()) - y).max(0.0),
+        )
+    }
+
+    /// Returns the rectangle shrunk by the padding on every edge
+    pub fn inset(&self, padding: f64) -> Rect {
+        Rect::new(
+            self.x + padding,
+            self.y + padding,
+            (self.width - padding * 2.0).max(0.0),
+            (self.height - padding * 2.0).max(0.0),
+        )
+    }
+}
+
+impl From<DomRect> for Rect {
+    fn from(value: DomRect) -> Self {
+        Self::new(value.x(), value.y(), value.width(), value.height())
+    }
+}
+
+/// A side of the anchor the floating element is placed on
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Top,
+    Right,
+    #[default]
+    Bottom,
+    Left,
+}
+
+impl Side {
+    pub fn opposite(&self) -> Self {
+        match self {
+            Self::Top => Self::Bottom,
+            Self::Right => Self::Left,
+            Self::Bottom => Self::Top,
+            Self::Left => Self::Right,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Top => "top",
+            Self::Right => "right",
+            Self::Bottom => "bottom",
+            Self::Left => "left",
+        }
+    }
+
+    fn is_vertical(&self) -> bool {
+        matches!(self, Self::Top | Self::Bottom)
+    }
+}
+
+/// How the floating element is aligned along the side of the anchor
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Start,
+    #[default]
+    Center,
+    End,
+}
+
+impl Align {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::Center => "center",
+            Self::End => "end",
+        }
+    }
+}
+
+/// Where the floating element is placed around the anchor
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    pub side: Side,
+    pub align: Align,
+}
+
+impl Placement {
+    pub fn new(side: Side, align: Align) -> Self {
+        Self { side, align }
+    }
+}
+
+/// The area floating elements are kept within
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum Boundary {
+    /// The viewport clipped by every ancestor of the anchor clipping its overflow, as found by
+    /// [clipping_ancestors]
+    #[default]
+    ClippingAncestors,
+    /// The padding box of the element, clipped by the viewport
+    Element(Element),
+    /// The viewport alone, for floating elements rendered outside scrollable panels like those
+    /// in the top layer
+    Viewport,
+}
+
+/// Options of [compute]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionOptions {
+    pub placement: Placement,
+    /// Gap between the anchor and the floating element in px
+    pub offset: f64,
+    pub boundary: Boundary,
+    /// Space kept between the floating element and edges of the boundary in px
+    pub padding: f64,
+    /// Whether the element moves to the opposite side when it overflows the boundary there and
+    /// fits better on the other
+    pub flip: bool,
+    /// Whether the element moves along the side to stay within the boundary
+    pub shift: bool,
+}
+
+impl Default for PositionOptions {
+    fn default() -> Self {
+        Self {
+            placement: Placement::default(),
+            offset: 8.0,
+            boundary: Boundary::default(),
+            padding: 8.0,
+            flip: true,
+            shift: true,
+        }
+    }
+}
+
+/// Where [compute] placed the floating element
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Computed {
+    pub x: f64,
+    pub y: f64,
+    /// The placement after flipping, which styles like arrows follow
+    pub placement: Placement,
+    /// Whether the anchor is out of the boundary, like scrolled out of its panel, where the
+    /// floating element is usually hidden
+    pub is_anchor_hidden: bool,
+}
+
+/// Returns whether the element clips its overflow, like scroll containers
+fn is_clipping(element: &Element) -> bool {
+    let Ok(Some(style)) = window().get_computed_style(element) else {
+        return false;
+    };
+    ["overflow-x", "overflow-y"].into_iter().any(|v| {
+        style
+            .get_property_value(v)
+            .is_ok_and(|v| v != "visible" && !v.is_empty())
+    })
+}
+
+/// Returns ancestors of the element clipping its overflow, nearest first, such as scrollable
+/// panels
+///
+/// The root element and the body are left out, as the viewport clips them and their overflow
+/// applies to the viewport.
+pub fn clipping_ancestors(element: &Element) -> Vec<Element> {
+    let document = document();
+    let body: Option<Element> = document.body().map(Into::into);
+    let mut ancestors = vec![];
+    let mut current = element.parent_element();
+    while let Some(ancestor) = current {
+        if body.as_ref() == Some(&ancestor)
+            || document.document_element().as_ref() == Some(&ancestor)
+        {
+            break;
+        }
+        if is_clipping(&ancestor) {
+            ancestors.push(ancestor.clone());
+        }
+        current = ancestor.parent_element();
+    }
+    ancestors
+}
+
+/// Returns the padding box of the element, which leaves out borders and scrollbars
+fn padding_box(element: &Element) -> Rect {
+    let rect = Rect::of(element);
+    Rect::new(
+        rect.x + element.client_left() as f64,
+        rect.y + element.client_top() as f64,
+        element.client_width() as f64,
+        element.client_height() as f64,
+    )
+}
+
+/// Returns the visible area of the viewport, without scrollbars
+fn viewport() -> Rect {
+    let root = document().document_element();
+    let (width, height) = match root {
+        Some(root) => (root.client_width() as f64, root.client_height() as f64),
+        None => (
+            window()
+                .inner_width()
+                .ok()
+                .and_then(|v| v.as_f64())
+                .unwrap_or_default(),
+            window()
+                .inner_height()
+                .ok()
+                .and_then(|v| v.as_f64())
+                .unwrap_or_default(),
+        ),
+    };
+    Rect::new(0.0, 0.0, width, height)
+}
+
+/// Returns the rectangle of the boundary of the anchor, before padding
+pub fn boundary_rect(anchor: &Element, boundary: &Boundary) -> Rect {
+    let viewport = viewport();
+    match boundary {
+        Boundary::ClippingAncestors => clipping_ancestors(anchor)
+            .iter()
+            .fold(viewport, |rect, v| rect.intersect(&padding_box(v))),
+        Boundary::Element(element) => viewport.intersect(&padding_box(element)),
+        Boundary::Viewport => viewport,
+    }
+}
+
+/// Returns the position of the floating element of the size on the side of the anchor
+fn place(anchor: &Rect, size: (f64, f64), placement: Placement, offset: f64) -> (f64, f64) {
+    let (width, height) = size;
+    let along = |start: f64, length: f64, own: f64| match placement.align {
+        Align::Start => start,
+        Align::Center => start + (length - own) / 2.0,
+        Align::End => start + length - own,
+    };
+    match placement.side {
+        Side::Top => (
+            along(anchor.x, anchor.width, width),
+            anchor.y - offset - height,
+        ),
+        Side::Bottom => (
+            along(anchor.x, anchor.width, width),
+            anchor.bottom() + offset,
+        ),
+        Side::Left => (
+            anchor.x - offset - width,
+            along(anchor.y, anchor.height, height),
+        ),
+        Side::Right => (
+            anchor.right() + offset,
+            along(anchor.y, anchor.height, height),
+        ),
+    }
+}
+
+/// Returns how far the floating element at the position overflows the boundary on the side
+fn overflow(position: (f64, f64), size: (f64, f64), boundary: &Rect, side: Side) -> f64 {
+    let (x, y) = position;
+    let (width, height) = size;
+    match side {
+        Side::Top => boundary.y - y,
+        Side::Bottom => y + height - boundary.bottom(),
+        Side::Left => boundary.x - x,
+        Side::Right => x + width - boundary.right(),
+    }
+}
+
+/// Computes the position of a floating element of the size from rectangles of the anchor and
+/// the boundary, where the padding of the options is applied to the boundary
+///
+/// This is what [compute] does after measuring elements, for callers measuring them otherwise.
+pub fn compute_rects(
+    anchor: &Rect,
+    size: (f64, f64),
+    boundary: &Rect,
+    options: &PositionOptions,
+) -> Computed {
+    let boundary = boundary.inset(options.padding);
+    let mut placement = options.placement;
+    let mut position = place(anchor, size, placement, options.offset);
+
+    if options.flip {
+        let current = overflow(position, size, &boundary, placement.side);
+        if current > 0.0 {
+            let flipped = Placement::new(placement.side.opposite(), placement.align);
+            let other = place(anchor, size, flipped, options.offset);
+            if overflow(other, size, &boundary, flipped.side) < current {
+                placement = flipped;
+                position = other;
+            }
+        }
+    }
+
+    if options.shift {
+        let clamp = |value: f64, start: f64, end: f64, own: f64| {
+            // Elements larger than the boundary stick to its start
+            value.min(end - own).max(start)
+        };
+        let (width, height) = size;
+        if placement.side.is_vertical() {
+            position.0 = clamp(position.0, boundary.x, boundary.right(), width);
+        } else {
+            position.1 = clamp(position.1, boundary.y, boundary.bottom(), height);
+        }
+    }
+
+    let is_anchor_hidden = anchor.right() <= boundary.x
+        || anchor.x >= boundary.right()
+        || anchor.bottom() <= boundary.y
+        || anchor.y >= boundary.bottom();
+    Computed {
+        x: position.0,
+        y: position.1,
+        placement,
+        is_anchor_hidden,
+    }
+}
+
+/// Computes the position of the floating element next to the anchor
+pub fn compute(anchor: &Element, floating: &Element, options: &PositionOptions) -> Computed {
+    let floating = Rect::of(floating);
+    compute_rects(
+        &Rect::of(anchor),
+        (floating.width, floating.height),
+        &boundary_rect(anchor, &options.boundary),
+        options,
+    )
+}
+
+/// Moves the floating element with `position: fixed` to the computed position
+///
+/// # Attributes
+/// **data-side** and **data-align** are set in the floating element to the placement after
+/// flipping
+///
+/// **data-anchor-hidden** is set in the floating element while the anchor is out of the
+/// boundary
+pub fn apply(floating: &HtmlElement, computed: &Computed) {
+    let style = floating.style();
+    let _ = style.set_property("left", &format!("{}px", computed.x));
+    let _ = style.set_property("top", &format!("{}px", computed.y));
+    let _ = floating.set_attribute("data-side", computed.placement.side.as_str());
+    let _ = floating.set_attribute("data-align", computed.placement.align.as_str());
+    if computed.is_anchor_hidden {
+        let _ = floating.set_attribute("data-anchor-hidden", "");
+    } else {
+        let _ = floating.remove_attribute("data-anchor-hidden");
+    }
+}
+
+/// Places the floating element with [apply] at once, and again whenever the page or a clipping
+/// ancestor of the anchor scrolls or the viewport is resized, until the returned listener is
+/// dropped
+pub fn auto_update(anchor: &Element, floating: &HtmlElement, options: PositionOptions) -> Listener {
+    let update = {
+        let anchor = anchor.clone();
+        let floating = floating.clone();
+        move || {
+            apply(
+                &floating,
+                &compute(&anchor, floating.unchecked_ref(), &options),
+            )
+        }
+    };
+    update();
+
+    let passive = ListenerOptions::passive();
+    let mut listener = Listener::new();
+    for ancestor in clipping_ancestors(anchor) {
+        let update = update.clone();
+        listener.on_with_options(&ancestor, "scroll", passive, move |_| update());
+    }
+    let on_scroll = update.clone();
+    listener
+        .on_with_options(&window(), "scroll", passive, move |_| on_scroll())
+        .on_with_options(&window(), "resize", passive, move |_| update());
+    listener
+}
@@ -0,0 +1,336 @@
+//! Keeping elements mounted while their exit animations run
+//!
+//! Removing or hiding an element at once cuts its closing animation short. A [Presence] marks
+//! the element closed first, and unmounts it only after animations and transitions it started
+//! have ended.
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::{events::EventListener, utils::window};
+use web_sys::{Element, Event, HtmlElement, Node};
+
+/// Returns whether the element has an animation or a transition that would run
+pub fn is_animated(element: &Element) -> bool {
+    let Ok(Some(style)) = window().get_computed_style(element) else {
+        return false;
+    };
+    let has_duration = |property: &str| {
+        style
+            .get_property_value(property)
+            .unwrap_or_default()
+            .split(',')
+            .any(|v| {
+                v.trim()
+                    .trim_end_matches("ms")
+                    .trim_end_matches('s')
+                    .parse::<f64>()
+                    .is_ok_and(|v| v > 0.0)
+            })
+    };
+    let has_animation = style
+        .get_property_value("animation-name")
+        .is_ok_and(|v| v.split(',').any(|v| v.trim() != "none"));
+    (has_animation && has_duration("animation-duration")) || has_duration("transition-duration")
+}
+
+/// How an element is unmounted once it closed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Unmount {
+    /// The element is removed from the document, and appended to the container again when it
+    /// opens
+    #[default]
+    Remove,
+    /// The element stays in the document with `hidden` attribute
+    Hide,
+}
+
+pub type UnmountHook = Box<dyn Fn()>;
+
+/// Hooks to [Presence]
+#[derive(Default)]
+pub struct PresenceHooks {
+    /// Called after the element is unmounted
+    pub unmount: Option<UnmountHook>,
+}
+
+/// Options of [Presence]
+pub struct PresenceOptions {
+    pub element: HtmlElement,
+    /// The node the element is appended to when it mounts with [Unmount::Remove], which defaults
+    /// to the parent of the element
+    pub container: Option<Node>,
+    pub unmount: Unmount,
+    /// Whether the element is initially open, and mounted
+    pub open: bool,
+    pub hooks: PresenceHooks,
+}
+
+impl PresenceOptions {
+    pub fn builder() -> PresenceOptionsBuilder {
+        PresenceOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [PresenceOptions]
+#[derive(Default)]
+pub struct PresenceOptionsBuilder {
+    element: Option<HtmlElement>,
+    container: Option<Node>,
+    unmount: Unmount,
+    open: bool,
+    hooks: PresenceHooks,
+}
+
+impl PresenceOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn element(mut self, element: HtmlElement) -> Self {
+        self.element = Some(element);
+        self
+    }
+
+    pub fn container(mut self, container: Node) -> Self {
+        self.container = Some(container);
+        self
+    }
+
+    pub fn unmount(mut self, unmount: Unmount) -> Self {
+        self.unmount = unmount;
+        self
+    }
+
+    pub fn open(mut self, open: bool) -> Self {
+        self.open = open;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: PresenceHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [PresenceOptions]
+    ///
+    /// # Panics
+    /// This method panics if element field is not set
+    pub fn build(self) -> PresenceOptions {
+        PresenceOptions {
+            element: self
+                .element
+                .expect("element must be set to build PresenceOptions"),
+            container: self.container,
+            unmount: self.unmount,
+            open: self.open,
+            hooks: self.hooks,
+        }
+    }
+}
+
+struct Inner {
+    options: Rc<PresenceOptions>,
+    container: Option<Node>,
+    is_open: bool,
+    is_present: bool,
+    /// Count of animations and transitions running in the element
+    running: usize,
+    /// Keeps the presence alive while it closes, so it can be dropped right after closing
+    closing: Option<Presence>,
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn reflect(&self) {
+        let state = if self.is_open { "open" } else { "closed" };
+        let _ = self.options.element.set_attribute("data-state", state);
+    }
+
+    fn mount(&mut self) {
+        if self.is_present {
+            return;
+        }
+        self.is_present = true;
+        let element = &self.options.element;
+        match self.options.unmount {
+            Unmount::Remove => {
+                if let Some(container) = &self.container {
+                    let _ = container.append_child(element);
+                }
+            }
+            Unmount::Hide => element.set_hidden(false),
+        }
+    }
+}
+
+/// An instance of presence
+///
+/// Opening mounts the element, and closing unmounts it after animations and transitions run by
+/// its closed state end. Without any, the element is unmounted at once.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-state** is set in the element to `open` or `closed`
+#[derive(Clone)]
+pub struct Presence(Rc<Mutex<Inner>>);
+
+impl Presence {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.0.lock().unwrap().is_open
+    }
+
+    /// Returns whether the element is mounted, which it stays while its closing animation runs
+    pub fn is_present(&self) -> bool {
+        self.0.lock().unwrap().is_present
+    }
+
+    pub fn element(&self) -> HtmlElement {
+        self.0.lock().unwrap().options.element.clone()
+    }
+
+    pub fn show(&self) {
+        self.set_open(true);
+    }
+
+    pub fn hide(&self) {
+        self.set_open(false);
+    }
+
+    pub fn set_open(&self, is_open: bool) {
+        let is_unmounting = {
+            let mut inner = self.0.lock().unwrap();
+            if inner.is_open == is_open {
+                return;
+            }
+            inner.is_open = is_open;
+            if is_open {
+                inner.closing = None;
+                inner.mount();
+                inner.reflect();
+                return;
+            }
+
+            inner.reflect();
+            if inner.is_present && (inner.running > 0 || is_animated(&inner.options.element)) {
+                inner.closing = Some(self.clone());
+                false
+            } else {
+                true
+            }
+        };
+
+        if is_unmounting {
+            self.unmount();
+        }
+    }
+
+    fn unmount(&self) {
+        let (options, _closing) = {
+            let mut inner = self.0.lock().unwrap();
+            if inner.is_open || !inner.is_present {
+                return;
+            }
+            inner.is_present = false;
+            inner.running = 0;
+            let element = inner.options.element.clone();
+            match inner.options.unmount {
+                Unmount::Remove => {
+                    // The parent is remembered, so the element mounts back where it was
+                    if let Some(parent) = element.parent_node() {
+                        inner.container.get_or_insert(parent);
+                    }
+                    element.remove();
+                }
+                Unmount::Hide => element.set_hidden(true),
+            }
+            (inner.options.clone(), inner.closing.take())
+        };
+
+        if let Some(hook) = &options.hooks.unmount {
+            hook();
+        }
+    }
+
+    fn handle_start(&self, event: &Event) {
+        let mut inner = self.0.lock().unwrap();
+        // Animations of descendants bubble up too
+        if event.target().as_ref() == Some(inner.options.element.as_ref()) {
+            inner.running += 1;
+        }
+    }
+
+    fn handle_end(&self, event: &Event) {
+        {
+            let mut inner = self.0.lock().unwrap();
+            if event.target().as_ref() != Some(inner.options.element.as_ref()) {
+                return;
+            }
+            inner.running = inner.running.saturating_sub(1);
+            if inner.running > 0 || inner.closing.is_none() {
+                return;
+            }
+        }
+        self.unmount();
+    }
+}
+
+pub fn create_presence(options: PresenceOptions) -> Presence {
+    let element = options.element.clone();
+    let container = options.container.clone().or_else(|| element.parent_node());
+    let is_open = options.open;
+    let unmount = options.unmount;
+
+    let presence = Presence(Rc::new(Mutex::new(Inner {
+        options: Rc::new(options),
+        container,
+        is_open,
+        is_present: element.is_connected() && !(unmount == Unmount::Hide && element.hidden()),
+        running: 0,
+        closing: None,
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&presence.0);
+    let listener = |event_type: &'static str, f: fn(&Presence, &Event)| {
+        let weak = weak.clone();
+        EventListener::new(&element, event_type, move |event| {
+            if let Some(presence) = Presence::from_weak(&weak) {
+                f(&presence, event);
+            }
+        })
+    };
+    let listeners = vec![
+        listener("animationstart", Presence::handle_start),
+        listener("transitionrun", Presence::handle_start),
+        listener("animationend", Presence::handle_end),
+        listener("animationcancel", Presence::handle_end),
+        listener("transitionend", Presence::handle_end),
+        listener("transitioncancel", Presence::handle_end),
+    ];
+
+    {
+        let mut inner = presence.0.lock().unwrap();
+        inner._listeners = listeners;
+        if is_open {
+            inner.mount();
+        } else if inner.is_present {
+            // A closed element starts unmounted, without running its closing animation
+            inner.is_present = false;
+            match unmount {
+                Unmount::Remove => element.remove(),
+                Unmount::Hide => element.set_hidden(true),
+            }
+        }
+        inner.reflect();
+    }
+    presence
+}
@@ -0,0 +1,135 @@
+//! Elements referenced by names, marked with `data-seigi-ref` in templates
+//!
+//! ```html
+//! <dialog>
+//!   <h2 data-seigi-ref="title"></h2>
+//!   <button data-seigi-ref="close">Close</button>
+//! </dialog>
+//! ```
+//!
+//! [Refs] of a root finds marked elements by their names instead of selectors repeated across
+//! the application, keeping them until the subtree changes. Widgets expose refs of their
+//! elements, like `FocusTrap::refs` of the target, and components keep one made in
+//! `connected` among their state.
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use js_sys::Array;
+use wasm_bindgen::{JsCast, prelude::Closure};
+use web_sys::{Element, HtmlElement, MutationObserver, MutationObserverInit};
+
+use crate::diagnostics::{self, Resource, Tracked};
+
+/// The attribute naming an element, with names separated by whitespace for elements referenced
+/// by several
+pub const REF_ATTRIBUTE: &str = "data-seigi-ref";
+
+/// Elements of each name in document order
+type Named = HashMap<String, Vec<HtmlElement>>;
+
+struct Inner {
+    root: Element,
+    /// Collected at once when asked first
+    cache: Rc<RefCell<Option<Named>>>,
+    /// Absent if the browser failed to create it, in which case elements aren't kept
+    observer: Option<MutationObserver>,
+    _callback: Closure<dyn Fn(Array)>,
+    /// Counts the observer in diagnostics while alive
+    _tracked: Tracked,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        if let Some(observer) = &self.observer {
+            observer.disconnect();
+        }
+    }
+}
+
+/// Referenced elements in the subtree of a root, kept until children or [REF_ATTRIBUTE]s in the
+/// subtree change
+///
+/// The root itself isn't included. This struct internally contains a handle(Rc) to actual data,
+/// so cloning this struct is a lightweight operation, and observing stops once every clone is
+/// dropped.
+#[derive(Clone)]
+pub struct Refs(Rc<Inner>);
+
+impl Refs {
+    /// Starts observing the root
+    pub fn new(root: Element) -> Self {
+        let cache = Rc::new(RefCell::new(None));
+        let callback = Closure::<dyn Fn(Array)>::new({
+            let cache = cache.clone();
+            move |_| {
+                cache.replace(None);
+            }
+        });
+        let observer = MutationObserver::new(callback.as_ref().unchecked_ref())
+            .ok()
+            .filter(|observer| {
+                let init = MutationObserverInit::new();
+                init.set_child_list(true);
+                init.set_subtree(true);
+                init.set_attributes(true);
+                init.set_attribute_filter(&Array::of1(&REF_ATTRIBUTE.into()));
+                observer.observe_with_options(&root, &init).is_ok()
+            });
+        Self(Rc::new(Inner {
+            root,
+            cache,
+            observer,
+            _callback: callback,
+            _tracked: diagnostics::track(Resource::Observer),
+        }))
+    }
+
+    /// Returns the root elements are found in
+    pub fn root(&self) -> Element {
+        self.0.root.clone()
+    }
+
+    /// Returns the first element of the name in document order
+    pub fn get(&self, name: &str) -> Option<HtmlElement> {
+        self.with(|refs| refs.get(name).and_then(|v| v.first().cloned()))
+    }
+
+    /// Returns every element of the name in document order
+    pub fn get_all(&self, name: &str) -> Vec<HtmlElement> {
+        self.with(|refs| refs.get(name).cloned().unwrap_or_default())
+    }
+
+    /// Calls the function with the elements of each name, collecting them unless kept
+    fn with<T>(&self, f: impl FnOnce(&Named) -> T) -> T {
+        let inner = &self.0;
+        let Some(observer) = &inner.observer else {
+            return f(&collect(&inner.root));
+        };
+        // Mutations are delivered to the callback in a microtask, so ones made earlier in the
+        // same task are taken here
+        if observer.take_records().length() > 0 {
+            inner.cache.replace(None);
+        }
+        let mut cache = inner.cache.borrow_mut();
+        f(cache.get_or_insert_with(|| collect(&inner.root)))
+    }
+}
+
+fn collect(root: &Element) -> Named {
+    let mut refs = Named::new();
+    let Ok(list) = root.query_selector_all(&format!("[{REF_ATTRIBUTE}]")) else {
+        return refs;
+    };
+    for element in (0..list.length()).filter_map(|v| list.item(v)) {
+        let Ok(element) = element.dyn_into::<HtmlElement>() else {
+            continue;
+        };
+        let names = element.get_attribute(REF_ATTRIBUTE).unwrap_or_default();
+        for name in names.split_whitespace() {
+            refs.entry(name.to_string())
+                .or_default()
+                .push(element.clone());
+        }
+    }
+    refs
+}
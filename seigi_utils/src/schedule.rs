@@ -0,0 +1,277 @@
+//! Rate limiting of callbacks by time and by animation frames
+//!
+//! [Debounce] runs a callback once calls have settled, [Throttle] runs it at most once per
+//! interval, and [Frame] batches calls into the next animation frame so layout is read and
+//! written once per frame. Pending runs are cancelled once every handle is dropped, so
+//! callbacks never run for widgets already gone.
+//!
+//! [focus] moves focus in the next task like [Frame] does for layout, returning a
+//! [ScheduledFocus] cancelled the same way.
+
+use std::{
+    cell::Cell,
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::{
+    render::{AnimationFrame, request_animation_frame},
+    timers::callback::Timeout,
+};
+use web_sys::HtmlElement;
+
+use crate::diagnostics::{self, Resource, Tracked};
+
+struct DebounceInner<T> {
+    delay: u32,
+    callback: Rc<dyn Fn(T)>,
+    pending: Option<(T, Timeout)>,
+}
+
+/// A callback run once it hasn't been called for a delay, with the argument of the last call
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+pub struct Debounce<T>(Rc<Mutex<DebounceInner<T>>>);
+
+impl<T> Clone for Debounce<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: 'static> Debounce<T> {
+    /// Schedules the callback with the argument, postponing the run scheduled before
+    pub fn call(&self, value: T) {
+        let weak = Rc::downgrade(&self.0);
+        let mut inner = self.0.lock().unwrap();
+        let timeout = Timeout::new(inner.delay, move || {
+            if let Some(inner) = weak.upgrade() {
+                Debounce(inner).flush();
+            }
+        });
+        inner.pending = Some((value, timeout));
+    }
+
+    /// Runs the scheduled callback now
+    pub fn flush(&self) {
+        let (callback, pending) = {
+            let mut inner = self.0.lock().unwrap();
+            (inner.callback.clone(), inner.pending.take())
+        };
+        if let Some((value, _)) = pending {
+            callback(value);
+        }
+    }
+
+    /// Cancels the scheduled callback
+    pub fn cancel(&self) {
+        self.0.lock().unwrap().pending = None;
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.0.lock().unwrap().pending.is_some()
+    }
+}
+
+/// Creates a [Debounce] of the callback, run once it hasn't been called for the delay in
+/// milliseconds
+pub fn debounce<T: 'static>(delay: u32, callback: impl Fn(T) + 'static) -> Debounce<T> {
+    Debounce(Rc::new(Mutex::new(DebounceInner {
+        delay,
+        callback: Rc::new(callback),
+        pending: None,
+    })))
+}
+
+struct ThrottleInner<T> {
+    interval: u32,
+    callback: Rc<dyn Fn(T)>,
+    /// Ends the interval since the last run, which is None when the callback can run at once
+    timer: Option<Timeout>,
+    /// The argument of the last call made during the interval, run once it ends
+    trailing: Option<T>,
+}
+
+/// A callback run at most once per interval
+///
+/// The first call runs at once, and the last call made during the interval runs once it ends,
+/// so the final state is never missed.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+pub struct Throttle<T>(Rc<Mutex<ThrottleInner<T>>>);
+
+impl<T> Clone for Throttle<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: 'static> Throttle<T> {
+    fn from_weak(weak: &Weak<Mutex<ThrottleInner<T>>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    /// Runs the callback with the argument, or schedules it to the end of the interval
+    pub fn call(&self, value: T) {
+        let callback = {
+            let mut inner = self.0.lock().unwrap();
+            if inner.timer.is_some() {
+                inner.trailing = Some(value);
+                return;
+            }
+            self.start(&mut inner);
+            inner.callback.clone()
+        };
+        callback(value);
+    }
+
+    fn start(&self, inner: &mut ThrottleInner<T>) {
+        let weak = Rc::downgrade(&self.0);
+        inner.timer = Some(Timeout::new(inner.interval, move || {
+            if let Some(throttle) = Throttle::from_weak(&weak) {
+                throttle.end();
+            }
+        }));
+    }
+
+    /// Ends the interval, running the trailing call which begins another interval
+    fn end(&self) {
+        let (callback, trailing) = {
+            let mut inner = self.0.lock().unwrap();
+            inner.timer = None;
+            let Some(trailing) = inner.trailing.take() else {
+                return;
+            };
+            self.start(&mut inner);
+            (inner.callback.clone(), trailing)
+        };
+        callback(trailing);
+    }
+
+    /// Cancels the trailing call and ends the interval, so the next call runs at once
+    pub fn cancel(&self) {
+        let mut inner = self.0.lock().unwrap();
+        inner.timer = None;
+        inner.trailing = None;
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.0.lock().unwrap().trailing.is_some()
+    }
+}
+
+/// Creates a [Throttle] of the callback, run at most once per the interval in milliseconds
+pub fn throttle<T: 'static>(interval: u32, callback: impl Fn(T) + 'static) -> Throttle<T> {
+    Throttle(Rc::new(Mutex::new(ThrottleInner {
+        interval,
+        callback: Rc::new(callback),
+        timer: None,
+        trailing: None,
+    })))
+}
+
+struct FrameInner {
+    callback: Rc<dyn Fn()>,
+    request: Option<AnimationFrame>,
+}
+
+/// A callback run in the next animation frame, however many times it is scheduled before
+///
+/// Scheduling from the callback itself requests another frame.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+#[derive(Clone)]
+pub struct Frame(Rc<Mutex<FrameInner>>);
+
+impl Frame {
+    fn from_weak(weak: &Weak<Mutex<FrameInner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    /// Schedules the callback to the next animation frame, unless it is already scheduled
+    pub fn schedule(&self) {
+        let mut inner = self.0.lock().unwrap();
+        if inner.request.is_some() {
+            return;
+        }
+        let weak = Rc::downgrade(&self.0);
+        inner.request = Some(request_animation_frame(move |_| {
+            let Some(frame) = Frame::from_weak(&weak) else {
+                return;
+            };
+            let callback = {
+                let mut inner = frame.0.lock().unwrap();
+                inner.request = None;
+                inner.callback.clone()
+            };
+            callback();
+        }));
+    }
+
+    /// Cancels the scheduled callback
+    pub fn cancel(&self) {
+        self.0.lock().unwrap().request = None;
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.0.lock().unwrap().request.is_some()
+    }
+}
+
+/// Creates a [Frame] of the callback
+pub fn frame(callback: impl Fn() + 'static) -> Frame {
+    Frame(Rc::new(Mutex::new(FrameInner {
+        callback: Rc::new(callback),
+        request: None,
+    })))
+}
+
+/// A focus move scheduled by [focus], cancelled when dropped
+///
+/// Widgets keep the focus moves they schedule, so ones pending when the widget is torn down,
+/// like initial focus of a trap deactivated right after activating, never land.
+#[must_use = "the focus move is cancelled when dropped, unless forgotten"]
+pub struct ScheduledFocus {
+    timeout: Timeout,
+    is_pending: Rc<Cell<bool>>,
+    tracked: Tracked,
+}
+
+impl ScheduledFocus {
+    /// Cancels the focus move
+    pub fn cancel(self) {
+        drop(self);
+    }
+
+    /// Lets the focus move land even though the handle is dropped
+    pub fn forget(self) {
+        self.timeout.forget();
+        self.tracked.forget();
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.is_pending.get()
+    }
+}
+
+/// Focuses the element in the next task, after browsers finish moving focus for the current
+/// event
+#[track_caller]
+pub fn focus(element: HtmlElement) -> ScheduledFocus {
+    let is_pending = Rc::new(Cell::new(true));
+    let timeout = Timeout::new(0, {
+        let is_pending = is_pending.clone();
+        move || {
+            is_pending.set(false);
+            let _ = element.focus();
+        }
+    });
+    ScheduledFocus {
+        timeout,
+        is_pending,
+        tracked: diagnostics::track(Resource::Timeout),
+    }
+}
@@ -0,0 +1,179 @@
+//! Locking scroll of the document while overlays are open
+//!
+//! Locks are counted, so overlays opening over each other each take a lock, and the document
+//! scrolls again only after every lock is released. Sheets, alert dialogs, command palettes and
+//! focus traps with `scroll_lock` all share this lock.
+
+use std::cell::RefCell;
+
+use gloo::{
+    events::{EventListener, EventListenerOptions},
+    utils::{body, document, document_element, window},
+};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, TouchEvent};
+
+/// The attribute marking elements that still scroll by touch while the document is locked
+pub const ALLOW_ATTRIBUTE: &str = "data-seigi-scroll-allow";
+
+#[derive(Default)]
+struct State {
+    count: usize,
+    /// Inline styles of the body before the first lock
+    overflow: String,
+    padding_right: String,
+    /// Prevents touch scrolling, which iOS Safari doesn't stop for hidden overflow of the body
+    touch_listener: Option<EventListener>,
+    /// Layers of locks taken with [lock_for], which still scroll by touch
+    layers: Vec<Element>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+/// Returns the width of the scrollbar of the document, which is 0 for overlay scrollbars or a
+/// gutter reserved with `scrollbar-gutter: stable`
+fn scrollbar_width() -> f64 {
+    let is_stable = window()
+        .get_computed_style(&document_element())
+        .ok()
+        .flatten()
+        .and_then(|v| v.get_property_value("scrollbar-gutter").ok())
+        .is_some_and(|v| v.contains("stable"));
+    if is_stable {
+        return 0.0;
+    }
+
+    let width = window()
+        .inner_width()
+        .ok()
+        .and_then(|v| v.as_f64())
+        .unwrap_or_default()
+        - document_element().client_width() as f64;
+    width.max(0.0)
+}
+
+fn apply(state: &mut State) {
+    let style = body().style();
+    state.overflow = style.get_property_value("overflow").unwrap_or_default();
+    state.padding_right = style
+        .get_property_value("padding-right")
+        .unwrap_or_default();
+
+    // The body is padded by the scrollbar it loses, so the content doesn't shift
+    let scrollbar = scrollbar_width();
+    if scrollbar > 0.0 {
+        let padding = window()
+            .get_computed_style(&body())
+            .ok()
+            .flatten()
+            .and_then(|v| v.get_property_value("padding-right").ok())
+            .and_then(|v| v.trim_end_matches("px").parse::<f64>().ok())
+            .unwrap_or_default();
+        let _ = style.set_property("padding-right", &format!("{}px", padding + scrollbar));
+    }
+    let _ = document_element()
+        .unchecked_into::<HtmlElement>()
+        .style()
+        .set_property("--seigi-scrollbar-width", &format!("{scrollbar}px"));
+    let _ = style.set_property("overflow", "hidden");
+
+    state.touch_listener = Some(EventListener::new_with_options(
+        &document(),
+        "touchmove",
+        EventListenerOptions::enable_prevent_default(),
+        |event| {
+            let target = event.target().and_then(|v| v.dyn_into::<Element>().ok());
+            let is_allowed = target.is_some_and(|target| {
+                target
+                    .closest(&format!("[{ALLOW_ATTRIBUTE}]"))
+                    .ok()
+                    .flatten()
+                    .is_some()
+                    || STATE
+                        .with_borrow(|state| state.layers.iter().any(|v| v.contains(Some(&target))))
+            });
+            // Pinch zooming stays allowed
+            let is_pinch = event
+                .dyn_ref::<TouchEvent>()
+                .is_some_and(|v| v.touches().length() > 1);
+            if !is_allowed && !is_pinch {
+                event.prevent_default();
+            }
+        },
+    ));
+}
+
+fn restore(state: &mut State) {
+    let style = body().style();
+    let _ = style.set_property("overflow", &state.overflow);
+    let _ = style.set_property("padding-right", &state.padding_right);
+    let _ = document_element()
+        .unchecked_into::<HtmlElement>()
+        .style()
+        .remove_property("--seigi-scrollbar-width");
+    state.touch_listener = None;
+}
+
+/// A lock on document scroll, released when dropped or with [ScrollLock::unlock]
+///
+/// While the document is locked, touch scrolling is prevented except in elements with
+/// **data-seigi-scroll-allow**, such as the content of the overlay, and in layers of locks taken
+/// with [lock_for].
+///
+/// # CSS Variables
+/// **--seigi-scrollbar-width** is set in the document element to the width of the scrollbar
+/// the body is padded by, so fixed elements can be padded the same
+#[must_use = "the document is unlocked when the lock is dropped"]
+pub struct ScrollLock {
+    layer: Option<Element>,
+}
+
+impl ScrollLock {
+    /// Releases the lock, which is the same as dropping it
+    pub fn unlock(self) {}
+}
+
+impl Drop for ScrollLock {
+    fn drop(&mut self) {
+        STATE.with_borrow_mut(|state| {
+            if let Some(layer) = &self.layer
+                && let Some(index) = state.layers.iter().position(|v| v == layer)
+            {
+                state.layers.remove(index);
+            }
+            state.count -= 1;
+            if state.count == 0 {
+                restore(state);
+            }
+        });
+    }
+}
+
+fn take(layer: Option<Element>) -> ScrollLock {
+    STATE.with_borrow_mut(|state| {
+        state.count += 1;
+        if state.count == 1 {
+            apply(state);
+        }
+        state.layers.extend(layer.clone());
+    });
+    ScrollLock { layer }
+}
+
+/// Locks scroll of the document
+pub fn lock() -> ScrollLock {
+    take(None)
+}
+
+/// Locks scroll of the document, except in the layer taking the lock, which still scrolls by
+/// touch while the lock is held
+pub fn lock_for(layer: &Element) -> ScrollLock {
+    take(Some(layer.clone()))
+}
+
+/// Returns whether any lock is held
+pub fn is_locked() -> bool {
+    STATE.with_borrow(|state| state.count > 0)
+}
@@ -0,0 +1,145 @@
+//! The top layer, where popovers and modal dialogs render above every other element
+//!
+//! Elements in the top layer aren't clipped by ancestors and ignore `z-index`, so widgets
+//! opting into it render above stacking contexts of other libraries. [make_popover] turns an
+//! element into a popover with the Popover API, and [show] and [hide] toggle it. Browsers
+//! without the API get the element hidden with `hidden` attribute instead, so the same code works
+//! everywhere. [show_modal] does the same for modal dialogs.
+//!
+//! Popovers in the top layer are positioned against the viewport, not their ancestors, so their
+//! positions are left to user CSS, like anchor positioning.
+
+use gloo::{events::EventListener, utils::document};
+use js_sys::Reflect;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{HtmlDialogElement, HtmlElement, ToggleEvent};
+
+/// Attribute set in dialogs shown as non-modal dialogs, where modal dialogs are unsupported
+pub const MODAL_FALLBACK_ATTRIBUTE: &str = "data-seigi-modal-fallback";
+
+/// How a popover is dismissed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Popover {
+    /// Light-dismissed by the browser on Escape and pointer presses outside, closing other
+    /// popovers of the mode that aren't its ancestors when shown
+    Auto,
+    /// Only dismissed by [hide]
+    Manual,
+}
+
+impl Popover {
+    fn attribute(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Manual => "manual",
+        }
+    }
+}
+
+fn has_method(target: &JsValue, name: &str) -> bool {
+    Reflect::get(target, &JsValue::from_str(name)).is_ok_and(|v| v.is_function())
+}
+
+/// Returns whether the browser supports the Popover API
+pub fn supports_popover() -> bool {
+    document()
+        .document_element()
+        .is_some_and(|v| has_method(&v, "showPopover"))
+}
+
+/// Returns whether the browser supports modal dialogs, which render in the top layer
+pub fn supports_modal_dialog() -> bool {
+    document()
+        .create_element("dialog")
+        .is_ok_and(|v| has_method(&v, "showModal"))
+}
+
+/// Returns whether the element is a popover the browser shows in the top layer
+pub fn is_popover(element: &HtmlElement) -> bool {
+    element.has_attribute("popover") && supports_popover()
+}
+
+/// Makes the element a popover of the mode, which is hidden until [show]
+///
+/// # Returns
+/// Whether the element is a popover, which is false if the browser doesn't support the
+/// Popover API. The element is hidden with `hidden` attribute then
+pub fn make_popover(element: &HtmlElement, mode: Popover) -> bool {
+    if !supports_popover() {
+        element.set_hidden(true);
+        return false;
+    }
+    // The hidden attribute would hide the popover even while it is shown
+    element.set_hidden(false);
+    let _ = element.set_attribute("popover", mode.attribute());
+    true
+}
+
+/// Shows the element in the top layer if it is a popover, or removes its `hidden` attribute
+pub fn show(element: &HtmlElement) {
+    if is_popover(element) {
+        if !element.matches(":popover-open").unwrap_or(false) {
+            let _ = element.show_popover();
+        }
+    } else {
+        element.set_hidden(false);
+    }
+}
+
+/// Hides the element from the top layer if it is a popover, or sets its `hidden` attribute
+pub fn hide(element: &HtmlElement) {
+    if is_popover(element) {
+        if element.matches(":popover-open").unwrap_or(false) {
+            let _ = element.hide_popover();
+        }
+    } else {
+        element.set_hidden(true);
+    }
+}
+
+/// Moves the shown popover above elements entering the top layer after it, like modal dialogs
+pub fn raise(element: &HtmlElement) {
+    if is_popover(element) && element.matches(":popover-open").unwrap_or(false) {
+        let _ = element.hide_popover();
+        let _ = element.show_popover();
+    }
+}
+
+/// Listens to the popover being hidden by the browser, like by light dismiss
+///
+/// The callback is also called on [hide], which widgets keeping their own state ignore as it is
+/// already closed.
+pub fn on_dismiss(element: &HtmlElement, callback: impl Fn() + 'static) -> EventListener {
+    EventListener::new(element, "toggle", move |event| {
+        if event
+            .dyn_ref::<ToggleEvent>()
+            .is_some_and(|v| v.new_state() == "closed")
+        {
+            callback();
+        }
+    })
+}
+
+/// Shows the dialog as a modal in the top layer
+///
+/// Browsers without modal dialogs show it as a non-modal dialog with
+/// [MODAL_FALLBACK_ATTRIBUTE], which user CSS positions above the page.
+pub fn show_modal(dialog: &HtmlDialogElement) {
+    if dialog.open() {
+        return;
+    }
+    if supports_modal_dialog() {
+        let _ = dialog.show_modal();
+    } else {
+        let _ = dialog.set_attribute(MODAL_FALLBACK_ATTRIBUTE, "");
+        dialog.set_open(true);
+    }
+}
+
+/// Closes the dialog shown by [show_modal]
+pub fn close_modal(dialog: &HtmlDialogElement) {
+    let _ = dialog.remove_attribute(MODAL_FALLBACK_ATTRIBUTE);
+    if dialog.open() {
+        dialog.close();
+    }
+}
@@ -0,0 +1,92 @@
+//! Diagnostic logging of seigi crates through the `log` facade
+//!
+//! With the `log` feature, widgets log their lifecycles, such as focus traps activating and
+//! toasts being dismissed, with targets of their crate names like `seigi_focus`. Without it,
+//! the logging macros expand to nothing.
+//!
+//! Logs reach any logger installed for the `log` crate. Applications without one can call
+//! [init] once to print them to the browser console.
+
+#[cfg(feature = "log")]
+#[doc(hidden)]
+pub use log as __log;
+
+#[cfg(feature = "log")]
+pub use log::LevelFilter;
+
+/// Logs a debug message of a widget lifecycle, like `log::debug!`
+#[cfg(feature = "log")]
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        $crate::trace::__log::debug!($($arg)+)
+    };
+}
+
+/// Logs a debug message of a widget lifecycle, like `log::debug!`
+#[cfg(not(feature = "log"))]
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        if false {
+            let _ = ::std::format_args!($($arg)+);
+        }
+    };
+}
+
+/// Logs a warning of misuse, like `log::warn!`
+#[cfg(feature = "log")]
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => {
+        $crate::trace::__log::warn!($($arg)+)
+    };
+}
+
+/// Logs a warning of misuse, like `log::warn!`
+#[cfg(not(feature = "log"))]
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => {
+        if false {
+            let _ = ::std::format_args!($($arg)+);
+        }
+    };
+}
+
+#[cfg(feature = "log")]
+struct ConsoleLogger;
+
+#[cfg(feature = "log")]
+impl log::Log for ConsoleLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let message =
+            wasm_bindgen::JsValue::from_str(&format!("[{}] {}", record.target(), record.args()));
+        match record.level() {
+            log::Level::Error => web_sys::console::error_1(&message),
+            log::Level::Warn => web_sys::console::warn_1(&message),
+            log::Level::Info => web_sys::console::info_1(&message),
+            log::Level::Debug | log::Level::Trace => web_sys::console::debug_1(&message),
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Prints logs up to the level to the browser console
+///
+/// Does nothing if another logger is already installed, whose level is kept.
+#[cfg(feature = "log")]
+pub fn init(level: LevelFilter) {
+    static LOGGER: ConsoleLogger = ConsoleLogger;
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(level);
+    }
+}
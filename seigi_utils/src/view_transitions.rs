@@ -0,0 +1,181 @@
+//! Animated transitions between states of the DOM with the View Transition API
+//!
+//! [start] captures the page, runs an update, and lets the browser animate between the old and
+//! new states. Browsers without the API, and users preferring reduced motion, get the update
+//! run at once without animation, so the same code works everywhere.
+//!
+//! Elements animate individually between the states when they share a `view-transition-name`,
+//! which [set_name] and [start_named] manage.
+
+use js_sys::{Function, Promise, Reflect};
+use wasm_bindgen::{JsCast, JsValue, closure::Closure};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::HtmlElement;
+
+use crate::media;
+
+const NAME_PROPERTY: &str = "view-transition-name";
+
+fn start_method() -> Option<Function> {
+    let document = gloo::utils::document();
+    Reflect::get(&document, &JsValue::from_str("startViewTransition"))
+        .ok()?
+        .dyn_into::<Function>()
+        .ok()
+}
+
+/// Returns whether the browser supports the View Transition API
+pub fn is_supported() -> bool {
+    start_method().is_some()
+}
+
+/// A phase of a [Transition]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// The update has run and the new state is in the DOM
+    Updated,
+    /// The animation is about to start
+    Ready,
+    /// The animation has finished and the new state is visible
+    Finished,
+}
+
+impl Phase {
+    fn property(&self) -> &'static str {
+        match self {
+            Self::Updated => "updateCallbackDone",
+            Self::Ready => "ready",
+            Self::Finished => "finished",
+        }
+    }
+}
+
+/// A transition started by [start]
+///
+/// Phases of transitions run without animation resolve at once.
+pub struct Transition {
+    /// The `ViewTransition`, or None if the update ran without animation
+    inner: Option<JsValue>,
+}
+
+impl Transition {
+    /// Returns whether the transition animates, which is false if the API is not supported or
+    /// the user prefers reduced motion
+    pub fn is_animated(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    /// Waits until the transition reaches the phase
+    ///
+    /// # Returns
+    /// Whether the phase was reached, which is false if the update failed, or the transition
+    /// was skipped before [Phase::Ready]
+    pub async fn phase(&self, phase: Phase) -> bool {
+        let Some(inner) = &self.inner else {
+            return true;
+        };
+        let Some(promise) = Reflect::get(inner, &JsValue::from_str(phase.property()))
+            .ok()
+            .and_then(|v| v.dyn_into::<Promise>().ok())
+        else {
+            return true;
+        };
+        JsFuture::from(promise).await.is_ok()
+    }
+
+    /// Waits until the update has run
+    pub async fn updated(&self) -> bool {
+        self.phase(Phase::Updated).await
+    }
+
+    /// Waits until the animation is about to start
+    pub async fn ready(&self) -> bool {
+        self.phase(Phase::Ready).await
+    }
+
+    /// Waits until the animation has finished, which also happens when skipped
+    pub async fn finished(&self) -> bool {
+        self.phase(Phase::Finished).await
+    }
+
+    /// Skips the animation, while the update still runs
+    pub fn skip(&self) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+        if let Some(skip) = Reflect::get(inner, &JsValue::from_str("skipTransition"))
+            .ok()
+            .and_then(|v| v.dyn_into::<Function>().ok())
+        {
+            let _ = skip.call0(inner);
+        }
+    }
+}
+
+/// Runs the update in a view transition
+///
+/// With animation, the update runs asynchronously once the browser has captured the old state,
+/// so state read right after this call may not be updated yet. Await [Transition::updated] to
+/// read it.
+pub fn start(update: impl FnOnce() + 'static) -> Transition {
+    let method = start_method().filter(|_| !media::prefers_reduced_motion());
+    let Some(method) = method else {
+        update();
+        return Transition { inner: None };
+    };
+
+    let callback = Closure::once_into_js(update);
+    match method.call1(&gloo::utils::document(), &callback) {
+        Ok(inner) => Transition { inner: Some(inner) },
+        // The callback is never called if starting failed, and is dropped with its update, so
+        // there's nothing to run again here
+        Err(_) => Transition { inner: None },
+    }
+}
+
+/// Runs the update in a view transition, naming the elements for its duration
+///
+/// Names are assigned before the old state is captured and cleared once the transition
+/// finishes. Elements created by the update must be named in the update to animate from
+/// named elements of the old state.
+pub fn start_named(names: &[(HtmlElement, &str)], update: impl FnOnce() + 'static) -> Transition {
+    for (element, name) in names {
+        set_name(element, name);
+    }
+    let elements: Vec<HtmlElement> = names.iter().map(|(v, _)| v.clone()).collect();
+    let transition = start(update);
+
+    let Some(inner) = transition.inner.clone() else {
+        for element in &elements {
+            clear_name(element);
+        }
+        return transition;
+    };
+    wasm_bindgen_futures::spawn_local(async move {
+        Transition { inner: Some(inner) }.finished().await;
+        for element in &elements {
+            clear_name(element);
+        }
+    });
+    transition
+}
+
+/// Sets `view-transition-name` of the element, which must be unique in the page while a
+/// transition runs
+pub fn set_name(element: &HtmlElement, name: &str) {
+    let _ = element.style().set_property(NAME_PROPERTY, name);
+}
+
+/// Removes `view-transition-name` of the element
+pub fn clear_name(element: &HtmlElement) {
+    let _ = element.style().remove_property(NAME_PROPERTY);
+}
+
+/// Returns `view-transition-name` set in the style of the element
+pub fn name(element: &HtmlElement) -> Option<String> {
+    element
+        .style()
+        .get_property_value(NAME_PROPERTY)
+        .ok()
+        .filter(|v| !v.is_empty())
+}
@@ -0,0 +1,73 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_testkit::Fixture;
+use seigi_utils::audit::{self, Issue};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn issues(fixture: &Fixture) -> Vec<Issue> {
+    let root = fixture.root();
+    audit::document()
+        .into_iter()
+        .filter(|v| root.contains(Some(&v.element)))
+        .map(|v| v.issue)
+        .collect()
+}
+
+#[wasm_bindgen_test]
+fn labelled_dialogs_pass() {
+    let fixture = Fixture::new(
+        r#"
+        <div role="dialog" aria-labelledby="title"><h2 id="title">Settings</h2></div>
+        <dialog open aria-label="Search"></dialog>
+        <dialog><p>Closed dialogs aren't audited</p></dialog>
+        "#,
+    );
+    assert_eq!(issues(&fixture), []);
+}
+
+#[wasm_bindgen_test]
+fn unlabelled_dialog_is_reported() {
+    let fixture = Fixture::new(r#"<div role="alertdialog"><button>OK</button></div>"#);
+    assert_eq!(issues(&fixture), [Issue::MissingLabel]);
+    assert_eq!(
+        audit::dialog_issues(&fixture.get("[role=alertdialog]")),
+        [Issue::MissingLabel]
+    );
+}
+
+#[wasm_bindgen_test]
+fn missing_references_and_duplicate_ids_are_reported() {
+    let fixture = Fixture::new(
+        r#"
+        <div role="dialog" aria-labelledby="missing" aria-describedby="same">
+            <p id="same">First</p>
+            <p id="same">Second</p>
+        </div>
+        "#,
+    );
+    let dialog = fixture.get("[role=dialog]");
+    assert_eq!(
+        audit::dialog_issues(&dialog),
+        [
+            Issue::MissingReference("missing".to_string()),
+            Issue::DuplicateId("same".to_string()),
+            Issue::DuplicateId("same".to_string()),
+        ]
+    );
+    assert!(issues(&fixture).contains(&Issue::DuplicateId("same".to_string())));
+}
+
+#[wasm_bindgen_test]
+fn live_regions_need_politeness() {
+    let fixture = Fixture::new(
+        r#"<div id="live" aria-live="polite"></div><div id="status" role="status"></div><div id="dead"></div>"#,
+    );
+    assert_eq!(audit::live_region_issues(&fixture.get("#live")), []);
+    assert_eq!(audit::live_region_issues(&fixture.get("#status")), []);
+    assert_eq!(
+        audit::live_region_issues(&fixture.get("#dead")),
+        [Issue::MissingPoliteness]
+    );
+}
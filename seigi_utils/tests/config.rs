@@ -0,0 +1,40 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::time::Duration;
+
+use seigi_utils::{
+    config::{self, GlobalConfig, MotionPolicy, Position},
+    id, media,
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn configure_applies_defaults() {
+    let previous = config::config();
+    config::configure(
+        GlobalConfig::builder()
+            .toast_timeout(Some(Duration::from_secs(8)))
+            .toast_position(Position::TopCenter)
+            .deactivate_on_escape(true)
+            .motion(MotionPolicy::Reduce)
+            .id_prefix("app")
+            .build(),
+    );
+
+    let current = config::config();
+    assert_eq!(current.toast_timeout, Some(Duration::from_secs(8)));
+    assert_eq!(current.toast_position, Position::TopCenter);
+    assert!(current.deactivate_on_escape);
+    assert_eq!(id::prefix(), "app");
+    assert!(media::prefers_reduced_motion());
+
+    config::configure(GlobalConfig {
+        motion: MotionPolicy::Allow,
+        ..current
+    });
+    assert!(!media::prefers_reduced_motion());
+
+    config::configure(previous);
+}
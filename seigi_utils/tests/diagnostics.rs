@@ -0,0 +1,34 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_utils::{
+    diagnostics::{self, Resource},
+    listener::Listener,
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn resources_are_counted_while_alive() {
+    if !diagnostics::is_enabled() {
+        assert!(diagnostics::report().entries.is_empty());
+        return;
+    }
+
+    let tracked = diagnostics::track_in("integration", Resource::Closure);
+    diagnostics::track_in("integration", Resource::Closure).forget();
+    let report = diagnostics::report();
+    assert_eq!(report.live("integration", Resource::Closure), 2);
+    drop(tracked);
+    assert_eq!(
+        diagnostics::report().live("integration", Resource::Closure),
+        1
+    );
+
+    // Listeners are counted in the file registering them, which isn't in a package's `src`
+    let mut listener = Listener::new();
+    listener.on(&gloo::utils::window(), "resize", |_| {});
+    assert_eq!(diagnostics::report().live(file!(), Resource::Listener), 1);
+    listener.abort();
+    assert_eq!(diagnostics::report().live(file!(), Resource::Listener), 0);
+}
@@ -0,0 +1,26 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_utils::ime;
+use wasm_bindgen_test::*;
+use web_sys::{KeyboardEvent, KeyboardEventInit};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn key_down(init: &KeyboardEventInit) -> KeyboardEvent {
+    KeyboardEvent::new_with_keyboard_event_init_dict("keydown", init).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn composition_is_detected() {
+    let init = KeyboardEventInit::new();
+    init.set_key("Enter");
+    assert!(!ime::is_composing(&key_down(&init)));
+
+    init.set_is_composing(true);
+    assert!(ime::is_composing(&key_down(&init)));
+
+    // Safari confirms compositions with keyCode 229 and isComposing unset
+    init.set_is_composing(false);
+    init.set_key_code(229);
+    assert!(ime::is_composing(&key_down(&init)));
+}
@@ -0,0 +1,24 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_utils::keyboard;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn keyboard_is_hidden_without_touch() {
+    // Test browsers have no on-screen keyboard, so the viewports agree
+    assert_eq!(keyboard::height(), 0.0);
+    assert!(!keyboard::is_visible());
+}
+
+#[wasm_bindgen_test]
+fn subscriptions_are_independent() {
+    let first = keyboard::subscribe(Box::new(|_| {}));
+    let second = keyboard::subscribe(Box::new(|_| {}));
+    assert_ne!(first, second);
+    keyboard::unsubscribe(first);
+    keyboard::unsubscribe(second);
+    // Subscribing again listens again
+    keyboard::unsubscribe(keyboard::subscribe(Box::new(|_| {})));
+}
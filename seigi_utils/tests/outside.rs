@@ -0,0 +1,80 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_testkit::Fixture;
+use seigi_utils::outside;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn hiding_others_leaves_zones_and_live_regions() {
+    let fixture = Fixture::new(
+        r#"
+<main id="main"><button>Behind</button></main>
+<aside id="hidden" aria-hidden="true"></aside>
+<div id="wrapper">
+    <section id="toasts"></section>
+    <p id="sibling">Sibling</p>
+</div>
+<div id="status" aria-live="polite"></div>
+<div id="layer"><div id="dialog"></div><p id="beside">Beside</p></div>
+"#,
+    );
+    let hidden = |selector: &str| fixture.get(selector).get_attribute("aria-hidden");
+    let zone = outside::allow(&fixture.get("#toasts"));
+
+    let others = outside::hide_others(&fixture.get("#dialog"));
+    assert_eq!(hidden("#main").as_deref(), Some("true"));
+    assert_eq!(hidden("#beside").as_deref(), Some("true"));
+    assert_eq!(hidden("#sibling").as_deref(), Some("true"));
+    // Ancestors of zones stay, along with zones, live regions and the dialog itself
+    assert_eq!(hidden("#wrapper"), None);
+    assert_eq!(hidden("#toasts"), None);
+    assert_eq!(hidden("#status"), None);
+    assert_eq!(hidden("#layer"), None);
+    assert_eq!(hidden("#dialog"), None);
+
+    drop(others);
+    outside::disallow(zone);
+    assert_eq!(hidden("#main"), None);
+    assert_eq!(hidden("#sibling"), None);
+    // Hidden before, so left as it was
+    assert_eq!(hidden("#hidden").as_deref(), Some("true"));
+}
+
+#[wasm_bindgen_test]
+fn zones_are_registered_by_selectors_and_attributes() {
+    let fixture = Fixture::new(
+        r#"
+<main id="main"><p id="text">Text</p></main>
+<div id="dialog"></div>
+<aside id="devtools" data-seigi-trusted><button id="inspect">Inspect</button></aside>
+"#,
+    );
+    let is_allowed = |selector: &str| outside::is_allowed(&fixture.get(selector));
+    assert!(is_allowed("#inspect"));
+    assert!(!is_allowed("#text"));
+
+    // Banners inserted after registering are allowed too
+    let zone = outside::allow_selector(".cookie-banner");
+    let banner = gloo::utils::document().create_element("div").unwrap();
+    banner.set_class_name("cookie-banner");
+    banner.set_inner_html("<button id=\"accept\">Accept</button>");
+    fixture.root().append_child(&banner).unwrap();
+    assert!(is_allowed("#accept"));
+    assert!(outside::is_allowed(
+        &fixture.get("#accept").first_child().unwrap()
+    ));
+
+    let others = outside::hide_others(&fixture.get("#dialog"));
+    assert_eq!(
+        fixture.get("#main").get_attribute("aria-hidden").as_deref(),
+        Some("true")
+    );
+    assert_eq!(fixture.get("#devtools").get_attribute("aria-hidden"), None);
+    assert_eq!(banner.get_attribute("aria-hidden"), None);
+    drop(others);
+
+    outside::disallow(zone);
+    assert!(!is_allowed("#accept"));
+}
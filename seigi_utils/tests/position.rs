@@ -0,0 +1,94 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_testkit::Fixture;
+use seigi_utils::position::{
+    self, Align, Boundary, Placement, PositionOptions, Rect, Side, clipping_ancestors,
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn overflowing_elements_flip_and_shift() {
+    let boundary = Rect::new(0.0, 0.0, 400.0, 600.0);
+    let options = PositionOptions::default();
+
+    // Below the anchor fits
+    let computed = position::compute_rects(
+        &Rect::new(150.0, 100.0, 100.0, 20.0),
+        (100.0, 50.0),
+        &boundary,
+        &options,
+    );
+    assert_eq!((computed.x, computed.y), (150.0, 128.0));
+    assert_eq!(computed.placement.side, Side::Bottom);
+
+    // Near the bottom edge, the element flips above the anchor
+    let computed = position::compute_rects(
+        &Rect::new(150.0, 560.0, 100.0, 20.0),
+        (100.0, 50.0),
+        &boundary,
+        &options,
+    );
+    assert_eq!(computed.placement.side, Side::Top);
+    assert_eq!(computed.y, 502.0);
+
+    // Near the left edge, the element shifts inside the padding
+    let computed = position::compute_rects(
+        &Rect::new(0.0, 100.0, 20.0, 20.0),
+        (100.0, 50.0),
+        &boundary,
+        &options,
+    );
+    assert_eq!(computed.x, 8.0);
+
+    // Without flipping and shifting, the element overflows
+    let computed = position::compute_rects(
+        &Rect::new(0.0, 560.0, 20.0, 20.0),
+        (100.0, 50.0),
+        &boundary,
+        &PositionOptions {
+            placement: Placement::new(Side::Bottom, Align::Start),
+            flip: false,
+            shift: false,
+            ..PositionOptions::default()
+        },
+    );
+    assert_eq!((computed.x, computed.y), (0.0, 588.0));
+    assert!(!computed.is_anchor_hidden);
+
+    let computed = position::compute_rects(
+        &Rect::new(0.0, 700.0, 20.0, 20.0),
+        (100.0, 50.0),
+        &boundary,
+        &options,
+    );
+    assert!(computed.is_anchor_hidden);
+}
+
+#[wasm_bindgen_test]
+fn scrollable_panels_bound_their_descendants() {
+    let fixture = Fixture::new(
+        r#"
+<div id="panel" style="overflow: auto; height: 100px; width: 200px; border: 0; padding: 0">
+    <div style="height: 400px"><button id="anchor">Anchor</button></div>
+</div>
+<div id="frame" style="height: 50px; width: 50px"></div>
+"#,
+    );
+    let anchor = fixture.get("#anchor");
+    let panel = fixture.get("#panel");
+    assert_eq!(clipping_ancestors(&anchor), [panel.clone().into()]);
+
+    let panel_rect = Rect::of(&panel);
+    let boundary = position::boundary_rect(&anchor, &Boundary::ClippingAncestors);
+    assert!(boundary.height <= panel_rect.height);
+    assert!(boundary.y >= panel_rect.y.max(0.0));
+
+    let viewport = position::boundary_rect(&anchor, &Boundary::Viewport);
+    assert!(viewport.height > boundary.height);
+
+    let frame = fixture.get("#frame");
+    let explicit = position::boundary_rect(&anchor, &Boundary::Element(frame.clone().into()));
+    assert_eq!(explicit.width, frame.client_width() as f64);
+}
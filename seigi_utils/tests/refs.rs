@@ -0,0 +1,48 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_testkit::{Fixture, wait};
+use seigi_utils::refs::Refs;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn refs_follow_changes_of_subtree() {
+    let fixture = Fixture::new(
+        r#"
+<div id="root" data-seigi-ref="root">
+    <h2 id="title" data-seigi-ref="title heading">Title</h2>
+    <button id="first" data-seigi-ref="action">First</button>
+    <button id="second" data-seigi-ref="action">Second</button>
+</div>
+"#,
+    );
+    let refs = Refs::new(fixture.get("#root").into());
+    let ids = |name: &str| {
+        refs.get_all(name)
+            .iter()
+            .map(|v| v.id())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(refs.get("title").map(|v| v.id()).as_deref(), Some("title"));
+    assert_eq!(refs.get("heading"), refs.get("title"));
+    assert_eq!(ids("action"), ["first", "second"]);
+    // The root itself isn't referenced
+    assert!(refs.get("root").is_none());
+
+    // Changes in the same task are seen right away
+    fixture.get("#first").remove();
+    assert_eq!(ids("action"), ["second"]);
+    fixture
+        .get("#title")
+        .set_attribute("data-seigi-ref", "label")
+        .unwrap();
+    assert!(refs.get("title").is_none());
+    assert_eq!(refs.get("label").map(|v| v.id()).as_deref(), Some("title"));
+
+    wait::tick().await;
+    let button = fixture.get("#second").clone_node().unwrap();
+    fixture.get("#root").append_child(&button).unwrap();
+    assert_eq!(refs.get_all("action").len(), 2);
+}
@@ -0,0 +1,30 @@
+#![cfg(target_arch = "wasm32")]
+
+use seigi_testkit::{Fixture, assert_focused, wait};
+use seigi_utils::schedule;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn scheduled_focus_lands_unless_cancelled() {
+    let fixture =
+        Fixture::new(r#"<button id="first">First</button><button id="second">Second</button>"#);
+    let first = fixture.get("#first");
+    let second = fixture.get("#second");
+
+    let focus = schedule::focus(first.clone());
+    assert!(focus.is_pending());
+    wait::tick().await;
+    assert!(!focus.is_pending());
+    assert_focused(&first);
+
+    schedule::focus(second.clone()).cancel();
+    drop(schedule::focus(second.clone()));
+    wait::tick().await;
+    assert_focused(&first);
+
+    schedule::focus(second.clone()).forget();
+    wait::tick().await;
+    assert_focused(&second);
+}
@@ -0,0 +1,79 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{cell::Cell, rc::Rc};
+
+use seigi_testkit::{Fixture, wait};
+use seigi_utils::top_layer::{self, Popover};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::HtmlDialogElement;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn is_open(element: &web_sys::HtmlElement) -> bool {
+    if top_layer::is_popover(element) {
+        element.matches(":popover-open").unwrap()
+    } else {
+        !element.hidden()
+    }
+}
+
+#[wasm_bindgen_test]
+fn popovers_show_and_hide() {
+    let fixture = Fixture::new(r#"<div id="popover">Popover</div>"#);
+    let element = fixture.get("#popover");
+
+    let is_popover = top_layer::make_popover(&element, Popover::Manual);
+    assert_eq!(is_popover, top_layer::supports_popover());
+    assert!(!is_open(&element));
+
+    top_layer::show(&element);
+    assert!(is_open(&element));
+    // Showing twice doesn't throw
+    top_layer::show(&element);
+    top_layer::raise(&element);
+    assert!(is_open(&element));
+
+    top_layer::hide(&element);
+    assert!(!is_open(&element));
+}
+
+#[wasm_bindgen_test]
+async fn hiding_is_reported() {
+    if !top_layer::supports_popover() {
+        return;
+    }
+    let fixture = Fixture::new(r#"<div id="popover">Popover</div>"#);
+    let element = fixture.get("#popover");
+    top_layer::make_popover(&element, Popover::Auto);
+    let dismissed = Rc::new(Cell::new(false));
+    let _listener = top_layer::on_dismiss(&element, {
+        let dismissed = dismissed.clone();
+        move || dismissed.set(true)
+    });
+
+    top_layer::show(&element);
+    wait::tick().await;
+    assert!(!dismissed.get());
+
+    element.hide_popover().unwrap();
+    wait::tick().await;
+    assert!(dismissed.get());
+}
+
+#[wasm_bindgen_test]
+fn modal_dialogs_open_and_close() {
+    let fixture = Fixture::new(r#"<dialog id="dialog">Dialog</dialog>"#);
+    let dialog = fixture.get("#dialog").unchecked_into::<HtmlDialogElement>();
+
+    top_layer::show_modal(&dialog);
+    assert!(dialog.open());
+    assert_eq!(
+        dialog.matches(":modal").unwrap_or(false),
+        top_layer::supports_modal_dialog()
+    );
+
+    top_layer::close_modal(&dialog);
+    assert!(!dialog.open());
+    assert!(!dialog.has_attribute(top_layer::MODAL_FALLBACK_ATTRIBUTE));
+}
@@ -0,0 +1,530 @@
+//! Validation of form fields by rules, reflected to the DOM
+//!
+//! A [Validator] checks fields against their [Rule]s and reflects errors to the elements, so
+//! assistive technologies announce them and styles can show them. Errors are also set as custom
+//! validity of the elements, so `:invalid` and constraint validation of forms agree with them.
+//!
+//! Messages of built-in rules are looked up in [seigi_utils::i18n] by keys like
+//! `validate.required`.
+
+pub mod rule;
+
+use std::{
+    collections::{HashMap, HashSet},
+    rc::{Rc, Weak},
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use gloo::events::EventListener;
+use seigi_utils::id::{self, Relation};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+
+pub use rule::{Rule, ValidationError, Values};
+
+/// A field validated by rules
+pub struct Field {
+    pub name: String,
+    /// An input, a select or a textarea holding the value
+    pub element: HtmlElement,
+    /// The element the first error message is written to
+    pub error: Option<HtmlElement>,
+    pub rules: Vec<Rule>,
+}
+
+impl Field {
+    pub fn new(name: impl Into<String>, element: HtmlElement) -> Self {
+        Self {
+            name: name.into(),
+            element,
+            error: None,
+            rules: vec![],
+        }
+    }
+
+    pub fn error(mut self, error: HtmlElement) -> Self {
+        self.error = Some(error);
+        self
+    }
+
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Returns the value of the element, which is empty for unchecked checkboxes and radios
+    pub fn value(&self) -> String {
+        let element = &self.element;
+        if let Some(input) = element.dyn_ref::<HtmlInputElement>() {
+            let is_checkable = matches!(input.type_().as_str(), "checkbox" | "radio");
+            if is_checkable && !input.checked() {
+                return String::new();
+            }
+            input.value()
+        } else if let Some(select) = element.dyn_ref::<HtmlSelectElement>() {
+            select.value()
+        } else if let Some(textarea) = element.dyn_ref::<HtmlTextAreaElement>() {
+            textarea.value()
+        } else {
+            element.text_content().unwrap_or_default()
+        }
+    }
+
+    fn set_custom_validity(&self, message: &str) {
+        let element = &self.element;
+        if let Some(input) = element.dyn_ref::<HtmlInputElement>() {
+            input.set_custom_validity(message);
+        } else if let Some(select) = element.dyn_ref::<HtmlSelectElement>() {
+            select.set_custom_validity(message);
+        } else if let Some(textarea) = element.dyn_ref::<HtmlTextAreaElement>() {
+            textarea.set_custom_validity(message);
+        }
+    }
+
+    fn reflect(&self, errors: &[ValidationError]) {
+        let element = &self.element;
+        let message = errors.first().map(|v| v.message.as_str());
+        self.set_custom_validity(message.unwrap_or_default());
+        match message {
+            Some(_) => {
+                let _ = element.set_attribute("aria-invalid", "true");
+                let _ = element.set_attribute("data-invalid", "");
+            }
+            None => {
+                let _ = element.remove_attribute("aria-invalid");
+                let _ = element.remove_attribute("data-invalid");
+            }
+        }
+
+        let Some(error) = &self.error else {
+            return;
+        };
+        id::ensure(error, "error");
+        error.set_text_content(message);
+        error.set_hidden(message.is_none());
+        // aria-errormessage is only read while the element is invalid, but some screen readers
+        // read it regardless
+        if message.is_some() {
+            id::relate(element, Relation::ErrorMessage, error);
+        } else {
+            id::unrelate(element, Relation::ErrorMessage, error);
+        }
+    }
+}
+
+/// When fields are validated without calling [Validator::validate]
+///
+/// Fields with errors are checked again on every input regardless, so errors disappear as soon
+/// as they are fixed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    /// Only when validated explicitly, such as on submit
+    Submit,
+    /// When focus leaves the field
+    #[default]
+    Blur,
+    /// On every input
+    Input,
+}
+
+/// Called with the name of a field and its errors when they change
+pub type ChangeHook = Box<dyn Fn(&str, &[ValidationError])>;
+
+/// Hooks to [Validator]
+#[derive(Default)]
+pub struct ValidatorHooks {
+    pub change: Option<ChangeHook>,
+}
+
+/// Options of [Validator]
+pub struct ValidatorOptions {
+    pub fields: Vec<Field>,
+    pub trigger: Trigger,
+    pub hooks: ValidatorHooks,
+}
+
+impl ValidatorOptions {
+    pub fn builder() -> ValidatorOptionsBuilder {
+        ValidatorOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [ValidatorOptions]
+#[derive(Default)]
+pub struct ValidatorOptionsBuilder {
+    fields: Vec<Field>,
+    trigger: Trigger,
+    hooks: ValidatorHooks,
+}
+
+impl ValidatorOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn field(mut self, field: Field) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    pub fn trigger(mut self, trigger: Trigger) -> Self {
+        self.trigger = trigger;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: ValidatorHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [ValidatorOptions]
+    pub fn build(self) -> ValidatorOptions {
+        ValidatorOptions {
+            fields: self.fields,
+            trigger: self.trigger,
+            hooks: self.hooks,
+        }
+    }
+}
+
+struct Subscriber {
+    callback: ChangeHook,
+    handle: u64,
+}
+
+struct Inner {
+    options: Rc<ValidatorOptions>,
+    errors: HashMap<String, Vec<ValidationError>>,
+    /// Results of asynchronous rules with the values they checked, reused while values stay
+    async_errors: HashMap<String, (String, Vec<ValidationError>)>,
+    /// Fields validated at least once, which are checked again on input
+    touched: HashSet<String>,
+    subscribers: Vec<Rc<Subscriber>>,
+    _listeners: Vec<EventListener>,
+}
+
+/// An instance of validator
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **aria-invalid** and **data-invalid** are set in elements of fields with errors
+///
+/// **aria-errormessage** is set in elements of fields with errors to their error elements, whose
+/// text is set to the first message, and which are hidden while there is none
+#[derive(Clone)]
+pub struct Validator(Rc<Mutex<Inner>>);
+
+impl Validator {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    fn options(&self) -> Rc<ValidatorOptions> {
+        self.0.lock().unwrap().options.clone()
+    }
+
+    /// Returns names of fields in the order they were added
+    pub fn fields(&self) -> Vec<String> {
+        self.options()
+            .fields
+            .iter()
+            .map(|v| v.name.clone())
+            .collect()
+    }
+
+    /// Returns values of every field
+    pub fn values(&self) -> Values {
+        self.options()
+            .fields
+            .iter()
+            .map(|v| (v.name.clone(), v.value()))
+            .collect()
+    }
+
+    /// Returns the element of the field
+    pub fn element(&self, name: &str) -> Option<HtmlElement> {
+        self.options()
+            .fields
+            .iter()
+            .find(|v| v.name == name)
+            .map(|v| v.element.clone())
+    }
+
+    /// Returns errors of the field from when it was last validated
+    pub fn errors(&self, name: &str) -> Vec<ValidationError> {
+        self.0
+            .lock()
+            .unwrap()
+            .errors
+            .get(name)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Runs synchronous rules of the field, with results of asynchronous ones if its value is
+    /// unchanged since they ran
+    fn check_errors(&self, field: &Field, values: &Values) -> Vec<ValidationError> {
+        let value = values.get(&field.name).cloned().unwrap_or_default();
+        let mut errors = field
+            .rules
+            .iter()
+            .filter_map(|v| v.check(&value, values))
+            .collect::<Vec<_>>();
+        if errors.is_empty()
+            && let Some((checked, async_errors)) =
+                self.0.lock().unwrap().async_errors.get(&field.name)
+            && *checked == value
+        {
+            errors.extend(async_errors.iter().cloned());
+        }
+        errors
+    }
+
+    /// Returns whether every field passes synchronous rules, without reflecting errors
+    pub fn is_valid(&self) -> bool {
+        let values = self.values();
+        self.options()
+            .fields
+            .iter()
+            .all(|v| self.check_errors(v, &values).is_empty())
+    }
+
+    fn set_errors_of(&self, field: &Field, errors: Vec<ValidationError>) {
+        let is_changed = {
+            let mut inner = self.0.lock().unwrap();
+            inner.touched.insert(field.name.clone());
+            let previous = inner.errors.insert(field.name.clone(), errors.clone());
+            previous.unwrap_or_default() != errors
+        };
+        field.reflect(&errors);
+        if is_changed {
+            self.notify(&field.name, &errors);
+        }
+    }
+
+    fn notify(&self, name: &str, errors: &[ValidationError]) {
+        if let Some(hook) = &self.options().hooks.change {
+            hook(name, errors);
+        }
+        let subscribers = self.0.lock().unwrap().subscribers.clone();
+        for subscriber in subscribers {
+            (subscriber.callback)(name, errors);
+        }
+    }
+
+    /// Subscribes to changes of errors, like [ValidatorHooks::change]
+    ///
+    /// # Returns
+    /// A handle to unsubscribe with [Validator::unsubscribe]
+    pub fn subscribe(&self, callback: ChangeHook) -> u64 {
+        static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+        let handle = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .push(Rc::new(Subscriber { callback, handle }));
+        handle
+    }
+
+    pub fn unsubscribe(&self, handle: u64) {
+        self.0
+            .lock()
+            .unwrap()
+            .subscribers
+            .retain(|v| v.handle != handle);
+    }
+
+    /// Sets errors of the field, such as those returned by a server
+    pub fn set_errors(&self, name: &str, errors: Vec<ValidationError>) {
+        let options = self.options();
+        if let Some(field) = options.fields.iter().find(|v| v.name == name) {
+            self.set_errors_of(field, errors);
+        }
+    }
+
+    /// Checks the field against synchronous rules and reflects errors
+    ///
+    /// # Returns
+    /// Whether the field is valid
+    pub fn check_field(&self, name: &str) -> bool {
+        let options = self.options();
+        let Some(field) = options.fields.iter().find(|v| v.name == name) else {
+            return true;
+        };
+        let errors = self.check_errors(field, &self.values());
+        let is_valid = errors.is_empty();
+        self.set_errors_of(field, errors);
+        is_valid
+    }
+
+    /// Checks every field against synchronous rules and reflects errors
+    ///
+    /// # Returns
+    /// Whether every field is valid
+    pub fn check(&self) -> bool {
+        let values = self.values();
+        let mut is_valid = true;
+        for field in &self.options().fields {
+            let errors = self.check_errors(field, &values);
+            is_valid &= errors.is_empty();
+            self.set_errors_of(field, errors);
+        }
+        is_valid
+    }
+
+    /// Validates the field against every rule, and reflects errors
+    ///
+    /// Errors are discarded if the value changed while asynchronous rules ran.
+    ///
+    /// # Returns
+    /// Whether the field is valid
+    pub async fn validate_field(&self, name: &str) -> bool {
+        let options = self.options();
+        let Some(field) = options.fields.iter().find(|v| v.name == name) else {
+            return true;
+        };
+        let values = self.values();
+        let value = field.value();
+        let mut errors = field
+            .rules
+            .iter()
+            .filter_map(|v| v.check(&value, &values))
+            .collect::<Vec<_>>();
+        if errors.is_empty() {
+            let mut async_errors = vec![];
+            for rule in field.rules.iter().filter(|v| v.is_async()) {
+                if let Some(error) = rule.check_async(&value, &values).await {
+                    async_errors.push(error);
+                }
+            }
+            if field.value() != value {
+                return self.errors(name).is_empty();
+            }
+            self.0
+                .lock()
+                .unwrap()
+                .async_errors
+                .insert(field.name.clone(), (value, async_errors.clone()));
+            errors = async_errors;
+        }
+        let is_valid = errors.is_empty();
+        self.set_errors_of(field, errors);
+        is_valid
+    }
+
+    /// Validates every field against every rule, and reflects errors
+    ///
+    /// # Returns
+    /// Whether every field is valid
+    pub async fn validate(&self) -> bool {
+        let mut is_valid = true;
+        for name in self.fields() {
+            is_valid &= self.validate_field(&name).await;
+        }
+        is_valid
+    }
+
+    /// Focuses the first field with errors
+    ///
+    /// # Returns
+    /// Whether there was one
+    pub fn focus_first_invalid(&self) -> bool {
+        let options = self.options();
+        let inner = self.0.lock().unwrap();
+        let Some(field) = options
+            .fields
+            .iter()
+            .find(|v| inner.errors.get(&v.name).is_some_and(|v| !v.is_empty()))
+        else {
+            return false;
+        };
+        drop(inner);
+        let _ = field.element.focus();
+        true
+    }
+
+    /// Clears errors of every field
+    pub fn reset(&self) {
+        let errors = {
+            let mut inner = self.0.lock().unwrap();
+            inner.async_errors.clear();
+            inner.touched.clear();
+            std::mem::take(&mut inner.errors)
+        };
+        for field in &self.options().fields {
+            field.reflect(&[]);
+            if errors.get(&field.name).is_some_and(|v| !v.is_empty()) {
+                self.notify(&field.name, &[]);
+            }
+        }
+    }
+
+    fn handle_input(&self, name: &str) {
+        let (trigger, touched) = {
+            let inner = self.0.lock().unwrap();
+            (inner.options.trigger, inner.touched.clone())
+        };
+        if trigger == Trigger::Input {
+            let validator = self.clone();
+            let name = name.to_string();
+            wasm_bindgen_futures::spawn_local(async move {
+                validator.validate_field(&name).await;
+            });
+        }
+        // Fields compared with this one are checked again too
+        for field in touched.iter().filter(|v| *v != name) {
+            self.check_field(field);
+        }
+        if trigger != Trigger::Input && touched.contains(name) {
+            self.check_field(name);
+        }
+    }
+
+    fn handle_blur(&self, name: &str) {
+        if self.0.lock().unwrap().options.trigger != Trigger::Blur {
+            return;
+        }
+        let validator = self.clone();
+        let name = name.to_string();
+        wasm_bindgen_futures::spawn_local(async move {
+            validator.validate_field(&name).await;
+        });
+    }
+}
+
+pub fn create_validator(options: ValidatorOptions) -> Validator {
+    let options = Rc::new(options);
+    let validator = Validator(Rc::new(Mutex::new(Inner {
+        options: options.clone(),
+        errors: HashMap::new(),
+        async_errors: HashMap::new(),
+        touched: HashSet::new(),
+        subscribers: vec![],
+        _listeners: vec![],
+    })));
+
+    let weak = Rc::downgrade(&validator.0);
+    let mut listeners = vec![];
+    for field in &options.fields {
+        let name = field.name.clone();
+        let listener = |event_type: &'static str, f: fn(&Validator, &str)| {
+            let (weak, name) = (weak.clone(), name.clone());
+            EventListener::new(&field.element, event_type, move |_| {
+                if let Some(validator) = Validator::from_weak(&weak) {
+                    f(&validator, &name);
+                }
+            })
+        };
+        listeners.push(listener("input", Validator::handle_input));
+        listeners.push(listener("change", Validator::handle_input));
+        listeners.push(listener("focusout", Validator::handle_blur));
+    }
+    validator.0.lock().unwrap()._listeners = listeners;
+    validator
+}
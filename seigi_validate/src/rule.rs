@@ -0,0 +1,184 @@
+//! Rules values of fields are checked against
+
+use std::{collections::HashMap, fmt::Display, future::Future, pin::Pin, rc::Rc};
+
+use js_sys::RegExp;
+use seigi_utils::i18n;
+
+/// Values of every field by their names, for rules comparing fields
+pub type Values = HashMap<String, String>;
+
+/// A failed rule of a field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// The kind of the rule, like `required`, which is also the key of its message in
+    /// [i18n] as `validate.{code}`
+    pub code: String,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Creates an error with the message of the code in [i18n]
+    pub fn translated(code: &str, args: &[(&str, &dyn Display)]) -> Self {
+        Self::new(code, i18n::translate(&format!("validate.{code}"), args))
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+pub type Check = Rc<dyn Fn(&str, &Values) -> Option<ValidationError>>;
+pub type AsyncCheck =
+    Rc<dyn Fn(String, Values) -> Pin<Box<dyn Future<Output = Option<ValidationError>>>>>;
+
+#[derive(Clone)]
+enum Kind {
+    Sync(Check),
+    Async(AsyncCheck),
+}
+
+/// A rule a value of a field must satisfy
+///
+/// Rules other than [Rule::required] pass empty values, so optional fields are only checked
+/// once filled.
+///
+/// # Examples
+/// `Rule::required()` and `Rule::min_length(8)` together require a value of at least 8
+/// characters
+#[derive(Clone)]
+pub struct Rule {
+    kind: Kind,
+    /// Whether the rule checks empty values too
+    checks_empty: bool,
+    message: Option<String>,
+}
+
+impl Rule {
+    /// Creates a rule from the check, which returns an error if the value fails
+    pub fn custom(check: impl Fn(&str, &Values) -> Option<ValidationError> + 'static) -> Self {
+        Self {
+            kind: Kind::Sync(Rc::new(check)),
+            checks_empty: false,
+            message: None,
+        }
+    }
+
+    /// Creates a rule from the asynchronous check, such as a request to a server
+    ///
+    /// Asynchronous rules run only after synchronous ones of the field pass.
+    pub fn custom_async<F>(check: impl Fn(String, Values) -> F + 'static) -> Self
+    where
+        F: Future<Output = Option<ValidationError>> + 'static,
+    {
+        Self {
+            kind: Kind::Async(Rc::new(move |value, values| Box::pin(check(value, values)))),
+            checks_empty: false,
+            message: None,
+        }
+    }
+
+    pub fn required() -> Self {
+        Self {
+            checks_empty: true,
+            ..Self::custom(|value, _| {
+                value
+                    .trim()
+                    .is_empty()
+                    .then(|| ValidationError::translated("required", &[]))
+            })
+        }
+    }
+
+    /// Requires at least the count of characters
+    pub fn min_length(min: usize) -> Self {
+        Self::custom(move |value, _| {
+            (value.chars().count() < min)
+                .then(|| ValidationError::translated("min_length", &[("min", &min)]))
+        })
+    }
+
+    /// Requires at most the count of characters
+    pub fn max_length(max: usize) -> Self {
+        Self::custom(move |value, _| {
+            (value.chars().count() > max)
+                .then(|| ValidationError::translated("max_length", &[("max", &max)]))
+        })
+    }
+
+    /// Requires the value to match the JavaScript regular expression entirely, like `pattern`
+    /// attribute of inputs
+    pub fn pattern(pattern: &str) -> Self {
+        let regex = RegExp::new(&format!("^(?:{pattern})$"), "u");
+        Self::custom(move |value, _| {
+            (!regex.test(value)).then(|| ValidationError::translated("pattern", &[]))
+        })
+    }
+
+    /// Requires the value to equal the value of the other field, such as to confirm passwords
+    pub fn matches(field: impl Into<String>) -> Self {
+        let field = field.into();
+        Self {
+            checks_empty: true,
+            ..Self::custom(move |value, values| {
+                let other = values.get(&field).map(String::as_str).unwrap_or_default();
+                (value != other).then(|| ValidationError::translated("matches", &[]))
+            })
+        }
+    }
+
+    /// Replaces the message of errors of the rule
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    pub fn is_async(&self) -> bool {
+        matches!(self.kind, Kind::Async(_))
+    }
+
+    fn customize(&self, error: ValidationError) -> ValidationError {
+        match &self.message {
+            Some(message) => ValidationError::new(error.code, message.clone()),
+            None => error,
+        }
+    }
+
+    fn skips(&self, value: &str) -> bool {
+        !self.checks_empty && value.is_empty()
+    }
+
+    /// Checks the value if the rule is synchronous
+    ///
+    /// # Returns
+    /// None if the value passes or the rule is asynchronous
+    pub fn check(&self, value: &str, values: &Values) -> Option<ValidationError> {
+        match &self.kind {
+            Kind::Sync(check) if !self.skips(value) => {
+                check(value, values).map(|v| self.customize(v))
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks the value, waiting for the rule if it is asynchronous
+    pub async fn check_async(&self, value: &str, values: &Values) -> Option<ValidationError> {
+        match &self.kind {
+            Kind::Async(check) if !self.skips(value) => check(value.to_string(), values.clone())
+                .await
+                .map(|v| self.customize(v)),
+            _ => self.check(value, values),
+        }
+    }
+}
@@ -0,0 +1,137 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use seigi_validate::{Rule, ValidationError, Values};
+
+fn values(entries: &[(&str, &str)]) -> Values {
+    entries
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+fn code(error: Option<ValidationError>) -> Option<String> {
+    error.map(|v| v.code)
+}
+
+/// Polls the future, which must be ready without waiting for anything
+fn ready<T>(future: impl Future<Output = T>) -> T {
+    let mut context = Context::from_waker(Waker::noop());
+    match pin!(future).poll(&mut context) {
+        Poll::Ready(value) => value,
+        Poll::Pending => panic!("the future is pending"),
+    }
+}
+
+#[test]
+fn required_rejects_blank_values() {
+    let rule = Rule::required();
+    let values = Values::new();
+    assert_eq!(code(rule.check("", &values)), Some("required".into()));
+    assert_eq!(code(rule.check("   ", &values)), Some("required".into()));
+    assert_eq!(rule.check("value", &values), None);
+    assert_eq!(
+        rule.check("", &values).unwrap().message,
+        "This field is required"
+    );
+}
+
+#[test]
+fn lengths_count_characters() {
+    let values = Values::new();
+    let min = Rule::min_length(3);
+    assert_eq!(code(min.check("ab", &values)), Some("min_length".into()));
+    assert_eq!(min.check("abc", &values), None);
+    // Characters, not bytes
+    assert_eq!(min.check("가나다", &values), None);
+    assert_eq!(
+        min.check("ab", &values).unwrap().message,
+        "Enter at least 3 characters"
+    );
+
+    let max = Rule::max_length(3);
+    assert_eq!(max.check("abc", &values), None);
+    assert_eq!(max.check("가나다", &values), None);
+    assert_eq!(code(max.check("abcd", &values)), Some("max_length".into()));
+}
+
+#[test]
+fn rules_other_than_required_pass_empty_values() {
+    let values = Values::new();
+    assert_eq!(Rule::min_length(3).check("", &values), None);
+    assert_eq!(
+        Rule::custom(|_, _| Some(ValidationError::new("never", "Never"))).check("", &values),
+        None
+    );
+    // Only an empty value is skipped, not a blank one
+    assert_eq!(
+        code(Rule::min_length(3).check(" ", &values)),
+        Some("min_length".into())
+    );
+}
+
+#[test]
+fn matches_compares_other_field() {
+    let rule = Rule::matches("password");
+    let values = values(&[("password", "secret")]);
+    assert_eq!(rule.check("secret", &values), None);
+    assert_eq!(code(rule.check("other", &values)), Some("matches".into()));
+    // Empty values are checked too, so clearing the confirmation fails
+    assert_eq!(code(rule.check("", &values)), Some("matches".into()));
+    // A missing field compares as empty
+    assert_eq!(rule.check("", &Values::new()), None);
+}
+
+#[test]
+fn custom_rule_sees_values() {
+    let rule = Rule::custom(|value, values| {
+        let start = values.get("start")?.parse::<u32>().ok()?;
+        (value.parse::<u32>().ok()? < start).then(|| ValidationError::new("range", "Too early"))
+    });
+    let values = values(&[("start", "10")]);
+    assert_eq!(rule.check("12", &values), None);
+    assert_eq!(
+        rule.check("5", &values),
+        Some(ValidationError::new("range", "Too early"))
+    );
+    assert_eq!(rule.check("5", &Values::new()), None);
+}
+
+#[test]
+fn message_replaces_message_keeping_code() {
+    let rule = Rule::required().message("Enter your name");
+    let error = rule.check("", &Values::new()).unwrap();
+    assert_eq!(error, ValidationError::new("required", "Enter your name"));
+    assert_eq!(error.to_string(), "Enter your name");
+}
+
+#[test]
+fn async_rule_runs_only_asynchronously() {
+    let rule = Rule::custom_async(|value, _| async move {
+        (value == "taken").then(|| ValidationError::new("taken", "Already taken"))
+    })
+    .message("Choose another name");
+    assert!(rule.is_async());
+    assert!(!Rule::required().is_async());
+
+    let values = Values::new();
+    // Synchronous checks skip asynchronous rules
+    assert_eq!(rule.check("taken", &values), None);
+    assert_eq!(
+        ready(rule.check_async("taken", &values)),
+        Some(ValidationError::new("taken", "Choose another name"))
+    );
+    assert_eq!(ready(rule.check_async("free", &values)), None);
+    assert_eq!(ready(rule.check_async("", &values)), None);
+
+    // Synchronous rules are checked by check_async as well
+    assert_eq!(
+        code(ready(Rule::required().check_async("", &values))),
+        Some("required".into())
+    );
+}
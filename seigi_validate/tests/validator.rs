@@ -0,0 +1,275 @@
+#![cfg(target_arch = "wasm32")]
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use seigi_testkit::{Fixture, assert_focused, events, wait};
+use seigi_validate::{
+    Field, Rule, Trigger, ValidationError, Validator, ValidatorHooks, ValidatorOptions, Values,
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::HtmlInputElement;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const MARKUP: &str = r#"
+<form>
+    <input id="name">
+    <p id="name-error" hidden></p>
+    <input id="password" type="password">
+    <input id="confirm" type="password">
+</form>
+"#;
+
+fn input(fixture: &Fixture, selector: &str) -> HtmlInputElement {
+    fixture.get(selector).unchecked_into()
+}
+
+/// Types the value into the input, dispatching an input event
+fn type_value(input: &HtmlInputElement, value: &str) {
+    input.set_value(value);
+    events::fire(input, "input");
+}
+
+fn codes(validator: &Validator, name: &str) -> Vec<String> {
+    validator.errors(name).into_iter().map(|v| v.code).collect()
+}
+
+fn create(fixture: &Fixture, trigger: Trigger, name: Rule) -> Validator {
+    seigi_validate::create_validator(
+        ValidatorOptions::builder()
+            .field(
+                Field::new("name", fixture.get("#name"))
+                    .error(fixture.get("#name-error"))
+                    .rule(name),
+            )
+            .trigger(trigger)
+            .build(),
+    )
+}
+
+#[wasm_bindgen_test]
+async fn blur_validates_and_input_clears_errors() {
+    let fixture = Fixture::new(MARKUP);
+    let validator = create(&fixture, Trigger::Blur, Rule::required());
+    let name = input(&fixture, "#name");
+    let error = fixture.get("#name-error");
+
+    // Untouched fields aren't validated on input
+    type_value(&name, "");
+    wait::tick().await;
+    assert!(!name.has_attribute("aria-invalid"));
+
+    events::fire(&name, "focusout");
+    wait::tick().await;
+    assert_eq!(codes(&validator, "name"), ["required"]);
+    assert_eq!(name.get_attribute("aria-invalid").as_deref(), Some("true"));
+    assert!(name.has_attribute("data-invalid"));
+    assert_eq!(
+        name.get_attribute("aria-errormessage").as_deref(),
+        Some("name-error")
+    );
+    assert_eq!(
+        error.text_content().as_deref(),
+        Some("This field is required")
+    );
+    assert!(!error.hidden());
+    assert!(!name.check_validity());
+    assert_eq!(
+        name.validation_message().as_deref(),
+        Ok("This field is required")
+    );
+
+    type_value(&name, "Kim");
+    assert!(validator.errors("name").is_empty());
+    assert!(!name.has_attribute("aria-invalid"));
+    assert!(!name.has_attribute("data-invalid"));
+    assert!(!name.has_attribute("aria-errormessage"));
+    assert!(error.hidden());
+    assert!(name.check_validity());
+}
+
+#[wasm_bindgen_test]
+async fn input_trigger_validates_on_input() {
+    let fixture = Fixture::new(MARKUP);
+    let validator = create(&fixture, Trigger::Input, Rule::min_length(3));
+    let name = input(&fixture, "#name");
+
+    type_value(&name, "ab");
+    wait::tick().await;
+    assert_eq!(codes(&validator, "name"), ["min_length"]);
+
+    type_value(&name, "abc");
+    wait::tick().await;
+    assert!(validator.errors("name").is_empty());
+}
+
+#[wasm_bindgen_test]
+async fn submit_trigger_waits_for_check() {
+    let fixture = Fixture::new(MARKUP);
+    let validator = create(&fixture, Trigger::Submit, Rule::required());
+    let name = input(&fixture, "#name");
+
+    events::fire(&name, "focusout");
+    type_value(&name, "");
+    wait::tick().await;
+    assert!(!name.has_attribute("aria-invalid"));
+    assert!(!validator.is_valid());
+
+    assert!(!validator.check());
+    assert_eq!(codes(&validator, "name"), ["required"]);
+    assert!(validator.focus_first_invalid());
+    assert_focused(&name);
+
+    // Checked fields are checked again on input
+    type_value(&name, "Kim");
+    assert!(validator.errors("name").is_empty());
+    assert!(validator.is_valid());
+    assert!(!validator.focus_first_invalid());
+}
+
+#[wasm_bindgen_test]
+async fn compared_field_is_checked_again() {
+    let fixture = Fixture::new(MARKUP);
+    let changes = Rc::new(RefCell::new(vec![]));
+    let validator = seigi_validate::create_validator(
+        ValidatorOptions::builder()
+            .field(Field::new("password", fixture.get("#password")).rule(Rule::min_length(4)))
+            .field(Field::new("confirm", fixture.get("#confirm")).rule(Rule::matches("password")))
+            .trigger(Trigger::Submit)
+            .hooks(ValidatorHooks {
+                change: Some(Box::new({
+                    let changes = changes.clone();
+                    move |name, errors| changes.borrow_mut().push((name.to_string(), errors.len()))
+                })),
+            })
+            .build(),
+    );
+    let (password, confirm) = (input(&fixture, "#password"), input(&fixture, "#confirm"));
+
+    password.set_value("secret");
+    confirm.set_value("secret");
+    assert!(validator.check());
+    assert!(changes.borrow().is_empty());
+
+    // Only the confirmation fails, though only the password changed
+    type_value(&password, "secrets");
+    assert!(validator.errors("password").is_empty());
+    assert_eq!(codes(&validator, "confirm"), ["matches"]);
+    assert_eq!(*changes.borrow(), [("confirm".to_string(), 1)]);
+
+    type_value(&confirm, "secrets");
+    assert!(validator.errors("confirm").is_empty());
+    assert_eq!(changes.borrow().last(), Some(&("confirm".to_string(), 0)));
+}
+
+#[wasm_bindgen_test]
+async fn async_errors_are_reused_while_value_stays() {
+    let fixture = Fixture::new(MARKUP);
+    let calls = Rc::new(Cell::new(0));
+    let rule = Rule::custom_async({
+        let calls = calls.clone();
+        move |value: String, _: Values| {
+            calls.set(calls.get() + 1);
+            async move {
+                wait::tick().await;
+                (value == "taken").then(|| ValidationError::new("taken", "Already taken"))
+            }
+        }
+    });
+    let validator = create(&fixture, Trigger::Submit, rule);
+    let name = input(&fixture, "#name");
+
+    name.set_value("taken");
+    assert!(!validator.validate_field("name").await);
+    assert_eq!(calls.get(), 1);
+    assert_eq!(codes(&validator, "name"), ["taken"]);
+
+    // Synchronous checks reuse the result for the same value
+    assert!(!validator.check_field("name"));
+    assert_eq!(codes(&validator, "name"), ["taken"]);
+    assert_eq!(calls.get(), 1);
+
+    name.set_value("free");
+    assert!(validator.check_field("name"));
+    assert!(validator.validate().await);
+    assert_eq!(calls.get(), 2);
+
+    validator.reset();
+    name.set_value("taken");
+    assert!(validator.check_field("name"));
+}
+
+#[wasm_bindgen_test]
+async fn async_errors_of_stale_values_are_discarded() {
+    let fixture = Fixture::new(MARKUP);
+    let is_running = Rc::new(Cell::new(false));
+    let rule = Rule::custom_async({
+        let is_running = is_running.clone();
+        move |_: String, _: Values| {
+            is_running.set(true);
+            async {
+                wait::tick().await;
+                Some(ValidationError::new("taken", "Already taken"))
+            }
+        }
+    });
+    let validator = create(&fixture, Trigger::Submit, rule);
+    let name = input(&fixture, "#name");
+
+    name.set_value("first");
+    let is_valid = Rc::new(Cell::new(None));
+    wasm_bindgen_futures::spawn_local({
+        let (validator, is_valid) = (validator.clone(), is_valid.clone());
+        async move { is_valid.set(Some(validator.validate_field("name").await)) }
+    });
+    // Changes while the rule runs
+    while !is_running.get() {
+        wait::microtask().await;
+    }
+    name.set_value("second");
+    while is_valid.get().is_none() {
+        wait::tick().await;
+    }
+
+    assert_eq!(is_valid.get(), Some(true));
+    assert!(validator.errors("name").is_empty());
+    assert!(!name.has_attribute("aria-invalid"));
+}
+
+#[wasm_bindgen_test]
+fn pattern_matches_entire_value() {
+    let rule = Rule::pattern("[0-9]{3}");
+    let values = Values::new();
+    assert_eq!(rule.check("123", &values), None);
+    assert_eq!(rule.check("", &values), None);
+    for value in ["12", "1234", "a123", "12a"] {
+        assert_eq!(
+            rule.check(value, &values).map(|v| v.code).as_deref(),
+            Some("pattern"),
+            "{value} matches"
+        );
+    }
+}
+
+#[wasm_bindgen_test]
+fn set_errors_reflects_server_errors() {
+    let fixture = Fixture::new(MARKUP);
+    let validator = create(&fixture, Trigger::Submit, Rule::required());
+    let error = fixture.get("#name-error");
+
+    validator.set_errors(
+        "name",
+        vec![ValidationError::new("server", "Name is taken")],
+    );
+    assert_eq!(error.text_content().as_deref(), Some("Name is taken"));
+    assert!(fixture.get("#name").has_attribute("data-invalid"));
+
+    validator.reset();
+    assert!(validator.errors("name").is_empty());
+    assert!(error.hidden());
+    assert!(!fixture.get("#name").has_attribute("data-invalid"));
+}
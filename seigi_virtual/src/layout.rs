@@ -0,0 +1,128 @@
+//! Item offsets and visible range computation, independent of the DOM
+
+/// An item to render, positioned along the scroll axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VirtualItem {
+    pub index: usize,
+    /// Offset of the item from the start of the content in pixels
+    pub start: f64,
+    /// Size of the item in pixels, measured or estimated
+    pub size: f64,
+    /// Whether the item is a sticky item rendered outside of the visible range
+    pub is_sticky: bool,
+}
+
+impl VirtualItem {
+    pub fn end(&self) -> f64 {
+        self.start + self.size
+    }
+}
+
+/// Offsets of items, with measured sizes falling back to the estimate
+///
+/// [crate::Virtualizer] lays items out with it, and it can be used on its own as it doesn't touch
+/// the DOM
+#[derive(Debug, Default, Clone)]
+pub struct Layout {
+    estimate: f64,
+    sizes: Vec<Option<f64>>,
+    /// Start offsets of items, with the total size as the last element
+    starts: Vec<f64>,
+}
+
+impl Layout {
+    /// Creates a layout of the count of items, all sized by the estimate until measured
+    pub fn new(count: usize, estimate: f64) -> Self {
+        let mut layout = Self {
+            estimate,
+            sizes: vec![None; count],
+            starts: vec![],
+        };
+        layout.rebuild(0);
+        layout
+    }
+
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Returns the size of the content, which is the end of the last item
+    pub fn total_size(&self) -> f64 {
+        self.starts.last().copied().unwrap_or(0.0)
+    }
+
+    /// Resizes to the count, keeping measurements of remaining items
+    pub fn set_count(&mut self, count: usize) {
+        let from = self.sizes.len().min(count);
+        self.sizes.resize(count, None);
+        self.rebuild(from);
+    }
+
+    /// Records the measured size of the item
+    ///
+    /// # Returns
+    /// Whether the size changed
+    pub fn measure(&mut self, index: usize, size: f64) -> bool {
+        let Some(current) = self.sizes.get_mut(index) else {
+            return false;
+        };
+        if current.is_some_and(|v| (v - size).abs() < 0.5) {
+            return false;
+        }
+        *current = Some(size);
+        self.rebuild(index);
+        true
+    }
+
+    /// Recomputes offsets from the item
+    fn rebuild(&mut self, from: usize) {
+        self.starts.truncate(from + 1);
+        if self.starts.is_empty() {
+            self.starts.push(0.0);
+        }
+        for index in from..self.sizes.len() {
+            let start = self.starts[index];
+            self.starts.push(start + self.size(index));
+        }
+    }
+
+    /// Returns the measured size of the item, or the estimate
+    pub fn size(&self, index: usize) -> f64 {
+        self.sizes[index].unwrap_or(self.estimate)
+    }
+
+    pub fn item(&self, index: usize) -> VirtualItem {
+        VirtualItem {
+            index,
+            start: self.starts[index],
+            size: self.size(index),
+            is_sticky: false,
+        }
+    }
+
+    /// Returns the index of the item at the offset
+    pub fn index_at(&self, offset: f64) -> usize {
+        if self.sizes.is_empty() {
+            return 0;
+        }
+        // starts is sorted, so the last start not after the offset is the item
+        let index = self.starts[..self.sizes.len()].partition_point(|v| *v <= offset);
+        index.saturating_sub(1)
+    }
+
+    /// Returns the indices of items intersecting the viewport, extended by overscan
+    pub fn range(&self, offset: f64, viewport: f64, overscan: usize) -> std::ops::Range<usize> {
+        let count = self.sizes.len();
+        if count == 0 {
+            return 0..0;
+        }
+
+        let start = self.index_at(offset);
+        let mut end = start;
+        while end < count && self.starts[end] < offset + viewport {
+            end += 1;
+        }
+
+        start.saturating_sub(overscan)..(end.max(start + 1) + overscan).min(count)
+    }
+}
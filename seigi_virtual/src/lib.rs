@@ -0,0 +1,596 @@
+//! Headless list virtualization rendering only the items in view
+//!
+//! [Virtualizer] decides which items are rendered and where, while rendering is left to the
+//! caller. Rendered elements are measured with a ResizeObserver, so items of unknown sizes are
+//! laid out correctly once rendered.
+
+pub mod layout;
+
+use std::{
+    rc::{Rc, Weak},
+    sync::Mutex,
+};
+
+use gloo::{events::EventListener, utils::document};
+use js_sys::Array;
+use seigi_utils::{
+    diagnostics::{self, Resource, Tracked},
+    intersection::{
+        Intersection, IntersectionHooks, IntersectionOptions, Length, RootMargin,
+        create_intersection,
+    },
+};
+use wasm_bindgen::{JsCast, prelude::Closure};
+use web_sys::{
+    Element, HtmlElement, ResizeObserver, ResizeObserverEntry, ScrollBehavior, ScrollToOptions,
+};
+
+use crate::layout::Layout;
+pub use crate::layout::VirtualItem;
+
+/// Axis items are laid out along
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    #[default]
+    Vertical,
+    Horizontal,
+}
+
+/// How sizes of items are known
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ItemSize {
+    /// Every item has the size in pixels, and elements are never measured
+    Fixed(f64),
+    /// Items are assumed to have the size in pixels until their elements are measured
+    Estimate(f64),
+}
+
+impl Default for ItemSize {
+    fn default() -> Self {
+        ItemSize::Estimate(40.0)
+    }
+}
+
+/// Where an item is placed in the viewport when scrolled to
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Start,
+    Center,
+    End,
+    /// Scrolls the least distance that brings the item into view, or not at all if it is
+    #[default]
+    Auto,
+}
+
+pub type ItemsHook = Box<dyn Fn(&[VirtualItem])>;
+pub type EndHook = Box<dyn Fn()>;
+
+/// Hooks to [Virtualizer]
+#[derive(Default)]
+pub struct VirtualizerHooks {
+    /// Called with items to render whenever they change
+    pub change: Option<ItemsHook>,
+    /// Called when the end of the list comes within the end margin of the viewport, such as to
+    /// load more items. Requires the content element
+    pub end: Option<EndHook>,
+}
+
+/// Options of [Virtualizer]
+pub struct VirtualizerOptions {
+    /// The element that scrolls
+    pub scroll_element: HtmlElement,
+    /// An element inside the scroll element sized to the total size of items, so the scrollbar
+    /// reflects every item
+    pub content: Option<HtmlElement>,
+    pub count: usize,
+    pub item_size: ItemSize,
+    /// The number of items rendered beyond each end of the viewport
+    pub overscan: usize,
+    pub orientation: Orientation,
+    /// Indices of sticky items such as group headers. The last sticky item before the visible
+    /// range is always rendered
+    pub sticky: Vec<usize>,
+    /// The distance in pixels from the viewport at which the end of the list is reported to the
+    /// end hook
+    pub end_margin: f64,
+    pub hooks: VirtualizerHooks,
+}
+
+impl VirtualizerOptions {
+    pub fn builder() -> VirtualizerOptionsBuilder {
+        VirtualizerOptionsBuilder::new()
+    }
+}
+
+/// A builder struct of [VirtualizerOptions]
+pub struct VirtualizerOptionsBuilder {
+    scroll_element: Option<HtmlElement>,
+    content: Option<HtmlElement>,
+    count: usize,
+    item_size: ItemSize,
+    overscan: usize,
+    orientation: Orientation,
+    sticky: Vec<usize>,
+    end_margin: f64,
+    hooks: VirtualizerHooks,
+}
+
+impl Default for VirtualizerOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            scroll_element: None,
+            content: None,
+            count: 0,
+            item_size: ItemSize::default(),
+            overscan: 3,
+            orientation: Orientation::default(),
+            sticky: vec![],
+            end_margin: 200.0,
+            hooks: VirtualizerHooks::default(),
+        }
+    }
+}
+
+impl VirtualizerOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn scroll_element(mut self, scroll_element: HtmlElement) -> Self {
+        self.scroll_element = Some(scroll_element);
+        self
+    }
+
+    pub fn content(mut self, content: HtmlElement) -> Self {
+        self.content = Some(content);
+        self
+    }
+
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = count;
+        self
+    }
+
+    pub fn item_size(mut self, item_size: ItemSize) -> Self {
+        self.item_size = item_size;
+        self
+    }
+
+    pub fn overscan(mut self, overscan: usize) -> Self {
+        self.overscan = overscan;
+        self
+    }
+
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn sticky(mut self, sticky: Vec<usize>) -> Self {
+        self.sticky = sticky;
+        self
+    }
+
+    pub fn end_margin(mut self, end_margin: f64) -> Self {
+        self.end_margin = end_margin;
+        self
+    }
+
+    pub fn hooks(mut self, hooks: VirtualizerHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Builds into [VirtualizerOptions]
+    ///
+    /// # Panics
+    /// This method panics if scroll_element field is not set
+    pub fn build(self) -> VirtualizerOptions {
+        VirtualizerOptions {
+            scroll_element: self
+                .scroll_element
+                .expect("scroll_element must be set to build VirtualizerOptions"),
+            content: self.content,
+            count: self.count,
+            item_size: self.item_size,
+            overscan: self.overscan,
+            orientation: self.orientation,
+            sticky: self.sticky,
+            end_margin: self.end_margin,
+            hooks: self.hooks,
+        }
+    }
+}
+
+struct Inner {
+    scroll_element: HtmlElement,
+    content: Option<HtmlElement>,
+    item_size: ItemSize,
+    overscan: usize,
+    orientation: Orientation,
+    sticky: Vec<usize>,
+    hooks: Rc<VirtualizerHooks>,
+    layout: Layout,
+    items: Vec<VirtualItem>,
+    item_observer: ResizeObserver,
+    viewport_observer: ResizeObserver,
+    /// An element placed at the end of the content, watched for the end hook
+    sentinel: Option<(HtmlElement, Intersection)>,
+    _item_callback: Closure<dyn Fn(Array)>,
+    _viewport_callback: Closure<dyn Fn()>,
+    /// Counts the observers in diagnostics while alive
+    _tracked: [Tracked; 2],
+    _listeners: Vec<EventListener>,
+}
+
+impl Inner {
+    fn offset(&self) -> f64 {
+        match self.orientation {
+            Orientation::Vertical => self.scroll_element.scroll_top() as f64,
+            Orientation::Horizontal => self.scroll_element.scroll_left() as f64,
+        }
+    }
+
+    fn viewport(&self) -> f64 {
+        match self.orientation {
+            Orientation::Vertical => self.scroll_element.client_height() as f64,
+            Orientation::Horizontal => self.scroll_element.client_width() as f64,
+        }
+    }
+
+    fn compute_items(&self) -> Vec<VirtualItem> {
+        let range = self
+            .layout
+            .range(self.offset(), self.viewport(), self.overscan);
+        let sticky = self
+            .sticky
+            .iter()
+            .copied()
+            .filter(|v| *v < range.start)
+            .max();
+
+        sticky
+            .map(|v| VirtualItem {
+                is_sticky: true,
+                ..self.layout.item(v)
+            })
+            .into_iter()
+            .chain(range.map(|v| self.layout.item(v)))
+            .collect()
+    }
+
+    fn reflect_size(&self) {
+        let Some(content) = &self.content else {
+            return;
+        };
+        let property = match self.orientation {
+            Orientation::Vertical => "height",
+            Orientation::Horizontal => "width",
+        };
+        let _ = content
+            .style()
+            .set_property(property, &format!("{}px", self.layout.total_size()));
+
+        if let Some((sentinel, _)) = &self.sentinel {
+            let property = match self.orientation {
+                Orientation::Vertical => "top",
+                Orientation::Horizontal => "left",
+            };
+            let offset = (self.layout.total_size() - 1.0).max(0.0);
+            let _ = sentinel
+                .style()
+                .set_property(property, &format!("{offset}px"));
+        }
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.item_observer.disconnect();
+        self.viewport_observer.disconnect();
+        if let Some((sentinel, _)) = &self.sentinel {
+            sentinel.remove();
+        }
+    }
+}
+
+/// An instance of virtualizer
+///
+/// Render the items passed to the change hook, positioned at [VirtualItem::start] inside the
+/// content element, and pass each rendered element to [Virtualizer::measure_element] unless
+/// item sizes are fixed. Elements are identified by their `data-index` attribute.
+///
+/// This struct internally contains a handle(Rc) to actual data, so cloning this struct is a
+/// lightweight operation
+///
+/// # Attributes
+/// **data-index** must be set in rendered item elements to their index
+#[derive(Clone)]
+pub struct Virtualizer(Rc<Mutex<Inner>>);
+
+impl Virtualizer {
+    fn from_weak(weak: &Weak<Mutex<Inner>>) -> Option<Self> {
+        weak.upgrade().map(Self)
+    }
+
+    /// Returns items to render
+    pub fn items(&self) -> Vec<VirtualItem> {
+        self.0.lock().unwrap().items.clone()
+    }
+
+    /// Returns the size of every item combined
+    pub fn total_size(&self) -> f64 {
+        self.0.lock().unwrap().layout.total_size()
+    }
+
+    pub fn count(&self) -> usize {
+        self.0.lock().unwrap().layout.count()
+    }
+
+    /// Changes the number of items, keeping measurements of remaining items
+    ///
+    /// The end hook is called again if the end of the list is still near the viewport, so
+    /// loading fewer items than fill it keeps loading.
+    pub fn set_count(&self, count: usize) {
+        self.0.lock().unwrap().layout.set_count(count);
+        self.update();
+        let inner = self.0.lock().unwrap();
+        if let Some((sentinel, intersection)) = &inner.sentinel {
+            intersection.refresh(sentinel);
+        }
+    }
+
+    pub fn set_sticky(&self, mut sticky: Vec<usize>) {
+        sticky.sort_unstable();
+        self.0.lock().unwrap().sticky = sticky;
+        self.update();
+    }
+
+    /// Measures the element and observes it for later size changes
+    pub fn measure_element(&self, element: &Element) {
+        {
+            let inner = self.0.lock().unwrap();
+            if matches!(inner.item_size, ItemSize::Fixed(_)) {
+                return;
+            }
+            inner.item_observer.observe(element);
+        }
+        self.measure(element);
+    }
+
+    /// Stops observing the element, such as when it is removed
+    pub fn unobserve_element(&self, element: &Element) {
+        self.0.lock().unwrap().item_observer.unobserve(element);
+    }
+
+    fn measure(&self, element: &Element) {
+        let Some(index) = element
+            .get_attribute("data-index")
+            .and_then(|v| v.parse::<usize>().ok())
+        else {
+            return;
+        };
+
+        {
+            let mut inner = self.0.lock().unwrap();
+            let rect = element.get_bounding_client_rect();
+            let size = match inner.orientation {
+                Orientation::Vertical => rect.height(),
+                Orientation::Horizontal => rect.width(),
+            };
+            if index >= inner.layout.count() {
+                return;
+            }
+            let before = inner.layout.item(index);
+            if !inner.layout.measure(index, size) {
+                return;
+            }
+
+            // Items above the viewport growing would push visible items down, so the scroll
+            // offset follows them
+            let delta = size - before.size;
+            if before.start < inner.offset() {
+                match inner.orientation {
+                    Orientation::Vertical => {
+                        inner.scroll_element.scroll_by_with_x_and_y(0.0, delta)
+                    }
+                    Orientation::Horizontal => {
+                        inner.scroll_element.scroll_by_with_x_and_y(delta, 0.0)
+                    }
+                }
+            }
+        }
+
+        self.update();
+    }
+
+    /// Scrolls so the item is aligned in the viewport
+    pub fn scroll_to_index(&self, index: usize, align: Align, smooth: bool) {
+        let offset = {
+            let inner = self.0.lock().unwrap();
+            if inner.layout.count() == 0 {
+                return;
+            }
+            let item = inner.layout.item(index.min(inner.layout.count() - 1));
+            let (offset, viewport) = (inner.offset(), inner.viewport());
+            match align {
+                Align::Start => item.start,
+                Align::Center => item.start + item.size / 2.0 - viewport / 2.0,
+                Align::End => item.end() - viewport,
+                Align::Auto if item.start < offset => item.start,
+                Align::Auto if item.end() > offset + viewport => item.end() - viewport,
+                Align::Auto => return,
+            }
+        };
+
+        self.scroll_to_offset(offset, smooth);
+    }
+
+    /// Scrolls to the offset in pixels
+    pub fn scroll_to_offset(&self, offset: f64, smooth: bool) {
+        let inner = self.0.lock().unwrap();
+        let offset = offset.clamp(0.0, (inner.layout.total_size() - inner.viewport()).max(0.0));
+        let options = ScrollToOptions::new();
+        match inner.orientation {
+            Orientation::Vertical => options.set_top(offset),
+            Orientation::Horizontal => options.set_left(offset),
+        }
+        options.set_behavior(if smooth {
+            ScrollBehavior::Smooth
+        } else {
+            ScrollBehavior::Auto
+        });
+        inner
+            .scroll_element
+            .scroll_to_with_scroll_to_options(&options);
+    }
+
+    /// Recomputes items to render and notifies the change hook if they changed
+    pub fn update(&self) {
+        let (items, hooks) = {
+            let mut inner = self.0.lock().unwrap();
+            inner.reflect_size();
+            let items = inner.compute_items();
+            if items == inner.items {
+                return;
+            }
+            inner.items = items.clone();
+            (items, inner.hooks.clone())
+        };
+
+        if let Some(hook) = &hooks.change {
+            hook(&items);
+        }
+    }
+}
+
+pub fn create_virtualizer(options: VirtualizerOptions) -> Virtualizer {
+    let VirtualizerOptions {
+        scroll_element,
+        content,
+        count,
+        item_size,
+        overscan,
+        orientation,
+        mut sticky,
+        end_margin,
+        hooks,
+    } = options;
+    sticky.sort_unstable();
+    let estimate = match item_size {
+        ItemSize::Fixed(v) | ItemSize::Estimate(v) => v,
+    };
+
+    let inner = Rc::new_cyclic(|weak: &Weak<Mutex<Inner>>| {
+        let item_callback: Closure<dyn Fn(Array)> = Closure::new({
+            let weak = weak.clone();
+            move |entries: Array| {
+                let Some(virtualizer) = Virtualizer::from_weak(&weak) else {
+                    return;
+                };
+                for entry in entries.iter() {
+                    let target = entry.unchecked_into::<ResizeObserverEntry>().target();
+                    if target.is_connected() {
+                        virtualizer.measure(&target);
+                    } else {
+                        virtualizer.unobserve_element(&target);
+                    }
+                }
+            }
+        });
+        let viewport_callback: Closure<dyn Fn()> = Closure::new({
+            let weak = weak.clone();
+            move || {
+                if let Some(virtualizer) = Virtualizer::from_weak(&weak) {
+                    virtualizer.update();
+                }
+            }
+        });
+
+        let item_observer = ResizeObserver::new(item_callback.as_ref().unchecked_ref())
+            .expect("failed to create resize observer");
+        let viewport_observer = ResizeObserver::new(viewport_callback.as_ref().unchecked_ref())
+            .expect("failed to create resize observer");
+        viewport_observer.observe(&scroll_element);
+
+        let scroll = EventListener::new(&scroll_element, "scroll", {
+            let weak = weak.clone();
+            move |_| {
+                if let Some(virtualizer) = Virtualizer::from_weak(&weak) {
+                    virtualizer.update();
+                }
+            }
+        });
+
+        let sentinel = content
+            .as_ref()
+            .filter(|_| hooks.end.is_some())
+            .map(|content| {
+                let sentinel = document()
+                    .create_element("div")
+                    .expect("failed to create element")
+                    .unchecked_into::<HtmlElement>();
+                let _ = sentinel.set_attribute("data-seigi-virtual-sentinel", "");
+                let _ = sentinel.set_attribute("aria-hidden", "true");
+                let _ = sentinel.set_attribute(
+                    "style",
+                    "position: absolute; width: 1px; height: 1px; pointer-events: none;",
+                );
+                let _ = content.append_child(&sentinel);
+
+                let margin = Length::Px(end_margin);
+                let intersection = create_intersection(
+                    IntersectionOptions::builder()
+                        .root(scroll_element.clone().into())
+                        .root_margin(match orientation {
+                            Orientation::Vertical => RootMargin::vertical(margin),
+                            Orientation::Horizontal => RootMargin::horizontal(margin),
+                        })
+                        .hooks(IntersectionHooks {
+                            enter: Some(Box::new({
+                                let weak = weak.clone();
+                                move |_| {
+                                    let Some(virtualizer) = Virtualizer::from_weak(&weak) else {
+                                        return;
+                                    };
+                                    let hooks = virtualizer.0.lock().unwrap().hooks.clone();
+                                    if let Some(hook) = &hooks.end {
+                                        hook();
+                                    }
+                                }
+                            })),
+                            ..Default::default()
+                        })
+                        .build(),
+                );
+                intersection.observe(&sentinel);
+                (sentinel, intersection)
+            });
+
+        Mutex::new(Inner {
+            scroll_element,
+            content,
+            item_size,
+            overscan,
+            orientation,
+            sticky,
+            hooks: Rc::new(hooks),
+            layout: Layout::new(count, estimate),
+            items: vec![],
+            item_observer,
+            viewport_observer,
+            sentinel,
+            _item_callback: item_callback,
+            _viewport_callback: viewport_callback,
+            _tracked: [
+                diagnostics::track(Resource::Observer),
+                diagnostics::track(Resource::Observer),
+            ],
+            _listeners: vec![scroll],
+        })
+    });
+
+    let virtualizer = Virtualizer(inner);
+    virtualizer.update();
+    virtualizer
+}
@@ -0,0 +1,87 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use seigi_virtual::layout::Layout;
+
+fn starts(layout: &Layout) -> Vec<f64> {
+    (0..layout.count()).map(|v| layout.item(v).start).collect()
+}
+
+#[test]
+fn empty_list() {
+    let layout = Layout::new(0, 10.0);
+    assert_eq!(layout.count(), 0);
+    assert_eq!(layout.total_size(), 0.0);
+    assert_eq!(layout.index_at(0.0), 0);
+    assert_eq!(layout.index_at(100.0), 0);
+    assert_eq!(layout.range(0.0, 100.0, 3), 0..0);
+}
+
+#[test]
+fn offset_past_end() {
+    let layout = Layout::new(10, 10.0);
+    assert_eq!(layout.total_size(), 100.0);
+    assert_eq!(layout.index_at(99.0), 9);
+    assert_eq!(layout.index_at(1000.0), 9);
+    assert_eq!(layout.index_at(-5.0), 0);
+    assert_eq!(layout.range(1000.0, 100.0, 0), 9..10);
+    assert_eq!(layout.range(1000.0, 100.0, 2), 7..10);
+}
+
+#[test]
+fn overscan_is_clamped() {
+    let layout = Layout::new(10, 10.0);
+    assert_eq!(layout.range(0.0, 25.0, 0), 0..3);
+    assert_eq!(layout.range(0.0, 25.0, 3), 0..6);
+    assert_eq!(layout.range(80.0, 100.0, 5), 3..10);
+    assert_eq!(layout.range(50.0, 10.0, 100), 0..10);
+    // An empty viewport still has the item at the offset
+    assert_eq!(layout.range(45.0, 0.0, 1), 3..6);
+}
+
+#[test]
+fn measure_rebuilds_from_item() {
+    let mut layout = Layout::new(10, 10.0);
+    assert!(layout.measure(5, 30.0));
+    assert_eq!(
+        starts(&layout),
+        [0.0, 10.0, 20.0, 30.0, 40.0, 50.0, 80.0, 90.0, 100.0, 110.0]
+    );
+    assert_eq!(layout.total_size(), 120.0);
+    assert_eq!(layout.index_at(79.0), 5);
+    assert_eq!(layout.index_at(80.0), 6);
+
+    // Differences under half a pixel and missing items are ignored
+    assert!(!layout.measure(5, 30.2));
+    assert!(!layout.measure(10, 30.0));
+    assert_eq!(layout.total_size(), 120.0);
+
+    // Measuring an earlier item keeps later measurements
+    assert!(layout.measure(2, 5.0));
+    assert_eq!(layout.item(3).start, 25.0);
+    assert_eq!(layout.item(5).start, 45.0);
+    assert_eq!(layout.item(5).size, 30.0);
+    assert_eq!(layout.item(6).start, 75.0);
+    assert_eq!(layout.total_size(), 115.0);
+}
+
+#[test]
+fn set_count_shrinks_and_grows() {
+    let mut layout = Layout::new(10, 10.0);
+    layout.measure(2, 20.0);
+    layout.measure(8, 50.0);
+    assert_eq!(layout.total_size(), 150.0);
+
+    layout.set_count(5);
+    assert_eq!(layout.count(), 5);
+    assert_eq!(layout.total_size(), 60.0);
+    assert_eq!(layout.index_at(1000.0), 4);
+    assert_eq!(layout.range(40.0, 100.0, 2), 1..5);
+
+    // Removed items come back with the estimate
+    layout.set_count(10);
+    assert_eq!(layout.count(), 10);
+    assert_eq!(layout.item(2).size, 20.0);
+    assert_eq!(layout.item(8).size, 10.0);
+    assert_eq!(layout.item(8).start, 90.0);
+    assert_eq!(layout.total_size(), 110.0);
+}
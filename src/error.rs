@@ -0,0 +1,65 @@
+//! Errors of every subsystem in one type
+//!
+//! Subsystems return their own errors, which convert into [Error] with `?` so applications using
+//! several of them can propagate a single type.
+
+use std::fmt::{self, Display};
+
+/// An error of one of the subsystems, with variants of the enabled features
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    #[cfg(feature = "components")]
+    Components(seigi_components::Error),
+    #[cfg(feature = "form")]
+    Form(seigi_form::Error),
+    #[cfg(feature = "toast")]
+    Toast(seigi_toast::Error),
+}
+
+impl Error {
+    /// Returns the error of the subsystem
+    fn inner(&self) -> &(dyn std::error::Error + 'static) {
+        match *self {
+            #[cfg(feature = "components")]
+            Self::Components(ref error) => error,
+            #[cfg(feature = "form")]
+            Self::Form(ref error) => error,
+            #[cfg(feature = "toast")]
+            Self::Toast(ref error) => error,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.inner(), f)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.inner())
+    }
+}
+
+#[cfg(feature = "components")]
+impl From<seigi_components::Error> for Error {
+    fn from(error: seigi_components::Error) -> Self {
+        Self::Components(error)
+    }
+}
+
+#[cfg(feature = "form")]
+impl From<seigi_form::Error> for Error {
+    fn from(error: seigi_form::Error) -> Self {
+        Self::Form(error)
+    }
+}
+
+#[cfg(feature = "toast")]
+impl From<seigi_toast::Error> for Error {
+    fn from(error: seigi_toast::Error) -> Self {
+        Self::Toast(error)
+    }
+}
@@ -0,0 +1,545 @@
+//! Classes exported to JavaScript, for frontends consuming seigi from npm
+//!
+//! Options are plain objects described by the TypeScript interfaces below, which
+//! wasm-bindgen writes into the generated definitions together with the classes.
+
+use js_sys::{Array, Function, Object, Reflect};
+use wasm_bindgen::prelude::*;
+use web_sys::HtmlElement;
+
+use crate::{
+    focus::{
+        EventOutcome, FocusTrap, FocusTrapEvent, FocusTrapHooks, FocusTrapOptions, InitialFocus,
+        InteractionKind, NavigationPolicy,
+    },
+    form::multi_stage::{Form, Orientation, Stage, UrlSync},
+    toast::{
+        DismissReason, Toast, ToastEvent, ToastHandle, ToastOrigin, ToastTimeout, ToasterOptions,
+    },
+};
+
+#[wasm_bindgen(typescript_custom_section)]
+const TYPESCRIPT: &str = r#"
+export interface SeigiFocusTrapOptions {
+    target: HTMLElement;
+    /** Elements outside the scope are not affected by the trap, which defaults to the body */
+    scope?: HTMLElement;
+    returnFocus?: boolean;
+    /** An element, a selector, or "auto", "last" and "none" */
+    initialFocus?: HTMLElement | string;
+    deactivateOnEscape?: boolean;
+    /**
+     * What the trap does when the page goes back or forward through history, "deactivate" by
+     * default, or a guard returning whether the navigation goes on
+     */
+    navigation?: "ignore" | "deactivate" | (() => boolean);
+    /** Called with events before the trap handles them, which it leaves alone if true is returned */
+    onEvent?: (event: Event) => boolean | void;
+    /**
+     * Milliseconds data-seigi-trap-rejected stays in the target after a press outside is
+     * blocked, 400 by default, or null to never set it
+     */
+    rejectedFeedback?: number | null;
+    /** Whether the rest of the page is aria-hidden while activated, leaving live regions */
+    hideOthers?: boolean;
+    /** Whether the document can't be scrolled while activated */
+    scrollLock?: boolean;
+    /** The document of the target by default, such as one of an iframe */
+    document?: Document;
+    onActivate?: () => void;
+    onDeactivate?: () => void;
+}
+
+/** An interaction outside an activated trap which the trap blocked */
+export type SeigiFocusTrapEvent = {
+    type: "outsideInteraction";
+    kind: "pointer" | "focus" | "key";
+    target: HTMLElement;
+};
+
+export interface SeigiToastOptions {
+    title: string;
+    description?: string;
+    /** Milliseconds until dismissed, or null to never be */
+    timeout?: number | null;
+    /** Toasts of the same group are summarized while several are shown */
+    group?: string;
+    /** Pinned toasts stay in front of the stack until dismissed */
+    pinned?: boolean;
+    /** Where the toast is animated in from, like the button which caused it */
+    origin?: Element | { x: number; y: number };
+}
+
+export type SeigiToastEvent =
+    | { type: "create"; handle: number }
+    | { type: "update"; handle: number }
+    | {
+          type: "dismiss";
+          handle: number;
+          reason: "timeout" | "user" | "resolved" | "swipe";
+          /** How the toast was swiped away, for the `swipe` reason */
+          gesture: SeigiSwipeGesture | null;
+      };
+
+export interface SeigiSwipeGesture {
+    direction: "up" | "down" | "left" | "right";
+    /** Distance the toast was dragged in pixels */
+    distance: number;
+    /** Speed of the toast until its release in pixels per millisecond */
+    velocity: number;
+    /** Whether the toast was released short of the distance dismissing it, but fast enough */
+    isFlick: boolean;
+}
+
+export interface SeigiToasterStats {
+    live: number;
+    dismissed: { timeout: number; user: number; resolved: number };
+}
+
+export interface SeigiFormOptions {
+    container: HTMLElement;
+    stages: HTMLElement[];
+    initialStage?: number;
+    viewTransition?: boolean;
+    orientation?: "horizontal" | "vertical";
+    /** Ids naming stages in URLs, in the order of stages */
+    stageIds?: string[];
+    /** Keeps the current stage in the URL, in the fragment or a query parameter */
+    urlSync?: "hash" | { query: string };
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "SeigiFocusTrapOptions")]
+    pub type JsFocusTrapOptions;
+
+    #[wasm_bindgen(typescript_type = "(event: SeigiFocusTrapEvent) => void")]
+    pub type JsFocusTrapCallback;
+
+    #[wasm_bindgen(typescript_type = "SeigiToastOptions")]
+    pub type JsToastOptions;
+
+    #[wasm_bindgen(typescript_type = "(event: SeigiToastEvent) => void")]
+    pub type JsToastCallback;
+
+    #[wasm_bindgen(typescript_type = "SeigiToasterStats")]
+    pub type JsToasterStats;
+
+    #[wasm_bindgen(typescript_type = "SeigiFormOptions")]
+    pub type JsFormOptions;
+
+    #[wasm_bindgen(typescript_type = "(stage: number) => void")]
+    pub type JsStageCallback;
+}
+
+/// Gets the property of the object, treating undefined and null as missing
+fn property(object: &JsValue, key: &str) -> Option<JsValue> {
+    Reflect::get(object, &JsValue::from_str(key))
+        .ok()
+        .filter(|v| !v.is_undefined() && !v.is_null())
+}
+
+fn required<T: JsCast>(object: &JsValue, key: &str) -> Result<T, JsError> {
+    property(object, key)
+        .and_then(|v| v.dyn_into().ok())
+        .ok_or_else(|| JsError::new(&format!("options.{key} is missing or of a wrong type")))
+}
+
+fn hook(object: &JsValue, key: &str) -> Option<Box<dyn Fn()>> {
+    let function = property(object, key)?.dyn_into::<Function>().ok()?;
+    Some(Box::new(move || {
+        let _ = function.call0(&JsValue::NULL);
+    }))
+}
+
+/// A focus trap, created with `new SeigiFocusTrap(options)`
+#[wasm_bindgen(js_name = SeigiFocusTrap)]
+pub struct JsFocusTrap(FocusTrap);
+
+#[wasm_bindgen(js_class = SeigiFocusTrap)]
+impl JsFocusTrap {
+    #[wasm_bindgen(constructor)]
+    pub fn new(options: JsFocusTrapOptions) -> Result<JsFocusTrap, JsError> {
+        let options: &JsValue = &options;
+        // Elements of iframes and popups aren't instances of HTMLElement of this window
+        let element = |key: &str| -> Result<Option<HtmlElement>, JsError> {
+            property(options, key)
+                .map(|v| {
+                    crate::focus::html_element(v).ok_or_else(|| {
+                        JsError::new(&format!("options.{key} is missing or of a wrong type"))
+                    })
+                })
+                .transpose()
+        };
+        let target = element("target")?
+            .ok_or_else(|| JsError::new("options.target is missing or of a wrong type"))?;
+        let mut builder = FocusTrapOptions::builder()
+            .target(target)
+            .hooks(FocusTrapHooks {
+                activate: hook(options, "onActivate"),
+                deactivate: hook(options, "onDeactivate"),
+            });
+        if let Some(scope) = element("scope")? {
+            builder = builder.scope(scope);
+        }
+        if let Some(document) = property(options, "document").filter(|v| v.is_object()) {
+            builder = builder.document(document.unchecked_into());
+        }
+        if let Some(return_focus) = property(options, "returnFocus").and_then(|v| v.as_bool()) {
+            builder = builder.return_focus(return_focus);
+        }
+        if let Some(deactivate_on_escape) =
+            property(options, "deactivateOnEscape").and_then(|v| v.as_bool())
+        {
+            builder = builder.deactivate_on_escape(deactivate_on_escape);
+        }
+        if let Some(navigation) = property(options, "navigation") {
+            builder = builder.navigation(match navigation.as_string().as_deref() {
+                Some("ignore") => NavigationPolicy::Ignore,
+                Some("deactivate") => NavigationPolicy::Deactivate,
+                _ => {
+                    let guard = navigation.dyn_into::<Function>().map_err(|_| {
+                        JsError::new("options.navigation is missing or of a wrong type")
+                    })?;
+                    NavigationPolicy::Guard(Box::new(move || {
+                        guard.call0(&JsValue::NULL).is_ok_and(|v| v.is_truthy())
+                    }))
+                }
+            });
+        }
+        if let Some(on_event) = property(options, "onEvent") {
+            let on_event = on_event
+                .dyn_into::<Function>()
+                .map_err(|_| JsError::new("options.onEvent is missing or of a wrong type"))?;
+            builder = builder.on_event(move |event| match on_event.call1(&JsValue::NULL, event) {
+                Ok(v) if v.is_truthy() => EventOutcome::Consumed,
+                _ => EventOutcome::Continue,
+            });
+        }
+        // null disables the feedback, which property() can't tell from a missing option
+        let rejected_feedback =
+            Reflect::get(options, &"rejectedFeedback".into()).unwrap_or(JsValue::UNDEFINED);
+        if rejected_feedback.is_null() {
+            builder = builder.rejected_feedback(None);
+        } else if let Some(millis) = rejected_feedback.as_f64() {
+            let duration = std::time::Duration::from_secs_f64(millis.max(0.0) / 1000.0);
+            builder = builder.rejected_feedback(Some(duration));
+        }
+        if let Some(hide_others) = property(options, "hideOthers").and_then(|v| v.as_bool()) {
+            builder = builder.hide_others(hide_others);
+        }
+        if let Some(scroll_lock) = property(options, "scrollLock").and_then(|v| v.as_bool()) {
+            builder = builder.scroll_lock(scroll_lock);
+        }
+        if let Some(initial_focus) = property(options, "initialFocus") {
+            builder = builder.initial_focus(match initial_focus.as_string().as_deref() {
+                Some("auto") => InitialFocus::Auto,
+                Some("last") => InitialFocus::Last,
+                Some("none") => InitialFocus::None,
+                Some(selector) => InitialFocus::Selector(selector.to_string()),
+                None => InitialFocus::Element(initial_focus.dyn_into::<HtmlElement>().map_err(
+                    |_| JsError::new("options.initialFocus is missing or of a wrong type"),
+                )?),
+            });
+        }
+        Ok(Self(crate::focus::create(builder.build())))
+    }
+
+    pub fn activate(&self) {
+        self.0.activate();
+    }
+
+    pub fn deactivate(&self) {
+        self.0.deactivate();
+    }
+
+    /// Applies the navigation option for a navigation the app makes itself, returning whether it
+    /// may go on
+    #[wasm_bindgen(js_name = beforeNavigation)]
+    pub fn before_navigation(&self) -> bool {
+        self.0.before_navigation()
+    }
+
+    #[wasm_bindgen(getter, js_name = isActivated)]
+    pub fn is_activated(&self) -> bool {
+        self.0.is_activated()
+    }
+
+    /// The id reflected to `data-seigi-trap-id` of the target
+    #[wasm_bindgen(getter)]
+    pub fn id(&self) -> String {
+        self.0.id()
+    }
+
+    /// Subscribes to interactions the trap blocks, returning an id to unsubscribe with
+    pub fn subscribe(&self, callback: JsFocusTrapCallback) -> f64 {
+        let callback: Function = callback.unchecked_into();
+        self.0.subscribe(Box::new(move |event: &FocusTrapEvent| {
+            let FocusTrapEvent::OutsideInteraction { kind, target } = event;
+            let kind = match kind {
+                InteractionKind::Pointer => "pointer",
+                InteractionKind::Focus => "focus",
+                InteractionKind::Key => "key",
+            };
+            let object = Object::new();
+            let _ = Reflect::set(&object, &"type".into(), &"outsideInteraction".into());
+            let _ = Reflect::set(&object, &"kind".into(), &kind.into());
+            let _ = Reflect::set(&object, &"target".into(), target);
+            let _ = callback.call1(&JsValue::NULL, &object);
+        })) as f64
+    }
+
+    pub fn unsubscribe(&self, id: f64) {
+        self.0.unsubscribe(id as u64);
+    }
+
+    /// Sets whether activated traps mark tab candidates with `data-seigi-tab-index`
+    #[wasm_bindgen(js_name = setDebugging)]
+    pub fn set_debugging(debugging: bool) {
+        crate::focus::set_debugging(debugging);
+    }
+}
+
+/// The global toaster, created with `new SeigiToaster(timeout)`
+///
+/// Every instance shares the global toasts, which are initialized by the first one with the
+/// default timeout in milliseconds.
+#[wasm_bindgen(js_name = SeigiToaster)]
+pub struct JsToaster(crate::toast::Toaster);
+
+#[wasm_bindgen(js_class = SeigiToaster)]
+impl JsToaster {
+    #[wasm_bindgen(constructor)]
+    pub fn new(timeout: Option<u32>) -> Result<JsToaster, JsError> {
+        if let Some(toaster) = crate::toast::toaster() {
+            return Ok(Self(toaster));
+        }
+        let options = match timeout {
+            Some(timeout) => ToasterOptions::default().with_timeout_secs(timeout as f64 / 1000.0),
+            None => ToasterOptions::default(),
+        };
+        crate::toast::initialize(options)
+            .map(Self)
+            .map_err(|v| JsError::new(&v.to_string()))
+    }
+
+    /// Creates a toast and returns its handle
+    pub fn create(&self, options: JsToastOptions) -> Result<u32, JsError> {
+        let options: &JsValue = &options;
+        let title = property(options, "title")
+            .and_then(|v| v.as_string())
+            .ok_or_else(|| JsError::new("options.title is missing or of a wrong type"))?;
+        let timeout = match Reflect::get(options, &JsValue::from_str("timeout")) {
+            Ok(v) if v.is_null() => ToastTimeout::None,
+            Ok(v) => match v.as_f64() {
+                Some(millis) => {
+                    ToastTimeout::Duration(std::time::Duration::from_secs_f64(millis / 1000.0))
+                }
+                None => ToastTimeout::Default,
+            },
+            Err(_) => ToastTimeout::Default,
+        };
+        let toast = Toast {
+            title,
+            description: property(options, "description").and_then(|v| v.as_string()),
+            dismiss: None,
+            timeout,
+            group: property(options, "group").and_then(|v| v.as_string()),
+            pinned: property(options, "pinned")
+                .and_then(|v| v.as_bool())
+                .unwrap_or_default(),
+            origin: property(options, "origin").and_then(|v| ToastOrigin::from_js(&v)),
+        };
+        Ok(self.0.add_toast(toast).0)
+    }
+
+    /// Dismisses the toast, returning whether it was found
+    pub fn dismiss(&self, handle: u32) -> bool {
+        self.0
+            .dismiss_toast(ToastHandle(handle), DismissReason::User)
+    }
+
+    /// Dismisses every toast of the group, returning how many were
+    #[wasm_bindgen(js_name = dismissGroup)]
+    pub fn dismiss_group(&self, group: &str) -> u32 {
+        self.0.dismiss_group(group, DismissReason::User) as u32
+    }
+
+    /// Returns counts of live toasts and toasts dismissed by each reason
+    pub fn stats(&self) -> JsToasterStats {
+        let stats = self.0.stats();
+        let dismissed = Object::new();
+        let _ = Reflect::set(&dismissed, &"timeout".into(), &stats.timed_out.into());
+        let _ = Reflect::set(&dismissed, &"user".into(), &stats.dismissed_by_user.into());
+        let _ = Reflect::set(&dismissed, &"resolved".into(), &stats.resolved.into());
+        let object = Object::new();
+        let _ = Reflect::set(&object, &"live".into(), &stats.live.into());
+        let _ = Reflect::set(&object, &"dismissed".into(), &dismissed);
+        object.unchecked_into()
+    }
+
+    /// Returns handles of toasts not dismissed yet, oldest first
+    pub fn handles(&self) -> Vec<u32> {
+        self.0.iter_handles().map(|v| v.0).collect()
+    }
+
+    /// Subscribes to toasts created and dismissed, returning an id to unsubscribe with
+    pub fn subscribe(&self, callback: JsToastCallback) -> f64 {
+        let callback: Function = callback.unchecked_into();
+        self.0.subscribe(Box::new(move |event: &ToastEvent| {
+            let object = Object::new();
+            let (kind, handle) = match event {
+                ToastEvent::Create { handle } => ("create", handle),
+                ToastEvent::Update { handle } => ("update", handle),
+                ToastEvent::Dismiss { handle, reason } => {
+                    let _ = Reflect::set(&object, &"reason".into(), &reason.as_str().into());
+                    let _ = Reflect::set(
+                        &object,
+                        &"gesture".into(),
+                        &match reason {
+                            DismissReason::Swipe(gesture) => gesture.to_js(),
+                            _ => JsValue::NULL,
+                        },
+                    );
+                    ("dismiss", handle)
+                }
+            };
+            let _ = Reflect::set(&object, &"type".into(), &kind.into());
+            let _ = Reflect::set(&object, &"handle".into(), &handle.0.into());
+            let _ = callback.call1(&JsValue::NULL, &object);
+        })) as f64
+    }
+
+    pub fn unsubscribe(&self, id: f64) {
+        self.0.unsubscribe(id as u64);
+    }
+}
+
+/// A multi staged form, created with `new SeigiForm(options)` and activated at once
+#[wasm_bindgen(js_name = SeigiForm)]
+pub struct JsForm(Form);
+
+#[wasm_bindgen(js_class = SeigiForm)]
+impl JsForm {
+    #[wasm_bindgen(constructor)]
+    pub fn new(options: JsFormOptions) -> Result<JsForm, JsError> {
+        let options: &JsValue = &options;
+        let ids = property(options, "stageIds")
+            .and_then(|v| v.dyn_into::<Array>().ok())
+            .map(|v| v.iter().map(|v| v.as_string()).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let stages = required::<Array>(options, "stages")?
+            .iter()
+            .enumerate()
+            .map(|(index, v)| {
+                let stage = Stage::from_container(v.dyn_into::<HtmlElement>()?);
+                Ok(match ids.get(index).cloned().flatten() {
+                    Some(id) => stage.id(id),
+                    None => stage,
+                })
+            })
+            .collect::<Result<Vec<_>, JsValue>>()
+            .map_err(|_| JsError::new("options.stages must be elements"))?;
+        let url_sync = property(options, "urlSync").and_then(|v| match v.as_string() {
+            Some(v) if v == "hash" => Some(UrlSync::Hash),
+            Some(_) => None,
+            None => property(&v, "query")?.as_string().map(UrlSync::Query),
+        });
+        let initial_stage = property(options, "initialStage")
+            .and_then(|v| v.as_f64())
+            .unwrap_or_default() as usize;
+        let orientation = match property(options, "orientation")
+            .and_then(|v| v.as_string())
+            .as_deref()
+        {
+            Some("vertical") => Orientation::Vertical,
+            _ => Orientation::Horizontal,
+        };
+        let mut builder = Form::builder();
+        if let Some(url_sync) = url_sync {
+            builder = builder.url_sync(url_sync);
+        }
+        let form = builder
+            .container(required(options, "container")?)
+            .add_stages(stages.into_iter())
+            .initial_stage(initial_stage)
+            .orientation(orientation)
+            .view_transition(
+                property(options, "viewTransition")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or_default(),
+            )
+            .build()
+            .map_err(|v| JsError::new(&v.to_string()))?;
+        form.initialize();
+        form.activate();
+        Ok(Self(form))
+    }
+
+    pub fn next(&self) {
+        self.0.next();
+    }
+
+    pub fn previous(&self) {
+        self.0.previous();
+    }
+
+    /// Moves to the stage if the user is allowed to, returning whether it is allowed
+    pub fn navigate(&self, stage: usize) -> bool {
+        self.0.navigate(stage)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn current(&self) -> usize {
+        self.0.current()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether any field differs from its initial value
+    #[wasm_bindgen(getter, js_name = isDirty)]
+    pub fn is_dirty(&self) -> bool {
+        self.0.is_dirty()
+    }
+
+    /// Takes the current values of fields as initial ones
+    #[wasm_bindgen(js_name = markPristine)]
+    pub fn mark_pristine(&self) {
+        self.0.mark_pristine();
+    }
+
+    /// Whether controls are disabled and every stage is shown for review or printing
+    #[wasm_bindgen(getter)]
+    pub fn readonly(&self) -> bool {
+        self.0.is_readonly()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_readonly(&self, readonly: bool) {
+        self.0.set_readonly(readonly);
+    }
+
+    pub fn activate(&self) {
+        self.0.activate();
+    }
+
+    pub fn deactivate(&self) {
+        self.0.deactivate();
+    }
+
+    /// Subscribes to changes of the current stage, returning an id to unsubscribe with
+    pub fn subscribe(&self, callback: JsStageCallback) -> f64 {
+        let callback: Function = callback.unchecked_into();
+        self.0.subscribe(Box::new(move |stage| {
+            let _ = callback.call1(&JsValue::NULL, &stage.into());
+        })) as f64
+    }
+
+    pub fn unsubscribe(&self, id: f64) {
+        self.0.unsubscribe(id as u64);
+    }
+}
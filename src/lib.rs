@@ -1,4 +1,111 @@
+//! Fundamental web UI components written in Rust
+//!
+//! Each subsystem is re-exported as a module behind a feature of the same name, all enabled by
+//! default through `full`. Depending on seigi with `default-features = false` and only the
+//! features in use keeps both compile times and wasm binaries small, while [utils] is always
+//! available.
+//!
+//! Fallible functions of subsystems return their own errors, which convert into [Error]. Functions
+//! suffixed with `_unchecked` panic instead, for applications where failing is a bug.
+//!
+//! Defaults shared by subsystems, like timeouts of toasts and the id prefix, are set once with
+//! [configure] before creating widgets.
+//!
+//! Common types and shortcuts starting builders, like [focus_trap], are imported at once from
+//! [prelude].
+
+#[cfg(feature = "alertdialog")]
+pub use seigi_alertdialog as alertdialog;
+#[cfg(feature = "avatar")]
+pub use seigi_avatar as avatar;
+#[cfg(feature = "color")]
+pub use seigi_color as color;
+#[cfg(feature = "command")]
+pub use seigi_command as command;
+#[cfg(feature = "components")]
 pub use seigi_components as components;
+#[cfg(feature = "date")]
+pub use seigi_date as date;
+#[cfg(feature = "dioxus")]
+pub use seigi_dioxus as dioxus;
+#[cfg(feature = "disclosure")]
+pub use seigi_disclosure as disclosure;
+#[cfg(feature = "dnd")]
+pub use seigi_dnd as dnd;
+#[cfg(feature = "focus")]
 pub use seigi_focus as focus;
+#[cfg(feature = "form")]
 pub use seigi_form as form;
+#[cfg(feature = "history")]
+pub use seigi_history as history;
+#[cfg(feature = "hotkeys")]
+pub use seigi_hotkeys as hotkeys;
+#[cfg(feature = "hovercard")]
+pub use seigi_hovercard as hovercard;
+#[cfg(feature = "masked_input")]
+pub use seigi_masked_input as masked_input;
+#[cfg(feature = "meter")]
+pub use seigi_meter as meter;
+#[cfg(feature = "navigation_menu")]
+pub use seigi_navigation_menu as navigation_menu;
+#[cfg(feature = "pagination")]
+pub use seigi_pagination as pagination;
+#[cfg(feature = "pin_input")]
+pub use seigi_pin_input as pin_input;
+#[cfg(feature = "progress")]
+pub use seigi_progress as progress;
+#[cfg(feature = "rating")]
+pub use seigi_rating as rating;
+#[cfg(feature = "resizable")]
+pub use seigi_resizable as resizable;
+#[cfg(feature = "scrollarea")]
+pub use seigi_scrollarea as scrollarea;
+#[cfg(feature = "sheet")]
+pub use seigi_sheet as sheet;
+#[cfg(feature = "stepper")]
+pub use seigi_stepper as stepper;
+#[cfg(feature = "table")]
+pub use seigi_table as table;
+#[cfg(feature = "tags_input")]
+pub use seigi_tags_input as tags_input;
+#[cfg(feature = "theme")]
+pub use seigi_theme as theme;
+#[cfg(feature = "toast")]
 pub use seigi_toast as toast;
+#[cfg(feature = "toolbar")]
+pub use seigi_toolbar as toolbar;
+#[cfg(feature = "upload")]
+pub use seigi_upload as upload;
+pub use seigi_utils as utils;
+#[cfg(feature = "diagnostics")]
+pub use seigi_utils::diagnostics;
+#[cfg(feature = "validate")]
+pub use seigi_validate as validate;
+#[cfg(feature = "virtualizer")]
+pub use seigi_virtual as virtualizer;
+
+mod error;
+#[cfg(feature = "js-api")]
+pub mod js;
+pub mod prelude;
+
+pub use error::Error;
+pub use seigi_utils::config::{GlobalConfig, configure};
+
+/// Starts the options of a focus trap on the target, which [focus::create] takes as they are
+#[cfg(feature = "focus")]
+pub fn focus_trap(target: web_sys::HtmlElement) -> focus::FocusTrapOptionsBuilder {
+    focus::FocusTrapOptions::builder().target(target)
+}
+
+/// Starts a toast with the title, which [toast::create_toast] takes as it is
+#[cfg(feature = "toast")]
+pub fn toast(title: impl ToString) -> toast::ToastBuilder {
+    toast::Toast::builder().title(title)
+}
+
+/// Starts a multi staged form in the container, to add stages to and build
+#[cfg(feature = "form")]
+pub fn form(container: web_sys::HtmlElement) -> form::multi_stage::FormBuilder {
+    form::multi_stage::Form::builder().container(container)
+}
@@ -0,0 +1,31 @@
+//! Items most applications use, imported at once with `use seigi::prelude::*`
+//!
+//! Alongside the types, the prelude brings [crate::focus_trap], [toast](crate::toast()) and
+//! [form](crate::form()), which start builders with defaults from a single argument:
+//!
+//! ```ignore
+//! use seigi::prelude::*;
+//!
+//! let trap = seigi::focus::create(focus_trap(dialog));
+//! trap.activate();
+//! seigi::toast::create_toast(toast("Saved").description("Changes are saved"))?;
+//! let form = form(container).add_stages(stages).build()?;
+//! ```
+
+pub use crate::{Error, configure};
+pub use seigi_utils::{config::Position, listener::Listener};
+
+#[cfg(feature = "focus")]
+pub use crate::focus_trap;
+#[cfg(feature = "focus")]
+pub use seigi_focus::{FocusTrap, FocusTrapOptions, InitialFocus};
+
+#[cfg(feature = "toast")]
+pub use crate::toast;
+#[cfg(feature = "toast")]
+pub use seigi_toast::{Toast, ToastHandle, Toaster, ToasterOptions};
+
+#[cfg(feature = "form")]
+pub use crate::form;
+#[cfg(feature = "form")]
+pub use seigi_form::multi_stage::{Form, Stage};
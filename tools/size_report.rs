@@ -0,0 +1,285 @@
+#!/usr/bin/env -S cargo -Zscript
+
+//! Reports the size of wasm binaries using each feature of seigi alone
+//!
+//! A probe crate calling into the subsystem of a feature is built for wasm32 with
+//! `default-features = false` and only that feature, so the size is what the feature adds to an
+//! application rather than what the linker strips.
+//!
+//! Binaries are processed by `wasm-bindgen` if it is installed, which removes descriptions of
+//! bindings, and sizes are of what browsers would download. Otherwise, only the code and data
+//! sections are counted, which is a bit more than that but enough to compare features and commits.
+//!
+//! Usage: `cargo -Zscript tools/size_report.rs [--save <file>] [--baseline <file>] [features...]`
+//!
+//! Without features, every feature in [PROBES] is reported, together with `minimal`, built
+//! without any, and `full`. `--save` writes sizes to the file, and `--baseline` compares them with
+//! sizes saved before.
+
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, exit},
+};
+
+/// Features and expressions using their subsystems, which keep them in probe binaries
+///
+/// Features added to the umbrella crate must be added here too.
+const PROBES: &[(&str, &str)] = &[
+    ("alertdialog", "seigi::alertdialog::create_alert_dialog as fn(_) -> _"),
+    ("avatar", "seigi::avatar::create_avatar as fn(_) -> _"),
+    ("color", "seigi::color::create_area as fn(_, _) -> _"),
+    ("command", "seigi::command::create_command_palette as fn(_) -> _"),
+    ("components", "seigi::components::define::<Probe> as fn(&str) -> _"),
+    ("date", "seigi::date::calendar::create_calendar as fn(_, _) -> _"),
+    ("disclosure", "seigi::disclosure::create_disclosure as fn(_) -> _"),
+    ("dnd", "seigi::dnd::create_drag_drop as fn(_) -> _"),
+    ("focus", "seigi::focus::create as fn(_) -> _"),
+    ("form", "seigi::form::multi_stage::Form::builder as fn() -> _"),
+    ("history", "seigi::history::create_history::<String> as fn(_) -> _"),
+    ("hotkeys", "seigi::hotkeys::register as fn(_) -> _"),
+    ("hovercard", "seigi::hovercard::create_hover_card as fn(_) -> _"),
+    ("masked_input", "seigi::masked_input::create_masked_input as fn(_) -> _"),
+    ("meter", "seigi::meter::create_meter as fn(_) -> _"),
+    ("navigation_menu", "seigi::navigation_menu::create_navigation_menu as fn(_) -> _"),
+    ("pagination", "seigi::pagination::create_pagination as fn(_) -> _"),
+    ("pin_input", "seigi::pin_input::create as fn(_) -> _"),
+    ("progress", "(|| drop(seigi::progress::start(\"probe\"))) as fn()"),
+    ("rating", "seigi::rating::create_rating as fn(_) -> _"),
+    ("resizable", "seigi::resizable::create_panel_group as fn(_) -> _"),
+    ("scrollarea", "seigi::scrollarea::create_scroll_area as fn(_) -> _"),
+    ("sheet", "seigi::sheet::create_sheet as fn(_) -> _"),
+    ("stepper", "seigi::stepper::create_stepper as fn(_) -> _"),
+    ("table", "seigi::table::navigation::create_grid_navigation as fn(_) -> _"),
+    ("tags_input", "seigi::tags_input::create_tags_input as fn(_) -> _"),
+    ("theme", "seigi::theme::create_theme_manager as fn(_) -> _"),
+    ("toast", "seigi::toast::initialize as fn(_) -> _"),
+    ("toolbar", "seigi::toolbar::create_toolbar as fn(_) -> _"),
+    ("upload", "seigi::upload::dropzone::create_dropzone as fn(_) -> _"),
+    ("validate", "seigi::validate::create_validator as fn(_) -> _"),
+    ("virtualizer", "seigi::virtualizer::create_virtualizer as fn(_) -> _"),
+];
+
+/// A component defined by the probe of `components`
+const COMPONENT: &str = r#"
+#[cfg(feature = "components")]
+struct Probe;
+
+#[cfg(feature = "components")]
+impl seigi::components::Component for Probe {
+    fn construct() -> Self {
+        Probe
+    }
+}
+"#;
+
+struct Args {
+    features: Vec<String>,
+    save: Option<PathBuf>,
+    baseline: Option<PathBuf>,
+}
+
+fn parse_args() -> Args {
+    let mut args = Args {
+        features: vec![],
+        save: None,
+        baseline: None,
+    };
+    let mut iter = env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--save" => args.save = iter.next().map(PathBuf::from),
+            "--baseline" => args.baseline = iter.next().map(PathBuf::from),
+            feature => args.features.push(feature.to_string()),
+        }
+    }
+    args
+}
+
+/// Writes the probe crate using the features into the directory
+fn write_probe(dir: &Path, root: &Path, features: &[&str]) {
+    fs::create_dir_all(dir.join("src")).expect("failed to create probe crate");
+    let manifest = format!(
+        r#"[package]
+name = "seigi_size_probe"
+version = "0.0.0"
+edition = "2024"
+publish = false
+
+[lib]
+crate-type = ["cdylib"]
+path = "src/lib.rs"
+
+[dependencies]
+seigi = {{ path = {root:?}, default-features = false, features = {features:?} }}
+
+[features]
+{feature_table}
+
+[profile.release]
+opt-level = "z"
+lto = true
+codegen-units = 1
+panic = "abort"
+strip = "debuginfo"
+
+[workspace]
+"#,
+        feature_table = PROBES
+            .iter()
+            .map(|(v, _)| format!("{v} = []"))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    );
+    fs::write(dir.join("Cargo.toml"), manifest).expect("failed to write probe manifest");
+
+    let mut source = String::from("#![allow(unused)]\n");
+    source.push_str(COMPONENT);
+    source.push_str("\n#[unsafe(no_mangle)]\npub extern \"C\" fn probe() {\n");
+    for (feature, expression) in PROBES {
+        source.push_str(&format!(
+            "    #[cfg(feature = {feature:?})]\n    std::hint::black_box({expression});\n"
+        ));
+    }
+    source.push_str("}\n");
+    fs::write(dir.join("src/lib.rs"), source).expect("failed to write probe source");
+}
+
+/// Builds the probe using the features, returning the size of its binary in bytes
+fn measure(root: &Path, features: &[&str]) -> u64 {
+    let dir = root.join("target/size-report/probe");
+    write_probe(&dir, root, features);
+    let status = Command::new("cargo")
+        .args(["build", "--release", "--target", "wasm32-unknown-unknown"])
+        .arg("--features")
+        .arg(features.join(","))
+        .env("CARGO_TARGET_DIR", root.join("target/size-report/target"))
+        .current_dir(&dir)
+        .status()
+        .expect("failed to run cargo");
+    if !status.success() {
+        eprintln!("failed to build the probe of {features:?}");
+        exit(1);
+    }
+    let binary = root
+        .join("target/size-report/target/wasm32-unknown-unknown/release/seigi_size_probe.wasm");
+
+    let out_dir = root.join("target/size-report/bindgen");
+    let bindgen = Command::new("wasm-bindgen")
+        .args(["--target", "web", "--no-typescript", "--out-dir"])
+        .arg(&out_dir)
+        .arg(&binary)
+        .output();
+    if bindgen.is_ok_and(|v| v.status.success()) {
+        return fs::metadata(out_dir.join("seigi_size_probe_bg.wasm"))
+            .expect("failed to read the processed probe binary")
+            .len();
+    }
+    sections_size(&fs::read(binary).expect("failed to read the probe binary"))
+}
+
+/// Returns the total size of the code and data sections of the wasm binary
+fn sections_size(binary: &[u8]) -> u64 {
+    const CODE: u8 = 10;
+    const DATA: u8 = 11;
+
+    fn leb128(binary: &[u8], position: &mut usize) -> u64 {
+        let (mut value, mut shift) = (0, 0);
+        loop {
+            let byte = binary[*position];
+            *position += 1;
+            value |= ((byte & 0x7f) as u64) << shift;
+            shift += 7;
+            if byte < 0x80 {
+                return value;
+            }
+        }
+    }
+
+    // Sections follow the magic number and the version
+    let mut position = 8;
+    let mut size = 0;
+    while position < binary.len() {
+        let id = binary[position];
+        position += 1;
+        let length = leb128(binary, &mut position);
+        if id == CODE || id == DATA {
+            size += length;
+        }
+        position += length as usize;
+    }
+    size
+}
+
+fn read_sizes(path: &Path) -> BTreeMap<String, u64> {
+    let Ok(text) = fs::read_to_string(path) else {
+        eprintln!("failed to read baseline {}", path.display());
+        return BTreeMap::new();
+    };
+    text.lines()
+        .filter_map(|line| {
+            let (name, size) = line.split_once('\t')?;
+            Some((name.to_string(), size.parse().ok()?))
+        })
+        .collect()
+}
+
+fn kib(bytes: i64) -> String {
+    format!("{:.1} KiB", bytes as f64 / 1024.0)
+}
+
+fn main() {
+    let args = parse_args();
+    let root = env::current_dir().expect("failed to get current directory");
+    if !root.join("seigi_utils").exists() {
+        eprintln!("run this at the root of the repository");
+        exit(1);
+    }
+
+    let features: Vec<&str> = if args.features.is_empty() {
+        PROBES.iter().map(|(v, _)| *v).collect()
+    } else {
+        args.features.iter().map(String::as_str).collect()
+    };
+    if let Some(unknown) = features.iter().find(|v| !PROBES.iter().any(|(p, _)| p == *v)) {
+        eprintln!("unknown feature {unknown}");
+        exit(1);
+    }
+
+    let minimal = measure(&root, &[]);
+    let mut sizes = vec![("minimal".to_string(), minimal)];
+    for feature in &features {
+        sizes.push((feature.to_string(), measure(&root, &[feature])));
+    }
+    if args.features.is_empty() {
+        sizes.push(("full".to_string(), measure(&root, &features)));
+    }
+
+    let baseline = args.baseline.as_deref().map(read_sizes).unwrap_or_default();
+    println!("| Feature | Size | Added to minimal | Change |");
+    println!("| --- | --: | --: | --: |");
+    for (name, size) in &sizes {
+        let added = if name == "minimal" {
+            "-".to_string()
+        } else {
+            kib(*size as i64 - minimal as i64)
+        };
+        let change = match baseline.get(name) {
+            Some(before) => {
+                let delta = *size as i64 - *before as i64;
+                format!("{}{}", if delta > 0 { "+" } else { "" }, kib(delta))
+            }
+            None => "-".to_string(),
+        };
+        println!("| {name} | {} | {added} | {change} |", kib(*size as i64));
+    }
+
+    if let Some(path) = args.save {
+        let text: String = sizes
+            .iter()
+            .map(|(name, size)| format!("{name}\t{size}\n"))
+            .collect();
+        fs::write(&path, text).expect("failed to save sizes");
+    }
+}